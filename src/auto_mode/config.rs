@@ -1,13 +1,94 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 
-use crate::auto_mode::{default_execution_entries, ExecutionEntry};
+use crate::auto_mode::{
+    default_execution_entries, AutoStrategy, CooldownKey, ExecutionEntry, COOLDOWN_DURATION,
+};
 use crate::error::ConfigError;
 use crate::utils::config_paths::ConfigPaths;
 
 const ALLOWED_CLI_TYPES: [&str; 3] = ["codex", "claude", "gemini"];
+const COOLDOWN_STATE_FILE: &str = "cooldowns.json";
+
+/// 冷却配置（`~/.aiw/config.json` 的 `auto_cooldown` 字段）
+///
+/// ```json
+/// "auto_cooldown": {
+///   "default_secs": 30,
+///   "persist": true,
+///   "overrides": { "codex+glm": 120 }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CooldownConfig {
+    /// 默认冷却时长（秒）
+    #[serde(default = "default_cooldown_secs")]
+    pub default_secs: u64,
+    /// 是否把故障时间持久化到 `~/.aiw/cooldowns.json`，重启后冷却仍然生效
+    #[serde(default)]
+    pub persist: bool,
+    /// 按 `cli+provider` 覆盖冷却时长（秒）
+    #[serde(default)]
+    pub overrides: HashMap<String, u64>,
+}
+
+fn default_cooldown_secs() -> u64 {
+    COOLDOWN_DURATION.as_secs()
+}
+
+impl Default for CooldownConfig {
+    fn default() -> Self {
+        Self {
+            default_secs: default_cooldown_secs(),
+            persist: false,
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl CooldownConfig {
+    /// 获取指定 CLI+Provider 组合的冷却时长
+    pub fn duration_for(&self, key: &CooldownKey) -> Duration {
+        let secs = self
+            .overrides
+            .get(&key.display_name())
+            .copied()
+            .unwrap_or(self.default_secs);
+        Duration::from_secs(secs)
+    }
+}
+
+/// 执行策略配置（`~/.aiw/config.json` 的 `auto_strategy` 字段）
+///
+/// ```json
+/// "auto_strategy": { "mode": "race", "top_k": 2 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StrategyConfig {
+    #[serde(default)]
+    pub mode: AutoStrategy,
+    /// Race 模式下每批并发的组合数
+    #[serde(default = "default_race_top_k")]
+    pub top_k: usize,
+}
+
+fn default_race_top_k() -> usize {
+    2
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        Self {
+            mode: AutoStrategy::default(),
+            top_k: default_race_top_k(),
+        }
+    }
+}
 
 pub struct ExecutionOrderConfig;
 
@@ -43,6 +124,55 @@ impl ExecutionOrderConfig {
         Ok(())
     }
 
+    /// 读取冷却配置，缺失时使用默认值
+    pub fn get_cooldown_config() -> Result<CooldownConfig, ConfigError> {
+        let path = Self::config_path()?;
+        if !path.exists() {
+            return Ok(CooldownConfig::default());
+        }
+
+        let config = Self::load_config_value(&path)?;
+        let cooldown_value = match &config {
+            Value::Object(map) => map.get("auto_cooldown"),
+            _ => return Err(ConfigError::InvalidFormat),
+        };
+
+        match cooldown_value {
+            Some(value) => {
+                serde_json::from_value(value.clone()).map_err(|_| ConfigError::InvalidType)
+            }
+            None => Ok(CooldownConfig::default()),
+        }
+    }
+
+    /// 读取执行策略配置，缺失时使用顺序模式
+    pub fn get_strategy_config() -> Result<StrategyConfig, ConfigError> {
+        let path = Self::config_path()?;
+        if !path.exists() {
+            return Ok(StrategyConfig::default());
+        }
+
+        let config = Self::load_config_value(&path)?;
+        let strategy_value = match &config {
+            Value::Object(map) => map.get("auto_strategy"),
+            _ => return Err(ConfigError::InvalidFormat),
+        };
+
+        match strategy_value {
+            Some(value) => {
+                serde_json::from_value(value.clone()).map_err(|_| ConfigError::InvalidType)
+            }
+            None => Ok(StrategyConfig::default()),
+        }
+    }
+
+    /// 冷却状态持久化文件路径
+    pub fn cooldown_state_path() -> Result<PathBuf, ConfigError> {
+        let paths =
+            ConfigPaths::new().map_err(|err| ConfigError::Io { message: err.to_string() })?;
+        Ok(paths.config_dir.join(COOLDOWN_STATE_FILE))
+    }
+
     /// 重置为默认配置
     pub fn reset_to_default() -> Vec<ExecutionEntry> {
         default_execution_entries()
@@ -1,10 +1,13 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use std::time::{Duration, Instant};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 
-use crate::cli_type::CliType;
+use crate::cli_type::{parse_cli_type, CliType};
+use crate::logging::warn;
+use crate::utils::file_lock::lock_exclusive;
 
 pub mod config;
 
@@ -63,6 +66,19 @@ pub struct ExecutionResult {
     pub duration: Duration,
 }
 
+/// Auto 模式执行策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoStrategy {
+    /// 按顺序尝试，失败后切换到下一个（默认）
+    #[default]
+    Sequential,
+    /// 并发启动前 K 个可用组合，取第一个成功的结果，取消其余
+    Race,
+    /// 并发运行所有可用组合，任意一个成功即视为成功
+    All,
+}
+
 /// 冷却键：(CliType, Provider) 组合
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CooldownKey {
@@ -83,10 +99,25 @@ impl CooldownKey {
     }
 }
 
-/// CLI+Provider 冷却状态管理（基于内存）
+/// 持久化的故障记录（`~/.aiw/cooldowns.json`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedFailure {
+    cli: String,
+    provider: String,
+    /// 故障时间（Unix 秒）
+    failed_at: u64,
+}
+
+/// CLI+Provider 冷却状态管理
+///
+/// 默认只在内存中记录；`auto_cooldown.persist = true` 时同步写入磁盘，
+/// 进程重启后冷却依然生效。
 pub struct CliCooldownManager {
     /// 记录每个 (CLI, Provider) 组合的最后故障时间
-    last_failure_times: Mutex<HashMap<CooldownKey, Instant>>,
+    last_failure_times: Mutex<HashMap<CooldownKey, SystemTime>>,
+    config: config::CooldownConfig,
+    /// 持久化文件路径（None 表示仅内存）
+    state_path: Option<PathBuf>,
 }
 
 impl Default for CliCooldownManager {
@@ -96,42 +127,101 @@ impl Default for CliCooldownManager {
 }
 
 impl CliCooldownManager {
+    /// 仅内存、使用默认冷却时长
     pub fn new() -> Self {
+        Self::with_config(config::CooldownConfig::default(), None)
+    }
+
+    /// 使用指定配置创建；给出 `state_path` 时从该文件恢复冷却状态
+    pub fn with_config(config: config::CooldownConfig, state_path: Option<PathBuf>) -> Self {
+        let times = state_path
+            .as_deref()
+            .map(load_persisted_failures)
+            .unwrap_or_default();
         Self {
-            last_failure_times: Mutex::new(HashMap::new()),
+            last_failure_times: Mutex::new(times),
+            config,
+            state_path,
         }
     }
 
+    /// 从 `~/.aiw/config.json` 读取冷却配置
+    pub fn from_user_config() -> Self {
+        let cooldown_config =
+            config::ExecutionOrderConfig::get_cooldown_config().unwrap_or_else(|err| {
+                warn(format!(
+                    "Invalid auto_cooldown config, using defaults: {}",
+                    err
+                ));
+                config::CooldownConfig::default()
+            });
+        let state_path = if cooldown_config.persist {
+            config::ExecutionOrderConfig::cooldown_state_path().ok()
+        } else {
+            None
+        };
+        Self::with_config(cooldown_config, state_path)
+    }
+
+    /// 冷却状态是否持久化到磁盘
+    pub fn is_persistent(&self) -> bool {
+        self.state_path.is_some()
+    }
+
+    /// 获取 CLI+Provider 组合的冷却时长
+    pub fn cooldown_duration(&self, cli_type: &CliType, provider: &str) -> Duration {
+        self.config
+            .duration_for(&CooldownKey::new(cli_type.clone(), provider))
+    }
+
     /// 记录 CLI+Provider 组合故障
     pub fn mark_failure(&self, cli_type: &CliType, provider: &str) {
         let key = CooldownKey::new(cli_type.clone(), provider);
+        let failed_at = SystemTime::now();
         let mut times = self.last_failure_times.lock().unwrap();
-        times.insert(key, Instant::now());
+        self.update(&mut times, |times| {
+            times.insert(key.clone(), failed_at);
+        });
     }
 
     /// 检查 CLI+Provider 组合是否在冷却期
     pub fn is_in_cooldown(&self, cli_type: &CliType, provider: &str) -> bool {
         let key = CooldownKey::new(cli_type.clone(), provider);
-        let times = self.last_failure_times.lock().unwrap();
+        let mut times = self.last_failure_times.lock().unwrap();
         if let Some(last_failure) = times.get(&key) {
-            let elapsed = last_failure.elapsed();
-            if elapsed < COOLDOWN_DURATION {
+            let last_failure = *last_failure;
+            if elapsed_since(last_failure) < self.config.duration_for(&key) {
                 return true;
-            } else {
-                // 冷却期已过，移除记录
-                drop(times);
-                let mut times = self.last_failure_times.lock().unwrap();
-                times.remove(&key);
-                return false;
             }
+            // 冷却期已过，移除记录；其他进程在此之后记录的新故障保留
+            self.update(&mut times, |times| {
+                if times.get(&key).is_some_and(|time| *time <= last_failure) {
+                    times.remove(&key);
+                }
+            });
         }
         false
     }
 
-    /// 清除所有冷却状态（用于测试或重置）
-    pub fn clear_all(&self) {
+    /// 清除单个 CLI+Provider 组合的冷却状态，返回是否存在记录
+    pub fn reset_cooldown(&self, cli_type: &CliType, provider: &str) -> bool {
+        let key = CooldownKey::new(cli_type.clone(), provider);
+        let mut times = self.last_failure_times.lock().unwrap();
+        let removed = times.contains_key(&key);
+        if removed {
+            self.update(&mut times, |times| {
+                times.remove(&key);
+            });
+        }
+        removed
+    }
+
+    /// 清除所有冷却状态，返回清除的记录数
+    pub fn clear_all(&self) -> usize {
         let mut times = self.last_failure_times.lock().unwrap();
-        times.clear();
+        let count = times.len();
+        self.update(&mut times, HashMap::clear);
+        count
     }
 
     /// 获取 CLI+Provider 组合剩余冷却时间（秒）
@@ -139,14 +229,102 @@ impl CliCooldownManager {
         let key = CooldownKey::new(cli_type.clone(), provider);
         let times = self.last_failure_times.lock().unwrap();
         times.get(&key).and_then(|last_failure| {
-            let elapsed = last_failure.elapsed();
-            if elapsed < COOLDOWN_DURATION {
-                Some(COOLDOWN_DURATION.as_secs() - elapsed.as_secs())
+            let duration = self.config.duration_for(&key);
+            let elapsed = elapsed_since(*last_failure);
+            if elapsed < duration {
+                Some(duration.as_secs() - elapsed.as_secs())
             } else {
                 None
             }
         })
     }
+
+    /// 把修改应用到内存状态；持久化时在文件锁内重新读取 cooldowns.json、应用同一修改后写回，
+    /// 多个 aiw 进程并发更新时不会覆盖彼此的记录，内存状态也同步为合并后的结果
+    fn update(
+        &self,
+        times: &mut HashMap<CooldownKey, SystemTime>,
+        change: impl Fn(&mut HashMap<CooldownKey, SystemTime>),
+    ) {
+        change(times);
+        let Some(path) = self.state_path.as_deref() else {
+            return;
+        };
+        match merge_persisted_failures(path, &change) {
+            Ok(merged) => *times = merged,
+            Err(err) => warn(format!(
+                "Failed to persist cooldown state to {}: {}",
+                path.display(),
+                err
+            )),
+        }
+    }
+}
+
+/// 时钟回拨时视为刚刚发生故障
+fn elapsed_since(time: SystemTime) -> Duration {
+    time.elapsed().unwrap_or(Duration::ZERO)
+}
+
+fn load_persisted_failures(path: &Path) -> HashMap<CooldownKey, SystemTime> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    let Ok(records) = serde_json::from_str::<Vec<PersistedFailure>>(&content) else {
+        warn(format!(
+            "Ignoring unreadable cooldown state at {}",
+            path.display()
+        ));
+        return HashMap::new();
+    };
+
+    records
+        .into_iter()
+        .filter_map(|record| {
+            let cli_type = parse_cli_type(&record.cli)?;
+            let failed_at = UNIX_EPOCH + Duration::from_secs(record.failed_at);
+            Some((CooldownKey::new(cli_type, record.provider), failed_at))
+        })
+        .collect()
+}
+
+/// 在 `cooldowns.json.lock` 的排他锁内读取、修改并写回冷却状态
+fn merge_persisted_failures(
+    path: &Path,
+    change: &dyn Fn(&mut HashMap<CooldownKey, SystemTime>),
+) -> std::io::Result<HashMap<CooldownKey, SystemTime>> {
+    let _lock = lock_exclusive(&path.with_extension("json.lock"))?;
+    let mut times = load_persisted_failures(path);
+    change(&mut times);
+    save_persisted_failures(path, &times)?;
+    Ok(times)
+}
+
+fn save_persisted_failures(
+    path: &Path,
+    times: &HashMap<CooldownKey, SystemTime>,
+) -> std::io::Result<()> {
+    let mut records: Vec<PersistedFailure> = times
+        .iter()
+        .map(|(key, failed_at)| PersistedFailure {
+            cli: key.cli_type.display_name().to_string(),
+            provider: key.provider.clone(),
+            failed_at: failed_at
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        })
+        .collect();
+    records.sort_by(|a, b| (&a.cli, &a.provider).cmp(&(&b.cli, &b.provider)));
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let payload = serde_json::to_string_pretty(&records)?;
+    // 先写临时文件再替换，不加锁的读取方不会读到半截 JSON
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, payload)?;
+    std::fs::rename(tmp, path)
 }
 
 /// 全局冷却管理器
@@ -154,7 +332,7 @@ static COOLDOWN_MANAGER: std::sync::OnceLock<CliCooldownManager> = std::sync::On
 
 impl CliCooldownManager {
     pub fn global() -> &'static CliCooldownManager {
-        COOLDOWN_MANAGER.get_or_init(CliCooldownManager::new)
+        COOLDOWN_MANAGER.get_or_init(CliCooldownManager::from_user_config)
     }
 }
 
@@ -172,3 +350,114 @@ pub fn resolve_first_available_cli() -> anyhow::Result<(CliType, String)> {
     anyhow::bail!("No valid CLI type found in auto execution order config")
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config_with_override(key: &str, secs: u64, persist: bool) -> config::CooldownConfig {
+        config::CooldownConfig {
+            default_secs: 30,
+            persist,
+            overrides: HashMap::from([(key.to_string(), secs)]),
+        }
+    }
+
+    #[test]
+    fn cooldown_duration_uses_per_provider_override() {
+        let manager =
+            CliCooldownManager::with_config(config_with_override("codex+glm", 0, false), None);
+
+        assert_eq!(
+            manager.cooldown_duration(&CliType::Codex, "glm"),
+            Duration::ZERO
+        );
+        assert_eq!(
+            manager.cooldown_duration(&CliType::Codex, "openai"),
+            Duration::from_secs(30)
+        );
+
+        manager.mark_failure(&CliType::Codex, "glm");
+        manager.mark_failure(&CliType::Codex, "openai");
+        assert!(!manager.is_in_cooldown(&CliType::Codex, "glm"));
+        assert!(manager.is_in_cooldown(&CliType::Codex, "openai"));
+    }
+
+    #[test]
+    fn reset_cooldown_clears_single_entry() {
+        let manager = CliCooldownManager::new();
+        manager.mark_failure(&CliType::Claude, "official");
+        manager.mark_failure(&CliType::Gemini, "official");
+
+        assert!(manager.reset_cooldown(&CliType::Claude, "official"));
+        assert!(!manager.reset_cooldown(&CliType::Claude, "official"));
+        assert!(!manager.is_in_cooldown(&CliType::Claude, "official"));
+        assert!(manager.is_in_cooldown(&CliType::Gemini, "official"));
+        assert_eq!(manager.clear_all(), 1);
+    }
+
+    #[test]
+    fn persisted_cooldowns_survive_restart() {
+        let dir = TempDir::new().unwrap();
+        let state_path = dir.path().join("cooldowns.json");
+        let cfg = config_with_override("claude+glm", 600, true);
+
+        let first = CliCooldownManager::with_config(cfg.clone(), Some(state_path.clone()));
+        first.mark_failure(&CliType::Claude, "glm");
+
+        let restarted = CliCooldownManager::with_config(cfg.clone(), Some(state_path.clone()));
+        assert!(restarted.is_in_cooldown(&CliType::Claude, "glm"));
+        assert!(
+            restarted
+                .remaining_cooldown_secs(&CliType::Claude, "glm")
+                .unwrap()
+                > 590
+        );
+
+        assert!(restarted.reset_cooldown(&CliType::Claude, "glm"));
+        let after_reset = CliCooldownManager::with_config(cfg, Some(state_path));
+        assert!(!after_reset.is_in_cooldown(&CliType::Claude, "glm"));
+    }
+
+    #[test]
+    fn concurrent_managers_merge_persisted_cooldowns() {
+        let dir = TempDir::new().unwrap();
+        let state_path = dir.path().join("cooldowns.json");
+        let cfg = config_with_override("claude+glm", 600, true);
+
+        // 两个进程各自启动时读取到的都是空状态
+        let first = CliCooldownManager::with_config(cfg.clone(), Some(state_path.clone()));
+        let second = CliCooldownManager::with_config(cfg.clone(), Some(state_path.clone()));
+        first.mark_failure(&CliType::Claude, "glm");
+        second.mark_failure(&CliType::Codex, "official");
+
+        let restarted = CliCooldownManager::with_config(cfg, Some(state_path.clone()));
+        assert!(restarted.is_in_cooldown(&CliType::Claude, "glm"));
+        assert!(restarted.is_in_cooldown(&CliType::Codex, "official"));
+
+        // 单项清除只删除该项，不会用旧的内存快照覆盖文件
+        assert!(first.reset_cooldown(&CliType::Claude, "glm"));
+        assert_eq!(load_persisted_failures(&state_path).len(), 1);
+    }
+
+    #[test]
+    fn strategy_config_defaults_and_parsing() {
+        let default = config::StrategyConfig::default();
+        assert_eq!(default.mode, AutoStrategy::Sequential);
+
+        let race: config::StrategyConfig =
+            serde_json::from_value(serde_json::json!({ "mode": "race" })).unwrap();
+        assert_eq!(race.mode, AutoStrategy::Race);
+        assert_eq!(race.top_k, 2);
+
+        let all: config::StrategyConfig =
+            serde_json::from_value(serde_json::json!({ "mode": "all", "top_k": 5 })).unwrap();
+        assert_eq!(all.mode, AutoStrategy::All);
+        assert_eq!(all.top_k, 5);
+
+        assert!(serde_json::from_value::<config::StrategyConfig>(
+            serde_json::json!({ "mode": "fastest" })
+        )
+        .is_err());
+    }
+}
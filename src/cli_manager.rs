@@ -96,6 +96,7 @@ pub struct CliTool {
 pub enum InstallType {
     Native, // Native executable
     Npm,    // NPM package
+    Brew,   // Homebrew formula or cask
     #[allow(dead_code)]
     Unknown, // Unknown installation type
 }
@@ -216,7 +217,7 @@ impl CliToolDetector {
             tool.install_path = Some(path.clone());
 
             // Try to detect installation type
-            tool.install_type = Self::detect_install_type_static(&path);
+            tool.install_type = Self::detect_install_type(&path);
 
             // Try to get version
             tool.version = Self::get_tool_version_static(&tool.command);
@@ -229,11 +230,22 @@ impl CliToolDetector {
     }
 
     /// Detect installation type from path
-    fn detect_install_type_static(path: &Path) -> Option<InstallType> {
-        if let Some(path_str) = path.to_str() {
+    ///
+    /// Symlinks are resolved first so an npm package linked into Homebrew's
+    /// bin directory is still reported as npm.
+    pub fn detect_install_type(path: &Path) -> Option<InstallType> {
+        let resolved = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if let Some(path_str) = resolved.to_str() {
             if path_str.contains("node_modules") || path_str.contains("npm") {
                 return Some(InstallType::Npm);
             }
+            if path_str.contains("/Cellar/")
+                || path_str.contains("/Caskroom/")
+                || path_str.contains("homebrew")
+                || path_str.contains("linuxbrew")
+            {
+                return Some(InstallType::Brew);
+            }
         }
         Some(InstallType::Native)
     }
@@ -5,6 +5,91 @@ use crate::config::{CLAUDE_BIN, CODEX_BIN, GEMINI_BIN};
 use crate::error::{errors, AgenticResult, AgenticWardenError};
 use std::collections::HashSet;
 use std::env;
+use std::path::Path;
+
+/// 参数模板可用的占位符
+///
+/// - `{prompt}`：任务 prompt，必须且只能出现一次
+/// - `{cwd}`：任务工作目录（未指定时为当前目录），可嵌在参数中，如 `--cd={cwd}`
+/// - `{cli_args}`：用户透传的 CLI 参数，必须单独占一个元素；缺省时追加在末尾
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &["{prompt}", "{cwd}", "{cli_args}"];
+
+/// config.json `cli_arg_templates` 中某个 CLI 的非交互参数模板
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgTemplate {
+    args: Vec<String>,
+}
+
+impl ArgTemplate {
+    /// 校验模板：占位符必须已知，`{prompt}` 恰好一次，`{cli_args}` 独占一个元素且最多一次
+    pub fn parse(args: Vec<String>) -> Result<Self, String> {
+        let mut prompts = 0;
+        let mut cli_args = 0;
+        for arg in &args {
+            let mut rest = arg.as_str();
+            while let Some(start) = rest.find('{') {
+                let Some(len) = rest[start..].find('}') else {
+                    break;
+                };
+                let placeholder = &rest[start..start + len + 1];
+                match placeholder {
+                    "{prompt}" => prompts += 1,
+                    "{cwd}" => {}
+                    "{cli_args}" if arg == "{cli_args}" => cli_args += 1,
+                    "{cli_args}" => {
+                        return Err("{cli_args} must be a separate argument".to_string());
+                    }
+                    other => {
+                        return Err(format!(
+                            "unknown placeholder {} (available: {})",
+                            other,
+                            TEMPLATE_PLACEHOLDERS.join(", ")
+                        ));
+                    }
+                }
+                rest = &rest[start + len + 1..];
+            }
+        }
+        if prompts != 1 {
+            return Err(format!(
+                "{{prompt}} must appear exactly once, found {}",
+                prompts
+            ));
+        }
+        if cli_args > 1 {
+            return Err("{cli_args} may appear at most once".to_string());
+        }
+        Ok(Self { args })
+    }
+
+    /// 代入占位符，生成最终参数
+    pub fn render(&self, prompt: &str, cwd: &Path, cli_args: &[String]) -> Vec<String> {
+        let cwd = cwd.to_string_lossy();
+        let mut rendered = Vec::with_capacity(self.args.len() + cli_args.len());
+        let mut cli_args_placed = false;
+        for arg in &self.args {
+            if arg == "{cli_args}" {
+                rendered.extend(cli_args.iter().cloned());
+                cli_args_placed = true;
+                continue;
+            }
+            // 先拆出 prompt 再替换 `{cwd}`，prompt 文本本身不做替换
+            rendered.push(match arg.split_once("{prompt}") {
+                Some((before, after)) => format!(
+                    "{}{}{}",
+                    before.replace("{cwd}", &cwd),
+                    prompt,
+                    after.replace("{cwd}", &cwd)
+                ),
+                None => arg.replace("{cwd}", &cwd),
+            });
+        }
+        if !cli_args_placed {
+            rendered.extend(cli_args.iter().cloned());
+        }
+        rendered
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CliType {
@@ -69,6 +154,43 @@ impl CliType {
         }
     }
 
+    /// 选择模型的参数名；没有对应参数的 CLI 返回 `None`
+    pub fn model_flag(&self) -> Option<&'static str> {
+        match self {
+            CliType::Claude | CliType::Codex | CliType::Gemini => Some("--model"),
+            CliType::Grok | CliType::Auto => None,
+        }
+    }
+
+    /// 把模型名翻译成该 CLI 的参数（模型名原样透传，不做校验）
+    pub fn model_args(&self, model: &str) -> Result<Vec<String>, String> {
+        let flag = self.model_flag().ok_or_else(|| {
+            format!(
+                "{} does not support selecting a model; drop the model override or use claude, codex or gemini",
+                self.display_name()
+            )
+        })?;
+        Ok(vec![flag.to_string(), model.to_string()])
+    }
+
+    /// 从命令参数中找出所选模型（`--model X` 或 `--model=X`）
+    pub fn model_from_args(&self, args: &[String]) -> Option<String> {
+        let flag = self.model_flag()?;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == flag {
+                return iter.next().cloned();
+            }
+            if let Some(value) = arg
+                .strip_prefix(flag)
+                .and_then(|rest| rest.strip_prefix('='))
+            {
+                return Some(value.to_string());
+            }
+        }
+        None
+    }
+
     /// 构建非交互式完整权限命令参数
     pub fn build_full_access_args(&self, prompt: &str) -> Vec<String> {
         self.build_full_access_args_with_cli(prompt, &[])
@@ -76,6 +198,30 @@ impl CliType {
 
     /// 构建非交互式完整权限命令参数，包含用户透传的CLI参数
     pub fn build_full_access_args_with_cli(&self, prompt: &str, cli_args: &[String]) -> Vec<String> {
+        self.build_full_access_args_in(prompt, cli_args, None)
+    }
+
+    /// 同 [`Self::build_full_access_args_with_cli`]，`cwd` 用于模板中的 `{cwd}`
+    ///
+    /// config.json 为该 CLI 配置了 `cli_arg_templates` 时按模板生成，否则使用内置参数。
+    pub fn build_full_access_args_in(
+        &self,
+        prompt: &str,
+        cli_args: &[String],
+        cwd: Option<&Path>,
+    ) -> Vec<String> {
+        let template = crate::utils::config_paths::ConfigPaths::new()
+            .ok()
+            .and_then(|paths| paths.user_config.arg_template(self));
+        if let Some(template) = template {
+            let current = env::current_dir().unwrap_or_default();
+            return template.render(prompt, cwd.unwrap_or(&current), cli_args);
+        }
+        self.default_full_access_args(prompt, cli_args)
+    }
+
+    /// 内置的非交互参数（未配置模板时使用）
+    fn default_full_access_args(&self, prompt: &str, cli_args: &[String]) -> Vec<String> {
         let mut args = match self {
             CliType::Claude => {
                 vec![
@@ -338,4 +484,79 @@ mod tests {
             }
         }
     }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn arg_template_renders_placeholders() {
+        let template = ArgTemplate::parse(strings(&[
+            "exec",
+            "--cd={cwd}",
+            "{cli_args}",
+            "--",
+            "{prompt}",
+        ]))
+        .unwrap();
+        let args = template.render(
+            "fix {cwd} tests",
+            Path::new("/work"),
+            &strings(&["--model", "o3"]),
+        );
+        assert_eq!(
+            args,
+            strings(&[
+                "exec",
+                "--cd=/work",
+                "--model",
+                "o3",
+                "--",
+                "fix {cwd} tests"
+            ])
+        );
+
+        let appended = ArgTemplate::parse(strings(&["-p", "{prompt}"])).unwrap();
+        assert_eq!(
+            appended.render("hi", Path::new("/w"), &strings(&["-v"])),
+            strings(&["-p", "hi", "-v"])
+        );
+    }
+
+    #[test]
+    fn model_override_maps_to_cli_flag() {
+        for cli_type in [CliType::Claude, CliType::Codex, CliType::Gemini] {
+            let args = cli_type.model_args("some-model").unwrap();
+            assert_eq!(args, strings(&["--model", "some-model"]));
+            assert_eq!(
+                cli_type.model_from_args(&strings(&["exec", "--model", "m1", "task"])),
+                Some("m1".to_string())
+            );
+        }
+        assert_eq!(
+            CliType::Claude.model_from_args(&strings(&["--model=m2", "task"])),
+            Some("m2".to_string())
+        );
+
+        let err = CliType::Grok.model_args("x").unwrap_err();
+        assert!(err.starts_with("grok does not support selecting a model"));
+        assert_eq!(
+            CliType::Grok.model_from_args(&strings(&["--model", "x"])),
+            None
+        );
+    }
+
+    #[test]
+    fn arg_template_validation_rejects_bad_templates() {
+        assert!(ArgTemplate::parse(strings(&["-p"]))
+            .unwrap_err()
+            .contains("exactly once"));
+        assert!(ArgTemplate::parse(strings(&["{prompt}", "{prompt}"])).is_err());
+        assert!(ArgTemplate::parse(strings(&["{model}", "{prompt}"]))
+            .unwrap_err()
+            .contains("unknown placeholder {model}"));
+        assert!(ArgTemplate::parse(strings(&["--x={cli_args}", "{prompt}"]))
+            .unwrap_err()
+            .contains("separate argument"));
+    }
 }
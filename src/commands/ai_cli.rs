@@ -4,6 +4,8 @@
 
 use crate::cli_type::{parse_cli_selector_strict, CliType};
 use crate::commands::cli_args::CliInvocation;
+use crate::provider::error::ProviderError;
+use crate::provider::{AiType, EnvInjector, ProviderManager};
 use crate::registry_factory::create_cli_registry;
 use crate::supervisor;
 use crate::task_prepare::{self, TaskParams};
@@ -11,6 +13,7 @@ use anyhow::{anyhow, Result};
 #[cfg(test)]
 use std::path::PathBuf;
 use std::process::ExitCode;
+use tracing::Instrument;
 
 use crate::task_record::WorktreeInfo;
 
@@ -19,9 +22,61 @@ pub struct AiCliCommand {
     pub ai_types: Vec<CliType>,
     pub role: Option<String>,
     pub provider: Option<String>,
+    /// 模型覆盖，按各 CLI 的模型参数传入
+    pub model: Option<String>,
     pub prompt: String,
     pub cli_args: Vec<String>,
     pub cwd: Option<std::path::PathBuf>,
+    /// 录制交互会话（仅交互模式生效）
+    pub record: bool,
+    /// 只打印解析后的命令，不启动进程
+    pub dry_run: bool,
+}
+
+/// `--dry-run` 解析出的单个 CLI 执行计划
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunPlan {
+    pub cli_type: CliType,
+    pub binary: String,
+    pub args: Vec<String>,
+    pub provider: Option<String>,
+    /// Provider 将注入的环境变量名（不含值）
+    pub env_names: Vec<String>,
+    pub cwd: std::path::PathBuf,
+    /// 任务模式会先创建 worktree，实际 cwd 为新 worktree
+    pub creates_worktree: bool,
+    /// Provider 选择的说明：不兼容、找不到而回退、或 auto 在启动时才决定
+    pub provider_note: Option<String>,
+}
+
+impl DryRunPlan {
+    fn print(&self) {
+        println!("[dry-run] {}", self.cli_type.display_name());
+        println!("  Binary:   {}", self.binary);
+        println!("  Args:");
+        for arg in &self.args {
+            println!("    {:?}", arg);
+        }
+        println!(
+            "  Provider: {}",
+            self.provider
+                .as_deref()
+                .unwrap_or("(none, native configuration)")
+        );
+        if let Some(note) = &self.provider_note {
+            println!("  Note:     {}", note);
+        }
+        if self.env_names.is_empty() {
+            println!("  Env:      (none)");
+        } else {
+            println!("  Env:      {}", self.env_names.join(", "));
+        }
+        if self.creates_worktree {
+            println!("  Cwd:      new worktree of {}", self.cwd.display());
+        } else {
+            println!("  Cwd:      {}", self.cwd.display());
+        }
+    }
 }
 
 impl AiCliCommand {
@@ -38,9 +93,12 @@ impl AiCliCommand {
             ai_types,
             role,
             provider,
+            model: None,
             prompt,
             cli_args,
             cwd,
+            record: false,
+            dry_run: false,
         }
     }
 
@@ -57,9 +115,12 @@ impl AiCliCommand {
             ai_types,
             role: inv.aiw_args.role,
             provider: inv.aiw_args.provider,
+            model: inv.aiw_args.model,
             prompt,
             cli_args: inv.remaining_args,
             cwd: inv.aiw_args.cwd,
+            record: inv.aiw_args.record,
+            dry_run: inv.aiw_args.dry_run,
         }
     }
 
@@ -72,6 +133,82 @@ impl AiCliCommand {
         println!("Commit: {}", info.commit);
     }
 
+    /// 打印每个 CLI 将要执行的命令、Provider 环境变量名和工作目录，不启动进程
+    ///
+    /// 复用任务准备的参数构建（角色注入 + 透传参数），但不创建 worktree，
+    /// 也不写入任务 registry。
+    pub fn dry_run(&self) -> Result<ExitCode> {
+        if self
+            .ai_types
+            .iter()
+            .any(|cli_type| matches!(cli_type, CliType::Auto))
+        {
+            return Err(anyhow!("Auto CLI type is only supported via `aiw auto`"));
+        }
+
+        let cwd = self
+            .cwd
+            .clone()
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| ".".into()));
+        let manager = ProviderManager::new().ok();
+
+        for cli_type in &self.ai_types {
+            let mut plan = self.dry_run_plan(cli_type, cwd.clone())?;
+            if let Some(manager) = &manager {
+                let selection = dry_run_provider(manager, cli_type, self.provider.as_deref());
+                plan.provider = selection.name;
+                plan.env_names = selection.env_names;
+                plan.provider_note = selection.note;
+            }
+            plan.print();
+        }
+        Ok(ExitCode::from(0))
+    }
+
+    /// 构建单个 CLI 的执行计划（不含 Provider 解析）
+    fn dry_run_plan(&self, cli_type: &CliType, cwd: std::path::PathBuf) -> Result<DryRunPlan> {
+        let command_name = cli_type.command_name();
+        let binary = which::which(command_name)
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|_| format!("{} (not found in PATH)", command_name));
+
+        let interactive = self.prompt.is_empty();
+        let args = if interactive {
+            with_model_args(cli_type, self.model.as_deref(), &self.cli_args)?
+        } else {
+            let base = task_prepare::prepare_task_base(TaskParams {
+                cli_type: cli_type.clone(),
+                prompt: self.prompt.clone(),
+                role: self.role.clone(),
+                role_position: Default::default(),
+                provider: None,
+                model: self.model.clone(),
+                cli_args: self.cli_args.clone(),
+                cwd: Some(cwd.clone()),
+                create_worktree: false,
+                worktree_key: None,
+                env: Vec::new(),
+                priority: None,
+            })?;
+            task_prepare::finalize_for_entry(&base, cli_type.clone(), None)
+                .args
+                .into_iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        Ok(DryRunPlan {
+            cli_type: cli_type.clone(),
+            binary,
+            args,
+            provider: None,
+            env_names: Vec::new(),
+            cwd,
+            creates_worktree: !interactive,
+            provider_note: None,
+        })
+    }
+
     /// 执行 AI CLI 命令
     pub async fn execute(&self) -> Result<ExitCode> {
         let original_dir = self.cwd.clone().unwrap_or_else(|| {
@@ -88,6 +225,10 @@ impl AiCliCommand {
             ));
         }
 
+        if self.record && !self.prompt.is_empty() {
+            eprintln!("⚠️  --record only applies to interactive sessions; ignoring it");
+        }
+
         let registry = create_cli_registry()?;
 
         if self.prompt.is_empty() {
@@ -103,10 +244,15 @@ impl AiCliCommand {
                 cli_type: self.ai_types[0].clone(),
                 prompt: String::new(),
                 role: None, // 交互模式不需要角色
+                role_position: Default::default(),
                 provider: self.provider.clone(),
+                model: self.model.clone(),
                 cli_args: self.cli_args.clone(),
                 cwd: Some(original_dir),
                 create_worktree: true,
+                worktree_key: None,
+                env: Vec::new(),
+                priority: None,
             })?;
 
             if let Some(ref info) = prepared.worktree_info {
@@ -115,12 +261,14 @@ impl AiCliCommand {
             }
 
             // This function spawns child and exits immediately
+            let _span = supervisor::cli_task_span(prepared.cli_type.display_name()).entered();
             let exit_code = supervisor::start_interactive_cli(
                 &registry,
                 &prepared.cli_type,
                 prepared.provider,
-                &self.cli_args,
+                &with_model_args(&prepared.cli_type, self.model.as_deref(), &self.cli_args)?,
                 prepared.cwd.clone(),
+                self.record,
             )?;
 
             if let Some(ref info) = prepared.worktree_info {
@@ -133,10 +281,15 @@ impl AiCliCommand {
                 cli_type: self.ai_types[0].clone(),
                 prompt: self.prompt.clone(),
                 role: self.role.clone(),
+                role_position: Default::default(),
                 provider: self.provider.clone(),
+                model: self.model.clone(),
                 cli_args: self.cli_args.clone(),
                 cwd: Some(original_dir),
                 create_worktree: true,
+                worktree_key: None,
+                env: Vec::new(),
+                priority: None,
             })?;
 
             if let Some(ref info) = prepared.worktree_info {
@@ -144,13 +297,14 @@ impl AiCliCommand {
                 eprintln!("Branch: {}, Commit: {}", info.branch, info.commit);
             }
 
+            let task_span = supervisor::cli_task_span(prepared.cli_type.display_name());
             let exit_code = supervisor::execute_cli(
                 &registry,
                 &prepared.cli_type,
                 &prepared.args,
                 prepared.provider,
                 prepared.cwd.clone(),
-            ).await?;
+            ).instrument(task_span).await?;
 
             if let Some(ref info) = prepared.worktree_info {
                 Self::output_worktree_info(info);
@@ -158,15 +312,26 @@ impl AiCliCommand {
             Ok(ExitCode::from((exit_code & 0xFF) as u8))
         } else {
             // 多个 CLI 批量执行
+            // 先确认每个 CLI 都支持模型参数，避免跑到一半才失败
+            if let Some(model) = &self.model {
+                for cli_type in &self.ai_types {
+                    cli_type.model_args(model).map_err(|err| anyhow!(err))?;
+                }
+            }
             // 用 prepare_task 创建 worktree 和处理角色
             let prepared = task_prepare::prepare_task(TaskParams {
                 cli_type: self.ai_types[0].clone(),
                 prompt: self.prompt.clone(),
                 role: self.role.clone(),
+                role_position: Default::default(),
                 provider: self.provider.clone(),
+                model: self.model.clone(),
                 cli_args: self.cli_args.clone(),
                 cwd: Some(original_dir),
                 create_worktree: true,
+                worktree_key: None,
+                env: Vec::new(),
+                priority: None,
             })?;
 
             if let Some(ref info) = prepared.worktree_info {
@@ -187,20 +352,26 @@ impl AiCliCommand {
                 types: self.ai_types.clone(),
             };
 
-            let exit_codes = supervisor::execute_multiple_clis(
+            let ai_types = self
+                .ai_types
+                .iter()
+                .map(|t| t.display_name())
+                .collect::<Vec<_>>()
+                .join(",");
+            let task_span = supervisor::cli_task_span(&ai_types);
+            let result = supervisor::execute_multiple_clis(
                 &registry,
                 &cli_selector,
                 &prepared.prompt,
                 prepared.provider,
+                self.model.as_deref(),
                 &self.cli_args,
                 prepared.cwd.clone(),
-            ).await?;
+            ).instrument(task_span).await?;
 
-            let final_exit_code = exit_codes
-                .iter()
-                .find(|&&code| code != 0)
-                .copied()
-                .unwrap_or(0);
+            println!();
+            print!("{}", render_multi_summary(&result));
+            let final_exit_code = result.final_exit_code();
 
             if let Some(ref info) = prepared.worktree_info {
                 Self::output_worktree_info(info);
@@ -227,6 +398,10 @@ impl AiCliCommand {
             ));
         }
 
+        if inv.aiw_args.record && !inv.is_interactive() {
+            eprintln!("⚠️  --record only applies to interactive sessions; ignoring it");
+        }
+
         let registry = create_cli_registry()?;
 
         if inv.is_interactive() {
@@ -235,10 +410,15 @@ impl AiCliCommand {
                 cli_type: inv.cli_type.clone(),
                 prompt: String::new(),
                 role: None, // 交互模式不需要角色
+                role_position: Default::default(),
                 provider: inv.aiw_args.provider.clone(),
+                model: inv.aiw_args.model.clone(),
                 cli_args: inv.remaining_args.clone(),
                 cwd: Some(original_dir),
                 create_worktree: false,
+                worktree_key: None,
+                env: Vec::new(),
+                priority: None,
             })?;
 
             if let Some(ref info) = prepared.worktree_info {
@@ -247,12 +427,18 @@ impl AiCliCommand {
             }
 
             // This function spawns child and exits immediately
+            let _span = supervisor::cli_task_span(prepared.cli_type.display_name()).entered();
             let exit_code = supervisor::start_interactive_cli(
                 &registry,
                 &prepared.cli_type,
                 prepared.provider,
-                &inv.remaining_args,
+                &with_model_args(
+                    &prepared.cli_type,
+                    inv.aiw_args.model.as_deref(),
+                    &inv.remaining_args,
+                )?,
                 prepared.cwd.clone(),
+                inv.aiw_args.record,
             )?;
 
             if let Some(ref info) = prepared.worktree_info {
@@ -266,10 +452,15 @@ impl AiCliCommand {
                 cli_type: inv.cli_type.clone(),
                 prompt: prompt.clone(),
                 role: inv.aiw_args.role.clone(),
+                role_position: Default::default(),
                 provider: inv.aiw_args.provider.clone(),
+                model: inv.aiw_args.model.clone(),
                 cli_args: inv.remaining_args.clone(),
                 cwd: Some(original_dir),
                 create_worktree: true,
+                worktree_key: None,
+                env: Vec::new(),
+                priority: None,
             })?;
 
             if let Some(ref info) = prepared.worktree_info {
@@ -277,13 +468,14 @@ impl AiCliCommand {
                 eprintln!("Branch: {}, Commit: {}", info.branch, info.commit);
             }
 
+            let task_span = supervisor::cli_task_span(prepared.cli_type.display_name());
             let exit_code = supervisor::execute_cli(
                 &registry,
                 &prepared.cli_type,
                 &prepared.args,
                 prepared.provider,
                 prepared.cwd.clone(),
-            ).await?;
+            ).instrument(task_span).await?;
 
             if let Some(ref info) = prepared.worktree_info {
                 Self::output_worktree_info(info);
@@ -293,6 +485,127 @@ impl AiCliCommand {
     }
 }
 
+/// 在透传参数前加上模型参数；CLI 不支持模型参数时报错
+fn with_model_args(
+    cli_type: &CliType,
+    model: Option<&str>,
+    cli_args: &[String],
+) -> Result<Vec<String>> {
+    let mut args = match model {
+        Some(model) => cli_type.model_args(model).map_err(|err| anyhow!(err))?,
+        None => Vec::new(),
+    };
+    args.extend(cli_args.iter().cloned());
+    Ok(args)
+}
+
+/// dry-run 预计使用的 Provider
+#[derive(Debug, Default)]
+struct DryRunProvider {
+    name: Option<String>,
+    /// 将注入的环境变量名（已排序）
+    env_names: Vec<String>,
+    note: Option<String>,
+}
+
+/// 按 supervisor 的优先级选出 Provider：显式指定且可用的优先，否则取该 AI 类型的默认值。
+/// supervisor 对显式指定的不兼容 Provider 照常启动、对找不到的静默回退，这里都写进 note
+fn dry_run_provider(
+    manager: &ProviderManager,
+    cli_type: &CliType,
+    requested: Option<&str>,
+) -> DryRunProvider {
+    let Ok(ai_type) = cli_type.display_name().parse::<AiType>() else {
+        return DryRunProvider::default();
+    };
+
+    if requested.is_some_and(|name| name.eq_ignore_ascii_case("auto")) {
+        return DryRunProvider {
+            name: Some("auto".to_string()),
+            env_names: Vec::new(),
+            note: Some(format!(
+                "picked at launch among compatible providers for {} (cooldown and health checks)",
+                ai_type
+            )),
+        };
+    }
+
+    let mut note = None;
+    let explicit = match requested {
+        Some(name) => match manager.get_provider(name) {
+            Ok(provider) => {
+                if !provider.supports(&ai_type) {
+                    let compatible = provider
+                        .compatible_with
+                        .as_ref()
+                        .map(|types| {
+                            types
+                                .iter()
+                                .map(|t| t.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        })
+                        .unwrap_or_default();
+                    note = Some(
+                        ProviderError::IncompatibleProvider {
+                            provider: name.to_string(),
+                            ai_type: ai_type.to_string(),
+                            compatible,
+                        }
+                        .to_string(),
+                    );
+                }
+                Some((name.to_string(), provider))
+            }
+            Err(err) => {
+                note = Some(format!("{}; falling back to the default provider", err));
+                None
+            }
+        },
+        None => None,
+    };
+
+    let Some((name, provider)) = explicit.or_else(|| manager.get_default_provider_for(&ai_type))
+    else {
+        return DryRunProvider {
+            name: None,
+            env_names: Vec::new(),
+            note,
+        };
+    };
+    let mut env_names: Vec<String> = EnvInjector::provider_env(provider).into_keys().collect();
+    env_names.sort();
+    DryRunProvider {
+        name: Some(name),
+        env_names,
+        note,
+    }
+}
+
+/// 多 CLI 执行结果汇总表：CLI、provider、退出码、耗时、日志路径
+pub fn render_multi_summary(result: &supervisor::MultiExecResult) -> String {
+    use prettytable::{format, Cell, Row, Table};
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(Row::new(
+        ["CLI", "PROVIDER", "EXIT", "DURATION", "LOG"]
+            .iter()
+            .map(|title| Cell::new(title))
+            .collect(),
+    ));
+    for outcome in &result.outcomes {
+        table.add_row(Row::new(vec![
+            Cell::new(outcome.cli_type.display_name()),
+            Cell::new(outcome.provider.as_deref().unwrap_or("native")),
+            Cell::new(&outcome.exit_code.to_string()),
+            Cell::new(&format!("{:.1}s", outcome.duration.as_secs_f64())),
+            Cell::new(&outcome.log_path.display().to_string()),
+        ]));
+    }
+    table.to_string()
+}
+
 /// 解析 AI 类型字符串
 pub fn parse_ai_types(input: &str) -> Result<Vec<CliType>> {
     let selector = parse_cli_selector_strict(input).map_err(|err| anyhow!(err.to_string()))?;
@@ -315,6 +628,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn multi_summary_lists_each_cli_outcome() {
+        let outcome = |cli_type: CliType, provider: Option<&str>, exit_code: i32, millis: u64| {
+            supervisor::CliExecOutcome {
+                cli_type,
+                provider: provider.map(str::to_string),
+                exit_code,
+                duration: std::time::Duration::from_millis(millis),
+                log_path: PathBuf::from(format!("/tmp/.aiw/logs/{}.log", exit_code)),
+            }
+        };
+        let result = supervisor::MultiExecResult {
+            outcomes: vec![
+                outcome(CliType::Claude, Some("openrouter"), 0, 12_340),
+                outcome(CliType::Codex, None, 2, 500),
+            ],
+        };
+
+        let table = render_multi_summary(&result);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].contains("PROVIDER") && lines[0].contains("LOG"));
+        assert!(lines[1].contains("claude") && lines[1].contains("openrouter"));
+        assert!(lines[1].contains("12.3s") && lines[1].contains("/tmp/.aiw/logs/0.log"));
+        assert!(lines[2].contains("codex") && lines[2].contains("native"));
+        assert_eq!(result.exit_codes(), vec![0, 2]);
+        assert_eq!(result.final_exit_code(), 2);
+    }
+
     #[test]
     fn test_worktree_info_output_format() {
         let info = WorktreeInfo {
@@ -372,7 +713,11 @@ mod tests {
             aiw_args: AiwArgs {
                 role: Some("senior".to_string()),
                 provider: Some("anthropic".to_string()),
+                model: Some("opus".to_string()),
                 cwd: None,
+                record: false,
+                dry_run: false,
+                prompt_file: None,
             },
             remaining_args: vec!["--flag".to_string()],
         };
@@ -381,6 +726,7 @@ mod tests {
         assert_eq!(cmd.ai_types, vec![CliType::Claude]);
         assert_eq!(cmd.role, Some("senior".to_string()));
         assert_eq!(cmd.provider, Some("anthropic".to_string()));
+        assert_eq!(cmd.model, Some("opus".to_string()));
         // remaining_args joined with spaces becomes prompt
         assert_eq!(cmd.prompt, "--flag");
         assert_eq!(cmd.cli_args, vec!["--flag".to_string()]);
@@ -396,7 +742,11 @@ mod tests {
             aiw_args: AiwArgs {
                 role: None,
                 provider: None,
+                model: None,
                 cwd: Some(PathBuf::from("/test/path")),
+                record: false,
+                dry_run: true,
+                prompt_file: None,
             },
             remaining_args: vec!["hello".to_string(), "world".to_string()],
         };
@@ -407,6 +757,79 @@ mod tests {
         assert!(cmd.provider.is_none());
         assert_eq!(cmd.prompt, "hello world");
         assert_eq!(cmd.cwd, Some(PathBuf::from("/test/path")));
+        assert!(cmd.dry_run);
+    }
+
+    #[test]
+    fn test_dry_run_plan_builds_task_args_without_worktree() {
+        use crate::cli_type::CliType;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cmd = AiCliCommand::new(
+            vec![CliType::Claude, CliType::Codex],
+            None,
+            None,
+            "fix tests".to_string(),
+            vec!["--model".to_string(), "x".to_string()],
+            None,
+        );
+
+        let plan = cmd
+            .dry_run_plan(&CliType::Codex, temp_dir.path().to_path_buf())
+            .unwrap();
+        assert_eq!(
+            plan.args,
+            CliType::Codex.build_full_access_args_with_cli("fix tests", &cmd.cli_args)
+        );
+        assert!(plan.creates_worktree);
+        assert_eq!(plan.cwd, temp_dir.path());
+        // 规划阶段不创建 worktree
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_dry_run_provider_reports_incompatible_and_missing_providers() {
+        use crate::provider::config::{Provider, ProvidersConfig};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("providers.json");
+        std::fs::write(
+            &path,
+            serde_json::to_string(&ProvidersConfig::default()).unwrap(),
+        )
+        .unwrap();
+        let mut manager = ProviderManager::new_with_path(path).unwrap();
+        manager.get_providers_config_mut().add_provider(
+            "kimi".to_string(),
+            Provider {
+                enabled: true,
+                scenario: None,
+                compatible_with: Some(vec![AiType::Claude]),
+                env: std::collections::HashMap::new(),
+                disabled_until: None,
+                env_overrides: std::collections::HashMap::new(),
+                priority: None,
+            },
+        );
+
+        let selection = dry_run_provider(&manager, &CliType::Claude, Some("kimi"));
+        assert_eq!(selection.name.as_deref(), Some("kimi"));
+        assert!(selection.note.is_none());
+
+        // supervisor 照常使用显式指定的 Provider，dry-run 需要指出不兼容
+        let selection = dry_run_provider(&manager, &CliType::Codex, Some("kimi"));
+        assert_eq!(selection.name.as_deref(), Some("kimi"));
+        let note = selection.note.unwrap();
+        assert!(note.contains("not compatible with codex"), "{}", note);
+
+        let selection = dry_run_provider(&manager, &CliType::Codex, Some("missing"));
+        assert_ne!(selection.name.as_deref(), Some("missing"));
+        assert!(selection.note.unwrap().contains("falling back"));
+
+        let selection = dry_run_provider(&manager, &CliType::Codex, Some("auto"));
+        assert_eq!(selection.name.as_deref(), Some("auto"));
+        assert!(selection.env_names.is_empty());
+        assert!(selection.note.unwrap().contains("picked at launch"));
     }
 }
 
@@ -1,5 +1,9 @@
 use std::process::ExitCode;
 
+use tracing::Instrument;
+
+use crate::auto_mode::CliCooldownManager;
+use crate::cli_type::parse_cli_type;
 use crate::commands::cli_args::CliInvocation;
 use crate::error::{ConfigError, ExecutionError};
 use crate::tui::screens::cli_order::run_cli_order_tui;
@@ -47,10 +51,15 @@ pub async fn handle_auto_command(args: &[String]) -> ExitCode {
             cli_type: crate::cli_type::CliType::Auto,
             prompt,
             role: inv.aiw_args.role,
+            role_position: Default::default(),
             provider: inv.aiw_args.provider,
+            model: inv.aiw_args.model,
             cli_args: inv.remaining_args,
             cwd: inv.aiw_args.cwd,
             create_worktree: false,
+            worktree_key: None,
+            env: Vec::new(),
+            priority: None,
         },
     ) {
         Ok(b) => b,
@@ -60,8 +69,12 @@ pub async fn handle_auto_command(args: &[String]) -> ExitCode {
         }
     };
 
-    match crate::supervisor::execute_cli_with_failover(&registry, &base).await {
-        Ok(exit_code) => ExitCode::from((exit_code & 0xFF) as u8),
+    let task_span = crate::supervisor::cli_task_span("auto");
+    match crate::supervisor::execute_cli_with_failover(&registry, &base)
+        .instrument(task_span)
+        .await
+    {
+        Ok(_) => ExitCode::SUCCESS,
         Err(e) => {
             eprintln!("{}", e);
             ExitCode::from(2)
@@ -85,6 +98,37 @@ pub fn handle_cli_order_command() -> ExitCode {
     }
 }
 
+/// 清除冷却状态：指定 cli+provider 时只清除该组合，否则清除全部
+pub fn handle_reset_cooldown_command(cli: Option<String>, provider: Option<String>) -> ExitCode {
+    let manager = CliCooldownManager::from_user_config();
+
+    match (cli, provider) {
+        (Some(cli), Some(provider)) => {
+            let Some(cli_type) = parse_cli_type(&cli) else {
+                eprintln!("Unknown CLI type: {}", cli);
+                return ExitCode::from(1);
+            };
+            let name = format!("{}+{}", cli_type.display_name(), provider);
+            if manager.reset_cooldown(&cli_type, &provider) {
+                println!("Cleared cooldown for {}", name);
+            } else {
+                println!("{} is not in cooldown", name);
+            }
+        }
+        _ => {
+            let cleared = manager.clear_all();
+            println!("Cleared {} cooldown record(s)", cleared);
+        }
+    }
+
+    if !manager.is_persistent() {
+        println!(
+            "Note: auto_cooldown.persist is disabled; cooldowns only live inside running aiw processes"
+        );
+    }
+    ExitCode::from(0)
+}
+
 fn format_auto_error(err: ExecutionError) -> (u8, String) {
     match err {
         ExecutionError::Config(err) => format_auto_config_error(&err),
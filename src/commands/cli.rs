@@ -0,0 +1,308 @@
+//! `aiw cli install|uninstall`：通过可用的包管理器安装或卸载 AI CLI
+//!
+//! 安装时按 npm → Homebrew 的顺序选用第一个可用的包管理器，执行后重新检测
+//! PATH 确认命令可用；卸载时根据现有安装路径反推当初使用的包管理器。
+
+use std::path::Path;
+use std::process::{Command, ExitCode};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::cli_manager::{CliTool, CliToolDetector, InstallType};
+use crate::commands::parser::CliAction;
+
+/// 支持的包管理器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Npm,
+    Brew,
+    BrewCask,
+}
+
+impl PackageManager {
+    pub fn program(self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Brew | PackageManager::BrewCask => "brew",
+        }
+    }
+
+    pub fn is_available(self) -> bool {
+        which::which(self.program()).is_ok()
+    }
+
+    fn install_args(self, package: &str) -> Vec<String> {
+        match self {
+            PackageManager::Npm => vec!["install".into(), "-g".into(), package.into()],
+            PackageManager::Brew => vec!["install".into(), package.into()],
+            PackageManager::BrewCask => vec!["install".into(), "--cask".into(), package.into()],
+        }
+    }
+
+    fn uninstall_args(self, package: &str) -> Vec<String> {
+        match self {
+            PackageManager::Npm => vec!["uninstall".into(), "-g".into(), package.into()],
+            PackageManager::Brew => vec!["uninstall".into(), package.into()],
+            PackageManager::BrewCask => {
+                vec!["uninstall".into(), "--cask".into(), package.into()]
+            }
+        }
+    }
+
+    /// 安装后 `CliToolDetector` 应识别出的安装类型
+    fn install_type(self) -> InstallType {
+        match self {
+            PackageManager::Npm => InstallType::Npm,
+            PackageManager::Brew | PackageManager::BrewCask => InstallType::Brew,
+        }
+    }
+}
+
+/// 各 AI CLI 在不同包管理器下的包名，按优先级排列
+fn packages_for(tool: &str) -> Option<&'static [(PackageManager, &'static str)]> {
+    match tool {
+        "claude" => Some(&[
+            (PackageManager::Npm, "@anthropic-ai/claude-code"),
+            (PackageManager::BrewCask, "claude-code"),
+        ]),
+        "codex" => Some(&[
+            (PackageManager::Npm, "@openai/codex"),
+            (PackageManager::BrewCask, "codex"),
+        ]),
+        "gemini" => Some(&[
+            (PackageManager::Npm, "@google/gemini-cli"),
+            (PackageManager::Brew, "gemini-cli"),
+        ]),
+        _ => None,
+    }
+}
+
+const SUPPORTED_TOOLS: &str = "claude, codex, gemini";
+
+fn supported_packages(tool: &str) -> Result<&'static [(PackageManager, &'static str)]> {
+    packages_for(tool).ok_or_else(|| {
+        anyhow!(
+            "Unsupported AI CLI '{}'. Supported: {}",
+            tool,
+            SUPPORTED_TOOLS
+        )
+    })
+}
+
+/// 一次待执行的包管理器调用
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackagePlan {
+    pub manager: PackageManager,
+    pub package: &'static str,
+    pub args: Vec<String>,
+}
+
+impl PackagePlan {
+    pub fn command_line(&self) -> String {
+        format!("{} {}", self.manager.program(), self.args.join(" "))
+    }
+
+    fn run(&self) -> Result<()> {
+        let status = Command::new(self.manager.program())
+            .args(&self.args)
+            .status()
+            .with_context(|| format!("Failed to run `{}`", self.command_line()))?;
+        if !status.success() {
+            bail!("`{}` exited with {}", self.command_line(), status);
+        }
+        Ok(())
+    }
+}
+
+/// 选择第一个可用的包管理器生成安装计划
+pub fn plan_install(tool: &str, available: impl Fn(PackageManager) -> bool) -> Result<PackagePlan> {
+    let packages = supported_packages(tool)?;
+
+    packages
+        .iter()
+        .find(|(manager, _)| available(*manager))
+        .map(|(manager, package)| PackagePlan {
+            manager: *manager,
+            package,
+            args: manager.install_args(package),
+        })
+        .ok_or_else(|| {
+            let managers: Vec<&str> = packages.iter().map(|(m, _)| m.program()).collect();
+            anyhow!(
+                "No supported installer found for '{}'. Install one of: {}",
+                tool,
+                managers.join(", ")
+            )
+        })
+}
+
+/// 根据已安装二进制的位置推断卸载方式
+pub fn plan_uninstall(tool: &str, install_path: &Path) -> Result<PackagePlan> {
+    let packages = supported_packages(tool)?;
+
+    CliToolDetector::detect_install_type(install_path)
+        .filter(|install_type| *install_type != InstallType::Native)
+        .and_then(|install_type| {
+            packages
+                .iter()
+                .find(|(manager, _)| manager.install_type() == install_type)
+        })
+        .map(|(manager, package)| PackagePlan {
+            manager: *manager,
+            package,
+            args: manager.uninstall_args(package),
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "'{}' at {} was not installed by a supported package manager; remove it manually",
+                tool,
+                install_path.display()
+            )
+        })
+}
+
+/// 处理 `aiw cli install|uninstall <tool>`
+pub fn handle_cli_action(action: CliAction) -> ExitCode {
+    let result = match action {
+        CliAction::Install { tool } => install(&tool.to_lowercase()),
+        CliAction::Uninstall { tool } => uninstall(&tool.to_lowercase()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::from(0),
+        Err(err) => {
+            eprintln!("❌ {:#}", err);
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn detect(tool: &str) -> Result<CliTool> {
+    let mut detector = CliToolDetector::new();
+    detector.detect_all_tools()?;
+    detector
+        .get_tool_by_command(tool)
+        .cloned()
+        .ok_or_else(|| anyhow!("Unknown AI CLI '{}'", tool))
+}
+
+fn describe(tool: &CliTool) -> String {
+    let install_type = match tool.install_type {
+        Some(InstallType::Npm) => "npm",
+        Some(InstallType::Brew) => "brew",
+        Some(InstallType::Native) => "native",
+        Some(InstallType::Unknown) | None => "unknown",
+    };
+    format!(
+        "{} ({}, {})",
+        tool.version.as_deref().unwrap_or("unknown version"),
+        install_type,
+        tool.install_path
+            .as_deref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default()
+    )
+}
+
+fn install(tool: &str) -> Result<()> {
+    supported_packages(tool)?;
+    let current = detect(tool)?;
+    if current.installed {
+        println!("✅ {} is already installed: {}", tool, describe(&current));
+        return Ok(());
+    }
+
+    let plan = plan_install(tool, PackageManager::is_available)?;
+
+    println!("📦 Installing {} with `{}`", tool, plan.command_line());
+    plan.run()?;
+
+    let installed = detect(tool)?;
+    if !installed.installed {
+        bail!(
+            "`{}` finished but `{}` is not on PATH. Make sure {}'s global bin directory is on PATH",
+            plan.command_line(),
+            tool,
+            plan.manager.program()
+        );
+    }
+
+    println!("✅ Installed {}: {}", tool, describe(&installed));
+    Ok(())
+}
+
+fn uninstall(tool: &str) -> Result<()> {
+    supported_packages(tool)?;
+    let current = detect(tool)?;
+    let Some(install_path) = current
+        .install_path
+        .as_deref()
+        .filter(|_| current.installed)
+    else {
+        println!("ℹ️  {} is not installed", tool);
+        return Ok(());
+    };
+
+    let plan = plan_uninstall(tool, install_path)?;
+    println!("🗑️  Uninstalling {} with `{}`", tool, plan.command_line());
+    plan.run()?;
+
+    let remaining = detect(tool)?;
+    if remaining.installed {
+        bail!(
+            "`{}` finished but `{}` is still on PATH at {}",
+            plan.command_line(),
+            tool,
+            describe(&remaining)
+        );
+    }
+
+    println!("✅ Uninstalled {}", tool);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn install_prefers_npm_and_falls_back_to_brew() {
+        let plan = plan_install("codex", |_| true).unwrap();
+        assert_eq!(plan.command_line(), "npm install -g @openai/codex");
+
+        let plan = plan_install("claude", |m| m != PackageManager::Npm).unwrap();
+        assert_eq!(plan.command_line(), "brew install --cask claude-code");
+
+        let plan = plan_install("gemini", |m| m == PackageManager::Brew).unwrap();
+        assert_eq!(plan.command_line(), "brew install gemini-cli");
+    }
+
+    #[test]
+    fn install_reports_missing_installers_and_unknown_tools() {
+        let err = plan_install("gemini", |_| false).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("No supported installer found for 'gemini'"));
+
+        let err = plan_install("grok", |_| true).unwrap_err();
+        assert!(err.to_string().contains("Supported: claude, codex, gemini"));
+    }
+
+    #[test]
+    fn uninstall_follows_the_existing_install_location() {
+        let npm = Path::new("/usr/local/lib/node_modules/@openai/codex/bin/codex");
+        assert_eq!(
+            plan_uninstall("codex", npm).unwrap().command_line(),
+            "npm uninstall -g @openai/codex"
+        );
+
+        let brew = Path::new("/opt/homebrew/Cellar/gemini-cli/0.1.0/bin/gemini");
+        assert_eq!(
+            plan_uninstall("gemini", brew).unwrap().command_line(),
+            "brew uninstall gemini-cli"
+        );
+
+        let native = Path::new("/home/u/.local/bin/claude");
+        assert!(plan_uninstall("claude", native).is_err());
+    }
+}
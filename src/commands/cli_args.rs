@@ -3,6 +3,8 @@
 //! 提供统一的 CLI 参数解析结构，分离 AIW 自有参数和透传参数
 
 use crate::cli_type::{parse_cli_type, CliType};
+use crate::utils::config_paths::parse_default_ai_type;
+use std::io::Read;
 use std::path::PathBuf;
 
 /// AIW 自有参数（抽取后不转发）
@@ -12,8 +14,16 @@ pub struct AiwArgs {
     pub role: Option<String>,
     /// AIW Provider 名称（使用 -mp/--aiw-provider 参数设置）
     pub provider: Option<String>,
+    /// 模型覆盖（--aiw-model），翻译为目标 CLI 的模型参数
+    pub model: Option<String>,
     /// 工作目录
     pub cwd: Option<PathBuf>,
+    /// 录制交互会话到 ~/.aiw/sessions/（--record）
+    pub record: bool,
+    /// 只打印解析后的命令，不启动进程（--dry-run）
+    pub dry_run: bool,
+    /// 从文件读取任务提示词（-f/--prompt-file），`-` 表示 stdin；`-f` 因此不会透传给 CLI
+    pub prompt_file: Option<String>,
 }
 
 /// CLI 调用的完整信息
@@ -41,12 +51,48 @@ impl CliInvocation {
             return Err("No command provided".to_string());
         }
 
+        let default_ai_type = crate::utils::config_paths::ConfigPaths::new()
+            .ok()
+            .and_then(|paths| paths.user_config.default_ai_type);
+        Self::from_external_with_default(tokens, default_ai_type.as_deref())
+    }
+
+    /// 解析 external 命令，首个参数不是 CLI 类型时回退到 `default_ai_type`
+    ///
+    /// 优先级：显式 CLI 类型 > config.json 的 `default_ai_type` > 报错。
+    /// 回退时全部参数（含首个）都作为该 CLI 的参数/提示词。
+    pub fn from_external_with_default(
+        tokens: &[String],
+        default_ai_type: Option<&str>,
+    ) -> Result<Self, String> {
+        if tokens.is_empty() {
+            return Err("No command provided".to_string());
+        }
+
         // 第一个 token 是 CLI 类型
-        let cli_type = parse_cli_type(&tokens[0])
-            .ok_or_else(|| format!("Unknown CLI type: {}", tokens[0]))?;
+        if let Some(cli_type) = parse_cli_type(&tokens[0]) {
+            return Self::parse_with_type(cli_type, &tokens[1..]);
+        }
+
+        // 看起来像子命令的单词（如拼错的 `stauts`）不当作提示词发给默认 CLI
+        if looks_like_command(&tokens[0]) {
+            return Err(format!(
+                "Unknown command or CLI type: {} (run `aiw --help`; to send a one-word prompt, name the CLI, e.g. `aiw codex {}`)",
+                tokens[0], tokens[0]
+            ));
+        }
 
-        // 解析剩余参数
-        Self::parse_with_type(cli_type, &tokens[1..])
+        match default_ai_type {
+            Some(name) => {
+                let cli_type = parse_default_ai_type(name)
+                    .map_err(|err| format!("{} in ~/.aiw/config.json", err))?;
+                Self::parse_with_type(cli_type, tokens)
+            }
+            None => Err(format!(
+                "Unknown CLI type: {} (set default_ai_type in ~/.aiw/config.json to run prompts without a CLI name)",
+                tokens[0]
+            )),
+        }
     }
 
     /// 解析 auto 命令
@@ -61,7 +107,7 @@ impl CliInvocation {
     ///
     /// 从 tokens 中提取 AIW 自有参数和透传参数
     fn parse_with_type(cli_type: CliType, tokens: &[String]) -> Result<Self, String> {
-        let (aiw_args, remaining_args) = extract_aiw_args(tokens);
+        let (aiw_args, remaining_args) = extract_aiw_args(tokens)?;
 
         Ok(Self {
             cli_type,
@@ -75,14 +121,37 @@ impl CliInvocation {
         matches!(self.cli_type, CliType::Auto)
     }
 
+    /// 读取 `--prompt-file` 指定的提示词并追加到透传参数末尾，作为任务描述
+    ///
+    /// 未指定 `--prompt-file` 时不做任何事。
+    pub fn load_prompt_file(&mut self) -> Result<(), String> {
+        let Some(source) = self.aiw_args.prompt_file.as_deref() else {
+            return Ok(());
+        };
+        let prompt = read_prompt_source(source)?;
+        self.remaining_args.push(prompt);
+        Ok(())
+    }
+
     /// 判断是否是交互模式（remaining_args 为空）
     pub fn is_interactive(&self) -> bool {
         self.remaining_args.is_empty()
     }
 }
 
+/// 单个由小写字母、数字、`-`、`_` 组成的词，形如子命令而不是提示词
+fn looks_like_command(token: &str) -> bool {
+    !token.is_empty()
+        && !token.starts_with('-')
+        && token
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
 /// 提取 AIW 固定参数，返回 (AiwArgs, 剩余参数)
-fn extract_aiw_args(tokens: &[String]) -> (AiwArgs, Vec<String>) {
+///
+/// `-f` 总是被 aiw 当作 `--prompt-file` 取走，不会透传给 CLI。
+fn extract_aiw_args(tokens: &[String]) -> Result<(AiwArgs, Vec<String>), String> {
     let mut aiw_args = AiwArgs::default();
     let mut remaining = Vec::new();
     let mut iter = tokens.iter().peekable();
@@ -99,11 +168,29 @@ fn extract_aiw_args(tokens: &[String]) -> (AiwArgs, Vec<String>) {
                     aiw_args.provider = Some(value.clone());
                 }
             }
+            "--aiw-model" => {
+                if let Some(value) = iter.next() {
+                    aiw_args.model = Some(value.clone());
+                }
+            }
             "-c" | "--cwd" | "-C" => {
                 if let Some(value) = iter.next() {
                     aiw_args.cwd = Some(PathBuf::from(value));
                 }
             }
+            "--record" => {
+                aiw_args.record = true;
+            }
+            "--dry-run" => {
+                aiw_args.dry_run = true;
+            }
+            "-f" | "--prompt-file" => {
+                // 缺少路径时报错，而不是静默进入交互模式
+                let value = iter
+                    .next()
+                    .ok_or_else(|| format!("{} requires a path or -", token))?;
+                aiw_args.prompt_file = Some(value.clone());
+            }
             _ => {
                 // 其他参数原样保留
                 remaining.push(token.clone());
@@ -111,7 +198,32 @@ fn extract_aiw_args(tokens: &[String]) -> (AiwArgs, Vec<String>) {
         }
     }
 
-    (aiw_args, remaining)
+    Ok((aiw_args, remaining))
+}
+
+/// 读取提示词：`-` 读 stdin，其余按文件路径读取；内容必须是非空 UTF-8
+pub fn read_prompt_source(source: &str) -> Result<String, String> {
+    let (bytes, label) = if source == "-" {
+        let mut buffer = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("Failed to read prompt from stdin: {}", e))?;
+        (buffer, "stdin".to_string())
+    } else {
+        let bytes = std::fs::read(source).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => format!("Prompt file not found: {}", source),
+            _ => format!("Failed to read prompt file {}: {}", source, e),
+        })?;
+        (bytes, format!("prompt file {}", source))
+    };
+
+    let prompt =
+        String::from_utf8(bytes).map_err(|_| format!("The {} is not valid UTF-8", label))?;
+    let prompt = prompt.trim();
+    if prompt.is_empty() {
+        return Err(format!("The {} is empty", label));
+    }
+    Ok(prompt.to_string())
 }
 
 #[cfg(test)]
@@ -149,6 +261,20 @@ mod tests {
         assert!(inv.remaining_args.is_empty());
     }
 
+    #[test]
+    fn test_model_override_extracted() {
+        let inv = CliInvocation::from_external(&[
+            "codex".to_string(),
+            "--aiw-model".to_string(),
+            "o3".to_string(),
+            "--model".to_string(),
+            "kept".to_string(),
+        ]).unwrap();
+        assert_eq!(inv.aiw_args.model, Some("o3".to_string()));
+        // CLI 自己的 --model 仍原样透传
+        assert_eq!(inv.remaining_args, vec!["--model", "kept"]);
+    }
+
     #[test]
     fn test_remaining_args_preserved() {
         let inv = CliInvocation::from_external(&[
@@ -174,6 +300,49 @@ mod tests {
         assert_eq!(inv.remaining_args, vec!["--cli-flag", "do something"]);
     }
 
+    #[test]
+    fn test_bare_prompt_uses_default_ai_type() {
+        let tokens = vec![
+            "fix the tests".to_string(),
+            "-mp".to_string(),
+            "glm".to_string(),
+        ];
+        let inv = CliInvocation::from_external_with_default(&tokens, Some("codex")).unwrap();
+        assert_eq!(inv.cli_type, CliType::Codex);
+        assert_eq!(inv.aiw_args.provider, Some("glm".to_string()));
+        assert_eq!(inv.remaining_args, vec!["fix the tests"]);
+
+        // 显式 CLI 类型优先于默认值
+        let explicit = vec!["gemini".to_string(), "hello".to_string()];
+        let inv = CliInvocation::from_external_with_default(&explicit, Some("codex")).unwrap();
+        assert_eq!(inv.cli_type, CliType::Gemini);
+        assert_eq!(inv.remaining_args, vec!["hello"]);
+
+        let err = CliInvocation::from_external_with_default(&tokens, None).unwrap_err();
+        assert!(err.contains("default_ai_type"));
+        assert!(CliInvocation::from_external_with_default(&tokens, Some("cursor")).is_err());
+        // auto 只能通过 `aiw auto` 使用，不能作为默认 CLI
+        let err = CliInvocation::from_external_with_default(&tokens, Some("auto")).unwrap_err();
+        assert!(err.contains("invalid default_ai_type 'auto'"), "{}", err);
+    }
+
+    #[test]
+    fn test_command_like_first_token_is_not_a_prompt() {
+        for word in ["stauts", "cladue", "list-tasks"] {
+            let tokens = vec![word.to_string(), "-mp".to_string(), "glm".to_string()];
+            let err =
+                CliInvocation::from_external_with_default(&tokens, Some("codex")).unwrap_err();
+            assert!(err.contains("Unknown command or CLI type"), "{}", err);
+        }
+
+        // 多词提示词和以参数开头的调用仍回退到默认 CLI
+        for first in ["Fix the tests", "-mp"] {
+            let tokens = vec![first.to_string(), "glm".to_string()];
+            let inv = CliInvocation::from_external_with_default(&tokens, Some("codex")).unwrap();
+            assert_eq!(inv.cli_type, CliType::Codex);
+        }
+    }
+
     #[test]
     fn test_auto_mode() {
         let inv = CliInvocation::from_auto(&["hello".to_string(), "world".to_string()]).unwrap();
@@ -248,6 +417,78 @@ mod tests {
         assert_eq!(inv.aiw_args.provider, Some("anthropic".to_string()));
     }
 
+    #[test]
+    fn test_record_flag_keeps_session_interactive() {
+        let inv = CliInvocation::from_external(&[
+            "claude".to_string(),
+            "--record".to_string(),
+        ]).unwrap();
+        assert!(inv.aiw_args.record);
+        assert!(inv.is_interactive());
+    }
+
+    #[test]
+    fn test_dry_run_flag_is_not_forwarded() {
+        let inv = CliInvocation::from_external(&[
+            "codex".to_string(),
+            "--dry-run".to_string(),
+            "fix the build".to_string(),
+        ]).unwrap();
+        assert!(inv.aiw_args.dry_run);
+        assert_eq!(inv.remaining_args, vec!["fix the build"]);
+    }
+
+    #[test]
+    fn test_prompt_file_becomes_task_prompt() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("task.md");
+        std::fs::write(&path, "  refactor the parser\n\nkeep tests green\n").unwrap();
+
+        let mut inv = CliInvocation::from_external(&[
+            "claude".to_string(),
+            "-f".to_string(),
+            path.display().to_string(),
+        ]).unwrap();
+        assert!(inv.is_interactive());
+        inv.load_prompt_file().unwrap();
+        assert!(!inv.is_interactive());
+        assert_eq!(
+            inv.remaining_args,
+            vec!["refactor the parser\n\nkeep tests green"]
+        );
+    }
+
+    #[test]
+    fn test_prompt_file_without_value_is_an_error() {
+        for flag in ["-f", "--prompt-file"] {
+            let err = CliInvocation::from_external(&[
+                "claude".to_string(),
+                "-r".to_string(),
+                "senior".to_string(),
+                flag.to_string(),
+            ]).unwrap_err();
+            assert_eq!(err, format!("{} requires a path or -", flag));
+        }
+    }
+
+    #[test]
+    fn test_prompt_file_errors_are_explicit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let missing = dir.path().join("missing.md");
+        let err = read_prompt_source(missing.to_str().unwrap()).unwrap_err();
+        assert!(err.starts_with("Prompt file not found"), "{}", err);
+
+        let binary = dir.path().join("binary.bin");
+        std::fs::write(&binary, [0xff, 0xfe, 0x00]).unwrap();
+        let err = read_prompt_source(binary.to_str().unwrap()).unwrap_err();
+        assert!(err.contains("not valid UTF-8"), "{}", err);
+
+        let empty = dir.path().join("empty.md");
+        std::fs::write(&empty, "\n  \n").unwrap();
+        let err = read_prompt_source(empty.to_str().unwrap()).unwrap_err();
+        assert!(err.contains("is empty"), "{}", err);
+    }
+
     #[test]
     fn test_codex_type() {
         let inv = CliInvocation::from_external(&["codex".to_string()]).unwrap();
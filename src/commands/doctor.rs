@@ -0,0 +1,461 @@
+//! `aiw doctor`：诊断 warden 运行环境
+//!
+//! 逐项检查 AI CLI、配置文件、角色目录、路由 LLM 端点、主目录权限和共享内存注册表，
+//! 以 ✓ / ! / ✗ 清单输出；失败项的提示复用 [`UserFacingError::hint`](crate::error::UserFacingError)。
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use crate::cli_manager::CliToolDetector;
+use crate::common::constants::files::PROVIDERS_JSON;
+use crate::error::{errors, AgenticWardenError, RegistryError};
+use crate::mcp_routing::config::{McpConfigManager, Resolved};
+use crate::provider::config::ProvidersConfig;
+use crate::roles::RoleManager;
+use crate::utils::config_paths::aiw_config_dir;
+
+const ENDPOINT_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 单项检查的结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    /// 不影响基本使用，但相关功能不可用
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    fn symbol(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "✓",
+            CheckStatus::Warn => "!",
+            CheckStatus::Fail => "✗",
+        }
+    }
+}
+
+/// 单项检查结果
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub hint: Option<String>,
+}
+
+impl CheckResult {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    /// 根据错误生成检查结果，提示取自 `to_user_facing`
+    fn from_error(
+        name: impl Into<String>,
+        status: CheckStatus,
+        detail: impl Into<String>,
+        error: &AgenticWardenError,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint: error.to_user_facing().hint,
+        }
+    }
+
+    fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        let hint = hint.into();
+        self.hint = Some(match self.hint.take() {
+            Some(existing) => format!("{} ({})", existing, hint),
+            None => hint,
+        });
+        self
+    }
+}
+
+/// AI CLI 是否在 PATH 中以及版本
+fn check_ai_clis() -> Vec<CheckResult> {
+    let mut detector = CliToolDetector::new();
+    let _ = detector.detect_all_tools();
+
+    let mut results: Vec<CheckResult> = detector
+        .get_tools()
+        .iter()
+        .map(|tool| {
+            if tool.installed {
+                let version = tool.version.as_deref().unwrap_or("version unknown");
+                CheckResult::pass(&tool.command, version)
+            } else {
+                let error = AgenticWardenError::Process {
+                    message: format!("{} not found on PATH", tool.command),
+                    command: tool.command.clone(),
+                    source: None,
+                };
+                CheckResult::from_error(
+                    &tool.command,
+                    CheckStatus::Warn,
+                    "not found on PATH",
+                    &error,
+                )
+                .with_hint(detector.get_install_hint(&tool.command))
+            }
+        })
+        .collect();
+
+    // 一个都没有时无法使用，升级为失败
+    if results.iter().all(|r| r.status != CheckStatus::Pass) {
+        for result in &mut results {
+            result.status = CheckStatus::Fail;
+        }
+    }
+    results
+}
+
+fn check_json_file<F>(name: &str, path: &Path, missing_status: CheckStatus, parse: F) -> CheckResult
+where
+    F: FnOnce(&Path) -> Result<String, String>,
+{
+    if !path.exists() {
+        let error = errors::config_error(format!("{} does not exist", path.display()));
+        return CheckResult::from_error(
+            name,
+            missing_status,
+            format!("{} not found", path.display()),
+            &error,
+        );
+    }
+    match parse(path) {
+        Ok(detail) => CheckResult::pass(name, detail),
+        Err(message) => {
+            let error = errors::config_error(message.clone());
+            CheckResult::from_error(name, CheckStatus::Fail, message, &error)
+        }
+    }
+}
+
+fn check_providers(dir: &Path) -> CheckResult {
+    check_json_file(
+        "providers.json",
+        &dir.join(PROVIDERS_JSON),
+        CheckStatus::Warn,
+        |path| {
+            let config = ProvidersConfig::load(path).map_err(|e| e.to_string())?;
+            Ok(format!(
+                "{} provider(s), default '{}'",
+                config.providers.len(),
+                config.default_provider
+            ))
+        },
+    )
+}
+
+fn check_mcp_config(dir: &Path) -> CheckResult {
+    check_json_file(
+        "mcp.json",
+        &dir.join("mcp.json"),
+        CheckStatus::Warn,
+        |path| {
+            let manager = McpConfigManager::load_from(path).map_err(|e| format!("{:#}", e))?;
+            Ok(format!(
+                "{} MCP server(s)",
+                manager.config().mcp_servers.len()
+            ))
+        },
+    )
+}
+
+fn check_roles() -> CheckResult {
+    let listed = RoleManager::new().and_then(|manager| manager.list_all_roles());
+    match listed {
+        Ok(roles) if roles.is_empty() => {
+            let error = errors::filesystem_error("No role files found", "~/.aiw/role");
+            CheckResult::from_error("roles", CheckStatus::Warn, "no roles found", &error)
+                .with_hint("add *.md files to ~/.aiw/role/ to use -r")
+        }
+        Ok(roles) => CheckResult::pass("roles", format!("{} role(s)", roles.len())),
+        Err(err) => {
+            let error = errors::filesystem_error(err.to_string(), "~/.aiw/role");
+            CheckResult::from_error("roles", CheckStatus::Fail, err.to_string(), &error)
+        }
+    }
+}
+
+/// 路由使用的 LLM 端点，与 `aiw mcp serve` 的解析顺序一致：
+/// mcp.json `routing.llm_endpoint` > `OPENAI_ENDPOINT` > 默认本地 Ollama
+fn routing_endpoint(dir: Option<&Path>) -> Resolved<String> {
+    // mcp.json 无法解析时由 check_mcp_config 报告，这里按未配置处理
+    let routing = dir
+        .and_then(|dir| McpConfigManager::load_from(dir.join("mcp.json")).ok())
+        .map(|manager| manager.config().routing.clone())
+        .unwrap_or_default();
+    routing.resolve().llm_endpoint
+}
+
+/// 路由使用的 LLM 端点是否可达
+async fn check_routing_endpoint(endpoint: Resolved<String>) -> CheckResult {
+    let Resolved {
+        value: endpoint,
+        source,
+    } = endpoint;
+    let client = match reqwest::Client::builder()
+        .timeout(ENDPOINT_PROBE_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => {
+            let error = errors::network_error(err.to_string());
+            return CheckResult::from_error(
+                "routing LLM",
+                CheckStatus::Warn,
+                err.to_string(),
+                &error,
+            );
+        }
+    };
+
+    // 任何 HTTP 响应都说明端点可达；鉴权由具体请求负责
+    match client.get(&endpoint).send().await {
+        Ok(response) => CheckResult::pass(
+            "routing LLM",
+            format!(
+                "{} (from {}, HTTP {})",
+                endpoint,
+                source,
+                response.status().as_u16()
+            ),
+        ),
+        Err(err) => {
+            let error = errors::network_error(err.to_string());
+            CheckResult::from_error(
+                "routing LLM",
+                CheckStatus::Warn,
+                format!("{} (from {}) unreachable", endpoint, source),
+                &error,
+            )
+            .with_hint(
+                "only needed for intelligent MCP routing; change it with `aiw routing config set llm_endpoint <URL>`",
+            )
+        }
+    }
+}
+
+/// `~/.aiw` 是否可写，以及在 Unix 上是否对其他用户可见（其中保存了 API Key）
+fn check_home_permissions(dir: &Path) -> CheckResult {
+    let name = "~/.aiw permissions";
+    if !dir.exists() {
+        return CheckResult::pass(name, "not created yet");
+    }
+
+    let probe = dir.join(format!(".doctor-{}", std::process::id()));
+    if let Err(err) = std::fs::write(&probe, b"") {
+        let error = errors::filesystem_error(err.to_string(), dir.display().to_string());
+        return CheckResult::from_error(name, CheckStatus::Fail, "not writable", &error);
+    }
+    let _ = std::fs::remove_file(&probe);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Ok(metadata) = std::fs::metadata(dir) {
+            let mode = metadata.permissions().mode() & 0o777;
+            if mode & 0o077 != 0 {
+                let error = errors::filesystem_error(
+                    format!("mode {:o} exposes credentials to other users", mode),
+                    dir.display().to_string(),
+                );
+                return CheckResult::from_error(
+                    name,
+                    CheckStatus::Warn,
+                    format!("mode {:o} is accessible by other users", mode),
+                    &error,
+                )
+                .with_hint(format!("chmod 700 {}", dir.display()));
+            }
+            return CheckResult::pass(name, format!("writable, mode {:o}", mode));
+        }
+    }
+
+    CheckResult::pass(name, "writable")
+}
+
+/// 共享内存注册表能否连接和读取
+fn check_registry() -> CheckResult {
+    let name = "task registry";
+    let entries = crate::registry_factory::create_cli_registry().and_then(|r| r.entries());
+    match entries {
+        Ok(entries) => {
+            let running = entries
+                .iter()
+                .filter(|entry| entry.record.completed_at.is_none())
+                .count();
+            CheckResult::pass(
+                name,
+                format!(
+                    "shared memory OK ({} task(s), {} running)",
+                    entries.len(),
+                    running
+                ),
+            )
+        }
+        Err(err) => {
+            let error = AgenticWardenError::Resource {
+                message: err.to_string(),
+                resource_type: "shared memory".to_string(),
+                source: None,
+            };
+            let result = CheckResult::from_error(name, CheckStatus::Fail, err.to_string(), &error);
+            match &err {
+                RegistryError::Corrupted { repair_command, .. } => {
+                    result.with_hint(format!("run `{}`", repair_command))
+                }
+                _ => result,
+            }
+        }
+    }
+}
+
+/// 运行全部检查
+pub async fn run_checks() -> Vec<CheckResult> {
+    let mut results = check_ai_clis();
+    let config_dir = aiw_config_dir();
+    match config_dir.as_deref() {
+        Some(dir) => {
+            results.push(check_providers(dir));
+            results.push(check_mcp_config(dir));
+            results.push(check_roles());
+            results.push(check_home_permissions(dir));
+        }
+        None => {
+            let error = errors::filesystem_error("Home directory not available", "~");
+            results.push(CheckResult::from_error(
+                "home directory",
+                CheckStatus::Fail,
+                "cannot determine home directory",
+                &error,
+            ));
+        }
+    }
+    results.push(check_routing_endpoint(routing_endpoint(config_dir.as_deref())).await);
+    results.push(check_registry());
+    results
+}
+
+/// 处理 `aiw doctor`
+pub async fn handle_doctor_command() -> ExitCode {
+    println!("🩺 AIW doctor\n");
+    let results = run_checks().await;
+
+    let width = results.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    for result in &results {
+        println!(
+            "  {} {:<width$}  {}",
+            result.status.symbol(),
+            result.name,
+            result.detail,
+            width = width
+        );
+        if result.status != CheckStatus::Pass {
+            if let Some(hint) = &result.hint {
+                println!("    {:<width$}  💡 {}", "", hint, width = width);
+            }
+        }
+    }
+
+    let failed = results
+        .iter()
+        .filter(|r| r.status == CheckStatus::Fail)
+        .count();
+    let warned = results
+        .iter()
+        .filter(|r| r.status == CheckStatus::Warn)
+        .count();
+    println!();
+    if failed > 0 {
+        println!("✗ {} problem(s), {} warning(s)", failed, warned);
+        ExitCode::from(1)
+    } else {
+        println!("✓ No problems found ({} warning(s))", warned);
+        ExitCode::from(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp_routing::config::ValueSource;
+    use tempfile::TempDir;
+
+    #[test]
+    fn missing_json_file_uses_config_hint() {
+        let dir = TempDir::new().unwrap();
+        let result = check_json_file(
+            "x.json",
+            &dir.path().join("x.json"),
+            CheckStatus::Warn,
+            |_| unreachable!("parser must not run for a missing file"),
+        );
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.hint.is_some());
+    }
+
+    #[test]
+    fn invalid_providers_file_fails() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join(PROVIDERS_JSON), "{ not json").unwrap();
+        let result = check_providers(dir.path());
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.hint.is_some());
+    }
+
+    #[test]
+    fn mcp_check_reads_the_given_directory() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("mcp.json"), "{ not json").unwrap();
+        assert_eq!(check_mcp_config(dir.path()).status, CheckStatus::Fail);
+
+        std::fs::write(
+            dir.path().join("mcp.json"),
+            r#"{"mcpServers": {"fs": {"command": "npx", "args": []}}}"#,
+        )
+        .unwrap();
+        let result = check_mcp_config(dir.path());
+        assert_eq!(result.status, CheckStatus::Pass, "{}", result.detail);
+        assert_eq!(result.detail, "1 MCP server(s)");
+    }
+
+    #[test]
+    fn routing_endpoint_comes_from_mcp_json() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("mcp.json"),
+            r#"{"mcpServers": {}, "routing": {"llm_endpoint": "http://llm.internal:8080"}}"#,
+        )
+        .unwrap();
+
+        let endpoint = routing_endpoint(Some(dir.path()));
+        assert_eq!(endpoint.value, "http://llm.internal:8080");
+        assert_eq!(endpoint.source, ValueSource::McpJson);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn world_readable_home_is_flagged() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+        let result = check_home_permissions(dir.path());
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.hint.unwrap().contains("chmod 700"));
+
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o700)).unwrap();
+        assert_eq!(check_home_permissions(dir.path()).status, CheckStatus::Pass);
+    }
+}
@@ -0,0 +1,128 @@
+//! call命令实现 - 绕过智能路由，直接调用某个服务器的工具
+
+use super::test::ensure_command;
+use crate::mcp_routing::config::{McpConfigManager, McpServerConfig};
+use crate::mcp_routing::js_orchestrator::SchemaValidator;
+use crate::mcp_routing::McpConnectionPool;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use serde_json::Value;
+use std::sync::Arc;
+
+pub async fn execute(server: &str, tool: &str, args: Option<&str>) -> Result<()> {
+    let args = parse_args(args)?;
+
+    let config = McpConfigManager::load()?.config().clone();
+    let server_config = config.mcp_servers.get(server).cloned().ok_or_else(|| {
+        let mut names: Vec<&str> = config.mcp_servers.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        anyhow!(
+            "MCP server '{}' not found. Configured servers: {}",
+            server,
+            if names.is_empty() {
+                "(none)".to_string()
+            } else {
+                names.join(", ")
+            }
+        )
+    })?;
+    if !server_config.enabled.unwrap_or(true) {
+        return Err(anyhow!(
+            "MCP server '{}' is disabled. Enable it with 'aiw mcp enable {}'",
+            server,
+            server
+        ));
+    }
+    ensure_command(&server_config.command)?;
+
+    let pool = McpConnectionPool::new(Arc::new(config));
+    let result = call(&pool, server, server_config, tool, args).await;
+    // 无论调用结果如何都关闭子进程
+    pool.shutdown().await;
+
+    match result? {
+        Value::String(text) => println!("{}", text),
+        other => println!("{}", serde_json::to_string_pretty(&other)?),
+    }
+    Ok(())
+}
+
+async fn call(
+    pool: &McpConnectionPool,
+    server: &str,
+    server_config: McpServerConfig,
+    tool: &str,
+    args: Value,
+) -> Result<Value> {
+    let handle = pool
+        .ensure_handle(server.to_string(), server_config)
+        .await?;
+
+    let tools = handle.list_tools().await?;
+    let definition = tools
+        .iter()
+        .find(|discovered| discovered.definition.name == tool)
+        .map(|discovered| &discovered.definition)
+        .ok_or_else(|| {
+            let names: Vec<&str> = tools
+                .iter()
+                .map(|discovered| discovered.definition.name.as_ref())
+                .collect();
+            anyhow!(
+                "Tool '{}' not found on MCP server '{}'. Available tools: {}",
+                tool,
+                server,
+                names.join(", ")
+            )
+        })?;
+
+    let violations = SchemaValidator::validate_arguments(&definition.input_schema, &args);
+    if !violations.is_empty() {
+        let summary = violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        return Err(anyhow!("Invalid arguments for '{}': {}", tool, summary));
+    }
+
+    eprintln!("{} Calling {}.{}", "🔧".cyan(), server.cyan(), tool.bold());
+    pool.call_tool(server, tool, args).await
+}
+
+/// 解析 `--args` 的 JSON；省略时为空对象，根必须是对象
+fn parse_args(raw: Option<&str>) -> Result<Value> {
+    let Some(raw) = raw else {
+        return Ok(Value::Object(Default::default()));
+    };
+    let value: Value =
+        serde_json::from_str(raw).map_err(|err| anyhow!("--args is not valid JSON: {}", err))?;
+    if !value.is_object() {
+        return Err(anyhow!(
+            "--args must be a JSON object, e.g. '{{\"path\": \"/tmp\"}}'"
+        ));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_default_to_empty_object() {
+        assert_eq!(parse_args(None).unwrap(), serde_json::json!({}));
+        assert_eq!(
+            parse_args(Some(r#"{"path": "/tmp"}"#)).unwrap(),
+            serde_json::json!({"path": "/tmp"})
+        );
+    }
+
+    #[test]
+    fn args_must_be_a_json_object() {
+        let err = parse_args(Some("[1, 2]")).unwrap_err();
+        assert!(err.to_string().contains("must be a JSON object"));
+        let err = parse_args(Some("{path")).unwrap_err();
+        assert!(err.to_string().contains("not valid JSON"));
+    }
+}
@@ -0,0 +1,516 @@
+//! 把 MCP 服务器写入各 AI CLI 自己的配置文件
+//!
+//! `aiw mcp install --into` 在写入 ~/.aiw/mcp.json 之外，同时登记到：
+//! - claude: `~/.claude.json` 的 `mcpServers`
+//! - codex:  `$CODEX_HOME/config.toml`（默认 `~/.codex/config.toml`）的 `[mcp_servers.<name>]`
+//! - gemini: `~/.gemini/settings.json` 的 `mcpServers`
+//!
+//! 目标文件必须已存在（CLI 至少运行过一次），修改前备份到 ~/.aiw/backups/。
+//!
+//! 密钥不以明文写入这些文件：`${VAR}` 引用原样保留（Codex 改用 `env_vars` 透传），
+//! 看起来像密钥的明文值会被跳过并给出提示。远程服务器写成各 CLI 原生的 URL 形式。
+
+use super::McpServerConfig;
+use crate::cli_type::{parse_cli_type, CliType};
+use crate::mcp::audit::is_secret_key;
+use crate::utils::config_paths::aiw_config_dir;
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 可以登记 MCP 服务器的 CLI
+const SUPPORTED: [CliType; 3] = [CliType::Claude, CliType::Codex, CliType::Gemini];
+
+/// 解析 `--into` 的值：claude、codex、gemini 或 all
+pub fn parse_targets(value: &str) -> Result<Vec<CliType>> {
+    if value.trim().eq_ignore_ascii_case("all") {
+        return Ok(SUPPORTED.to_vec());
+    }
+    match parse_cli_type(value.trim()) {
+        Some(cli) if SUPPORTED.contains(&cli) => Ok(vec![cli]),
+        _ => Err(anyhow!(
+            "Invalid --into value '{}'; expected claude, codex, gemini or all",
+            value
+        )),
+    }
+}
+
+/// 成功写入一个 CLI 配置后的结果
+pub struct AddedServer {
+    /// 修改前的备份，失败回滚时用 [`CliMcpConfig::restore`] 还原
+    pub backup: PathBuf,
+    /// 未能写入的环境变量等提示
+    pub warnings: Vec<String>,
+}
+
+/// 某个 CLI 的 MCP 配置文件
+pub struct CliMcpConfig {
+    pub cli: CliType,
+    pub path: PathBuf,
+}
+
+impl CliMcpConfig {
+    /// 按各 CLI 的约定定位配置文件
+    pub fn locate(cli: CliType) -> Result<Self> {
+        let home = dirs::home_dir().ok_or_else(|| anyhow!("Cannot find home directory"))?;
+        let codex_home = std::env::var_os("CODEX_HOME")
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from);
+        Self::locate_in(cli, &home, codex_home)
+    }
+
+    fn locate_in(cli: CliType, home: &Path, codex_home: Option<PathBuf>) -> Result<Self> {
+        let path = match &cli {
+            CliType::Claude => home.join(".claude.json"),
+            CliType::Codex => codex_home
+                .unwrap_or_else(|| home.join(".codex"))
+                .join("config.toml"),
+            CliType::Gemini => home.join(".gemini").join("settings.json"),
+            other => {
+                return Err(anyhow!(
+                    "{} has no MCP server configuration",
+                    other.display_name()
+                ))
+            }
+        };
+        Ok(Self { cli, path })
+    }
+
+    /// 写入前检查：文件存在、可解析且没有同名服务器
+    pub fn check(&self, name: &str) -> Result<()> {
+        if !self.path.exists() {
+            return Err(anyhow!(
+                "{} config {} not found; run `{}` once to create it",
+                self.cli.display_name(),
+                self.path.display(),
+                self.cli.display_name()
+            ));
+        }
+        if self.has_server(name)? {
+            return Err(anyhow!(
+                "{} already has an MCP server named '{}' in {}",
+                self.cli.display_name(),
+                name,
+                self.path.display()
+            ));
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> Result<String> {
+        fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))
+    }
+
+    fn read_json(&self) -> Result<Value> {
+        let content = self.read()?;
+        if content.trim().is_empty() {
+            return Ok(json!({}));
+        }
+        let value: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid JSON in {}", self.path.display()))?;
+        if !value.is_object() {
+            return Err(anyhow!("{} is not a JSON object", self.path.display()));
+        }
+        Ok(value)
+    }
+
+    fn read_toml(&self) -> Result<toml::Table> {
+        self.read()?
+            .parse::<toml::Table>()
+            .with_context(|| format!("Invalid TOML in {}", self.path.display()))
+    }
+
+    fn has_server(&self, name: &str) -> Result<bool> {
+        Ok(match self.cli {
+            CliType::Codex => self
+                .read_toml()?
+                .get("mcp_servers")
+                .and_then(|servers| servers.get(name))
+                .is_some(),
+            _ => self
+                .read_json()?
+                .get("mcpServers")
+                .and_then(|servers| servers.get(name))
+                .is_some(),
+        })
+    }
+
+    /// 备份后写入服务器条目；写入失败时从备份还原
+    pub fn add_server(&self, name: &str, server: &McpServerConfig) -> Result<AddedServer> {
+        self.check(name)?;
+        let backup = self.backup()?;
+        let written = match self.cli {
+            CliType::Codex => self.append_toml_server(name, server),
+            _ => self.insert_json_server(name, server),
+        };
+        match written {
+            Ok(warnings) => Ok(AddedServer { backup, warnings }),
+            Err(err) => {
+                self.restore(&backup)?;
+                Err(err)
+            }
+        }
+    }
+
+    /// 用 [`CliMcpConfig::add_server`] 留下的备份覆盖配置文件
+    pub fn restore(&self, backup: &Path) -> Result<()> {
+        fs::copy(backup, &self.path).with_context(|| {
+            format!(
+                "Failed to restore {} from {}",
+                self.path.display(),
+                backup.display()
+            )
+        })?;
+        Ok(())
+    }
+
+    /// claude/gemini 都会展开 `${VAR}`，引用原样写入
+    fn insert_json_server(&self, name: &str, server: &McpServerConfig) -> Result<Vec<String>> {
+        let mut root = self.read_json()?;
+        let mut warnings = Vec::new();
+        let entry = match remote_url(server) {
+            Some(url) => {
+                warnings.extend(remote_env_warning(server));
+                match self.cli {
+                    CliType::Claude => json!({ "type": "http", "url": url }),
+                    _ => json!({ "httpUrl": url }),
+                }
+            }
+            None => {
+                let mut entry = json!({
+                    "command": server.command,
+                    "args": server.args,
+                });
+                let mut env = serde_json::Map::new();
+                for (key, value) in sorted_env(server) {
+                    if env_reference(value).is_some() || !is_secret_key(key) {
+                        env.insert(key.clone(), json!(value));
+                    } else {
+                        warnings.push(skipped_secret_warning(&self.cli, key));
+                    }
+                }
+                if !env.is_empty() {
+                    entry["env"] = Value::Object(env);
+                }
+                if self.cli == CliType::Claude {
+                    entry["type"] = json!("stdio");
+                }
+                entry
+            }
+        };
+
+        let servers = root
+            .as_object_mut()
+            .expect("checked in read_json")
+            .entry("mcpServers")
+            .or_insert_with(|| json!({}));
+        let Some(servers) = servers.as_object_mut() else {
+            return Err(anyhow!(
+                "mcpServers in {} is not an object",
+                self.path.display()
+            ));
+        };
+        servers.insert(name.to_string(), entry);
+
+        let content = serde_json::to_string_pretty(&root)?;
+        fs::write(&self.path, content)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(warnings)
+    }
+
+    /// 以追加方式写入，保留用户 config.toml 中的注释和排版
+    fn append_toml_server(&self, name: &str, server: &McpServerConfig) -> Result<Vec<String>> {
+        let (section, warnings) = codex_server_section(name, server)?;
+        let existing = self.read()?;
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        let separator = if existing.is_empty() || existing.ends_with("\n\n") {
+            ""
+        } else if existing.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        write!(file, "{}{}", separator, section)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        Ok(warnings)
+    }
+
+    fn backup(&self) -> Result<PathBuf> {
+        let dir = aiw_config_dir()
+            .ok_or_else(|| anyhow!("Cannot find home directory"))?
+            .join("backups");
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create backup directory: {}", dir.display()))?;
+        let extension = self
+            .path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("bak");
+        let backup = dir.join(format!(
+            "{}-{}.{}",
+            self.cli.display_name(),
+            Utc::now().format("%Y%m%dT%H%M%S%.6f"),
+            extension
+        ));
+        fs::copy(&self.path, &backup).with_context(|| {
+            format!(
+                "Failed to back up {} to {}",
+                self.path.display(),
+                backup.display()
+            )
+        })?;
+        Ok(backup)
+    }
+}
+
+/// `${VAR}` 形式的环境变量引用，返回变量名
+fn env_reference(value: &str) -> Option<&str> {
+    value
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))
+        .filter(|var| !var.is_empty())
+}
+
+/// 远程服务器在 mcp.json 中记为 `command: "remote"`，`args[0]` 为 URL
+fn remote_url(server: &McpServerConfig) -> Option<&str> {
+    if server.command == "remote" {
+        server.args.first().map(String::as_str)
+    } else {
+        None
+    }
+}
+
+fn sorted_env(server: &McpServerConfig) -> Vec<(&String, &String)> {
+    let mut env: Vec<_> = server.env.iter().collect();
+    env.sort();
+    env
+}
+
+fn remote_env_warning(server: &McpServerConfig) -> Option<String> {
+    (!server.env.is_empty()).then(|| {
+        "env is not passed to remote servers; configure credentials in the CLI instead".to_string()
+    })
+}
+
+fn skipped_secret_warning(cli: &CliType, key: &str) -> String {
+    format!(
+        "Skipped {} for {}: secret values are not copied in plain text; use `--env {}=${{{}}}` and export it before starting {}",
+        key,
+        cli.display_name(),
+        key,
+        key,
+        cli.display_name()
+    )
+}
+
+/// 生成 `[mcp_servers.<name>]` 段落；表头手写，避免与文件中已有的 `[mcp_servers.*]` 重复定义父表
+///
+/// Codex 不展开 `${VAR}`：同名引用写进 `env_vars` 由 Codex 从自身环境透传，
+/// 改名的引用和明文密钥跳过并返回提示。
+fn codex_server_section(name: &str, server: &McpServerConfig) -> Result<(String, Vec<String>)> {
+    let key = if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        name.to_string()
+    } else {
+        toml::Value::String(name.to_string()).to_string()
+    };
+
+    let mut warnings = Vec::new();
+    let mut entry = toml::Table::new();
+    let mut env = toml::Table::new();
+    if let Some(url) = remote_url(server) {
+        warnings.extend(remote_env_warning(server));
+        entry.insert("url".into(), url.into());
+    } else {
+        entry.insert("command".into(), server.command.clone().into());
+        entry.insert(
+            "args".into(),
+            toml::Value::Array(server.args.iter().cloned().map(Into::into).collect()),
+        );
+        let mut forwarded = Vec::new();
+        for (key, value) in sorted_env(server) {
+            match env_reference(value) {
+                Some(var) if var == key => forwarded.push(toml::Value::from(key.clone())),
+                Some(var) => warnings.push(format!(
+                    "Skipped {} for codex: Codex cannot map it to ${{{}}}; export {} before starting codex",
+                    key, var, key
+                )),
+                None if is_secret_key(key) => {
+                    warnings.push(skipped_secret_warning(&CliType::Codex, key))
+                }
+                None => {
+                    env.insert(key.clone(), value.clone().into());
+                }
+            }
+        }
+        if !forwarded.is_empty() {
+            entry.insert("env_vars".into(), toml::Value::Array(forwarded));
+        }
+    }
+    let mut section = format!(
+        "[mcp_servers.{}]\n{}",
+        key,
+        toml::to_string(&entry).context("Failed to serialize Codex MCP server entry")?
+    );
+
+    if !env.is_empty() {
+        section.push_str(&format!(
+            "\n[mcp_servers.{}.env]\n{}",
+            key,
+            toml::to_string(&env).context("Failed to serialize Codex MCP server env")?
+        ));
+    }
+    Ok((section, warnings))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn server() -> McpServerConfig {
+        McpServerConfig {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "@test/server".to_string()],
+            env: HashMap::from([
+                ("API_KEY".to_string(), "secret".to_string()),
+                ("GITHUB_TOKEN".to_string(), "${GITHUB_TOKEN}".to_string()),
+                ("LOG_LEVEL".to_string(), "debug".to_string()),
+            ]),
+            description: None,
+            category: None,
+            enabled: None,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn parse_targets_accepts_supported_clis_and_all() {
+        assert_eq!(parse_targets("codex").unwrap(), vec![CliType::Codex]);
+        assert_eq!(parse_targets("ALL").unwrap().len(), 3);
+        assert!(parse_targets("grok").is_err());
+        assert!(parse_targets("vim").is_err());
+    }
+
+    #[test]
+    fn writes_json_and_toml_entries_without_clobbering_existing_content() {
+        let home = tempfile::tempdir().unwrap();
+        fs::write(
+            home.path().join(".claude.json"),
+            r#"{"theme":"dark","mcpServers":{"old":{"command":"old-mcp"}}}"#,
+        )
+        .unwrap();
+        let codex_home = home.path().join("codex");
+        fs::create_dir_all(&codex_home).unwrap();
+        fs::write(
+            codex_home.join("config.toml"),
+            "# my settings\nmodel = \"o3\"\n\n[mcp_servers.git]\ncommand = \"uvx\"\n",
+        )
+        .unwrap();
+
+        let claude = CliMcpConfig::locate_in(CliType::Claude, home.path(), None).unwrap();
+        let codex =
+            CliMcpConfig::locate_in(CliType::Codex, home.path(), Some(codex_home.clone())).unwrap();
+        let gemini = CliMcpConfig::locate_in(CliType::Gemini, home.path(), None).unwrap();
+
+        // Write through the helpers directly; add_server also backs up under ~/.aiw
+        let warnings = claude.insert_json_server("fs", &server()).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Skipped API_KEY for claude"));
+        codex.append_toml_server("fs", &server()).unwrap();
+
+        let root: Value = serde_json::from_str(&fs::read_to_string(&claude.path).unwrap()).unwrap();
+        assert_eq!(root["theme"], "dark");
+        assert_eq!(root["mcpServers"]["old"]["command"], "old-mcp");
+        assert_eq!(root["mcpServers"]["fs"]["type"], "stdio");
+        assert_eq!(root["mcpServers"]["fs"]["args"][1], "@test/server");
+        let env = &root["mcpServers"]["fs"]["env"];
+        assert_eq!(env["GITHUB_TOKEN"], "${GITHUB_TOKEN}");
+        assert_eq!(env["LOG_LEVEL"], "debug");
+        assert!(env.get("API_KEY").is_none());
+
+        let content = fs::read_to_string(&codex.path).unwrap();
+        assert!(content.starts_with("# my settings\nmodel = \"o3\"\n"));
+        codex.append_toml_server("@scope/name", &server()).unwrap();
+        let content = fs::read_to_string(&codex.path).unwrap();
+        let table: toml::Table = content.parse().unwrap();
+        assert_eq!(table["mcp_servers"]["fs"]["command"].as_str(), Some("npx"));
+        // 明文密钥不落盘，同名引用交给 Codex 透传
+        assert!(!content.contains("secret"));
+        assert_eq!(
+            table["mcp_servers"]["fs"]["env_vars"][0].as_str(),
+            Some("GITHUB_TOKEN")
+        );
+        assert_eq!(
+            table["mcp_servers"]["fs"]["env"]["LOG_LEVEL"].as_str(),
+            Some("debug")
+        );
+        assert_eq!(table["mcp_servers"]["git"]["command"].as_str(), Some("uvx"));
+        assert!(table["mcp_servers"].get("@scope/name").is_some());
+
+        assert!(claude
+            .check("fs")
+            .unwrap_err()
+            .to_string()
+            .contains("already has"));
+        assert!(codex.check("fs").is_err());
+        assert!(codex.check("other").is_ok());
+        assert!(gemini
+            .check("fs")
+            .unwrap_err()
+            .to_string()
+            .contains("run `gemini` once"));
+    }
+
+    #[test]
+    fn remote_servers_use_each_clis_url_form() {
+        let home = tempfile::tempdir().unwrap();
+        fs::write(home.path().join(".claude.json"), "{}").unwrap();
+        fs::create_dir_all(home.path().join(".gemini")).unwrap();
+        fs::write(home.path().join(".gemini/settings.json"), "").unwrap();
+        let remote = McpServerConfig {
+            command: "remote".to_string(),
+            args: vec!["https://mcp.example.com/mcp".to_string()],
+            env: HashMap::new(),
+            description: None,
+            category: None,
+            enabled: None,
+            source: None,
+        };
+
+        let claude = CliMcpConfig::locate_in(CliType::Claude, home.path(), None).unwrap();
+        claude.insert_json_server("web", &remote).unwrap();
+        let root: Value = serde_json::from_str(&fs::read_to_string(&claude.path).unwrap()).unwrap();
+        assert_eq!(
+            root["mcpServers"]["web"],
+            json!({"type": "http", "url": "https://mcp.example.com/mcp"})
+        );
+
+        let gemini = CliMcpConfig::locate_in(CliType::Gemini, home.path(), None).unwrap();
+        gemini.insert_json_server("web", &remote).unwrap();
+        let root: Value = serde_json::from_str(&fs::read_to_string(&gemini.path).unwrap()).unwrap();
+        assert_eq!(
+            root["mcpServers"]["web"]["httpUrl"],
+            "https://mcp.example.com/mcp"
+        );
+
+        let (section, warnings) = codex_server_section("web", &remote).unwrap();
+        assert!(warnings.is_empty());
+        let table: toml::Table = section.parse().unwrap();
+        assert_eq!(
+            table["mcp_servers"]["web"]["url"].as_str(),
+            Some("https://mcp.example.com/mcp")
+        );
+        assert!(table["mcp_servers"]["web"].get("command").is_none());
+    }
+}
@@ -2,11 +2,17 @@
 //!
 //! 提供对 ~/.aiw/mcp.json 的读写和操作功能
 
+use crate::mcp_routing::config::RoutingConfig;
+use crate::utils::config_paths::aiw_config_dir;
 use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// 最多保留的 mcp.json 备份数量，超出时删除最旧的
+const MAX_BACKUPS: usize = 20;
 
 /// MCP服务器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,11 +33,38 @@ pub struct McpServerConfig {
     pub source: Option<String>,
 }
 
+impl McpServerConfig {
+    /// 校验单个服务器条目（导入或手工编辑后写入前调用）
+    pub fn validate(&self, name: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(anyhow!("MCP server name cannot be empty"));
+        }
+        if self.command.trim().is_empty() {
+            return Err(anyhow!("MCP server '{}' is missing a command", name));
+        }
+        if let Some(key) = self
+            .env
+            .keys()
+            .find(|key| key.is_empty() || key.contains('='))
+        {
+            return Err(anyhow!(
+                "MCP server '{}' has an invalid environment variable name '{}'",
+                name,
+                key
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// MCP配置文件根结构
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct McpConfig {
     pub mcp_servers: HashMap<String, McpServerConfig>,
+    /// 路由/决策设置，由 `aiw routing config` 维护
+    #[serde(default, skip_serializing_if = "RoutingConfig::is_empty")]
+    pub routing: RoutingConfig,
 }
 
 /// MCP配置文件编辑器
@@ -42,9 +75,8 @@ pub struct McpConfigEditor {
 impl McpConfigEditor {
     /// 创建新的配置编辑器
     pub fn new() -> Result<Self> {
-        let config_path = dirs::home_dir()
+        let config_path = aiw_config_dir()
             .ok_or_else(|| anyhow!("Cannot find home directory"))?
-            .join(".aiw")
             .join("mcp.json");
 
         Ok(Self { config_path })
@@ -72,6 +104,7 @@ impl McpConfigEditor {
             // 配置文件不存在，返回空配置
             return Ok(McpConfig {
                 mcp_servers: HashMap::new(),
+                routing: RoutingConfig::default(),
             });
         }
 
@@ -88,9 +121,10 @@ impl McpConfigEditor {
         Ok(config)
     }
 
-    /// 写入配置文件
+    /// 写入配置文件（写入前自动备份当前文件）
     pub fn write(&self, config: &McpConfig) -> Result<()> {
         self.ensure_config_dir()?;
+        self.backup()?;
 
         let content =
             serde_json::to_string_pretty(config).context("Failed to serialize MCP config")?;
@@ -105,6 +139,76 @@ impl McpConfigEditor {
         Ok(())
     }
 
+    /// 备份目录（与 mcp.json 同级的 backups/，即 ~/.aiw/backups/）
+    pub fn backup_dir(&self) -> PathBuf {
+        self.config_path
+            .parent()
+            .map(|dir| dir.join("backups"))
+            .unwrap_or_else(|| PathBuf::from("backups"))
+    }
+
+    /// 把当前 mcp.json 复制为带时间戳的备份；配置文件不存在时返回 None
+    pub fn backup(&self) -> Result<Option<PathBuf>> {
+        if !self.config_path.exists() {
+            return Ok(None);
+        }
+
+        let dir = self.backup_dir();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create backup directory: {}", dir.display()))?;
+        let path = dir.join(format!(
+            "mcp-{}.json",
+            Utc::now().format("%Y%m%dT%H%M%S%.6f")
+        ));
+        fs::copy(&self.config_path, &path)
+            .with_context(|| format!("Failed to back up MCP config to {}", path.display()))?;
+
+        for stale in self.list_backups()?.into_iter().skip(MAX_BACKUPS) {
+            let _ = fs::remove_file(stale);
+        }
+        Ok(Some(path))
+    }
+
+    /// 列出备份，最新的在前
+    pub fn list_backups(&self) -> Result<Vec<PathBuf>> {
+        let dir = self.backup_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut backups: Vec<PathBuf> = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read backup directory: {}", dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("mcp-") && name.ends_with(".json"))
+            })
+            .collect();
+        // 文件名中的时间戳按字典序即按时间排序
+        backups.sort();
+        backups.reverse();
+        Ok(backups)
+    }
+
+    /// 用备份覆盖 mcp.json。备份必须是合法配置；覆盖前先备份当前文件，便于撤销
+    pub fn restore(&self, backup: &Path) -> Result<()> {
+        let content = fs::read_to_string(backup)
+            .with_context(|| format!("Failed to read backup {}", backup.display()))?;
+        serde_json::from_str::<McpConfig>(&content)
+            .with_context(|| format!("Backup {} is not a valid MCP config", backup.display()))?;
+
+        self.ensure_config_dir()?;
+        self.backup()?;
+        fs::write(&self.config_path, content).with_context(|| {
+            format!(
+                "Failed to write MCP config to {}",
+                self.config_path.display()
+            )
+        })?;
+        Ok(())
+    }
+
     /// 检查服务器是否存在
     pub fn server_exists(&self, name: &str) -> Result<bool> {
         let config = self.read()?;
@@ -173,6 +277,37 @@ impl McpConfigEditor {
         Ok(())
     }
 
+    /// 批量设置启用状态，只写一次文件；返回状态实际发生变化的服务器
+    ///
+    /// 任一名称不存在时报错且不做任何修改；已处于目标状态的服务器跳过。
+    pub fn set_servers_enabled(&self, names: &[String], enabled: bool) -> Result<Vec<String>> {
+        let mut config = self.read()?;
+
+        let missing: Vec<&str> = names
+            .iter()
+            .filter(|name| !config.mcp_servers.contains_key(*name))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow!("MCP server(s) not found: {}", missing.join(", ")));
+        }
+
+        let mut changed = Vec::new();
+        for name in names {
+            if let Some(server) = config.mcp_servers.get_mut(name) {
+                if server.enabled.unwrap_or(true) != enabled {
+                    server.enabled = Some(enabled);
+                    changed.push(name.clone());
+                }
+            }
+        }
+        if !changed.is_empty() {
+            self.write(&config)?;
+        }
+
+        Ok(changed)
+    }
+
     /// 列出所有服务器
     pub fn list_servers(&self) -> Result<Vec<(String, McpServerConfig)>> {
         let config = self.read()?;
@@ -202,30 +337,28 @@ impl McpConfigEditor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::CONFIG_DIR_ENV;
+    use serial_test::serial;
     use std::env;
     use tempfile::TempDir;
 
+    /// 通过 `AIW_CONFIG_DIR` 把编辑器指向临时目录，不改动进程的 HOME
     fn setup_test_env() -> (TempDir, McpConfigEditor) {
         let temp_dir = TempDir::new().unwrap();
-        let config_path = temp_dir.path().join("mcp.json");
-
-        // 临时修改HOME环境变量
-        let original_home = env::var("HOME").ok();
-        env::set_var("HOME", temp_dir.path());
 
-        let editor = McpConfigEditor {
-            config_path: temp_dir.path().join(".aiw").join("mcp.json"),
-        };
-
-        // 恢复原始HOME
-        if let Some(home) = original_home {
-            env::set_var("HOME", home);
+        let original = env::var_os(CONFIG_DIR_ENV);
+        env::set_var(CONFIG_DIR_ENV, temp_dir.path().join(".aiw"));
+        let editor = McpConfigEditor::new().unwrap();
+        match original {
+            Some(dir) => env::set_var(CONFIG_DIR_ENV, dir),
+            None => env::remove_var(CONFIG_DIR_ENV),
         }
 
         (temp_dir, editor)
     }
 
     #[test]
+    #[serial]
     fn test_read_empty_config() {
         let (_temp, editor) = setup_test_env();
         let config = editor.read().unwrap();
@@ -233,6 +366,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_add_server() {
         let (_temp, editor) = setup_test_env();
 
@@ -254,6 +388,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_remove_server() {
         let (_temp, editor) = setup_test_env();
 
@@ -275,6 +410,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_duplicate_server() {
         let (_temp, editor) = setup_test_env();
 
@@ -296,6 +432,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     fn test_set_enabled() {
         let (_temp, editor) = setup_test_env();
 
@@ -315,4 +452,81 @@ mod tests {
         let server = editor.get_server("test").unwrap().unwrap();
         assert_eq!(server.enabled, Some(false));
     }
+
+    #[test]
+    #[serial]
+    fn test_set_servers_enabled_skips_unchanged_and_rejects_unknown() {
+        let (_temp, editor) = setup_test_env();
+        let server = |enabled: Option<bool>| McpServerConfig {
+            command: "npx".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            description: None,
+            category: None,
+            enabled,
+            source: None,
+        };
+        editor.add_server("a", server(None)).unwrap();
+        editor.add_server("b", server(Some(false))).unwrap();
+        editor.add_server("c", server(Some(true))).unwrap();
+
+        let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let changed = editor
+            .set_servers_enabled(&names(&["a", "b", "c"]), false)
+            .unwrap();
+        assert_eq!(changed, vec!["a", "c"]);
+        assert!(editor
+            .list_servers()
+            .unwrap()
+            .iter()
+            .all(|(_, cfg)| cfg.enabled == Some(false)));
+
+        // 没有变化时不写文件（不产生新备份）
+        let backups = editor.list_backups().unwrap().len();
+        assert!(editor
+            .set_servers_enabled(&names(&["a", "b"]), false)
+            .unwrap()
+            .is_empty());
+        assert_eq!(editor.list_backups().unwrap().len(), backups);
+
+        let err = editor
+            .set_servers_enabled(&names(&["a", "nope"]), true)
+            .unwrap_err();
+        assert!(err.to_string().contains("nope"));
+        assert_eq!(
+            editor.get_server("a").unwrap().unwrap().enabled,
+            Some(false)
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_writes_are_backed_up_and_restorable() {
+        let (_temp, editor) = setup_test_env();
+        assert!(editor.backup().unwrap().is_none(), "nothing to back up yet");
+
+        let server_config = McpServerConfig {
+            command: "npx".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            description: None,
+            category: None,
+            enabled: Some(true),
+            source: None,
+        };
+        editor.add_server("test", server_config).unwrap();
+        editor.remove_server("test").unwrap();
+
+        // remove 之前的备份里仍有 test
+        let backups = editor.list_backups().unwrap();
+        assert_eq!(backups.len(), 1);
+        editor.restore(&backups[0]).unwrap();
+        assert!(editor.server_exists("test").unwrap());
+        assert_eq!(editor.list_backups().unwrap().len(), 2);
+
+        let broken = editor.backup_dir().join("mcp-broken.json");
+        fs::write(&broken, "{ not json").unwrap();
+        assert!(editor.restore(&broken).is_err());
+        assert!(editor.server_exists("test").unwrap());
+    }
 }
@@ -56,8 +56,16 @@ pub fn execute() -> Result<()> {
         editor_cmd.green()
     );
 
-    // 读取编辑前的内容用于验证
+    // 编辑前备份，编辑结果无效时据此恢复
+    let backup = editor.backup()?;
     let original_content = fs::read_to_string(&config_path)?;
+    let revert = || -> Result<()> {
+        match &backup {
+            Some(path) => fs::copy(path, &config_path).map(|_| ()),
+            None => fs::write(&config_path, &original_content),
+        }
+        .with_context(|| format!("Failed to restore {}", config_path.display()))
+    };
 
     // 打开编辑器
     let status = Command::new(&editor_cmd)
@@ -83,8 +91,8 @@ pub fn execute() -> Result<()> {
                     println!();
                 }
                 Err(e) => {
-                    // JSON有效但结构不正确，恢复原始内容
-                    fs::write(&config_path, original_content)?;
+                    // JSON有效但结构不正确，恢复备份
+                    revert()?;
                     eprintln!("{} Invalid MCP configuration structure: {}", "❌".red(), e);
                     println!();
                     println!("Changes have been reverted.");
@@ -93,13 +101,21 @@ pub fn execute() -> Result<()> {
             }
         }
         Err(e) => {
-            // JSON语法错误，恢复原始内容
-            fs::write(&config_path, original_content)?;
-            eprintln!("{} Invalid JSON syntax", "❌".red());
+            // JSON语法错误，恢复备份
+            revert()?;
+            eprintln!(
+                "{} Invalid JSON syntax at line {}, column {}",
+                "❌".red(),
+                e.line(),
+                e.column()
+            );
             println!();
             println!("Error: {}", e);
             println!();
             println!("Changes have been reverted.");
+            if let Some(path) = &backup {
+                println!("Backup: {}", path.display());
+            }
             println!();
         }
     }
@@ -1,50 +1,61 @@
 //! enable/disable命令实现 - 启用/禁用MCP服务器
+//!
+//! 支持单个名称、逗号分隔的名称列表以及 `--all`。所有修改合并为一次写入，
+//! 运行中的 aiw MCP 服务器通过配置文件监听自动重新加载。
 
 use super::McpConfigEditor;
 use anyhow::Result;
 use colored::Colorize;
 
-pub fn execute_enable(name: &str) -> Result<()> {
-    let editor = McpConfigEditor::new()?;
+pub fn execute_enable(name: Option<&str>, all: bool) -> Result<()> {
+    execute_toggle(name, all, true)
+}
 
-    // 检查服务器是否存在
-    if !editor.server_exists(name)? {
-        eprintln!("{} MCP server '{}' not found", "❌".red(), name.yellow());
-        println!();
-        println!("Available servers:");
-        let servers = editor.list_servers()?;
-        for (server_name, _) in servers {
-            println!("  • {}", server_name);
+pub fn execute_disable(name: Option<&str>, all: bool) -> Result<()> {
+    execute_toggle(name, all, false)
+}
+
+/// 解析逗号分隔的服务器名称，去掉空项和重复项（保持顺序）
+pub fn parse_server_names(input: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in input
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
         }
-        println!();
-        println!(
-            "Use '{}' to see all servers",
-            format!("{} mcp list", "aiw").cyan()
-        );
-        return Ok(());
     }
-
-    // 启用服务器
-    editor.set_server_enabled(name, true)?;
-
-    println!("{} Enabled MCP server '{}'", "✅".green(), name.cyan());
-    println!();
-    println!("Restart your AI CLI to apply changes.");
-    println!();
-
-    Ok(())
+    names
 }
 
-pub fn execute_disable(name: &str) -> Result<()> {
+fn execute_toggle(name: Option<&str>, all: bool, enabled: bool) -> Result<()> {
     let editor = McpConfigEditor::new()?;
+    let servers = editor.list_servers()?;
+
+    let names: Vec<String> = if all {
+        servers.iter().map(|(name, _)| name.clone()).collect()
+    } else {
+        parse_server_names(name.unwrap_or_default())
+    };
+    if names.is_empty() {
+        println!("No MCP servers to update.");
+        return Ok(());
+    }
 
     // 检查服务器是否存在
-    if !editor.server_exists(name)? {
-        eprintln!("{} MCP server '{}' not found", "❌".red(), name.yellow());
+    let missing: Vec<&String> = names
+        .iter()
+        .filter(|name| !servers.iter().any(|(server, _)| server == *name))
+        .collect();
+    if !missing.is_empty() {
+        for name in missing {
+            eprintln!("{} MCP server '{}' not found", "❌".red(), name.yellow());
+        }
         println!();
         println!("Available servers:");
-        let servers = editor.list_servers()?;
-        for (server_name, _) in servers {
+        for (server_name, _) in &servers {
             println!("  • {}", server_name);
         }
         println!();
@@ -55,14 +66,59 @@ pub fn execute_disable(name: &str) -> Result<()> {
         return Ok(());
     }
 
-    // 禁用服务器
-    editor.set_server_enabled(name, false)?;
+    let changed = editor.set_servers_enabled(&names, enabled)?;
+    let (verb, state) = if enabled {
+        ("Enabled", "enabled")
+    } else {
+        ("Disabled", "disabled")
+    };
 
-    println!("{} Disabled MCP server '{}'", "✅".green(), name.cyan());
+    for name in &changed {
+        println!("{} {} MCP server '{}'", "✅".green(), verb, name.cyan());
+    }
+    for name in names.iter().filter(|name| !changed.contains(name)) {
+        println!("{} '{}' is already {}", "•".dimmed(), name, state);
+    }
     println!();
-    println!("The server configuration is preserved but will not be loaded.");
-    println!("To re-enable: {} mcp enable {}", "aiw".cyan(), name);
+    println!(
+        "{} changed, {} already {}",
+        changed.len(),
+        names.len() - changed.len(),
+        state
+    );
+
+    if !changed.is_empty() {
+        println!();
+        if !enabled {
+            println!("The server configuration is preserved but will not be loaded.");
+            println!(
+                "To re-enable: {} mcp enable {}",
+                "aiw".cyan(),
+                if all {
+                    "--all".to_string()
+                } else {
+                    changed.join(",")
+                }
+            );
+        }
+        println!("A running aiw MCP server reloads mcp.json automatically;");
+        println!("restart other AI CLIs to apply changes.");
+    }
     println!();
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_server_names_trims_and_dedups() {
+        assert_eq!(
+            parse_server_names(" a, b,,a ,c "),
+            vec!["a".to_string(), "b".to_string(), "c".to_string()]
+        );
+        assert!(parse_server_names(" , ").is_empty());
+    }
+}
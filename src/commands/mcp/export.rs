@@ -0,0 +1,196 @@
+//! export命令实现 - 导出服务器配置到可分享的文件
+
+use super::config_editor::McpConfig;
+use super::{McpConfigEditor, McpServerConfig};
+use crate::mcp::audit::is_secret_key;
+use crate::mcp_routing::config::RoutingConfig;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub fn execute(file: &str, redact: bool) -> Result<()> {
+    let editor = McpConfigEditor::new()?;
+    let config = editor.read()?;
+
+    let mut servers = config.mcp_servers;
+    if redact {
+        redact_env(&mut servers);
+        redact_args(&mut servers);
+    }
+    let count = servers.len();
+
+    // routing 段属于本机设置（可能含 token），不随服务器一起导出
+    let exported = McpConfig {
+        mcp_servers: servers,
+        routing: RoutingConfig::default(),
+    };
+    let content =
+        serde_json::to_string_pretty(&exported).context("Failed to serialize MCP config")?;
+    let path = Path::new(file);
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+
+    println!(
+        "{} Exported {} MCP server(s) to {}",
+        "✅".green(),
+        count,
+        file.cyan()
+    );
+    if redact {
+        println!(
+            "Environment values and secret arguments were replaced with ${{KEY}} placeholders."
+        );
+    } else if servers_have_secrets(&exported.mcp_servers) {
+        println!(
+            "{} Environment values and secret arguments are included as-is; use --redact before sharing",
+            "⚠️".yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// 把环境变量值替换为 `${KEY}` 占位符（已是占位符的保持不变）
+fn redact_env(servers: &mut HashMap<String, McpServerConfig>) {
+    for server in servers.values_mut() {
+        for (key, value) in server.env.iter_mut() {
+            if !is_placeholder(value) {
+                *value = format!("${{{}}}", key);
+            }
+        }
+    }
+}
+
+/// 按与环境变量相同的规则（[`is_secret_key`]）替换疑似密钥参数的值：
+/// `--token X`、`--api-key=X` 变为 `--token ${TOKEN}`、`--api-key=${API_KEY}`
+fn redact_args(servers: &mut HashMap<String, McpServerConfig>) {
+    for server in servers.values_mut() {
+        redact_arg_list(&mut server.args);
+    }
+}
+
+/// 替换一组参数中的密钥值，返回是否有值被替换
+fn redact_arg_list(args: &mut [String]) -> bool {
+    let mut redacted = false;
+    let mut pending: Option<String> = None;
+    for arg in args.iter_mut() {
+        // 上一个参数是 `--token` 这类不带值的密钥参数，当前参数就是它的值
+        if let Some(placeholder) = pending.take() {
+            if !arg.starts_with('-') {
+                if !is_placeholder(arg) {
+                    *arg = placeholder;
+                    redacted = true;
+                }
+                continue;
+            }
+        }
+        if !arg.starts_with('-') {
+            continue;
+        }
+        let flag = arg.trim_start_matches('-');
+        match flag.split_once('=') {
+            Some((name, value)) => {
+                if is_secret_key(name) && !is_placeholder(value) {
+                    let name = name.to_string();
+                    let prefix_len = arg.len() - value.len();
+                    arg.replace_range(prefix_len.., &placeholder(&name));
+                    redacted = true;
+                }
+            }
+            None if is_secret_key(flag) => pending = Some(placeholder(flag)),
+            None => {}
+        }
+    }
+    redacted
+}
+
+/// 参数名对应的占位符：`api-key` → `${API_KEY}`
+fn placeholder(name: &str) -> String {
+    format!("${{{}}}", name.replace('-', "_").to_ascii_uppercase())
+}
+
+fn is_placeholder(value: &str) -> bool {
+    value.starts_with("${") && value.ends_with('}')
+}
+
+fn servers_have_secrets(servers: &HashMap<String, McpServerConfig>) -> bool {
+    servers
+        .values()
+        .any(|server| !server.env.is_empty() || redact_arg_list(&mut server.args.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redact_replaces_values_with_placeholders() {
+        let mut servers = HashMap::new();
+        servers.insert(
+            "github".to_string(),
+            McpServerConfig {
+                command: "npx".to_string(),
+                args: vec![],
+                env: [
+                    ("GITHUB_TOKEN".to_string(), "ghp_secret".to_string()),
+                    ("API_URL".to_string(), "${API_URL}".to_string()),
+                ]
+                .into(),
+                description: None,
+                category: None,
+                enabled: None,
+                source: None,
+            },
+        );
+
+        redact_env(&mut servers);
+
+        let env = &servers["github"].env;
+        assert_eq!(env["GITHUB_TOKEN"], "${GITHUB_TOKEN}");
+        assert_eq!(env["API_URL"], "${API_URL}");
+    }
+
+    #[test]
+    fn redact_replaces_secret_argument_values() {
+        let mut args: Vec<String> = [
+            "-y",
+            "server-github",
+            "--token",
+            "ghp_secret",
+            "--api-key=sk-live",
+            "--password",
+            "${PASSWORD}",
+            "--port",
+            "8080",
+            "--access-token",
+            "--verbose",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+
+        assert!(redact_arg_list(&mut args));
+        assert_eq!(
+            args,
+            [
+                "-y",
+                "server-github",
+                "--token",
+                "${TOKEN}",
+                "--api-key=${API_KEY}",
+                "--password",
+                "${PASSWORD}",
+                "--port",
+                "8080",
+                "--access-token",
+                "--verbose",
+            ]
+        );
+        assert!(!redact_arg_list(&mut args), "already redacted");
+    }
+}
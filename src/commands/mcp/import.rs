@@ -0,0 +1,159 @@
+//! import命令实现 - 从文件导入服务器配置
+
+use super::config_editor::McpConfig;
+use super::{McpConfigEditor, McpServerConfig};
+use anyhow::{anyhow, Context, Result};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::fs;
+
+/// 导入结果统计
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ImportSummary {
+    added: Vec<String>,
+    overwritten: Vec<String>,
+    skipped: Vec<String>,
+    removed: Vec<String>,
+}
+
+pub fn execute(file: &str, replace: bool, overwrite: bool) -> Result<()> {
+    let content = fs::read_to_string(file).with_context(|| format!("Failed to read {}", file))?;
+    let incoming: McpConfig = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not a valid MCP config (expected mcpServers)", file))?;
+    for (name, server) in &incoming.mcp_servers {
+        server.validate(name)?;
+    }
+    if incoming.mcp_servers.is_empty() {
+        return Err(anyhow!("{} contains no MCP servers", file));
+    }
+
+    let editor = McpConfigEditor::new()?;
+    let mut config = editor.read()?;
+    let summary = apply_import(
+        &mut config.mcp_servers,
+        incoming.mcp_servers,
+        replace,
+        overwrite,
+    );
+    editor.write(&config)?;
+
+    println!(
+        "{} Imported MCP servers from {} into {}",
+        "✅".green(),
+        file.cyan(),
+        editor.config_path().display()
+    );
+    print_names("Added", &summary.added);
+    print_names("Overwritten", &summary.overwritten);
+    print_names("Removed", &summary.removed);
+    print_names("Skipped (already exists)", &summary.skipped);
+    if !summary.skipped.is_empty() {
+        println!("Use --overwrite to replace existing servers with the imported ones.");
+    }
+    println!("Restart your AI CLI to apply changes.");
+
+    Ok(())
+}
+
+/// 合并（或替换）服务器表；合并时同名服务器默认跳过，`overwrite` 时覆盖
+fn apply_import(
+    existing: &mut HashMap<String, McpServerConfig>,
+    incoming: HashMap<String, McpServerConfig>,
+    replace: bool,
+    overwrite: bool,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+
+    if replace {
+        summary.removed = existing
+            .keys()
+            .filter(|name| !incoming.contains_key(*name))
+            .cloned()
+            .collect();
+        existing.clear();
+    }
+
+    for (name, server) in incoming {
+        if existing.contains_key(&name) {
+            if !overwrite {
+                summary.skipped.push(name);
+                continue;
+            }
+            summary.overwritten.push(name.clone());
+        } else {
+            summary.added.push(name.clone());
+        }
+        existing.insert(name, server);
+    }
+
+    summary.added.sort();
+    summary.overwritten.sort();
+    summary.skipped.sort();
+    summary.removed.sort();
+    summary
+}
+
+fn print_names(label: &str, names: &[String]) {
+    if !names.is_empty() {
+        println!("  {} ({}): {}", label, names.len(), names.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(command: &str) -> McpServerConfig {
+        McpServerConfig {
+            command: command.to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            description: None,
+            category: None,
+            enabled: None,
+            source: None,
+        }
+    }
+
+    fn servers(entries: &[(&str, &str)]) -> HashMap<String, McpServerConfig> {
+        entries
+            .iter()
+            .map(|(name, command)| (name.to_string(), server(command)))
+            .collect()
+    }
+
+    #[test]
+    fn merge_skips_existing_servers_unless_overwrite() {
+        let mut existing = servers(&[("fs", "local-fs")]);
+        let summary = apply_import(
+            &mut existing,
+            servers(&[("fs", "team-fs"), ("git", "team-git")]),
+            false,
+            false,
+        );
+        assert_eq!(summary.added, vec!["git"]);
+        assert_eq!(summary.skipped, vec!["fs"]);
+        assert_eq!(existing["fs"].command, "local-fs");
+
+        let summary = apply_import(&mut existing, servers(&[("fs", "team-fs")]), false, true);
+        assert_eq!(summary.overwritten, vec!["fs"]);
+        assert_eq!(existing["fs"].command, "team-fs");
+    }
+
+    #[test]
+    fn replace_drops_servers_missing_from_import() {
+        let mut existing = servers(&[("fs", "local-fs"), ("old", "old-mcp")]);
+        let summary = apply_import(&mut existing, servers(&[("fs", "team-fs")]), true, false);
+        assert_eq!(summary.removed, vec!["old"]);
+        assert_eq!(summary.added, vec!["fs"]);
+        assert_eq!(existing.len(), 1);
+        assert_eq!(existing["fs"].command, "team-fs");
+    }
+
+    #[test]
+    fn validate_rejects_entries_without_command() {
+        assert!(server("").validate("broken").is_err());
+        assert!(server("npx").validate("").is_err());
+        assert!(server("npx").validate("ok").is_ok());
+    }
+}
@@ -3,13 +3,20 @@
 //! 提供对 ~/.aiw/mcp.json 的管理命令
 
 mod add;
+mod call;
+mod cli_config;
 pub mod config_editor;
 mod edit;
 mod enable_disable;
+mod export;
 mod get;
+mod import;
 mod list;
 mod remove;
 pub mod registry;
+mod restore;
+mod test;
+mod tools;
 
 pub use config_editor::{McpConfigEditor, McpServerConfig};
 
@@ -35,17 +42,45 @@ pub enum McpCommand {
     /// 获取服务器配置
     Get { name: String },
     /// 启用服务器
-    Enable { name: String },
+    Enable { name: Option<String>, all: bool },
     /// 禁用服务器
-    Disable { name: String },
+    Disable { name: Option<String>, all: bool },
     /// 编辑配置文件
     Edit,
+    /// 从备份恢复配置文件
+    Restore {
+        backup: Option<String>,
+        list: bool,
+        yes: bool,
+    },
+    /// 启动服务器并列出工具
+    Test { name: String },
+    /// 列出发现的工具及其路由索引状态
+    Tools { server: Option<String> },
+    /// 直接调用服务器的工具（不经过智能路由）
+    Call {
+        server: String,
+        tool: String,
+        args: Option<String>,
+    },
+    /// 导出服务器配置
+    Export { file: String, redact: bool },
+    /// 导入服务器配置
+    Import {
+        file: String,
+        replace: bool,
+        overwrite: bool,
+    },
 
     /// 搜索MCP服务器
     Search {
         query: String,
         source: Option<String>,
         limit: Option<usize>,
+        sort: Option<String>,
+        no_cache: bool,
+        /// 每个仓库请求的超时秒数
+        timeout: Option<u64>,
     },
 
     /// 安装MCP服务器
@@ -54,6 +89,9 @@ pub enum McpCommand {
         source: Option<String>,
         env: Vec<(String, String)>,
         skip_env: bool,
+        /// 同时写入这些 AI CLI 的配置（claude|codex|gemini|all）
+        into: Option<String>,
+        timeout: Option<u64>,
     },
 
     /// 查看服务器信息
@@ -63,7 +101,10 @@ pub enum McpCommand {
     Update,
 
     /// 交互式浏览所有服务器
-    Browse { source: Option<String> },
+    Browse {
+        source: Option<String>,
+        timeout: Option<u64>,
+    },
 }
 
 /// 执行MCP命令
@@ -81,22 +122,41 @@ pub async fn handle_mcp_command(cmd: McpCommand) -> Result<()> {
         } => add::execute(&name, &command, args, description, category, env, disabled),
         McpCommand::Remove { name, yes } => remove::execute(&name, yes),
         McpCommand::Get { name } => get::execute(&name),
-        McpCommand::Enable { name } => enable_disable::execute_enable(&name),
-        McpCommand::Disable { name } => enable_disable::execute_disable(&name),
+        McpCommand::Enable { name, all } => enable_disable::execute_enable(name.as_deref(), all),
+        McpCommand::Disable { name, all } => enable_disable::execute_disable(name.as_deref(), all),
         McpCommand::Edit => edit::execute(),
+        McpCommand::Restore { backup, list, yes } => restore::execute(backup.as_deref(), list, yes),
+        McpCommand::Test { name } => test::execute(&name).await,
+        McpCommand::Tools { server } => tools::execute(server.as_deref()).await,
+        McpCommand::Call { server, tool, args } => {
+            call::execute(&server, &tool, args.as_deref()).await
+        }
+        McpCommand::Export { file, redact } => export::execute(&file, redact),
+        McpCommand::Import {
+            file,
+            replace,
+            overwrite,
+        } => import::execute(&file, replace, overwrite),
         McpCommand::Search {
             query,
             source,
             limit,
-        } => registry::search::execute(&query, source, limit).await,
+            sort,
+            no_cache,
+            timeout,
+        } => registry::search::execute(&query, source, limit, sort, no_cache, timeout).await,
         McpCommand::Install {
             name,
             source,
             env,
             skip_env,
-        } => registry::install::execute(&name, source, env, skip_env).await,
+            into,
+            timeout,
+        } => {
+            registry::install::execute(&name, source, env, skip_env, into.as_deref(), timeout).await
+        }
         McpCommand::Info { name, source } => registry::info::execute(&name, source).await,
         McpCommand::Update => registry::update::execute().await,
-        McpCommand::Browse { source } => registry::browse::execute(source).await,
+        McpCommand::Browse { source, timeout } => registry::browse::execute(source, timeout).await,
     }
 }
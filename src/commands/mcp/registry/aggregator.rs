@@ -1,9 +1,15 @@
-use super::{official::OfficialRegistrySource, smithery::SmitherySource, source::RegistrySource};
+use super::{
+    official::OfficialRegistrySource,
+    search_cache::SearchCache,
+    smithery::SmitherySource,
+    source::{RegistrySource, DEFAULT_REQUEST_TIMEOUT},
+};
 use crate::commands::mcp::McpServerConfig;
 use anyhow::{anyhow, Result};
 use futures::future::join_all;
 use std::{
     collections::HashMap,
+    future::Future,
     hash::{Hash, Hasher},
     sync::Arc,
     time::{Duration, Instant},
@@ -17,6 +23,44 @@ const CACHE_TTL: Duration = Duration::from_secs(3600);
 pub struct RegistryAggregator {
     sources: Vec<Box<dyn RegistrySource>>,
     cache: Arc<RwLock<HashMap<CacheKey, CachedEntry>>>,
+    disk_cache: Option<SearchCache>,
+    read_cache: bool,
+    request_timeout: Duration,
+    deadline: Duration,
+}
+
+/// Search results plus the registries that failed or timed out.
+#[derive(Debug, Default)]
+pub struct SearchOutcome {
+    pub results: Vec<McpServerInfo>,
+    /// `(source id, error)` for each registry that did not answer
+    pub failures: Vec<(String, String)>,
+}
+
+/// Ordering of `mcp search` results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchSort {
+    /// Closest name match first, then the registry order
+    #[default]
+    Relevance,
+    Downloads,
+    Name,
+}
+
+impl std::str::FromStr for SearchSort {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "relevance" => Ok(SearchSort::Relevance),
+            "downloads" => Ok(SearchSort::Downloads),
+            "name" => Ok(SearchSort::Name),
+            other => Err(format!(
+                "Invalid sort '{}': expected downloads, name or relevance",
+                other
+            )),
+        }
+    }
 }
 
 impl Default for RegistryAggregator {
@@ -27,32 +71,97 @@ impl Default for RegistryAggregator {
 
 impl RegistryAggregator {
     pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    /// Default registries whose requests give up after `timeout` (`--timeout`)
+    pub fn with_timeout(timeout: Duration) -> Self {
         let sources: Vec<Box<dyn RegistrySource>> = vec![
-            Box::new(OfficialRegistrySource::new()),
-            Box::new(SmitherySource::new()),
+            Box::new(OfficialRegistrySource::with_timeout(timeout)),
+            Box::new(SmitherySource::with_timeout(timeout)),
         ];
-        Self::with_sources(sources)
+        let aggregator = Self::with_sources(sources).with_timeouts(timeout, timeout * 2);
+        match SearchCache::new() {
+            Ok(cache) => aggregator.with_disk_cache(cache),
+            Err(_) => aggregator,
+        }
     }
 
     pub fn with_sources(sources: Vec<Box<dyn RegistrySource>>) -> Self {
         Self {
             sources,
             cache: Arc::new(RwLock::new(HashMap::new())),
+            disk_cache: None,
+            read_cache: true,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            deadline: DEFAULT_REQUEST_TIMEOUT * 2,
         }
     }
 
+    /// `request` bounds each call to a single registry; `deadline` bounds a
+    /// whole lookup, which may fall back from one registry to the next.
+    pub fn with_timeouts(mut self, request: Duration, deadline: Duration) -> Self {
+        self.request_timeout = request;
+        self.deadline = deadline.max(request);
+        self
+    }
+
+    /// Persist search results across invocations
+    pub fn with_disk_cache(mut self, cache: SearchCache) -> Self {
+        self.disk_cache = Some(cache);
+        self
+    }
+
+    /// Ignore cached results and query the registries (`--no-cache`).
+    /// Fresh results are still written back.
+    pub fn bypass_cache(mut self) -> Self {
+        self.read_cache = false;
+        self
+    }
+
     pub async fn search(
         &self,
         query: &str,
         source_filter: Option<&str>,
         limit: usize,
     ) -> Result<Vec<McpServerInfo>> {
+        Ok(self
+            .search_with_failures(query, source_filter, limit)
+            .await?
+            .results)
+    }
+
+    /// Like [`search`](Self::search), but also reports the registries that
+    /// failed or timed out. Registries are queried concurrently and a slow
+    /// one never holds back results from the others; this only fails when
+    /// no registry answered.
+    pub async fn search_with_failures(
+        &self,
+        query: &str,
+        source_filter: Option<&str>,
+        limit: usize,
+    ) -> Result<SearchOutcome> {
         let filter = source_filter.map(|s| s.to_lowercase());
         let key = CacheKey::new(query, filter.clone(), limit);
 
-        if let Some(cached) = self.cache.read().await.get(&key) {
-            if cached.created_at.elapsed() < CACHE_TTL {
-                return Ok(cached.results.clone());
+        if self.read_cache {
+            if let Some(cached) = self.cache.read().await.get(&key) {
+                if cached.created_at.elapsed() < CACHE_TTL {
+                    return Ok(SearchOutcome {
+                        results: cached.results.clone(),
+                        failures: Vec::new(),
+                    });
+                }
+            }
+            if let Some(results) = self
+                .disk_cache
+                .as_ref()
+                .and_then(|disk| disk.load(query, filter.as_deref(), limit))
+            {
+                return Ok(SearchOutcome {
+                    results,
+                    failures: Vec::new(),
+                });
             }
         }
 
@@ -61,9 +170,11 @@ impl RegistryAggregator {
             return Err(anyhow!("No registry source matched the filter"));
         }
 
+        // 各来源并发查询，整体耗时不超过单个请求超时
         let mut tasks = Vec::new();
         for source in &sources {
-            tasks.push(source.search(query, limit));
+            let call = source.search(query, limit);
+            tasks.push(bounded(source.source_id(), self.request_timeout, call));
         }
 
         let results = join_all(tasks).await;
@@ -72,7 +183,7 @@ impl RegistryAggregator {
         for (idx, result) in results.into_iter().enumerate() {
             match result {
                 Ok(list) => merged_inputs.push((sources[idx].priority(), list)),
-                Err(err) => errors.push((sources[idx].source_id().to_string(), err)),
+                Err(err) => errors.push((sources[idx].source_id().to_string(), err.to_string())),
             }
         }
 
@@ -84,6 +195,12 @@ impl RegistryAggregator {
         }
 
         let merged = merge_results(merged_inputs);
+        // 部分来源失败时不落盘，免得缺失的结果被缓存下来
+        if errors.is_empty() {
+            if let Some(disk) = &self.disk_cache {
+                let _ = disk.store(query, filter.as_deref(), limit, &merged);
+            }
+        }
         self.cache.write().await.insert(
             key,
             CachedEntry {
@@ -92,7 +209,10 @@ impl RegistryAggregator {
             },
         );
 
-        Ok(merged)
+        Ok(SearchOutcome {
+            results: merged,
+            failures: errors,
+        })
     }
 
     pub async fn get_server_detail(
@@ -107,9 +227,15 @@ impl RegistryAggregator {
             return Err(anyhow!("No registry source available for detail lookup"));
         }
 
+        let started = Instant::now();
         let mut last_error: Option<anyhow::Error> = None;
         for source in sources {
-            match source.get_server(qualified_name).await {
+            let Some(budget) = self.remaining(started) else {
+                last_error = Some(self.deadline_error(source.source_id()));
+                break;
+            };
+            let lookup = source.get_server(qualified_name);
+            match bounded(source.source_id(), budget, lookup).await {
                 Ok(Some(detail)) => return Ok(detail),
                 Ok(None) => continue,
                 Err(err) => last_error = Some(err),
@@ -138,9 +264,15 @@ impl RegistryAggregator {
             return Err(anyhow!("No registry source available for install"));
         }
 
+        let started = Instant::now();
         let mut last_error: Option<anyhow::Error> = None;
         for source in sources {
-            match source.get_install_config(qualified_name).await {
+            let Some(budget) = self.remaining(started) else {
+                last_error = Some(self.deadline_error(source.source_id()));
+                break;
+            };
+            let lookup = source.get_install_config(qualified_name);
+            match bounded(source.source_id(), budget, lookup).await {
                 Ok(cfg) => return Ok(cfg),
                 Err(err) => last_error = Some(err),
             }
@@ -156,6 +288,23 @@ impl RegistryAggregator {
 
     pub async fn clear_cache(&self) {
         self.cache.write().await.clear();
+        if let Some(disk) = &self.disk_cache {
+            let _ = disk.clear();
+        }
+    }
+
+    /// Time left before the overall deadline of a lookup started at `started`
+    fn remaining(&self, started: Instant) -> Option<Duration> {
+        let left = self.deadline.checked_sub(started.elapsed())?;
+        (!left.is_zero()).then_some(left.min(self.request_timeout))
+    }
+
+    fn deadline_error(&self, source: &str) -> anyhow::Error {
+        anyhow!(
+            "Gave up before querying {}: registry lookup exceeded {}s",
+            source,
+            self.deadline.as_secs_f32()
+        )
     }
 
     fn filtered_sources(&self, filter: Option<&str>) -> Vec<&dyn RegistrySource> {
@@ -171,6 +320,22 @@ impl RegistryAggregator {
     }
 }
 
+/// Run one registry call, turning an elapsed `budget` into a timeout error
+async fn bounded<T>(
+    source: &str,
+    budget: Duration,
+    call: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match tokio::time::timeout(budget, call).await {
+        Ok(result) => result,
+        Err(_) => Err(anyhow!(
+            "{} did not respond within {}s (raise it with --timeout)",
+            source,
+            budget.as_secs_f32()
+        )),
+    }
+}
+
 fn merge_results(inputs: Vec<(u8, Vec<McpServerInfo>)>) -> Vec<McpServerInfo> {
     let mut map: HashMap<String, (u8, McpServerInfo)> = HashMap::new();
 
@@ -194,6 +359,59 @@ fn merge_results(inputs: Vec<(u8, Vec<McpServerInfo>)>) -> Vec<McpServerInfo> {
     merged.into_iter().map(|(_, info)| info).collect()
 }
 
+/// Reorder search results. Sorting is stable, so ties keep the merged
+/// registry order.
+pub fn sort_results(results: &mut [McpServerInfo], query: &str, sort: SearchSort) {
+    match sort {
+        SearchSort::Relevance => {
+            let query = query.trim().to_lowercase();
+            results.sort_by_cached_key(|info| std::cmp::Reverse(relevance(info, &query)));
+        }
+        SearchSort::Downloads => results.sort_by(|a, b| {
+            b.downloads
+                .unwrap_or(0)
+                .cmp(&a.downloads.unwrap_or(0))
+                .then_with(|| a.display_name.cmp(&b.display_name))
+        }),
+        SearchSort::Name => results.sort_by_cached_key(|info| {
+            (
+                info.display_name.to_lowercase(),
+                info.qualified_name.clone(),
+            )
+        }),
+    }
+}
+
+/// 0–4: exact name, name prefix, name substring, description mention, no match
+fn relevance(info: &McpServerInfo, query: &str) -> u8 {
+    if query.is_empty() {
+        return 0;
+    }
+    let display = info.display_name.to_lowercase();
+    let qualified = info.qualified_name.to_lowercase();
+    let short = qualified
+        .rsplit(['/', ':'])
+        .next()
+        .unwrap_or(&qualified)
+        .to_string();
+
+    if display == query || short == query || qualified == query {
+        4
+    } else if display.starts_with(query) || short.starts_with(query) {
+        3
+    } else if display.contains(query) || qualified.contains(query) {
+        2
+    } else if info
+        .description
+        .as_deref()
+        .is_some_and(|d| d.to_lowercase().contains(query))
+    {
+        1
+    } else {
+        0
+    }
+}
+
 #[derive(Clone)]
 struct CacheKey {
     query: String,
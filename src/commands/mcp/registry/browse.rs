@@ -5,13 +5,22 @@
 //! - Right panel: Server details preview
 //! - Search/filter functionality
 //! - Tab to toggle focus between panels
+//! - Mouse: click to select, wheel to scroll, click the install hint to install
 
-use super::{aggregator::RegistryAggregator, install, types::EnvVarSpec, McpServerInfo};
+use super::{
+    aggregator::RegistryAggregator, install, interactive, source::DEFAULT_REQUEST_TIMEOUT,
+    types::EnvVarSpec, McpServerInfo,
+};
+use crate::mcp_routing::category;
+use crate::tui::components::{StyleManager, Theme};
 use crate::tui::screens::InstalledMcpScreen;
 use crate::tui::{Screen, ScreenAction};
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -19,36 +28,18 @@ use indicatif::{ProgressBar, ProgressStyle};
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     symbols::border,
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::collections::HashMap;
+use std::time::Duration;
 
-// Modern color palette using RGB
-mod colors {
-    use ratatui::style::Color;
-
-    // Primary colors
-    pub const PRIMARY: Color = Color::Rgb(99, 102, 241); // Indigo
-    pub const SECONDARY: Color = Color::Rgb(168, 85, 247); // Purple
-
-    // Accent colors
-    pub const SUCCESS: Color = Color::Rgb(16, 185, 129); // Emerald
-    pub const WARNING: Color = Color::Rgb(245, 158, 11); // Amber
-    pub const INFO: Color = Color::Rgb(56, 189, 248); // Sky
-
-    // Neutral colors
-    pub const TEXT: Color = Color::Rgb(226, 232, 240); // Slate 200
-    pub const TEXT_DIM: Color = Color::Rgb(148, 163, 184); // Slate 400
-    pub const SURFACE: Color = Color::Rgb(30, 41, 59); // Slate 800
-    pub const BORDER: Color = Color::Rgb(71, 85, 105); // Slate 600
-
-    // Source colors
-    pub const REGISTRY: Color = Color::Rgb(52, 211, 153); // Emerald 400
-    pub const SMITHERY: Color = Color::Rgb(251, 146, 60); // Orange 400
+/// Colors come from the shared TUI theme (`~/.aiw/theme.json`)
+fn theme() -> &'static Theme {
+    StyleManager::theme()
 }
 use std::io;
 
@@ -178,6 +169,26 @@ struct BrowserState {
     source_filter: Option<String>,
     env_input: Option<EnvInputState>,
     installed_screen: Option<InstalledMcpScreen>,
+    /// Registries that failed or timed out while loading
+    unavailable_sources: Vec<String>,
+    // Areas from the last draw, used to map mouse coordinates
+    list_area: Rect,
+    detail_area: Rect,
+    install_hint_area: Rect,
+}
+
+/// What the event loop should do after a mouse event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MouseAction {
+    None,
+    Install,
+}
+
+fn hit(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x
+        && column < area.x.saturating_add(area.width)
+        && row >= area.y
+        && row < area.y.saturating_add(area.height)
 }
 
 impl BrowserState {
@@ -194,6 +205,10 @@ impl BrowserState {
             source_filter,
             env_input: None,
             installed_screen: None,
+            unavailable_sources: Vec::new(),
+            list_area: Rect::default(),
+            detail_area: Rect::default(),
+            install_hint_area: Rect::default(),
         };
         if !state.filtered.is_empty() {
             state.list_state.select(Some(0));
@@ -303,11 +318,53 @@ impl BrowserState {
     fn scroll_detail_down(&mut self) {
         self.scroll_offset = self.scroll_offset.saturating_add(1);
     }
+
+    /// Select the list row under the cursor (inside the borders)
+    fn select_at(&mut self, row: u16) {
+        let top = self.list_area.y + 1;
+        let bottom = self.list_area.y + self.list_area.height.saturating_sub(1);
+        if row < top || row >= bottom {
+            return;
+        }
+        let index = self.list_state.offset() + usize::from(row - top);
+        if index < self.filtered.len() && self.list_state.selected() != Some(index) {
+            self.list_state.select(Some(index));
+            self.scroll_offset = 0;
+        }
+    }
+
+    /// Click selects a server, the wheel moves the selection over the list and
+    /// scrolls over the detail panel, and clicking the install hint installs.
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> MouseAction {
+        let (column, row) = (mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::ScrollUp if hit(self.detail_area, column, row) => {
+                self.scroll_detail_up()
+            }
+            MouseEventKind::ScrollDown if hit(self.detail_area, column, row) => {
+                self.scroll_detail_down()
+            }
+            MouseEventKind::ScrollUp if hit(self.list_area, column, row) => self.move_up(),
+            MouseEventKind::ScrollDown if hit(self.list_area, column, row) => self.move_down(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                if hit(self.install_hint_area, column, row) && self.selected_server().is_some() {
+                    return MouseAction::Install;
+                }
+                if hit(self.list_area, column, row) {
+                    self.select_at(row);
+                }
+            }
+            _ => {}
+        }
+        MouseAction::None
+    }
 }
 
 /// Execute the interactive browse command
-pub async fn execute(source: Option<String>) -> Result<()> {
-    let aggregator = RegistryAggregator::new();
+pub async fn execute(source: Option<String>, timeout: Option<u64>) -> Result<()> {
+    let aggregator = RegistryAggregator::with_timeout(
+        timeout.map_or(DEFAULT_REQUEST_TIMEOUT, Duration::from_secs),
+    );
 
     // Show loading spinner
     let spinner = ProgressBar::new_spinner()
@@ -319,17 +376,26 @@ pub async fn execute(source: Option<String>) -> Result<()> {
         .with_message("Loading MCP servers from registries...");
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    // Fetch all servers
-    let results = aggregator.search("", source.as_deref(), 500).await?;
+    // Fetch all servers; a registry that times out is skipped
+    let outcome = aggregator
+        .search_with_failures("", source.as_deref(), 500)
+        .await;
     spinner.finish_and_clear();
+    let outcome = outcome?;
+    interactive::warn_unavailable_sources(&outcome.failures);
 
-    if results.is_empty() {
+    if outcome.results.is_empty() {
         println!("No MCP servers found.");
         return Ok(());
     }
 
     // Run TUI
-    let selected = run_tui(results, source, &aggregator).await?;
+    let unavailable = outcome
+        .failures
+        .into_iter()
+        .map(|(source, _)| source)
+        .collect();
+    let selected = run_tui(outcome.results, source, unavailable, &aggregator).await?;
 
     // Handle selection
     if let Some((server, env_vars)) = selected {
@@ -340,6 +406,7 @@ pub async fn execute(source: Option<String>) -> Result<()> {
             Some(server.source.clone()),
             env_vars,
             false,
+            &[],
         )
         .await?;
     }
@@ -350,6 +417,7 @@ pub async fn execute(source: Option<String>) -> Result<()> {
 async fn run_tui(
     servers: Vec<McpServerInfo>,
     source: Option<String>,
+    unavailable_sources: Vec<String>,
     aggregator: &RegistryAggregator,
 ) -> Result<Option<(McpServerInfo, Vec<(String, String)>)>> {
     // Setup terminal
@@ -360,6 +428,7 @@ async fn run_tui(
     let mut terminal = Terminal::new(backend)?;
 
     let mut state = BrowserState::new(servers, source);
+    state.unavailable_sources = unavailable_sources;
     let result = run_event_loop(&mut terminal, &mut state, aggregator).await;
 
     // Restore terminal
@@ -382,7 +451,27 @@ async fn run_event_loop(
     loop {
         terminal.draw(|f| draw_ui(f, state))?;
 
-        if let Event::Key(key) = event::read()? {
+        let event = event::read()?;
+        if let Event::Mouse(mouse) = event {
+            // Overlays and text input stay keyboard-only
+            if state.installed_screen.is_some() || state.env_input.is_some() || state.search_mode {
+                continue;
+            }
+            if state.show_help {
+                if matches!(mouse.kind, MouseEventKind::Down(_)) {
+                    state.show_help = false;
+                }
+                continue;
+            }
+            if state.handle_mouse(mouse) == MouseAction::Install {
+                if let Some(install) = start_install(state, aggregator).await {
+                    return Ok(Some(install));
+                }
+            }
+            continue;
+        }
+
+        if let Event::Key(key) = event {
             if key.kind != KeyEventKind::Press {
                 continue;
             }
@@ -511,22 +600,8 @@ async fn run_event_loop(
                     state.scroll_detail_up();
                 }
                 KeyCode::Enter => {
-                    if let Some(server) = state.selected_server() {
-                        // Fetch server details to get environment variable requirements
-                        let detail = aggregator
-                            .get_server_detail(&server.qualified_name, Some(server.source.as_str()))
-                            .await;
-
-                        match detail {
-                            Ok(detail) if !detail.required_env.is_empty() => {
-                                // Enter environment variable input mode
-                                state.env_input = Some(EnvInputState::new(detail.required_env));
-                            }
-                            _ => {
-                                // No environment variables required, proceed with installation
-                                return Ok(Some((server.clone(), Vec::new())));
-                            }
-                        }
+                    if let Some(install) = start_install(state, aggregator).await {
+                        return Ok(Some(install));
                     }
                 }
                 _ => {}
@@ -535,6 +610,27 @@ async fn run_event_loop(
     }
 }
 
+/// Start installing the selected server. Returns the install request right away
+/// when no environment variables are needed, otherwise opens the env input dialog.
+async fn start_install(
+    state: &mut BrowserState,
+    aggregator: &RegistryAggregator,
+) -> Option<(McpServerInfo, Vec<(String, String)>)> {
+    let server = state.selected_server()?.clone();
+    // Fetch server details to get environment variable requirements
+    let detail = aggregator
+        .get_server_detail(&server.qualified_name, Some(server.source.as_str()))
+        .await;
+
+    match detail {
+        Ok(detail) if !detail.required_env.is_empty() => {
+            state.env_input = Some(EnvInputState::new(detail.required_env));
+            None
+        }
+        _ => Some((server, Vec::new())),
+    }
+}
+
 fn draw_ui(f: &mut Frame, state: &mut BrowserState) {
     let size = f.size();
 
@@ -555,9 +651,11 @@ fn draw_ui(f: &mut Frame, state: &mut BrowserState) {
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
         .split(main_chunks[0]);
 
+    state.list_area = content_chunks[0];
+    state.detail_area = content_chunks[1];
     draw_list_panel(f, state, content_chunks[0]);
     draw_detail_panel(f, state, content_chunks[1]);
-    draw_status_bar(f, state, main_chunks[1]);
+    state.install_hint_area = draw_status_bar(f, state, main_chunks[1]);
 
     // Draw search bar at bottom if in search mode
     if state.search_mode {
@@ -601,9 +699,9 @@ fn draw_list_panel(f: &mut Frame, state: &mut BrowserState, area: Rect) {
 
             // Source indicator
             let (src_icon, src_color) = match server.source.as_str() {
-                "registry" => ("●", colors::REGISTRY),
-                "smithery" => ("◆", colors::SMITHERY),
-                _ => ("○", colors::TEXT_DIM),
+                "registry" => ("●", theme().success),
+                "smithery" => ("◆", theme().warning),
+                _ => ("○", theme().text_dim),
             };
 
             // Downloads text
@@ -616,12 +714,12 @@ fn draw_list_panel(f: &mut Frame, state: &mut BrowserState, area: Rect) {
                 Span::raw(" "),
                 Span::styled(
                     truncate_str(&server.qualified_name, 28),
-                    Style::default().fg(colors::TEXT),
+                    Style::default().fg(theme().text),
                 ),
                 Span::raw(" "),
                 Span::styled(
                     format!("⬇{}", dl_text),
-                    Style::default().fg(colors::TEXT_DIM),
+                    Style::default().fg(theme().text_dim),
                 ),
             ]);
 
@@ -630,15 +728,15 @@ fn draw_list_panel(f: &mut Frame, state: &mut BrowserState, area: Rect) {
         .collect();
 
     let title_spans = vec![
-        Span::styled(" MCP Servers ", Style::default().fg(colors::TEXT).add_modifier(Modifier::BOLD)),
+        Span::styled(" MCP Servers ", Style::default().fg(theme().text).add_modifier(Modifier::BOLD)),
         Span::styled(
             format!("({}/{})", state.filtered.len(), state.servers.len()),
-            Style::default().fg(colors::TEXT_DIM),
+            Style::default().fg(theme().text_dim),
         ),
         if !state.search_query.is_empty() {
             Span::styled(
                 format!(" 🔍 {}", state.search_query),
-                Style::default().fg(colors::WARNING),
+                Style::default().fg(theme().warning),
             )
         } else {
             Span::raw("")
@@ -652,11 +750,11 @@ fn draw_list_panel(f: &mut Frame, state: &mut BrowserState, area: Rect) {
                 .borders(Borders::ALL)
                 .border_set(border::ROUNDED)
                 .title(Line::from(title_spans))
-                .border_style(Style::default().fg(colors::PRIMARY)),
+                .border_style(Style::default().fg(theme().primary)),
         )
         .highlight_style(
             Style::default()
-                .bg(colors::SURFACE)
+                .bg(theme().surface)
                 .add_modifier(Modifier::BOLD),
         )
         .highlight_symbol("▸ ");
@@ -675,16 +773,16 @@ fn truncate_str(s: &str, max_len: usize) -> String {
 fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
     let content = if let Some(server) = state.selected_server() {
         let (src_color, src_label) = match server.source.as_str() {
-            "registry" => (colors::REGISTRY, "Official Registry"),
-            "smithery" => (colors::SMITHERY, "Smithery"),
-            _ => (colors::TEXT_DIM, "Unknown"),
+            "registry" => (theme().success, "Official Registry"),
+            "smithery" => (theme().warning, "Smithery"),
+            _ => (theme().text_dim, "Unknown"),
         };
 
         let (type_color, type_icon) = match server.install.label() {
-            "npm" => (colors::INFO, "📦"),
-            "uvx" => (colors::WARNING, "🐍"),
-            "docker" => (colors::SECONDARY, "🐳"),
-            _ => (colors::TEXT_DIM, "📋"),
+            "npm" => (theme().info, "📦"),
+            "uvx" => (theme().warning, "🐍"),
+            "docker" => (theme().secondary, "🐳"),
+            _ => (theme().text_dim, "📋"),
         };
 
         let mut lines = vec![
@@ -693,7 +791,7 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
                 Span::styled(
                     &server.qualified_name,
                     Style::default()
-                        .fg(colors::TEXT)
+                        .fg(theme().text)
                         .add_modifier(Modifier::BOLD),
                 ),
             ]),
@@ -705,12 +803,12 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
                     format!("{:<6}", server.install.label()),
                     Style::default().fg(type_color),
                 ),
-                Span::styled("  ●  ", Style::default().fg(colors::BORDER)),
+                Span::styled("  ●  ", Style::default().fg(theme().border)),
                 Span::styled(src_label, Style::default().fg(src_color)),
                 if let Some(author) = &server.author {
                     Span::styled(
                         format!("  ●  by {}", author),
-                        Style::default().fg(colors::TEXT_DIM),
+                        Style::default().fg(theme().text_dim),
                     )
                 } else {
                     Span::raw("")
@@ -718,18 +816,31 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
             ]),
             // Downloads
             Line::from(vec![
-                Span::styled("⬇ ", Style::default().fg(colors::SUCCESS)),
+                Span::styled("⬇ ", Style::default().fg(theme().success)),
                 Span::styled(
                     format_downloads(server.downloads),
-                    Style::default().fg(colors::TEXT_DIM),
+                    Style::default().fg(theme().text_dim),
+                ),
+                Span::styled(" downloads", Style::default().fg(theme().text_dim)),
+            ]),
+            // Category (same classifier the router uses for tool embeddings)
+            Line::from(vec![
+                Span::styled("🏷 ", Style::default().fg(theme().info)),
+                Span::styled(
+                    category::resolve(
+                        None,
+                        &server.qualified_name,
+                        "",
+                        server.description.as_deref().unwrap_or_default(),
+                    ),
+                    Style::default().fg(theme().text_dim),
                 ),
-                Span::styled(" downloads", Style::default().fg(colors::TEXT_DIM)),
             ]),
             Line::from(""),
             // Description header
             Line::from(Span::styled(
                 "━━━ Description ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
-                Style::default().fg(colors::BORDER),
+                Style::default().fg(theme().border),
             )),
             Line::from(""),
         ];
@@ -742,7 +853,7 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
         for line in desc.lines() {
             lines.push(Line::from(Span::styled(
                 line,
-                Style::default().fg(colors::TEXT),
+                Style::default().fg(theme().text),
             )));
         }
 
@@ -750,14 +861,14 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             "━━━ Quick Install ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
-            Style::default().fg(colors::BORDER),
+            Style::default().fg(theme().border),
         )));
         lines.push(Line::from(""));
         lines.push(Line::from(vec![
-            Span::styled("  $ ", Style::default().fg(colors::TEXT_DIM)),
+            Span::styled("  $ ", Style::default().fg(theme().text_dim)),
             Span::styled(
                 format!("aiw mcp install {}", server.qualified_name),
-                Style::default().fg(colors::SUCCESS),
+                Style::default().fg(theme().success),
             ),
         ]));
         lines.push(Line::from(""));
@@ -765,15 +876,15 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
         // Keyboard hints
         lines.push(Line::from(Span::styled(
             "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
-            Style::default().fg(colors::BORDER),
+            Style::default().fg(theme().border),
         )));
         lines.push(Line::from(vec![
-            Span::styled("  Enter ", Style::default().fg(colors::SUCCESS).add_modifier(Modifier::BOLD)),
-            Span::styled("Install", Style::default().fg(colors::TEXT_DIM)),
-            Span::styled("    Tab ", Style::default().fg(colors::INFO).add_modifier(Modifier::BOLD)),
-            Span::styled("Scroll ↓", Style::default().fg(colors::TEXT_DIM)),
-            Span::styled("    S-Tab ", Style::default().fg(colors::INFO).add_modifier(Modifier::BOLD)),
-            Span::styled("Scroll ↑", Style::default().fg(colors::TEXT_DIM)),
+            Span::styled("  Enter ", Style::default().fg(theme().success).add_modifier(Modifier::BOLD)),
+            Span::styled("Install", Style::default().fg(theme().text_dim)),
+            Span::styled("    Tab ", Style::default().fg(theme().info).add_modifier(Modifier::BOLD)),
+            Span::styled("Scroll ↓", Style::default().fg(theme().text_dim)),
+            Span::styled("    S-Tab ", Style::default().fg(theme().info).add_modifier(Modifier::BOLD)),
+            Span::styled("Scroll ↑", Style::default().fg(theme().text_dim)),
         ]));
 
         lines
@@ -782,12 +893,12 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
             Line::from(""),
             Line::from(Span::styled(
                 "  No server selected",
-                Style::default().fg(colors::TEXT_DIM),
+                Style::default().fg(theme().text_dim),
             )),
             Line::from(""),
             Line::from(Span::styled(
                 "  Use ↑/↓ to navigate the list",
-                Style::default().fg(colors::TEXT_DIM),
+                Style::default().fg(theme().text_dim),
             )),
         ]
     };
@@ -798,9 +909,9 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
                 .borders(Borders::ALL)
                 .border_set(border::ROUNDED)
                 .title(Line::from(vec![
-                    Span::styled(" Details ", Style::default().fg(colors::TEXT).add_modifier(Modifier::BOLD)),
+                    Span::styled(" Details ", Style::default().fg(theme().text).add_modifier(Modifier::BOLD)),
                 ]))
-                .border_style(Style::default().fg(colors::SECONDARY)),
+                .border_style(Style::default().fg(theme().secondary)),
         )
         .wrap(Wrap { trim: false })
         .scroll((state.scroll_offset, 0));
@@ -808,37 +919,72 @@ fn draw_detail_panel(f: &mut Frame, state: &BrowserState, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
-fn draw_status_bar(f: &mut Frame, state: &BrowserState, area: Rect) {
+/// Draws the status bar and returns the clickable area of its "Enter Install" hint
+fn draw_status_bar(f: &mut Frame, state: &BrowserState, area: Rect) -> Rect {
     let registry_count = state.servers.iter().filter(|s| s.source == "registry").count();
     let smithery_count = state.servers.iter().filter(|s| s.source == "smithery").count();
 
-    let status_line = Line::from(vec![
-        Span::styled(" MCP Browser ", Style::default().fg(colors::TEXT).add_modifier(Modifier::BOLD)),
-        Span::styled("│", Style::default().fg(colors::BORDER)),
+    let mut spans = vec![
+        Span::styled(" MCP Browser ", Style::default().fg(theme().text).add_modifier(Modifier::BOLD)),
+        Span::styled("│", Style::default().fg(theme().border)),
         Span::styled(
             format!(" {} servers ", state.servers.len()),
-            Style::default().fg(colors::TEXT_DIM),
+            Style::default().fg(theme().text_dim),
         ),
-        Span::styled("│", Style::default().fg(colors::BORDER)),
-        Span::styled(" ●", Style::default().fg(colors::REGISTRY)),
-        Span::styled(format!("{} ", registry_count), Style::default().fg(colors::TEXT_DIM)),
-        Span::styled("◆", Style::default().fg(colors::SMITHERY)),
-        Span::styled(format!("{} ", smithery_count), Style::default().fg(colors::TEXT_DIM)),
-        Span::styled("│", Style::default().fg(colors::BORDER)),
-        Span::styled(" ↑↓", Style::default().fg(colors::INFO)),
-        Span::styled(" Nav ", Style::default().fg(colors::TEXT_DIM)),
-        Span::styled("/", Style::default().fg(colors::WARNING)),
-        Span::styled(" Search ", Style::default().fg(colors::TEXT_DIM)),
-        Span::styled("i", Style::default().fg(colors::WARNING)),
-        Span::styled(" Installed ", Style::default().fg(colors::TEXT_DIM)),
-        Span::styled("Enter", Style::default().fg(colors::SUCCESS)),
-        Span::styled(" Install ", Style::default().fg(colors::TEXT_DIM)),
-        Span::styled("?", Style::default().fg(colors::SECONDARY)),
-        Span::styled(" Help ", Style::default().fg(colors::TEXT_DIM)),
+        Span::styled("│", Style::default().fg(theme().border)),
+        Span::styled(" ●", Style::default().fg(theme().success)),
+        Span::styled(format!("{} ", registry_count), Style::default().fg(theme().text_dim)),
+        Span::styled("◆", Style::default().fg(theme().warning)),
+        Span::styled(format!("{} ", smithery_count), Style::default().fg(theme().text_dim)),
+    ];
+    if !state.unavailable_sources.is_empty() {
+        spans.push(Span::styled(
+            format!("⚠ {} unavailable ", state.unavailable_sources.join(", ")),
+            Style::default().fg(theme().warning),
+        ));
+    }
+    spans.extend([
+        Span::styled("│", Style::default().fg(theme().border)),
+        Span::styled(" ↑↓", Style::default().fg(theme().info)),
+        Span::styled(" Nav ", Style::default().fg(theme().text_dim)),
+        Span::styled("/", Style::default().fg(theme().warning)),
+        Span::styled(" Search ", Style::default().fg(theme().text_dim)),
+        Span::styled("i", Style::default().fg(theme().warning)),
+        Span::styled(" Installed ", Style::default().fg(theme().text_dim)),
+        Span::styled("Enter", Style::default().fg(theme().success)),
+        Span::styled(" Install ", Style::default().fg(theme().text_dim)),
+        Span::styled("?", Style::default().fg(theme().secondary)),
+        Span::styled(" Help ", Style::default().fg(theme().text_dim)),
     ]);
+    let status_line = Line::from(spans);
 
-    let paragraph = Paragraph::new(status_line).style(Style::default().bg(colors::SURFACE));
+    let hint_start = status_line
+        .spans
+        .iter()
+        .position(|span| span.content == "Enter")
+        .unwrap_or(status_line.spans.len());
+    let hint_x: usize = status_line.spans[..hint_start]
+        .iter()
+        .map(|span| span.width())
+        .sum();
+    let hint_width: usize = status_line
+        .spans
+        .iter()
+        .skip(hint_start)
+        .take(2)
+        .map(|span| span.width())
+        .sum();
+    let hint_area = Rect {
+        x: area.x.saturating_add(hint_x as u16),
+        y: area.y,
+        width: hint_width as u16,
+        height: area.height,
+    }
+    .intersection(area);
+
+    let paragraph = Paragraph::new(status_line).style(Style::default().bg(theme().surface));
     f.render_widget(paragraph, area);
+    hint_area
 }
 
 fn draw_search_bar(f: &mut Frame, state: &BrowserState, size: Rect) {
@@ -850,22 +996,22 @@ fn draw_search_bar(f: &mut Frame, state: &BrowserState, size: Rect) {
     };
 
     let search_line = Line::from(vec![
-        Span::styled(" 🔍 Search: ", Style::default().fg(colors::WARNING)),
-        Span::styled(&state.search_query, Style::default().fg(colors::TEXT)),
-        Span::styled("_", Style::default().fg(colors::TEXT).add_modifier(Modifier::SLOW_BLINK)),
+        Span::styled(" 🔍 Search: ", Style::default().fg(theme().warning)),
+        Span::styled(&state.search_query, Style::default().fg(theme().text)),
+        Span::styled("_", Style::default().fg(theme().text).add_modifier(Modifier::SLOW_BLINK)),
         Span::styled(
             "  (Enter to confirm, Esc to cancel)",
-            Style::default().fg(colors::TEXT_DIM),
+            Style::default().fg(theme().text_dim),
         ),
     ]);
 
-    let paragraph = Paragraph::new(search_line).style(Style::default().bg(colors::SURFACE));
+    let paragraph = Paragraph::new(search_line).style(Style::default().bg(theme().surface));
     f.render_widget(paragraph, area);
 }
 
 fn draw_help_popup(f: &mut Frame, size: Rect) {
     let popup_width = 55;
-    let popup_height = 19;
+    let popup_height = 20;
     let area = Rect {
         x: (size.width.saturating_sub(popup_width)) / 2,
         y: (size.height.saturating_sub(popup_height)) / 2,
@@ -878,52 +1024,56 @@ fn draw_help_popup(f: &mut Frame, size: Rect) {
         Line::from(Span::styled(
             "  ⌨️  Keyboard Shortcuts",
             Style::default()
-                .fg(colors::TEXT)
+                .fg(theme().text)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("    ↑/k  ↓/j      ", Style::default().fg(colors::INFO)),
-            Span::styled("Move up/down", Style::default().fg(colors::TEXT)),
+            Span::styled("    ↑/k  ↓/j      ", Style::default().fg(theme().info)),
+            Span::styled("Move up/down", Style::default().fg(theme().text)),
+        ]),
+        Line::from(vec![
+            Span::styled("    PgUp  PgDn    ", Style::default().fg(theme().info)),
+            Span::styled("Page up/down (10 items)", Style::default().fg(theme().text)),
         ]),
         Line::from(vec![
-            Span::styled("    PgUp  PgDn    ", Style::default().fg(colors::INFO)),
-            Span::styled("Page up/down (10 items)", Style::default().fg(colors::TEXT)),
+            Span::styled("    Home  End     ", Style::default().fg(theme().info)),
+            Span::styled("Jump to start/end", Style::default().fg(theme().text)),
         ]),
         Line::from(vec![
-            Span::styled("    Home  End     ", Style::default().fg(colors::INFO)),
-            Span::styled("Jump to start/end", Style::default().fg(colors::TEXT)),
+            Span::styled("    Tab  S-Tab    ", Style::default().fg(theme().info)),
+            Span::styled("Scroll details panel", Style::default().fg(theme().text)),
         ]),
         Line::from(vec![
-            Span::styled("    Tab  S-Tab    ", Style::default().fg(colors::INFO)),
-            Span::styled("Scroll details panel", Style::default().fg(colors::TEXT)),
+            Span::styled("    Click  Wheel  ", Style::default().fg(theme().info)),
+            Span::styled("Select server / scroll", Style::default().fg(theme().text)),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("    /             ", Style::default().fg(colors::WARNING)),
-            Span::styled("Search/filter servers", Style::default().fg(colors::TEXT)),
+            Span::styled("    /             ", Style::default().fg(theme().warning)),
+            Span::styled("Search/filter servers", Style::default().fg(theme().text)),
         ]),
         Line::from(vec![
-            Span::styled("    c             ", Style::default().fg(colors::WARNING)),
-            Span::styled("Clear current filter", Style::default().fg(colors::TEXT)),
+            Span::styled("    c             ", Style::default().fg(theme().warning)),
+            Span::styled("Clear current filter", Style::default().fg(theme().text)),
         ]),
         Line::from(vec![
-            Span::styled("    i             ", Style::default().fg(colors::WARNING)),
-            Span::styled("View installed MCPs", Style::default().fg(colors::TEXT)),
+            Span::styled("    i             ", Style::default().fg(theme().warning)),
+            Span::styled("View installed MCPs", Style::default().fg(theme().text)),
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("    Enter         ", Style::default().fg(colors::SUCCESS)),
-            Span::styled("Install selected server", Style::default().fg(colors::TEXT)),
+            Span::styled("    Enter         ", Style::default().fg(theme().success)),
+            Span::styled("Install selected server", Style::default().fg(theme().text)),
         ]),
         Line::from(vec![
-            Span::styled("    q  Esc        ", Style::default().fg(colors::SECONDARY)),
-            Span::styled("Quit browser", Style::default().fg(colors::TEXT)),
+            Span::styled("    q  Esc        ", Style::default().fg(theme().secondary)),
+            Span::styled("Quit browser", Style::default().fg(theme().text)),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             "        Press any key to close",
-            Style::default().fg(colors::TEXT_DIM),
+            Style::default().fg(theme().text_dim),
         )),
     ];
 
@@ -932,10 +1082,10 @@ fn draw_help_popup(f: &mut Frame, size: Rect) {
             .borders(Borders::ALL)
             .border_set(border::ROUNDED)
             .title(Line::from(vec![
-                Span::styled(" Help ", Style::default().fg(colors::TEXT).add_modifier(Modifier::BOLD)),
+                Span::styled(" Help ", Style::default().fg(theme().text).add_modifier(Modifier::BOLD)),
             ]))
-            .border_style(Style::default().fg(colors::SUCCESS))
-            .style(Style::default().bg(Color::Rgb(15, 23, 42))), // Slate 900
+            .border_style(Style::default().fg(theme().success))
+            .style(Style::default().bg(theme().background)),
     );
 
     f.render_widget(Clear, area);
@@ -971,7 +1121,7 @@ fn draw_env_input_dialog(f: &mut Frame, state: &mut BrowserState, area: Rect) {
                 Span::styled(
                     spec.name.to_string(),
                     Style::default()
-                        .fg(colors::PRIMARY)
+                        .fg(theme().primary)
                         .add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(if spec.required {
@@ -987,7 +1137,7 @@ fn draw_env_input_dialog(f: &mut Frame, state: &mut BrowserState, area: Rect) {
             if let Some(desc) = &spec.description {
                 lines.push(Line::from(Span::styled(
                     desc.clone(),
-                    Style::default().fg(colors::TEXT_DIM),
+                    Style::default().fg(theme().text_dim),
                 )));
             }
 
@@ -995,7 +1145,7 @@ fn draw_env_input_dialog(f: &mut Frame, state: &mut BrowserState, area: Rect) {
             if let Some(default) = &spec.default {
                 lines.push(Line::from(Span::styled(
                     format!("Default: {}", default),
-                    Style::default().fg(colors::TEXT_DIM),
+                    Style::default().fg(theme().text_dim),
                 )));
             }
 
@@ -1007,7 +1157,7 @@ fn draw_env_input_dialog(f: &mut Frame, state: &mut BrowserState, area: Rect) {
                 Span::styled(
                     format!("{}_", env_input.input_buffer()),
                     Style::default()
-                        .fg(colors::SUCCESS)
+                        .fg(theme().success)
                         .add_modifier(Modifier::BOLD),
                 ),
             ]);
@@ -1023,7 +1173,7 @@ fn draw_env_input_dialog(f: &mut Frame, state: &mut BrowserState, area: Rect) {
             );
             lines.push(Line::from(Span::styled(
                 progress,
-                Style::default().fg(colors::INFO),
+                Style::default().fg(theme().info),
             )));
 
             lines.push(Line::from(""));
@@ -1031,12 +1181,12 @@ fn draw_env_input_dialog(f: &mut Frame, state: &mut BrowserState, area: Rect) {
             // Instructions
             lines.push(Line::from(Span::styled(
                 "Press Enter to continue, ESC to cancel",
-                Style::default().fg(colors::TEXT_DIM).add_modifier(Modifier::DIM),
+                Style::default().fg(theme().text_dim).add_modifier(Modifier::DIM),
             )));
             if env_input.has_remaining_optional() {
                 lines.push(Line::from(Span::styled(
                     "Press 'a' to skip optional variables",
-                    Style::default().fg(colors::TEXT_DIM).add_modifier(Modifier::DIM),
+                    Style::default().fg(theme().text_dim).add_modifier(Modifier::DIM),
                 )));
             }
         }
@@ -1049,13 +1199,13 @@ fn draw_env_input_dialog(f: &mut Frame, state: &mut BrowserState, area: Rect) {
                     .title(Line::from(vec![Span::styled(
                         " Environment Variables ",
                         Style::default()
-                            .fg(colors::TEXT)
+                            .fg(theme().text)
                             .add_modifier(Modifier::BOLD),
                     )]))
-                    .border_style(Style::default().fg(colors::PRIMARY))
-                    .style(Style::default().bg(Color::Rgb(15, 23, 42))), // Slate 900
+                    .border_style(Style::default().fg(theme().primary))
+                    .style(Style::default().bg(theme().background)),
             )
-            .style(Style::default().fg(colors::TEXT));
+            .style(Style::default().fg(theme().text));
 
         f.render_widget(paragraph, dialog_area);
     }
@@ -1528,4 +1678,84 @@ mod tests {
 
         assert_eq!(env_input.current_spec().unwrap().name, "TEST_VAR");
     }
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: crossterm::event::KeyModifiers::NONE,
+        }
+    }
+
+    fn state_with_layout(count: usize) -> BrowserState {
+        let mut state = BrowserState::new(create_test_servers(count), None);
+        state.list_area = Rect::new(0, 0, 40, 12);
+        state.detail_area = Rect::new(40, 0, 60, 12);
+        state.install_hint_area = Rect::new(30, 12, 11, 1);
+        state
+    }
+
+    #[test]
+    fn test_mouse_click_selects_list_row() {
+        let mut state = state_with_layout(20);
+        state.scroll_offset = 4;
+
+        // Row 0 is the top border, so row 3 is the third item
+        let left = MouseEventKind::Down(MouseButton::Left);
+        assert_eq!(state.handle_mouse(mouse(left, 5, 3)), MouseAction::None);
+        assert_eq!(state.list_state.selected(), Some(2));
+        assert_eq!(state.scroll_offset, 0);
+
+        // Borders and rows past the last item leave the selection alone
+        state.handle_mouse(mouse(left, 5, 0));
+        state.handle_mouse(mouse(left, 5, 11));
+        assert_eq!(state.list_state.selected(), Some(2));
+
+        let mut short = state_with_layout(3);
+        short.handle_mouse(mouse(left, 5, 8));
+        assert_eq!(short.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_mouse_click_accounts_for_list_offset() {
+        let mut state = state_with_layout(30);
+        *state.list_state.offset_mut() = 10;
+
+        state.handle_mouse(mouse(MouseEventKind::Down(MouseButton::Left), 5, 1));
+        assert_eq!(state.list_state.selected(), Some(10));
+    }
+
+    #[test]
+    fn test_mouse_wheel_moves_list_and_scrolls_detail() {
+        let mut state = state_with_layout(10);
+
+        state.handle_mouse(mouse(MouseEventKind::ScrollDown, 5, 5));
+        state.handle_mouse(mouse(MouseEventKind::ScrollDown, 5, 5));
+        assert_eq!(state.list_state.selected(), Some(2));
+        state.handle_mouse(mouse(MouseEventKind::ScrollUp, 5, 5));
+        assert_eq!(state.list_state.selected(), Some(1));
+
+        state.handle_mouse(mouse(MouseEventKind::ScrollDown, 60, 5));
+        state.handle_mouse(mouse(MouseEventKind::ScrollDown, 60, 5));
+        assert_eq!(state.scroll_offset, 2);
+        assert_eq!(state.list_state.selected(), Some(1));
+        state.handle_mouse(mouse(MouseEventKind::ScrollUp, 60, 5));
+        assert_eq!(state.scroll_offset, 1);
+    }
+
+    #[test]
+    fn test_mouse_click_on_install_hint() {
+        let left = MouseEventKind::Down(MouseButton::Left);
+        let mut state = state_with_layout(5);
+        assert_eq!(
+            state.handle_mouse(mouse(left, 32, 12)),
+            MouseAction::Install
+        );
+        assert_eq!(state.handle_mouse(mouse(left, 20, 12)), MouseAction::None);
+
+        // Nothing to install when the filter matches no servers
+        let mut empty = state_with_layout(0);
+        assert_eq!(empty.handle_mouse(mouse(left, 32, 12)), MouseAction::None);
+    }
 }
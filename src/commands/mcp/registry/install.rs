@@ -1,18 +1,41 @@
-use super::{aggregator::RegistryAggregator, interactive, types::McpServerDetail};
-use crate::commands::mcp::{McpConfigEditor, McpServerConfig};
+use super::{
+    aggregator::RegistryAggregator,
+    interactive,
+    source::DEFAULT_REQUEST_TIMEOUT,
+    types::{McpServerDetail, ServerInstallType},
+};
+use crate::cli_type::CliType;
+use crate::commands::mcp::cli_config::{self, AddedServer, CliMcpConfig};
+use crate::commands::mcp::{test::routing_config, McpConfigEditor, McpServerConfig};
+use crate::mcp_routing::probe_server;
 use anyhow::{anyhow, Result};
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::collections::HashMap;
+use std::ffi::OsString;
+use std::time::Duration;
 
 pub async fn execute(
     name: &str,
     source: Option<String>,
     env_vars: Vec<(String, String)>,
     skip_env: bool,
+    into: Option<&str>,
+    timeout: Option<u64>,
 ) -> Result<()> {
-    let aggregator = RegistryAggregator::new();
-    install_with_aggregator(&aggregator, name, source, env_vars, skip_env).await
+    let into = into.map(cli_config::parse_targets).transpose()?;
+    let aggregator = RegistryAggregator::with_timeout(
+        timeout.map_or(DEFAULT_REQUEST_TIMEOUT, Duration::from_secs),
+    );
+    install_with_aggregator(
+        &aggregator,
+        name,
+        source,
+        env_vars,
+        skip_env,
+        into.as_deref().unwrap_or_default(),
+    )
+    .await
 }
 
 pub async fn install_with_aggregator(
@@ -21,6 +44,7 @@ pub async fn install_with_aggregator(
     source: Option<String>,
     env_vars: Vec<(String, String)>,
     skip_env: bool,
+    into: &[CliType],
 ) -> Result<()> {
     let spinner = ProgressBar::new_spinner()
         .with_style(
@@ -31,14 +55,9 @@ pub async fn install_with_aggregator(
         .with_message("Resolving server details...");
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    let detail = aggregator
-        .get_server_detail(name, source.as_deref())
-        .await?;
-
-    let mut config = aggregator
-        .get_install_config(name, source.as_deref())
-        .await?;
-    apply_detail_metadata(&detail, &mut config);
+    let resolved = resolve(aggregator, name, source.as_deref()).await;
+    spinner.finish_and_clear();
+    let (detail, mut config) = resolved?;
 
     let provided_env = parse_env_pairs(env_vars);
     let resolved_env =
@@ -56,8 +75,171 @@ pub async fn install_with_aggregator(
             .or_insert_with(|| normalize_env_reference(&key, &value));
     }
 
+    let name = detail.info.qualified_name.clone();
+
+    // Check every CLI target before touching any config
+    let targets = into
+        .iter()
+        .cloned()
+        .map(CliMcpConfig::locate)
+        .collect::<Result<Vec<_>>>()?;
+    for target in &targets {
+        target.check(&name)?;
+    }
+
+    let editor = write_config(&detail, config.clone())?;
+
+    if matches!(detail.info.install, ServerInstallType::Remote { .. }) {
+        println!("{} Remote servers are not launch-checked", "ℹ️".cyan());
+    } else if skip_env {
+        println!(
+            "{} Skipped launch check (--skip-env); run `aiw mcp test {}` once its environment is set",
+            "ℹ️".cyan(),
+            name
+        );
+    } else {
+        verify_launch(&editor, &name, config).await?;
+    }
+
+    println!(
+        "{} Installed {} from {}",
+        "✅".green(),
+        name.cyan(),
+        detail.info.source
+    );
+    println!(
+        "Configuration saved to {}",
+        editor.config_path().display()
+    );
+
+    add_to_cli_configs(&editor, &name, &config, &targets)
+}
+
+/// Register the server in each CLI config; on any failure restore the configs
+/// already changed from their backups and drop the server from mcp.json
+fn add_to_cli_configs(
+    editor: &McpConfigEditor,
+    name: &str,
+    config: &McpServerConfig,
+    targets: &[CliMcpConfig],
+) -> Result<()> {
+    let mut added: Vec<(&CliMcpConfig, AddedServer)> = Vec::new();
+    for target in targets {
+        match target.add_server(name, config) {
+            Ok(result) => added.push((target, result)),
+            Err(err) => {
+                for (done, result) in &added {
+                    if let Err(restore_err) = done.restore(&result.backup) {
+                        eprintln!("{} {:#}", "⚠️".yellow(), restore_err);
+                    }
+                }
+                editor.remove_server(name)?;
+                return Err(anyhow!(
+                    "Failed to add {} to {}: {:#}. Restored the CLI configs changed so far and removed it from {}",
+                    name,
+                    target.cli.display_name(),
+                    err,
+                    editor.config_path().display()
+                ));
+            }
+        }
+    }
+
+    for (target, result) in &added {
+        for warning in &result.warnings {
+            println!("{} {}", "⚠️".yellow(), warning);
+        }
+        println!(
+            "{} Added {} to {} ({}; backup: {})",
+            "✅".green(),
+            name.cyan(),
+            target.cli.display_name(),
+            target.path.display(),
+            result.backup.display()
+        );
+    }
+    Ok(())
+}
+
+/// Registry lookups behind the install spinner: detail, runtime check and
+/// install-ready config
+async fn resolve(
+    aggregator: &RegistryAggregator,
+    name: &str,
+    source: Option<&str>,
+) -> Result<(McpServerDetail, McpServerConfig)> {
+    let detail = aggregator.get_server_detail(name, source).await?;
+    check_runtime(&detail.info.install, std::env::var_os("PATH"))?;
+    let mut config = aggregator.get_install_config(name, source).await?;
+    apply_detail_metadata(&detail, &mut config);
+    Ok((detail, config))
+}
+
+/// Runtime an install type launches through, with a hint for installing it
+fn required_runtime(install: &ServerInstallType) -> Option<(&'static str, &'static str)> {
+    match install {
+        ServerInstallType::Npm { .. } => {
+            Some(("npx", "install Node.js (https://nodejs.org) first"))
+        }
+        ServerInstallType::Uvx { .. } => {
+            Some(("uvx", "install uv first (https://docs.astral.sh/uv/)"))
+        }
+        ServerInstallType::Docker { .. } => Some((
+            "docker",
+            "install Docker first (https://docs.docker.com/get-docker/)",
+        )),
+        ServerInstallType::Remote { .. } => None,
+    }
+}
+
+/// Pre-flight check that the server's runtime is on `path`
+fn check_runtime(install: &ServerInstallType, path: Option<OsString>) -> Result<()> {
+    let Some((binary, hint)) = required_runtime(install) else {
+        return Ok(());
+    };
+    let cwd = std::env::current_dir()?;
+    which::which_in(binary, path, cwd)
+        .map(|_| ())
+        .map_err(|_| anyhow!("{} not found; {}", binary, hint))
+}
+
+/// Start the freshly installed server once; remove it from mcp.json if it fails
+async fn verify_launch(
+    editor: &McpConfigEditor,
+    name: &str,
+    config: McpServerConfig,
+) -> Result<()> {
+    let spinner = ProgressBar::new_spinner()
+        .with_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner} {msg}")
+                .unwrap(),
+        )
+        .with_message(format!("Checking that {} starts...", name));
+    spinner.enable_steady_tick(std::time::Duration::from_millis(100));
+    let probe = probe_server(name, &routing_config(config)).await;
     spinner.finish_and_clear();
-    write_config(&detail, config)
+
+    match probe {
+        Ok(tools) => {
+            println!(
+                "{} {} started and exposes {} tool(s)",
+                "✓".green(),
+                name,
+                tools.len()
+            );
+            Ok(())
+        }
+        Err(err) => {
+            editor.remove_server(name)?;
+            Err(anyhow!(
+                "MCP server '{}' failed to start: {}. Removed it from {}",
+                name,
+                err,
+                editor.config_path().display()
+            ))
+        }
+    }
 }
 
 fn apply_detail_metadata(detail: &McpServerDetail, config: &mut McpServerConfig) {
@@ -79,7 +261,7 @@ fn parse_env_pairs(pairs: Vec<(String, String)>) -> HashMap<String, String> {
     map
 }
 
-fn write_config(detail: &McpServerDetail, config: McpServerConfig) -> Result<()> {
+fn write_config(detail: &McpServerDetail, config: McpServerConfig) -> Result<McpConfigEditor> {
     let editor = McpConfigEditor::new()?;
     let name = detail.info.qualified_name.clone();
 
@@ -88,18 +270,7 @@ fn write_config(detail: &McpServerDetail, config: McpServerConfig) -> Result<()>
     }
 
     editor.add_server(&name, config)?;
-
-    println!(
-        "{} Installed {} from {}",
-        "✅".green(),
-        name.cyan(),
-        detail.info.source
-    );
-    println!(
-        "Configuration saved to {}",
-        editor.config_path().display()
-    );
-    Ok(())
+    Ok(editor)
 }
 
 fn normalize_env_reference(name: &str, raw: &str) -> String {
@@ -115,3 +286,42 @@ fn normalize_env_reference(name: &str, raw: &str) -> String {
         format!("${{{}}}", name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_runtime_names_the_tool_to_install() {
+        let empty = tempfile::tempdir().unwrap();
+        let path = Some(empty.path().as_os_str().to_os_string());
+
+        let err = check_runtime(
+            &ServerInstallType::Uvx {
+                package: "mcp-server-git".to_string(),
+            },
+            path.clone(),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("uvx not found; install uv first"));
+
+        let err = check_runtime(
+            &ServerInstallType::Npm {
+                package: "@test/server".to_string(),
+            },
+            path.clone(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Node.js"));
+
+        check_runtime(
+            &ServerInstallType::Remote {
+                url: "https://example.com/mcp".to_string(),
+            },
+            path,
+        )
+        .expect("remote servers need no local runtime");
+    }
+}
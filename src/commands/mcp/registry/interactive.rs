@@ -29,6 +29,13 @@ pub fn render_results(results: &[McpServerInfo]) {
     table.printstd();
 }
 
+/// Tell the user which registries are missing from partial results
+pub fn warn_unavailable_sources(failures: &[(String, String)]) {
+    for (source, err) in failures {
+        eprintln!("{} {} skipped: {}", "⚠️".yellow(), source, err);
+    }
+}
+
 pub fn prompt_selection(total: usize) -> Result<Option<usize>> {
     if total == 0 {
         return Ok(None);
@@ -5,6 +5,7 @@ pub mod install;
 pub mod interactive;
 pub mod official;
 pub mod search;
+pub mod search_cache;
 pub mod smithery;
 pub mod source;
 pub mod types;
@@ -1,5 +1,5 @@
 use super::{
-    source::RegistrySource,
+    source::{http_client, RegistrySource, DEFAULT_REQUEST_TIMEOUT},
     types::{EnvVarSpec, McpServerDetail, McpServerInfo, ServerInstallType},
 };
 use crate::commands::mcp::McpServerConfig;
@@ -23,19 +23,16 @@ impl Default for OfficialRegistrySource {
 
 impl OfficialRegistrySource {
     pub fn new() -> Self {
-        Self::with_base_url(DEFAULT_BASE_URL, None)
+        Self::with_timeout(DEFAULT_REQUEST_TIMEOUT)
     }
 
-    pub fn with_base_url(base_url: impl Into<String>, client: Option<Client>) -> Self {
-        let http_client = client.unwrap_or_else(|| {
-            Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .expect("Failed to build reqwest client")
-        });
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::with_base_url(DEFAULT_BASE_URL, Some(http_client(timeout)))
+    }
 
+    pub fn with_base_url(base_url: impl Into<String>, client: Option<Client>) -> Self {
         Self {
-            client: http_client,
+            client: client.unwrap_or_else(|| http_client(DEFAULT_REQUEST_TIMEOUT)),
             base_url: base_url.into(),
         }
     }
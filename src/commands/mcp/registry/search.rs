@@ -1,10 +1,33 @@
-use super::{aggregator::RegistryAggregator, install, interactive};
+use super::{
+    aggregator::{self, RegistryAggregator, SearchSort},
+    install, interactive,
+    source::DEFAULT_REQUEST_TIMEOUT,
+};
 use anyhow::{anyhow, Result};
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
+use std::time::Duration;
 
-pub async fn execute(query: &str, source: Option<String>, limit: Option<usize>) -> Result<()> {
-    let aggregator = RegistryAggregator::new();
+pub async fn execute(
+    query: &str,
+    source: Option<String>,
+    limit: Option<usize>,
+    sort: Option<String>,
+    no_cache: bool,
+    timeout: Option<u64>,
+) -> Result<()> {
+    let sort: SearchSort = match sort {
+        Some(value) => value.parse().map_err(|err: String| anyhow!(err))?,
+        None => SearchSort::default(),
+    };
+    let limit = limit.unwrap_or(20);
+
+    let mut aggregator = RegistryAggregator::with_timeout(
+        timeout.map_or(DEFAULT_REQUEST_TIMEOUT, Duration::from_secs),
+    );
+    if no_cache {
+        aggregator = aggregator.bypass_cache();
+    }
     let spinner = ProgressBar::new_spinner()
         .with_style(
             ProgressStyle::default_spinner()
@@ -14,10 +37,15 @@ pub async fn execute(query: &str, source: Option<String>, limit: Option<usize>)
         .with_message("Searching MCP servers...");
     spinner.enable_steady_tick(std::time::Duration::from_millis(100));
 
-    let results = aggregator
-        .search(query, source.as_deref(), limit.unwrap_or(20))
-        .await?;
+    let outcome = aggregator
+        .search_with_failures(query, source.as_deref(), limit)
+        .await;
     spinner.finish_and_clear();
+    let outcome = outcome?;
+    interactive::warn_unavailable_sources(&outcome.failures);
+    let mut results = outcome.results;
+    aggregator::sort_results(&mut results, query, sort);
+    results.truncate(limit);
 
     if results.is_empty() {
         return Err(anyhow!("No MCP servers found for '{}'", query));
@@ -39,6 +67,7 @@ pub async fn execute(query: &str, source: Option<String>, limit: Option<usize>)
             Some(selected.source.clone()),
             Vec::new(),
             false,
+            &[],
         )
         .await?;
     }
@@ -0,0 +1,165 @@
+//! On-disk cache of registry search results (`~/.aiw/cache/mcp_search/`).
+//!
+//! One JSON file per query+source. Entries expire after a short TTL so new
+//! registry releases show up quickly, while repeated searches and `mcp browse`
+//! right after a search skip the network.
+
+use super::types::McpServerInfo;
+use crate::config::MCP_SEARCH_CACHE_TTL_ENV;
+use crate::utils::config_paths::ConfigPaths;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default lifetime of a cached search
+pub const DEFAULT_TTL: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedSearch {
+    query: String,
+    source: Option<String>,
+    limit: usize,
+    fetched_at: DateTime<Utc>,
+    results: Vec<McpServerInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl SearchCache {
+    /// `~/.aiw/cache/mcp_search/`, TTL from `AIW_MCP_SEARCH_CACHE_TTL` (seconds)
+    pub fn new() -> Result<Self> {
+        let paths = ConfigPaths::new()?;
+        let ttl = std::env::var(MCP_SEARCH_CACHE_TTL_ENV)
+            .ok()
+            .and_then(|raw| raw.trim().parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_TTL);
+        Ok(Self::with_dir(
+            paths.config_dir.join("cache").join("mcp_search"),
+            ttl,
+        ))
+    }
+
+    pub fn with_dir(dir: impl Into<PathBuf>, ttl: Duration) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl,
+        }
+    }
+
+    /// Fresh results for `query`+`source`. An entry fetched with a larger
+    /// limit also answers smaller ones.
+    pub fn load(
+        &self,
+        query: &str,
+        source: Option<&str>,
+        limit: usize,
+    ) -> Option<Vec<McpServerInfo>> {
+        let raw = fs::read_to_string(self.entry_path(query, source)).ok()?;
+        let cached: CachedSearch = serde_json::from_str(&raw).ok()?;
+        let age = Utc::now()
+            .signed_duration_since(cached.fetched_at)
+            .to_std()
+            .unwrap_or_default();
+        if age >= self.ttl || cached.limit < limit {
+            return None;
+        }
+        Some(cached.results)
+    }
+
+    pub fn store(
+        &self,
+        query: &str,
+        source: Option<&str>,
+        limit: usize,
+        results: &[McpServerInfo],
+    ) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+        let entry = CachedSearch {
+            query: query.to_string(),
+            source: source.map(str::to_string),
+            limit,
+            fetched_at: Utc::now(),
+            results: results.to_vec(),
+        };
+        let path = self.entry_path(query, source);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(&entry)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Remove every cached search
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(err).with_context(|| format!("Failed to clear {}", self.dir.display())),
+        }
+    }
+
+    fn entry_path(&self, query: &str, source: Option<&str>) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(query.trim().to_lowercase().as_bytes());
+        hasher.update([0]);
+        hasher.update(source.unwrap_or("*").as_bytes());
+        let digest = format!("{:x}", hasher.finalize());
+        self.dir.join(format!("{}.json", &digest[..32]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::mcp::registry::types::ServerInstallType;
+
+    fn server(name: &str) -> McpServerInfo {
+        McpServerInfo {
+            qualified_name: name.to_string(),
+            display_name: name.to_string(),
+            description: None,
+            source: "registry".to_string(),
+            install: ServerInstallType::Npm {
+                package: format!("@test/{}", name),
+            },
+            author: None,
+            downloads: Some(1),
+        }
+    }
+
+    #[test]
+    fn stored_search_is_reused_until_it_expires() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SearchCache::with_dir(dir.path(), DEFAULT_TTL);
+
+        assert!(cache.load("files", None, 20).is_none());
+        cache
+            .store("files", None, 20, &[server("a"), server("b")])
+            .unwrap();
+
+        let hit = cache.load(" Files ", None, 10).expect("cached");
+        assert_eq!(hit.len(), 2);
+        assert_eq!(hit[0].qualified_name, "a");
+        assert!(cache.load("files", Some("smithery"), 10).is_none());
+        assert!(
+            cache.load("files", None, 50).is_none(),
+            "a smaller fetch cannot answer a larger limit"
+        );
+
+        let expired = SearchCache::with_dir(dir.path(), Duration::ZERO);
+        assert!(expired.load("files", None, 20).is_none());
+
+        cache.clear().unwrap();
+        assert!(cache.load("files", None, 20).is_none());
+    }
+}
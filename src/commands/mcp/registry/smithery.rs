@@ -1,5 +1,5 @@
 use super::{
-    source::RegistrySource,
+    source::{http_client, RegistrySource, DEFAULT_REQUEST_TIMEOUT},
     types::{EnvVarSpec, McpServerDetail, McpServerInfo, ServerInstallType},
 };
 use crate::commands::mcp::McpServerConfig;
@@ -24,8 +24,12 @@ impl Default for SmitherySource {
 
 impl SmitherySource {
     pub fn new() -> Self {
+        Self::with_timeout(DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    pub fn with_timeout(timeout: Duration) -> Self {
         let api_key = std::env::var("SMITHERY_API_KEY").ok();
-        Self::with_base_url(DEFAULT_BASE_URL, api_key, None)
+        Self::with_base_url(DEFAULT_BASE_URL, api_key, Some(http_client(timeout)))
     }
 
     pub fn with_base_url(
@@ -33,15 +37,8 @@ impl SmitherySource {
         api_key: Option<String>,
         client: Option<Client>,
     ) -> Self {
-        let http_client = client.unwrap_or_else(|| {
-            Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .expect("Failed to build reqwest client")
-        });
-
         Self {
-            client: http_client,
+            client: client.unwrap_or_else(|| http_client(DEFAULT_REQUEST_TIMEOUT)),
             base_url: base_url.into(),
             api_key,
         }
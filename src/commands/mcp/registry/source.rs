@@ -2,6 +2,19 @@ use super::types::{McpServerDetail, McpServerInfo};
 use crate::commands::mcp::McpServerConfig;
 use anyhow::Result;
 use async_trait::async_trait;
+use reqwest::Client;
+use std::time::Duration;
+
+/// Per-registry request timeout when `--timeout` is not given
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HTTP client shared by the registry sources
+pub fn http_client(timeout: Duration) -> Client {
+    Client::builder()
+        .timeout(timeout)
+        .build()
+        .expect("Failed to build reqwest client")
+}
 
 #[async_trait]
 pub trait RegistrySource: Send + Sync {
@@ -1,7 +1,8 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Installation type supported by registry entries.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ServerInstallType {
     Npm { package: String },
     Uvx { package: String },
@@ -42,7 +43,7 @@ impl fmt::Display for ServerInstallType {
 }
 
 /// Minimal search result entry.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerInfo {
     pub qualified_name: String,
     pub display_name: String,
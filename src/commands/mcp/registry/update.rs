@@ -28,7 +28,7 @@ pub async fn execute() -> Result<()> {
     }
 
     if success {
-        println!("{}", "Cache refreshed (in-memory)".green());
+        println!("{}", "Cache refreshed".green());
         Ok(())
     } else {
         Err(anyhow!(
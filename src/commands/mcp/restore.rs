@@ -0,0 +1,98 @@
+//! restore命令实现 - 从 ~/.aiw/backups/ 恢复 mcp.json
+
+use super::McpConfigEditor;
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use dialoguer::Confirm;
+use std::path::{Path, PathBuf};
+
+pub fn execute(backup: Option<&str>, list: bool, yes: bool) -> Result<()> {
+    let editor = McpConfigEditor::new()?;
+    let backups = editor.list_backups()?;
+
+    if list {
+        if backups.is_empty() {
+            println!("No backups in {}", editor.backup_dir().display());
+            return Ok(());
+        }
+        println!(
+            "Backups in {} (newest first):",
+            editor.backup_dir().display()
+        );
+        for (index, path) in backups.iter().enumerate() {
+            println!("  {:>2}. {}", index + 1, file_name(path));
+        }
+        return Ok(());
+    }
+
+    let target = select_backup(&editor, &backups, backup)?;
+
+    println!(
+        "{} Restore {} from {}",
+        "⚠️".yellow(),
+        editor.config_path().display(),
+        file_name(&target).cyan()
+    );
+    let confirmed = if yes {
+        true
+    } else {
+        Confirm::new()
+            .with_prompt("Continue?")
+            .default(false)
+            .interact()?
+    };
+    if !confirmed {
+        println!("{}", "Cancelled".yellow());
+        return Ok(());
+    }
+
+    editor.restore(&target)?;
+    println!(
+        "{} Restored MCP config from {}",
+        "✅".green(),
+        file_name(&target)
+    );
+    println!("The previous config was backed up first; run 'aiw mcp restore --list' to see it.");
+    Ok(())
+}
+
+/// 未指定时取最新备份；可传列表序号（1 为最新）、备份文件名或路径
+fn select_backup(
+    editor: &McpConfigEditor,
+    backups: &[PathBuf],
+    requested: Option<&str>,
+) -> Result<PathBuf> {
+    let Some(requested) = requested else {
+        return backups
+            .first()
+            .cloned()
+            .ok_or_else(|| anyhow!("No backups in {}", editor.backup_dir().display()));
+    };
+
+    if let Ok(index) = requested.parse::<usize>() {
+        return index
+            .checked_sub(1)
+            .and_then(|index| backups.get(index))
+            .cloned()
+            .ok_or_else(|| anyhow!("No backup #{} (see 'aiw mcp restore --list')", requested));
+    }
+
+    let path = Path::new(requested);
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    let in_dir = editor.backup_dir().join(requested);
+    if in_dir.is_file() {
+        return Ok(in_dir);
+    }
+    Err(anyhow!(
+        "Backup '{}' not found (see 'aiw mcp restore --list')",
+        requested
+    ))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
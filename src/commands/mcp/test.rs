@@ -0,0 +1,112 @@
+//! test命令实现 - 启动服务器、列出工具后关闭
+
+use super::{McpConfigEditor, McpServerConfig};
+use crate::mcp_routing::{config, probe_server};
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+
+pub async fn execute(name: &str) -> Result<()> {
+    let editor = McpConfigEditor::new()?;
+    let server = editor.get_server(name)?.ok_or_else(|| {
+        anyhow!(
+            "MCP server '{}' not found in {}",
+            name,
+            editor.config_path().display()
+        )
+    })?;
+
+    ensure_command(&server.command)?;
+
+    println!(
+        "{} Starting '{}': {} {}",
+        "🔌".cyan(),
+        name.cyan(),
+        server.command,
+        server.args.join(" ")
+    );
+
+    let tools = probe_server(name, &routing_config(server)).await?;
+    if tools.is_empty() {
+        return Err(anyhow!(
+            "MCP server '{}' started but returned no tools",
+            name
+        ));
+    }
+
+    println!(
+        "{} '{}' exposes {} tool(s):",
+        "✅".green(),
+        name.cyan(),
+        tools.len()
+    );
+    println!();
+    for tool in &tools {
+        println!("  • {}", tool.name.bold());
+        if let Some(description) = tool.description.as_deref() {
+            println!("    {}", description.dimmed());
+        }
+        let schema = serde_json::to_string_pretty(tool.input_schema.as_ref())?;
+        for line in schema.lines() {
+            println!("    {}", line);
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// 启动前确认命令存在，给出比 spawn 失败更明确的提示
+pub(super) fn ensure_command(command: &str) -> Result<()> {
+    which::which(command).map(|_| ()).map_err(|_| {
+        anyhow!(
+            "Command '{}' not found. Install it or fix the server's command in mcp.json",
+            command
+        )
+    })
+}
+
+/// mcp.json 条目转换为路由层启动配置，`mcp install` 安装后校验也会用到
+pub(crate) fn routing_config(server: McpServerConfig) -> config::McpServerConfig {
+    config::McpServerConfig {
+        command: server.command,
+        args: server.args,
+        env: server.env,
+        description: server.description,
+        category: server.category,
+        enabled: server.enabled,
+        health_check: None,
+        source: server.source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_command_is_reported_by_name() {
+        let err = ensure_command("aiw-definitely-missing-mcp-server").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Command 'aiw-definitely-missing-mcp-server' not found"));
+    }
+
+    #[test]
+    fn routing_config_keeps_launch_settings() {
+        let server = McpServerConfig {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "server".to_string()],
+            env: [("API_KEY".to_string(), "${API_KEY}".to_string())].into(),
+            description: None,
+            category: None,
+            enabled: Some(false),
+            source: Some("registry".to_string()),
+        };
+
+        let converted = routing_config(server);
+        assert_eq!(converted.command, "npx");
+        assert_eq!(converted.args, vec!["-y", "server"]);
+        assert_eq!(converted.env["API_KEY"], "${API_KEY}");
+        assert_eq!(converted.enabled, Some(false));
+    }
+}
@@ -0,0 +1,79 @@
+//! tools命令实现 - 按服务器列出发现的工具及其是否进入路由索引
+
+use crate::mcp_routing::health::ServerStatus;
+use crate::mcp_routing::models::DiscoveredServerTools;
+use crate::mcp_routing::IntelligentRouter;
+use anyhow::Result;
+use colored::Colorize;
+
+pub async fn execute(server: Option<&str>) -> Result<()> {
+    // 与 `aiw mcp serve` 相同的启动流程：连接所有启用的服务器并建立路由索引
+    let router = IntelligentRouter::initialize().await?;
+    let report = router.discovered_tools_report(server).await;
+    router.connection_pool().shutdown().await;
+    let report = report?;
+
+    if report.servers.is_empty() {
+        println!("No MCP servers configured. Add one with `aiw mcp add <name> <command>`.");
+        return Ok(());
+    }
+
+    for entry in &report.servers {
+        print_server(entry);
+        println!();
+    }
+    Ok(())
+}
+
+fn print_server(entry: &DiscoveredServerTools) {
+    let status = match entry.status {
+        ServerStatus::Up => entry.status.as_str().green(),
+        ServerStatus::Disabled => entry.status.as_str().dimmed(),
+        ServerStatus::Down | ServerStatus::Failed => entry.status.as_str().red(),
+    };
+    let indexed = entry.tools.iter().filter(|tool| tool.indexed).count();
+    println!(
+        "{} [{}] {} tool(s), {} indexed",
+        entry.server.cyan().bold(),
+        status,
+        entry.tools.len(),
+        indexed
+    );
+    if let Some(error) = entry.error.as_deref() {
+        println!("  {} {}", "error:".red(), error);
+    }
+    if entry.tools.is_empty() && entry.status == ServerStatus::Up {
+        println!("  {}", "(no tools discovered)".dimmed());
+    }
+
+    for tool in &entry.tools {
+        let marker = if tool.indexed {
+            "✓".green()
+        } else {
+            "✗".red()
+        };
+        let mut notes = Vec::new();
+        if !tool.indexed {
+            notes.push("not in routing index");
+        }
+        if tool.schemaless {
+            notes.push("no input schema");
+        }
+        if notes.is_empty() {
+            println!("  {} {}", marker, tool.name.bold());
+        } else {
+            println!(
+                "  {} {} {}",
+                marker,
+                tool.name.bold(),
+                format!("({})", notes.join(", ")).yellow()
+            );
+        }
+        if let Some(description) = tool.description.as_deref() {
+            println!(
+                "      {}",
+                description.lines().next().unwrap_or("").dimmed()
+            );
+        }
+    }
+}
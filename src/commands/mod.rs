@@ -4,10 +4,18 @@
 
 pub mod ai_cli;
 pub mod auto;
+pub mod cli;
 pub mod cli_args;
+pub mod doctor;
 pub mod market;
 pub mod mcp;
 pub mod parser;
+pub mod provider;
+pub mod registry;
+pub mod routing;
+pub mod status;
+pub mod sync;
+pub mod task;
 pub mod tui_commands;
 pub mod patch;
 
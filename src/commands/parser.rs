@@ -38,6 +38,28 @@ impl ParsedCliArgs {
 pub enum RolesAction {
     /// 列出所有可用的角色配置
     List,
+    /// 显示角色解析后的最终内容（用户角色优先，其次内置）
+    Show {
+        /// 角色名称
+        name: String,
+        /// 原样输出角色文件内容
+        #[arg(long)]
+        raw: bool,
+        /// 预览内置角色的指定语言版本（en 或 zh-CN）
+        #[arg(long, value_name = "LANG")]
+        lang: Option<String>,
+    },
+    /// 列出被 ~/.aiw/role/ 中同名用户角色覆盖的内置角色
+    Overrides {
+        /// 只看该内置角色，并显示与用户版本的差异
+        name: Option<String>,
+        /// 显示每个被覆盖角色的差异
+        #[arg(long)]
+        diff: bool,
+        /// 对比的内置角色语言（en 或 zh-CN）
+        #[arg(long, value_name = "LANG")]
+        lang: Option<String>,
+    },
 }
 
 /// 补丁管理动作
@@ -111,6 +133,199 @@ pub enum ConfigAction {
     /// 管理 AI CLI 执行顺序
     #[command(name = "cli-order")]
     CliOrder,
+    /// 清除 auto 模式的 CLI+Provider 冷却状态（不带参数时清除全部）
+    #[command(name = "reset-cooldown")]
+    ResetCooldown {
+        /// AI CLI 类型（codex/claude/gemini）
+        #[arg(long, requires = "provider")]
+        cli: Option<String>,
+        /// Provider 名称
+        #[arg(long, requires = "cli")]
+        provider: Option<String>,
+    },
+}
+
+/// Provider 管理动作
+#[derive(Subcommand, Debug, Clone)]
+pub enum ProviderAction {
+    /// 端到端验证 Provider（凭据 + 连通性）
+    Test {
+        /// Provider 名称
+        name: String,
+    },
+
+    /// 检查 providers.json（未知的兼容类型、重复名称、缺失字段、默认 Provider 兼容性）
+    Validate,
+
+    /// 删除 Provider
+    Remove {
+        /// Provider 名称
+        name: String,
+        /// 删除默认 Provider 时改用的新默认 Provider
+        #[arg(long = "default", value_name = "PROVIDER")]
+        new_default: Option<String>,
+        /// 跳过确认提示
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// 修改 Provider 字段（不带字段参数时逐项交互编辑）
+    Edit {
+        /// Provider 名称
+        name: String,
+        /// 场景描述
+        #[arg(long)]
+        scenario: Option<String>,
+        /// 兼容的 AI CLI（逗号分隔，如 claude,codex；`all` 表示全部）
+        #[arg(long = "compatible-with", value_name = "TYPES")]
+        compatible_with: Option<String>,
+        /// `--aiw-provider auto` 的优先级（数值越小越优先；`none` 清除）
+        #[arg(long)]
+        priority: Option<String>,
+        /// 设置环境变量 (KEY=VALUE，可多次使用)
+        #[arg(long = "env")]
+        env_vars: Vec<String>,
+        /// 删除环境变量（可多次使用）
+        #[arg(long = "unset-env", value_name = "KEY")]
+        unset_env: Vec<String>,
+        /// 启用 Provider
+        #[arg(long, conflicts_with = "disable")]
+        enable: bool,
+        /// 停用 Provider
+        #[arg(long)]
+        disable: bool,
+    },
+}
+
+/// 任务注册表维护动作
+#[derive(Subcommand, Debug, Clone)]
+pub enum RegistryAction {
+    /// 备份并重建损坏的共享内存注册表
+    Reset {
+        /// 强制重建指定进程的注册表（不指定时只修复检测到损坏的注册表）
+        #[arg(long)]
+        pid: Option<u32>,
+        /// 删除进程已退出但仍标记为运行中的条目
+        #[arg(long = "purge-dead")]
+        purge_dead: bool,
+    },
+}
+
+/// 单个任务的操作
+#[derive(Subcommand, Debug, Clone)]
+pub enum TaskAction {
+    /// 把任务的日志、元数据和命令行打包成 tar.gz（环境变量值会脱敏）
+    Export {
+        /// 任务 ID、PID 或日志 ID
+        #[arg(value_name = "TASK_ID")]
+        task_id: String,
+        /// 输出文件（默认 ~/.aiw/exports/task-<id>-<时间戳>.tar.gz）
+        #[arg(value_name = "OUTPUT")]
+        output: Option<std::path::PathBuf>,
+    },
+    /// 跟随任务日志输出（类似 tail -f），任务结束时显示退出码，Ctrl-C 断开
+    Attach {
+        /// 任务 ID、PID 或日志 ID
+        #[arg(value_name = "TASK_ID")]
+        task_id: String,
+        /// 只显示包含该文本的行
+        #[arg(long, value_name = "PATTERN")]
+        grep: Option<String>,
+        /// 把 --grep 的模式当作正则表达式
+        #[arg(long, requires = "grep")]
+        regex: bool,
+        /// 每个匹配行前后额外显示的行数
+        #[arg(short = 'C', long, value_name = "LINES", requires = "grep")]
+        context: Option<usize>,
+    },
+}
+
+/// MCP 路由/决策设置动作
+#[derive(Subcommand, Debug, Clone)]
+pub enum RoutingAction {
+    /// 查看或修改 ~/.aiw/mcp.json 的 routing 段（无子命令时显示生效配置）
+    Config {
+        #[command(subcommand)]
+        action: Option<RoutingConfigAction>,
+    },
+}
+
+/// `aiw routing config` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum RoutingConfigAction {
+    /// 显示某个键的生效值及来源
+    Get {
+        /// 配置键（如 llm_model、fast_path_threshold）
+        key: String,
+    },
+    /// 写入 routing 段（优先于环境变量）
+    Set {
+        /// 配置键
+        key: String,
+        /// 配置值
+        value: String,
+    },
+    /// 删除 routing 段中的键，恢复环境变量或默认值
+    Unset {
+        /// 配置键
+        key: String,
+    },
+}
+
+/// AI CLI 安装管理动作
+#[derive(Subcommand, Debug, Clone)]
+pub enum CliAction {
+    /// 使用可用的包管理器（npm / Homebrew）安装 AI CLI
+    Install {
+        /// AI CLI 名称（claude/codex/gemini）
+        tool: String,
+    },
+    /// 通过原安装方式卸载 AI CLI
+    Uninstall {
+        /// AI CLI 名称（claude/codex/gemini）
+        tool: String,
+    },
+}
+
+/// Google Drive 配置同步动作
+#[derive(Subcommand, Debug, Clone)]
+pub enum SyncAction {
+    /// 上传配置目录（不指定目录时上传全部已配置目录）
+    Push {
+        /// 只上传这些目录（见 `aiw sync config list-dirs`）
+        #[arg(value_name = "DIR", conflicts_with = "name")]
+        directories: Vec<String>,
+        /// 以命名配置包上传全部 AI CLI 配置
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// 下载并恢复配置目录（不指定目录时恢复全部已配置目录）
+    Pull {
+        /// 只恢复这些目录（见 `aiw sync config list-dirs`）
+        #[arg(value_name = "DIR", conflicts_with = "name")]
+        directories: Vec<String>,
+        /// 下载指定名称的配置包
+        #[arg(long)]
+        name: Option<String>,
+        /// 本地修改比远端备份新时也直接覆盖
+        #[arg(long)]
+        force: bool,
+    },
+    /// 显示同步状态
+    Status,
+    /// 同步设置
+    Config {
+        #[command(subcommand)]
+        action: SyncConfigAction,
+    },
+}
+
+/// `aiw sync config` 子命令
+#[derive(Subcommand, Debug, Clone)]
+pub enum SyncConfigAction {
+    /// 列出已配置的同步目录
+    #[command(name = "list-dirs")]
+    ListDirs,
 }
 
 /// MCP服务器管理动作
@@ -160,6 +375,15 @@ pub enum McpAction {
         /// 返回结果数量限制
         #[arg(long)]
         limit: Option<usize>,
+        /// 排序方式 (downloads|name|relevance，默认 relevance)
+        #[arg(long)]
+        sort: Option<String>,
+        /// 忽略本地缓存，直接查询仓库
+        #[arg(long = "no-cache")]
+        no_cache: bool,
+        /// 每个仓库请求的超时秒数（默认 10）
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
     },
 
     /// 安装MCP服务器
@@ -175,6 +399,12 @@ pub enum McpAction {
         /// 跳过环境变量配置
         #[arg(long = "skip-env")]
         skip_env: bool,
+        /// 同时写入 AI CLI 自己的 MCP 配置 (claude|codex|gemini|all)
+        #[arg(long, value_name = "CLI")]
+        into: Option<String>,
+        /// 每个仓库请求的超时秒数（默认 10）
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
     },
 
     /// 查看MCP服务器信息
@@ -194,6 +424,9 @@ pub enum McpAction {
         /// 指定来源 (registry|smithery)
         #[arg(long)]
         source: Option<String>,
+        /// 每个仓库请求的超时秒数（默认 10）
+        #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+        timeout: Option<u64>,
     },
 
     /// 获取服务器详细配置
@@ -204,24 +437,91 @@ pub enum McpAction {
 
     /// 启用服务器
     Enable {
-        /// 服务器名称
-        name: String,
+        /// 服务器名称，多个用逗号分隔
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        name: Option<String>,
+        /// 启用全部服务器
+        #[arg(long)]
+        all: bool,
     },
 
     /// 禁用服务器
     Disable {
-        /// 服务器名称
-        name: String,
+        /// 服务器名称，多个用逗号分隔
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        name: Option<String>,
+        /// 禁用全部服务器
+        #[arg(long)]
+        all: bool,
     },
 
     /// 在编辑器中编辑配置文件
     Edit,
 
+    /// 从 ~/.aiw/backups/ 恢复 mcp.json（默认最新备份）
+    Restore {
+        /// 备份序号（1 为最新）、文件名或路径
+        backup: Option<String>,
+        /// 列出可用备份
+        #[arg(long, conflicts_with = "backup")]
+        list: bool,
+        /// 跳过确认提示
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// 启动服务器并列出其提供的工具
+    Test {
+        /// 服务器名称
+        name: String,
+    },
+
+    /// 启动所有启用的服务器，按服务器列出发现的工具及其是否进入路由索引
+    Tools {
+        /// 只显示该服务器
+        server: Option<String>,
+    },
+
+    /// 直接调用服务器的某个工具并输出原始结果（不经过智能路由）
+    Call {
+        /// 服务器名称
+        server: String,
+        /// 工具名称
+        tool: String,
+        /// 工具参数（JSON 对象，默认 {}）
+        #[arg(long)]
+        args: Option<String>,
+    },
+
+    /// 导出服务器配置到可分享的文件
+    Export {
+        /// 输出文件路径
+        file: String,
+        /// 把环境变量值替换为 ${KEY} 占位符
+        #[arg(long)]
+        redact: bool,
+    },
+
+    /// 从文件导入服务器配置（默认与现有配置合并，同名服务器跳过）
+    Import {
+        /// 导入文件路径
+        file: String,
+        /// 用导入的服务器替换全部现有服务器
+        #[arg(long)]
+        replace: bool,
+        /// 合并时覆盖同名服务器（默认跳过）
+        #[arg(long, conflicts_with = "replace")]
+        overwrite: bool,
+    },
+
     /// 启动MCP服务器（内部使用）
     Serve {
-        /// 传输协议
-        #[arg(long, default_value = "stdio")]
-        transport: String,
+        /// 传输协议（stdio | sse），未指定时读取 AGENTIC_WARDEN_MCP_TRANSPORT，默认 stdio
+        #[arg(long)]
+        transport: Option<String>,
+        /// SSE 监听地址 host:port，未指定时读取 AGENTIC_WARDEN_MCP_BIND，默认 127.0.0.1:8765
+        #[arg(long)]
+        bind: Option<String>,
         /// 日志级别
         #[arg(long, default_value = "info")]
         log_level: String,
@@ -348,15 +648,63 @@ pub enum Commands {
     /// 显示任务状态
     Status {
         /// 启动TUI界面（默认显示文本摘要）
-        #[arg(long)]
+        #[arg(long, conflicts_with_all = ["plain", "watch"])]
         tui: bool,
+        /// 以纯文本表格显示任务并定期刷新（适合 SSH/管道/CI）
+        #[arg(long)]
+        plain: bool,
+        /// 同 --plain
+        #[arg(long)]
+        watch: bool,
+        /// 只输出一次快照后退出（配合 --plain）
+        #[arg(long)]
+        once: bool,
+        /// 刷新间隔（秒）
+        #[arg(long, value_name = "SECS", default_value_t = crate::commands::status::DEFAULT_WATCH_INTERVAL_SECS, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+
+    /// 启动 Provider 管理 TUI（带子命令时执行对应动作）
+    Provider {
+        #[command(subcommand)]
+        action: Option<ProviderAction>,
     },
 
-    /// 启动 Provider 管理 TUI
-    Provider,
+    /// 诊断运行环境（AI CLI、配置文件、路由端点、注册表）
+    Doctor,
+
+    /// 任务注册表维护
+    #[command(subcommand)]
+    Registry(RegistryAction),
+
+    /// 单个任务的诊断操作（导出诊断包）
+    #[command(subcommand)]
+    Task(TaskAction),
+
+    /// MCP 路由/决策配置
+    #[command(subcommand)]
+    Routing(RoutingAction),
+
+    /// 安装或卸载 AI CLI
+    #[command(subcommand)]
+    Cli(CliAction),
+
+    /// 通过 Google Drive 同步 AI CLI 配置
+    #[command(subcommand)]
+    Sync(SyncAction),
 
     /// 等待所有并发AI CLI任务完成（跨进程）
-    Wait,
+    ///
+    /// 指定任务 ID（支持 `*` 通配）或 `--all` 时只等待对应任务，任一失败返回非零退出码
+    Wait {
+        /// 要等待的任务 ID、PID 或日志 ID，可使用 `*` 通配
+        #[arg(value_name = "TASK_ID", conflicts_with = "all")]
+        task_ids: Vec<String>,
+
+        /// 等待本机所有注册表中正在运行的任务
+        #[arg(long)]
+        all: bool,
+    },
 
     /// 等待指定进程的共享任务完成
     #[command(name = "pwait")]
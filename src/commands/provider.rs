@@ -0,0 +1,494 @@
+//! `aiw provider test|remove|edit <name>`、`aiw provider validate`
+//!
+//! test：按 CLI 实际启动时的方式构建环境变量（进程环境 + Provider env），
+//! 然后对每个兼容的 AI 类型向其 API 端点发一个最小请求，验证凭据和连通性。
+//! remove / edit：通过 `ProviderManager` 修改 providers.json，保存前整体校验，
+//! 不会留下指向不存在 Provider 的默认值。
+//! validate：不加载配置，直接检查 providers.json 原文并列出全部问题。
+
+use std::io::IsTerminal;
+use std::process::ExitCode;
+
+use anyhow::{anyhow, Result};
+use dialoguer::{Confirm, Input};
+
+use crate::common::constants::files::PROVIDERS_JSON;
+use crate::provider::config::{Provider, ProvidersConfig};
+use crate::provider::probe::{build_client, probe_request, run_probe};
+use crate::provider::validation::{check_providers_json, ConfigIssue, Severity};
+use crate::provider::{AiType, EnvInjector, ProviderManager};
+use crate::utils::config_paths::aiw_config_dir;
+
+const TESTABLE_AI_TYPES: [AiType; 4] =
+    [AiType::Claude, AiType::Codex, AiType::Gemini, AiType::Grok];
+
+/// 处理 `aiw provider test <name>`
+pub async fn handle_provider_test(name: &str) -> ExitCode {
+    let manager = match ProviderManager::new() {
+        Ok(manager) => manager,
+        Err(err) => {
+            eprintln!("❌ Failed to load providers: {}", err);
+            return ExitCode::from(1);
+        }
+    };
+    let provider = match manager.get_provider(name) {
+        Ok(provider) => provider,
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            return ExitCode::from(1);
+        }
+    };
+
+    let resolved = {
+        let provider = provider.clone();
+        tokio::task::spawn_blocking(move || EnvInjector::resolved_provider_env(&provider)).await
+    };
+    let env = match resolved {
+        Ok(Ok(provider_env)) => EnvInjector::build_env(&provider_env),
+        Ok(Err(err)) => {
+            eprintln!("❌ {}", err);
+            return ExitCode::from(1);
+        }
+        Err(err) => {
+            eprintln!("❌ Failed to resolve provider secrets: {}", err);
+            return ExitCode::from(1);
+        }
+    };
+    let client = match build_client(&env) {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("❌ Invalid proxy configuration: {}", err);
+            return ExitCode::from(1);
+        }
+    };
+
+    println!("🔍 Testing provider '{}'...", name);
+    let mut failed = 0;
+    let mut tested = 0;
+    for ai_type in TESTABLE_AI_TYPES {
+        if !provider.is_compatible_with(&ai_type) {
+            continue;
+        }
+        let Some(request) = probe_request(&ai_type, &env) else {
+            println!(
+                "  ⏭️  {:<7} skipped (no API key; the CLI may use its own login)",
+                ai_type
+            );
+            continue;
+        };
+
+        tested += 1;
+        let outcome = run_probe(&client, name, ai_type, request).await;
+        let status = outcome
+            .status
+            .map(|code| format!("HTTP {}", code))
+            .unwrap_or_else(|| "no response".to_string());
+        let latency = outcome.latency.as_millis();
+        match &outcome.error {
+            None => println!("  ✅ {:<7} {} {}ms", outcome.ai_type, status, latency),
+            Some(err) => {
+                failed += 1;
+                let payload = err.to_user_facing();
+                println!("  ❌ {:<7} {} {}ms", outcome.ai_type, status, latency);
+                println!("     {}", payload.message);
+                if let Some(hint) = payload.hint {
+                    println!("     💡 {}", hint);
+                }
+            }
+        }
+    }
+
+    if tested == 0 {
+        println!(
+            "⚠️  Nothing to test: provider '{}' has no API keys for its compatible CLIs",
+            name
+        );
+        return ExitCode::from(0);
+    }
+    if failed > 0 {
+        println!("❌ {}/{} checks failed", failed, tested);
+        return ExitCode::from(1);
+    }
+    println!("✅ All {} checks passed", tested);
+    ExitCode::from(0)
+}
+
+/// 处理 `aiw provider validate`：有错误时退出码为 1，只有警告时为 0
+pub fn handle_provider_validate() -> ExitCode {
+    let Some(path) = aiw_config_dir().map(|dir| dir.join(PROVIDERS_JSON)) else {
+        eprintln!("❌ Cannot find home directory");
+        return ExitCode::from(1);
+    };
+    if !path.exists() {
+        println!(
+            "ℹ️  {} does not exist yet; the default configuration will be created on first use",
+            path.display()
+        );
+        return ExitCode::from(0);
+    }
+
+    println!("🔍 Validating {}", path.display());
+    let content = match ProvidersConfig::read_migrated(&path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            return ExitCode::from(1);
+        }
+    };
+    let issues = validate_content(&content);
+
+    for issue in &issues {
+        match issue.severity {
+            Severity::Error => println!("  ❌ {}", issue),
+            Severity::Warning => println!("  ⚠️  {}", issue),
+        }
+    }
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
+    let warnings = issues.len() - errors;
+    if errors > 0 {
+        println!("❌ {} error(s), {} warning(s)", errors, warnings);
+        return ExitCode::from(1);
+    }
+    if warnings > 0 {
+        println!("⚠️  No errors, {} warning(s)", warnings);
+    } else {
+        println!("✅ No problems found");
+    }
+    ExitCode::from(0)
+}
+
+/// 语义检查之外再按加载时的方式解析一次，捕获其余的格式错误（如未知字段）
+fn validate_content(content: &str) -> Vec<ConfigIssue> {
+    let mut issues = check_providers_json(content);
+    if !issues.iter().any(ConfigIssue::is_error) {
+        let loaded = serde_json::from_str::<ProvidersConfig>(content)
+            .map_err(anyhow::Error::from)
+            .and_then(|mut config| config.ensure_defaults_and_validate());
+        if let Err(err) = loaded {
+            issues.push(ConfigIssue {
+                severity: Severity::Error,
+                provider: None,
+                message: err.to_string(),
+            });
+        }
+    }
+    issues
+}
+
+/// 处理 `aiw provider remove <name> [--default <PROVIDER>] [-y]`
+pub fn handle_provider_remove(name: &str, new_default: Option<&str>, yes: bool) -> ExitCode {
+    match remove_provider(name, new_default, yes) {
+        Ok(()) => ExitCode::from(0),
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn remove_provider(name: &str, new_default: Option<&str>, yes: bool) -> Result<()> {
+    let mut manager = ProviderManager::new()?;
+    let config = manager.get_providers_config();
+    if config.get_provider(name).is_none() {
+        return Err(anyhow!("Provider '{}' not found", name));
+    }
+    let is_default = config.default_provider == name || config.default_for_type(name).is_some();
+    if is_default && new_default.is_none() {
+        return Err(anyhow!(
+            "'{}' is a default provider; pass --default <PROVIDER> to choose its replacement",
+            name
+        ));
+    }
+
+    if !yes {
+        let prompt = match new_default.filter(|_| is_default) {
+            Some(replacement) => format!(
+                "Remove provider '{}' and make '{}' the default?",
+                name, replacement
+            ),
+            None => format!("Remove provider '{}'?", name),
+        };
+        let confirmed = Confirm::new()
+            .with_prompt(prompt)
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            println!("Cancelled");
+            return Ok(());
+        }
+    }
+
+    manager.remove_provider_replacing_default(name, new_default)?;
+    println!("✅ Removed provider '{}'", name);
+    if is_default {
+        println!(
+            "   Default provider is now '{}'",
+            manager.default_provider_name()
+        );
+    }
+    Ok(())
+}
+
+/// `aiw provider edit` 的字段修改；全部为空时进入交互编辑
+#[derive(Debug, Default, Clone)]
+pub struct ProviderEdit {
+    pub scenario: Option<String>,
+    pub compatible_with: Option<String>,
+    pub priority: Option<String>,
+    pub env_vars: Vec<String>,
+    pub unset_env: Vec<String>,
+    pub enabled: Option<bool>,
+}
+
+impl ProviderEdit {
+    fn is_empty(&self) -> bool {
+        self.scenario.is_none()
+            && self.compatible_with.is_none()
+            && self.priority.is_none()
+            && self.env_vars.is_empty()
+            && self.unset_env.is_empty()
+            && self.enabled.is_none()
+    }
+
+    /// 把修改应用到 `provider`；字段格式错误时不做任何修改
+    pub fn apply(&self, provider: &mut Provider) -> Result<()> {
+        let compatible_with = self
+            .compatible_with
+            .as_deref()
+            .map(parse_compatible_with)
+            .transpose()?;
+        let priority = self.priority.as_deref().map(parse_priority).transpose()?;
+        let env = self
+            .env_vars
+            .iter()
+            .map(|pair| {
+                pair.split_once('=')
+                    .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+                    .ok_or_else(|| anyhow!("Invalid --env '{}': expected KEY=VALUE", pair))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(scenario) = &self.scenario {
+            let scenario = scenario.trim();
+            provider.scenario = (!scenario.is_empty()).then(|| scenario.to_string());
+        }
+        if let Some(compatible_with) = compatible_with {
+            provider.compatible_with = compatible_with;
+        }
+        if let Some(priority) = priority {
+            provider.priority = priority;
+        }
+        for key in &self.unset_env {
+            provider.env.remove(key);
+            provider.env_overrides.remove(key);
+        }
+        provider.env.extend(env);
+        if let Some(enabled) = self.enabled {
+            provider.enabled = enabled;
+            provider.disabled_until = None;
+        }
+        Ok(())
+    }
+}
+
+/// 处理 `aiw provider edit <name> [字段参数...]`
+pub fn handle_provider_edit(name: &str, edit: ProviderEdit) -> ExitCode {
+    match edit_provider(name, edit) {
+        Ok(()) => ExitCode::from(0),
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn edit_provider(name: &str, edit: ProviderEdit) -> Result<()> {
+    let mut manager = ProviderManager::new()?;
+    let mut provider = manager
+        .get_providers_config()
+        .get_provider(name)
+        .cloned()
+        .ok_or_else(|| anyhow!("Provider '{}' not found", name))?;
+
+    let edit = if edit.is_empty() {
+        if !std::io::stdin().is_terminal() {
+            return Err(anyhow!(
+                "Nothing to change; pass field flags (see 'aiw help provider') or run in a terminal"
+            ));
+        }
+        prompt_edit(name, &provider)?
+    } else {
+        edit
+    };
+
+    edit.apply(&mut provider)?;
+    manager.update_provider(name, provider)?;
+    println!("✅ Updated provider '{}'", name);
+    Ok(())
+}
+
+/// 逐项询问可编辑字段，回车保留当前值；环境变量值请用 --env 修改
+fn prompt_edit(name: &str, provider: &Provider) -> Result<ProviderEdit> {
+    println!("Editing provider '{}' (press Enter to keep a value)", name);
+    let scenario: String = Input::new()
+        .with_prompt("Scenario")
+        .with_initial_text(provider.scenario.clone().unwrap_or_default())
+        .allow_empty(true)
+        .interact_text()?;
+    let compatible_with: String = Input::new()
+        .with_prompt("Compatible with (comma-separated, or 'all')")
+        .with_initial_text(format_compatible_with(provider))
+        .validate_with(|value: &String| {
+            parse_compatible_with(value)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact_text()?;
+    let priority: String = Input::new()
+        .with_prompt("Auto priority (lower wins, or 'none')")
+        .with_initial_text(
+            provider
+                .priority
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "none".to_string()),
+        )
+        .validate_with(|value: &String| {
+            parse_priority(value).map(|_| ()).map_err(|e| e.to_string())
+        })
+        .interact_text()?;
+    let enabled = Confirm::new()
+        .with_prompt("Enabled?")
+        .default(provider.enabled)
+        .interact()?;
+
+    Ok(ProviderEdit {
+        scenario: Some(scenario),
+        compatible_with: Some(compatible_with),
+        priority: Some(priority),
+        enabled: Some(enabled),
+        ..ProviderEdit::default()
+    })
+}
+
+fn format_compatible_with(provider: &Provider) -> String {
+    match &provider.compatible_with {
+        None => "all".to_string(),
+        Some(types) => types
+            .iter()
+            .map(|ai_type| ai_type.to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    }
+}
+
+/// `all`（或空）表示兼容全部；其余值必须是 claude/codex/gemini/grok
+pub fn parse_compatible_with(value: &str) -> Result<Option<Vec<AiType>>> {
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("all") {
+        return Ok(None);
+    }
+    let mut types = Vec::new();
+    for item in value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        let ai_type = item
+            .parse::<AiType>()
+            .ok()
+            .filter(|ai_type| TESTABLE_AI_TYPES.contains(ai_type))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unknown AI type '{}' (expected claude, codex, gemini, grok or all)",
+                    item
+                )
+            })?;
+        if !types.contains(&ai_type) {
+            types.push(ai_type);
+        }
+    }
+    Ok(Some(types))
+}
+
+fn parse_priority(value: &str) -> Result<Option<u32>> {
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    value.parse::<u32>().map(Some).map_err(|_| {
+        anyhow!(
+            "Invalid priority '{}': expected a non-negative number or none",
+            value
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn validate_reports_load_errors_missed_by_the_checks() {
+        let content = r#"{
+            "providers": {"official": {"env": {}, "colour": "blue"}},
+            "default_provider": "official"
+        }"#;
+        let issues = validate_content(content);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error());
+        assert!(issues[0].message.contains("colour"), "{}", issues[0]);
+
+        let valid = r#"{"providers": {"official": {}}, "default_provider": "official"}"#;
+        assert!(validate_content(valid).is_empty());
+    }
+
+    #[test]
+    fn compatible_with_accepts_cli_types_only() {
+        assert_eq!(parse_compatible_with(" all ").unwrap(), None);
+        assert_eq!(
+            parse_compatible_with("Claude, codex,claude").unwrap(),
+            Some(vec![AiType::Claude, AiType::Codex])
+        );
+        assert!(parse_compatible_with("claude,auto").is_err());
+        assert!(parse_compatible_with("cursor").is_err());
+    }
+
+    #[test]
+    fn edit_applies_fields_and_leaves_provider_untouched_on_error() {
+        let mut provider = Provider {
+            enabled: false,
+            scenario: Some("old".to_string()),
+            compatible_with: None,
+            env: HashMap::from([("OLD_KEY".to_string(), "x".to_string())]),
+            disabled_until: Some(1),
+            env_overrides: HashMap::new(),
+            priority: Some(3),
+        };
+
+        let bad = ProviderEdit {
+            scenario: Some("new".to_string()),
+            env_vars: vec!["NOVALUE".to_string()],
+            ..ProviderEdit::default()
+        };
+        assert!(bad.apply(&mut provider).is_err());
+        assert_eq!(provider.scenario.as_deref(), Some("old"));
+
+        let edit = ProviderEdit {
+            scenario: Some(" ".to_string()),
+            compatible_with: Some("gemini".to_string()),
+            priority: Some("none".to_string()),
+            env_vars: vec!["API_KEY=a=b".to_string()],
+            unset_env: vec!["OLD_KEY".to_string()],
+            enabled: Some(true),
+        };
+        edit.apply(&mut provider).unwrap();
+        assert_eq!(provider.scenario, None);
+        assert_eq!(provider.compatible_with, Some(vec![AiType::Gemini]));
+        assert_eq!(provider.priority, None);
+        assert_eq!(provider.env.get("API_KEY").map(String::as_str), Some("a=b"));
+        assert!(!provider.env.contains_key("OLD_KEY"));
+        assert!(provider.enabled);
+        assert_eq!(provider.disabled_until, None);
+    }
+}
@@ -0,0 +1,120 @@
+//! `aiw registry reset`：修复共享内存任务注册表
+//!
+//! 每个 CLI 进程使用独立的 `{pid}_task` 共享内存段。进程崩溃后可能留下锁残留或
+//! 半写入的段，导致后续连接失败；这里负责备份、重建这些段，并清理进程早已退出
+//! 却仍标记为运行中的条目。
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use crate::config::AUTH_DIRECTORY;
+use crate::platform;
+use crate::storage::{RepairReport, SharedMemoryStorage};
+use crate::unified_registry::Registry;
+use crate::utils::config_paths::aiw_config_dir;
+
+const BACKUP_DIRECTORY: &str = "registry-backups";
+
+/// 损坏注册表的备份目录：`~/.aiw/registry-backups`
+pub fn backup_dir() -> PathBuf {
+    aiw_config_dir()
+        .unwrap_or_else(|| std::env::temp_dir().join(AUTH_DIRECTORY))
+        .join(BACKUP_DIRECTORY)
+}
+
+fn print_report(report: &RepairReport) {
+    match (&report.corruption, report.reinitialized) {
+        (None, false) => println!("  ✅ {} is healthy", report.namespace),
+        (corruption, _) => {
+            let reason = corruption.as_deref().unwrap_or("forced reset");
+            println!("  🔧 {} reinitialized ({})", report.namespace, reason);
+            if let Some(path) = &report.backup_path {
+                println!("     backup: {}", path.display());
+            }
+            println!(
+                "     restored {} entr(ies), dropped {} unreadable",
+                report.restored_entries, report.dropped_entries
+            );
+        }
+    }
+}
+
+/// 检查并（可选）删除进程已退出的运行中条目，返回删除失败时的错误数
+fn handle_dead_entries(namespace: &str, purge: bool) -> usize {
+    let registry = match Registry::shared_memory_with_namespace(namespace.to_string()) {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("  ❌ {}: {}", namespace, err);
+            return 1;
+        }
+    };
+    let dead = match registry.dead_running_entries(platform::process_alive) {
+        Ok(dead) => dead,
+        Err(err) => {
+            eprintln!("  ❌ {}: {}", namespace, err);
+            return 1;
+        }
+    };
+    if dead.is_empty() {
+        return 0;
+    }
+
+    let pids: Vec<u32> = dead.iter().map(|entry| entry.pid).collect();
+    if !purge {
+        println!(
+            "  ⚠️  {} has {} running entr(ies) whose process is gone: {:?}",
+            namespace,
+            pids.len(),
+            pids
+        );
+        println!("     re-run with --purge-dead to remove them");
+        return 0;
+    }
+
+    match registry.remove_entries(&pids) {
+        Ok(removed) => {
+            println!("  🧹 {}: purged {} dead entr(ies)", namespace, removed);
+            0
+        }
+        Err(err) => {
+            eprintln!("  ❌ {}: {}", namespace, err);
+            1
+        }
+    }
+}
+
+/// 处理 `aiw registry reset [--pid <PID>] [--purge-dead]`
+///
+/// 指定 `--pid` 时强制重建该进程的注册表；否则只修复检测到损坏的注册表，
+/// 避免把仍在使用的共享内存段从正在运行的进程下面删掉。
+pub fn handle_registry_reset(pid: Option<u32>, purge_dead: bool) -> ExitCode {
+    let namespaces = match pid {
+        Some(pid) => vec![format!("{}_task", pid)],
+        None => SharedMemoryStorage::list_namespaces(),
+    };
+    if namespaces.is_empty() {
+        println!("No task registries found. Use --pid <PID> to target a specific process.");
+        return ExitCode::from(0);
+    }
+
+    let backup_dir = backup_dir();
+    let mut failures = 0;
+    println!("🔍 Checking {} task registr(ies)...", namespaces.len());
+    for namespace in &namespaces {
+        match Registry::<SharedMemoryStorage>::repair(namespace, pid.is_some(), &backup_dir) {
+            Ok(report) => print_report(&report),
+            Err(err) => {
+                failures += 1;
+                eprintln!("  ❌ {}: {}", namespace, err);
+                continue;
+            }
+        }
+        failures += handle_dead_entries(namespace, purge_dead);
+    }
+
+    if failures > 0 {
+        eprintln!("❌ {} registr(ies) could not be repaired", failures);
+        return ExitCode::from(1);
+    }
+    ExitCode::from(0)
+}
@@ -0,0 +1,75 @@
+//! `aiw routing config`：查看和修改 MCP 路由/决策配置
+//!
+//! 配置写入 `~/.aiw/mcp.json` 的 `routing` 段，`IntelligentRouter` 启动时优先于
+//! `OPENAI_*` / `AIW_EMBEDDING_*` 环境变量使用。显示时 token 一律打码。
+
+use std::process::ExitCode;
+
+use anyhow::Result;
+
+use crate::commands::mcp::McpConfigEditor;
+use crate::commands::parser::RoutingConfigAction;
+use crate::mcp_routing::config::{is_token_reference, ResolvedRoutingConfig};
+
+/// 处理 `aiw routing config [get|set|unset]`
+pub fn handle_routing_config(action: Option<RoutingConfigAction>) -> ExitCode {
+    match run(action) {
+        Ok(()) => ExitCode::from(0),
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn run(action: Option<RoutingConfigAction>) -> Result<()> {
+    let editor = McpConfigEditor::new()?;
+    let mut config = editor.read()?;
+
+    match action {
+        None => {
+            println!("Routing config ({})", editor.config_path().display());
+            println!("Precedence: mcp.json > environment > config.json (embedding only) > default");
+            println!();
+            print_rows(&config.routing.resolve(), None);
+            Ok(())
+        }
+        Some(RoutingConfigAction::Get { key }) => {
+            // 校验键名
+            config.routing.get(&key)?;
+            print_rows(&config.routing.resolve(), Some(&key));
+            Ok(())
+        }
+        Some(RoutingConfigAction::Set { key, value }) => {
+            config.routing.set(&key, &value)?;
+            editor.write(&config)?;
+            println!("✅ Set {} in {}", key, editor.config_path().display());
+            if key == "llm_token" && !is_token_reference(&value) {
+                println!("⚠️  The token is stored in plain text; restrict access to mcp.json");
+                println!("   Store a reference instead: ${{env:NAME}} or ${{file:/path/to/token}}");
+            }
+            print_rows(&config.routing.resolve(), Some(&key));
+            Ok(())
+        }
+        Some(RoutingConfigAction::Unset { key }) => {
+            config.routing.unset(&key)?;
+            editor.write(&config)?;
+            println!(
+                "🗑️  Removed {} from {}",
+                key,
+                editor.config_path().display()
+            );
+            print_rows(&config.routing.resolve(), Some(&key));
+            Ok(())
+        }
+    }
+}
+
+fn print_rows(resolved: &ResolvedRoutingConfig, only: Option<&str>) {
+    for (key, value, source) in resolved.rows() {
+        if only.is_some_and(|only| only != key) {
+            continue;
+        }
+        println!("  {:<22} {:<40} ({})", key, value, source);
+    }
+}
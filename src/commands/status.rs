@@ -0,0 +1,166 @@
+//! `aiw status --plain` / `aiw status --watch`
+//!
+//! 不进入 TUI 的任务状态表，适合 SSH、管道和 CI。数据来源与 TUI 状态页相同
+//! （当前进程命名空间的 CLI 任务注册表）。终端上原地刷新；输出被重定向时
+//! 逐次追加快照，不写任何控制字符。
+
+use std::io::{self, IsTerminal, Write};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use prettytable::{format, Cell, Row, Table};
+
+use crate::registry_factory::create_cli_registry;
+use crate::storage::RegistryEntry;
+use crate::task_record::{format_duration, TaskStatus};
+
+/// 与 TUI 状态页一致的默认刷新间隔
+pub const DEFAULT_WATCH_INTERVAL_SECS: u64 = 2;
+
+/// 把任务条目渲染成紧凑表格（按 PID 排序）
+pub fn render_status_table(entries: &[RegistryEntry], now: DateTime<Utc>) -> String {
+    if entries.is_empty() {
+        return "No tasks.\n".to_string();
+    }
+
+    let mut entries: Vec<&RegistryEntry> = entries.iter().collect();
+    entries.sort_by_key(|entry| entry.pid);
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_CLEAN);
+    table.set_titles(Row::new(
+        ["ID", "AI", "STATUS", "PID", "ELAPSED", "EXIT"]
+            .iter()
+            .map(|title| Cell::new(title))
+            .collect(),
+    ));
+
+    for entry in entries {
+        let record = &entry.record;
+        let id = record
+            .task_id
+            .as_deref()
+            .map(|id| id.get(..8).unwrap_or(id))
+            .unwrap_or("-");
+        let status = match record.status {
+            TaskStatus::Running => "running",
+            TaskStatus::CompletedButUnread => "completed",
+        };
+        let exit = record
+            .exit_code
+            .map(|code| code.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        table.add_row(Row::new(vec![
+            Cell::new(id),
+            Cell::new(record.ai_type.as_deref().unwrap_or("-")),
+            Cell::new(status),
+            Cell::new(&entry.pid.to_string()),
+            Cell::new(&format_duration(record.duration(now))),
+            Cell::new(&exit),
+        ]));
+    }
+
+    table.to_string()
+}
+
+/// 处理 `aiw status --plain [--once] [--interval N]`
+pub async fn handle_status_plain(once: bool, interval: Duration) -> ExitCode {
+    let registry = match create_cli_registry() {
+        Ok(registry) => registry,
+        Err(err) => {
+            eprintln!("❌ Failed to connect to task registry: {}", err);
+            return ExitCode::from(1);
+        }
+    };
+
+    let in_place = io::stdout().is_terminal();
+    let mut previous_lines = 0;
+    loop {
+        let entries = match registry.entries() {
+            Ok(entries) => entries,
+            Err(err) => {
+                eprintln!("❌ Failed to read task registry: {}", err);
+                return ExitCode::from(1);
+            }
+        };
+        let now = Utc::now();
+        let snapshot = render_status_table(&entries, now);
+
+        let mut stdout = io::stdout().lock();
+        if once {
+            let _ = write!(stdout, "{}", snapshot);
+            let _ = stdout.flush();
+            return ExitCode::from(0);
+        }
+
+        let header = format!(
+            "{} task(s) at {} (every {}s, Ctrl+C to stop)\n",
+            entries.len(),
+            now.with_timezone(&chrono::Local).format("%H:%M:%S"),
+            interval.as_secs()
+        );
+        if in_place && previous_lines > 0 {
+            // 回到上一帧开头并清除到屏幕末尾，不使用备用屏幕
+            let _ = write!(stdout, "\x1b[{}F\x1b[J", previous_lines);
+        } else if previous_lines > 0 {
+            let _ = writeln!(stdout);
+        }
+        let _ = write!(stdout, "{}{}", header, snapshot);
+        let _ = stdout.flush();
+        drop(stdout);
+        previous_lines = header.lines().count() + snapshot.lines().count();
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => return ExitCode::from(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_record::TaskRecord;
+    use chrono::Duration as ChronoDuration;
+
+    fn entry(pid: u32, ai_type: &str, started_at: DateTime<Utc>) -> RegistryEntry {
+        let mut record = TaskRecord::new(
+            started_at,
+            format!("log-{pid}"),
+            format!("/tmp/log-{pid}.log"),
+            None,
+        );
+        record.ai_type = Some(ai_type.to_string());
+        record.task_id = Some("0123456789abcdef".to_string());
+        RegistryEntry {
+            pid,
+            key: pid.to_string(),
+            record,
+        }
+    }
+
+    #[test]
+    fn status_table_lists_tasks_by_pid_with_age_and_exit_code() {
+        let now = Utc::now();
+        let mut finished = entry(200, "codex", now - ChronoDuration::seconds(7_200));
+        finished.record.status = TaskStatus::CompletedButUnread;
+        finished.record.completed_at = Some(now - ChronoDuration::seconds(3_500));
+        finished.record.exit_code = Some(2);
+        let running = entry(100, "claude", now - ChronoDuration::seconds(42));
+
+        let table = render_status_table(&[finished, running], now);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].contains("ID") && lines[0].contains("EXIT"));
+        assert!(lines[1].contains("01234567") && lines[1].contains("claude"));
+        assert!(lines[1].contains("running") && lines[1].contains("42s"));
+        assert!(lines[2].contains("codex") && lines[2].contains("completed"));
+        assert!(lines[2].contains("1h1m") && lines[2].trim_end().ends_with('2'));
+    }
+
+    #[test]
+    fn status_table_reports_empty_registry() {
+        assert_eq!(render_status_table(&[], Utc::now()), "No tasks.\n");
+    }
+}
@@ -0,0 +1,41 @@
+//! `aiw sync`：通过 Google Drive 同步 AI CLI 配置目录
+
+use std::process::ExitCode;
+
+use crate::commands::parser::{SyncAction, SyncConfigAction};
+use crate::sync::error::SyncResult;
+use crate::sync::sync_command::SyncCommand;
+
+/// 处理 `aiw sync push|pull|status|config`
+pub async fn handle_sync_action(action: SyncAction) -> ExitCode {
+    match run(action).await {
+        Ok(code) => ExitCode::from(code.clamp(0, 255) as u8),
+        Err(err) => {
+            eprintln!("❌ {}", err);
+            ExitCode::from(1)
+        }
+    }
+}
+
+async fn run(action: SyncAction) -> SyncResult<i32> {
+    let mut command = SyncCommand::new()?;
+
+    match action {
+        SyncAction::Push {
+            name: Some(name), ..
+        } => command.execute_push(Some(name)).await,
+        SyncAction::Push { directories, .. } => {
+            command.execute_push_directories(&directories).await
+        }
+        SyncAction::Pull {
+            name: Some(name), ..
+        } => command.execute_pull(Some(name)).await,
+        SyncAction::Pull {
+            directories, force, ..
+        } => command.execute_pull_directories(&directories, force).await,
+        SyncAction::Status => command.execute_status().await,
+        SyncAction::Config {
+            action: SyncConfigAction::ListDirs,
+        } => command.execute_list_dirs(),
+    }
+}
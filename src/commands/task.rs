@@ -0,0 +1,230 @@
+//! `aiw task export` / `aiw task attach`
+//!
+//! 在本机所有 CLI 任务注册表中按任务 ID、任务名、PID 或日志 ID 查找任务。
+//! 诊断包内容见 [`crate::task_export`]。MCP 启动的任务只存在于 MCP 进程内，
+//! 需通过 MCP 工具 `export_task` / `manage_task` 操作。
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use crate::platform;
+use crate::registry_factory::CliRegistry;
+use crate::storage::RegistryEntry;
+use crate::supervisor::read_log_from;
+use crate::task_export::{default_output_path, export_task};
+use crate::task_record::TaskStatus;
+use crate::utils::log_filter::{LineFilter, LineFilterStream};
+use crate::wait_mode::{collect_entries, entry_matches, open_all_cli_registries};
+
+/// attach 时轮询日志与任务状态的间隔
+const ATTACH_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 按任务 ID、PID 或日志 ID 找到唯一的任务；找不到或不唯一时打印原因
+fn find_task(registries: &[CliRegistry], task_id: &str) -> Option<RegistryEntry> {
+    let entries = match collect_entries(registries) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("❌ Failed to read task registries: {}", err);
+            return None;
+        }
+    };
+
+    let mut matches: Vec<_> = entries
+        .into_iter()
+        .filter(|entry| entry_matches(entry, task_id))
+        .collect();
+    match matches.len() {
+        1 => matches.pop(),
+        0 => {
+            eprintln!("❌ No task matches '{}'", task_id);
+            None
+        }
+        _ => {
+            let pids: Vec<u32> = matches.iter().map(|entry| entry.pid).collect();
+            eprintln!(
+                "❌ '{}' matches {} tasks (PIDs {:?}); use a more specific ID",
+                task_id,
+                matches.len(),
+                pids
+            );
+            None
+        }
+    }
+}
+
+fn open_registries() -> Option<Vec<CliRegistry>> {
+    match open_all_cli_registries() {
+        Ok(registries) => Some(registries),
+        Err(err) => {
+            eprintln!("❌ Failed to read task registries: {}", err);
+            None
+        }
+    }
+}
+
+/// 处理 `aiw task export <TASK_ID> [OUTPUT]`
+pub fn handle_task_export(task_id: &str, output: Option<PathBuf>) -> ExitCode {
+    let Some(registries) = open_registries() else {
+        return ExitCode::from(1);
+    };
+    let Some(entry) = find_task(&registries, task_id) else {
+        return ExitCode::from(1);
+    };
+
+    let output = match output {
+        Some(path) => path,
+        None => match default_output_path(entry.pid, &entry.record) {
+            Ok(path) => path,
+            Err(err) => {
+                eprintln!("❌ {}", err);
+                return ExitCode::from(1);
+            }
+        },
+    };
+
+    match export_task(entry.pid, &entry.record, &output) {
+        Ok(summary) => {
+            println!(
+                "📦 Exported task {} to {} ({} bytes)",
+                entry.pid, summary.path, summary.compressed_bytes
+            );
+            for name in &summary.entries {
+                println!("   {}", name);
+            }
+            println!("Environment variable values are redacted; review the log before sharing.");
+            ExitCode::from(0)
+        }
+        Err(err) => {
+            eprintln!("❌ Failed to export task: {:#}", err);
+            ExitCode::from(1)
+        }
+    }
+}
+
+/// 处理 `aiw task attach <TASK_ID>`：像 `tail -f` 一样跟随任务日志
+///
+/// 任务进入终态后输出剩余日志和最终状态行；Ctrl-C 只断开跟随，任务继续运行。
+/// 指定 `--grep` 时只输出匹配的行（及上下文）。
+pub async fn handle_task_attach(
+    task_id: &str,
+    grep: Option<&str>,
+    regex: bool,
+    context: usize,
+) -> ExitCode {
+    let filter = match grep.map(|pattern| LineFilter::new(pattern, regex, context)) {
+        Some(Ok(filter)) => Some(filter),
+        Some(Err(err)) => {
+            eprintln!("❌ {}", err);
+            return ExitCode::from(1);
+        }
+        None => None,
+    };
+    let Some(registries) = open_registries() else {
+        return ExitCode::from(1);
+    };
+    let Some(entry) = find_task(&registries, task_id) else {
+        return ExitCode::from(1);
+    };
+
+    let pid = entry.pid;
+    println!(
+        "📎 Attached to task {} (log: {}); Ctrl-C to detach",
+        pid, entry.record.log_path
+    );
+
+    tokio::select! {
+        code = follow_task(&registries, entry, filter.as_ref().map(LineFilter::stream)) => code,
+        _ = tokio::signal::ctrl_c() => {
+            println!();
+            println!("Detached from task {}; it keeps running in the background.", pid);
+            ExitCode::from(0)
+        }
+    }
+}
+
+async fn follow_task(
+    registries: &[CliRegistry],
+    entry: RegistryEntry,
+    mut filter: Option<LineFilterStream>,
+) -> ExitCode {
+    let log_path = PathBuf::from(&entry.record.log_path);
+    let mut offset = 0;
+    let mut last_seen = entry.record;
+
+    loop {
+        // 先对账再读日志，保证终态前写入的内容都已输出
+        let now = Utc::now();
+        for registry in registries {
+            if let Err(err) = registry.reconcile_tasks(now, platform::process_alive) {
+                eprintln!("⚠️  Failed to reconcile task registry: {}", err);
+            }
+        }
+        let current = collect_entries(registries)
+            .ok()
+            .and_then(|entries| entries.into_iter().find(|e| e.pid == entry.pid));
+        let finished = match current {
+            Some(current) => {
+                let done = current.record.status != TaskStatus::Running;
+                last_seen = current.record;
+                done
+            }
+            // 条目已被其他等待者读取并移除
+            None => true,
+        };
+
+        offset = print_new_log(&log_path, offset, filter.as_mut());
+
+        if finished {
+            if let Some(filter) = filter.as_mut() {
+                write_stdout(&filter.finish());
+            }
+            println!("{}", final_status_line(entry.pid, &last_seen));
+            return match last_seen.exit_code {
+                Some(0) => ExitCode::from(0),
+                _ => ExitCode::from(1),
+            };
+        }
+        tokio::time::sleep(ATTACH_POLL_INTERVAL).await;
+    }
+}
+
+/// 输出新增日志（经过 `--grep` 过滤）并返回新的偏移；日志暂不可读时保持原偏移
+fn print_new_log(log_path: &Path, offset: u64, filter: Option<&mut LineFilterStream>) -> u64 {
+    match read_log_from(log_path, offset) {
+        Ok((text, next)) => {
+            match filter {
+                Some(filter) => write_stdout(&filter.push(&text)),
+                None => write_stdout(&text),
+            }
+            next
+        }
+        Err(_) => offset,
+    }
+}
+
+fn write_stdout(text: &str) {
+    if !text.is_empty() {
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(text.as_bytes());
+        let _ = stdout.flush();
+    }
+}
+
+fn final_status_line(pid: u32, record: &crate::task_record::TaskRecord) -> String {
+    match record.exit_code {
+        Some(0) => format!("✅ Task {} completed (exit code 0)", pid),
+        Some(code) => format!("❌ Task {} failed (exit code {})", pid, code),
+        None => match record.terminating_signal() {
+            Some(signal) => format!("❌ Task {} was terminated by {}", pid, signal),
+            None => format!(
+                "⚠️  Task {} ended without an exit code ({})",
+                pid,
+                record.cleanup_reason.as_deref().unwrap_or("unknown")
+            ),
+        },
+    }
+}
@@ -7,12 +7,48 @@ pub const SHARED_NAMESPACE: &str = "agentic-task";
 // Shared memory size for task registry (16MB)
 // Supports approximately 4000+ TaskRecord entries with overhead
 // Compatible with both 32-bit and 64-bit Windows systems
+// Minimum region size; raising registry_max_entries grows the region beyond it
 pub const SHARED_MEMORY_SIZE: usize = 16 * 1024 * 1024;
 
 pub const WAIT_INTERVAL_ENV: &str = "AGENTIC_WARDEN_WAIT_INTERVAL_SEC";
 pub const LEGACY_WAIT_INTERVAL_ENV: &str = "CODEX_WORKER_WAIT_INTERVAL_SEC";
+pub const REGISTRATION_TIMEOUT_ENV: &str = "AGENTIC_WARDEN_REGISTRATION_TIMEOUT_SEC";
+pub const TASK_RETENTION_ENV: &str = "AGENTIC_WARDEN_TASK_RETENTION_SEC";
+pub const MAX_COMPLETED_TASKS_ENV: &str = "AGENTIC_WARDEN_MAX_COMPLETED_TASKS";
+pub const MAX_CONCURRENT_TASKS_ENV: &str = "AGENTIC_WARDEN_MAX_CONCURRENT_TASKS";
+pub const MAX_CONCURRENT_TASKS_PER_AI_ENV: &str = "AGENTIC_WARDEN_MAX_CONCURRENT_TASKS_PER_AI";
+pub const MCP_TRANSPORT_ENV: &str = "AGENTIC_WARDEN_MCP_TRANSPORT";
+pub const MCP_BIND_ENV: &str = "AGENTIC_WARDEN_MCP_BIND";
 pub const DEBUG_ENV: &str = "AGENTIC_WARDEN_DEBUG";
 pub const LEGACY_DEBUG_ENV: &str = "CODEX_WORKER_DEBUG";
+/// `json` 切换为结构化 JSON 日志，其他值或未设置时使用默认的可读格式
+pub const LOG_FORMAT_ENV: &str = "AIW_LOG_FORMAT";
+/// 日志过滤规则（`error`/`warn`/`info`/`debug` 或 `aiw::mcp_routing=debug` 这类 directive），优先于 `RUST_LOG`
+pub const LOG_ENV: &str = "AIW_LOG";
+/// 路由向量化后端：`auto`（默认）、`fastembed`、`local`、`mock`
+pub const EMBEDDING_BACKEND_ENV: &str = "AIW_EMBEDDING_BACKEND";
+/// 本地 ONNX 模型目录（含 model.onnx 与 tokenizer 文件），离线环境使用
+pub const EMBEDDING_MODEL_DIR_ENV: &str = "AIW_EMBEDDING_MODEL_DIR";
+/// fastembed 模型下载缓存目录（默认为 fastembed 自带的 `.fastembed_cache`）
+pub const FASTEMBED_CACHE_ENV: &str = "AIW_FASTEMBED_CACHE";
+/// Hugging Face 镜像地址（如 `https://hf-mirror.com`），用于下载 fastembed 模型
+pub const FASTEMBED_MIRROR_ENV: &str = "AIW_FASTEMBED_MIRROR";
+/// 是否记录路由历史并据此加权（默认开启），`false`/`0`/`off` 关闭
+pub const ROUTING_HISTORY_ENV: &str = "AIW_ROUTING_HISTORY";
+/// 代理工具参数不符合 input_schema 时的处理：`reject`（默认）、`warn`、`off`
+pub const ARGUMENT_VALIDATION_ENV: &str = "AIW_ARGUMENT_VALIDATION";
+/// `aiw mcp search` 结果磁盘缓存的有效期（秒），默认 600
+pub const MCP_SEARCH_CACHE_TTL_ENV: &str = "AIW_MCP_SEARCH_CACHE_TTL";
+/// 替代 `~/.aiw` 的配置根目录（也可用 `aiw --config-dir <DIR>` 设置）
+pub const CONFIG_DIR_ENV: &str = "AIW_CONFIG_DIR";
+/// 强制内置角色语言（如 `en`、`zh-CN`），优先于系统 locale
+pub const LANG_ENV: &str = "AIW_LANG";
+/// 共享内存任务注册表的最大条目数，优先于 config.json 的 `registry_max_entries`
+pub const REGISTRY_MAX_ENTRIES_ENV: &str = "AIW_REGISTRY_MAX_ENTRIES";
+/// 单条任务记录序列化后的最大字节数，优先于 config.json 的 `registry_max_value_bytes`
+pub const REGISTRY_MAX_VALUE_BYTES_ENV: &str = "AIW_REGISTRY_MAX_VALUE_BYTES";
+/// 设为 `1`/`true`/`on` 时，MCP server 把每次 initialize/list_tools/call_tool 记录到 `~/.aiw/logs/mcp_audit.jsonl`
+pub const MCP_AUDIT_ENV: &str = "AIW_MCP_AUDIT";
 
 // Common constants used across modules
 pub const AUTH_DIRECTORY: &str = ".aiw";
@@ -21,6 +57,29 @@ pub const AUTH_FILE_NAME: &str = "auth.json";
 pub const MAX_RECORD_AGE: Duration = Duration::from_secs(12 * 60 * 60);
 pub const WAIT_INTERVAL_DEFAULT: Duration = Duration::from_secs(30);
 pub const MAX_WAIT_DURATION: Duration = Duration::from_secs(24 * 60 * 60);
+/// MCP start_task 等待子进程写入 registry 的默认时长
+pub const REGISTRATION_TIMEOUT_DEFAULT: Duration = Duration::from_secs(3);
+/// 已结束任务在 MCP registry 中的默认保留时长
+pub const TASK_RETENTION_DEFAULT: Duration = Duration::from_secs(24 * 60 * 60);
+/// MCP registry 中最多保留的已结束任务数
+pub const MAX_COMPLETED_TASKS_DEFAULT: usize = 200;
+/// MCP start_task 同时运行的任务上限，超出的启动请求排队等待
+pub const MAX_CONCURRENT_TASKS_DEFAULT: usize = 16;
+/// 共享内存任务注册表默认最多容纳的条目数
+pub const REGISTRY_MAX_ENTRIES_DEFAULT: usize = 4096;
+/// 单条任务记录（JSON）默认的最大字节数
+pub const REGISTRY_MAX_VALUE_BYTES_DEFAULT: usize = 64 * 1024;
+/// 按条目数估算共享内存段大小时，每个条目预留的字节数
+pub const REGISTRY_BYTES_PER_ENTRY: usize = 4096;
+/// MCP server 后台对账（回收进程已消失的任务）的间隔
+pub const TASK_RECONCILE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// MCP SSE 传输默认监听地址（仅本机回环，避免暴露到局域网）
+pub const MCP_BIND_DEFAULT: &str = "127.0.0.1:8765";
+/// MCP server 退出时等待在途任务进程组退出的时长，超时后 SIGKILL
+pub const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
+/// config.json `task_hooks` 中单个钩子的默认最长执行时间
+pub const TASK_HOOK_TIMEOUT_DEFAULT: Duration = Duration::from_secs(10);
 
 /// Patch 配置：max-token patch 的可配置参数
 ///
@@ -61,8 +120,9 @@ impl Default for PatchConfig {
 impl PatchConfig {
     /// 配置文件路径：`~/.aiw/patch.json`
     fn config_path() -> std::path::PathBuf {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        std::path::PathBuf::from(home).join(AUTH_DIRECTORY).join("patch.json")
+        crate::utils::config_paths::aiw_config_dir()
+            .unwrap_or_else(|| std::path::PathBuf::from(".").join(AUTH_DIRECTORY))
+            .join("patch.json")
     }
 
     /// 从磁盘加载配置；不存在则返回默认值
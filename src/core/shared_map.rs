@@ -1,8 +1,10 @@
 use crate::error::{AgenticResult, AgenticWardenError};
+use crate::utils::file_lock::{lock_exclusive, FileLock};
 use raw_sync::locks::{LockImpl, LockInit, Mutex};
 use shared_hashmap::{SharedMemoryContents, SharedMemoryHashMap};
 use shared_memory::{Shmem, ShmemConf, ShmemError};
 use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[repr(C)]
@@ -23,6 +25,8 @@ pub(crate) enum SharedMapError {
     LockInit(String),
     #[error("shared lock access failed: {0}")]
     LockGuard(String),
+    #[error("failed to write shared memory backup: {0}")]
+    Backup(#[from] std::io::Error),
 }
 
 /// Internal representation that mirrors SharedMemoryHashMap's structure
@@ -66,6 +70,91 @@ pub fn open_or_create(
     }
 }
 
+/// 只打开已存在的共享内存段；不存在时返回 `Ok(None)`，不会顺带创建
+pub(crate) fn open_if_exists(
+    namespace: &str,
+    size: usize,
+) -> Result<Option<SharedMemoryHashMap<String, String>>, SharedMapError> {
+    match open_existing(namespace, size) {
+        Ok(map) => Ok(Some(map)),
+        Err(SharedMapError::Shmem(ShmemError::MapOpenFailed(_)))
+        | Err(SharedMapError::Shmem(ShmemError::LinkDoesNotExist))
+        | Err(SharedMapError::Shmem(ShmemError::NoLinkOrOsId)) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// 把共享内存段原样备份到 `backup_path` 后删除它（注册表修复使用）
+///
+/// 返回 `Ok(false)` 表示该命名空间不存在，无需处理。
+pub(crate) fn backup_and_remove(
+    namespace: &str,
+    size: usize,
+    backup_path: &Path,
+) -> Result<bool, SharedMapError> {
+    let mut shm = match ShmemConf::new().os_id(namespace).size(size).open() {
+        Ok(shm) => shm,
+        Err(ShmemError::MapOpenFailed(_))
+        | Err(ShmemError::LinkDoesNotExist)
+        | Err(ShmemError::NoLinkOrOsId) => return Ok(false),
+        Err(err) => return Err(err.into()),
+    };
+
+    // SAFETY: 只读复制映射区域；修复时该段已不可用，内容是否一致不影响安全性
+    let bytes = unsafe { shm.as_slice() };
+    std::fs::write(backup_path, bytes)?;
+
+    // 成为 owner 后 drop 时解除链接，下次 open_or_create 会重新初始化
+    let _ = shm.set_owner(true);
+    Ok(true)
+}
+
+/// 获取命名空间级的跨进程写锁（阻塞等待），drop 时释放
+///
+/// 共享内存 map 只保证单次 get/insert 原子；标记完成、对账这类先读后写的更新
+/// 必须在整个读改写期间持有此锁，否则多个 warden 进程会互相覆盖对方的修改。
+/// 锁文件为 [`lock_dir`] 下的 `<namespace>.lock`。
+pub(crate) fn lock_namespace(namespace: &str) -> std::io::Result<FileLock> {
+    lock_exclusive(&lock_dir()?.join(format!("{namespace}.lock")))
+}
+
+/// 当前用户私有的锁目录
+///
+/// 优先 `$XDG_RUNTIME_DIR/aiw/locks`，否则 `<临时目录>/aiw-<uid>/locks`。不跟随
+/// `AIW_CONFIG_DIR`：共享内存是整机范围的，同一用户不同配置目录的进程也必须互斥。
+/// 回退目录位于全局可写的临时目录中，创建为 0700 并拒绝属于其他用户的已有目录，
+/// 防止他人预先占位或持有锁。
+fn lock_dir() -> std::io::Result<PathBuf> {
+    if let Some(runtime) = std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(runtime).join("aiw").join("locks"));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+        // SAFETY: getuid 没有失败路径
+        let uid = unsafe { libc::getuid() };
+        let root = std::env::temp_dir().join(format!("aiw-{uid}"));
+        match std::fs::DirBuilder::new().mode(0o700).create(&root) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(err),
+        }
+        let metadata = std::fs::symlink_metadata(&root)?;
+        if !metadata.is_dir() || metadata.uid() != uid {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("{} is not a directory owned by uid {uid}", root.display()),
+            ));
+        }
+        Ok(root.join("locks"))
+    }
+    #[cfg(not(unix))]
+    {
+        Ok(std::env::temp_dir().join("aiw").join("locks"))
+    }
+}
+
 fn open_existing(
     namespace: &str,
     size: usize,
@@ -248,5 +337,52 @@ fn to_agentic(err: SharedMapError, namespace: &str) -> AgenticWardenError {
             operation: Some("shared_memory_lock::guard".to_string()),
             source: None,
         },
+        SharedMapError::Backup(source) => AgenticWardenError::Filesystem {
+            message: format!("Failed to back up shared memory ({namespace}): {source}"),
+            path: namespace.to_string(),
+            source: Some(Box::new(source)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn lock_dir_prefers_xdg_runtime_dir() {
+        let previous = std::env::var_os("XDG_RUNTIME_DIR");
+        let runtime = tempfile::tempdir().unwrap();
+        std::env::set_var("XDG_RUNTIME_DIR", runtime.path());
+
+        let dir = lock_dir();
+
+        match previous {
+            Some(value) => std::env::set_var("XDG_RUNTIME_DIR", value),
+            None => std::env::remove_var("XDG_RUNTIME_DIR"),
+        }
+        assert_eq!(dir.unwrap(), runtime.path().join("aiw").join("locks"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    fn lock_dir_fallback_is_private_to_the_user() {
+        use std::os::unix::fs::MetadataExt;
+
+        let previous = std::env::var_os("XDG_RUNTIME_DIR");
+        std::env::remove_var("XDG_RUNTIME_DIR");
+
+        let dir = lock_dir();
+
+        if let Some(value) = previous {
+            std::env::set_var("XDG_RUNTIME_DIR", value);
+        }
+        let dir = dir.unwrap();
+        let root = std::fs::metadata(dir.parent().unwrap()).unwrap();
+        assert_eq!(root.uid(), unsafe { libc::getuid() });
+        assert_eq!(root.mode() & 0o077, 0);
     }
 }
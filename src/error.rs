@@ -508,6 +508,45 @@ pub enum RegistryError {
     TaskNotFound(u32),
     #[error("process tree error: {0}")]
     ProcessTree(String),
+    #[error("task registry '{namespace}' is corrupted: {detail}. Run `{repair_command}` to back it up and reinitialize it")]
+    Corrupted {
+        namespace: String,
+        detail: String,
+        repair_command: String,
+    },
+    #[error("task registry '{namespace}' is full: {reason}. Prune finished tasks or raise registry_max_entries / registry_max_value_bytes in ~/.aiw/config.json (env: AIW_REGISTRY_MAX_ENTRIES / AIW_REGISTRY_MAX_VALUE_BYTES)")]
+    Full { namespace: String, reason: String },
+    #[error("no registry for {0}")]
+    NotFound(String),
+}
+
+impl RegistryError {
+    /// 命名空间对应的共享内存段不存在；`{pid}_task` 形式的命名空间报告为 PID
+    pub fn not_found(namespace: &str) -> Self {
+        match namespace
+            .strip_suffix("_task")
+            .and_then(|pid| pid.parse::<u32>().ok())
+        {
+            Some(pid) => RegistryError::NotFound(format!("pid {}", pid)),
+            None => RegistryError::NotFound(format!("namespace '{}'", namespace)),
+        }
+    }
+
+    /// 构造指向 `aiw registry reset` 的损坏错误
+    pub fn corrupted(namespace: &str, detail: impl Into<String>) -> Self {
+        let repair_command = match namespace
+            .strip_suffix("_task")
+            .and_then(|pid| pid.parse::<u32>().ok())
+        {
+            Some(pid) => format!("aiw registry reset --pid {}", pid),
+            None => "aiw registry reset".to_string(),
+        };
+        RegistryError::Corrupted {
+            namespace: namespace.to_string(),
+            detail: detail.into(),
+            repair_command,
+        }
+    }
 }
 
 impl From<shared_hashmap::Error> for RegistryError {
@@ -973,6 +1012,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn corrupted_registry_error_points_to_reset() {
+        let err = RegistryError::corrupted("4242_task", "stale lock");
+        let message = err.to_string();
+        assert!(message.contains("stale lock"));
+        assert!(message.contains("aiw registry reset --pid 4242"));
+
+        let err = RegistryError::corrupted("custom", "bad");
+        assert!(err.to_string().contains("`aiw registry reset`"));
+    }
+
     #[test]
     fn sync_operation_helpers_work() {
         let err = errors::sync_error(SyncOperation::Upload, "network reset");
@@ -15,32 +15,74 @@ USAGE:
     aiw [OPTIONS] <AI_CLI> [AI_OPTIONS] "<TASK>"
 
 AI CLI COMMANDS:
-    aiw <agent> [-r ROLE] [-mp PROVIDER] [CLI_OPTIONS] ["TASK"]
+    aiw <agent> [-r ROLE] [-mp PROVIDER] [--aiw-model MODEL] [--record] [--dry-run] [CLI_OPTIONS] ["TASK"]
 
     Agents:     claude | codex | gemini | all | "agent1|agent2"
     -r ROLE     Inject role prompt (run 'aiw roles list' to see all)
     -mp PROVIDER Use specific provider (e.g., cdx, glm)
+    --aiw-model MODEL Run with a different model (passed as the CLI's --model)
+    --record    Record interactive session to ~/.aiw/sessions/
+    --dry-run   Print the resolved command, provider env names and cwd without running
+    -f FILE     Read the task prompt from FILE (use '-' for stdin)
     [CLI_OPTIONS] Forwarded to AI CLI (run 'aiw claude --help' to see all)
     "TASK"      Task description (omit for interactive mode)
 
     ⚠️  Parameter order: -r and -mp must come BEFORE CLI options!
 
+    Default agent: when the first argument is not an agent name, aiw uses
+    "default_ai_type" from ~/.aiw/config.json and treats every argument as
+    the task, e.g. aiw "fix the tests". An explicit agent always wins; with
+    no default_ai_type configured, an unknown first argument is an error.
+    A single lowercase word (e.g. a mistyped subcommand) is never sent as a
+    prompt; "auto" cannot be the default (use aiw auto).
+
+    Prompt size: set "max_prompt_chars" in ~/.aiw/config.json to reject prompts
+    (task plus injected roles) longer than that many characters. With
+    "truncate_long_prompts": true the task text is cut to fit instead.
+
+    Argument templates: "cli_arg_templates" in ~/.aiw/config.json replaces
+    the built-in arguments of a CLI for non-interactive tasks, e.g.
+        "cli_arg_templates": {"codex": ["exec", "--model", "o3", "{cli_args}", "{prompt}"]}
+    Placeholders: {prompt} (required, exactly once), {cwd} (task working
+    directory, may be embedded like "--cd={cwd}") and {cli_args} (forwarded
+    CLI options, as its own element; appended at the end when omitted).
+    Invalid templates are ignored with a warning.
+
     Quick start:
         aiw claude "explain this code"           # Simple task
         aiw claude                               # Interactive mode
+        aiw claude --record                      # Interactive, recorded
+        aiw claude --dry-run "fix the tests"     # Show what would run
+        aiw claude -f task.md                    # Prompt from a file
+        cat task.md | aiw codex -f -             # Prompt from stdin
         aiw claude -r common "write a function"  # With role
         aiw claude -mp glm "help me debug"        # With provider
         aiw claude -r common -mp glm "full example"  # Role + provider
+        aiw "fix the tests"                      # Uses default_ai_type
 
     For detailed AI CLI help:  aiw help claude
 
 MANAGEMENT COMMANDS:
     dashboard                   Show Dashboard (default when no args)
-    status [--tui]              Show task status
+    status [--tui|--watch]      Show task status
     provider                    Launch Provider Management TUI
+    provider test <name>        Check a provider's credentials and connectivity
+    provider validate           Check providers.json for config mistakes
+    provider remove|edit <name> Delete a provider or change its fields
+    doctor                      Diagnose CLIs, config files and registry health
+    registry reset              Back up and rebuild a corrupted task registry
+    task export <TASK_ID> [OUT] Bundle a task's log and metadata into a tar.gz
+    task attach <TASK_ID>       Stream a running task's log until it finishes
+    routing config [get|set|unset]
+                                Show or edit MCP routing/decision settings
+    cli install|uninstall <TOOL>
+                                Install or remove claude/codex/gemini via npm or Homebrew
+    sync push|pull [DIR...]     Sync AI CLI config directories with Google Drive
+    sync config list-dirs       List directories that sync can target
     auto <prompt>               Run auto failover mode
     config cli-order            Manage AI CLI execution order (TUI)
-    wait                        Wait for all AI CLI tasks to complete
+    config reset-cooldown       Clear auto-mode failover cooldowns
+    wait [TASK_ID...|--all]     Wait for all (or the given) AI CLI tasks to complete
     pwait <PID>                 Wait for specific process tasks
     examples / demo             Show usage examples
     help [COMMAND]              Show help for command
@@ -57,13 +99,20 @@ MCP COMMANDS:
     mcp update                  Update MCP registry cache
     mcp browse                  Interactive MCP server browser
     mcp get <name>              Get server configuration
-    mcp enable <name>           Enable MCP server
-    mcp disable <name>          Disable MCP server
+    mcp enable <names|--all>    Enable MCP server(s) (comma-separated)
+    mcp disable <names|--all>   Disable MCP server(s) (comma-separated)
     mcp edit                    Edit MCP configuration file
+    mcp test <name>             Start server and list its tools
+    mcp tools [server]          Show discovered tools and routing index status
+    mcp call <server> <tool>    Call a tool directly (--args '<json>')
+    mcp export <file>           Export server configs (--redact strips secrets)
+    mcp import <file>           Import server configs (merge by default)
     mcp serve                   Start MCP server (internal use)
 
 ROLE COMMANDS:
     roles list                  List all available role configurations
+    roles show <NAME>           Show the resolved content of a role
+    roles overrides [NAME]      Builtin roles shadowed by ~/.aiw/role/ files
 
 PLUGIN COMMANDS:
     plugin browse               Interactive plugin browser (TUI)
@@ -79,6 +128,13 @@ PLUGIN COMMANDS:
 OPTIONS:
     --help, -h                  Show this help message
     --version, -V               Show version information
+    --config-dir <DIR>          Use DIR instead of ~/.aiw for roles, providers, mcp.json
+                                and config.json (must come before the command;
+                                same as AIW_CONFIG_DIR=<DIR>)
+    -q, --quiet                 Only print errors from aiw's own logging (same as
+                                AIW_LOG=error). Status and routing traces are logged
+                                at info/debug; enable them with e.g. AIW_LOG=info
+                                or AIW_LOG=aiw::mcp_routing=debug
 
 EXAMPLES:
     # AI CLI with role injection
@@ -146,6 +202,12 @@ pub fn print_command_help(command: &str) -> io::Result<()> {
         "dashboard" => print_dashboard_help(),
         "examples" | "demo" => print_examples_help(),
         "update" => print_update_help(),
+        "doctor" => print_doctor_help(),
+        "registry" => print_registry_help(),
+        "task" => print_task_help(),
+        "routing" => print_routing_help(),
+        "sync" => print_sync_help(),
+        "cli" => print_cli_help(),
         "mcp" => print_mcp_help(),
         "config" => print_config_help(),
         "roles" => print_roles_help(),
@@ -187,8 +249,11 @@ ROLE INJECTION:
 
 PROVIDER SELECTION:
     -mp, --aiw-provider <PROVIDER>    Use specific provider (e.g., cdx, glm)
+    -mp auto                          Pick the best compatible provider automatically
 
     Without -mp, uses the default provider configured in ~/.aiw/providers.json
+    With -mp auto, providers in cooldown are skipped, the rest get a quick health
+    check, and the pick goes to healthy ones by "priority" then measured latency.
 
 WORKING DIRECTORY (optional):
     -C, --cwd <DIR>              Set working directory for AI CLI process
@@ -198,6 +263,15 @@ WORKING DIRECTORY (optional):
     - Directory must exist (error if not found)
     - Path must be a directory (error if it's a file)
 
+DRY RUN:
+    --dry-run                    Print binary, args (with role injection), provider
+                                 env var names and cwd, then exit without running
+
+PROMPT FILE:
+    -f, --prompt-file <PATH>     Read the task prompt from a UTF-8 file ('-' reads stdin)
+                                 -f is always taken by aiw and never forwarded;
+                                 use the long form of a CLI's own -f option
+
 PARAMETER FORWARDING:
     All other parameters are forwarded directly to {} CLI.
 
@@ -324,7 +398,9 @@ USAGE:
 
 DESCRIPTION:
     Run automatic failover across AI CLIs in the configured order.
-    Execution order is stored in ~/.aiw/config.json (cli_execution_order).
+    Execution order is stored in ~/.aiw/config.json (auto_execution_order).
+    Failed and skipped entries are logged as warnings; set AIW_LOG=info to
+    also see which entry is being tried.
 
 EXAMPLES:
     aiw auto "Fix this bug"
@@ -366,10 +442,18 @@ WAIT COMMAND
 
 USAGE:
     aiw wait
+    aiw wait <TASK_ID>...
+    aiw wait --all
 
 DESCRIPTION:
     Enter monitoring mode to track all AI CLI task completion across processes.
 
+    With task ids, only the matching tasks are waited for. A target matches a
+    task id, PID or log id and may contain '*' wildcards. --all waits for every
+    running task found in any task registry on this machine. Each task's final
+    status and exit code is printed as it finishes, and the command exits with
+    1 if any of them failed.
+
 FEATURES:
     - Shows active tasks and their progress
     - Displays completion status for finished tasks
@@ -387,6 +471,10 @@ EXAMPLES:
 
     # Terminal 2: Monitor all tasks
     aiw wait
+
+    # Wait for specific tasks and fail if any of them failed
+    aiw wait 3f2a9c1e review-*
+    aiw wait --all && echo "all tasks succeeded"
 "#;
     print!("{}", help_text);
     io::stdout().flush()
@@ -427,12 +515,19 @@ STATUS COMMAND
 
 USAGE:
     aiw status [--tui]
+    aiw status --plain|--watch [--once] [--interval <SECS>]
 
 DESCRIPTION:
     Display task status and system information.
 
 OPTIONS:
-    --tui    Launch TUI interface for detailed status view
+    --tui               Launch TUI interface for detailed status view
+    --plain, --watch    Print a task table (id, ai, status, pid, age, exit)
+                        and refresh it without entering the TUI; works over
+                        SSH, pipes and CI. Redrawn in place on a terminal,
+                        appended as snapshots otherwise
+    --once              Print a single table snapshot and exit
+    --interval <SECS>   Refresh interval, default 2
 
 TEXT OUTPUT (default):
     - Active AI CLI tasks
@@ -448,6 +543,8 @@ TUI OUTPUT (--tui):
 EXAMPLES:
     aiw status
     aiw status --tui
+    aiw status --watch
+    aiw status --plain --once
 "#;
     print!("{}", help_text);
     io::stdout().flush()
@@ -460,10 +557,42 @@ PROVIDER COMMAND
 
 USAGE:
     aiw provider
+    aiw provider test <NAME>
+    aiw provider validate
+    aiw provider remove <NAME> [--default <PROVIDER>] [-y]
+    aiw provider edit <NAME> [--scenario <TEXT>] [--compatible-with <TYPES>]
+                             [--priority <N|none>] [--env KEY=VALUE]...
+                             [--unset-env KEY]... [--enable|--disable]
 
 DESCRIPTION:
     Launch the TUI Provider Management interface.
 
+    `aiw provider test <NAME>` checks a provider end-to-end: it builds the
+    same environment the CLI would get and sends a minimal request to the
+    endpoint of every compatible AI type, printing pass/fail, HTTP status,
+    latency and a hint on failure. Exits with 1 if any check fails.
+
+    `aiw provider validate` checks providers.json without loading it and
+    lists every problem: unknown compatible_with entries, duplicate names,
+    missing required fields, defaults that don't fit their CLI, and
+    suspicious entries such as a provider compatible with nothing. Exits
+    with 1 on errors; warnings alone exit with 0.
+
+    `aiw provider remove <NAME>` deletes a provider after confirmation (-y
+    skips it). A provider that is the global or a per-type default can only
+    be removed with --default <PROVIDER>, which takes over those defaults.
+
+    `aiw provider edit <NAME>` changes the given fields; without field flags
+    it prompts for scenario, compatible CLIs, priority and enabled state.
+    --compatible-with takes claude,codex,gemini,grok or `all`. Changes that
+    would leave a default pointing at a missing or incompatible provider
+    are rejected and nothing is written.
+
+    With "auto_provider_by_scenario": true in providers.json, tasks started
+    over MCP without a provider use the compatible provider whose scenario
+    best matches the task prompt (embedding similarity). Weak matches fall
+    back to the configured default provider.
+
     This is a shortcut command that directly opens the Provider Management
     screen in the TUI. You can also access it from the Dashboard by pressing 'P'.
 
@@ -604,6 +733,221 @@ WHAT GETS UPDATED:
     io::stdout().flush()
 }
 
+/// Print help for doctor command
+fn print_doctor_help() -> io::Result<()> {
+    let help_text = r#"
+DOCTOR COMMAND
+
+USAGE:
+    aiw doctor
+
+DESCRIPTION:
+    Diagnose the AIW environment and print a checklist:
+
+    - claude / codex / gemini / grok presence on PATH and version
+    - ~/.aiw/providers.json and ~/.aiw/mcp.json exist and parse
+    - role files under ~/.aiw/role/ (or user_roles_dir)
+    - ~/.aiw is writable and not readable by other users
+    - routing LLM endpoint (routing.llm_endpoint in mcp.json, then
+      OPENAI_ENDPOINT, default local Ollama) is reachable
+    - the shared-memory task registry can be opened
+
+    ✓ = OK, ! = warning (optional feature unavailable), ✗ = problem.
+    Each non-passing check prints a hint. Exits with 1 if any check fails.
+"#;
+    print!("{}", help_text);
+    io::stdout().flush()
+}
+
+/// Print help for registry command
+fn print_registry_help() -> io::Result<()> {
+    let help_text = r#"
+REGISTRY COMMANDS
+
+USAGE:
+    aiw registry reset [--pid <PID>] [--purge-dead]
+
+DESCRIPTION:
+    Each aiw process keeps its tasks in a shared-memory segment named
+    <PID>_task. A crash can leave a stale lock or a half-written segment
+    behind, and later connections fail with "task registry is corrupted".
+
+    reset                       Check every registry and rebuild corrupted ones
+        --pid <PID>             Force a rebuild of that process's registry
+        --purge-dead            Remove running entries whose process is gone
+
+    Before a rebuild, the raw segment is saved to ~/.aiw/registry-backups/.
+    Entries that can still be parsed are written back into the new segment.
+
+CAPACITY:
+    A registry holds at most 4096 tasks of up to 64 KiB each; past that,
+    launches fail with "task registry is full". Raise the limits in
+    ~/.aiw/config.json ("registry_max_entries", "registry_max_value_bytes")
+    or with AIW_REGISTRY_MAX_ENTRIES / AIW_REGISTRY_MAX_VALUE_BYTES.
+
+    Every aiw process reserves about 4 KiB of shared memory per entry
+    (never less than 16 MiB), so 16384 entries cost 64 MiB per process.
+    The record size limit does not reserve extra memory. New limits apply
+    to segments created after the change.
+"#;
+    print!("{}", help_text);
+    io::stdout().flush()
+}
+
+/// Print help for task command
+fn print_task_help() -> io::Result<()> {
+    let help_text = r#"
+TASK COMMANDS
+
+USAGE:
+    aiw task export <TASK_ID> [OUTPUT]
+    aiw task attach <TASK_ID> [--grep <PATTERN> [--regex] [-C <LINES>]]
+
+DESCRIPTION:
+    export                      Write a diagnostic bundle for one task
+        TASK_ID                 Task ID, name, PID or log ID (as shown by 'aiw status')
+        OUTPUT                  Archive path (default ~/.aiw/exports/task-<id>-<time>.tar.gz)
+
+    The tar.gz contains:
+        task.json               The task record, including its PID
+        command.txt             The resolved AI CLI command line
+        env.txt                 Provider and task env var names, values redacted
+        worktree.json           Worktree path/branch/commit (worktree tasks only)
+        logs/                   The task's log file
+
+    attach                      Follow a task's log like 'tail -f'
+        TASK_ID                 Task ID, name, PID or log ID
+        Prints the log written so far, then new output as it arrives. When the
+        task finishes, prints a final status line with the exit code and exits
+        with 0 on success, 1 otherwise. Ctrl-C detaches; the task keeps running.
+        --grep <PATTERN>        Only print lines containing PATTERN
+        --regex                 Treat PATTERN as a regular expression; an
+                                invalid one is rejected before attaching
+        -C, --context <LINES>   Also print LINES lines around each match,
+                                with '--' between separate groups
+
+    Tasks started through the MCP server live in that server's memory; use the
+    export_task and manage_task MCP tools for them instead (manage_task's
+    logs action takes the same filter as pattern / pattern_is_regex /
+    context_lines).
+"#;
+    print!("{}", help_text);
+    io::stdout().flush()
+}
+
+/// Print help for routing command
+fn print_routing_help() -> io::Result<()> {
+    let help_text = r#"
+ROUTING COMMANDS
+
+USAGE:
+    aiw routing config                      Show the effective routing config
+    aiw routing config get <KEY>            Show one value and where it came from
+    aiw routing config set <KEY> <VALUE>    Store a value in ~/.aiw/mcp.json
+    aiw routing config unset <KEY>          Remove a stored value
+
+DESCRIPTION:
+    Settings live in the "routing" section of ~/.aiw/mcp.json and take
+    precedence over environment variables. The token is always masked.
+
+KEYS:
+    llm_endpoint          Decision LLM endpoint     (env: OPENAI_ENDPOINT)
+    llm_model             Decision LLM model        (env: OPENAI_MODEL)
+    llm_token             Decision LLM token        (env: OPENAI_TOKEN)
+                          or a reference: ${env:NAME} / ${file:/path}
+    llm_timeout_secs      Decision timeout, default 120
+    fast_path_threshold   Vector score (0-1) that skips LLM orchestration, default 0.75
+    orchestration_timeout_secs
+                          Time limit for LLM orchestration before falling back
+                          to vector search, default 90
+    embedding_backend     auto|fastembed|local|mock (env: AIW_EMBEDDING_BACKEND)
+    embedding_model_dir   Local ONNX model directory (env: AIW_EMBEDDING_MODEL_DIR)
+                          fastembed downloads use AIW_FASTEMBED_CACHE as the
+                          cache directory and AIW_FASTEMBED_MIRROR (e.g.
+                          https://hf-mirror.com) instead of huggingface.co
+    max_dynamic_tools     Dynamic tools kept before FIFO eviction, default 5
+    history_enabled       Record routes and favour recently successful tools,
+                          default true (env: AIW_ROUTING_HISTORY)
+    argument_validation   reject|warn|off: check proxied tool arguments against
+                          the tool's input schema, default warn
+                          (env: AIW_ARGUMENT_VALIDATION)
+    warm_up_concurrency   Servers discovered in parallel at startup, default 4
+    discovery_timeout_secs
+                          Per-server time limit for startup tool discovery;
+                          slower servers are skipped and marked down, default 30
+    max_result_bytes      Text size of a proxied tool result before it is
+                          truncated with a marker, default 262144 (256 KiB)
+    max_structured_result_bytes
+                          Largest structuredContent passed through unchanged;
+                          bigger ones are omitted, default 1048576 (1 MiB)
+
+    Changes apply the next time `aiw mcp serve` starts.
+
+EXAMPLES:
+    aiw routing config set llm_model qwen3:8b
+    aiw routing config set fast_path_threshold 0.85
+    aiw routing config unset llm_endpoint
+"#;
+    print!("{}", help_text);
+    io::stdout().flush()
+}
+
+/// Print help for cli command
+fn print_cli_help() -> io::Result<()> {
+    let help_text = r#"
+CLI COMMANDS
+
+USAGE:
+    aiw cli install <TOOL>      Install an AI CLI (claude, codex, gemini)
+    aiw cli uninstall <TOOL>    Remove an AI CLI installed by npm or Homebrew
+
+DESCRIPTION:
+    install uses the first available package manager (npm, then Homebrew)
+    and checks that the command is on PATH afterwards. Already installed
+    tools are reported with their version and install type.
+
+    uninstall resolves the binary on PATH to find the package manager that
+    installed it; standalone installs must be removed manually.
+
+EXAMPLES:
+    aiw cli install codex
+    aiw cli uninstall gemini
+"#;
+    print!("{}", help_text);
+    io::stdout().flush()
+}
+
+/// Print help for sync command
+fn print_sync_help() -> io::Result<()> {
+    let help_text = r#"
+SYNC COMMANDS
+
+USAGE:
+    aiw sync push [DIR...]              Upload changed config directories
+    aiw sync push --name <NAME>         Upload all AI CLI configs as one named bundle
+    aiw sync pull [DIR...] [--force]    Restore config directories from Google Drive
+    aiw sync pull --name <NAME>         Restore a named bundle
+    aiw sync status                     Show Google Drive and local config status
+    aiw sync config list-dirs           List directories configured in ~/.aiw/sync.json
+
+DESCRIPTION:
+    Without DIR arguments every configured directory is synced. A DIR may be
+    the configured path (~/.claude), the expanded path, or just the directory
+    name (.claude or claude); unknown names are rejected.
+
+    Pull refuses to overwrite a directory that changed locally since the last
+    sync when the remote backup is older. It asks on a terminal; pass --force
+    to overwrite without asking.
+
+EXAMPLES:
+    aiw sync config list-dirs
+    aiw sync push claude codex
+    aiw sync pull .gemini --force
+"#;
+    print!("{}", help_text);
+    io::stdout().flush()
+}
+
 /// Print help for mcp command
 fn print_mcp_help() -> io::Result<()> {
     let help_text = r#"
@@ -624,26 +968,74 @@ MCP SERVER MANAGEMENT:
     enable <name>               Enable a disabled server
     disable <name>              Disable an enabled server
     edit                        Edit MCP configuration in editor
+                                Invalid JSON is reverted and its line/column reported
     get <name>                  Show server configuration
+    test <name>                 Start server, list its tools, then stop it
+    tools [<server>]            Start all enabled servers and list the tools each
+                                one discovered, marking tools missing from the
+                                routing index or without an input schema
+    restore [<backup>] [-y]     Roll mcp.json back to a backup (default: newest)
+        --list                  List backups, newest first
+                                <backup> is a list number, file name or path
+
+    Every change to mcp.json is preceded by a timestamped backup in
+    ~/.aiw/backups/ (the newest 20 are kept).
+
+SHARING:
+    export <file> [--redact]    Write all servers to a portable file
+        --redact                Replace env values and secret args (--token X,
+                                --api-key=X) with ${KEY} placeholders
+    import <file> [OPTIONS]     Add servers from an exported file, keeping
+                                existing servers (default)
+        --overwrite             Replace existing servers of the same name
+        --replace               Replace every existing server with the import
 
 MCP REGISTRY:
-    browse [--source <src>]     Interactive server browser
+    browse [--source <src>] [--timeout <secs>]
+                                Interactive server browser
     search <query> [OPTIONS]    Search MCP registries for servers
         --source <src>          Specify source (registry|smithery)
         --limit <n>             Limit results count
+        --sort <order>          downloads|name|relevance (default: relevance)
+        --no-cache              Ignore cached results and query the registries
+                                Results are cached in ~/.aiw/cache/mcp_search/
+                                for 10 minutes (Env: AIW_MCP_SEARCH_CACHE_TTL, seconds),
+                                shared with browse
+        --timeout <secs>        Per-registry request timeout (default: 10);
+                                a registry that does not answer in time is
+                                skipped and the others' results are shown
     install <name> [OPTIONS]    Install server from registry
         --source <src>          Specify source
         --env KEY=VALUE         Environment variables (repeatable)
         --skip-env              Skip environment variable configuration
+        --into <cli>            Also add it to claude|codex|gemini|all's own MCP
+                                config (~/.claude.json, ~/.codex/config.toml,
+                                ~/.gemini/settings.json); the file must exist
+                                and is backed up to ~/.aiw/backups/ first;
+                                plain-text secrets are skipped, use ${VAR}
+        --timeout <secs>        Per-registry request timeout (default: 10);
+                                the whole lookup gives up after twice that
     info <name> [--source <src>]
                                 Show detailed server information
     update                      Update registry cache
 
 INTERNAL:
-    serve [--transport <type>] [--log-level <level>]
+    serve [--transport <type>] [--bind <host:port>] [--log-level <level>]
                                 Start MCP server (for Claude Code)
-        --transport             Transport type (stdio, default: stdio)
+                                On SIGINT/SIGTERM running tasks get SIGTERM
+                                to their process groups, SIGKILL after 5s,
+                                and are marked manager_shutdown
+        --transport             Transport type (stdio|sse, default: stdio)
+                                Env: AGENTIC_WARDEN_MCP_TRANSPORT
+        --bind                  SSE listen address (default: 127.0.0.1:8765)
+                                Env: AGENTIC_WARDEN_MCP_BIND
+                                SSE has no authentication; keep it on loopback
         --log-level             Log level (debug|info|warn|error)
+                                Set AIW_MCP_AUDIT=1 to append every initialize,
+                                list_tools and call_tool (arguments with secrets
+                                redacted, outcome, duration) to
+                                ~/.aiw/logs/mcp_audit.jsonl (mode 0600, rotated
+                                to mcp_audit.jsonl.1 at 10 MiB)
 
 EXAMPLES:
 
@@ -653,21 +1045,34 @@ EXAMPLES:
     aiw mcp add myserver node server.js --env API_KEY=xxx --description "My server"
     aiw mcp enable filesystem
     aiw mcp disable filesystem
+    aiw mcp disable brave-search,memory
+    aiw mcp enable --all
     aiw mcp remove filesystem -y
 
+    # Share servers with a teammate
+    aiw mcp export team-mcp.json --redact
+    aiw mcp import team-mcp.json --overwrite
+
     # Registry operations
     aiw mcp browse
     aiw mcp browse --source smithery
     aiw mcp search "filesystem"
     aiw mcp search "database" --source registry --limit 10
+    aiw mcp search "github" --sort downloads --no-cache
+    aiw mcp search "postgres" --timeout 30
     aiw mcp install @anthropic/filesystem
     aiw mcp install myserver --env API_KEY=xxx
+    aiw mcp install @anthropic/filesystem --into claude
     aiw mcp info @anthropic/filesystem
     aiw mcp update
 
     # Configuration
     aiw mcp get filesystem
     aiw mcp edit
+    aiw mcp test filesystem
+    aiw mcp call filesystem list_directory --args '{"path": "/tmp"}'
+    aiw mcp restore --list
+    aiw mcp restore 2
 
 MCP CONFIGURATION:
     Servers: ~/.aiw/mcp.json
@@ -712,7 +1117,19 @@ USAGE:
     aiw roles <SUBCOMMAND>
 
 SUBCOMMANDS:
-    list    List all available role configurations
+    list           List all available role configurations
+    show <NAME>    Show the resolved content of a role
+    overrides [NAME]
+                   List builtin roles and mark the ones a user role of the
+                   same name replaces; with NAME, diff that role
+
+SHOW OPTIONS:
+    --raw          Print the role file verbatim
+    --lang <LANG>  Preview a builtin role in a specific language (en, zh-CN)
+
+OVERRIDES OPTIONS:
+    --diff         Diff every overridden builtin against its user file
+    --lang <LANG>  Builtin language to diff against (en, zh-CN)
 
 DESCRIPTION:
     Manage AI CLI role configurations.
@@ -724,17 +1141,20 @@ DESCRIPTION:
     You can also create custom roles in ~/.aiw/role/ directory.
 
 LANGUAGE SUPPORT:
-    Roles are available in multiple languages. The system automatically detects
-    your locale and selects the appropriate language version:
-
-    - English locales (en_US, en_GB, etc.) → English role versions
-    - Chinese and other locales → Chinese role versions (default)
+    Builtin roles ship in English (en) and Chinese (zh-CN). The language comes
+    from AIW_LANG when set (e.g. AIW_LANG=en), otherwise from the system locale:
+    Chinese locales use zh-CN, everything else uses en.
 
-    Currently, only the "common" role has an English translation. Other roles
-    will use Chinese as fallback. Additional translations will be added gradually.
+    If a role has no translation for that language, AIW falls back to the same
+    base language (zh, zh-TW → zh-CN), then to English.
 
 EXAMPLES:
     aiw roles list
+    aiw roles show debugger
+    aiw roles show common --lang en
+    aiw roles show my-role --raw
+    aiw roles overrides
+    aiw roles overrides security --lang en
 
 BUILTIN ROLES:
     common              General programming standards and best practices
@@ -789,10 +1209,12 @@ ROLE INJECTION:
         [your prompt]
 
 ROLE PRIORITY:
-    1. Builtin roles (embedded in binary, always available)
-    2. Custom roles (from ~/.aiw/role/, if exists)
+    1. Custom roles (from ~/.aiw/role/, if exists)
+    2. Builtin roles (embedded in binary, always available)
 
-    If a role name exists in both, builtin takes precedence.
+    If a role name exists in both, the custom role takes precedence; a custom
+    file that fails to parse is ignored. 'aiw roles overrides' shows which
+    builtins are replaced.
 
 ADVANCED USAGE:
     # Use role with provider and custom parameters
@@ -19,10 +19,12 @@ pub mod pwait_mode;
 pub mod registry;
 pub mod registry_factory;
 pub mod roles;
+pub mod session_recorder;
 pub mod signal;
 pub mod storage;
 pub mod supervisor;
 pub mod sync;
+pub mod task_hooks;
 pub mod task_record;
 pub mod tui;
 pub mod unified_registry;
@@ -30,6 +32,7 @@ pub mod utils;
 pub mod wait_mode;
 pub mod worktree;
 pub mod task_prepare;
+pub mod task_export;
 pub mod patcher;
 
 pub mod cli_manager;
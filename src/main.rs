@@ -3,36 +3,59 @@ mod help;
 
 use aiw::commands::ai_cli::AiCliCommand;
 use aiw::commands::cli_args::CliInvocation;
-use aiw::commands::parser::{ConfigAction, McpAction, RolesAction, PatchAction, Cli, Commands};
+use aiw::commands::parser::{
+    Cli, Commands, ConfigAction, McpAction, PatchAction, ProviderAction, RegistryAction,
+    RolesAction, RoutingAction, TaskAction,
+};
 use aiw::execute_enhanced_update;
 use aiw::mcp::AgenticWardenMcpServer;
 use aiw::commands::market::handle_plugin_action;
 use aiw::pwait_mode;
 use aiw::roles::RoleManager;
 use aiw::tui;
+use aiw::utils::logger::{init_cli_logger, take_quiet_flag, LogFormat, QUIET_LOG_LEVEL};
 use aiw::wait_mode;
 use help::{print_command_help, print_general_help, print_quick_examples};
 use std::process::ExitCode;
 
-#[tokio::main]
-async fn main() -> ExitCode {
-    let args: Vec<String> = std::env::args().collect();
+fn main() -> ExitCode {
+    // fastembed 镜像的 HF_ENDPOINT 在 runtime 工作线程启动前写入：set_var 与其他线程读取环境不安全
+    aiw::mcp_routing::export_mirror_endpoint();
+
+    match tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime.block_on(run()),
+        Err(err) => {
+            eprintln!("Failed to start async runtime: {}", err);
+            ExitCode::from(1)
+        }
+    }
+}
+
+async fn run() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().collect();
+
+    // -q/--quiet 同样写入环境变量，嵌套启动的 aiw（auto 模式、MCP 任务）也只输出错误
+    if take_quiet_flag(&mut args) {
+        std::env::set_var(aiw::config::LOG_ENV, QUIET_LOG_LEVEL);
+    }
+
+    // --config-dir 必须在其他处理之前生效；写入环境变量，子进程（嵌套的 aiw）也会继承
+    match aiw::utils::config_paths::take_config_dir_arg(&mut args) {
+        Ok(Some(dir)) => std::env::set_var(aiw::config::CONFIG_DIR_ENV, dir),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::from(2);
+        }
+    }
 
     // 初始化日志系统
     // MCP serve 模式必须把 tracing 写到 stderr，因为 stdout 是 JSON-RPC 协议通道
     let is_mcp_serve = args.len() >= 3 && args[1] == "mcp" && args[2] == "serve";
-    if is_mcp_serve {
-        tracing_subscriber::fmt()
-            .with_target(false)
-            .with_max_level(tracing::Level::WARN)
-            .with_writer(std::io::stderr)
-            .init();
-    } else {
-        tracing_subscriber::fmt()
-            .with_target(false)
-            .with_max_level(tracing::Level::WARN)
-            .init();
-    }
+    init_cli_logger(LogFormat::from_env(), is_mcp_serve);
 
     // 处理版本标志 - 在解析CLI之前检查
     if args.len() == 2 && (args[1] == "--version" || args[1] == "-V") {
@@ -44,20 +67,23 @@ async fn main() -> ExitCode {
     if args.len() >= 2 {
         match args[1].as_str() {
             "codex" | "claude" | "gemini" | "auto" => {
-                return match handle_external_ai_cli(&args).await {
+                let code = match handle_external_ai_cli(&args).await {
                     Ok(code) => code,
                     Err(err) => {
                         eprintln!("{}", err);
                         ExitCode::from(1)
                     }
                 };
+                // 任务结束钩子在后台运行，退出前给它们一个截止时间
+                aiw::task_hooks::wait_for_pending().await;
+                return code;
             }
             _ => {}
         }
     }
 
     // 处理其他命令
-    let command = Cli::parse_command();
+    let command = Cli::parse_command_from(&args);
     match main_impl(command).await {
         Ok(code) => code,
         Err(err) => {
@@ -79,7 +105,18 @@ async fn handle_external_ai_cli(args: &[String]) -> Result<ExitCode, String> {
     let tokens = args[1..].to_vec();
 
     // 使用新的 CliInvocation 解析
-    let inv = CliInvocation::from_external(&tokens)?;
+    let mut inv = CliInvocation::from_external(&tokens)?;
+    inv.load_prompt_file()?;
+
+    // dry-run 不探测 Provider：auto 的实际选择留到启动时，计划里只作说明
+    if inv.aiw_args.dry_run {
+        return AiCliCommand::from_invocation(inv)
+            .dry_run()
+            .map_err(|e| e.to_string());
+    }
+
+    inv.aiw_args.provider =
+        aiw::task_prepare::resolve_auto_provider(&inv.cli_type, inv.aiw_args.provider.take()).await;
 
     // 打印启动信息
     if inv.is_interactive() {
@@ -104,20 +141,105 @@ async fn handle_external_ai_cli(args: &[String]) -> Result<ExitCode, String> {
 async fn main_impl(command: Commands) -> Result<ExitCode, String> {
     match command {
         Commands::Dashboard => launch_tui(None).await,
-        Commands::Status { tui } => {
+        Commands::Status {
+            tui,
+            plain,
+            watch,
+            once,
+            interval,
+        } => {
             if tui {
                 // 启动TUI界面
                 launch_tui(Some(tui::ScreenType::Status)).await
+            } else if plain || watch || once {
+                Ok(aiw::commands::status::handle_status_plain(
+                    once,
+                    std::time::Duration::from_secs(interval),
+                )
+                .await)
             } else {
                 // 显示文本摘要
                 handle_status_command()
             }
         }
-        Commands::Provider => launch_tui(Some(tui::ScreenType::Provider)).await,
-        Commands::Wait => {
+        Commands::Provider { action: None } => {
+            launch_tui(Some(tui::ScreenType::Provider)).await
+        }
+        Commands::Provider {
+            action: Some(ProviderAction::Test { name }),
+        } => Ok(aiw::commands::provider::handle_provider_test(&name).await),
+        Commands::Provider {
+            action: Some(ProviderAction::Validate),
+        } => Ok(aiw::commands::provider::handle_provider_validate()),
+        Commands::Provider {
+            action:
+                Some(ProviderAction::Remove {
+                    name,
+                    new_default,
+                    yes,
+                }),
+        } => Ok(aiw::commands::provider::handle_provider_remove(
+            &name,
+            new_default.as_deref(),
+            yes,
+        )),
+        Commands::Provider {
+            action:
+                Some(ProviderAction::Edit {
+                    name,
+                    scenario,
+                    compatible_with,
+                    priority,
+                    env_vars,
+                    unset_env,
+                    enable,
+                    disable,
+                }),
+        } => {
+            let edit = aiw::commands::provider::ProviderEdit {
+                scenario,
+                compatible_with,
+                priority,
+                env_vars,
+                unset_env,
+                enabled: (enable || disable).then_some(enable),
+            };
+            Ok(aiw::commands::provider::handle_provider_edit(&name, edit))
+        }
+        Commands::Doctor => Ok(aiw::commands::doctor::handle_doctor_command().await),
+        Commands::Registry(RegistryAction::Reset { pid, purge_dead }) => Ok(
+            aiw::commands::registry::handle_registry_reset(pid, purge_dead),
+        ),
+        Commands::Task(TaskAction::Export { task_id, output }) => {
+            Ok(aiw::commands::task::handle_task_export(&task_id, output))
+        }
+        Commands::Task(TaskAction::Attach {
+            task_id,
+            grep,
+            regex,
+            context,
+        }) => Ok(aiw::commands::task::handle_task_attach(
+            &task_id,
+            grep.as_deref(),
+            regex,
+            context.unwrap_or(0),
+        )
+        .await),
+        Commands::Routing(RoutingAction::Config { action }) => {
+            Ok(aiw::commands::routing::handle_routing_config(action))
+        }
+        Commands::Cli(action) => Ok(aiw::commands::cli::handle_cli_action(action)),
+        Commands::Sync(action) => Ok(aiw::commands::sync::handle_sync_action(action).await),
+        Commands::Wait { task_ids, all } if task_ids.is_empty() && !all => {
             wait_mode::run().map_err(|e| e.to_string())?;
             Ok(ExitCode::from(0))
         }
+        Commands::Wait { task_ids, all } => {
+            let outcome = wait_mode::run_targets(&task_ids, all)
+                .await
+                .map_err(|e| e.to_string())?;
+            Ok(ExitCode::from(if outcome.is_success() { 0 } else { 1 }))
+        }
         Commands::PWait { pid } => {
             // 等待指定进程的共享内存任务完成
             match pwait_mode::run_for_pid(pid) {
@@ -268,7 +390,18 @@ async fn handle_external_command(tokens: Vec<String>) -> Result<ExitCode, String
     }
 
     // 使用新的 CliInvocation 解析
-    let inv = CliInvocation::from_external(&tokens)?;
+    let mut inv = CliInvocation::from_external(&tokens)?;
+    inv.load_prompt_file()?;
+
+    // dry-run 不探测 Provider：auto 的实际选择留到启动时，计划里只作说明
+    if inv.aiw_args.dry_run {
+        return AiCliCommand::from_invocation(inv)
+            .dry_run()
+            .map_err(|e| e.to_string());
+    }
+
+    inv.aiw_args.provider =
+        aiw::task_prepare::resolve_auto_provider(&inv.cli_type, inv.aiw_args.provider.take()).await;
 
     // 打印启动信息
     if inv.is_interactive() {
@@ -305,6 +438,9 @@ fn handle_help_command(topic: Option<String>) -> Result<ExitCode, String> {
 fn handle_config_action(action: ConfigAction) -> Result<ExitCode, String> {
     match action {
         ConfigAction::CliOrder => Ok(aiw::commands::auto::handle_cli_order_command()),
+        ConfigAction::ResetCooldown { cli, provider } => Ok(
+            aiw::commands::auto::handle_reset_cooldown_command(cli, provider),
+        ),
     }
 }
 
@@ -389,12 +525,18 @@ async fn handle_mcp_action(action: McpAction) -> Result<ExitCode, String> {
             query,
             source,
             limit,
+            sort,
+            no_cache,
+            timeout,
         } => {
             use aiw::commands::mcp::{handle_mcp_command, McpCommand};
             match handle_mcp_command(McpCommand::Search {
                 query,
                 source,
                 limit,
+                sort,
+                no_cache,
+                timeout,
             })
             .await
             {
@@ -410,6 +552,8 @@ async fn handle_mcp_action(action: McpAction) -> Result<ExitCode, String> {
             source,
             env_vars,
             skip_env,
+            into,
+            timeout,
         } => {
             use aiw::commands::mcp::{handle_mcp_command, McpCommand};
             let mut env = Vec::new();
@@ -430,6 +574,8 @@ async fn handle_mcp_action(action: McpAction) -> Result<ExitCode, String> {
                 source,
                 env,
                 skip_env,
+                into,
+                timeout,
             })
             .await
             {
@@ -470,9 +616,9 @@ async fn handle_mcp_action(action: McpAction) -> Result<ExitCode, String> {
                 }
             }
         }
-        McpAction::Enable { name } => {
+        McpAction::Enable { name, all } => {
             use aiw::commands::mcp::{handle_mcp_command, McpCommand};
-            match handle_mcp_command(McpCommand::Enable { name }).await {
+            match handle_mcp_command(McpCommand::Enable { name, all }).await {
                 Ok(_) => Ok(ExitCode::from(0)),
                 Err(e) => {
                     eprintln!("Error: {}", e);
@@ -480,9 +626,9 @@ async fn handle_mcp_action(action: McpAction) -> Result<ExitCode, String> {
                 }
             }
         }
-        McpAction::Disable { name } => {
+        McpAction::Disable { name, all } => {
             use aiw::commands::mcp::{handle_mcp_command, McpCommand};
-            match handle_mcp_command(McpCommand::Disable { name }).await {
+            match handle_mcp_command(McpCommand::Disable { name, all }).await {
                 Ok(_) => Ok(ExitCode::from(0)),
                 Err(e) => {
                     eprintln!("Error: {}", e);
@@ -500,9 +646,79 @@ async fn handle_mcp_action(action: McpAction) -> Result<ExitCode, String> {
                 }
             }
         }
-        McpAction::Browse { source } => {
+        McpAction::Restore { backup, list, yes } => {
             use aiw::commands::mcp::{handle_mcp_command, McpCommand};
-            match handle_mcp_command(McpCommand::Browse { source }).await {
+            match handle_mcp_command(McpCommand::Restore { backup, list, yes }).await {
+                Ok(_) => Ok(ExitCode::from(0)),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    Ok(ExitCode::from(1))
+                }
+            }
+        }
+        McpAction::Test { name } => {
+            use aiw::commands::mcp::{handle_mcp_command, McpCommand};
+            match handle_mcp_command(McpCommand::Test { name }).await {
+                Ok(_) => Ok(ExitCode::from(0)),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    Ok(ExitCode::from(1))
+                }
+            }
+        }
+        McpAction::Tools { server } => {
+            use aiw::commands::mcp::{handle_mcp_command, McpCommand};
+            match handle_mcp_command(McpCommand::Tools { server }).await {
+                Ok(_) => Ok(ExitCode::from(0)),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    Ok(ExitCode::from(1))
+                }
+            }
+        }
+        McpAction::Call { server, tool, args } => {
+            use aiw::commands::mcp::{handle_mcp_command, McpCommand};
+            match handle_mcp_command(McpCommand::Call { server, tool, args }).await {
+                Ok(_) => Ok(ExitCode::from(0)),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    Ok(ExitCode::from(1))
+                }
+            }
+        }
+        McpAction::Export { file, redact } => {
+            use aiw::commands::mcp::{handle_mcp_command, McpCommand};
+            match handle_mcp_command(McpCommand::Export { file, redact }).await {
+                Ok(_) => Ok(ExitCode::from(0)),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    Ok(ExitCode::from(1))
+                }
+            }
+        }
+        McpAction::Import {
+            file,
+            replace,
+            overwrite,
+        } => {
+            use aiw::commands::mcp::{handle_mcp_command, McpCommand};
+            match handle_mcp_command(McpCommand::Import {
+                file,
+                replace,
+                overwrite,
+            })
+            .await
+            {
+                Ok(_) => Ok(ExitCode::from(0)),
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    Ok(ExitCode::from(1))
+                }
+            }
+        }
+        McpAction::Browse { source, timeout } => {
+            use aiw::commands::mcp::{handle_mcp_command, McpCommand};
+            match handle_mcp_command(McpCommand::Browse { source, timeout }).await {
                 Ok(_) => Ok(ExitCode::from(0)),
                 Err(e) => {
                     eprintln!("Error: {}", e);
@@ -512,16 +728,39 @@ async fn handle_mcp_action(action: McpAction) -> Result<ExitCode, String> {
         }
         McpAction::Serve {
             transport,
+            bind,
             log_level,
-        } => handle_mcp_serve(transport, log_level).await,
+        } => handle_mcp_serve(transport, bind, log_level).await,
     }
 }
 
-async fn handle_mcp_serve(transport: String, log_level: String) -> Result<ExitCode, String> {
+async fn handle_mcp_serve(
+    transport: Option<String>,
+    bind: Option<String>,
+    log_level: String,
+) -> Result<ExitCode, String> {
+    use aiw::config::{MCP_BIND_DEFAULT, MCP_BIND_ENV, MCP_TRANSPORT_ENV};
+
+    // 优先级：命令行参数 > 环境变量 > 默认值
+    let transport = transport
+        .or_else(|| std::env::var(MCP_TRANSPORT_ENV).ok())
+        .map(|value| value.trim().to_ascii_lowercase())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| "stdio".to_string());
+    let bind = bind
+        .or_else(|| std::env::var(MCP_BIND_ENV).ok())
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| MCP_BIND_DEFAULT.to_string());
+
     // NOTE: global tracing subscriber is already set in main(), so we just
     // log a debug message here instead of re-initialising.
     tracing::debug!("MCP serve starting with log_level={}", log_level);
 
+    // 从初始化阶段开始 stdout 就只属于 JSON-RPC，bootstrap 期间的输出同样要改道
+    if transport == "stdio" {
+        aiw::utils::protocol_stdout::reserve_for_protocol();
+    }
+
     // Note: Claude Code hooks were removed in v6.0.0 (CC session history deleted)
     // No hooks installation/uninstallation needed
 
@@ -534,21 +773,37 @@ async fn handle_mcp_serve(transport: String, log_level: String) -> Result<ExitCo
     match transport.as_str() {
         "stdio" => {
             // 使用stdio传输启动MCP服务器
-            eprintln!("Starting Agentic-Warden MCP server with stdio transport...");
+            tracing::info!("Starting Agentic-Warden MCP server with stdio transport...");
 
             match run_mcp_server_stdio(mcp_server).await {
                 Ok(_) => {
-                    eprintln!("MCP server stopped gracefully");
+                    tracing::info!("MCP server stopped gracefully");
+                    Ok(ExitCode::from(0))
+                }
+                Err(e) => {
+                    tracing::error!("MCP server error: {}", e);
+                    Ok(ExitCode::from(1))
+                }
+            }
+        }
+        "sse" => {
+            let addr: std::net::SocketAddr = bind
+                .parse()
+                .map_err(|e| format!("Invalid MCP bind address '{}': {}", bind, e))?;
+
+            match mcp_server.run_sse(addr).await {
+                Ok(_) => {
+                    tracing::info!("MCP server stopped gracefully");
                     Ok(ExitCode::from(0))
                 }
                 Err(e) => {
-                    eprintln!("MCP server error: {}", e);
+                    tracing::error!("MCP server error: {}", e);
                     Ok(ExitCode::from(1))
                 }
             }
         }
         _ => Err(format!(
-            "Unsupported transport: {}. Supported: stdio",
+            "Unsupported transport: {}. Supported: stdio, sse",
             transport
         )),
     }
@@ -591,7 +846,139 @@ async fn handle_roles_command(action: RolesAction) -> Result<ExitCode, String> {
 
             Ok(ExitCode::from(0))
         }
+        RolesAction::Show { name, raw, lang } => {
+            let lang = role_language(lang)?;
+
+            let role = aiw::roles::load_role(&name, &lang).map_err(|e| e.to_string())?;
+
+            if raw {
+                // 内置角色的 content 就是嵌入的文件本身
+                let content = if role.file_path.exists() {
+                    std::fs::read_to_string(&role.file_path).map_err(|e| {
+                        format!("Failed to read {}: {}", role.file_path.display(), e)
+                    })?
+                } else {
+                    role.content
+                };
+                print!("{}", content);
+                return Ok(ExitCode::from(0));
+            }
+
+            println!("{}: {}", role.name, role.description);
+            println!("Source: {}", role.file_path.display());
+            println!("{}", "-".repeat(60));
+            println!("{}", role.content.trim_end());
+
+            Ok(ExitCode::from(0))
+        }
+        RolesAction::Overrides { name, diff, lang } => {
+            handle_role_overrides(name.as_deref(), diff, &role_language(lang)?)
+        }
+    }
+}
+
+/// `--lang` of the roles commands; defaults to the detected language
+fn role_language(lang: Option<String>) -> Result<String, String> {
+    match lang {
+        Some(lang) if lang == "en" || lang == "zh-CN" => Ok(lang),
+        Some(other) => Err(format!(
+            "Unsupported role language '{}'. Use 'en' or 'zh-CN'",
+            other
+        )),
+        None => Ok(aiw::roles::detect_language()),
+    }
+}
+
+/// `aiw roles overrides [NAME] [--diff]`
+fn handle_role_overrides(name: Option<&str>, diff: bool, lang: &str) -> Result<ExitCode, String> {
+    use aiw::roles::overrides::{builtin_overrides, OverrideState};
+    use colored::Colorize;
+
+    let manager = RoleManager::new().map_err(|e| format!("Failed to load roles: {}", e))?;
+    let mut overrides = builtin_overrides(&manager);
+    if let Some(name) = name {
+        overrides.retain(|entry| entry.name == name);
+        if overrides.is_empty() {
+            return Err(format!(
+                "'{}' is not a builtin role (run 'aiw roles list')",
+                name
+            ));
+        }
+    }
+
+    let shadowed = overrides
+        .iter()
+        .filter(|entry| matches!(entry.state, OverrideState::Overridden(_)))
+        .count();
+    if name.is_none() {
+        println!(
+            "Builtin roles ({}), {} overridden by user roles:",
+            overrides.len(),
+            shadowed
+        );
+    }
+    for entry in &overrides {
+        match &entry.state {
+            OverrideState::Builtin => println!("  {:<22} builtin", entry.name),
+            OverrideState::Overridden(role) => println!(
+                "  {:<22} {} {}",
+                entry.name,
+                "overridden by".yellow(),
+                role.file_path.display()
+            ),
+            OverrideState::Invalid { path, error } => println!(
+                "  {:<22} {} {} ({})",
+                entry.name,
+                "builtin, user file ignored:".red(),
+                path.display(),
+                error
+            ),
+        }
+    }
+
+    // 指定名称时直接显示差异
+    if !(diff || name.is_some()) {
+        if shadowed > 0 {
+            println!("\nShow the differences with: aiw roles overrides --diff");
+        }
+        return Ok(ExitCode::from(0));
+    }
+    for entry in &overrides {
+        let OverrideState::Overridden(role) = &entry.state else {
+            continue;
+        };
+        let builtin =
+            aiw::roles::builtin::get_builtin_role(&entry.name, lang).map_err(|e| e.to_string())?;
+        let user = std::fs::read_to_string(&role.file_path)
+            .map_err(|e| format!("Failed to read {}: {}", role.file_path.display(), e))?;
+        let diff = aiw::roles::overrides::unified_diff(
+            &builtin.file_path.display().to_string(),
+            &role.file_path.display().to_string(),
+            &builtin.content,
+            &user,
+            3,
+        );
+        println!();
+        if diff.is_empty() {
+            println!("{}: user file is identical to the builtin role", entry.name);
+            continue;
+        }
+        for line in diff.lines() {
+            let line = if line.starts_with("+++") || line.starts_with("---") {
+                line.bold()
+            } else if line.starts_with("@@") {
+                line.cyan()
+            } else if line.starts_with('+') {
+                line.green()
+            } else if line.starts_with('-') {
+                line.red()
+            } else {
+                line.normal()
+            };
+            println!("{}", line);
+        }
     }
+    Ok(ExitCode::from(0))
 }
 
 /// 运行stdio传输的MCP服务器
@@ -0,0 +1,343 @@
+//! MCP 请求审计日志
+//!
+//! 设置 `AIW_MCP_AUDIT=1` 后，server 处理的每个 initialize / list_tools / call_tool
+//! 都追加一行 JSON 到 `~/.aiw/logs/mcp_audit.jsonl`：时间、方法、工具名、参数摘要
+//! （疑似密钥的字段被替换，长字符串被截断）、成功与否和耗时。
+//! 只写文件、不经过 stdout，stdio 传输的协议流不受影响；写入失败只记 debug 日志。
+//! 文件权限为 0600；超过 [`MAX_AUDIT_BYTES`] 时改名为 `mcp_audit.jsonl.1`（覆盖上一份）后重新开始。
+
+use crate::config::MCP_AUDIT_ENV;
+use crate::utils::config_paths::aiw_config_dir;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const AUDIT_FILE_NAME: &str = "mcp_audit.jsonl";
+/// 单个审计文件的大小上限，超过后轮转
+pub const MAX_AUDIT_BYTES: u64 = 10 * 1024 * 1024;
+
+const REDACTED: &str = "<redacted>";
+/// 参数摘要中字符串保留的最大字符数
+const MAX_STRING_CHARS: usize = 200;
+/// 参数摘要展开的最大嵌套层数
+const MAX_DEPTH: usize = 4;
+/// 字段名包含这些片段（不区分大小写）时值被替换
+const SECRET_KEY_PARTS: &[&str] = &[
+    "secret",
+    "password",
+    "passwd",
+    "api_key",
+    "apikey",
+    "authorization",
+    "credential",
+    "cookie",
+];
+
+/// 审计日志中的一行
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub method: &'static str,
+    /// initialize 时客户端的名称和版本
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Value>,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl AuditEntry {
+    pub fn new(method: &'static str, started_at: DateTime<Utc>, duration: Duration) -> Self {
+        Self {
+            timestamp: started_at,
+            method,
+            client: None,
+            tool: None,
+            arguments: None,
+            success: true,
+            error: None,
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+
+    pub fn client(mut self, client: impl Into<String>) -> Self {
+        self.client = Some(client.into());
+        self
+    }
+
+    pub fn tool(mut self, name: impl Into<String>) -> Self {
+        self.tool = Some(name.into());
+        self
+    }
+
+    /// 记录参数摘要（已脱敏）
+    pub fn arguments(mut self, arguments: Option<&Map<String, Value>>) -> Self {
+        self.arguments = arguments.map(|arguments| summarize(&Value::Object(arguments.clone())));
+        self
+    }
+
+    pub fn failed(mut self, error: impl Into<String>) -> Self {
+        self.success = false;
+        self.error = Some(error.into());
+        self
+    }
+}
+
+/// 追加写入的审计日志文件
+#[derive(Debug)]
+pub struct AuditLog {
+    path: PathBuf,
+    max_bytes: u64,
+    file: Mutex<AuditFile>,
+}
+
+#[derive(Debug)]
+struct AuditFile {
+    file: File,
+    /// 当前文件已写入的字节数
+    size: u64,
+}
+
+impl AuditLog {
+    /// `AIW_MCP_AUDIT` 开启时打开 `~/.aiw/logs/mcp_audit.jsonl`，否则返回 None
+    pub fn from_env() -> Option<Self> {
+        let enabled = std::env::var(MCP_AUDIT_ENV).ok().is_some_and(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "on" | "yes"
+            )
+        });
+        if !enabled {
+            return None;
+        }
+        let path = aiw_config_dir()?.join("logs").join(AUDIT_FILE_NAME);
+        match Self::open(&path) {
+            Ok(log) => {
+                tracing::info!("MCP audit log: {}", path.display());
+                Some(log)
+            }
+            Err(err) => {
+                tracing::warn!("Cannot open MCP audit log {}: {}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        Self::open_with_limit(path, MAX_AUDIT_BYTES)
+    }
+
+    /// 与 `open` 相同，但文件超过 `max_bytes` 时轮转
+    pub fn open_with_limit(path: &Path, max_bytes: u64) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = open_private(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            file: Mutex::new(AuditFile { file, size }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 轮转后的上一份日志
+    pub fn rotated_path(&self) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(".1");
+        PathBuf::from(name)
+    }
+
+    pub fn record(&self, entry: &AuditEntry) {
+        let Ok(mut line) = serde_json::to_string(entry) else {
+            return;
+        };
+        line.push('\n');
+        let mut current = self.file.lock();
+        if current.size > 0 && current.size + line.len() as u64 > self.max_bytes {
+            if let Err(err) = self.rotate(&mut current) {
+                tracing::debug!("Failed to rotate MCP audit log: {}", err);
+            }
+        }
+        match current.file.write_all(line.as_bytes()) {
+            Ok(()) => current.size += line.len() as u64,
+            Err(err) => tracing::debug!("Failed to write MCP audit entry: {}", err),
+        }
+    }
+
+    fn rotate(&self, current: &mut AuditFile) -> std::io::Result<()> {
+        fs::rename(&self.path, self.rotated_path())?;
+        current.file = open_private(&self.path)?;
+        current.size = 0;
+        Ok(())
+    }
+}
+
+/// 以追加方式打开，只有当前用户可读写（参数摘要仍可能包含敏感内容）
+fn open_private(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(path)?;
+    #[cfg(unix)]
+    {
+        // 旧版本创建的文件可能是 0644，打开时一并收紧
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(file)
+}
+
+/// 参数摘要：疑似密钥的字段替换为 `<redacted>`，长字符串截断，过深的嵌套折叠
+pub fn summarize(value: &Value) -> Value {
+    summarize_at(value, 0)
+}
+
+fn summarize_at(value: &Value, depth: usize) -> Value {
+    match value {
+        Value::String(text) if text.chars().count() > MAX_STRING_CHARS => {
+            let kept: String = text.chars().take(MAX_STRING_CHARS).collect();
+            Value::String(format!("{}… ({} chars)", kept, text.chars().count()))
+        }
+        Value::Object(_) | Value::Array(_) if depth >= MAX_DEPTH => Value::String("…".to_string()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| {
+                    let summary = if is_secret_key(key) {
+                        Value::String(REDACTED.to_string())
+                    } else {
+                        summarize_at(value, depth + 1)
+                    };
+                    (key.clone(), summary)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| summarize_at(item, depth + 1))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+pub(crate) fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase().replace('-', "_");
+    key.ends_with("token")
+        || key.ends_with("_key")
+        || SECRET_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn summary_redacts_secrets_and_truncates_long_strings() {
+        let summary = summarize(&json!({
+            "task": "x".repeat(500),
+            "env": {"ANTHROPIC_API_KEY": "sk-live", "RUST_LOG": "debug"},
+            "headers": [{"Authorization": "Bearer abc"}],
+            "access_token": "abc",
+            "max_tokens": 10,
+            "path": "/tmp"
+        }));
+
+        assert_eq!(summary["env"]["ANTHROPIC_API_KEY"], REDACTED);
+        assert_eq!(summary["env"]["RUST_LOG"], "debug");
+        assert_eq!(summary["headers"][0]["Authorization"], REDACTED);
+        assert_eq!(summary["access_token"], REDACTED);
+        assert_eq!(summary["max_tokens"], 10);
+        assert_eq!(summary["path"], "/tmp");
+        let task = summary["task"].as_str().unwrap();
+        assert!(task.ends_with("… (500 chars)"));
+        assert_eq!(
+            task.chars().count(),
+            MAX_STRING_CHARS + "… (500 chars)".chars().count()
+        );
+    }
+
+    #[test]
+    fn entries_are_appended_as_json_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::open(&dir.path().join("logs").join(AUDIT_FILE_NAME)).unwrap();
+
+        let mut arguments = Map::new();
+        arguments.insert("password".to_string(), json!("hunter2"));
+        log.record(
+            &AuditEntry::new("call_tool", Utc::now(), Duration::from_millis(42))
+                .tool("start_task")
+                .arguments(Some(&arguments)),
+        );
+        log.record(
+            &AuditEntry::new("list_tools", Utc::now(), Duration::from_millis(1))
+                .failed("invalid cursor"),
+        );
+
+        let content = fs::read_to_string(log.path()).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["tool"], "start_task");
+        assert_eq!(lines[0]["arguments"]["password"], REDACTED);
+        assert_eq!(lines[0]["duration_ms"], 42);
+        assert_eq!(lines[0]["success"], true);
+        assert_eq!(lines[1]["success"], false);
+        assert_eq!(lines[1]["error"], "invalid cursor");
+        assert!(lines[1].get("tool").is_none());
+        assert!(!content.contains("hunter2"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(log.path()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn log_rotates_once_it_exceeds_the_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(AUDIT_FILE_NAME);
+        let entry = AuditEntry::new("list_tools", Utc::now(), Duration::from_millis(1));
+        let line_len = serde_json::to_string(&entry).unwrap().len() as u64 + 1;
+        let log = AuditLog::open_with_limit(&path, line_len * 2).unwrap();
+
+        for _ in 0..3 {
+            log.record(&entry);
+        }
+
+        let current = fs::read_to_string(log.path()).unwrap();
+        let rotated = fs::read_to_string(log.rotated_path()).unwrap();
+        assert_eq!(rotated.lines().count(), 2);
+        assert_eq!(current.lines().count(), 1);
+
+        // 重新打开时沿用已有文件的大小，下一条仍写入当前文件
+        let reopened = AuditLog::open_with_limit(&path, line_len * 2).unwrap();
+        reopened.record(&entry);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+    }
+}
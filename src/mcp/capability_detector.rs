@@ -3,8 +3,11 @@
 //! Tests whether the connected MCP client supports dynamic tool registration
 //! (notifications/tools/list_changed) by actually sending a test notification.
 
+use crate::mcp_routing::models::{ExecutionMode, RoutingMode};
 use rmcp::model::InitializeRequestParams;
 use rmcp::service::{Peer, RoleServer};
+use schemars::JsonSchema;
+use serde::Serialize;
 
 #[derive(Debug, Clone)]
 pub struct ClientCapabilities {
@@ -26,6 +29,18 @@ impl ClientCapabilities {
         }
     }
 
+    /// Execution mode `intelligent_route` falls back to for this client.
+    ///
+    /// Callers asking for `dynamic` are downgraded to `query` when the client
+    /// cannot handle tool list changes.
+    pub fn effective_execution_mode(&self) -> ExecutionMode {
+        if self.supports_dynamic_tools {
+            ExecutionMode::Dynamic
+        } else {
+            ExecutionMode::Query
+        }
+    }
+
     /// Test if client supports dynamic tool registration.
     ///
     /// # Current Implementation (Placeholder)
@@ -36,17 +51,62 @@ impl ClientCapabilities {
     /// Attempting to send notifications during initialization could interfere with the
     /// connection handshake.
     ///
-    /// **Workaround**: We assume all clients support dynamic tools and rely on a query-based
-    /// approach:
-    /// - After generating new tools via `intelligent_route`, clients should re-query the
-    ///   tool list using the standard MCP `tools/list` method
-    /// - This is compatible with all MCP clients as per the protocol specification
+    /// **Workaround**: We assume all clients support dynamic tools. When a tool is registered
+    /// via `intelligent_route`, the server sends `notifications/tools/list_changed`; if that
+    /// fails, clients are expected to re-query the tool list using the standard MCP
+    /// `tools/list` method.
     ///
     /// **Future**: If rmcp adds a proper notification API or capability negotiation protocol,
     /// this function should be updated to perform actual testing.
     pub async fn test_dynamic_tools_support(_peer: &Peer<RoleServer>) -> bool {
-        eprintln!("   🔍 [DEBUG] test_dynamic_tools_support called!");
-        eprintln!("   ✅ Dynamic tools: ENABLED (query-based mode)");
+        tracing::debug!("test_dynamic_tools_support called!");
+        tracing::info!("Dynamic tools: ENABLED (query-based mode)");
         true
     }
 }
+
+/// Snapshot returned by the `capabilities` MCP tool.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CapabilitiesReport {
+    /// Whether the client has completed `initialize` on this connection
+    pub client_connected: bool,
+    pub client_name: Option<String>,
+    pub client_version: Option<String>,
+    pub supports_dynamic_tools: bool,
+    /// Mode `intelligent_route` uses when the caller requests `dynamic`
+    pub execution_mode: ExecutionMode,
+    /// Number of dynamically registered tools currently exposed
+    pub dynamic_tool_count: usize,
+    /// Routing pipeline settled at startup: orchestration, vector_llm or vector_only
+    pub routing_mode: RoutingMode,
+}
+
+impl CapabilitiesReport {
+    pub fn new(
+        capabilities: Option<&ClientCapabilities>,
+        dynamic_tool_count: usize,
+        routing_mode: RoutingMode,
+    ) -> Self {
+        match capabilities {
+            Some(caps) => Self {
+                client_connected: true,
+                client_name: Some(caps.client_name.clone()),
+                client_version: Some(caps.client_version.clone()),
+                supports_dynamic_tools: caps.supports_dynamic_tools,
+                execution_mode: caps.effective_execution_mode(),
+                dynamic_tool_count,
+                routing_mode,
+            },
+            // Before initialize nothing is known, so the requested mode is kept as is
+            None => Self {
+                client_connected: false,
+                client_name: None,
+                client_version: None,
+                supports_dynamic_tools: false,
+                execution_mode: ExecutionMode::Dynamic,
+                dynamic_tool_count,
+                routing_mode,
+            },
+        }
+    }
+}
@@ -0,0 +1,252 @@
+//! start_task 并发上限
+//!
+//! 全局一个信号量，另按 AI 类型各一个（可选）。拿不到名额的启动请求进入队列，
+//! 由后台任务等待名额后再真正启动；队列深度通过 registry_stats 暴露。
+//! `supervisor::execute_cli`（代码生成等内部调用）也在同一组名额内运行。
+
+use crate::config::{
+    MAX_CONCURRENT_TASKS_DEFAULT, MAX_CONCURRENT_TASKS_ENV, MAX_CONCURRENT_TASKS_PER_AI_ENV,
+};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// 占用中的启动名额，任务结束（drop）时归还
+#[derive(Debug)]
+pub struct LaunchSlot {
+    _per_type: Option<OwnedSemaphorePermit>,
+    _global: OwnedSemaphorePermit,
+}
+
+/// 排队中的启动请求
+#[derive(Debug, Clone)]
+pub struct QueuedLaunch {
+    pub ai_type: String,
+    pub queued_at: DateTime<Utc>,
+}
+
+/// 出队后启动失败的请求。没有进程也就没有 registry 条目，错误保存在这里供按 task_id 查询
+#[derive(Debug, Clone)]
+pub struct FailedLaunch {
+    pub ai_type: String,
+    pub failed_at: DateTime<Utc>,
+    pub error: String,
+}
+
+/// 保留的启动失败记录上限，超出时淘汰最早的
+const MAX_FAILED_LAUNCHES: usize = 256;
+
+pub struct LaunchLimiter {
+    global: Arc<Semaphore>,
+    per_type_limit: Option<usize>,
+    per_type: Mutex<HashMap<String, Arc<Semaphore>>>,
+    queued: Mutex<HashMap<String, QueuedLaunch>>,
+    failed: Mutex<HashMap<String, FailedLaunch>>,
+}
+
+impl LaunchLimiter {
+    pub fn new(global_limit: usize, per_type_limit: Option<usize>) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(global_limit.max(1))),
+            per_type_limit: per_type_limit.map(|limit| limit.max(1)),
+            per_type: Mutex::new(HashMap::new()),
+            queued: Mutex::new(HashMap::new()),
+            failed: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 按环境变量构造：全局默认 16，按 AI 类型默认不限制
+    pub fn from_env() -> Self {
+        let read = |var: &str| {
+            std::env::var(var)
+                .ok()
+                .and_then(|raw| raw.trim().parse::<usize>().ok())
+                .filter(|value| *value > 0)
+        };
+        Self::new(
+            read(MAX_CONCURRENT_TASKS_ENV).unwrap_or(MAX_CONCURRENT_TASKS_DEFAULT),
+            read(MAX_CONCURRENT_TASKS_PER_AI_ENV),
+        )
+    }
+
+    fn type_semaphore(&self, ai_type: &str) -> Option<Arc<Semaphore>> {
+        let limit = self.per_type_limit?;
+        let mut per_type = self.per_type.lock();
+        Some(Arc::clone(
+            per_type
+                .entry(ai_type.to_string())
+                .or_insert_with(|| Arc::new(Semaphore::new(limit))),
+        ))
+    }
+
+    /// 立即获取名额；任一上限已满时返回 None
+    pub fn try_acquire(&self, ai_type: &str) -> Option<LaunchSlot> {
+        let per_type = match self.type_semaphore(ai_type) {
+            Some(semaphore) => Some(semaphore.try_acquire_owned().ok()?),
+            None => None,
+        };
+        let global = Arc::clone(&self.global).try_acquire_owned().ok()?;
+        Some(LaunchSlot {
+            _per_type: per_type,
+            _global: global,
+        })
+    }
+
+    /// 等待名额。先占 AI 类型名额再占全局名额，顺序固定以免互相等待
+    pub async fn acquire(&self, ai_type: &str) -> LaunchSlot {
+        let per_type = match self.type_semaphore(ai_type) {
+            Some(semaphore) => Some(
+                semaphore
+                    .acquire_owned()
+                    .await
+                    .expect("launch semaphore is never closed"),
+            ),
+            None => None,
+        };
+        let global = Arc::clone(&self.global)
+            .acquire_owned()
+            .await
+            .expect("launch semaphore is never closed");
+        LaunchSlot {
+            _per_type: per_type,
+            _global: global,
+        }
+    }
+
+    pub fn enqueue(&self, task_id: &str, ai_type: &str) {
+        self.queued.lock().insert(
+            task_id.to_string(),
+            QueuedLaunch {
+                ai_type: ai_type.to_string(),
+                queued_at: Utc::now(),
+            },
+        );
+    }
+
+    /// 从队列中移除；返回 false 表示任务已在排队期间被取消
+    pub fn dequeue(&self, task_id: &str) -> bool {
+        self.queued.lock().remove(task_id).is_some()
+    }
+
+    pub fn queued(&self, task_id: &str) -> Option<QueuedLaunch> {
+        self.queued.lock().get(task_id).cloned()
+    }
+
+    pub fn queue_depth(&self) -> usize {
+        self.queued.lock().len()
+    }
+
+    /// 记录排队任务出队后的启动失败，使其 task_id 以失败的终态可查
+    pub fn record_failure(&self, task_id: &str, ai_type: &str, error: String) {
+        let mut failed = self.failed.lock();
+        if failed.len() >= MAX_FAILED_LAUNCHES && !failed.contains_key(task_id) {
+            let oldest = failed
+                .iter()
+                .min_by_key(|(_, launch)| launch.failed_at)
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                failed.remove(&oldest);
+            }
+        }
+        failed.insert(
+            task_id.to_string(),
+            FailedLaunch {
+                ai_type: ai_type.to_string(),
+                failed_at: Utc::now(),
+                error,
+            },
+        );
+    }
+
+    pub fn failed(&self, task_id: &str) -> Option<FailedLaunch> {
+        self.failed.lock().get(task_id).cloned()
+    }
+
+    /// task_id 被重新使用时丢弃旧的失败记录
+    pub fn clear_failure(&self, task_id: &str) {
+        self.failed.lock().remove(task_id);
+    }
+}
+
+/// MCP server 进程内共享的限流器
+pub fn global() -> &'static LaunchLimiter {
+    static LIMITER: OnceLock<LaunchLimiter> = OnceLock::new();
+    LIMITER.get_or_init(LaunchLimiter::from_env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn per_type_and_global_limits_are_both_enforced() {
+        let limiter = LaunchLimiter::new(3, Some(2));
+
+        let claude_a = limiter.try_acquire("claude").expect("first claude slot");
+        let _claude_b = limiter.try_acquire("claude").expect("second claude slot");
+        assert!(
+            limiter.try_acquire("claude").is_none(),
+            "claude is at its limit"
+        );
+
+        let _codex = limiter
+            .try_acquire("codex")
+            .expect("codex has its own slots");
+        assert!(
+            limiter.try_acquire("gemini").is_none(),
+            "global limit reached"
+        );
+
+        drop(claude_a);
+        assert!(limiter.try_acquire("gemini").is_some());
+    }
+
+    #[tokio::test]
+    async fn queued_launch_starts_when_a_slot_frees() {
+        let limiter = Arc::new(LaunchLimiter::new(1, None));
+        let running = limiter.try_acquire("codex").unwrap();
+
+        limiter.enqueue("task-1", "codex");
+        assert_eq!(limiter.queue_depth(), 1);
+
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            tokio::spawn(async move {
+                let slot = limiter.acquire("codex").await;
+                (limiter.dequeue("task-1"), slot)
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(
+            !waiter.is_finished(),
+            "launch waits while the slot is taken"
+        );
+
+        drop(running);
+        let (still_queued, _slot) = waiter.await.unwrap();
+        assert!(still_queued);
+        assert_eq!(limiter.queue_depth(), 0);
+    }
+
+    #[test]
+    fn failed_launches_are_kept_per_task_id() {
+        let limiter = LaunchLimiter::new(1, None);
+        assert!(limiter.failed("task-1").is_none());
+
+        limiter.record_failure("task-1", "codex", "worktree creation failed".to_string());
+        let failed = limiter.failed("task-1").expect("failure recorded");
+        assert_eq!(failed.ai_type, "codex");
+        assert_eq!(failed.error, "worktree creation failed");
+
+        limiter.clear_failure("task-1");
+        assert!(limiter.failed("task-1").is_none());
+
+        for i in 0..=MAX_FAILED_LAUNCHES {
+            limiter.record_failure(&format!("task-{i}"), "codex", "boom".to_string());
+        }
+        assert_eq!(limiter.failed.lock().len(), MAX_FAILED_LAUNCHES);
+    }
+}
@@ -1,23 +1,37 @@
+pub mod audit;
 pub mod capability_detector;
 mod js_executor;
+pub mod launch_limiter;
+pub mod pagination;
+pub mod result_limit;
+pub mod shutdown;
 mod table_format;
 pub use js_executor::{JsExecutionReport, JsToolExecutor};
 
+use crate::core::models::ProcessTreeInfo;
 use crate::platform;
 use crate::provider::config::AiType;
 use crate::registry_factory::RegistryFactory;
+use crate::supervisor::ResultExtraction;
+use crate::task_export::ExportSummary;
+use crate::task_prepare::{RolePosition, RoleSpec};
 use crate::task_record::{TaskStatus, WorktreeInfo};
+use crate::utils::log_filter::LineFilter;
 use anyhow::Error;
 use chrono::{DateTime, Utc};
 
-use crate::mcp_routing::js_orchestrator::{BoaRuntimePool, McpFunctionInjector};
-use crate::mcp_routing::registry::{DynamicToolRegistry, RegisteredTool};
+use crate::mcp_routing::config::ArgumentValidation;
+use crate::mcp_routing::js_orchestrator::{
+    BoaRuntimePool, JsCodeValidator, McpFunctionInjector, SchemaValidator,
+};
+use crate::mcp_routing::registry::{DynamicToolRegistry, RegisteredTool, ToolSource};
 use crate::mcp_routing::{
-    models::{IntelligentRouteRequest, IntelligentRouteResponse},
+    models::{DiscoveredToolsReport, IntelligentRouteRequest, IntelligentRouteResponse},
     IntelligentRouter,
 };
 use crate::roles::{builtin::list_builtin_roles, RoleManager, RoleInfo};
-use capability_detector::ClientCapabilities;
+use audit::{AuditEntry, AuditLog};
+use capability_detector::{CapabilitiesReport, ClientCapabilities};
 use rmcp::{
     handler::server::prompt::PromptContext,
     handler::server::router::prompt::PromptRouter,
@@ -40,14 +54,16 @@ use rmcp::{
 // no replacement available yet — logging notifications still work and are used in start_task.
 #[allow(deprecated)]
 use rmcp::model::{LoggingLevel, LoggingMessageNotificationParam};
+use rmcp::transport::sse_server::SseServer;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::fs;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, RwLock};
 use tokio::time::{Duration, Instant};
+use tracing::Instrument;
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
 pub struct EmptyParams {}
@@ -59,17 +75,44 @@ pub struct StartTaskParams {
     pub ai_type: Option<AiType>,
     /// Task description/prompt for the AI.
     pub task: String,
+    /// Optional human-friendly alias; task tools accept it in place of the task_id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Optional caller-chosen task_id (e.g. an orchestrator's job id) instead of a
+    /// random UUID: 1-128 characters without whitespace or control characters.
+    /// Rejected while another task with the same id is queued, starting or running;
+    /// the id of a finished task can be reused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<String>,
     /// Optional provider name to use for this task.
     ///
     /// All available providers and their scenarios are defined in ~/.aiw/providers.json.
     /// Each provider can have a 'scenario' field describing when to use it.
     ///
     /// If not specified, the default_provider from configuration will be used.
+    /// Use "auto" to pick the healthiest compatible provider that is not cooling down.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub provider: Option<String>,
-    /// Optional role name to inject from ~/.aiw/role directory.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub role: Option<String>,
+    /// Optional model override, passed through unvalidated as the CLI's model flag
+    /// (--model for claude, codex and gemini). CLIs without a model flag reject it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Optional scheduling priority as a Unix nice value (-20 highest .. 19 lowest),
+    /// e.g. 10 for background work that should yield CPU to interactive tasks.
+    /// Mapped to a priority class on Windows. Clamped to what the server may apply
+    /// (unprivileged users cannot go below the server's own nice value).
+    /// Default: inherit the server's priority.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    /// Optional role(s) to inject from ~/.aiw/role directory: a comma-separated
+    /// string ("security,common") or an ordered list (["security", "common"]).
+    /// Unknown roles are skipped; roles with identical content are included once.
+    /// Falls back to "common" only when none of the named roles exist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<RoleSpec>,
+    /// Where role content goes relative to the task: "before" (default) or "after".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role_position: Option<RolePosition>,
     /// Optional working directory for the AI CLI process.
     /// If specified, the AI CLI will be started in this directory.
     /// The directory must exist and be a valid directory.
@@ -81,13 +124,38 @@ pub struct StartTaskParams {
     /// Whether to create a git worktree for isolated execution (default: false).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub worktree: Option<bool>,
+    /// Extra environment variables for the AI CLI process (e.g. RUST_LOG).
+    /// Applied after provider env injection, so these take precedence.
+    /// Only the variable names are recorded in the task registry.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<std::collections::BTreeMap<String, String>>,
+    /// Seconds to wait for the spawned CLI to appear in the task registry.
+    /// Falls back to AGENTIC_WARDEN_REGISTRATION_TIMEOUT_SEC, then 3s.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registration_timeout_secs: Option<u64>,
+    /// How get_task_output extracts the task's structured result from its output:
+    /// last_json (default, last top-level JSON object), marker (last block between
+    /// <<<AIW_RESULT>>> and <<<END_AIW_RESULT>>>) or none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result_extraction: Option<ResultExtraction>,
+}
+
+/// Whether start_task spawned the CLI or queued it behind the concurrency limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchStatus {
+    Running,
+    /// Waiting for a free slot; the task starts once a running task finishes.
+    Queued,
 }
 
 /// Internal result from start_task (not exposed as MCP tool).
 #[derive(Debug, Clone)]
 pub struct TaskLaunchResult {
     pub task_id: String,
-    pub pid: u32,
+    pub status: LaunchStatus,
+    /// Process ID; `None` while the launch is queued.
+    pub pid: Option<u32>,
+    /// Launch time, or the time the task was queued.
     pub started_at: DateTime<Utc>,
     pub worktree_info: Option<WorktreeInfo>,
     pub log_file: Option<String>,
@@ -98,6 +166,9 @@ pub struct TaskInfo {
     /// UUID task identifier.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub task_id: Option<String>,
+    /// Human-friendly alias, if one was set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     /// Process ID.
     pub pid: u32,
     /// Log file path.
@@ -109,6 +180,9 @@ pub struct TaskInfo {
     /// Optional completion time.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub completed_at: Option<DateTime<Utc>>,
+    /// Seconds from start to completion, or elapsed so far while running.
+    #[serde(default)]
+    pub duration_secs: i64,
     /// Cleanup reason if any.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cleanup_reason: Option<String>,
@@ -126,15 +200,24 @@ pub struct TaskInfo {
     /// Worktree isolation info.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub worktree_info: Option<WorktreeInfo>,
+    /// Names of task-level environment variables passed to the CLI.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_var_names: Vec<String>,
+    /// Model the CLI was launched with, if one was selected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Nice value applied at launch; absent when the priority was inherited.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
 }
 
 /// Action to perform on a managed task.
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum ManageAction {
-    /// Get current task state including status, process_alive, exit_code, started_at, completed_at.
+    /// Get current task state including status, process_alive, exit_code, started_at, completed_at, duration_secs.
     Status,
-    /// Get task log content. Use tail_lines to limit output.
+    /// Get task log content. Use tail_lines to limit output and pattern to keep only matching lines.
     Logs,
     /// Terminate the running task.
     Stop,
@@ -142,13 +225,27 @@ pub enum ManageAction {
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub struct ManageTaskParams {
-    /// UUID task identifier.
+    /// UUID task identifier or task name.
     pub task_id: String,
     /// Action to perform on the task.
     pub action: ManageAction,
     /// (logs only) Number of tail lines. Default: all.
+    /// Tailing scans back at most the last 8 MiB of the log.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tail_lines: Option<usize>,
+    /// (logs only) Remove ANSI escape sequences (colors, cursor moves) from the log. Default: false.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strip_ansi: Option<bool>,
+    /// (logs only) Return only lines containing this text, applied after tail_lines and strip_ansi.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// (logs only) Treat pattern as a regular expression instead of plain text. Default: false.
+    #[serde(default)]
+    pub pattern_is_regex: bool,
+    /// (logs only) Lines of context to include before and after each matching line. Default: 0.
+    /// Non-adjacent groups are separated by a `--` line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_lines: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
@@ -178,6 +275,9 @@ pub struct ManageTaskResult {
     /// Task completion time if finished. Returned by: status, stop.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub completed_at: Option<DateTime<Utc>>,
+    /// Seconds from start to completion, or elapsed so far while running. Returned by: status, stop.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<i64>,
 
     /// Path to the log file. Returned by: status (always), logs (always).
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -198,12 +298,39 @@ pub struct ManageTaskResult {
     pub worktree_info: Option<WorktreeInfo>,
 }
 
+/// 读取正整数环境变量，非法或为 0 时返回 None
+fn read_env_u64(var: &str) -> Option<u64> {
+    std::env::var(var)
+        .ok()
+        .and_then(|raw| raw.trim().parse::<u64>().ok())
+        .filter(|value| *value > 0)
+}
+
+/// 解析 registry 等待时长：参数 > 环境变量 > 默认值
+fn registration_timeout(requested_secs: Option<u64>) -> Duration {
+    use crate::config::{REGISTRATION_TIMEOUT_DEFAULT, REGISTRATION_TIMEOUT_ENV};
+
+    requested_secs
+        .filter(|secs| *secs > 0)
+        .or_else(|| read_env_u64(REGISTRATION_TIMEOUT_ENV))
+        .map(Duration::from_secs)
+        .unwrap_or(REGISTRATION_TIMEOUT_DEFAULT)
+}
+
+/// 等待新任务出现在 registry 中。
+///
+/// `launch_failure` 由后台执行任务在启动失败时写入，一旦收到就立即返回真实错误，
+/// 而不是等到超时再报告笼统的注册失败。到达截止时间后还会再检查一次 registry 和
+/// 失败通道，恰好在最后一次轮询之后出现的条目或错误不会被误报成超时。
+/// 返回 `Ok(None)` 只表示超时且没有收到启动错误。
 async fn wait_for_registry_entry(
     registry: &crate::registry_factory::McpRegistry,
     existing: &HashSet<u32>,
+    timeout: Duration,
+    launch_failure: &mut oneshot::Receiver<String>,
 ) -> Result<Option<crate::storage::RegistryEntry>, String> {
-    let deadline = Instant::now() + Duration::from_secs(3);
-    while Instant::now() < deadline {
+    let deadline = Instant::now() + timeout;
+    loop {
         let entries = registry.entries().map_err(|e| e.to_string())?;
         if let Some(new_entry) = entries
             .into_iter()
@@ -211,9 +338,15 @@ async fn wait_for_registry_entry(
         {
             return Ok(Some(new_entry));
         }
-        tokio::time::sleep(Duration::from_millis(100)).await;
+        if let Ok(err) = launch_failure.try_recv() {
+            return Err(format!("Failed to launch task: {}", err));
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        tokio::time::sleep(remaining.min(Duration::from_millis(100))).await;
     }
-    Ok(None)
 }
 
 /// 从 registry 中查找任务的日志文件并读取最后 N 行作为摘要
@@ -231,38 +364,161 @@ fn read_log_summary_from_registry<S: crate::storage::TaskStorage>(
     crate::supervisor::read_task_logs(&log_path, Some(max_lines)).ok()
 }
 
-#[allow(deprecated)] // LoggingLevel / LoggingMessageNotificationParam deprecated by SEP-2577 in rmcp 2.0; no replacement yet
+fn parse_task_cli_type(ai_type: &str) -> Result<crate::cli_type::CliType, String> {
+    crate::cli_type::parse_cli_type(ai_type).ok_or_else(|| {
+        format!(
+            "Invalid AI type: {}. Must be claude, codex, or gemini",
+            ai_type
+        )
+    })
+}
+
+/// Race/All 策略下多个组合并发运行，共用一个 worktree 会互相覆盖改动，直接拒绝
+fn reject_parallel_worktree(
+    cli_type: &crate::cli_type::CliType,
+    worktree: Option<bool>,
+) -> Result<(), String> {
+    use crate::auto_mode::config::ExecutionOrderConfig;
+    use crate::auto_mode::AutoStrategy;
+
+    if !matches!(cli_type, crate::cli_type::CliType::Auto) || !worktree.unwrap_or(false) {
+        return Ok(());
+    }
+    // 与 supervisor 一致：策略配置无效时按顺序模式执行
+    let strategy = ExecutionOrderConfig::get_strategy_config().unwrap_or_default();
+    match strategy.mode {
+        AutoStrategy::Sequential => Ok(()),
+        mode => Err(format!(
+            "worktree is not supported with the {} auto strategy: the CLIs run in parallel \
+             and would share one worktree. Pick a single ai_type or use the sequential strategy",
+            format!("{:?}", mode).to_lowercase()
+        )),
+    }
+}
+
+/// Auto 任务结束后把 task_id 转到获胜组合的条目上，并移除其余组合的条目
+///
+/// 启动时 task_id 绑定的是最先出现在 registry 中的进程；Race/All 下它可能是落败者，
+/// 顺序模式下可能是已失败的前一个组合。
+fn bind_failover_winner(
+    registry: &crate::registry_factory::McpRegistry,
+    task_id: &str,
+    outcome: &crate::supervisor::FailoverOutcome,
+) {
+    if let Some((pid, record)) = registry.get_by_task_id(task_id) {
+        if pid != outcome.winner_pid {
+            registry.update_task_metadata(
+                outcome.winner_pid,
+                task_id.to_string(),
+                record.worktree_info.clone(),
+            );
+            if record.name.is_some() {
+                registry.set_task_name(outcome.winner_pid, record.name.clone());
+            }
+        }
+    }
+    if let Err(err) = registry.remove_entries(&outcome.other_pids) {
+        tracing::warn!("start_task: failed to remove losing auto attempts: {}", err);
+    }
+}
+
+/// Launch a task, or queue it when the concurrency limit
+/// (AGENTIC_WARDEN_MAX_CONCURRENT_TASKS, optionally per AI type) is reached.
 pub async fn start_task(
     params: StartTaskParams,
     peer: Arc<RwLock<Option<rmcp::service::Peer<RoleServer>>>>,
 ) -> Result<TaskLaunchResult, String> {
-    use crate::cli_type::parse_cli_type;
+    let task_id = match &params.task_id {
+        Some(task_id) => {
+            validate_task_id(task_id)?;
+            task_id.clone()
+        }
+        None => uuid::Uuid::new_v4().to_string(),
+    };
+    let ai_type_str = params.ai_type.clone().unwrap_or(AiType::Auto).to_string();
+    let cli_type = parse_task_cli_type(&ai_type_str)?;
+    reject_parallel_worktree(&cli_type, params.worktree)?;
+    if let Some(name) = &params.name {
+        validate_task_name(name)?;
+    }
+    let claim = claim_task_id(&task_id)?;
+
+    let limiter = launch_limiter::global();
+    if let Some(slot) = limiter.try_acquire(&ai_type_str) {
+        return launch_task(params, peer, task_id, slot).await;
+    }
+
+    limiter.enqueue(&task_id, &ai_type_str);
+    tracing::info!(
+        "start_task: concurrency limit reached, queued {} task {} ({} waiting)",
+        ai_type_str,
+        task_id,
+        limiter.queue_depth()
+    );
+
+    let queued_id = task_id.clone();
+    tokio::spawn(async move {
+        let _claim = claim;
+        let slot = limiter.acquire(&ai_type_str).await;
+        // 排队期间被取消的任务直接释放名额
+        if !limiter.dequeue(&queued_id) {
+            return;
+        }
+        if let Err(err) = launch_task(params, peer, queued_id.clone(), slot).await {
+            tracing::warn!(
+                "start_task: queued task {} failed to launch: {}",
+                queued_id,
+                err
+            );
+            limiter.record_failure(&queued_id, &ai_type_str, err);
+        }
+    });
+
+    Ok(TaskLaunchResult {
+        task_id,
+        status: LaunchStatus::Queued,
+        pid: None,
+        started_at: Utc::now(),
+        worktree_info: None,
+        log_file: None,
+    })
+}
+
+/// 占用 `slot` 启动任务并等待其出现在 registry 中；名额随后台执行结束归还
+#[allow(deprecated)] // LoggingLevel / LoggingMessageNotificationParam deprecated by SEP-2577 in rmcp 2.0; no replacement yet
+async fn launch_task(
+    params: StartTaskParams,
+    peer: Arc<RwLock<Option<rmcp::service::Peer<RoleServer>>>>,
+    task_id: String,
+    slot: launch_limiter::LaunchSlot,
+) -> Result<TaskLaunchResult, String> {
     use crate::supervisor;
     use crate::task_prepare::{self, TaskParams};
 
-    let task_id = uuid::Uuid::new_v4().to_string();
     let registry = RegistryFactory::instance().get_mcp_registry();
 
     let ai_type = params.ai_type.clone().unwrap_or(AiType::Auto);
     let ai_type_str = ai_type.to_string();
-    let cli_type = parse_cli_type(&ai_type_str).ok_or_else(|| {
-        format!(
-            "Invalid AI type: {}. Must be claude, codex, or gemini",
-            ai_type_str
-        )
-    })?;
+    let cli_type = parse_task_cli_type(&ai_type_str)?;
 
     let is_auto = matches!(cli_type, crate::cli_type::CliType::Auto);
+    let provider = task_prepare::resolve_auto_provider(&cli_type, params.provider.clone()).await;
 
     // 统一准备：角色处理 + worktree 创建
     let base = task_prepare::prepare_task_base(TaskParams {
         cli_type: cli_type.clone(),
         prompt: params.task.clone(),
-        role: params.role.clone(),
-        provider: params.provider.clone(),
+        role: params.role.as_ref().map(RoleSpec::to_role_string),
+        role_position: params.role_position.unwrap_or_default(),
+        provider,
+        model: params.model.clone(),
         cli_args: params.cli_args.clone().unwrap_or_default(),
         cwd: params.cwd.clone().map(PathBuf::from),
         create_worktree: params.worktree.unwrap_or(false),
+        // 只有调用方指定的 task_id 能在重新启动时复用 worktree，随机生成的 id 不登记
+        worktree_key: params.task_id.clone(),
+        env: params.env.clone().unwrap_or_default().into_iter().collect(),
+        priority: params.priority,
     })
     .map_err(|e| e.to_string())?;
 
@@ -278,18 +534,44 @@ pub async fn start_task(
     let notify_peer = peer.clone();
     let notify_task_id = task_id.clone();
     let notify_task_desc = params.task.clone();
+    let result_extraction = params.result_extraction.unwrap_or_default();
+
+    // 后台任务启动失败时通过该通道回传错误
+    let (launch_tx, mut launch_rx) = oneshot::channel::<String>();
+    // task_id 绑定到 registry 条目后通知后台任务，之后才能按 task_id 提取结果
+    let (bound_tx, bound_rx) = oneshot::channel::<()>();
+
+    // supervisor 的结构化事件（launch/exit/timeout）挂在该 span 下，JSON 日志里可按 task_id 关联
+    let task_span = tracing::info_span!("task", task_id = %task_id, ai_type = %ai_type_str);
 
     if is_auto {
         // Auto 模式：故障切换执行
         let spawn_registry = registry.clone();
 
-        tokio::spawn(async move {
+        let launch = async move {
+            let _slot = slot;
             let result = supervisor::execute_cli_with_failover(
                 &spawn_registry,
                 &base,
             )
             .await;
 
+            if let Err(err) = &result {
+                let _ = launch_tx.send(err.to_string());
+            }
+
+            let bound = bound_rx.await.is_ok();
+            if let (true, Ok(outcome)) = (bound, &result) {
+                bind_failover_winner(&spawn_registry, &notify_task_id, outcome);
+                if let Err(err) = supervisor::capture_task_result(
+                    &spawn_registry,
+                    &notify_task_id,
+                    result_extraction,
+                ) {
+                    tracing::warn!("start_task: failed to capture task result: {}", err);
+                }
+            }
+
             // 读取日志摘要
             let log_summary = read_log_summary_from_registry(&spawn_registry, &notify_task_id, 20);
 
@@ -298,7 +580,10 @@ pub async fn start_task(
                 Err(_) => (LoggingLevel::Error, "failed"),
             };
             if let Some(p) = notify_peer.read().await.as_ref() {
-                eprintln!("[aiw] Sending task completion notification for task_id={}", notify_task_id);
+                tracing::debug!(
+                    "Sending task completion notification for task_id={}",
+                    notify_task_id
+                );
                 let mut data = serde_json::json!({
                     "event": "task_completed",
                     "task_id": notify_task_id,
@@ -306,6 +591,9 @@ pub async fn start_task(
                     "task": notify_task_desc,
                     "message": format!("Task '{}' {}", notify_task_desc, status_str),
                 });
+                if let Ok(outcome) = &result {
+                    data["pid"] = serde_json::Value::from(outcome.winner_pid);
+                }
                 if let Some(summary) = log_summary {
                     data["log_summary"] = serde_json::Value::String(summary);
                 }
@@ -315,20 +603,21 @@ pub async fn start_task(
                     )
                     .await
                 {
-                    Ok(_) => eprintln!("[aiw] Notification sent successfully"),
-                    Err(e) => eprintln!("[aiw] Failed to send notification: {:?}", e),
+                    Ok(_) => tracing::debug!("Notification sent successfully"),
+                    Err(e) => tracing::warn!("Failed to send notification: {:?}", e),
                 }
             } else {
-                eprintln!("[aiw] No peer available for notification");
+                tracing::debug!("No peer available for notification");
             }
 
             if let Err(err) = result {
-                eprintln!("[aiw] start_task: auto failover failed: {}", err);
+                tracing::warn!("start_task: auto failover failed: {}", err);
             }
-        });
+        };
+        tokio::spawn(launch.instrument(task_span));
     } else {
         // 非 Auto 模式：直接执行指定 CLI
-        let resolved_provider = params.provider.clone();
+        let resolved_provider = base.user_provider.clone();
         let prepared = task_prepare::finalize_for_entry(&base, cli_type.clone(), resolved_provider);
 
         let spawn_registry = registry.clone();
@@ -336,17 +625,37 @@ pub async fn start_task(
         let spawn_args = prepared.args.clone();
         let spawn_provider = prepared.provider.clone();
         let spawn_cwd = prepared.cwd.clone();
+        let spawn_env = prepared.env.clone();
+        let spawn_priority = prepared.priority;
 
-        tokio::spawn(async move {
-            let result = supervisor::execute_cli(
+        let launch = async move {
+            let _slot = slot;
+            let result = supervisor::execute_cli_with_env(
                 &spawn_registry,
                 &spawn_cli_type,
                 &spawn_args,
                 spawn_provider,
                 spawn_cwd,
+                &spawn_env,
+                spawn_priority,
             )
             .await;
 
+            if let Err(err) = &result {
+                let _ = launch_tx.send(err.to_string());
+            }
+
+            let bound = bound_rx.await.is_ok();
+            if bound && result.is_ok() {
+                if let Err(err) = supervisor::capture_task_result(
+                    &spawn_registry,
+                    &notify_task_id,
+                    result_extraction,
+                ) {
+                    tracing::warn!("start_task: failed to capture task result: {}", err);
+                }
+            }
+
             // 读取日志摘要
             let log_summary = read_log_summary_from_registry(&spawn_registry, &notify_task_id, 20);
 
@@ -355,7 +664,10 @@ pub async fn start_task(
                 Err(_) => (LoggingLevel::Error, "failed"),
             };
             if let Some(p) = notify_peer.read().await.as_ref() {
-                eprintln!("[aiw] Sending task completion notification for task_id={}", notify_task_id);
+                tracing::debug!(
+                    "Sending task completion notification for task_id={}",
+                    notify_task_id
+                );
                 let mut data = serde_json::json!({
                     "event": "task_completed",
                     "task_id": notify_task_id,
@@ -372,32 +684,45 @@ pub async fn start_task(
                     )
                     .await
                 {
-                    Ok(_) => eprintln!("[aiw] Notification sent successfully"),
-                    Err(e) => eprintln!("[aiw] Failed to send notification: {:?}", e),
+                    Ok(_) => tracing::debug!("Notification sent successfully"),
+                    Err(e) => tracing::warn!("Failed to send notification: {:?}", e),
                 }
             } else {
-                eprintln!("[aiw] No peer available for notification");
+                tracing::debug!("No peer available for notification");
             }
 
             if let Err(err) = result {
-                eprintln!(
+                tracing::warn!(
                     "start_task: failed to launch {} task: {}",
                     spawn_cli_type.display_name(),
                     err
                 );
             }
-        });
+        };
+        tokio::spawn(launch.instrument(task_span));
     }
 
-    let new_entry = wait_for_registry_entry(&registry, &existing).await?;
-    let entry = new_entry.ok_or_else(|| "Failed to register task in MCP registry".to_string())?;
+    let timeout = registration_timeout(params.registration_timeout_secs);
+    let new_entry = wait_for_registry_entry(&registry, &existing, timeout, &mut launch_rx).await?;
+    let entry = new_entry.ok_or_else(|| {
+        format!(
+            "Timed out after {}s waiting for the task to appear in the MCP registry \
+             (no launch error was reported; raise registration_timeout_secs if the CLI starts slowly)",
+            timeout.as_secs()
+        )
+    })?;
 
     // Bind UUID and worktree info to the registry entry
     registry.update_task_metadata(entry.pid, task_id.clone(), worktree_info.clone());
+    if let Some(name) = params.name.clone() {
+        registry.set_task_name(entry.pid, Some(name));
+    }
+    let _ = bound_tx.send(());
 
     Ok(TaskLaunchResult {
         task_id,
-        pid: entry.pid,
+        status: LaunchStatus::Running,
+        pid: Some(entry.pid),
         started_at: entry.record.started_at,
         worktree_info,
         log_file: Some(entry.record.log_path.clone()),
@@ -407,31 +732,194 @@ pub async fn start_task(
 fn registry_entry_to_task_info(entry: crate::storage::RegistryEntry) -> TaskInfo {
     TaskInfo {
         task_id: entry.record.task_id.clone(),
+        name: entry.record.name.clone(),
         pid: entry.pid,
         log_file: entry.record.log_path.clone(),
         status: entry.record.status.clone(),
         started_at: entry.record.started_at,
         completed_at: entry.record.completed_at,
+        duration_secs: entry.record.duration(Utc::now()).num_seconds(),
         cleanup_reason: entry.record.cleanup_reason.clone(),
         manager_pid: entry.record.manager_pid,
         exit_code: entry.record.exit_code,
         log_id: entry.record.log_id.clone(),
         result: entry.record.result.clone(),
         worktree_info: entry.record.worktree_info.clone(),
+        env_var_names: entry.record.env_var_names.clone(),
+        model: entry.record.model.clone(),
+        priority: entry.record.priority,
     }
 }
 
-/// Resolve a task_id to (pid, TaskRecord). Shared by stop/logs/status handlers.
+/// Resolve a task_id UUID or task name to (pid, TaskRecord). Shared by stop/logs/status handlers.
+///
+/// UUIDs win over names; a name carried by several tasks is rejected rather than guessed.
 fn resolve_task_id(task_id: &str) -> Result<(u32, crate::task_record::TaskRecord), String> {
     let registry = RegistryFactory::instance().get_mcp_registry();
-    registry
-        .get_by_task_id(task_id)
-        .ok_or_else(|| format!("task_id '{}' not found in MCP registry", task_id))
+    if let Some(found) = registry.get_by_task_id(task_id) {
+        return Ok(found);
+    }
+
+    let mut named = registry.find_by_name(task_id);
+    match named.len() {
+        1 => return Ok(named.remove(0)),
+        0 => {}
+        _ => {
+            let ids: Vec<String> = named
+                .iter()
+                .map(|(pid, record)| {
+                    record
+                        .task_id
+                        .clone()
+                        .unwrap_or_else(|| format!("pid {}", pid))
+                })
+                .collect();
+            return Err(format!(
+                "task name '{}' is ambiguous: it matches {} tasks ({}); use the task_id instead",
+                task_id,
+                named.len(),
+                ids.join(", ")
+            ));
+        }
+    }
+
+    if launch_limiter::global().queued(task_id).is_some() {
+        Err(format!(
+            "task_id '{}' is queued waiting for a free launch slot",
+            task_id
+        ))
+    } else if let Some(failed) = launch_limiter::global().failed(task_id) {
+        Err(format!(
+            "task_id '{}' failed to launch after waiting in the queue: {}",
+            task_id, failed.error
+        ))
+    } else {
+        Err(format!("task_id '{}' not found in MCP registry", task_id))
+    }
+}
+
+/// 调用方提供的 task_id 的最大字符数
+const MAX_TASK_ID_CHARS: usize = 128;
+
+fn validate_task_id(task_id: &str) -> Result<(), String> {
+    if task_id.is_empty() || task_id.chars().count() > MAX_TASK_ID_CHARS {
+        return Err(format!(
+            "Invalid task_id {:?}: must be 1-{} characters",
+            task_id, MAX_TASK_ID_CHARS
+        ));
+    }
+    if task_id.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return Err(format!(
+            "Invalid task_id {:?}: must not contain whitespace or control characters",
+            task_id
+        ));
+    }
+    Ok(())
+}
+
+/// 排队中或正在等待注册的 task_id；registry 在绑定之前还查不到它们
+fn launching_task_ids() -> &'static parking_lot::Mutex<HashSet<String>> {
+    static LAUNCHING: std::sync::OnceLock<parking_lot::Mutex<HashSet<String>>> =
+        std::sync::OnceLock::new();
+    LAUNCHING.get_or_init(Default::default)
+}
+
+/// 启动期间占用的 task_id，drop 时释放
+struct TaskIdClaim(String);
+
+impl Drop for TaskIdClaim {
+    fn drop(&mut self) {
+        launching_task_ids().lock().remove(&self.0);
+    }
+}
+
+/// 占用 task_id；同 id 的任务仍在排队、启动或尚未结束时返回错误
+///
+/// 已结束任务的 id 可以复用，注册新任务时旧记录的 task_id 会被清空。
+fn claim_task_id(task_id: &str) -> Result<TaskIdClaim, String> {
+    let mut launching = launching_task_ids().lock();
+    let registry = RegistryFactory::instance().get_mcp_registry();
+    if let Some((pid, record)) = registry.get_by_task_id(task_id) {
+        if !record.status.is_terminal() {
+            return Err(format!(
+                "task_id '{}' is already used by a task that has not finished (pid {})",
+                task_id, pid
+            ));
+        }
+    }
+    if !launching.insert(task_id.to_string()) {
+        return Err(format!(
+            "task_id '{}' is already used by a task that is queued or starting",
+            task_id
+        ));
+    }
+    launch_limiter::global().clear_failure(task_id);
+    Ok(TaskIdClaim(task_id.to_string()))
+}
+
+/// 任务名需非空、不含空白且不超过 64 字符，便于在对话里直接引用
+fn validate_task_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.chars().count() > 64 {
+        return Err(format!(
+            "Invalid task name '{}': must be 1-64 characters",
+            name
+        ));
+    }
+    if name.chars().any(char::is_whitespace) {
+        return Err(format!(
+            "Invalid task name '{}': must not contain whitespace",
+            name
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct ListTasksParams {
+    /// Only return tasks launched by this warden session, i.e. whose manager_pid
+    /// is the current process or one of its ancestors. Defaults to all tasks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owned_only: Option<bool>,
+}
+
+/// 当前会话的进程链（自身 + 祖先）；进程树不可读时仅包含自身
+fn session_pids() -> HashSet<u32> {
+    let mut pids: HashSet<u32> = ProcessTreeInfo::current()
+        .map(|tree| tree.process_chain.into_iter().collect())
+        .unwrap_or_default();
+    pids.insert(platform::current_pid());
+    pids
+}
+
+/// 任务是否由给定会话进程链中的某个进程启动
+pub fn is_owned_by(manager_pid: Option<u32>, session_pids: &HashSet<u32>) -> bool {
+    manager_pid.is_some_and(|pid| session_pids.contains(&pid))
 }
 
 pub async fn list_tasks() -> Result<Vec<TaskInfo>, String> {
+    list_tasks_filtered(ListTasksParams::default()).await
+}
+
+/// [`list_tasks`] with an optional `owned_only` filter; the global view stays the default.
+pub async fn list_tasks_filtered(params: ListTasksParams) -> Result<Vec<TaskInfo>, String> {
     let registry = RegistryFactory::instance().get_mcp_registry();
-    let entries = registry.entries().map_err(|e| e.to_string())?;
+    // 先对账，避免把已崩溃的进程报告为 running
+    registry
+        .reconcile_tasks(Utc::now(), platform::process_alive)
+        .map_err(|e| e.to_string())?;
+    let mut entries = registry.entries().map_err(|e| e.to_string())?;
+    // 按启动时间排序，保证分页游标在多次请求间稳定
+    entries.sort_by(|a, b| {
+        a.record
+            .started_at
+            .cmp(&b.record.started_at)
+            .then(a.pid.cmp(&b.pid))
+    });
+
+    if params.owned_only.unwrap_or(false) {
+        let owners = session_pids();
+        entries.retain(|entry| is_owned_by(entry.record.manager_pid, &owners));
+    }
 
     // Include all tasks (running + completed), not just alive processes
     Ok(entries
@@ -440,9 +928,20 @@ pub async fn list_tasks() -> Result<Vec<TaskInfo>, String> {
         .collect())
 }
 
+/// Paginated variant of [`list_tasks`]; `offset` comes from a decoded cursor.
+pub async fn list_tasks_paginated(
+    offset: usize,
+    page_size: usize,
+    params: ListTasksParams,
+) -> Result<pagination::Page<TaskInfo>, String> {
+    let tasks = list_tasks_filtered(params).await?;
+    Ok(pagination::paginate(tasks, offset, page_size))
+}
+
 pub async fn manage_task(params: ManageTaskParams) -> Result<ManageTaskResult, String> {
-    let task_id = params.task_id;
-    let (pid, record) = resolve_task_id(&task_id)?;
+    let (pid, record) = resolve_task_id(&params.task_id)?;
+    // 按名字引用时也回传 UUID
+    let task_id = record.task_id.clone().unwrap_or(params.task_id);
 
     match params.action {
         ManageAction::Status => {
@@ -451,11 +950,12 @@ pub async fn manage_task(params: ManageTaskParams) -> Result<ManageTaskResult, S
                 pid,
                 action: ManageAction::Status,
                 status: Some(record.status.clone()),
-                process_alive: Some(platform::process_alive(pid)),
+                process_alive: Some(record.process_alive(pid)),
                 exit_code: record.exit_code,
                 result: record.result.clone(),
                 started_at: Some(record.started_at),
                 completed_at: record.completed_at,
+                duration_secs: Some(record.duration(Utc::now()).num_seconds()),
                 log_file: Some(record.log_path.clone()),
                 log_content: None,
                 success: None,
@@ -464,8 +964,26 @@ pub async fn manage_task(params: ManageTaskParams) -> Result<ManageTaskResult, S
             })
         }
         ManageAction::Logs => {
+            // 先校验过滤条件，非法正则不必读日志
+            let filter = params
+                .pattern
+                .as_deref()
+                .map(|pattern| {
+                    LineFilter::new(
+                        pattern,
+                        params.pattern_is_regex,
+                        params.context_lines.unwrap_or(0),
+                    )
+                })
+                .transpose()?;
             let log_path = PathBuf::from(record.log_path.clone());
-            let content = crate::supervisor::read_task_logs(&log_path, params.tail_lines)?;
+            let mut content = crate::supervisor::read_task_logs(&log_path, params.tail_lines)?;
+            if params.strip_ansi.unwrap_or(false) {
+                content = crate::utils::ansi::strip_ansi(&content);
+            }
+            if let Some(filter) = filter {
+                content = filter.apply(&content);
+            }
 
             Ok(ManageTaskResult {
                 task_id,
@@ -477,6 +995,7 @@ pub async fn manage_task(params: ManageTaskParams) -> Result<ManageTaskResult, S
                 result: None,
                 started_at: None,
                 completed_at: None,
+                duration_secs: None,
                 log_file: Some(record.log_path),
                 log_content: Some(content),
                 success: None,
@@ -507,6 +1026,7 @@ pub async fn manage_task(params: ManageTaskParams) -> Result<ManageTaskResult, S
                 result: updated_record.result.clone(),
                 started_at: Some(updated_record.started_at),
                 completed_at: updated_record.completed_at,
+                duration_secs: Some(updated_record.duration(Utc::now()).num_seconds()),
                 log_file: None,
                 log_content: None,
                 success: Some(true),
@@ -517,151 +1037,456 @@ pub async fn manage_task(params: ManageTaskParams) -> Result<ManageTaskResult, S
     }
 }
 
-// ===== list_roles / list_providers =====
-
-#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
-pub struct ListRolesResult {
-    pub builtin_roles: Vec<String>,
-    pub user_roles: Vec<RoleInfo>,
-}
+// ===== get_task_output =====
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
-pub struct ListProvidersResult {
-    pub default_provider: String,
-    pub providers: Vec<ProviderSummary>,
+pub struct GetTaskOutputParams {
+    /// UUID task identifier or task name.
+    pub task_id: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
-pub struct ProviderSummary {
-    pub name: String,
-    pub enabled: bool,
+pub struct TaskOutputResult {
+    /// UUID task identifier.
+    pub task_id: String,
+    /// Registry task status.
+    pub status: TaskStatus,
+    /// Exit code if available.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub scenario: Option<String>,
+    pub exit_code: Option<i32>,
+    /// Whether `result` was parsed as JSON.
+    pub structured: bool,
+    /// Structured result extracted from the task output. Falls back to the
+    /// status string (e.g. "success") when no result block was found.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub compatible_with: Option<Vec<String>>,
+    pub result: Option<serde_json::Value>,
 }
 
-pub async fn list_roles() -> Result<ListRolesResult, String> {
-    let builtin_roles = list_builtin_roles();
-    let manager = RoleManager::new().map_err(|e| e.to_string())?;
-    let user_roles = manager
-        .list_all_roles()
-        .map_err(|e| e.to_string())?
-        .into_iter()
-        .map(|r| r.as_info())
-        .collect();
-    Ok(ListRolesResult {
-        builtin_roles,
-        user_roles,
-    })
-}
+/// Return only the task's extracted result, without its raw log.
+pub async fn get_task_output(params: GetTaskOutputParams) -> Result<TaskOutputResult, String> {
+    let (pid, record) = resolve_task_id(&params.task_id)?;
+    if record.status == TaskStatus::Running && record.process_alive(pid) {
+        return Err(format!(
+            "Task {} is still running; output is available once it completes",
+            params.task_id
+        ));
+    }
 
-pub async fn list_providers() -> Result<ListProvidersResult, String> {
-    let manager =
-        crate::provider::manager::ProviderManager::new().map_err(|e| e.to_string())?;
-    let default_name = manager
-        .get_default_provider()
-        .map(|(name, _)| name)
-        .unwrap_or_default();
-    let providers = manager
-        .list_providers()
-        .into_iter()
-        .map(|(name, p)| ProviderSummary {
-            name: name.clone(),
-            enabled: p.is_enabled(),
-            scenario: p.scenario.clone(),
-            compatible_with: p
-                .compatible_with
-                .as_ref()
-                .map(|v| v.iter().map(|t| t.to_string()).collect()),
-        })
-        .collect();
-    Ok(ListProvidersResult {
-        default_provider: default_name,
-        providers,
+    let parsed = record
+        .result
+        .as_deref()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(raw).ok())
+        .filter(|value| value.is_object() || value.is_array());
+    let structured = parsed.is_some();
+    let result = parsed.or_else(|| record.result.clone().map(serde_json::Value::String));
+
+    Ok(TaskOutputResult {
+        task_id: record.task_id.unwrap_or(params.task_id),
+        status: record.status,
+        exit_code: record.exit_code,
+        structured,
+        result,
     })
 }
 
-#[derive(Clone)]
-pub struct AgenticWardenMcpServer {
-    router: Arc<IntelligentRouter>,
-    tool_router: ToolRouter<Self>,
-    prompt_router: PromptRouter<Self>,
-    // Client capability detection
-    client_capabilities: Arc<RwLock<Option<ClientCapabilities>>>,
-    // Dynamic tool registry (SSOT for MCP tools)
-    tool_registry: Arc<DynamicToolRegistry>,
-    // Store peer for sending notifications
-    peer: Arc<RwLock<Option<rmcp::service::Peer<RoleServer>>>>,
-    js_executor: Arc<JsToolExecutor>,
+// ===== export_task =====
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ExportTaskParams {
+    /// UUID task identifier or task name.
+    pub task_id: String,
+    /// Where to write the tar.gz. Must be a new file under ~/.aiw/exports (relative paths resolve
+    /// there) or inside the task's worktree. Defaults to ~/.aiw/exports/task-<task_id>-<timestamp>.tar.gz.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
 }
 
-#[rmcp::tool_router(router = tool_router)]
-impl AgenticWardenMcpServer {
-    pub async fn bootstrap() -> Result<Self, String> {
-        let router = IntelligentRouter::initialize()
-            .await
-            .map_err(|e| format!("Failed to initialise intelligent router: {e}"))?;
-        let connection_pool = router.connection_pool();
+/// Bundle the task's log, record, command line, redacted env names and worktree info.
+pub async fn export_task(params: ExportTaskParams) -> Result<ExportSummary, String> {
+    let (pid, record) = resolve_task_id(&params.task_id)?;
+    let output = match params.output_path {
+        Some(path) => {
+            crate::task_export::checked_output_path(&record, &path).map_err(|e| e.to_string())?
+        }
+        None => crate::task_export::default_output_path(pid, &record).map_err(|e| e.to_string())?,
+    };
+    crate::task_export::export_task(pid, &record, &output).map_err(|e| format!("{:#}", e))
+}
 
-        // Use router's shared registry and extend with server's base tools
-        let registry = router
-            .dynamic_registry()
-            .ok_or_else(|| "Router dynamic registry not initialized".to_string())?;
-        let tool_router = Self::tool_router();
-        let base_tools = tool_router.list_all();
-        registry.extend_base_tools(base_tools).await;
+// ===== rename_task =====
 
-  
-        // Initialize conversation history store
-        let _db_path = Self::get_history_db_path()
-            .map_err(|e| format!("Failed to get history DB path: {e}"))?;
-        let boa_pool = Arc::new(
-            BoaRuntimePool::new()
-                .await
-                .map_err(|e| format!("Failed to initialize Boa runtime pool: {e}"))?,
-        );
-        let injector = Arc::new(McpFunctionInjector::new(connection_pool.clone()));
-        let js_executor = Arc::new(JsToolExecutor::new(Arc::clone(&boa_pool), injector));
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct RenameTaskParams {
+    /// UUID task identifier or the task's current name.
+    pub task_id: String,
+    /// New name for the task. Omit to remove the current name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
 
-        // Start config file watcher for hot reload
-        let config_path = dirs::home_dir()
+/// Attach, replace or remove a task's human-friendly name.
+pub async fn rename_task(params: RenameTaskParams) -> Result<TaskInfo, String> {
+    if let Some(name) = &params.name {
+        validate_task_name(name)?;
+    }
+    let (pid, mut record) = resolve_task_id(&params.task_id)?;
+    let registry = RegistryFactory::instance().get_mcp_registry();
+    if !registry.set_task_name(pid, params.name.clone()) {
+        return Err(format!(
+            "task_id '{}' not found in MCP registry",
+            params.task_id
+        ));
+    }
+    record.name = params.name;
+    Ok(registry_entry_to_task_info(crate::storage::RegistryEntry {
+        pid,
+        key: pid.to_string(),
+        record,
+    }))
+}
+
+// ===== get_tool_source =====
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct GetToolSourceParams {
+    /// Name of a dynamically registered tool.
+    pub name: String,
+}
+
+// ===== register_js_tool =====
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct RegisterJsToolParams {
+    /// Tool name; must not collide with a base tool.
+    pub name: String,
+    /// Description shown to the client in tools/list.
+    pub description: String,
+    /// JSON schema (object root) for the workflow input.
+    pub input_schema: serde_json::Value,
+    /// Workflow code defining `async function workflow(input)`; call MCP tools via `mcp.call(server, tool, args)`.
+    pub js_code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct RegisterJsToolResult {
+    pub name: String,
+    /// True when newly registered, false when an existing dynamic tool was replaced.
+    pub is_new: bool,
+    /// Non-fatal schema/code validation warnings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Validate a hand-written JS workflow and register it as a dynamic tool.
+pub async fn register_js_tool(
+    registry: &DynamicToolRegistry,
+    params: RegisterJsToolParams,
+) -> Result<RegisterJsToolResult, String> {
+    let name = params.name.trim().to_string();
+    if name.is_empty() {
+        return Err("name cannot be empty".to_string());
+    }
+    if registry.is_base_tool(&name).await {
+        return Err(format!("'{}' collides with a built-in tool", name));
+    }
+
+    let schema = SchemaValidator::validate(&params.input_schema);
+    if !schema.is_valid {
+        return Err(format!(
+            "input_schema is invalid: {}",
+            schema.errors.join("; ")
+        ));
+    }
+
+    let code = JsCodeValidator::validate(&params.js_code).map_err(|e| e.to_string())?;
+    if !code.passed {
+        return Err(format!(
+            "js_code failed validation: {}",
+            code.errors.join("; ")
+        ));
+    }
+
+    let is_new = registry
+        .register_js_tool(
+            name.clone(),
+            params.description,
+            params.input_schema,
+            params.js_code,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(RegisterJsToolResult {
+        name,
+        is_new,
+        warnings: schema.warnings.into_iter().chain(code.warnings).collect(),
+    })
+}
+
+// ===== list_discovered_tools =====
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct ListDiscoveredToolsParams {
+    /// Only report this MCP server (a name from mcp.json).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+}
+
+// ===== prune_tasks =====
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+pub struct PruneTasksParams {
+    /// Remove finished tasks older than this many seconds.
+    /// Falls back to AGENTIC_WARDEN_TASK_RETENTION_SEC, then 24h.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_secs: Option<u64>,
+    /// Keep at most this many finished tasks, evicting the oldest first.
+    /// Falls back to AGENTIC_WARDEN_MAX_COMPLETED_TASKS, then 200.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_completed_tasks: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct PruneTasksResult {
+    /// Number of registry entries removed.
+    pub removed_entries: usize,
+    /// Bytes freed by deleting log files.
+    pub reclaimed_bytes: u64,
+    /// PIDs of the removed entries.
+    pub removed_pids: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ReconcileTasksResult {
+    /// PIDs of running tasks whose process had vanished and were marked completed.
+    pub reconciled_pids: Vec<u32>,
+}
+
+/// Mark running MCP tasks whose process is gone as completed
+/// (`cleanup_reason = "process_vanished"`), keeping their exit code if one was recorded.
+pub async fn reconcile_tasks() -> Result<ReconcileTasksResult, String> {
+    let registry = RegistryFactory::instance().get_mcp_registry();
+    let events = registry
+        .reconcile_tasks(Utc::now(), platform::process_alive)
+        .map_err(|e| e.to_string())?;
+    Ok(ReconcileTasksResult {
+        reconciled_pids: events.into_iter().map(|event| event._pid).collect(),
+    })
+}
+
+/// Periodically reconcile the MCP registry so `list_tasks` never reports
+/// crashed processes as running, then prune expired finished tasks.
+fn spawn_task_reconciler() {
+    use crate::config::TASK_RECONCILE_INTERVAL;
+
+    tokio::spawn(async {
+        let mut ticker = tokio::time::interval(TASK_RECONCILE_INTERVAL);
+        loop {
+            ticker.tick().await;
+            match reconcile_tasks().await {
+                Ok(result) if !result.reconciled_pids.is_empty() => {
+                    tracing::info!("reconciled vanished task(s): {:?}", result.reconciled_pids)
+                }
+                Ok(_) => {}
+                Err(err) => tracing::warn!("task reconciliation failed: {}", err),
+            }
+            match prune_tasks(PruneTasksParams::default()).await {
+                Ok(result) if result.removed_entries > 0 => {
+                    tracing::info!("pruned finished task(s): {:?}", result.removed_pids)
+                }
+                Ok(_) => {}
+                Err(err) => tracing::warn!("task pruning failed: {}", err),
+            }
+        }
+    });
+}
+
+/// Remove finished tasks (and their log files) from the MCP registry.
+/// Only the record status counts: running tasks are never touched.
+pub async fn prune_tasks(params: PruneTasksParams) -> Result<PruneTasksResult, String> {
+    use crate::config::{
+        MAX_COMPLETED_TASKS_DEFAULT, MAX_COMPLETED_TASKS_ENV, TASK_RETENTION_DEFAULT,
+        TASK_RETENTION_ENV,
+    };
+
+    let max_age_secs = params
+        .max_age_secs
+        .or_else(|| read_env_u64(TASK_RETENTION_ENV))
+        .unwrap_or(TASK_RETENTION_DEFAULT.as_secs());
+    let max_completed = params
+        .max_completed_tasks
+        .or_else(|| read_env_u64(MAX_COMPLETED_TASKS_ENV).map(|v| v as usize))
+        .unwrap_or(MAX_COMPLETED_TASKS_DEFAULT);
+
+    let registry = RegistryFactory::instance().get_mcp_registry();
+    let report = registry
+        .prune_tasks(
+            Utc::now(),
+            chrono::Duration::seconds(max_age_secs.min(i64::MAX as u64) as i64),
+            max_completed,
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(PruneTasksResult {
+        removed_entries: report.removed_entries(),
+        reclaimed_bytes: report.reclaimed_bytes,
+        removed_pids: report.removed_pids,
+    })
+}
+
+// ===== registry_stats =====
+
+/// Aggregate counts over the MCP task registry (read-only).
+pub async fn registry_stats() -> Result<crate::registry::RegistryStats, String> {
+    let registry = RegistryFactory::instance().get_mcp_registry();
+    let entries = registry.entries().map_err(|e| e.to_string())?;
+    let mut stats = crate::registry::RegistryStats::collect(
+        entries.iter().map(|entry| &entry.record),
+        Utc::now(),
+    );
+    stats.queued = launch_limiter::global().queue_depth();
+    Ok(stats)
+}
+
+// ===== list_roles / list_providers =====
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ListRolesResult {
+    pub builtin_roles: Vec<String>,
+    pub user_roles: Vec<RoleInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ListProvidersResult {
+    pub default_provider: String,
+    pub providers: Vec<ProviderSummary>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub struct ProviderSummary {
+    pub name: String,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compatible_with: Option<Vec<String>>,
+}
+
+pub async fn list_roles() -> Result<ListRolesResult, String> {
+    let builtin_roles = list_builtin_roles();
+    let manager = RoleManager::new().map_err(|e| e.to_string())?;
+    let user_roles = manager
+        .list_all_roles()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|r| r.as_info())
+        .collect();
+    Ok(ListRolesResult {
+        builtin_roles,
+        user_roles,
+    })
+}
+
+pub async fn list_providers() -> Result<ListProvidersResult, String> {
+    let manager =
+        crate::provider::manager::ProviderManager::new().map_err(|e| e.to_string())?;
+    let default_name = manager
+        .get_default_provider()
+        .map(|(name, _)| name)
+        .unwrap_or_default();
+    let providers = manager
+        .list_providers()
+        .into_iter()
+        .map(|(name, p)| ProviderSummary {
+            name: name.clone(),
+            enabled: p.is_enabled(),
+            scenario: p.scenario.clone(),
+            compatible_with: p
+                .compatible_with
+                .as_ref()
+                .map(|v| v.iter().map(|t| t.to_string()).collect()),
+        })
+        .collect();
+    Ok(ListProvidersResult {
+        default_provider: default_name,
+        providers,
+    })
+}
+
+#[derive(Clone)]
+pub struct AgenticWardenMcpServer {
+    router: Arc<IntelligentRouter>,
+    tool_router: ToolRouter<Self>,
+    prompt_router: PromptRouter<Self>,
+    // Client capability detection
+    client_capabilities: Arc<RwLock<Option<ClientCapabilities>>>,
+    // Dynamic tool registry (SSOT for MCP tools)
+    tool_registry: Arc<DynamicToolRegistry>,
+    // Store peer for sending notifications
+    peer: Arc<RwLock<Option<rmcp::service::Peer<RoleServer>>>>,
+    js_executor: Arc<JsToolExecutor>,
+    // Request audit log (AIW_MCP_AUDIT), shared by all connections
+    audit: Option<Arc<AuditLog>>,
+}
+
+#[rmcp::tool_router(router = tool_router)]
+impl AgenticWardenMcpServer {
+    pub async fn bootstrap() -> Result<Self, String> {
+        let router = IntelligentRouter::initialize()
+            .await
+            .map_err(|e| format!("Failed to initialise intelligent router: {e}"))?;
+        let connection_pool = router.connection_pool();
+
+        // Use router's shared registry and extend with server's base tools
+        let registry = router
+            .dynamic_registry()
+            .ok_or_else(|| "Router dynamic registry not initialized".to_string())?;
+        let tool_router = Self::tool_router();
+        let base_tools = tool_router.list_all();
+        registry.extend_base_tools(base_tools).await;
+
+        // Routing history (conversation_history.db) is opened by the router itself
+        let boa_pool = Arc::new(
+            BoaRuntimePool::new()
+                .await
+                .map_err(|e| format!("Failed to initialize Boa runtime pool: {e}"))?,
+        );
+        let injector = Arc::new(McpFunctionInjector::new(connection_pool));
+        let js_executor = Arc::new(JsToolExecutor::new(Arc::clone(&boa_pool), injector));
+
+        // Start config file watcher for hot reload
+        let config_path = crate::utils::config_paths::aiw_config_dir()
             .ok_or_else(|| "Cannot find home directory".to_string())?
-            .join(".aiw")
             .join("mcp.json");
 
+        let router = Arc::new(router);
+        router.start_supervisor();
+
         if config_path.exists() {
             use crate::mcp_routing::config_watcher;
-            if let Err(e) = config_watcher::start_config_watcher(connection_pool, config_path).await
+            if let Err(e) =
+                config_watcher::start_config_watcher(Arc::clone(&router), config_path).await
             {
-                eprintln!("⚠️  Failed to start config watcher: {}", e);
+                tracing::warn!("Failed to start config watcher: {}", e);
             }
         }
 
+        spawn_task_reconciler();
+
         let prompt_router = Self::prompt_router();
 
         Ok(Self {
-            router: Arc::new(router),
+            router,
             tool_router,
             prompt_router,
             client_capabilities: Arc::new(RwLock::new(None)),
             tool_registry: registry,
             peer: Arc::new(RwLock::new(None)),
             js_executor,
+            audit: AuditLog::from_env().map(Arc::new),
         })
     }
 
-    fn get_history_db_path() -> Result<PathBuf, String> {
-        let config_dir = dirs::config_dir()
-            .ok_or_else(|| "Failed to get config directory".to_string())?
-            .join("aiw");
-
-        std::fs::create_dir_all(&config_dir)
-            .map_err(|e| format!("Failed to create config directory: {e}"))?;
-
-        Ok(config_dir.join("conversation_history.db"))
-    }
-
     /// Get all tool definitions (for testing and debugging)
     ///
     /// Returns all available MCP tools, including:
@@ -682,8 +1507,8 @@ impl AgenticWardenMcpServer {
         input_schema: serde_json::Value,
     ) -> Tool {
         let schema_map = match input_schema {
-            serde_json::Value::Object(map) => map,
-            _ => serde_json::Map::new(),
+            serde_json::Value::Object(map) if !SchemaValidator::is_schemaless(&map) => map,
+            _ => SchemaValidator::inferred_schema(),
         };
 
         Tool::new(name.to_string(), description.to_string(), Arc::new(schema_map))
@@ -696,6 +1521,7 @@ impl AgenticWardenMcpServer {
     pub async fn intelligent_route_tool(
         &self,
         params: Parameters<IntelligentRouteRequest>,
+        context: RequestContext<RoleServer>,
     ) -> Result<Json<IntelligentRouteResponse>, String> {
         use crate::mcp_routing::models::ExecutionMode;
 
@@ -705,11 +1531,11 @@ impl AgenticWardenMcpServer {
         // (only if not explicitly overridden by caller)
         if request.execution_mode == ExecutionMode::Dynamic {
             if let Some(caps) = self.client_capabilities.read().await.as_ref() {
-                if !caps.supports_dynamic_tools {
+                if caps.effective_execution_mode() == ExecutionMode::Query {
                     // Client doesn't support dynamic registration, use query mode
                     request.execution_mode = ExecutionMode::Query;
-                    eprintln!(
-                        "   ⚠️  Switching to Query mode (client doesn't support dynamic tools)"
+                    tracing::warn!(
+                        "Switching to Query mode (client doesn't support dynamic tools)"
                     );
                 }
             }
@@ -717,12 +1543,12 @@ impl AgenticWardenMcpServer {
 
         let mut response = self
             .router
-            .intelligent_route(request.clone())
+            .intelligent_route_with_cancel(request.clone(), context.ct.clone())
             .await
             .map_err(|err| err.to_string())?;
 
-        // Handle dynamic registration mode
-        if request.execution_mode == ExecutionMode::Dynamic {
+        // Handle dynamic registration mode (the client may have given up meanwhile)
+        if request.execution_mode == ExecutionMode::Dynamic && !context.ct.is_cancelled() {
             if let Some(ref selected) = response.selected_tool {
                 // Get the tool schema
                 let schema_response = self
@@ -745,35 +1571,35 @@ impl AgenticWardenMcpServer {
 
                     let is_new = self
                         .tool_registry
-                        .register_proxied_tool(
+                        .register_routed_tool(
                             selected.mcp_server.clone(),
                             selected.tool_name.clone(),
                             tool_definition,
+                            response.route_id,
                         )
                         .await
                         .map_err(|err| err.to_string())?;
 
                     // Send notification if this is a new tool
                     if is_new {
-                        eprintln!("📝 Dynamically registered tool: {}", selected.tool_name);
-
-                        // Send ToolListChangedNotification to client
-                        if self.peer.read().await.is_some() {
-                            // Note: Notification sending disabled due to rmcp API constraints
-                            // The client should re-query tools after receiving intelligent_route response
-                            eprintln!(
-                                "   📝 Tool '{}' registered - client should re-query tool list",
-                                selected.tool_name
-                            );
-                        }
+                        tracing::info!("Dynamically registered tool: {}", selected.tool_name);
+                        self.notify_tool_list_changed(&selected.tool_name).await;
                     }
 
                     response.tool_schema = Some(schema);
                     response.dynamically_registered = true;
-                    response.message = format!(
-                        "Tool '{}' registered. Call it directly with full context for accurate parameters.",
-                        selected.tool_name
-                    );
+                    response.schema_inferred = schema_response.schema_inferred;
+                    response.message = if schema_response.schema_inferred {
+                        format!(
+                            "Tool '{}' registered without a published input schema. Infer its arguments from the description and check the result carefully.",
+                            selected.tool_name
+                        )
+                    } else {
+                        format!(
+                            "Tool '{}' registered. Call it directly with full context for accurate parameters.",
+                            selected.tool_name
+                        )
+                    };
                 }
             }
         }
@@ -781,6 +1607,35 @@ impl AgenticWardenMcpServer {
         Ok(Json(response))
     }
 
+    /// 向支持动态工具的客户端发送 tools/list_changed；
+    /// 不支持或发送失败时退回到提示客户端重新查询工具列表
+    async fn notify_tool_list_changed(&self, tool_name: &str) {
+        let supports_dynamic_tools = self
+            .client_capabilities
+            .read()
+            .await
+            .as_ref()
+            .is_some_and(|caps| caps.supports_dynamic_tools);
+        let peer = self.peer.read().await.clone();
+
+        if let (true, Some(peer)) = (supports_dynamic_tools, peer) {
+            match peer.notify_tool_list_changed().await {
+                Ok(()) => {
+                    tracing::info!("Sent tools/list_changed for '{}'", tool_name);
+                    return;
+                }
+                Err(err) => {
+                    tracing::warn!("Failed to send tools/list_changed: {}", err);
+                }
+            }
+        }
+
+        tracing::info!(
+            "Tool '{}' registered - client should re-query tool list",
+            tool_name
+        );
+    }
+
     #[tool(
         name = "list_roles",
         description = "List all available roles (builtin + user-defined from ~/.aiw/role/). Roles inject system prompts into AI CLI tasks."
@@ -805,12 +1660,246 @@ impl AgenticWardenMcpServer {
         Ok(table_format::format_providers_table(&result))
     }
 
+    #[tool(
+        name = "get_task_output",
+        description = "Return only the structured result of a completed task, without its raw log. The result is extracted when the task exits successfully, using start_task's result_extraction: last_json (default, last JSON object printed), marker (last block between <<<AIW_RESULT>>> and <<<END_AIW_RESULT>>>) or none. structured=false means no result block was found and result holds the status string."
+    )]
+    pub async fn get_task_output_tool(
+        &self,
+        params: Parameters<GetTaskOutputParams>,
+    ) -> Result<Json<TaskOutputResult>, String> {
+        get_task_output(params.0).await.map(Json)
+    }
+
+    #[tool(
+        name = "export_task",
+        description = "Write a diagnostic tar.gz for a task: its log file, the serialized task record, the resolved command line, the names of injected provider/task environment variables (values redacted) and worktree info. Returns the archive path; output_path defaults to ~/.aiw/exports/."
+    )]
+    pub async fn export_task_tool(
+        &self,
+        params: Parameters<ExportTaskParams>,
+    ) -> Result<Json<ExportSummary>, String> {
+        export_task(params.0).await.map(Json)
+    }
+
+    #[tool(
+        name = "rename_task",
+        description = "Give a task a human-friendly name that task tools accept in place of its UUID task_id (e.g. \"auth-refactor\"). Omit name to remove it. Names need not be unique, but an ambiguous name is rejected when used; use the task_id then."
+    )]
+    pub async fn rename_task_tool(
+        &self,
+        params: Parameters<RenameTaskParams>,
+    ) -> Result<Json<TaskInfo>, String> {
+        rename_task(params.0).await.map(Json)
+    }
+
+    #[tool(
+        name = "get_tool_source",
+        description = "Inspect a dynamically registered tool before relying on it. For JS-orchestrated tools returns the generated js_code, its input_schema and the MCP functions the code calls (server/tool are omitted when not string literals). For proxied tools returns the downstream server and tool name instead."
+    )]
+    pub async fn get_tool_source_tool(
+        &self,
+        params: Parameters<GetToolSourceParams>,
+    ) -> Result<Json<ToolSource>, String> {
+        let name = params.0.name;
+        self.tool_registry
+            .get_tool(&name)
+            .await
+            .map(|registered| Json(registered.source()))
+            .ok_or_else(|| format!("'{}' is not a dynamically registered tool", name))
+    }
+
+    #[tool(
+        name = "register_js_tool",
+        description = "Register a hand-written JS workflow as a dynamic tool, bypassing LLM orchestration. js_code must define `async function workflow(input)` and may call `mcp.call(server, tool, args)`; it is syntax/security checked and dry-run before registration, and input_schema must have an object root. Names of built-in tools are rejected. Re-registering a dynamic tool replaces it (is_new=false); the oldest dynamic tool is evicted once the limit is reached."
+    )]
+    pub async fn register_js_tool_tool(
+        &self,
+        params: Parameters<RegisterJsToolParams>,
+    ) -> Result<Json<RegisterJsToolResult>, String> {
+        let result = register_js_tool(&self.tool_registry, params.0).await?;
+        tracing::info!(
+            "Manually registered JS tool '{}' (new: {})",
+            result.name,
+            result.is_new
+        );
+        self.notify_tool_list_changed(&result.name).await;
+        Ok(Json(result))
+    }
+
+    #[tool(
+        name = "prune_tasks",
+        description = "Remove finished tasks older than max_age_secs (default 24h) or beyond max_completed_tasks (default 200) from the task registry, deleting their log files. Running tasks are never removed."
+    )]
+    pub async fn prune_tasks_tool(
+        &self,
+        params: Parameters<PruneTasksParams>,
+    ) -> Result<String, String> {
+        let result = prune_tasks(params.0).await?;
+        Ok(format!(
+            "Pruned {} task(s), reclaimed {} bytes",
+            result.removed_entries, result.reclaimed_bytes
+        ))
+    }
+
+    #[tool(
+        name = "reconcile_tasks",
+        description = "Mark running tasks whose process has died without cleanup as completed (cleanup_reason: process_vanished). Runs automatically every 30s; call it to refresh task status immediately."
+    )]
+    pub async fn reconcile_tasks_tool(
+        &self,
+        _params: Parameters<EmptyParams>,
+    ) -> Result<String, String> {
+        let result = reconcile_tasks().await?;
+        if result.reconciled_pids.is_empty() {
+            return Ok("No vanished tasks found".to_string());
+        }
+        Ok(format!(
+            "Reconciled {} vanished task(s): {:?}",
+            result.reconciled_pids.len(),
+            result.reconciled_pids
+        ))
+    }
+
+    #[tool(
+        name = "registry_stats",
+        description = "Show aggregate task registry statistics: running/completed/failed counts, launches queued behind the concurrency limit, tasks per AI type, total log bytes on disk, oldest running task age, and worktree usage."
+    )]
+    pub async fn registry_stats_tool(
+        &self,
+        _params: Parameters<EmptyParams>,
+    ) -> Result<String, String> {
+        let stats = registry_stats().await?;
+        Ok(table_format::format_registry_stats_table(&stats))
+    }
+
+    #[tool(
+        name = "mcp_server_health",
+        description = "Show the up/down state of each configured downstream MCP server with its consecutive call failures, last error and seconds until the next automatic reconnect attempt. Servers go down after 3 consecutive failures or a crash, are skipped by routing and restarted automatically with backoff; status 'failed' means restarts were exhausted until mcp.json is saved again."
+    )]
+    pub async fn mcp_server_health_tool(
+        &self,
+        _params: Parameters<EmptyParams>,
+    ) -> Result<String, String> {
+        let health = self.router.connection_pool().health().await;
+        Ok(table_format::format_server_health_table(&health))
+    }
+
+    #[tool(
+        name = "list_discovered_tools",
+        description = "List the raw tools each downstream MCP server reported in tools/list, with descriptions, whether each tool made it into the routing index (indexed=false means intelligent_route can never select it) and whether it lacks an input schema. Servers that are down or failed discovery are included with their error. Pass server to inspect a single server."
+    )]
+    pub async fn list_discovered_tools_tool(
+        &self,
+        params: Parameters<ListDiscoveredToolsParams>,
+    ) -> Result<Json<DiscoveredToolsReport>, String> {
+        self.router
+            .discovered_tools_report(params.0.server.as_deref())
+            .await
+            .map(Json)
+            .map_err(|err| err.to_string())
+    }
+
+    #[tool(
+        name = "capabilities",
+        description = "Show the detected MCP client (name, version), whether it supports dynamic tool registration, the execution mode intelligent_route falls back to (dynamic or query), and the routing mode in effect (orchestration, vector_llm, or vector_only when the LLM was unreachable at startup). Use it to debug why routing behaves differently across clients."
+    )]
+    pub async fn capabilities_tool(
+        &self,
+        _params: Parameters<EmptyParams>,
+    ) -> Result<Json<CapabilitiesReport>, String> {
+        let dynamic_tool_count = self.get_dynamic_tool_count().await;
+        let capabilities = self.client_capabilities.read().await;
+        Ok(Json(CapabilitiesReport::new(
+            capabilities.as_ref(),
+            dynamic_tool_count,
+            self.router.routing_mode(),
+        )))
+    }
+
     pub async fn run(self) -> Result<(), Box<dyn std::error::Error>> {
-        eprintln!("🚀 Agentic-Warden intelligent MCP router ready (stdio transport)");
+        tracing::info!("Agentic-Warden intelligent MCP router ready (stdio transport)");
+        crate::utils::protocol_stdout::reserve_for_protocol();
         let transport = (tokio::io::stdin(), tokio::io::stdout());
-        self.serve(transport).await?.waiting().await?;
+        let service = self.clone().serve(transport).await?;
+        let cancel = service.cancellation_token();
+        let waiting = service.waiting();
+        tokio::pin!(waiting);
+
+        // 客户端关闭 stdin 或收到 SIGINT/SIGTERM 都会结束 stdio 循环
+        let result = tokio::select! {
+            result = &mut waiting => result,
+            signal = crate::signal::shutdown_signal() => {
+                if let Err(err) = signal {
+                    tracing::warn!("Failed to listen for shutdown signals: {}", err);
+                }
+                cancel.cancel();
+                waiting.await
+            }
+        };
+
+        self.shutdown().await;
+        result?;
         Ok(())
     }
+
+    /// 通过 TCP + SSE 提供服务，直到收到 SIGINT/SIGTERM
+    ///
+    /// 每个 SSE 连接拿到独立的服务实例：router、动态工具注册表和 JS 执行器共享，
+    /// 客户端能力与 peer 按连接隔离，保证通知只发给对应的客户端。
+    /// 该传输没有鉴权，非回环地址意味着任何能访问该端口的人都能启动 AI CLI 任务。
+    pub async fn run_sse(self, bind: SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
+        if !bind.ip().is_loopback() {
+            tracing::warn!(
+                "MCP SSE transport is bound to {} without authentication; anyone who can reach this port can launch AI CLI tasks",
+                bind
+            );
+        }
+
+        let server = SseServer::serve(bind).await?;
+        tracing::info!(
+            "Agentic-Warden intelligent MCP router ready (sse transport on http://{}/sse)",
+            bind
+        );
+
+        let this = self.clone();
+        let cancel = server.with_service(move || this.for_connection());
+        let signal = crate::signal::shutdown_signal().await;
+        cancel.cancel();
+        self.shutdown().await;
+        signal?;
+        Ok(())
+    }
+
+    /// 退出前终止本会话的在途任务，并停止所有下游 MCP 服务器
+    async fn shutdown(&self) {
+        match shutdown::settle_tasks().await {
+            Ok(report) => {
+                if !report.terminated.is_empty() {
+                    tracing::info!(
+                        "Terminated {} running task(s): {:?}",
+                        report.terminated.len(),
+                        report.terminated
+                    );
+                }
+            }
+            Err(err) => tracing::warn!("Failed to clean up running tasks: {}", err),
+        }
+
+        let stopped = self.router.connection_pool().shutdown().await;
+        if stopped > 0 {
+            tracing::info!("Stopped {} downstream MCP server(s)", stopped);
+        }
+    }
+
+    /// 为新连接复制服务实例，重置按连接区分的状态
+    fn for_connection(&self) -> Self {
+        Self {
+            client_capabilities: Arc::new(RwLock::new(None)),
+            peer: Arc::new(RwLock::new(None)),
+            ..self.clone()
+        }
+    }
 }
 
 #[rmcp::prompt_router]
@@ -848,18 +1937,18 @@ impl AgenticWardenMcpServer {
 
 impl ServerHandler for AgenticWardenMcpServer {
     async fn list_tools(
-        &self,
-        _request: Option<PaginatedRequestParams>,
-        _context: RequestContext<RoleServer>,
-    ) -> Result<rmcp::model::ListToolsResult, rmcp::ErrorData> {
-        let tools_snapshot = self.tool_registry.get_all_tool_definitions().await;
-        let tools = (*tools_snapshot).clone();
-
-        Ok(rmcp::model::ListToolsResult {
-            tools,
-            next_cursor: None,
-            meta: None,
-        })
+        &self,
+        request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::ListToolsResult, rmcp::ErrorData> {
+        let started_at = Utc::now();
+        let started = Instant::now();
+        let result = self.list_tools_page(request).await;
+        self.audit(
+            || AuditEntry::new("list_tools", started_at, started.elapsed()),
+            result.as_ref().err().map(|err| err.message.to_string()),
+        );
+        result
     }
 
     async fn list_prompts(
@@ -888,139 +1977,33 @@ impl ServerHandler for AgenticWardenMcpServer {
         request: CallToolRequestParams,
         context: RequestContext<RoleServer>,
     ) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
-        // First, try to call base tools via tool_router
-        if self.tool_router.has_route(&request.name) {
-            // This is a base tool, delegate to tool_router
-            let tool_context = ToolCallContext::new(self, request, context);
-            return self.tool_router.call(tool_context).await;
-        }
-
-        // Check if this is a dynamically registered tool
-        if let Some(registered) = self.tool_registry.get_tool(&request.name).await {
-            match registered {
-                RegisteredTool::ProxiedMcp(proxy) => {
-                    let result = self
-                        .router
-                        .execute_tool(crate::mcp_routing::models::ExecuteToolRequest {
-                            mcp_server: proxy.server.clone(),
-                            tool_name: proxy.original_name.clone(),
-                            arguments: serde_json::Value::Object(
-                                request.arguments.unwrap_or_default(),
-                            ),
-                            session_id: None,
-                        })
-                        .await
-                        .map_err(|e| {
-                            rmcp::ErrorData::internal_error(
-                                format!("Tool execution failed: {}", e),
-                                None,
-                            )
-                        })?;
-
-                    if result.success {
-                        self.tool_registry.record_execution(&request.name).await;
-                        let content_str = result
-                            .result
-                            .as_ref()
-                            .map(|r| serde_json::to_string_pretty(&r.output).unwrap_or_default())
-                            .unwrap_or_default();
-                        let structured = result.result.map(|r| r.output);
-
-                        let mut result = rmcp::model::CallToolResult::success(vec![
-                            rmcp::model::ContentBlock::text(content_str),
-                        ]);
-                        result.structured_content = structured;
-                        result.is_error = None;
-                        Ok(result)
-                    } else {
-                        Err(rmcp::ErrorData::internal_error(result.message, None))
-                    }
-                }
-                RegisteredTool::JsOrchestrated(js_tool) => {
-                    let input = serde_json::Value::Object(request.arguments.unwrap_or_default());
-                    let execution = self
-                        .js_executor
-                        .execute(&js_tool, input)
-                        .await
-                        .map_err(Self::map_js_tool_error)?;
-
-                    self.tool_registry.record_execution(&request.name).await;
-                    eprintln!(
-                        "⚙️  JS workflow '{}' completed in {} ms",
-                        request.name, execution.duration_ms
-                    );
-
-                    let output_str = serde_json::to_string_pretty(&execution.output)
-                        .unwrap_or_else(|_| execution.output.to_string());
-
-                    let mut result = rmcp::model::CallToolResult::success(vec![
-                        rmcp::model::ContentBlock::text(output_str),
-                    ]);
-                    result.structured_content = Some(execution.output);
-                    result.is_error = None;
-                    Ok(result)
-                }
-            }
-        } else {
-            // Fallback: try to proxy directly to a downstream MCP server.
-            // Supports "server::tool_name" format or looks up the tool in the
-            // full tool_registry by scanning all registered downstream tools.
-            let resolved = if let Some((s, t)) = request.name.split_once("::") {
-                Some((s.to_string(), t.to_string()))
-            } else {
-                // Search tool_registry for a matching tool name across all servers
-                let registry = self.router.tool_registry().read().await;
-                registry
-                    .keys()
-                    .find(|key| key.split("::").nth(1) == Some(request.name.as_ref()))
-                    .map(|key| {
-                        let parts: Vec<&str> = key.split("::").collect();
-                        (parts[0].to_string(), parts[1].to_string())
-                    })
-            };
-
-            let Some((server, tool_name)) = resolved else {
-                return Err(rmcp::ErrorData::method_not_found::<
-                    rmcp::model::CallToolRequestMethod,
-                >());
-            };
-
-            let result = self
-                .router
-                .execute_tool(crate::mcp_routing::models::ExecuteToolRequest {
-                    mcp_server: server,
-                    tool_name,
-                    arguments: serde_json::Value::Object(
-                        request.arguments.unwrap_or_default(),
-                    ),
-                    session_id: None,
-                })
-                .await
-                .map_err(|e| {
-                    rmcp::ErrorData::internal_error(
-                        format!("Direct proxy execution failed: {}", e),
-                        None,
-                    )
-                })?;
-
-            if result.success {
-                let content_str = result
-                    .result
-                    .as_ref()
-                    .map(|r| serde_json::to_string_pretty(&r.output).unwrap_or_default())
-                    .unwrap_or_default();
-                let structured = result.result.map(|r| r.output);
-
-                let mut result = rmcp::model::CallToolResult::success(vec![
-                    rmcp::model::ContentBlock::text(content_str),
-                ]);
-                result.structured_content = structured;
-                result.is_error = None;
-                Ok(result)
-            } else {
-                Err(rmcp::ErrorData::internal_error(result.message, None))
+        let started_at = Utc::now();
+        let started = Instant::now();
+        let audited = self
+            .audit
+            .as_ref()
+            .map(|_| (request.name.to_string(), request.arguments.clone()));
+
+        let result = self.dispatch_tool_call(request, context).await;
+
+        let error = match &result {
+            Err(err) => Some(err.message.to_string()),
+            Ok(result) if result.is_error == Some(true) => {
+                Some("tool returned an error result".to_string())
             }
+            Ok(_) => None,
+        };
+        if let Some((tool, arguments)) = audited {
+            self.audit(
+                || {
+                    AuditEntry::new("call_tool", started_at, started.elapsed())
+                        .tool(tool)
+                        .arguments(arguments.as_ref())
+                },
+                error,
+            );
         }
+        result
     }
 
     async fn initialize(
@@ -1028,13 +2011,22 @@ impl ServerHandler for AgenticWardenMcpServer {
         request: InitializeRequestParams,
         context: RequestContext<RoleServer>,
     ) -> Result<InitializeResult, rmcp::ErrorData> {
+        let started_at = Utc::now();
+        let started = Instant::now();
+
         // Create initial capabilities (before testing)
         let capabilities = ClientCapabilities::from_init_request(&request);
+        let client = format!(
+            "{} {}",
+            capabilities.client_name, capabilities.client_version
+        );
 
-        eprintln!("🔌 MCP Client connected:");
-        eprintln!("   Name: {}", capabilities.client_name);
-        eprintln!("   Version: {}", capabilities.client_version);
-        eprintln!("   Testing dynamic tools support...");
+        tracing::info!(
+            "MCP client connected: {} {}",
+            capabilities.client_name,
+            capabilities.client_version
+        );
+        tracing::debug!("Testing dynamic tools support...");
 
         // Clone Arc for background task
         let client_capabilities = Arc::clone(&self.client_capabilities);
@@ -1053,12 +2045,12 @@ impl ServerHandler for AgenticWardenMcpServer {
             if let Some(caps) = client_capabilities.write().await.as_mut() {
                 caps.supports_dynamic_tools = supports;
 
-                eprintln!(
-                    "   Mode: {}",
+                tracing::info!(
+                    "Mode: {}",
                     if supports {
-                        "✅ Dynamic registration (primary mode)"
+                        "Dynamic registration (primary mode)"
                     } else {
-                        "⚠️  Two-phase negotiation (fallback mode)"
+                        "Two-phase negotiation (fallback mode)"
                     }
                 );
             }
@@ -1083,7 +2075,7 @@ impl ServerHandler for AgenticWardenMcpServer {
         )
         .with_title("Agentic Warden MCP Server".to_string())
         .with_website_url("https://github.com/putao520/agentic-warden".to_string());
-        Ok(InitializeResult::new(capabilities)
+        let result = InitializeResult::new(capabilities)
             .with_protocol_version(request.protocol_version)
             .with_server_info(server_info)
             .with_instructions(concat!(
@@ -1096,8 +2088,15 @@ impl ServerHandler for AgenticWardenMcpServer {
                 "5. If you launched multiple tasks, track all task_ids and check/report results as needed.\n",
                 "6. Never assume a task is done without checking its status via manage_task.\n",
                 "7. If the user asked you to do something that involves background tasks, your job is not done until ALL tasks have completed and you have reported the results.\n",
-                "8. start_task returns log_file in status_message. Use manage_task with action='logs' to check real-time progress at any time."
-            ).to_string()))
+                "8. start_task returns log_file in status_message. Use manage_task with action='logs' to check real-time progress at any time.\n",
+                "9. When a task prints a final JSON result, use get_task_output to read just that result instead of the full log."
+            ).to_string());
+
+        self.audit(
+            || AuditEntry::new("initialize", started_at, started.elapsed()).client(client),
+            None,
+        );
+        Ok(result)
     }
 
     // =========================================================================
@@ -1111,9 +2110,12 @@ impl ServerHandler for AgenticWardenMcpServer {
     ) -> Result<CreateTaskResult, rmcp::ErrorData> {
         // Extract start_task params from the tool call arguments
         let args_value = serde_json::Value::Object(request.arguments.unwrap_or_default());
-        let params: StartTaskParams = serde_json::from_value(args_value).map_err(|e| {
+        let mut params: StartTaskParams = serde_json::from_value(args_value).map_err(|e| {
             rmcp::ErrorData::invalid_params(format!("Invalid start_task params: {}", e), None)
         })?;
+        if params.provider.is_none() {
+            params.provider = self.provider_by_scenario(&params);
+        }
 
         let peer = Arc::clone(&self.peer);
         let result = start_task(params, peer).await.map_err(|e| {
@@ -1127,24 +2129,37 @@ impl ServerHandler for AgenticWardenMcpServer {
             result.started_at.to_rfc3339(),
             now,
         )
-        .with_status_message(format!(
-            "Task launched. log_file: {}",
-            result.log_file.as_deref().unwrap_or("unknown")
-        ))
+        .with_status_message(match result.status {
+            LaunchStatus::Running => format!(
+                "Task launched. log_file: {}",
+                result.log_file.as_deref().unwrap_or("unknown")
+            ),
+            LaunchStatus::Queued => "Queued: waiting for a launch slot".to_string(),
+        })
         .with_poll_interval(2000);
         Ok(CreateTaskResult::new(task))
     }
 
     async fn list_tasks(
         &self,
-        _request: Option<PaginatedRequestParams>,
+        request: Option<PaginatedRequestParams>,
         _context: RequestContext<RoleServer>,
     ) -> Result<ListTasksResult, rmcp::ErrorData> {
-        let tasks = list_tasks().await.map_err(|e| {
-            rmcp::ErrorData::internal_error(format!("Failed to list tasks: {}", e), None)
-        })?;
+        let (offset, page_size) =
+            pagination::page_request(request.as_ref(), pagination::DEFAULT_TASKS_PAGE_SIZE)
+                .map_err(|e| rmcp::ErrorData::invalid_params(e, None))?;
+        let params = ListTasksParams {
+            owned_only: pagination::owned_only_request(request.as_ref()),
+        };
 
-        let mcp_tasks: Vec<rmcp::model::Task> = tasks
+        let page = list_tasks_paginated(offset, page_size, params)
+            .await
+            .map_err(|e| {
+                rmcp::ErrorData::internal_error(format!("Failed to list tasks: {}", e), None)
+            })?;
+
+        let mcp_tasks: Vec<rmcp::model::Task> = page
+            .items
             .into_iter()
             .map(|t| {
                 let (status, msg) = task_info_to_mcp_status(&t);
@@ -1161,9 +2176,10 @@ impl ServerHandler for AgenticWardenMcpServer {
             .collect();
 
         let mut result = ListTasksResult::new(mcp_tasks);
+        result.next_cursor = page.next_cursor;
         // rmcp 2.0 移除了 total 一等字段（spec 对齐），用 meta 承载 total 语义
         let mut meta = serde_json::Map::new();
-        meta.insert("total".to_string(), serde_json::json!(result.tasks.len() as u64));
+        meta.insert("total".to_string(), serde_json::json!(page.total as u64));
         result.meta = Some(rmcp::model::Meta(meta));
         Ok(result)
     }
@@ -1173,11 +2189,33 @@ impl ServerHandler for AgenticWardenMcpServer {
         request: GetTaskParams,
         _context: RequestContext<RoleServer>,
     ) -> Result<GetTaskResult, rmcp::ErrorData> {
+        if let Some(queued) = launch_limiter::global().queued(&request.task_id) {
+            let task = rmcp::model::Task::new(
+                request.task_id,
+                RmcpTaskStatus::Working,
+                queued.queued_at.to_rfc3339(),
+                Utc::now().to_rfc3339(),
+            )
+            .with_status_message("Queued: waiting for a launch slot")
+            .with_poll_interval(2000);
+            return Ok(GetTaskResult::new(task));
+        }
+        if let Some(failed) = launch_limiter::global().failed(&request.task_id) {
+            let task = rmcp::model::Task::new(
+                request.task_id,
+                RmcpTaskStatus::Failed,
+                failed.failed_at.to_rfc3339(),
+                failed.failed_at.to_rfc3339(),
+            )
+            .with_status_message(format!("Failed to launch: {}", failed.error));
+            return Ok(GetTaskResult::new(task));
+        }
+
         let (pid, record) = resolve_task_id(&request.task_id).map_err(|e| {
             rmcp::ErrorData::invalid_params(e, None)
         })?;
 
-        let alive = platform::process_alive(pid);
+        let alive = record.process_alive(pid);
         let (status, msg) = record_to_mcp_status(&record, alive);
 
         let task = rmcp::model::Task::new(
@@ -1203,7 +2241,7 @@ impl ServerHandler for AgenticWardenMcpServer {
             rmcp::ErrorData::invalid_params(e, None)
         })?;
 
-        let alive = platform::process_alive(pid);
+        let alive = record.process_alive(pid);
         if alive {
             return Err(rmcp::ErrorData::internal_error(
                 "Task is still running; result not yet available",
@@ -1212,7 +2250,8 @@ impl ServerHandler for AgenticWardenMcpServer {
         }
 
         // Read log content as the task result
-        let log_content = fs::read_to_string(&record.log_path).unwrap_or_default();
+        let log_path = PathBuf::from(&record.log_path);
+        let log_content = crate::supervisor::read_task_logs(&log_path, None).unwrap_or_default();
         let payload = serde_json::json!({
             "task_id": request.task_id,
             "pid": pid,
@@ -1232,10 +2271,27 @@ impl ServerHandler for AgenticWardenMcpServer {
         request: CancelTaskParams,
         _context: RequestContext<RoleServer>,
     ) -> Result<CancelTaskResult, rmcp::ErrorData> {
+        // 尚未启动的任务只需移出队列，等待中的启动随后会放弃
+        if let Some(queued) = launch_limiter::global().queued(&request.task_id) {
+            launch_limiter::global().dequeue(&request.task_id);
+            let task = rmcp::model::Task::new(
+                request.task_id,
+                RmcpTaskStatus::Cancelled,
+                queued.queued_at.to_rfc3339(),
+                Utc::now().to_rfc3339(),
+            )
+            .with_status_message("Cancelled before launch");
+            return Ok(CancelTaskResult::new(task));
+        }
+
         let manage_params = ManageTaskParams {
             task_id: request.task_id.clone(),
             action: ManageAction::Stop,
             tail_lines: None,
+            strip_ansi: None,
+            pattern: None,
+            pattern_is_regex: false,
+            context_lines: None,
         };
 
         manage_task(manage_params).await.map_err(|e| {
@@ -1247,7 +2303,7 @@ impl ServerHandler for AgenticWardenMcpServer {
             rmcp::ErrorData::internal_error(e, None)
         })?;
 
-        let alive = platform::process_alive(pid);
+        let alive = record.process_alive(pid);
         let (status, msg) = record_to_mcp_status(&record, alive);
 
         let task = rmcp::model::Task::new(
@@ -1307,6 +2363,170 @@ fn record_to_mcp_status(
 }
 
 impl AgenticWardenMcpServer {
+    async fn list_tools_page(
+        &self,
+        request: Option<PaginatedRequestParams>,
+    ) -> Result<rmcp::model::ListToolsResult, rmcp::ErrorData> {
+        let (offset, page_size) =
+            pagination::page_request(request.as_ref(), pagination::DEFAULT_TOOLS_PAGE_SIZE)
+                .map_err(|e| rmcp::ErrorData::invalid_params(e, None))?;
+
+        let tools_snapshot = self.tool_registry.get_all_tool_definitions().await;
+        let page = pagination::paginate((*tools_snapshot).clone(), offset, page_size);
+
+        Ok(rmcp::model::ListToolsResult {
+            tools: page.items,
+            next_cursor: page.next_cursor,
+            meta: None,
+        })
+    }
+
+    /// Base tools first, then dynamic tools, then direct `server::tool` proxying
+    async fn dispatch_tool_call(
+        &self,
+        request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<rmcp::model::CallToolResult, rmcp::ErrorData> {
+        // First, try to call base tools via tool_router
+        if self.tool_router.has_route(&request.name) {
+            // This is a base tool, delegate to tool_router
+            let tool_context = ToolCallContext::new(self, request, context);
+            return self.tool_router.call(tool_context).await;
+        }
+
+        // Check if this is a dynamically registered tool
+        if let Some(registered) = self.tool_registry.get_tool(&request.name).await {
+            match registered {
+                RegisteredTool::ProxiedMcp(proxy) => {
+                    let arguments =
+                        serde_json::Value::Object(request.arguments.unwrap_or_default());
+                    self.check_proxied_arguments(&request.name, &proxy.tool, &arguments)?;
+
+                    let result = self
+                        .router
+                        .execute_tool(crate::mcp_routing::models::ExecuteToolRequest {
+                            mcp_server: proxy.server.clone(),
+                            tool_name: proxy.original_name.clone(),
+                            arguments,
+                            session_id: None,
+                            route_id: proxy.route_id,
+                        })
+                        .await
+                        .map_err(|e| {
+                            rmcp::ErrorData::internal_error(
+                                format!("Tool execution failed: {}", e),
+                                None,
+                            )
+                        })?;
+
+                    if result.success {
+                        self.tool_registry.record_execution(&request.name).await;
+                        Ok(self.bounded_output(&request.name, result.result.map(|r| r.output)))
+                    } else {
+                        Err(rmcp::ErrorData::internal_error(result.message, None))
+                    }
+                }
+                RegisteredTool::JsOrchestrated(js_tool) => {
+                    let input = serde_json::Value::Object(request.arguments.unwrap_or_default());
+                    let execution = self
+                        .js_executor
+                        .execute(&js_tool, input)
+                        .await
+                        .map_err(Self::map_js_tool_error)?;
+
+                    self.tool_registry.record_execution(&request.name).await;
+                    tracing::info!(
+                        "JS workflow '{}' completed in {} ms",
+                        request.name,
+                        execution.duration_ms
+                    );
+
+                    Ok(self.bounded_output(&request.name, Some(execution.output)))
+                }
+            }
+        } else {
+            // Fallback: try to proxy directly to a downstream MCP server.
+            // Supports "server::tool_name" format or looks up the tool in the
+            // full tool_registry by scanning all registered downstream tools.
+            let resolved = if let Some((s, t)) = request.name.split_once("::") {
+                Some((s.to_string(), t.to_string()))
+            } else {
+                // Search tool_registry for a matching tool name across all servers
+                let registry = self.router.tool_registry().read().await;
+                registry
+                    .keys()
+                    .find(|key| key.split("::").nth(1) == Some(request.name.as_ref()))
+                    .map(|key| {
+                        let parts: Vec<&str> = key.split("::").collect();
+                        (parts[0].to_string(), parts[1].to_string())
+                    })
+            };
+
+            let Some((server, tool_name)) = resolved else {
+                return Err(rmcp::ErrorData::method_not_found::<
+                    rmcp::model::CallToolRequestMethod,
+                >());
+            };
+
+            let result = self
+                .router
+                .execute_tool(crate::mcp_routing::models::ExecuteToolRequest {
+                    mcp_server: server,
+                    tool_name,
+                    arguments: serde_json::Value::Object(
+                        request.arguments.unwrap_or_default(),
+                    ),
+                    session_id: None,
+                    route_id: None,
+                })
+                .await
+                .map_err(|e| {
+                    rmcp::ErrorData::internal_error(
+                        format!("Direct proxy execution failed: {}", e),
+                        None,
+                    )
+                })?;
+
+            if result.success {
+                Ok(self.bounded_output(&request.name, result.result.map(|r| r.output)))
+            } else {
+                Err(rmcp::ErrorData::internal_error(result.message, None))
+            }
+        }
+    }
+
+    /// Tool output as a CallToolResult, truncated to the configured result limits
+    fn bounded_output(
+        &self,
+        tool: &str,
+        output: Option<serde_json::Value>,
+    ) -> rmcp::model::CallToolResult {
+        match output {
+            Some(output) => {
+                result_limit::bounded_result(tool, output, self.router.result_limits())
+            }
+            None => {
+                let mut result = rmcp::model::CallToolResult::success(vec![
+                    rmcp::model::ContentBlock::text(String::new()),
+                ]);
+                result.is_error = None;
+                result
+            }
+        }
+    }
+
+    /// Append one entry to the audit log when AIW_MCP_AUDIT is on
+    fn audit(&self, entry: impl FnOnce() -> AuditEntry, error: Option<String>) {
+        let Some(log) = &self.audit else {
+            return;
+        };
+        let entry = entry();
+        log.record(&match error {
+            Some(error) => entry.failed(error),
+            None => entry,
+        });
+    }
+
     fn map_js_tool_error(err: Error) -> rmcp::ErrorData {
         let message = err.to_string();
         let lowered = message.to_ascii_lowercase();
@@ -1320,4 +2540,50 @@ impl AgenticWardenMcpServer {
 
         rmcp::ErrorData::internal_error(format!("{prefix}: {message}"), None)
     }
+
+    /// Provider chosen from `providers.json` scenarios for a task that named none
+    ///
+    /// Only applies to a concrete AI type; `auto` tasks pick providers per
+    /// failover entry instead.
+    fn provider_by_scenario(&self, params: &StartTaskParams) -> Option<String> {
+        let ai_type = params.ai_type.as_ref().filter(|ai| **ai != AiType::Auto)?;
+        let manager = crate::provider::ProviderManager::new().ok()?;
+        let selected =
+            manager.select_by_scenario(ai_type, &params.task, self.router.embedder().as_ref())?;
+        tracing::info!("start_task: provider '{}' selected by scenario", selected);
+        Some(selected)
+    }
+
+    /// Check proxied tool arguments against the tool's input schema before
+    /// dispatch, so the caller sees which fields are wrong instead of a
+    /// downstream server error.
+    fn check_proxied_arguments(
+        &self,
+        name: &str,
+        tool: &rmcp::model::Tool,
+        arguments: &serde_json::Value,
+    ) -> Result<(), rmcp::ErrorData> {
+        let mode = self.router.argument_validation();
+        if mode == ArgumentValidation::Off {
+            return Ok(());
+        }
+        let violations = SchemaValidator::validate_arguments(&tool.input_schema, arguments);
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        let summary = violations
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        if mode == ArgumentValidation::Warn {
+            tracing::warn!("{}: arguments do not match input schema: {}", name, summary);
+            return Ok(());
+        }
+        Err(rmcp::ErrorData::invalid_params(
+            format!("Invalid arguments for '{}': {}", name, summary),
+            Some(serde_json::json!({ "tool": name, "violations": violations })),
+        ))
+    }
 }
@@ -0,0 +1,147 @@
+//! 基于游标的分页辅助（list_tools / list_tasks 共用）
+//!
+//! 游标是对偏移量的不透明编码，客户端只需原样回传 `next_cursor`。
+//! 页大小通过请求 `_meta.pageSize` 指定，缺省使用调用方给出的默认值。
+//! list_tasks 另外接受 `_meta.ownedOnly`，只列出当前会话启动的任务。
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use rmcp::model::PaginatedRequestParams;
+
+const CURSOR_PREFIX: &str = "offset:";
+const PAGE_SIZE_META_KEY: &str = "pageSize";
+const OWNED_ONLY_META_KEY: &str = "ownedOnly";
+
+/// list_tools 默认每页条数
+pub const DEFAULT_TOOLS_PAGE_SIZE: usize = 100;
+/// list_tasks 默认每页条数
+pub const DEFAULT_TASKS_PAGE_SIZE: usize = 50;
+/// 单页上限，防止客户端一次请求过多
+pub const MAX_PAGE_SIZE: usize = 500;
+
+/// 单页结果
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub total: usize,
+}
+
+pub fn encode_cursor(offset: usize) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{CURSOR_PREFIX}{offset}"))
+}
+
+pub fn decode_cursor(cursor: &str) -> Result<usize, String> {
+    let invalid = || format!("Invalid pagination cursor: {}", cursor);
+    let raw = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let text = String::from_utf8(raw).map_err(|_| invalid())?;
+    text.strip_prefix(CURSOR_PREFIX)
+        .and_then(|offset| offset.parse::<usize>().ok())
+        .ok_or_else(invalid)
+}
+
+/// 从请求参数中提取 (偏移量, 页大小)
+pub fn page_request(
+    request: Option<&PaginatedRequestParams>,
+    default_page_size: usize,
+) -> Result<(usize, usize), String> {
+    let Some(request) = request else {
+        return Ok((0, default_page_size));
+    };
+
+    let offset = match request.cursor.as_deref() {
+        Some(cursor) => decode_cursor(cursor)?,
+        None => 0,
+    };
+    let page_size = request
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.0.get(PAGE_SIZE_META_KEY))
+        .and_then(|value| value.as_u64())
+        .map(|size| size as usize)
+        .unwrap_or(default_page_size);
+
+    Ok((offset, page_size))
+}
+
+/// 从请求 `_meta` 中读取 list_tasks 的 `ownedOnly` 过滤开关
+pub fn owned_only_request(request: Option<&PaginatedRequestParams>) -> Option<bool> {
+    request
+        .and_then(|request| request.meta.as_ref())
+        .and_then(|meta| meta.0.get(OWNED_ONLY_META_KEY))
+        .and_then(|value| value.as_bool())
+}
+
+/// 按偏移量切出一页，剩余数据时返回下一页游标
+pub fn paginate<T>(items: Vec<T>, offset: usize, page_size: usize) -> Page<T> {
+    let total = items.len();
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let start = offset.min(total);
+    let end = start.saturating_add(page_size).min(total);
+
+    let next_cursor = (end < total).then(|| encode_cursor(end));
+    let items = items.into_iter().skip(start).take(end - start).collect();
+
+    Page {
+        items,
+        next_cursor,
+        total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cursor_roundtrip() {
+        for offset in [0, 1, 42, 10_000] {
+            assert_eq!(decode_cursor(&encode_cursor(offset)).unwrap(), offset);
+        }
+    }
+
+    #[test]
+    fn rejects_garbage_cursor() {
+        assert!(decode_cursor("not-a-cursor").is_err());
+        assert!(decode_cursor(&URL_SAFE_NO_PAD.encode("offset:abc")).is_err());
+    }
+
+    #[test]
+    fn paginate_walks_all_items() {
+        let items: Vec<u32> = (0..7).collect();
+
+        let first = paginate(items.clone(), 0, 3);
+        assert_eq!(first.items, vec![0, 1, 2]);
+        assert_eq!(first.total, 7);
+
+        let offset = decode_cursor(first.next_cursor.as_deref().unwrap()).unwrap();
+        let second = paginate(items.clone(), offset, 3);
+        assert_eq!(second.items, vec![3, 4, 5]);
+
+        let offset = decode_cursor(second.next_cursor.as_deref().unwrap()).unwrap();
+        let last = paginate(items, offset, 3);
+        assert_eq!(last.items, vec![6]);
+        assert!(last.next_cursor.is_none());
+    }
+
+    #[test]
+    fn paginate_past_end_is_empty() {
+        let page = paginate(vec![1, 2, 3], 10, 5);
+        assert!(page.items.is_empty());
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[test]
+    fn page_request_defaults_without_params() {
+        assert_eq!(page_request(None, 25).unwrap(), (0, 25));
+    }
+
+    #[test]
+    fn owned_only_is_read_from_meta() {
+        assert_eq!(owned_only_request(None), None);
+
+        let request: PaginatedRequestParams =
+            serde_json::from_value(serde_json::json!({ "_meta": { "ownedOnly": true } })).unwrap();
+        assert_eq!(owned_only_request(Some(&request)), Some(true));
+    }
+}
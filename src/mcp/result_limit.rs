@@ -0,0 +1,111 @@
+//! 代理工具结果的大小限制
+//!
+//! 下游工具的输出会被格式化成 JSON 文本返回给客户端，没有上限时几 MB 的结果会拖垮客户端和日志。
+//! 文本超过 `max_result_bytes` 时截断并附上说明原始大小的标记；structuredContent
+//! 单独以 `max_structured_result_bytes` 为上限，只有完整放得下时才原样返回，不做截断。
+//! 两个上限都可以在 mcp.json 的 `routing` 段配置（`aiw routing config set`）。
+
+use crate::mcp_routing::config::ResultLimits;
+use rmcp::model::{CallToolResult, ContentBlock};
+use serde_json::Value;
+
+/// 把工具输出转换为成功的 CallToolResult，按 `limits` 截断文本、取舍结构化内容
+pub fn bounded_result(tool: &str, output: Value, limits: ResultLimits) -> CallToolResult {
+    let mut text = serde_json::to_string_pretty(&output).unwrap_or_else(|_| output.to_string());
+    let text_bytes = text.len();
+    if text_bytes > limits.max_result_bytes {
+        tracing::warn!(
+            "Result of '{}' is {} bytes; truncated to max_result_bytes ({})",
+            tool,
+            text_bytes,
+            limits.max_result_bytes
+        );
+        truncate_at_char_boundary(&mut text, limits.max_result_bytes);
+        text.push_str(&format!(
+            "\n\n[... truncated: result is {} bytes, showing the first {}. Raise routing.max_result_bytes to see more]",
+            text_bytes,
+            text.len()
+        ));
+    }
+
+    let structured_bytes = serde_json::to_vec(&output)
+        .map(|bytes| bytes.len())
+        .unwrap_or(usize::MAX);
+    let structured = if structured_bytes <= limits.max_structured_result_bytes {
+        Some(output)
+    } else {
+        tracing::warn!(
+            "Structured result of '{}' is {} bytes; omitted (max_structured_result_bytes {})",
+            tool,
+            structured_bytes,
+            limits.max_structured_result_bytes
+        );
+        text.push_str(&format!(
+            "\n[structured content omitted: {} bytes exceeds routing.max_structured_result_bytes ({})]",
+            structured_bytes, limits.max_structured_result_bytes
+        ));
+        None
+    };
+
+    let mut result = CallToolResult::success(vec![ContentBlock::text(text)]);
+    result.structured_content = structured;
+    result.is_error = None;
+    result
+}
+
+/// 截到不超过 `max_bytes` 的最近字符边界，避免切开多字节字符
+fn truncate_at_char_boundary(text: &mut String, max_bytes: usize) {
+    let mut end = max_bytes.min(text.len());
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn text_of(result: &CallToolResult) -> String {
+        let value = serde_json::to_value(result).unwrap();
+        value["content"][0]["text"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn small_results_pass_through_unchanged() {
+        let output = json!({"files": ["a.txt", "b.txt"]});
+        let result = bounded_result("list_files", output.clone(), ResultLimits::default());
+
+        assert_eq!(
+            text_of(&result),
+            serde_json::to_string_pretty(&output).unwrap()
+        );
+        assert_eq!(result.structured_content, Some(output));
+    }
+
+    #[test]
+    fn large_results_are_truncated_and_structured_content_dropped_over_its_cap() {
+        let output = json!({"content": "é".repeat(4000)});
+        let limits = ResultLimits {
+            max_result_bytes: 1001,
+            max_structured_result_bytes: 16 * 1024,
+        };
+
+        let result = bounded_result("read_file", output.clone(), limits);
+        let text = text_of(&result);
+        let (kept, marker) = text.split_once("\n\n[... truncated").unwrap();
+        assert!(kept.len() <= 1001);
+        assert!(marker.contains("result is 8019 bytes"), "{marker}");
+        // 文本被截断，但完整的结构化内容仍在上限内
+        assert_eq!(result.structured_content, Some(output.clone()));
+
+        let limits = ResultLimits {
+            max_result_bytes: 1001,
+            max_structured_result_bytes: 1024,
+        };
+        let result = bounded_result("read_file", output, limits);
+        assert!(result.structured_content.is_none());
+        assert!(text_of(&result).contains("[structured content omitted: 8014 bytes"));
+    }
+}
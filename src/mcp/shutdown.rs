@@ -0,0 +1,125 @@
+//! MCP server 退出时的任务处理
+//!
+//! 收到 SIGINT/SIGTERM 后，终止由本进程启动、仍在运行的任务所在的进程组，
+//! 并把任务标记为 `manager_shutdown`。
+//!
+//! 任务无法脱离 warden 继续运行：输出经管道由 warden 写入日志，子进程设置了
+//! `kill_on_drop`，Linux 上还有 `PR_SET_PDEATHSIG`。与其留下写不进日志、
+//! 也没人标记完成的进程，不如在退出前明确终止。
+
+use crate::config::SHUTDOWN_GRACE_PERIOD;
+use crate::platform;
+use crate::registry_factory::RegistryFactory;
+use crate::storage::RegistryEntry;
+use crate::task_record::TaskStatus;
+use chrono::Utc;
+use tokio::time::{sleep, Duration, Instant};
+
+/// 被 warden 退出终止的任务写入的 result
+pub const MANAGER_SHUTDOWN_RESULT: &str = "manager_shutdown";
+
+/// 退出处理结果
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// 已终止并标记完成的任务 PID
+    pub terminated: Vec<u32>,
+}
+
+/// 由 `manager_pid` 启动且仍在运行的任务 PID
+pub fn in_flight_pids(entries: &[RegistryEntry], manager_pid: u32) -> Vec<u32> {
+    entries
+        .iter()
+        .filter(|entry| {
+            entry.record.status == TaskStatus::Running
+                && entry.record.manager_pid == Some(manager_pid)
+                && !entry.record.pid_reused(entry.pid)
+        })
+        .map(|entry| entry.pid)
+        .collect()
+}
+
+/// 终止本进程启动的在途任务：先 SIGTERM，宽限期后 SIGKILL
+pub async fn settle_tasks() -> Result<ShutdownReport, String> {
+    let registry = RegistryFactory::instance().get_mcp_registry();
+    let entries = registry.entries().map_err(|e| e.to_string())?;
+    let pids = in_flight_pids(&entries, platform::current_pid());
+
+    for &pid in &pids {
+        signal_group(pid, false);
+    }
+
+    let deadline = Instant::now() + SHUTDOWN_GRACE_PERIOD;
+    while Instant::now() < deadline && pids.iter().any(|&pid| platform::process_alive(pid)) {
+        sleep(Duration::from_millis(200)).await;
+    }
+
+    let now = Utc::now();
+    for &pid in &pids {
+        if platform::process_alive(pid) {
+            signal_group(pid, true);
+        }
+        registry
+            .mark_completed(pid, Some(MANAGER_SHUTDOWN_RESULT.to_string()), None, now)
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(ShutdownReport { terminated: pids })
+}
+
+/// 向任务的进程组发信号（任务进程启动时已 `setpgid(0, 0)`，PGID 即 PID），
+/// 使 AI CLI 派生的子进程一并退出
+#[cfg(unix)]
+fn signal_group(pid: u32, force: bool) {
+    use nix::sys::signal::{kill, killpg, Signal};
+    use nix::unistd::Pid;
+
+    let signal = if force {
+        Signal::SIGKILL
+    } else {
+        Signal::SIGTERM
+    };
+    let pid = Pid::from_raw(pid as i32);
+    if killpg(pid, signal).is_err() {
+        // 进程组已不存在时退回到单个进程
+        let _ = kill(pid, signal);
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_group(pid: u32, _force: bool) {
+    platform::terminate_process(pid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_record::TaskRecord;
+
+    fn entry(pid: u32, manager_pid: u32, status: TaskStatus) -> RegistryEntry {
+        let mut record = TaskRecord::new(
+            Utc::now(),
+            pid.to_string(),
+            format!("/tmp/{}.log", pid),
+            Some(manager_pid),
+        );
+        record.status = status;
+        RegistryEntry {
+            pid,
+            key: pid.to_string(),
+            record,
+        }
+    }
+
+    #[test]
+    fn only_running_tasks_of_this_manager_are_in_flight() {
+        let entries = vec![
+            entry(10, 1, TaskStatus::Running),
+            entry(11, 1, TaskStatus::CompletedButUnread),
+            entry(12, 2, TaskStatus::Running),
+            entry(13, 1, TaskStatus::Running),
+        ];
+
+        assert_eq!(in_flight_pids(&entries, 1), vec![10, 13]);
+        assert!(in_flight_pids(&entries, 3).is_empty());
+    }
+}
@@ -3,6 +3,10 @@
 use prettytable::{format, Cell, Row, Table};
 
 use super::{ListProvidersResult, ListRolesResult, TaskInfo};
+use crate::mcp_routing::health::ServerHealth;
+use crate::registry::RegistryStats;
+use crate::task_record::format_duration;
+use chrono::Duration;
 
 /// Safely truncate a string to at most `max_chars` characters (not bytes),
 /// appending "..." if truncated.
@@ -26,10 +30,12 @@ pub fn format_tasks_table(tasks: &[TaskInfo]) -> String {
 
     table.add_row(Row::new(vec![
         Cell::new("TASK_ID"),
+        Cell::new("NAME"),
         Cell::new("PID"),
         Cell::new("STATUS"),
         Cell::new("STARTED_AT"),
         Cell::new("COMPLETED_AT"),
+        Cell::new("DURATION"),
     ]));
 
     for t in tasks {
@@ -38,6 +44,7 @@ pub fn format_tasks_table(tasks: &[TaskInfo]) -> String {
             .as_deref()
             .map(|id| if id.len() > 10 { &id[..10] } else { id })
             .unwrap_or("-");
+        let name = t.name.as_deref().unwrap_or("-");
         let status = format!("{:?}", t.status).to_lowercase();
         let started = t.started_at.format("%Y-%m-%d %H:%M:%S").to_string();
         let completed = t
@@ -47,10 +54,12 @@ pub fn format_tasks_table(tasks: &[TaskInfo]) -> String {
 
         table.add_row(Row::new(vec![
             Cell::new(task_id),
+            Cell::new(name),
             Cell::new(&t.pid.to_string()),
             Cell::new(&status),
             Cell::new(&started),
             Cell::new(&completed),
+            Cell::new(&format_duration(Duration::seconds(t.duration_secs))),
         ]));
     }
 
@@ -134,3 +143,87 @@ pub fn format_providers_table(result: &ListProvidersResult) -> String {
 
     table.to_string()
 }
+
+/// Format aggregate registry statistics as a two-column ASCII table.
+pub fn format_registry_stats_table(stats: &RegistryStats) -> String {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+    table.add_row(Row::new(vec![Cell::new("METRIC"), Cell::new("VALUE")]));
+
+    let by_type = if stats.by_ai_type.is_empty() {
+        "-".to_string()
+    } else {
+        stats
+            .by_ai_type
+            .iter()
+            .map(|(ai_type, count)| format!("{ai_type}={count}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let oldest = stats
+        .oldest_running_secs
+        .map(|secs| format!("{secs}s"))
+        .unwrap_or_else(|| "-".to_string());
+
+    let rows = [
+        ("TOTAL", stats.total.to_string()),
+        ("RUNNING", stats.running.to_string()),
+        ("COMPLETED", stats.completed.to_string()),
+        ("FAILED", stats.failed.to_string()),
+        ("QUEUED", stats.queued.to_string()),
+        ("BY_AI_TYPE", by_type),
+        ("WITH_WORKTREE", stats.with_worktree.to_string()),
+        ("LOG_BYTES", stats.log_bytes.to_string()),
+        ("OLDEST_RUNNING", oldest),
+    ];
+    for (metric, value) in rows {
+        table.add_row(Row::new(vec![Cell::new(metric), Cell::new(&value)]));
+    }
+
+    table.to_string()
+}
+
+/// Format connection pool health as an ASCII table.
+pub fn format_server_health_table(servers: &[ServerHealth]) -> String {
+    if servers.is_empty() {
+        return "No MCP servers configured.".to_string();
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+    table.add_row(Row::new(vec![
+        Cell::new("SERVER"),
+        Cell::new("STATUS"),
+        Cell::new("CONNECTED"),
+        Cell::new("FAILURES"),
+        Cell::new("RESTARTS"),
+        Cell::new("RETRY_IN"),
+        Cell::new("LAST_ERROR"),
+    ]));
+
+    for server in servers {
+        let retry_in = server
+            .retry_in_secs
+            .map(|secs| format!("{secs}s"))
+            .unwrap_or_else(|| "-".to_string());
+        let last_error = server
+            .last_error
+            .as_deref()
+            .map(|err| truncate_str(err, 60))
+            .unwrap_or_else(|| "-".to_string());
+
+        table.add_row(Row::new(vec![
+            Cell::new(&server.server),
+            Cell::new(server.status.as_str()),
+            Cell::new(if server.connected { "yes" } else { "no" }),
+            Cell::new(&server.consecutive_failures.to_string()),
+            Cell::new(&server.restart_attempts.to_string()),
+            Cell::new(&retry_in),
+            Cell::new(&last_error),
+        ]));
+    }
+
+    table.to_string()
+}
@@ -0,0 +1,164 @@
+//! Tool category resolution.
+//!
+//! Priority: `category` in the server's mcp.json entry, then keywords in the
+//! server name, then keywords in the tool name/description, otherwise
+//! [`UNCATEGORIZED`]. Server-name hits win over the description because a
+//! server usually covers one domain while descriptions mention many.
+
+use super::models::UNCATEGORIZED;
+
+/// Category → keywords, checked in order; the first match wins.
+const RULES: &[(&str, &[&str])] = &[
+    (
+        "filesystem",
+        &["filesystem", "file", "directory", "folder", "path"],
+    ),
+    (
+        "version-control",
+        &[
+            "git",
+            "github",
+            "gitlab",
+            "commit",
+            "branch",
+            "pull request",
+        ],
+    ),
+    (
+        "database",
+        &[
+            "database", "sql", "postgres", "mysql", "sqlite", "mongo", "redis",
+        ],
+    ),
+    (
+        "browser",
+        &[
+            "browser",
+            "puppeteer",
+            "playwright",
+            "screenshot",
+            "web page",
+        ],
+    ),
+    ("search", &["search", "brave", "tavily", "exa", "google"]),
+    ("web", &["fetch", "http", "url", "scrape", "crawl"]),
+    (
+        "memory",
+        &["memory", "knowledge graph", "entities", "observations"],
+    ),
+    (
+        "communication",
+        &["slack", "email", "discord", "message", "notification"],
+    ),
+    (
+        "cloud",
+        &["aws", "azure", "gcp", "kubernetes", "docker", "s3"],
+    ),
+];
+
+/// Resolve the category of one tool.
+pub fn resolve(configured: Option<&str>, server: &str, tool: &str, description: &str) -> String {
+    if let Some(category) = configured.map(str::trim).filter(|c| !c.is_empty()) {
+        return category.to_lowercase();
+    }
+    classify(&[server])
+        .or_else(|| classify(&[tool, description]))
+        .unwrap_or(UNCATEGORIZED)
+        .to_string()
+}
+
+/// Keyword classifier over free text (server names, descriptions).
+pub fn classify(texts: &[&str]) -> Option<&'static str> {
+    let haystack = texts
+        .iter()
+        .map(|text| normalize(text))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let words: Vec<&str> = haystack.split_whitespace().collect();
+
+    RULES.iter().find_map(|(category, keywords)| {
+        keywords
+            .iter()
+            .any(|keyword| contains_keyword(&haystack, &words, keyword))
+            .then_some(*category)
+    })
+}
+
+/// Lowercase and turn separators (`-`, `_`, `/`, `.`, `@`) into spaces.
+fn normalize(text: &str) -> String {
+    text.chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                ' '
+            }
+        })
+        .collect()
+}
+
+/// Single-word keywords match a word or its plural; multi-word keywords match as a phrase.
+fn contains_keyword(haystack: &str, words: &[&str], keyword: &str) -> bool {
+    if keyword.contains(' ') {
+        return haystack.contains(keyword);
+    }
+    words.iter().any(|word| {
+        *word == keyword
+            || [word.strip_suffix('s'), word.strip_suffix("es")]
+                .into_iter()
+                .flatten()
+                .any(|stem| stem == keyword)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn configured_category_wins() {
+        assert_eq!(
+            resolve(Some(" Filesystem "), "git", "log", "Show commits"),
+            "filesystem"
+        );
+        assert_eq!(resolve(Some(""), "git", "log", ""), "version-control");
+    }
+
+    #[test]
+    fn server_name_beats_description() {
+        assert_eq!(
+            resolve(None, "server-filesystem", "search_files", "Search files"),
+            "filesystem"
+        );
+        assert_eq!(
+            resolve(
+                None,
+                "@modelcontextprotocol/server-github",
+                "create_issue",
+                ""
+            ),
+            "version-control"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_description_keywords() {
+        assert_eq!(
+            resolve(None, "acme", "query", "Run a SQL query against Postgres"),
+            "database"
+        );
+        assert_eq!(
+            resolve(
+                None,
+                "acme",
+                "add_observations",
+                "Add observations to entities"
+            ),
+            "memory"
+        );
+        assert_eq!(
+            resolve(None, "acme", "do_thing", "Profile the thing"),
+            UNCATEGORIZED
+        );
+    }
+}
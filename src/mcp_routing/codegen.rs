@@ -25,7 +25,12 @@ impl CodegenBackend {
     /// - If OPENAI_TOKEN exists → Ollama mode
     /// - Otherwise → AI CLI mode (default: claude)
     pub fn from_env() -> Self {
-        if std::env::var("OPENAI_TOKEN").is_ok() {
+        Self::for_token(std::env::var("OPENAI_TOKEN").is_ok())
+    }
+
+    /// Select the backend for a resolved LLM token (mcp.json `routing` or env)
+    pub fn for_token(has_token: bool) -> Self {
+        if has_token {
             Self::Ollama
         } else {
             Self::AiCli
@@ -49,8 +54,15 @@ impl CodeGeneratorFactory {
         default_endpoint: String,
         default_model: String,
     ) -> Result<Arc<dyn WorkflowPlannerEngine>> {
-        let backend = CodegenBackend::from_env();
+        Self::create(CodegenBackend::from_env(), default_endpoint, default_model)
+    }
 
+    /// Create code generator for an explicit backend
+    pub fn create(
+        backend: CodegenBackend,
+        default_endpoint: String,
+        default_model: String,
+    ) -> Result<Arc<dyn WorkflowPlannerEngine>> {
         match backend {
             CodegenBackend::Ollama => {
                 Self::create_ollama_generator(default_endpoint, default_model)
@@ -66,7 +78,7 @@ impl CodeGeneratorFactory {
     ) -> Result<Arc<dyn WorkflowPlannerEngine>> {
         let timeout = 30 * 60; // 30 minutes in seconds
         let decision_engine = DecisionEngine::new(&endpoint, &model, timeout)?;
-        eprintln!("🤖 Ollama code generator initialized: {}", endpoint);
+        tracing::info!("Ollama code generator initialized: {}", endpoint);
         Ok(Arc::new(decision_engine))
     }
 
@@ -90,8 +102,8 @@ impl CodeGeneratorFactory {
         // Provider can be any string (llmlite, openrouter, anthropic, etc.)
         let provider = std::env::var("CLI_PROVIDER").ok();
 
-        eprintln!(
-            "🤖 AI CLI code generator initialized: {} (provider: {:?})",
+        tracing::info!(
+            "AI CLI code generator initialized: {} (provider: {:?})",
             cli_type.display_name(),
             provider
         );
@@ -122,14 +134,14 @@ impl AiCliCodeGenerator {
     /// Call AI CLI with prompt and get response
     /// Uses temporary files to avoid stdin/stdout capture issues
     async fn call_ai_cli(&self, prompt: &str) -> Result<String> {
-        eprintln!("   🔍 [CODEX] Starting AI CLI call...");
-        eprintln!("   🔍 [CODEX] CLI type: {}", self.cli_type.display_name());
-        eprintln!("   🔍 [CODEX] Timeout: {:?}", self.timeout);
-        eprintln!("   🔍 [CODEX] Prompt length: {} chars", prompt.len());
+        tracing::debug!("[CODEX] Starting AI CLI call...");
+        tracing::debug!("[CODEX] CLI type: {}", self.cli_type.display_name());
+        tracing::debug!("[CODEX] Timeout: {:?}", self.timeout);
+        tracing::debug!("[CODEX] Prompt length: {} chars", prompt.len());
 
         let registry = create_cli_registry().context("Failed to create CLI registry")?;
 
-        eprintln!("   🔍 [CODEX] CLI registry created successfully");
+        tracing::debug!("[CODEX] CLI registry created successfully");
 
         // Create temporary file for input
         let prompt_file =
@@ -149,20 +161,20 @@ impl AiCliCodeGenerator {
             _ => self.cli_type.build_full_access_args(prompt),
         };
 
-        eprintln!("   🔍 [CODEX] CLI args built: {} args", cli_args.len());
+        tracing::debug!("[CODEX] CLI args built: {} args", cli_args.len());
 
         // Convert to OsString for supervisor
         let os_args: Vec<std::ffi::OsString> = cli_args.into_iter().map(|s| s.into()).collect();
 
-        eprintln!("   🔍 [CODEX] Calling supervisor::execute_cli...");
+        tracing::debug!("[CODEX] Calling supervisor::execute_cli...");
 
         // Execute CLI normally (no output capture)
         let exit_code =
             supervisor::execute_cli(&registry, &self.cli_type, &os_args, self.provider.clone(), None)
                 .await;
 
-        eprintln!(
-            "   🔍 [CODEX] Supervisor call completed with exit code: {:?}",
+        tracing::debug!(
+            "[CODEX] Supervisor call completed with exit code: {:?}",
             exit_code
         );
 
@@ -176,8 +188,8 @@ impl AiCliCodeGenerator {
 
                 // Parse log files to get actual CODEX output
                 let actual_output = parse_codex_log_output().await?;
-                eprintln!(
-                    "   🔍 [CODEX] Retrieved actual output, length: {}",
+                tracing::debug!(
+                    "[CODEX] Retrieved actual output, length: {}",
                     actual_output.len()
                 );
                 Ok(actual_output)
@@ -222,7 +234,7 @@ async fn parse_codex_log_output() -> Result<String> {
 
     let (log_path, _) = latest_log.ok_or_else(|| anyhow!("No CODEX log files found"))?;
 
-    eprintln!("   🔍 [CODEX] Reading log file: {:?}", log_path);
+    tracing::debug!("[CODEX] Reading log file: {:?}", log_path);
 
     // Read the log file - for AI CLI, this contains the raw AI response
     let log_content = std::fs::read_to_string(&log_path).context("Failed to read log file")?;
@@ -245,7 +257,7 @@ impl WorkflowPlannerEngine for AiCliCodeGenerator {
         user_request: &str,
         available_tools: &[CandidateToolInfo],
     ) -> Result<WorkflowPlan> {
-        eprintln!("   🔍 [PLANNER] Starting plan_workflow...");
+        tracing::debug!("[PLANNER] Starting plan_workflow...");
 
         if user_request.trim().is_empty() {
             return Err(anyhow!("user_request cannot be empty"));
@@ -254,42 +266,39 @@ impl WorkflowPlannerEngine for AiCliCodeGenerator {
             return Err(anyhow!("No MCP tools available for workflow planning"));
         }
 
-        eprintln!(
-            "   🔍 [PLANNER] Input validated, {} tools available",
+        tracing::debug!(
+            "[PLANNER] Input validated, {} tools available",
             available_tools.len()
         );
 
         let prompt = build_planning_prompt(user_request, available_tools);
-        eprintln!(
-            "   🔍 [PLANNER] Planning prompt built, length: {}",
-            prompt.len()
-        );
+        tracing::debug!("[PLANNER] Planning prompt built, length: {}", prompt.len());
 
-        eprintln!("   🔍 [PLANNER] Calling AI CLI for workflow planning...");
+        tracing::debug!("[PLANNER] Calling AI CLI for workflow planning...");
         let response = self.call_ai_cli(&prompt).await?;
-        eprintln!(
-            "   🔍 [PLANNER] AI CLI response received, length: {}",
+        tracing::debug!(
+            "[PLANNER] AI CLI response received, length: {}",
             response.len()
         );
-        eprintln!("   🔍 [PLANNER] Raw response:\n{}", response);
+        tracing::debug!("[PLANNER] Raw response:\n{}", response);
 
         // Extract JSON from response
         let json_str = extract_json_from_response(&response)
             .ok_or_else(|| anyhow!("AI CLI response does not contain valid JSON"))?;
 
-        eprintln!("   🔍 [PLANNER] JSON extracted, length: {}", json_str.len());
-        eprintln!("   🔍 [PLANNER] Extracted JSON:\n{}", json_str);
+        tracing::debug!("[PLANNER] JSON extracted, length: {}", json_str.len());
+        tracing::debug!("[PLANNER] Extracted JSON:\n{}", json_str);
 
         let mut plan: WorkflowPlan = serde_json::from_str(&json_str)
             .map_err(|e| {
-                eprintln!("   ❌ [PLANNER] JSON parse error: {}", e);
-                eprintln!("   ❌ [PLANNER] Failed JSON:\n{}", json_str);
+                tracing::debug!("[PLANNER] JSON parse error: {}", e);
+                tracing::debug!("[PLANNER] Failed JSON:\n{}", json_str);
                 e
             })
             .context("Failed to parse workflow plan JSON from AI CLI")?;
 
-        eprintln!(
-            "   🔍 [PLANNER] Workflow plan parsed, feasible: {}, steps: {}",
+        tracing::debug!(
+            "[PLANNER] Workflow plan parsed, feasible: {}, steps: {}",
             plan.is_feasible,
             plan.steps.len()
         );
@@ -297,12 +306,12 @@ impl WorkflowPlannerEngine for AiCliCodeGenerator {
         // Normalize plan
         finalize_workflow_plan(&mut plan, user_request);
 
-        eprintln!("   🔍 [PLANNER] Workflow plan finalized successfully");
+        tracing::debug!("[PLANNER] Workflow plan finalized successfully");
         Ok(plan)
     }
 
     async fn generate_js_code(&self, plan: &WorkflowPlan) -> Result<String> {
-        eprintln!("   🔍 [CODEGEN] Starting JavaScript code generation...");
+        tracing::debug!("[CODEGEN] Starting JavaScript code generation...");
 
         if !plan.is_feasible {
             return Err(anyhow!(
@@ -314,21 +323,21 @@ impl WorkflowPlannerEngine for AiCliCodeGenerator {
             return Err(anyhow!("Workflow plan must contain at least one step"));
         }
 
-        eprintln!(
-            "   🔍 [CODEGEN] Plan validation passed, generating code for {} steps",
+        tracing::debug!(
+            "[CODEGEN] Plan validation passed, generating code for {} steps",
             plan.steps.len()
         );
 
         let prompt = build_codegen_prompt(plan);
-        eprintln!(
-            "   🔍 [CODEGEN] Code generation prompt built, length: {}",
+        tracing::debug!(
+            "[CODEGEN] Code generation prompt built, length: {}",
             prompt.len()
         );
 
-        eprintln!("   🔍 [CODEGEN] Calling AI CLI for JavaScript generation...");
+        tracing::debug!("[CODEGEN] Calling AI CLI for JavaScript generation...");
         let response = self.call_ai_cli(&prompt).await?;
-        eprintln!(
-            "   🔍 [CODEGEN] AI CLI response received, length: {}",
+        tracing::debug!(
+            "[CODEGEN] AI CLI response received, length: {}",
             response.len()
         );
 
@@ -339,8 +348,8 @@ impl WorkflowPlannerEngine for AiCliCodeGenerator {
             return Err(anyhow!("AI CLI returned empty JavaScript code"));
         }
 
-        eprintln!(
-            "   🔍 [CODEGEN] JavaScript code extracted, length: {}",
+        tracing::debug!(
+            "[CODEGEN] JavaScript code extracted, length: {}",
             code.len()
         );
 
@@ -375,7 +384,7 @@ impl WorkflowPlannerEngine for AiCliCodeGenerator {
             return Err(anyhow!("Generated JavaScript contains markdown fences"));
         }
 
-        eprintln!("   🔍 [CODEGEN] JavaScript code validation passed");
+        tracing::debug!("[CODEGEN] JavaScript code validation passed");
         Ok(code)
     }
 }
@@ -637,4 +646,111 @@ async function workflow() {}
         );
         assert_eq!(derive_workflow_name(""), "workflow_plan");
     }
+
+    /// 进程仍在运行（僵尸状态视为已结束）
+    #[cfg(target_os = "linux")]
+    fn running(pid: i32) -> bool {
+        std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .ok()
+            .and_then(|stat| {
+                let (_, rest) = stat.rsplit_once(')')?;
+                Some(!rest.trim_start().starts_with('Z'))
+            })
+            .unwrap_or(false)
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn cancelling_orchestration_stops_the_planner_cli() {
+        use crate::config::CONFIG_DIR_ENV;
+        use crate::mcp_routing::js_orchestrator::WorkflowOrchestrator;
+        use std::os::unix::fs::PermissionsExt;
+        use tokio_util::sync::CancellationToken;
+
+        // 假的 codex：派生一个孙进程后一直等待，把两个 pid 写到文件里
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pids");
+        let script = dir.path().join("codex");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nsleep 300 &\necho \"$$ $!\" > '{}'\nwait\n",
+                pid_file.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let old_path = std::env::var_os("PATH");
+        let old_config_dir = std::env::var_os(CONFIG_DIR_ENV);
+        let mut paths = vec![dir.path().to_path_buf()];
+        paths.extend(std::env::split_paths(&old_path.clone().unwrap_or_default()));
+        std::env::set_var("PATH", std::env::join_paths(paths).unwrap());
+        std::env::set_var(CONFIG_DIR_ENV, dir.path().join("config"));
+
+        let orchestrator = WorkflowOrchestrator::with_planner(Arc::new(AiCliCodeGenerator::new(
+            CliType::Codex,
+            None,
+        )));
+        let tools = vec![CandidateToolInfo {
+            server: "fs".to_string(),
+            tool: "list_files".to_string(),
+            description: "List files".to_string(),
+            schema_snippet: None,
+            category: "filesystem".to_string(),
+        }];
+        let cancel = CancellationToken::new();
+        let trigger = {
+            let cancel = cancel.clone();
+            let pid_file = pid_file.clone();
+            tokio::spawn(async move {
+                while !std::fs::read_to_string(&pid_file).is_ok_and(|pids| pids.ends_with('\n')) {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                cancel.cancel();
+            })
+        };
+
+        // 与 try_orchestrate 相同：取消时直接丢弃编排 future
+        let outcome = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::select! {
+                biased;
+                _ = cancel.cancelled() => None,
+                result = orchestrator.orchestrate("list the files", &tools) => Some(result),
+            }
+        })
+        .await;
+
+        match old_path {
+            Some(path) => std::env::set_var("PATH", path),
+            None => std::env::remove_var("PATH"),
+        }
+        match old_config_dir {
+            Some(dir) => std::env::set_var(CONFIG_DIR_ENV, dir),
+            None => std::env::remove_var(CONFIG_DIR_ENV),
+        }
+        trigger.abort();
+        assert!(
+            matches!(outcome, Ok(None)),
+            "orchestration was not cancelled"
+        );
+
+        let pids: Vec<i32> = std::fs::read_to_string(&pid_file)
+            .unwrap()
+            .split_whitespace()
+            .map(|pid| pid.parse().unwrap())
+            .collect();
+        assert_eq!(pids.len(), 2);
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while pids.iter().any(|pid| running(*pid)) && std::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        for pid in pids {
+            assert!(
+                !running(pid),
+                "planner process {pid} outlived the cancelled orchestration"
+            );
+        }
+    }
 }
@@ -1,5 +1,5 @@
+use crate::utils::config_paths::aiw_config_dir;
 use anyhow::{anyhow, Context, Result};
-use dirs::home_dir;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
@@ -23,6 +23,8 @@ pub struct McpConfig {
     pub version: String,
     #[serde(rename = "mcpServers", alias = "mcp_servers")]
     pub mcp_servers: HashMap<String, McpServerConfig>,
+    #[serde(default, skip_serializing_if = "RoutingConfig::is_empty")]
+    pub routing: RoutingConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +63,696 @@ pub const DEFAULT_CLUSTERING_THRESHOLD: f32 = 0.7;
 pub const DEFAULT_RERANK_TOP_K: usize = 5;
 pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.5;
 
+// Routing/decision defaults, overridable via the `routing` section or env vars
+pub const DEFAULT_LLM_ENDPOINT: &str = "http://localhost:11434";
+pub const DEFAULT_LLM_MODEL: &str = "qwen3:1.7b";
+pub const DEFAULT_LLM_TIMEOUT_SECS: u64 = 120;
+pub const DEFAULT_FAST_PATH_THRESHOLD: f32 = 0.75;
+pub const DEFAULT_ORCHESTRATION_TIMEOUT_SECS: u64 = 90;
+pub const DEFAULT_MAX_DYNAMIC_TOOLS: usize = 5;
+pub const DEFAULT_HISTORY_ENABLED: bool = true;
+pub const DEFAULT_WARM_UP_CONCURRENCY: usize = 4;
+pub const DEFAULT_DISCOVERY_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_MAX_RESULT_BYTES: usize = 256 * 1024;
+pub const DEFAULT_MAX_STRUCTURED_RESULT_BYTES: usize = 1024 * 1024;
+
+/// How proxied tool calls treat arguments that don't match the tool's input schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ArgumentValidation {
+    /// Refuse the call and list the offending fields
+    Reject,
+    /// Log the mismatch and forward the call anyway. The default: many servers
+    /// publish loose or inaccurate schemas, so rejecting is opt-in
+    #[default]
+    Warn,
+    Off,
+}
+
+impl ArgumentValidation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Warn => "warn",
+            Self::Off => "off",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "reject" | "strict" => Some(Self::Reject),
+            "warn" => Some(Self::Warn),
+            "off" | "none" => Some(Self::Off),
+            _ => None,
+        }
+    }
+}
+
+/// Size caps for results returned from proxied tool calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResultLimits {
+    /// Text content beyond this many bytes is cut off with a truncation marker
+    pub max_result_bytes: usize,
+    /// structuredContent is only passed through when its JSON fits in this many bytes
+    pub max_structured_result_bytes: usize,
+}
+
+impl Default for ResultLimits {
+    fn default() -> Self {
+        Self {
+            max_result_bytes: DEFAULT_MAX_RESULT_BYTES,
+            max_structured_result_bytes: DEFAULT_MAX_STRUCTURED_RESULT_BYTES,
+        }
+    }
+}
+
+/// Keys accepted by `aiw routing config get/set/unset`
+pub const ROUTING_CONFIG_KEYS: &[&str] = &[
+    "llm_endpoint",
+    "llm_model",
+    "llm_token",
+    "llm_timeout_secs",
+    "fast_path_threshold",
+    "orchestration_timeout_secs",
+    "embedding_backend",
+    "embedding_model_dir",
+    "max_dynamic_tools",
+    "history_enabled",
+    "argument_validation",
+    "warm_up_concurrency",
+    "discovery_timeout_secs",
+    "max_result_bytes",
+    "max_structured_result_bytes",
+];
+
+/// `routing` section of mcp.json; values set here win over environment variables.
+///
+/// Keys are written in snake_case, matching `aiw routing config set`; the
+/// camelCase spelling used by earlier versions is still accepted on read.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    #[serde(
+        default,
+        alias = "llmEndpoint",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub llm_endpoint: Option<String>,
+    #[serde(default, alias = "llmModel", skip_serializing_if = "Option::is_none")]
+    pub llm_model: Option<String>,
+    #[serde(default, alias = "llmToken", skip_serializing_if = "Option::is_none")]
+    pub llm_token: Option<String>,
+    #[serde(
+        default,
+        alias = "llmTimeoutSecs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub llm_timeout_secs: Option<u64>,
+    #[serde(
+        default,
+        alias = "fastPathThreshold",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fast_path_threshold: Option<f32>,
+    /// Upper bound for a full LLM orchestration before falling back to vector search
+    #[serde(
+        default,
+        alias = "orchestrationTimeoutSecs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub orchestration_timeout_secs: Option<u64>,
+    #[serde(
+        default,
+        alias = "embeddingBackend",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub embedding_backend: Option<String>,
+    #[serde(
+        default,
+        alias = "embeddingModelDir",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub embedding_model_dir: Option<String>,
+    #[serde(
+        default,
+        alias = "maxDynamicTools",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_dynamic_tools: Option<usize>,
+    /// Record routing decisions in conversation_history.db and use them to bias ranking
+    #[serde(
+        default,
+        alias = "historyEnabled",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub history_enabled: Option<bool>,
+    /// reject|warn|off for proxied tool arguments that don't match the input schema
+    #[serde(
+        default,
+        alias = "argumentValidation",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub argument_validation: Option<String>,
+    /// Servers started and queried in parallel while discovering tools at startup
+    #[serde(
+        default,
+        alias = "warmUpConcurrency",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub warm_up_concurrency: Option<usize>,
+    /// Per-server limit on connect + tool listing during startup discovery
+    #[serde(
+        default,
+        alias = "discoveryTimeoutSecs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub discovery_timeout_secs: Option<u64>,
+    /// Text size at which proxied tool results are truncated
+    #[serde(
+        default,
+        alias = "maxResultBytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_result_bytes: Option<usize>,
+    /// Largest structuredContent passed through from proxied tool results
+    #[serde(
+        default,
+        alias = "maxStructuredResultBytes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_structured_result_bytes: Option<usize>,
+}
+
+/// Where an effective routing value came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueSource {
+    McpJson,
+    Env,
+    ConfigJson,
+    Default,
+}
+
+impl std::fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::McpJson => "mcp.json",
+            Self::Env => "env",
+            Self::ConfigJson => "config.json",
+            Self::Default => "default",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub source: ValueSource,
+}
+
+/// Routing settings after applying mcp.json > env > defaults
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedRoutingConfig {
+    pub llm_endpoint: Resolved<String>,
+    pub llm_model: Resolved<String>,
+    pub llm_token: Resolved<Option<String>>,
+    pub llm_timeout_secs: Resolved<u64>,
+    pub fast_path_threshold: Resolved<f32>,
+    pub orchestration_timeout_secs: Resolved<u64>,
+    pub embedding_backend: Resolved<String>,
+    pub embedding_model_dir: Resolved<Option<String>>,
+    pub max_dynamic_tools: Resolved<usize>,
+    pub history_enabled: Resolved<bool>,
+    pub argument_validation: Resolved<ArgumentValidation>,
+    pub warm_up_concurrency: Resolved<usize>,
+    pub discovery_timeout_secs: Resolved<u64>,
+    pub max_result_bytes: Resolved<usize>,
+    pub max_structured_result_bytes: Resolved<usize>,
+}
+
+impl ResolvedRoutingConfig {
+    /// An LLM is in use when a token is set or the endpoint differs from the local default
+    pub fn has_external_llm(&self) -> bool {
+        self.llm_token.value.is_some() || self.llm_endpoint.value != DEFAULT_LLM_ENDPOINT
+    }
+
+    pub fn result_limits(&self) -> ResultLimits {
+        ResultLimits {
+            max_result_bytes: self.max_result_bytes.value,
+            max_structured_result_bytes: self.max_structured_result_bytes.value,
+        }
+    }
+
+    /// `(key, display value, source)` rows with the token masked
+    pub fn rows(&self) -> Vec<(&'static str, String, ValueSource)> {
+        fn optional(value: &Option<String>) -> String {
+            value.clone().unwrap_or_else(|| "(unset)".to_string())
+        }
+        vec![
+            (
+                "llm_endpoint",
+                self.llm_endpoint.value.clone(),
+                self.llm_endpoint.source,
+            ),
+            (
+                "llm_model",
+                self.llm_model.value.clone(),
+                self.llm_model.source,
+            ),
+            (
+                "llm_token",
+                self.llm_token
+                    .value
+                    .as_deref()
+                    .map(crate::provider::env_injector::EnvInjector::mask_api_key)
+                    .unwrap_or_else(|| "(unset)".to_string()),
+                self.llm_token.source,
+            ),
+            (
+                "llm_timeout_secs",
+                self.llm_timeout_secs.value.to_string(),
+                self.llm_timeout_secs.source,
+            ),
+            (
+                "fast_path_threshold",
+                self.fast_path_threshold.value.to_string(),
+                self.fast_path_threshold.source,
+            ),
+            (
+                "orchestration_timeout_secs",
+                self.orchestration_timeout_secs.value.to_string(),
+                self.orchestration_timeout_secs.source,
+            ),
+            (
+                "embedding_backend",
+                self.embedding_backend.value.clone(),
+                self.embedding_backend.source,
+            ),
+            (
+                "embedding_model_dir",
+                optional(&self.embedding_model_dir.value),
+                self.embedding_model_dir.source,
+            ),
+            (
+                "max_dynamic_tools",
+                self.max_dynamic_tools.value.to_string(),
+                self.max_dynamic_tools.source,
+            ),
+            (
+                "history_enabled",
+                self.history_enabled.value.to_string(),
+                self.history_enabled.source,
+            ),
+            (
+                "argument_validation",
+                self.argument_validation.value.as_str().to_string(),
+                self.argument_validation.source,
+            ),
+            (
+                "warm_up_concurrency",
+                self.warm_up_concurrency.value.to_string(),
+                self.warm_up_concurrency.source,
+            ),
+            (
+                "discovery_timeout_secs",
+                self.discovery_timeout_secs.value.to_string(),
+                self.discovery_timeout_secs.source,
+            ),
+            (
+                "max_result_bytes",
+                self.max_result_bytes.value.to_string(),
+                self.max_result_bytes.source,
+            ),
+            (
+                "max_structured_result_bytes",
+                self.max_structured_result_bytes.value.to_string(),
+                self.max_structured_result_bytes.source,
+            ),
+        ]
+    }
+}
+
+fn pick<T>(configured: Option<T>, env: Option<T>, default: T) -> Resolved<T> {
+    match (configured, env) {
+        (Some(value), _) => Resolved {
+            value,
+            source: ValueSource::McpJson,
+        },
+        (None, Some(value)) => Resolved {
+            value,
+            source: ValueSource::Env,
+        },
+        (None, None) => Resolved {
+            value: default,
+            source: ValueSource::Default,
+        },
+    }
+}
+
+/// 最后一层回退：~/.aiw/config.json 中的 embedding 设置
+fn with_user_config(
+    resolved: Resolved<Option<String>>,
+    fallback: Option<String>,
+) -> Resolved<Option<String>> {
+    match (resolved.source, fallback) {
+        (ValueSource::Default, Some(value)) => Resolved {
+            value: Some(value),
+            source: ValueSource::ConfigJson,
+        },
+        _ => resolved,
+    }
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "on" | "yes" => Some(true),
+        "false" | "0" | "off" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// `llm_token` may be a literal or a `${env:NAME}` / `${file:/path}` reference,
+/// resolved each time the config is loaded; an unresolvable reference counts as unset
+fn resolve_token(token: &str, env: &impl Fn(&str) -> Option<String>) -> Option<String> {
+    use crate::provider::secrets::{parse_reference, SecretRef};
+
+    let resolved = match parse_reference(token) {
+        Ok(None) => return Some(token.to_string()),
+        Ok(Some(SecretRef::Env(name))) => {
+            env(name).ok_or_else(|| format!("environment variable {} is not set", name))
+        }
+        Ok(Some(SecretRef::File(path))) => {
+            let path = crate::utils::config_paths::expand_home(path);
+            std::fs::read_to_string(&path)
+                .map(|content| content.trim_end_matches(['\r', '\n']).to_string())
+                .map_err(|err| format!("cannot read {}: {}", path.display(), err))
+        }
+        Ok(Some(SecretRef::Keychain(_))) => Err(TOKEN_REFERENCE_HINT.to_string()),
+        Err(err) => Err(err),
+    };
+    match resolved {
+        Ok(secret) if !secret.is_empty() => Some(secret),
+        Ok(_) => {
+            tracing::warn!(
+                "routing.llm_token {} resolved to an empty token",
+                token.trim()
+            );
+            None
+        }
+        Err(reason) => {
+            tracing::warn!(
+                "Cannot resolve routing.llm_token {}: {}",
+                token.trim(),
+                reason
+            );
+            None
+        }
+    }
+}
+
+const TOKEN_REFERENCE_HINT: &str = "llm_token references must be ${env:NAME} or ${file:/path}";
+
+/// Whether a stored `llm_token` is a reference rather than the token itself
+pub fn is_token_reference(token: &str) -> bool {
+    matches!(
+        crate::provider::secrets::parse_reference(token),
+        Ok(Some(_))
+    )
+}
+
+fn pick_optional<T>(configured: Option<T>, env: Option<T>) -> Resolved<Option<T>> {
+    pick(configured.map(Some), env.map(Some), None)
+}
+
+impl RoutingConfig {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Resolve against the process environment and `~/.aiw/config.json`
+    pub fn resolve(&self) -> ResolvedRoutingConfig {
+        let user_config = crate::utils::config_paths::ConfigPaths::new()
+            .map(|paths| paths.user_config)
+            .unwrap_or_default();
+        self.resolve_with(
+            |key| std::env::var(key).ok().filter(|value| !value.is_empty()),
+            &user_config,
+        )
+    }
+
+    pub fn resolve_with(
+        &self,
+        env: impl Fn(&str) -> Option<String>,
+        user_config: &crate::utils::config_paths::UserConfig,
+    ) -> ResolvedRoutingConfig {
+        use crate::config::{
+            ARGUMENT_VALIDATION_ENV, EMBEDDING_BACKEND_ENV, EMBEDDING_MODEL_DIR_ENV,
+            ROUTING_HISTORY_ENV,
+        };
+
+        let embedding_backend = with_user_config(
+            pick_optional(self.embedding_backend.clone(), env(EMBEDDING_BACKEND_ENV)),
+            user_config.embedding_backend.clone(),
+        );
+        let embedding_model_dir = with_user_config(
+            pick_optional(
+                self.embedding_model_dir.clone(),
+                env(EMBEDDING_MODEL_DIR_ENV),
+            ),
+            user_config.embedding_model_dir.clone(),
+        );
+
+        ResolvedRoutingConfig {
+            llm_endpoint: pick(
+                self.llm_endpoint.clone(),
+                env("OPENAI_ENDPOINT"),
+                DEFAULT_LLM_ENDPOINT.to_string(),
+            ),
+            llm_model: pick(
+                self.llm_model.clone(),
+                env("OPENAI_MODEL"),
+                DEFAULT_LLM_MODEL.to_string(),
+            ),
+            llm_token: pick_optional(
+                self.llm_token
+                    .as_deref()
+                    .and_then(|token| resolve_token(token, &env)),
+                env("OPENAI_TOKEN"),
+            ),
+            llm_timeout_secs: pick(self.llm_timeout_secs, None, DEFAULT_LLM_TIMEOUT_SECS),
+            fast_path_threshold: pick(self.fast_path_threshold, None, DEFAULT_FAST_PATH_THRESHOLD),
+            orchestration_timeout_secs: pick(
+                self.orchestration_timeout_secs,
+                None,
+                DEFAULT_ORCHESTRATION_TIMEOUT_SECS,
+            ),
+            embedding_backend: Resolved {
+                value: embedding_backend
+                    .value
+                    .unwrap_or_else(|| "auto".to_string()),
+                source: embedding_backend.source,
+            },
+            embedding_model_dir,
+            max_dynamic_tools: pick(self.max_dynamic_tools, None, DEFAULT_MAX_DYNAMIC_TOOLS),
+            history_enabled: pick(
+                self.history_enabled,
+                env(ROUTING_HISTORY_ENV).as_deref().and_then(parse_flag),
+                DEFAULT_HISTORY_ENABLED,
+            ),
+            argument_validation: pick(
+                self.argument_validation
+                    .as_deref()
+                    .and_then(ArgumentValidation::parse),
+                env(ARGUMENT_VALIDATION_ENV)
+                    .as_deref()
+                    .and_then(ArgumentValidation::parse),
+                ArgumentValidation::default(),
+            ),
+            warm_up_concurrency: pick(self.warm_up_concurrency, None, DEFAULT_WARM_UP_CONCURRENCY),
+            discovery_timeout_secs: pick(
+                self.discovery_timeout_secs,
+                None,
+                DEFAULT_DISCOVERY_TIMEOUT_SECS,
+            ),
+            max_result_bytes: pick(self.max_result_bytes, None, DEFAULT_MAX_RESULT_BYTES),
+            max_structured_result_bytes: pick(
+                self.max_structured_result_bytes,
+                None,
+                DEFAULT_MAX_STRUCTURED_RESULT_BYTES,
+            ),
+        }
+    }
+
+    /// Raw value stored in mcp.json for `key` (token masked)
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(match key {
+            "llm_endpoint" => self.llm_endpoint.clone(),
+            "llm_model" => self.llm_model.clone(),
+            "llm_token" => self.llm_token.as_deref().map(|token| {
+                if is_token_reference(token) {
+                    token.to_string()
+                } else {
+                    crate::provider::env_injector::EnvInjector::mask_api_key(token)
+                }
+            }),
+            "llm_timeout_secs" => self.llm_timeout_secs.map(|v| v.to_string()),
+            "fast_path_threshold" => self.fast_path_threshold.map(|v| v.to_string()),
+            "orchestration_timeout_secs" => self.orchestration_timeout_secs.map(|v| v.to_string()),
+            "embedding_backend" => self.embedding_backend.clone(),
+            "embedding_model_dir" => self.embedding_model_dir.clone(),
+            "max_dynamic_tools" => self.max_dynamic_tools.map(|v| v.to_string()),
+            "history_enabled" => self.history_enabled.map(|v| v.to_string()),
+            "argument_validation" => self.argument_validation.clone(),
+            "warm_up_concurrency" => self.warm_up_concurrency.map(|v| v.to_string()),
+            "discovery_timeout_secs" => self.discovery_timeout_secs.map(|v| v.to_string()),
+            "max_result_bytes" => self.max_result_bytes.map(|v| v.to_string()),
+            "max_structured_result_bytes" => {
+                self.max_structured_result_bytes.map(|v| v.to_string())
+            }
+            other => return Err(unknown_routing_key(other)),
+        })
+    }
+
+    /// Validate and store `value` under `key`
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(anyhow!(
+                "Value for '{}' cannot be empty; use unset instead",
+                key
+            ));
+        }
+        match key {
+            "llm_endpoint" => {
+                McpConfigManager::validate_openai_endpoint(value)?;
+                self.llm_endpoint = Some(value.to_string());
+            }
+            "llm_model" => self.llm_model = Some(value.to_string()),
+            "llm_token" => {
+                use crate::provider::secrets::{parse_reference, SecretRef};
+                match parse_reference(value) {
+                    Ok(None | Some(SecretRef::Env(_) | SecretRef::File(_))) => {}
+                    Ok(Some(SecretRef::Keychain(_))) => return Err(anyhow!(TOKEN_REFERENCE_HINT)),
+                    Err(err) => return Err(anyhow!("Invalid llm_token reference: {}", err)),
+                }
+                self.llm_token = Some(value.to_string());
+            }
+            "llm_timeout_secs" => {
+                let secs: u64 = value
+                    .parse()
+                    .map_err(|_| anyhow!("llm_timeout_secs must be a whole number of seconds"))?;
+                if secs == 0 {
+                    return Err(anyhow!("llm_timeout_secs must be greater than 0"));
+                }
+                self.llm_timeout_secs = Some(secs);
+            }
+            "fast_path_threshold" => {
+                let threshold: f32 = value
+                    .parse()
+                    .map_err(|_| anyhow!("fast_path_threshold must be a number"))?;
+                if !(0.0..=1.0).contains(&threshold) {
+                    return Err(anyhow!("fast_path_threshold must be between 0 and 1"));
+                }
+                self.fast_path_threshold = Some(threshold);
+            }
+            "orchestration_timeout_secs" => {
+                let secs: u64 = value.parse().map_err(|_| {
+                    anyhow!("orchestration_timeout_secs must be a whole number of seconds")
+                })?;
+                if secs == 0 {
+                    return Err(anyhow!("orchestration_timeout_secs must be greater than 0"));
+                }
+                self.orchestration_timeout_secs = Some(secs);
+            }
+            "embedding_backend" => {
+                super::EmbeddingBackendKind::parse(value)?;
+                self.embedding_backend = Some(value.to_ascii_lowercase());
+            }
+            "embedding_model_dir" => self.embedding_model_dir = Some(value.to_string()),
+            "max_dynamic_tools" => {
+                let max: usize = value
+                    .parse()
+                    .map_err(|_| anyhow!("max_dynamic_tools must be a positive integer"))?;
+                if max == 0 {
+                    return Err(anyhow!("max_dynamic_tools must be at least 1"));
+                }
+                self.max_dynamic_tools = Some(max);
+            }
+            "history_enabled" => {
+                let enabled = parse_flag(value)
+                    .ok_or_else(|| anyhow!("history_enabled must be true or false"))?;
+                self.history_enabled = Some(enabled);
+            }
+            "argument_validation" => {
+                let mode = ArgumentValidation::parse(value)
+                    .ok_or_else(|| anyhow!("argument_validation must be reject, warn or off"))?;
+                self.argument_validation = Some(mode.as_str().to_string());
+            }
+            "warm_up_concurrency" => {
+                let limit: usize = value
+                    .parse()
+                    .map_err(|_| anyhow!("warm_up_concurrency must be a positive integer"))?;
+                if limit == 0 {
+                    return Err(anyhow!("warm_up_concurrency must be at least 1"));
+                }
+                self.warm_up_concurrency = Some(limit);
+            }
+            "discovery_timeout_secs" => {
+                let secs: u64 = value.parse().map_err(|_| {
+                    anyhow!("discovery_timeout_secs must be a whole number of seconds")
+                })?;
+                if secs == 0 {
+                    return Err(anyhow!("discovery_timeout_secs must be greater than 0"));
+                }
+                self.discovery_timeout_secs = Some(secs);
+            }
+            "max_result_bytes" | "max_structured_result_bytes" => {
+                let bytes: usize = value
+                    .parse()
+                    .map_err(|_| anyhow!("{} must be a positive number of bytes", key))?;
+                if bytes == 0 {
+                    return Err(anyhow!("{} must be greater than 0", key));
+                }
+                if key == "max_result_bytes" {
+                    self.max_result_bytes = Some(bytes);
+                } else {
+                    self.max_structured_result_bytes = Some(bytes);
+                }
+            }
+            other => return Err(unknown_routing_key(other)),
+        }
+        Ok(())
+    }
+
+    /// Remove `key` so the env var or default applies again
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        match key {
+            "llm_endpoint" => self.llm_endpoint = None,
+            "llm_model" => self.llm_model = None,
+            "llm_token" => self.llm_token = None,
+            "llm_timeout_secs" => self.llm_timeout_secs = None,
+            "fast_path_threshold" => self.fast_path_threshold = None,
+            "orchestration_timeout_secs" => self.orchestration_timeout_secs = None,
+            "embedding_backend" => self.embedding_backend = None,
+            "embedding_model_dir" => self.embedding_model_dir = None,
+            "max_dynamic_tools" => self.max_dynamic_tools = None,
+            "history_enabled" => self.history_enabled = None,
+            "argument_validation" => self.argument_validation = None,
+            "warm_up_concurrency" => self.warm_up_concurrency = None,
+            "discovery_timeout_secs" => self.discovery_timeout_secs = None,
+            "max_result_bytes" => self.max_result_bytes = None,
+            "max_structured_result_bytes" => self.max_structured_result_bytes = None,
+            other => return Err(unknown_routing_key(other)),
+        }
+        Ok(())
+    }
+}
+
+fn unknown_routing_key(key: &str) -> anyhow::Error {
+    anyhow!(
+        "Unknown routing key '{}'. Valid keys: {}",
+        key,
+        ROUTING_CONFIG_KEYS.join(", ")
+    )
+}
+
 pub struct McpConfigManager {
     path: PathBuf,
     config: McpConfig,
@@ -69,7 +761,12 @@ pub struct McpConfigManager {
 
 impl McpConfigManager {
     pub fn load() -> Result<Self> {
-        let path = resolve_config_path()?;
+        Self::load_from(resolve_config_path()?)
+    }
+
+    /// Load a specific mcp.json instead of the resolved default location
+    pub fn load_from(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
 
         // Try to load from file, but handle missing file gracefully
         let (config, last_loaded) = if path.exists() {
@@ -90,6 +787,7 @@ impl McpConfigManager {
             let mut config = McpConfig {
                 version: DEFAULT_VERSION.to_string(),
                 mcp_servers: std::collections::HashMap::new(),
+                routing: RoutingConfig::default(),
             };
 
             // Apply environment variable overrides
@@ -149,7 +847,7 @@ impl McpConfigManager {
     }
 
     /// Validate OpenAI endpoint URL format
-    fn validate_openai_endpoint(endpoint: &str) -> Result<()> {
+    pub(crate) fn validate_openai_endpoint(endpoint: &str) -> Result<()> {
         let url = endpoint
             .parse::<url::Url>()
             .with_context(|| format!("Invalid OpenAI endpoint URL: {}", endpoint))?;
@@ -323,15 +1021,301 @@ impl McpConfig {
         }
         Ok(())
     }
+
+    /// Servers whose tools must leave or join the routing index when moving
+    /// from `self` to `new`. A server whose launch settings changed shows up
+    /// in both lists.
+    pub fn server_changes(&self, new: &McpConfig) -> ServerChanges {
+        let enabled = |config: &McpConfig, name: &str| {
+            config
+                .mcp_servers
+                .get(name)
+                .filter(|server| server.enabled.unwrap_or(true))
+                .cloned()
+        };
+
+        let mut names: Vec<&String> = self
+            .mcp_servers
+            .keys()
+            .chain(new.mcp_servers.keys())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut changes = ServerChanges::default();
+        for name in names {
+            match (enabled(self, name), enabled(new, name)) {
+                (Some(_), None) => changes.removed.push(name.clone()),
+                (None, Some(_)) => changes.added.push(name.clone()),
+                (Some(old), Some(new)) if !old.same_launch(&new) => {
+                    changes.removed.push(name.clone());
+                    changes.added.push(name.clone());
+                }
+                _ => {}
+            }
+        }
+        changes
+    }
+}
+
+impl McpServerConfig {
+    /// Whether both configs start the same process (command, args and env)
+    pub fn same_launch(&self, other: &McpServerConfig) -> bool {
+        self.command == other.command && self.args == other.args && self.env == other.env
+    }
+}
+
+/// Result of [`McpConfig::server_changes`]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ServerChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
 }
 
 fn resolve_config_path() -> Result<PathBuf> {
-    // Only support global config at ~/.aiw/mcp.json
+    // Only support global config at ~/.aiw/mcp.json (or under AIW_CONFIG_DIR)
     // 100% compatible with Claude Code and other AI tools
-    let home = home_dir().ok_or_else(|| anyhow!("Cannot find home directory"))?;
-    Ok(home.join(".aiw").join(DEFAULT_CONFIG_FILE))
+    let dir = aiw_config_dir().ok_or_else(|| anyhow!("Cannot find home directory"))?;
+    Ok(dir.join(DEFAULT_CONFIG_FILE))
 }
 
 fn default_version() -> String {
     DEFAULT_VERSION.to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(command: &str, enabled: Option<bool>) -> McpServerConfig {
+        McpServerConfig {
+            command: command.to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            description: None,
+            category: None,
+            enabled,
+            health_check: None,
+            source: None,
+        }
+    }
+
+    fn config(servers: &[(&str, McpServerConfig)]) -> McpConfig {
+        McpConfig {
+            version: DEFAULT_VERSION.to_string(),
+            mcp_servers: servers
+                .iter()
+                .map(|(name, server)| (name.to_string(), server.clone()))
+                .collect(),
+            routing: RoutingConfig::default(),
+        }
+    }
+
+    #[test]
+    fn server_changes_tracks_added_removed_and_relaunched_servers() {
+        let old = config(&[
+            ("kept", server("kept-mcp", None)),
+            ("dropped", server("dropped-mcp", None)),
+            ("changed", server("old-mcp", None)),
+            ("disabled", server("disabled-mcp", None)),
+            ("enabled", server("enabled-mcp", Some(false))),
+        ]);
+        let new = config(&[
+            ("kept", server("kept-mcp", Some(true))),
+            ("changed", server("new-mcp", None)),
+            ("disabled", server("disabled-mcp", Some(false))),
+            ("enabled", server("enabled-mcp", None)),
+            ("fresh", server("fresh-mcp", None)),
+        ]);
+
+        let changes = old.server_changes(&new);
+        assert_eq!(changes.added, vec!["changed", "enabled", "fresh"]);
+        assert_eq!(changes.removed, vec!["changed", "disabled", "dropped"]);
+    }
+
+    #[test]
+    fn server_changes_is_empty_for_identical_configs() {
+        let config = config(&[("fs", server("fs-mcp", None))]);
+        assert_eq!(config.server_changes(&config), ServerChanges::default());
+    }
+
+    #[test]
+    fn routing_values_prefer_mcp_json_over_env_and_defaults() {
+        let routing = RoutingConfig {
+            llm_model: Some("llama3".to_string()),
+            max_dynamic_tools: Some(8),
+            ..RoutingConfig::default()
+        };
+        let env = |key: &str| match key {
+            "OPENAI_MODEL" => Some("from-env".to_string()),
+            "OPENAI_ENDPOINT" => Some("http://llm.internal:8080".to_string()),
+            _ => None,
+        };
+        let user_config = crate::utils::config_paths::UserConfig {
+            embedding_backend: Some("mock".to_string()),
+            ..Default::default()
+        };
+
+        let resolved = routing.resolve_with(env, &user_config);
+        assert_eq!(resolved.llm_model.value, "llama3");
+        assert_eq!(resolved.llm_model.source, ValueSource::McpJson);
+        assert_eq!(resolved.llm_endpoint.source, ValueSource::Env);
+        assert_eq!(resolved.llm_timeout_secs.value, DEFAULT_LLM_TIMEOUT_SECS);
+        assert_eq!(resolved.llm_timeout_secs.source, ValueSource::Default);
+        assert_eq!(
+            resolved.orchestration_timeout_secs.value,
+            DEFAULT_ORCHESTRATION_TIMEOUT_SECS
+        );
+        assert_eq!(resolved.max_dynamic_tools.value, 8);
+        assert_eq!(resolved.embedding_backend.value, "mock");
+        assert_eq!(resolved.embedding_backend.source, ValueSource::ConfigJson);
+        assert!(resolved.has_external_llm());
+    }
+
+    #[test]
+    fn routing_token_is_masked_in_rows_and_get() {
+        let routing = RoutingConfig {
+            llm_token: Some("sk-1234567890abcdef".to_string()),
+            ..RoutingConfig::default()
+        };
+        let resolved = routing.resolve_with(|_| None, &Default::default());
+        let (_, token, source) = resolved
+            .rows()
+            .into_iter()
+            .find(|(key, _, _)| *key == "llm_token")
+            .unwrap();
+        assert_eq!(token, "sk-1***cdef");
+        assert_eq!(source, ValueSource::McpJson);
+        assert_eq!(routing.get("llm_token").unwrap().unwrap(), "sk-1***cdef");
+    }
+
+    #[test]
+    fn routing_token_references_resolve_from_env_and_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let token_file = dir.path().join("token");
+        std::fs::write(&token_file, "sk-from-file\n").unwrap();
+        let env = |key: &str| (key == "ROUTING_TOKEN").then(|| "sk-from-env".to_string());
+
+        let mut routing = RoutingConfig::default();
+        routing.set("llm_token", "${env:ROUTING_TOKEN}").unwrap();
+        assert_eq!(
+            routing.get("llm_token").unwrap().unwrap(),
+            "${env:ROUTING_TOKEN}"
+        );
+        let resolved = routing.resolve_with(env, &Default::default());
+        assert_eq!(resolved.llm_token.value.as_deref(), Some("sk-from-env"));
+
+        routing
+            .set("llm_token", &format!("${{file:{}}}", token_file.display()))
+            .unwrap();
+        let resolved = routing.resolve_with(env, &Default::default());
+        assert_eq!(resolved.llm_token.value.as_deref(), Some("sk-from-file"));
+
+        // 无法解析的引用视为未设置
+        routing.set("llm_token", "${env:MISSING_TOKEN}").unwrap();
+        let resolved = routing.resolve_with(env, &Default::default());
+        assert_eq!(resolved.llm_token.value, None);
+
+        assert!(routing.set("llm_token", "${keychain:aiw}").is_err());
+        // 来源未知的写法是字面量 token
+        routing.set("llm_token", "${vault:aiw}").unwrap();
+        assert!(!is_token_reference("${vault:aiw}"));
+        let resolved = routing.resolve_with(env, &Default::default());
+        assert_eq!(resolved.llm_token.value.as_deref(), Some("${vault:aiw}"));
+    }
+
+    #[test]
+    fn routing_keys_serialize_snake_case_and_accept_camel_case() {
+        let routing: RoutingConfig = serde_json::from_value(serde_json::json!({
+            "llmEndpoint": "http://llm.internal:8080",
+            "max_result_bytes": 1024,
+            "fastPathThreshold": 0.5,
+        }))
+        .unwrap();
+        assert_eq!(
+            routing.llm_endpoint.as_deref(),
+            Some("http://llm.internal:8080")
+        );
+        assert_eq!(routing.max_result_bytes, Some(1024));
+        assert_eq!(routing.fast_path_threshold, Some(0.5));
+
+        let written = serde_json::to_value(&routing).unwrap();
+        assert_eq!(written["llm_endpoint"], "http://llm.internal:8080");
+        assert_eq!(written["fast_path_threshold"], 0.5);
+        assert!(written.get("llmEndpoint").is_none());
+    }
+
+    #[test]
+    fn routing_set_validates_values_and_unset_clears() {
+        let mut routing = RoutingConfig::default();
+        routing.set("fast_path_threshold", "0.9").unwrap();
+        routing
+            .set("llm_endpoint", "https://api.example.com/v1")
+            .unwrap();
+        assert_eq!(routing.fast_path_threshold, Some(0.9));
+
+        assert!(routing.set("fast_path_threshold", "1.5").is_err());
+        assert!(routing.set("max_dynamic_tools", "0").is_err());
+        assert!(routing.set("orchestration_timeout_secs", "0").is_err());
+        routing.set("orchestration_timeout_secs", "30").unwrap();
+        assert_eq!(
+            routing
+                .resolve_with(|_| None, &Default::default())
+                .orchestration_timeout_secs
+                .value,
+            30
+        );
+        routing.unset("orchestration_timeout_secs").unwrap();
+        assert!(routing.set("llm_endpoint", "ftp://example.com").is_err());
+        assert!(routing.set("embedding_backend", "gpu").is_err());
+        assert!(routing.set("history_enabled", "maybe").is_err());
+        routing.set("history_enabled", "off").unwrap();
+        assert_eq!(routing.history_enabled, Some(false));
+        routing.unset("history_enabled").unwrap();
+        assert!(routing.set("argument_validation", "lenient").is_err());
+        let resolved = routing.resolve_with(|_| None, &Default::default());
+        assert_eq!(resolved.argument_validation.value, ArgumentValidation::Warn);
+        assert_eq!(resolved.argument_validation.source, ValueSource::Default);
+        routing.set("argument_validation", "REJECT").unwrap();
+        assert_eq!(routing.argument_validation.as_deref(), Some("reject"));
+        assert_eq!(
+            routing
+                .resolve_with(|_| None, &Default::default())
+                .argument_validation
+                .value,
+            ArgumentValidation::Reject
+        );
+        routing.unset("argument_validation").unwrap();
+        assert!(routing.set("warm_up_concurrency", "0").is_err());
+        assert!(routing.set("discovery_timeout_secs", "soon").is_err());
+        routing.set("warm_up_concurrency", "8").unwrap();
+        let resolved = routing.resolve_with(|_| None, &Default::default());
+        assert_eq!(resolved.warm_up_concurrency.value, 8);
+        assert_eq!(
+            resolved.discovery_timeout_secs.value,
+            DEFAULT_DISCOVERY_TIMEOUT_SECS
+        );
+        routing.unset("warm_up_concurrency").unwrap();
+        assert!(routing.set("max_result_bytes", "0").is_err());
+        assert!(routing.set("max_structured_result_bytes", "1MB").is_err());
+        routing.set("max_result_bytes", "4096").unwrap();
+        let limits = routing
+            .resolve_with(|_| None, &Default::default())
+            .result_limits();
+        assert_eq!(limits.max_result_bytes, 4096);
+        assert_eq!(
+            limits.max_structured_result_bytes,
+            DEFAULT_MAX_STRUCTURED_RESULT_BYTES
+        );
+        routing.unset("max_result_bytes").unwrap();
+        assert!(routing
+            .set("colour", "blue")
+            .unwrap_err()
+            .to_string()
+            .contains("Valid keys"));
+
+        routing.unset("fast_path_threshold").unwrap();
+        routing.unset("llm_endpoint").unwrap();
+        assert!(routing.is_empty());
+    }
+}
@@ -1,8 +1,8 @@
 //! MCP配置文件监听和热重载
 //!
-//! 监听 ~/.aiw/mcp.json 文件变化并自动重载配置
+//! 监听 ~/.aiw/mcp.json 文件变化并自动重载配置，新增/移除的服务器会增量更新路由索引
 
-use crate::mcp_routing::{config::McpConfigManager, McpConnectionPool};
+use crate::mcp_routing::{config::McpConfigManager, IntelligentRouter};
 use anyhow::{Context, Result};
 use notify::{
     event::{AccessKind, AccessMode, ModifyKind},
@@ -13,7 +13,7 @@ use tokio::sync::mpsc;
 
 /// Start watching MCP configuration file for changes
 pub async fn start_config_watcher(
-    connection_pool: Arc<McpConnectionPool>,
+    router: Arc<IntelligentRouter>,
     config_path: PathBuf,
 ) -> Result<()> {
     let (tx, mut rx) = mpsc::channel(100);
@@ -22,22 +22,22 @@ pub async fn start_config_watcher(
     let watcher_path = config_path.clone();
     std::thread::spawn(move || {
         if let Err(e) = run_file_watcher(watcher_path, tx) {
-            eprintln!("⚠️  Config file watcher stopped: {}", e);
+            tracing::warn!("Config file watcher stopped: {}", e);
         }
     });
 
     // Handle file change events
     tokio::spawn(async move {
-        eprintln!("👀 Watching MCP config file: {}", config_path.display());
+        tracing::info!("Watching MCP config file: {}", config_path.display());
 
         while let Some(event) = rx.recv().await {
             if should_reload(&event) {
-                match reload_config(&connection_pool).await {
+                match reload_config(&router).await {
                     Ok(()) => {
                         // Success message is printed in update_config
                     }
                     Err(e) => {
-                        eprintln!("⚠️  Failed to reload MCP config: {}", e);
+                        tracing::warn!("Failed to reload MCP config: {}", e);
                     }
                 }
             }
@@ -91,7 +91,7 @@ fn should_reload(event: &Event) -> bool {
     }
 }
 
-async fn reload_config(connection_pool: &McpConnectionPool) -> Result<()> {
+async fn reload_config(router: &IntelligentRouter) -> Result<()> {
     // Small delay to ensure file write is complete
     tokio::time::sleep(Duration::from_millis(100)).await;
 
@@ -99,7 +99,7 @@ async fn reload_config(connection_pool: &McpConnectionPool) -> Result<()> {
 
     let new_config = Arc::new(config_manager.config().clone());
 
-    connection_pool.update_config(new_config).await;
+    router.apply_config(new_config).await;
 
     Ok(())
 }
@@ -8,14 +8,37 @@ use ollama_rs::{
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::{collections::HashSet, sync::Arc, time::Duration};
+use thiserror::Error;
 use tokio::time::timeout;
 
+/// LLM calls per decision: the first answer plus corrective retries when the
+/// model names a tool that is not among the candidates.
+pub const MAX_DECISION_ATTEMPTS: usize = 3;
+
+/// How long the startup probe waits for the decision endpoint to answer.
+pub const LLM_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Check that something answers HTTP at the decision `endpoint`.
+///
+/// Any response counts, including 401/404 from OpenAI-compatible APIs; only
+/// connection failures and timeouts mean the LLM is unreachable.
+pub async fn probe_endpoint(endpoint: &str, timeout: Duration) -> Result<()> {
+    let client = reqwest::Client::builder().timeout(timeout).build()?;
+    client
+        .get(endpoint)
+        .send()
+        .await
+        .with_context(|| format!("LLM endpoint {} is unreachable", endpoint))?;
+    Ok(())
+}
+
 #[derive(Debug, Clone)]
 pub struct CandidateToolInfo {
     pub server: String,
     pub tool: String,
     pub description: String,
     pub schema_snippet: Option<String>,
+    pub category: String,
 }
 
 pub struct DecisionInput {
@@ -32,6 +55,23 @@ pub struct DecisionOutcome {
     pub confidence: f32,
 }
 
+/// Why `DecisionEngine::decide` could not produce a usable decision.
+#[derive(Debug, Error)]
+pub enum DecisionError {
+    #[error("No candidates available for decision engine")]
+    NoCandidates,
+    /// The LLM could not be reached, failed, or timed out.
+    #[error("LLM unavailable: {0}")]
+    LlmUnavailable(String),
+    /// The LLM kept choosing a tool that is not among the candidates.
+    #[error("LLM returned unknown tool {server}::{tool} after {attempts} attempts")]
+    InvalidTool {
+        server: String,
+        tool: String,
+        attempts: usize,
+    },
+}
+
 /// Abstracts LLM chat completion clients so they can be mocked in tests.
 #[async_trait]
 pub trait LlmClient: Send + Sync {
@@ -81,38 +121,66 @@ impl DecisionEngine {
         }
     }
 
-    pub async fn decide(&self, input: DecisionInput) -> Result<DecisionOutcome> {
+    /// Ask the LLM to pick one of `input.candidates`.
+    ///
+    /// A reply naming a server/tool outside the candidates gets a corrective
+    /// follow-up, up to [`MAX_DECISION_ATTEMPTS`] calls in total. Unparsable
+    /// replies still fall back to the top-ranked candidate.
+    pub async fn decide(&self, input: DecisionInput) -> Result<DecisionOutcome, DecisionError> {
         if input.candidates.is_empty() {
-            return Err(anyhow!("No candidates available for decision engine"));
+            return Err(DecisionError::NoCandidates);
         }
         let system_prompt = "You are Agentic-Warden's internal router. \
             Choose the best MCP tool for the user request. \
             Respond ONLY with valid JSON in the following shape: \n\
             {\"server\": \"server-name\", \"tool\": \"tool-name\", \"arguments\": {...}, \"rationale\": \"why\", \"confidence\": 0.0-1.0}";
 
-        let user_prompt = build_user_prompt(&input);
-        let request = ChatMessageRequest::new(
-            self.model.clone(),
-            vec![
-                ChatMessage::system(system_prompt.to_string()),
-                ChatMessage::user(user_prompt),
-            ],
-        );
+        let mut messages = vec![
+            ChatMessage::system(system_prompt.to_string()),
+            ChatMessage::user(build_user_prompt(&input)),
+        ];
+        let mut rejected = None;
+
+        for _ in 0..MAX_DECISION_ATTEMPTS {
+            let request = ChatMessageRequest::new(self.model.clone(), messages.clone());
+            let response = timeout(self.timeout, self.client.chat(request))
+                .await
+                .map_err(|_| DecisionError::LlmUnavailable("LLM decision timed out".into()))?
+                .map_err(|err| DecisionError::LlmUnavailable(err.to_string()))?;
+            let content = response.message.content;
+
+            let decision = match parse_decision(&content, &input.candidates) {
+                Ok(decision) => decision,
+                Err(_) => {
+                    // Fallback to first candidate with empty arguments.
+                    let fallback = &input.candidates[0];
+                    return Ok(DecisionOutcome {
+                        server: fallback.server.clone(),
+                        tool: fallback.tool.clone(),
+                        arguments: Value::Object(Default::default()),
+                        rationale: "Fallback to top-ranked candidate due to parsing error".into(),
+                        confidence: 0.25,
+                    });
+                }
+            };
 
-        let response = timeout(self.timeout, self.client.chat(request))
-            .await
-            .map_err(|_| anyhow!("LLM decision timed out"))??;
-
-        parse_decision(&response.message.content, &input.candidates).or_else(|_| {
-            // Fallback to first candidate with empty arguments.
-            let fallback = &input.candidates[0];
-            Ok(DecisionOutcome {
-                server: fallback.server.clone(),
-                tool: fallback.tool.clone(),
-                arguments: Value::Object(Default::default()),
-                rationale: "Fallback to top-ranked candidate due to parsing error".into(),
-                confidence: 0.25,
-            })
+            if is_candidate(&decision, &input.candidates) {
+                return Ok(decision);
+            }
+
+            messages.push(ChatMessage::assistant(content));
+            messages.push(ChatMessage::user(build_corrective_prompt(
+                &decision,
+                &input.candidates,
+            )));
+            rejected = Some((decision.server, decision.tool));
+        }
+
+        let (server, tool) = rejected.unwrap_or_default();
+        Err(DecisionError::InvalidTool {
+            server,
+            tool,
+            attempts: MAX_DECISION_ATTEMPTS,
         })
     }
 
@@ -260,6 +328,26 @@ fn build_user_prompt(input: &DecisionInput) -> String {
     prompt
 }
 
+fn is_candidate(decision: &DecisionOutcome, candidates: &[CandidateToolInfo]) -> bool {
+    candidates
+        .iter()
+        .any(|c| c.server == decision.server && c.tool == decision.tool)
+}
+
+fn build_corrective_prompt(decision: &DecisionOutcome, candidates: &[CandidateToolInfo]) -> String {
+    let names: Vec<String> = candidates
+        .iter()
+        .map(|c| format!("{}::{}", c.server, c.tool))
+        .collect();
+    format!(
+        "{}::{} is not one of the candidate tools. Choose exactly one of: {}. \
+        Respond again with the same JSON shape.",
+        decision.server,
+        decision.tool,
+        names.join(", ")
+    )
+}
+
 fn parse_decision(content: &str, candidates: &[CandidateToolInfo]) -> Result<DecisionOutcome> {
     #[derive(Deserialize)]
     struct Decision {
@@ -627,3 +715,132 @@ impl WorkflowPlannerEngine for DecisionEngine {
         DecisionEngine::generate_js_code(self, plan).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// Replays canned replies and records how many chat requests were made.
+    struct ScriptedClient {
+        replies: Mutex<Vec<Result<String, String>>>,
+        requests: Mutex<Vec<usize>>,
+    }
+
+    impl ScriptedClient {
+        fn new(replies: Vec<Result<&str, &str>>) -> Arc<Self> {
+            Arc::new(Self {
+                replies: Mutex::new(
+                    replies
+                        .into_iter()
+                        .rev()
+                        .map(|reply| reply.map(str::to_string).map_err(str::to_string))
+                        .collect(),
+                ),
+                requests: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl LlmClient for ScriptedClient {
+        async fn chat(&self, request: ChatMessageRequest) -> Result<ChatMessageResponse> {
+            self.requests.lock().push(request.messages.len());
+            let content = self
+                .replies
+                .lock()
+                .pop()
+                .expect("unexpected LLM call")
+                .map_err(|err| anyhow!(err))?;
+            Ok(serde_json::from_value(json!({
+                "model": "mock",
+                "created_at": "2024-01-01T00:00:00Z",
+                "message": {"role": "assistant", "content": content},
+                "done": true
+            }))?)
+        }
+    }
+
+    fn input() -> DecisionInput {
+        let candidate = |server: &str, tool: &str| CandidateToolInfo {
+            server: server.to_string(),
+            tool: tool.to_string(),
+            description: String::new(),
+            schema_snippet: None,
+            category: "general".to_string(),
+        };
+        DecisionInput {
+            user_request: "read README.md".to_string(),
+            candidates: vec![candidate("fs", "read_file"), candidate("git", "log")],
+        }
+    }
+
+    #[tokio::test]
+    async fn hallucinated_tool_is_retried_with_a_correction() {
+        let client = ScriptedClient::new(vec![
+            Ok(r#"{"server": "fs", "tool": "cat_file", "arguments": {}}"#),
+            Ok(r#"{"server": "fs", "tool": "read_file", "arguments": {"path": "README.md"}}"#),
+        ]);
+        let engine = DecisionEngine::with_client(client.clone(), "mock", 5);
+
+        let decision = engine.decide(input()).await.unwrap();
+        assert_eq!(decision.tool, "read_file");
+        assert_eq!(decision.arguments["path"], "README.md");
+        // Second request carries the rejected answer and the correction.
+        assert_eq!(*client.requests.lock(), vec![2, 4]);
+    }
+
+    #[tokio::test]
+    async fn persistent_hallucination_is_reported_as_invalid_tool() {
+        let reply = Ok(r#"{"server": "web", "tool": "fetch"}"#);
+        let client = ScriptedClient::new(vec![reply; MAX_DECISION_ATTEMPTS]);
+        let engine = DecisionEngine::with_client(client, "mock", 5);
+
+        match engine.decide(input()).await {
+            Err(DecisionError::InvalidTool {
+                server,
+                tool,
+                attempts,
+            }) => {
+                assert_eq!((server.as_str(), tool.as_str()), ("web", "fetch"));
+                assert_eq!(attempts, MAX_DECISION_ATTEMPTS);
+            }
+            other => panic!("expected InvalidTool, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_failure_is_reported_as_unavailable() {
+        let client = ScriptedClient::new(vec![Err("connection refused")]);
+        let engine = DecisionEngine::with_client(client, "mock", 5);
+
+        assert!(matches!(
+            engine.decide(input()).await,
+            Err(DecisionError::LlmUnavailable(message)) if message.contains("connection refused")
+        ));
+    }
+
+    #[tokio::test]
+    async fn probe_accepts_any_http_response_and_rejects_closed_ports() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let open = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0u8; 1024]);
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+        });
+        probe_endpoint(&open, LLM_PROBE_TIMEOUT).await.unwrap();
+
+        let closed = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let err = probe_endpoint(&closed, LLM_PROBE_TIMEOUT)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unreachable"), "{err}");
+    }
+}
@@ -1,4 +1,32 @@
-use anyhow::{anyhow, Result};
+use super::config::ResolvedRoutingConfig;
+use crate::config::{
+    EMBEDDING_BACKEND_ENV, EMBEDDING_MODEL_DIR_ENV, FASTEMBED_CACHE_ENV, FASTEMBED_MIRROR_ENV,
+};
+use crate::utils::config_paths::{expand_home, ConfigPaths};
+use anyhow::{anyhow, Context, Result};
+use fastembed::{
+    EmbeddingModel, InitOptions, InitOptionsUserDefined, Pooling, TextEmbedding, TokenizerFiles,
+    UserDefinedEmbeddingModel,
+};
+use parking_lot::Mutex;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// all-MiniLM-L6-v2 output dimension (shared by every backend so indexes stay compatible).
+pub const EMBEDDING_DIMENSION: usize = 384;
+
+/// Model directory checked when nothing is configured: `~/.aiw/models/all-MiniLM-L6-v2`.
+const BUNDLED_MODEL_DIR: &str = "models/all-MiniLM-L6-v2";
+
+/// Download attempts before fastembed is given up on.
+const DOWNLOAD_ATTEMPTS: u32 = 3;
+/// Wait before the first retry; doubled after each failed attempt.
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(2);
+/// Endpoint variable read by fastembed's Hugging Face client.
+const HF_ENDPOINT_ENV: &str = "HF_ENDPOINT";
 
 /// Type alias for the embedding generator closure (keeps signatures readable).
 type EmbeddingGenerator = dyn Fn(&str) -> Vec<f32> + Send + Sync;
@@ -47,4 +75,423 @@ impl EmbeddingBackend for MockEmbeddingBackend {
         }
         Ok(results)
     }
-}
\ No newline at end of file
+}
+
+/// fastembed (ONNX Runtime) backend, either downloaded from Hugging Face or loaded from disk.
+pub struct FastEmbedBackend {
+    model: Mutex<TextEmbedding>,
+}
+
+/// Where and how fastembed fetches its model.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadOptions {
+    /// Overrides fastembed's `.fastembed_cache` in the working directory
+    pub cache_dir: Option<PathBuf>,
+    /// Hugging Face mirror used instead of huggingface.co
+    pub mirror: Option<String>,
+    pub attempts: u32,
+    pub retry_delay: Duration,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            cache_dir: None,
+            mirror: None,
+            attempts: DOWNLOAD_ATTEMPTS,
+            retry_delay: DOWNLOAD_RETRY_DELAY,
+        }
+    }
+}
+
+impl DownloadOptions {
+    /// Read `AIW_FASTEMBED_CACHE` / `AIW_FASTEMBED_MIRROR`.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(env: impl Fn(&str) -> Option<String>) -> Self {
+        let value = |key: &str| {
+            env(key)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        Self {
+            cache_dir: value(FASTEMBED_CACHE_ENV).map(|dir| expand_home(&dir)),
+            mirror: value(FASTEMBED_MIRROR_ENV).map(|url| url.trim_end_matches('/').to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Delay before retry number `attempt` (1-based): 2s, 4s, 8s, ...
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.retry_delay * 2u32.saturating_pow(attempt.saturating_sub(1))
+    }
+}
+
+impl FastEmbedBackend {
+    /// Download (or reuse the cached) all-MiniLM-L6-v2 model.
+    pub fn download() -> Result<Self> {
+        Self::download_with(&DownloadOptions::from_env())
+    }
+
+    /// Download with retries; the final error explains the offline and mirror options.
+    ///
+    /// Blocks for the download and the backoff between attempts, so async callers
+    /// must run it on a blocking thread.
+    pub fn download_with(options: &DownloadOptions) -> Result<Self> {
+        if options.mirror.is_some() && std::env::var_os(HF_ENDPOINT_ENV).is_none() {
+            // fastembed 只从 HF_ENDPOINT 读取 Hub 地址，见 export_mirror_endpoint
+            tracing::warn!(
+                "{} is set but {} is not; the model is downloaded from huggingface.co",
+                FASTEMBED_MIRROR_ENV,
+                HF_ENDPOINT_ENV
+            );
+        }
+
+        let attempts = options.attempts.max(1);
+        let mut last_error = String::new();
+        for attempt in 1..=attempts {
+            let mut init =
+                InitOptions::new(EmbeddingModel::AllMiniLML6V2).with_show_download_progress(true);
+            if let Some(dir) = &options.cache_dir {
+                init = init.with_cache_dir(dir.clone());
+            }
+            match TextEmbedding::try_new(init) {
+                Ok(model) => {
+                    return Ok(Self {
+                        model: Mutex::new(model),
+                    })
+                }
+                Err(e) => last_error = e.to_string(),
+            }
+            if attempt < attempts {
+                let delay = options.backoff(attempt);
+                tracing::warn!(
+                    "fastembed model download failed (attempt {}/{}): {}; retrying in {}s",
+                    attempt,
+                    attempts,
+                    last_error,
+                    delay.as_secs()
+                );
+                std::thread::sleep(delay);
+            }
+        }
+        Err(download_error(options, attempts, &last_error))
+    }
+
+    /// Load an all-MiniLM-L6-v2 export from `dir` without touching the network.
+    ///
+    /// The directory must contain `model.onnx`, `tokenizer.json`, `config.json`,
+    /// `special_tokens_map.json` and `tokenizer_config.json`.
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let read = |name: &str| {
+            let path = dir.join(name);
+            std::fs::read(&path).with_context(|| format!("missing model file {}", path.display()))
+        };
+        let tokenizer_files = TokenizerFiles {
+            tokenizer_file: read("tokenizer.json")?,
+            config_file: read("config.json")?,
+            special_tokens_map_file: read("special_tokens_map.json")?,
+            tokenizer_config_file: read("tokenizer_config.json")?,
+        };
+        let definition = UserDefinedEmbeddingModel::new(read("model.onnx")?, tokenizer_files)
+            .with_pooling(Pooling::Mean);
+        let model =
+            TextEmbedding::try_new_from_user_defined(definition, InitOptionsUserDefined::default())
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to load embedding model from {}: {}",
+                        dir.display(),
+                        e
+                    )
+                })?;
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+}
+
+impl EmbeddingBackend for FastEmbedBackend {
+    fn dimension(&self) -> usize {
+        EMBEDDING_DIMENSION
+    }
+
+    fn embed_batch(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.model
+            .lock()
+            .embed(inputs.to_vec(), None)
+            .map_err(|e| anyhow!("Embedding generation failed: {}", e))
+    }
+}
+
+/// Export `AIW_FASTEMBED_MIRROR` as `HF_ENDPOINT` for fastembed's Hub client.
+///
+/// fastembed has no endpoint option, and `set_var` is unsound once other threads
+/// may read the environment, so `main` calls this before starting the tokio runtime.
+/// An explicit `HF_ENDPOINT` wins.
+pub fn export_mirror_endpoint() {
+    if std::env::var_os(HF_ENDPOINT_ENV).is_some() {
+        return;
+    }
+    if let Some(mirror) = DownloadOptions::from_env().mirror {
+        std::env::set_var(HF_ENDPOINT_ENV, mirror);
+    }
+}
+
+fn download_error(options: &DownloadOptions, attempts: u32, cause: &str) -> anyhow::Error {
+    let source = options
+        .mirror
+        .as_deref()
+        .map(|mirror| format!(" from {}", mirror))
+        .unwrap_or_default();
+    anyhow!(
+        "Failed to download the all-MiniLM-L6-v2 embedding model{} after {} attempt(s): {}\n\
+         If huggingface.co is blocked or slow, set {}=https://hf-mirror.com (or another mirror).\n\
+         To run offline, point {} at a local model directory or set {}=mock.",
+        source,
+        attempts,
+        cause,
+        FASTEMBED_MIRROR_ENV,
+        EMBEDDING_MODEL_DIR_ENV,
+        EMBEDDING_BACKEND_ENV
+    )
+}
+
+/// Deterministic bag-of-words vector: each lowercase token is hashed into a bucket.
+///
+/// Only keyword overlap is captured, so routing quality is lower than with a real
+/// model, but it needs no model files at all.
+pub fn hashed_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0; EMBEDDING_DIMENSION];
+    for token in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+    {
+        let mut hasher = DefaultHasher::new();
+        token.to_lowercase().hash(&mut hasher);
+        vector[(hasher.finish() % EMBEDDING_DIMENSION as u64) as usize] += 1.0;
+    }
+    if vector.iter().all(|value| *value == 0.0) {
+        vector[0] = 1.0;
+    }
+    vector
+}
+
+/// Which embedding backend the router should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingBackendKind {
+    /// Local model directory if configured, then fastembed download, then the
+    /// bundled model directory, then the keyword-hash fallback.
+    #[default]
+    Auto,
+    FastEmbed,
+    Local,
+    Mock,
+}
+
+impl EmbeddingBackendKind {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "auto" => Ok(Self::Auto),
+            "fastembed" => Ok(Self::FastEmbed),
+            "local" => Ok(Self::Local),
+            "mock" | "hash" => Ok(Self::Mock),
+            other => Err(anyhow!(
+                "Unknown embedding backend '{}' (expected auto, fastembed, local or mock)",
+                other
+            )),
+        }
+    }
+}
+
+/// Embedding backend selected at startup, with a label for diagnostics.
+pub struct SelectedEmbedding {
+    pub backend: Arc<dyn EmbeddingBackend>,
+    pub description: String,
+}
+
+impl SelectedEmbedding {
+    fn new(backend: impl EmbeddingBackend + 'static, description: String) -> Self {
+        Self {
+            backend: Arc::new(backend),
+            description,
+        }
+    }
+
+    fn hashed() -> Self {
+        Self::new(
+            MockEmbeddingBackend::new(EMBEDDING_DIMENSION, hashed_embedding),
+            "keyword hash (no model, reduced routing quality)".to_string(),
+        )
+    }
+}
+
+/// Build the embedding backend described by the resolved `routing` settings.
+pub fn select_embedding_backend(routing: &ResolvedRoutingConfig) -> Result<SelectedEmbedding> {
+    let kind = EmbeddingBackendKind::parse(&routing.embedding_backend.value)?;
+    let model_dir = routing
+        .embedding_model_dir
+        .value
+        .as_deref()
+        .map(expand_home);
+    let bundled_dir = ConfigPaths::new()
+        .ok()
+        .map(|paths| paths.config_dir.join(BUNDLED_MODEL_DIR));
+
+    let selected = resolve_backend(kind, model_dir, bundled_dir)?;
+    tracing::info!("Embedding backend: {}", selected.description);
+    Ok(selected)
+}
+
+fn resolve_backend(
+    kind: EmbeddingBackendKind,
+    model_dir: Option<PathBuf>,
+    bundled_dir: Option<PathBuf>,
+) -> Result<SelectedEmbedding> {
+    let load_local = |dir: &Path| {
+        FastEmbedBackend::from_dir(dir).map(|backend| {
+            SelectedEmbedding::new(backend, format!("local model ({})", dir.display()))
+        })
+    };
+
+    match kind {
+        EmbeddingBackendKind::FastEmbed => FastEmbedBackend::download().map(|backend| {
+            SelectedEmbedding::new(backend, "fastembed all-MiniLM-L6-v2".to_string())
+        }),
+        EmbeddingBackendKind::Local => {
+            let dir = model_dir.or(bundled_dir).ok_or_else(|| {
+                anyhow!(
+                    "Embedding backend 'local' requires {} or embedding_model_dir in config.json",
+                    EMBEDDING_MODEL_DIR_ENV
+                )
+            })?;
+            load_local(&dir)
+        }
+        EmbeddingBackendKind::Mock => Ok(SelectedEmbedding::hashed()),
+        EmbeddingBackendKind::Auto => {
+            if let Some(dir) = &model_dir {
+                match load_local(dir) {
+                    Ok(selected) => return Ok(selected),
+                    Err(err) => tracing::warn!("{:#}", err),
+                }
+            }
+            match FastEmbedBackend::download() {
+                Ok(backend) => {
+                    return Ok(SelectedEmbedding::new(
+                        backend,
+                        "fastembed all-MiniLM-L6-v2".to_string(),
+                    ))
+                }
+                Err(err) => tracing::warn!("{:#}", err),
+            }
+            if let Some(dir) = bundled_dir.filter(|dir| dir.join("model.onnx").is_file()) {
+                match load_local(&dir) {
+                    Ok(selected) => return Ok(selected),
+                    Err(err) => tracing::warn!("{:#}", err),
+                }
+            }
+            tracing::warn!(
+                "No embedding model available; set {} to a local model directory for better routing",
+                EMBEDDING_MODEL_DIR_ENV
+            );
+            Ok(SelectedEmbedding::hashed())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_backend_kinds() {
+        assert_eq!(
+            EmbeddingBackendKind::parse("").unwrap(),
+            EmbeddingBackendKind::Auto
+        );
+        assert_eq!(
+            EmbeddingBackendKind::parse("FastEmbed").unwrap(),
+            EmbeddingBackendKind::FastEmbed
+        );
+        assert_eq!(
+            EmbeddingBackendKind::parse("local").unwrap(),
+            EmbeddingBackendKind::Local
+        );
+        assert_eq!(
+            EmbeddingBackendKind::parse("mock").unwrap(),
+            EmbeddingBackendKind::Mock
+        );
+        assert!(EmbeddingBackendKind::parse("openai").is_err());
+    }
+
+    #[test]
+    fn hashed_embedding_is_deterministic_and_keyword_sensitive() {
+        let a = hashed_embedding("Take a screenshot");
+        assert_eq!(a.len(), EMBEDDING_DIMENSION);
+        assert_eq!(a, hashed_embedding("take a SCREENSHOT"));
+        assert_ne!(a, hashed_embedding("read a file"));
+        assert_eq!(hashed_embedding("").len(), EMBEDDING_DIMENSION);
+    }
+
+    #[test]
+    fn local_backend_reports_missing_model_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = resolve_backend(
+            EmbeddingBackendKind::Local,
+            Some(dir.path().to_path_buf()),
+            None,
+        )
+        .err()
+        .expect("empty directory cannot be loaded");
+        assert!(format!("{:#}", err).contains("missing model file"));
+
+        let err = resolve_backend(EmbeddingBackendKind::Local, None, None)
+            .err()
+            .expect("local backend needs a directory");
+        assert!(err.to_string().contains(EMBEDDING_MODEL_DIR_ENV));
+    }
+
+    #[test]
+    fn download_options_read_cache_and_mirror() {
+        let options = DownloadOptions::from_lookup(|key| match key {
+            FASTEMBED_CACHE_ENV => Some("/var/cache/aiw".to_string()),
+            FASTEMBED_MIRROR_ENV => Some(" https://hf-mirror.com/ ".to_string()),
+            _ => None,
+        });
+        assert_eq!(options.cache_dir, Some(PathBuf::from("/var/cache/aiw")));
+        assert_eq!(options.mirror.as_deref(), Some("https://hf-mirror.com"));
+        assert_eq!(options.attempts, DOWNLOAD_ATTEMPTS);
+        assert_eq!(options.backoff(1), DOWNLOAD_RETRY_DELAY);
+        assert_eq!(options.backoff(3), DOWNLOAD_RETRY_DELAY * 4);
+
+        assert_eq!(
+            DownloadOptions::from_lookup(|_| Some("  ".to_string())),
+            DownloadOptions::default()
+        );
+    }
+
+    #[test]
+    fn download_error_points_at_mirror_and_offline_options() {
+        let options = DownloadOptions {
+            mirror: Some("https://hf-mirror.com".to_string()),
+            ..DownloadOptions::default()
+        };
+        let message = download_error(&options, 3, "connection reset").to_string();
+        assert!(message.contains("from https://hf-mirror.com after 3 attempt(s)"));
+        assert!(message.contains(FASTEMBED_MIRROR_ENV));
+        assert!(message.contains(EMBEDDING_MODEL_DIR_ENV));
+        assert!(message.contains(EMBEDDING_BACKEND_ENV));
+    }
+
+    #[test]
+    fn mock_backend_embeds_with_router_dimension() {
+        let selected = resolve_backend(EmbeddingBackendKind::Mock, None, None).unwrap();
+        assert_eq!(selected.backend.dimension(), EMBEDDING_DIMENSION);
+        let vectors = selected
+            .backend
+            .embed_batch(&["list files".to_string()])
+            .unwrap();
+        assert_eq!(vectors[0].len(), EMBEDDING_DIMENSION);
+    }
+}
@@ -0,0 +1,356 @@
+//! Per-server health tracking for the MCP connection pool.
+//!
+//! A server is marked down after [`UNHEALTHY_AFTER_FAILURES`] consecutive call
+//! failures (or immediately if it cannot be started). While down, calls are
+//! rejected until the reconnect backoff elapses; the next call (or the pool
+//! supervisor) then gets one reconnect attempt, and each failed attempt doubles
+//! the wait. After [`MAX_RECONNECT_ATTEMPTS`] the server is given up on until
+//! its config is reloaded.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Consecutive failures before a server is considered down
+pub const UNHEALTHY_AFTER_FAILURES: u32 = 3;
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(5);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(300);
+/// Reconnect/restart attempts before a server is permanently down
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+/// A server that stays up this long gets a fresh restart budget
+const STABLE_UPTIME: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ServerStatus {
+    Up,
+    Down,
+    /// Restart attempts exhausted; stays down until mcp.json is reloaded
+    Failed,
+    Disabled,
+}
+
+impl ServerStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServerStatus::Up => "up",
+            ServerStatus::Down => "down",
+            ServerStatus::Failed => "failed",
+            ServerStatus::Disabled => "disabled",
+        }
+    }
+}
+
+/// Health snapshot of one configured server
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ServerHealth {
+    pub server: String,
+    pub status: ServerStatus,
+    /// Whether the pool currently holds a live connection
+    pub connected: bool,
+    pub consecutive_failures: u32,
+    /// Reconnect/restart attempts since the server last stayed up
+    pub restart_attempts: u32,
+    pub last_error: Option<String>,
+    /// Seconds until the next reconnect attempt, when down
+    pub retry_in_secs: Option<u64>,
+}
+
+/// Whether a call to a server may go ahead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CallGate {
+    Healthy,
+    /// Server is down but the backoff elapsed: drop the old connection and retry
+    Reconnect,
+    /// Server is down and still backing off
+    Blocked(String),
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    reconnect_attempts: u32,
+    retry_at: Option<Instant>,
+    up_since: Option<Instant>,
+    gave_up: bool,
+}
+
+impl Entry {
+    fn is_down(&self) -> bool {
+        self.gave_up || self.consecutive_failures >= UNHEALTHY_AFTER_FAILURES
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct HealthTracker {
+    entries: HashMap<String, Entry>,
+}
+
+impl HealthTracker {
+    /// Whether routing may pick `server`: it is up, or it is down but its
+    /// reconnect backoff has elapsed, so the call gets the reconnect attempt
+    pub fn is_healthy(&self, server: &str, now: Instant) -> bool {
+        let Some(entry) = self.entries.get(server).filter(|entry| entry.is_down()) else {
+            return true;
+        };
+        !entry.gave_up && entry.retry_at.is_some_and(|retry_at| retry_at <= now)
+    }
+
+    pub fn has_given_up(&self, server: &str) -> bool {
+        self.entries.get(server).is_some_and(|entry| entry.gave_up)
+    }
+
+    /// Decide whether a call may proceed. A due reconnect claims the slot by
+    /// pushing `retry_at` forward, so concurrent callers don't all respawn it.
+    pub fn begin_call(&mut self, server: &str, now: Instant) -> CallGate {
+        let Some(entry) = self.entries.get_mut(server).filter(|entry| entry.is_down()) else {
+            return CallGate::Healthy;
+        };
+
+        if entry.gave_up {
+            return CallGate::Blocked(format!(
+                "MCP server '{}' is permanently down after {} restart attempts (last error: {}); fix it and save mcp.json to retry",
+                server,
+                entry.reconnect_attempts,
+                entry.last_error.as_deref().unwrap_or("unknown")
+            ));
+        }
+
+        match entry.retry_at {
+            Some(retry_at) if retry_at > now => CallGate::Blocked(format!(
+                "MCP server '{}' is down after {} consecutive failures (last error: {}); next reconnect attempt in {}s",
+                server,
+                entry.consecutive_failures,
+                entry.last_error.as_deref().unwrap_or("unknown"),
+                retry_at.saturating_duration_since(now).as_secs().max(1)
+            )),
+            _ => {
+                entry.retry_at = Some(now + reconnect_delay(entry.reconnect_attempts));
+                CallGate::Reconnect
+            }
+        }
+    }
+
+    /// Record a successful call or (re)connect. The restart budget is only
+    /// refilled once the server has stayed up for a while, so a crash loop
+    /// still runs into [`MAX_RECONNECT_ATTEMPTS`].
+    pub fn record_success(&mut self, server: &str, now: Instant) {
+        if let Some(entry) = self.entries.get_mut(server) {
+            if entry.is_down() {
+                entry.up_since = Some(now);
+            }
+            entry.consecutive_failures = 0;
+            entry.last_error = None;
+            entry.retry_at = None;
+        }
+    }
+
+    /// Forget everything about a server (config reloaded or server removed)
+    pub fn reset(&mut self, server: &str) {
+        self.entries.remove(server);
+    }
+
+    /// Record a failed call. Returns true when the server is (now) down.
+    pub fn record_failure(&mut self, server: &str, error: String, now: Instant) -> bool {
+        let entry = self.entries.entry(server.to_string()).or_default();
+        entry.consecutive_failures += 1;
+        entry.last_error = Some(error);
+        if entry.is_down() && !entry.gave_up {
+            if entry
+                .up_since
+                .take()
+                .is_some_and(|since| now.saturating_duration_since(since) >= STABLE_UPTIME)
+            {
+                entry.reconnect_attempts = 0;
+            }
+
+            if entry.reconnect_attempts >= MAX_RECONNECT_ATTEMPTS {
+                entry.gave_up = true;
+                entry.retry_at = None;
+            } else {
+                entry.retry_at = Some(now + reconnect_delay(entry.reconnect_attempts));
+                entry.reconnect_attempts += 1;
+            }
+        }
+        entry.is_down()
+    }
+
+    /// Mark a server down straight away (e.g. it failed to start)
+    pub fn mark_down(&mut self, server: &str, error: String, now: Instant) {
+        let entry = self.entries.entry(server.to_string()).or_default();
+        entry.consecutive_failures = entry.consecutive_failures.max(UNHEALTHY_AFTER_FAILURES - 1);
+        self.record_failure(server, error, now);
+    }
+
+    pub fn snapshot(
+        &self,
+        server: &str,
+        enabled: bool,
+        connected: bool,
+        now: Instant,
+    ) -> ServerHealth {
+        let entry = self.entries.get(server);
+        let down = entry.is_some_and(Entry::is_down);
+        let status = if !enabled {
+            ServerStatus::Disabled
+        } else if entry.is_some_and(|entry| entry.gave_up) {
+            ServerStatus::Failed
+        } else if down {
+            ServerStatus::Down
+        } else {
+            ServerStatus::Up
+        };
+
+        ServerHealth {
+            server: server.to_string(),
+            status,
+            connected,
+            consecutive_failures: entry.map_or(0, |entry| entry.consecutive_failures),
+            restart_attempts: entry.map_or(0, |entry| entry.reconnect_attempts),
+            last_error: entry.and_then(|entry| entry.last_error.clone()),
+            retry_in_secs: entry
+                .filter(|_| down)
+                .and_then(|entry| entry.retry_at)
+                .map(|retry_at| retry_at.saturating_duration_since(now).as_secs()),
+        }
+    }
+}
+
+fn reconnect_delay(attempts: u32) -> Duration {
+    RECONNECT_BASE_DELAY
+        .saturating_mul(1 << attempts.min(6))
+        .min(RECONNECT_MAX_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_goes_down_after_consecutive_failures_and_recovers_on_success() {
+        let mut tracker = HealthTracker::default();
+        let now = Instant::now();
+
+        for _ in 0..UNHEALTHY_AFTER_FAILURES - 1 {
+            assert!(!tracker.record_failure("fs", "broken pipe".into(), now));
+        }
+        assert!(tracker.is_healthy("fs", now));
+        assert_eq!(tracker.begin_call("fs", now), CallGate::Healthy);
+
+        assert!(tracker.record_failure("fs", "broken pipe".into(), now));
+        assert!(!tracker.is_healthy("fs", now));
+        let health = tracker.snapshot("fs", true, false, now);
+        assert_eq!(health.status, ServerStatus::Down);
+        assert_eq!(health.last_error.as_deref(), Some("broken pipe"));
+        assert_eq!(health.retry_in_secs, Some(RECONNECT_BASE_DELAY.as_secs()));
+
+        tracker.record_success("fs", now);
+        assert!(tracker.is_healthy("fs", now));
+        assert_eq!(
+            tracker.snapshot("fs", true, true, now).consecutive_failures,
+            0
+        );
+    }
+
+    #[test]
+    fn down_server_blocks_calls_until_backoff_elapses() {
+        let mut tracker = HealthTracker::default();
+        let now = Instant::now();
+        tracker.mark_down("fs", "spawn failed".into(), now);
+
+        match tracker.begin_call("fs", now) {
+            CallGate::Blocked(message) => {
+                assert!(message.contains("spawn failed"), "{message}");
+            }
+            other => panic!("expected blocked call, got {other:?}"),
+        }
+
+        let due = now + RECONNECT_BASE_DELAY;
+        // Once the backoff elapses the server is routable again, so a call can reconnect it
+        assert!(!tracker.is_healthy("fs", now));
+        assert!(tracker.is_healthy("fs", due));
+        assert_eq!(tracker.begin_call("fs", due), CallGate::Reconnect);
+        // The reconnect attempt is claimed; concurrent callers stay blocked
+        assert!(matches!(
+            tracker.begin_call("fs", due),
+            CallGate::Blocked(_)
+        ));
+        assert!(!tracker.is_healthy("fs", due));
+    }
+
+    #[test]
+    fn failed_reconnects_back_off_exponentially() {
+        assert_eq!(reconnect_delay(0), RECONNECT_BASE_DELAY);
+        assert_eq!(reconnect_delay(1), RECONNECT_BASE_DELAY * 2);
+        assert_eq!(reconnect_delay(2), RECONNECT_BASE_DELAY * 4);
+        assert_eq!(reconnect_delay(20), RECONNECT_MAX_DELAY);
+
+        let mut tracker = HealthTracker::default();
+        let now = Instant::now();
+        tracker.mark_down("fs", "down".into(), now);
+        tracker.record_failure("fs", "still down".into(), now);
+        let health = tracker.snapshot("fs", true, false, now);
+        assert_eq!(
+            health.retry_in_secs,
+            Some(RECONNECT_BASE_DELAY.as_secs() * 2)
+        );
+    }
+
+    #[test]
+    fn disabled_servers_report_disabled() {
+        let tracker = HealthTracker::default();
+        let health = tracker.snapshot("fs", false, false, Instant::now());
+        assert_eq!(health.status, ServerStatus::Disabled);
+        assert_eq!(health.retry_in_secs, None);
+    }
+
+    #[test]
+    fn crash_loop_is_given_up_after_max_attempts() {
+        let mut tracker = HealthTracker::default();
+        let mut now = Instant::now();
+
+        // Every restart succeeds but the server dies again right away
+        for _ in 0..MAX_RECONNECT_ATTEMPTS {
+            tracker.mark_down("fs", "process exited unexpectedly".into(), now);
+            assert!(!tracker.has_given_up("fs"));
+            now += RECONNECT_MAX_DELAY;
+            assert_eq!(tracker.begin_call("fs", now), CallGate::Reconnect);
+            tracker.record_success("fs", now);
+        }
+
+        tracker.mark_down("fs", "process exited unexpectedly".into(), now);
+        assert!(tracker.has_given_up("fs"));
+        assert!(!tracker.is_healthy("fs", now));
+        let health = tracker.snapshot("fs", true, false, now);
+        assert_eq!(health.status, ServerStatus::Failed);
+        assert_eq!(health.restart_attempts, MAX_RECONNECT_ATTEMPTS);
+        assert_eq!(health.retry_in_secs, None);
+        match tracker.begin_call("fs", now + RECONNECT_MAX_DELAY) {
+            CallGate::Blocked(message) => assert!(message.contains("permanently down")),
+            other => panic!("expected blocked call, got {other:?}"),
+        }
+
+        tracker.reset("fs");
+        assert!(tracker.is_healthy("fs", now));
+    }
+
+    #[test]
+    fn stable_uptime_refills_restart_budget() {
+        let mut tracker = HealthTracker::default();
+        let mut now = Instant::now();
+
+        for _ in 0..MAX_RECONNECT_ATTEMPTS - 1 {
+            tracker.mark_down("fs", "crash".into(), now);
+            now += RECONNECT_MAX_DELAY;
+            tracker.record_success("fs", now);
+        }
+
+        now += STABLE_UPTIME;
+        tracker.mark_down("fs", "crash".into(), now);
+        assert_eq!(tracker.snapshot("fs", true, false, now).restart_attempts, 1);
+    }
+}
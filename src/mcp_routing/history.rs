@@ -0,0 +1,272 @@
+//! Routing history stored in `conversation_history.db`.
+//!
+//! Every routed request is recorded with its embedding and the selected tool;
+//! executing the tool later overwrites the outcome of that routing decision
+//! (identified by its row id). When a new request is close to a past one,
+//! tools that succeeded for it rank slightly higher and tools that failed rank
+//! slightly lower. Only the newest [`MAX_ROWS`] routes are kept.
+
+use super::models::SelectedRoute;
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Most recent rows compared against a new request
+const RECENT_WINDOW: usize = 500;
+/// Rows kept in the table; older routes are deleted when a new one is recorded
+pub const MAX_ROWS: i64 = 5_000;
+/// Cosine similarity above which a past request counts as the same kind of request
+pub const SIMILARITY_THRESHOLD: f32 = 0.85;
+/// Largest score adjustment, applied to an identical past request
+pub const MAX_BIAS: f32 = 0.05;
+
+/// A past route whose request resembles the current one
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryMatch {
+    pub server: String,
+    pub tool: String,
+    pub success: bool,
+    pub similarity: f32,
+}
+
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+}
+
+impl HistoryStore {
+    /// `<config dir>/aiw/conversation_history.db`
+    pub fn default_path() -> Result<PathBuf> {
+        let dir = dirs::config_dir()
+            .ok_or_else(|| anyhow!("Failed to get config directory"))?
+            .join("aiw");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        Ok(dir.join("conversation_history.db"))
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn =
+            Connection::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS route_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                request TEXT NOT NULL,
+                embedding BLOB NOT NULL,
+                server TEXT NOT NULL,
+                tool TEXT NOT NULL,
+                success INTEGER NOT NULL,
+                recorded_at TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_route_history_tool
+                ON route_history (server, tool);",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Record a routing decision and return its id, pruning rows beyond [`MAX_ROWS`]
+    pub fn record(
+        &self,
+        request: &str,
+        embedding: &[f32],
+        selection: &SelectedRoute,
+        success: bool,
+    ) -> Result<i64> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO route_history (request, embedding, server, tool, success, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                request,
+                encode(embedding),
+                selection.mcp_server,
+                selection.tool_name,
+                success,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "DELETE FROM route_history WHERE id <= ?1",
+            params![id - MAX_ROWS],
+        )?;
+        Ok(id)
+    }
+
+    /// Overwrite the outcome of routing decision `route_id` after its tool ran.
+    /// Returns false when the route is gone (pruned) or selected a different tool.
+    pub fn record_outcome(
+        &self,
+        route_id: i64,
+        server: &str,
+        tool: &str,
+        success: bool,
+    ) -> Result<bool> {
+        let updated = self.conn.lock().execute(
+            "UPDATE route_history SET success = ?4
+             WHERE id = ?1 AND server = ?2 AND tool = ?3",
+            params![route_id, server, tool, success],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Recent routes for requests similar to `embedding`, newest first
+    pub fn recent_for(&self, embedding: &[f32]) -> Result<Vec<HistoryMatch>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT server, tool, success, embedding FROM route_history
+             ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![RECENT_WINDOW as i64], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, Vec<u8>>(3)?,
+            ))
+        })?;
+
+        let mut matches = Vec::new();
+        for row in rows {
+            let (server, tool, success, blob) = row?;
+            let similarity = cosine(embedding, &decode(&blob));
+            if similarity >= SIMILARITY_THRESHOLD {
+                matches.push(HistoryMatch {
+                    server,
+                    tool,
+                    success,
+                    similarity,
+                });
+            }
+        }
+        Ok(matches)
+    }
+}
+
+/// Score adjustment per `server::tool`. Only the newest similar route of each
+/// tool counts: `+MAX_BIAS` scaled by similarity on success, the negative on failure.
+pub fn score_bias(matches: &[HistoryMatch]) -> HashMap<String, f32> {
+    let mut bias = HashMap::new();
+    for entry in matches {
+        let sign = if entry.success { 1.0 } else { -1.0 };
+        bias.entry(format!("{}::{}", entry.server, entry.tool))
+            .or_insert(sign * MAX_BIAS * entry.similarity);
+    }
+    bias
+}
+
+fn encode(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+/// Cosine similarity; 0.0 for mismatched or empty vectors.
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 {
+        0.0
+    } else {
+        dot / denom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn route(server: &str, tool: &str) -> SelectedRoute {
+        SelectedRoute {
+            mcp_server: server.to_string(),
+            tool_name: tool.to_string(),
+            arguments: Value::Null,
+            rationale: String::new(),
+            category: None,
+        }
+    }
+
+    #[test]
+    fn similar_requests_find_past_routes() {
+        let store = HistoryStore::in_memory().unwrap();
+        store
+            .record("read a file", &[1.0, 0.0], &route("fs", "read_file"), true)
+            .unwrap();
+        store
+            .record("git log", &[0.0, 1.0], &route("git", "log"), true)
+            .unwrap();
+
+        let matches = store.recent_for(&[0.99, 0.05]).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].tool, "read_file");
+        assert!(matches[0].success);
+    }
+
+    #[test]
+    fn outcome_updates_its_own_route_and_flips_bias() {
+        let store = HistoryStore::in_memory().unwrap();
+        let first = store
+            .record("read a file", &[1.0, 0.0], &route("fs", "read_file"), true)
+            .unwrap();
+        let second = store
+            .record("git log", &[0.0, 1.0], &route("fs", "read_file"), true)
+            .unwrap();
+        assert!(store
+            .record_outcome(first, "fs", "read_file", false)
+            .unwrap());
+        assert!(!store.record_outcome(first, "fs", "unknown", true).unwrap());
+        assert!(!store.record_outcome(-1, "fs", "read_file", true).unwrap());
+
+        let bias = score_bias(&store.recent_for(&[1.0, 0.0]).unwrap());
+        let delta = bias["fs::read_file"];
+        assert!(
+            (delta + MAX_BIAS).abs() < 1e-6,
+            "failure should lower the score"
+        );
+        // 同一工具的另一次路由不受影响
+        let bias = score_bias(&store.recent_for(&[0.0, 1.0]).unwrap());
+        assert!(bias["fs::read_file"] > 0.0);
+        assert!(second > first);
+    }
+
+    #[test]
+    fn old_routes_are_pruned_beyond_the_row_cap() {
+        let store = HistoryStore::in_memory().unwrap();
+        let mut last = 0;
+        for _ in 0..MAX_ROWS + 10 {
+            last = store
+                .record("read a file", &[1.0, 0.0], &route("fs", "read_file"), true)
+                .unwrap();
+        }
+        let (count, oldest): (i64, i64) = store
+            .conn
+            .lock()
+            .query_row("SELECT COUNT(*), MIN(id) FROM route_history", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(count, MAX_ROWS);
+        assert_eq!(oldest, last - MAX_ROWS + 1);
+    }
+}
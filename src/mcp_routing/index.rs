@@ -6,11 +6,16 @@ use std::collections::HashMap;
 const TOOLS_COLLECTION: &str = "mcp_tools";
 const METHODS_COLLECTION: &str = "mcp_methods";
 
+/// Scores closer than this are treated as a tie and ordered by `server::tool`.
+const SCORE_EPSILON: f32 = 1e-6;
+
+#[derive(Clone)]
 pub struct ToolEmbedding {
     pub record: ToolVectorRecord,
     pub vector: Vec<f32>,
 }
 
+#[derive(Clone)]
 pub struct MethodEmbedding {
     pub record: MethodVectorRecord,
     pub vector: Vec<f32>,
@@ -21,6 +26,7 @@ pub struct ScoredTool {
     pub server: String,
     pub tool: String,
     pub description: Option<String>,
+    pub category: Option<String>,
     pub score: f32,
 }
 
@@ -34,6 +40,10 @@ pub struct ScoredMethod {
 pub struct MemRoutingIndex {
     db: CacheDB,
     dimension: usize,
+    // Embedded entries kept alongside the db so servers can be removed
+    // without re-embedding everything else
+    tools: Vec<ToolEmbedding>,
+    methods: Vec<MethodEmbedding>,
 }
 
 impl MemRoutingIndex {
@@ -41,10 +51,78 @@ impl MemRoutingIndex {
         let mut db = CacheDB::new();
         db.create_collection(TOOLS_COLLECTION.to_string(), dimension, Distance::Cosine)?;
         db.create_collection(METHODS_COLLECTION.to_string(), dimension, Distance::Cosine)?;
-        Ok(Self { db, dimension })
+        Ok(Self {
+            db,
+            dimension,
+            tools: Vec::new(),
+            methods: Vec::new(),
+        })
     }
 
     pub fn rebuild(&mut self, tools: &[ToolEmbedding], methods: &[MethodEmbedding]) -> Result<()> {
+        self.tools = tools.to_vec();
+        self.methods = methods.to_vec();
+        self.reload()
+    }
+
+    /// Add embeddings for newly available tools. Entries with the same id
+    /// (a server being re-added) replace the old ones.
+    pub fn insert(&mut self, tools: &[ToolEmbedding], methods: &[MethodEmbedding]) -> Result<()> {
+        let replaces_existing = tools
+            .iter()
+            .any(|tool| self.tools.iter().any(|t| t.record.id == tool.record.id))
+            || methods
+                .iter()
+                .any(|method| self.methods.iter().any(|m| m.record.id == method.record.id));
+        if replaces_existing {
+            self.tools
+                .retain(|t| !tools.iter().any(|tool| tool.record.id == t.record.id));
+            self.methods
+                .retain(|m| !methods.iter().any(|method| method.record.id == m.record.id));
+            self.tools.extend_from_slice(tools);
+            self.methods.extend_from_slice(methods);
+            return self.reload();
+        }
+
+        for tool in tools {
+            self.db
+                .insert_into_collection(TOOLS_COLLECTION, embedding_from_tool(tool)?)?;
+        }
+        for method in methods {
+            self.db
+                .insert_into_collection(METHODS_COLLECTION, embedding_from_method(method)?)?;
+        }
+        self.tools.extend_from_slice(tools);
+        self.methods.extend_from_slice(methods);
+        Ok(())
+    }
+
+    /// Drop every entry belonging to `server`; returns the number of tools removed.
+    /// memvdb cannot delete single embeddings, so the collections are reloaded
+    /// from the cached vectors (nothing is re-embedded).
+    pub fn remove_server(&mut self, server: &str) -> Result<usize> {
+        let before = self.tools.len();
+        self.tools.retain(|tool| tool.record.server != server);
+        self.methods.retain(|method| method.record.server != server);
+        let removed = before - self.tools.len();
+        if removed > 0 {
+            self.reload()?;
+        }
+        Ok(removed)
+    }
+
+    pub fn tool_count(&self) -> usize {
+        self.tools.len()
+    }
+
+    /// Whether `server::tool` has an embedding in the index
+    pub fn contains_tool(&self, server: &str, tool: &str) -> bool {
+        self.tools
+            .iter()
+            .any(|entry| entry.record.server == server && entry.record.tool_name == tool)
+    }
+
+    fn reload(&mut self) -> Result<()> {
         self.db = CacheDB::new();
         self.db.create_collection(
             TOOLS_COLLECTION.to_string(),
@@ -57,17 +135,19 @@ impl MemRoutingIndex {
             Distance::Cosine,
         )?;
 
-        for tool in tools {
+        for tool in &self.tools {
             self.db
                 .insert_into_collection(TOOLS_COLLECTION, embedding_from_tool(tool)?)?;
         }
-        for method in methods {
+        for method in &self.methods {
             self.db
                 .insert_into_collection(METHODS_COLLECTION, embedding_from_method(method)?)?;
         }
         Ok(())
     }
 
+    /// Top `limit` tools by cosine similarity. Ties are ordered by `server::tool`,
+    /// so equal scores always rank the same way.
     pub fn search_tools(&self, vector: &[f32], limit: usize) -> Result<Vec<ScoredTool>> {
         if vector.len() != self.dimension {
             return Err(anyhow!(
@@ -80,13 +160,16 @@ impl MemRoutingIndex {
             .db
             .get_collection(TOOLS_COLLECTION)
             .ok_or_else(|| anyhow!("Tool collection not initialised"))?;
-        let results = tools.get_similarity(&adapt_query(vector), limit);
+        let results = stable_order(
+            tools.get_similarity(&adapt_query(vector), self.tools.len()),
+            limit,
+        );
         for r in &results {
             let tool_name = r.embedding.metadata.as_ref()
                 .and_then(|m| m.get("tool"))
                 .map(|s| s.as_str())
                 .unwrap_or("?");
-            eprintln!("   📊 score={:.4} tool={}", r.score, tool_name);
+            tracing::debug!("score={:.4} tool={}", r.score, tool_name);
         }
         Ok(results
             .into_iter()
@@ -106,12 +189,39 @@ impl MemRoutingIndex {
             .db
             .get_collection(METHODS_COLLECTION)
             .ok_or_else(|| anyhow!("Method collection not initialised"))?;
-        Ok(methods
-            .get_similarity(&adapt_query(vector), limit)
-            .into_iter()
-            .filter_map(scored_method_from_result)
-            .collect())
+        Ok(stable_order(
+            methods.get_similarity(&adapt_query(vector), self.methods.len()),
+            limit,
+        )
+        .into_iter()
+        .filter_map(scored_method_from_result)
+        .collect())
+    }
+}
+
+/// Sort by score (descending) with ties broken by `server::tool`, then keep `limit`.
+///
+/// Every entry is scored before truncating so equal scores at the cut-off do not
+/// depend on the vector store's internal order. Scores are bucketed by
+/// [`SCORE_EPSILON`] to keep the comparison a total order.
+fn stable_order(mut results: Vec<SimilarityResult>, limit: usize) -> Vec<SimilarityResult> {
+    fn sort_key(result: &SimilarityResult) -> (i64, String) {
+        let bucket = (result.score / SCORE_EPSILON).round() as i64;
+        let field = |name: &str| {
+            result
+                .embedding
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get(name))
+                .cloned()
+                .unwrap_or_default()
+        };
+        (-bucket, format!("{}::{}", field("server"), field("tool")))
     }
+
+    results.sort_by_cached_key(sort_key);
+    results.truncate(limit);
+    results
 }
 
 fn embedding_from_tool(entry: &ToolEmbedding) -> Result<Embedding> {
@@ -145,10 +255,12 @@ fn scored_tool_from_result(result: SimilarityResult) -> Option<ScoredTool> {
     let server = metadata.get("server")?.clone();
     let tool = metadata.get("tool")?.clone();
     let description = metadata.get("description").cloned();
+    let category = metadata.get("category").cloned();
     Some(ScoredTool {
         server,
         tool,
         description,
+        category,
         score,
     })
 }
@@ -168,3 +280,97 @@ fn scored_method_from_result(result: SimilarityResult) -> Option<ScoredMethod> {
 fn adapt_query(vector: &[f32]) -> Vec<f32> {
     vector.to_vec()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(server: &str, tool: &str, vector: Vec<f32>) -> (ToolEmbedding, MethodEmbedding) {
+        let metadata = HashMap::from([
+            ("server".to_string(), server.to_string()),
+            ("tool".to_string(), tool.to_string()),
+        ]);
+        let tool_embedding = ToolEmbedding {
+            record: ToolVectorRecord {
+                id: format!("{server}::{tool}"),
+                server: server.to_string(),
+                tool_name: tool.to_string(),
+                description: String::new(),
+                metadata: metadata.clone(),
+            },
+            vector: vector.clone(),
+        };
+        let method_embedding = MethodEmbedding {
+            record: MethodVectorRecord {
+                id: format!("method::{server}::{tool}"),
+                server: server.to_string(),
+                tool_name: tool.to_string(),
+                description: String::new(),
+                metadata,
+            },
+            vector,
+        };
+        (tool_embedding, method_embedding)
+    }
+
+    fn servers_matching(index: &MemRoutingIndex, vector: &[f32]) -> Vec<String> {
+        index
+            .search_tools(vector, 10)
+            .unwrap()
+            .into_iter()
+            .map(|scored| scored.server)
+            .collect()
+    }
+
+    #[test]
+    fn insert_and_remove_server_update_search_results() {
+        let mut index = MemRoutingIndex::new(2).unwrap();
+        let (fs_tool, fs_method) = entry("fs", "read_file", vec![1.0, 0.0]);
+        index.rebuild(&[fs_tool], &[fs_method]).unwrap();
+
+        let (git_tool, git_method) = entry("git", "log", vec![0.0, 1.0]);
+        index.insert(&[git_tool], &[git_method]).unwrap();
+        assert_eq!(index.tool_count(), 2);
+        assert!(servers_matching(&index, &[0.0, 1.0]).contains(&"git".to_string()));
+
+        assert_eq!(index.remove_server("git").unwrap(), 1);
+        assert_eq!(index.tool_count(), 1);
+        assert_eq!(servers_matching(&index, &[0.0, 1.0]), vec!["fs"]);
+        assert_eq!(index.remove_server("git").unwrap(), 0);
+    }
+
+    #[test]
+    fn equal_scores_are_ordered_by_server_and_tool() {
+        let mut index = MemRoutingIndex::new(2).unwrap();
+        let entries = [
+            entry("zeta", "read", vec![1.0, 0.0]),
+            entry("alpha", "write", vec![1.0, 0.0]),
+            entry("alpha", "read", vec![1.0, 0.0]),
+            entry("beta", "other", vec![0.0, 1.0]),
+        ];
+        let (tools, methods): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        index.rebuild(&tools, &methods).unwrap();
+
+        for _ in 0..10 {
+            let ranked: Vec<String> = index
+                .search_tools(&[1.0, 0.0], 3)
+                .unwrap()
+                .into_iter()
+                .map(|scored| format!("{}::{}", scored.server, scored.tool))
+                .collect();
+            assert_eq!(ranked, vec!["alpha::read", "alpha::write", "zeta::read"]);
+        }
+    }
+
+    #[test]
+    fn reinserting_a_tool_replaces_the_old_entry() {
+        let mut index = MemRoutingIndex::new(2).unwrap();
+        let (tool, method) = entry("fs", "read_file", vec![1.0, 0.0]);
+        index.insert(&[tool], &[method]).unwrap();
+        let (tool, method) = entry("fs", "read_file", vec![0.0, 1.0]);
+        index.insert(&[tool], &[method]).unwrap();
+
+        assert_eq!(index.tool_count(), 1);
+        assert_eq!(servers_matching(&index, &[1.0, 0.0]), vec!["fs"]);
+    }
+}
@@ -10,6 +10,8 @@ use boa_engine::{
     NativeFunction,
 };
 use boa_gc::{custom_trace, Finalize, Trace};
+use schemars::JsonSchema;
+use serde::Serialize;
 use serde_json::Value;
 use std::sync::Arc;
 use tokio::{runtime::Handle, sync::oneshot};
@@ -28,6 +30,16 @@ impl McpToolInvoker for McpConnectionPool {
     }
 }
 
+/// An `mcp.call(server, tool, ...)` site found in workflow source.
+///
+/// `server`/`tool` are `None` when the argument is not a string literal and is
+/// only known at run time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct McpCallSite {
+    pub server: Option<String>,
+    pub tool: Option<String>,
+}
+
 /// MCP function injector
 #[derive(Clone)]
 pub struct McpFunctionInjector {
@@ -113,6 +125,71 @@ impl McpFunctionInjector {
         Ok(())
     }
 
+    /// Statically list the distinct `mcp.call` sites in `js_code`, in source order.
+    ///
+    /// This is a lexical scan, not a parse: it is meant for auditing generated
+    /// workflows, whose calls the code generator writes with literal names.
+    pub fn call_sites(js_code: &str) -> Vec<McpCallSite> {
+        let mut sites: Vec<McpCallSite> = Vec::new();
+        for (start, _) in js_code.match_indices("mcp.call") {
+            let preceded_by_ident = js_code[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$' || c == '.');
+            if preceded_by_ident {
+                continue;
+            }
+            let Some(rest) = js_code[start + "mcp.call".len()..]
+                .trim_start()
+                .strip_prefix('(')
+            else {
+                continue;
+            };
+
+            let (server, rest) = Self::string_literal_arg(rest);
+            let tool = rest
+                .and_then(|rest| rest.trim_start().strip_prefix(','))
+                .and_then(|rest| Self::string_literal_arg(rest).0);
+            let site = McpCallSite { server, tool };
+            if !sites.contains(&site) {
+                sites.push(site);
+            }
+        }
+        sites
+    }
+
+    /// Read a leading quoted argument: its value (if it is a plain literal) and the text after it.
+    fn string_literal_arg(text: &str) -> (Option<String>, Option<&str>) {
+        let text = text.trim_start();
+        let Some(quote) = text
+            .chars()
+            .next()
+            .filter(|&c| matches!(c, '"' | '\'' | '`'))
+        else {
+            return (None, None);
+        };
+        let mut value = String::new();
+        let mut escaped = false;
+        for (index, c) in text[1..].char_indices() {
+            if escaped {
+                value.push(c);
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                let after = &text[1 + index + c.len_utf8()..];
+                // Template literals with interpolation are only resolved at run time
+                if quote == '`' && value.contains("${") {
+                    return (None, Some(after));
+                }
+                return (Some(value), Some(after));
+            } else {
+                value.push(c);
+            }
+        }
+        (None, None)
+    }
+
     fn parse_call_args(
         args: &[JsValue],
         context: &mut Context,
@@ -212,6 +289,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn call_sites_lists_literal_and_dynamic_calls() {
+        let code = r#"
+            async function workflow(input) {
+                const a = await mcp.call("fs", 'read_file', { path: input.path });
+                const b = await mcp.call( `git` , "log", {});
+                const again = await mcp.call("fs", "read_file", {});
+                const c = await mcp.call(input.server, "search", {});
+                const d = await mcp.call(`${input.server}`, "x", {});
+                notmcp.call("ignored", "ignored");
+                return [a, b, again, c, d];
+            }
+        "#;
+        let site = |server: Option<&str>, tool: Option<&str>| McpCallSite {
+            server: server.map(str::to_string),
+            tool: tool.map(str::to_string),
+        };
+        assert_eq!(
+            McpFunctionInjector::call_sites(code),
+            vec![
+                site(Some("fs"), Some("read_file")),
+                site(Some("git"), Some("log")),
+                site(None, None),
+                site(None, Some("x")),
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_mcp_call_injection_and_invocation() {
         let invoker = Arc::new(MockInvoker::new(json!({"ok": true})));
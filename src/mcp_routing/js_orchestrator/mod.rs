@@ -12,8 +12,8 @@ pub mod validator;
 pub mod workflow_planner;
 
 pub use engine::{BoaRuntime, BoaRuntimePool, SecurityConfig};
-pub use injector::{McpFunctionInjector, McpToolInvoker};
+pub use injector::{McpCallSite, McpFunctionInjector, McpToolInvoker};
 pub use schema_corrector::{IterativeSchemaFixer, SchemaCorrectionResult, SchemaCorrector};
-pub use schema_validator::{SchemaValidationResult, SchemaValidator};
+pub use schema_validator::{ArgumentViolation, SchemaValidationResult, SchemaValidator};
 pub use validator::{JsCodeValidator, ValidationResult};
 pub use workflow_planner::{OrchestratedTool, WorkflowOrchestrator};
@@ -217,8 +217,8 @@ impl IterativeSchemaFixer {
         let mut current_schema = initial_schema;
 
         for iteration in 0..self.max_iterations {
-            eprintln!(
-                "🔄 Schema correction iteration {}/{}",
+            tracing::debug!(
+                "Schema correction iteration {}/{}",
                 iteration + 1,
                 self.max_iterations
             );
@@ -227,20 +227,20 @@ impl IterativeSchemaFixer {
             let validation = SchemaValidator::validate(&corrected.schema);
 
             if validation.is_valid {
-                eprintln!(
-                    "✅ Schema validation passed after {} iteration(s)",
+                tracing::debug!(
+                    "Schema validation passed after {} iteration(s)",
                     iteration + 1
                 );
                 if !validation.warnings.is_empty() {
-                    eprintln!(
-                        "⚠️  Schema warnings after correction: {:?}",
+                    tracing::debug!(
+                        "Schema warnings after correction: {:?}",
                         validation.warnings
                     );
                 }
                 return Ok(corrected.schema);
             }
 
-            eprintln!("⚠️  Validation errors: {:?}", validation.errors);
+            tracing::debug!("Validation errors: {:?}", validation.errors);
 
             let llm_corrected = match self
                 .llm_correct_schema(
@@ -254,7 +254,7 @@ impl IterativeSchemaFixer {
             {
                 Ok(value) => value,
                 Err(err) => {
-                    eprintln!("⚠️  LLM schema correction failed: {}", err);
+                    tracing::warn!("LLM schema correction failed: {}", err);
                     current_schema = corrected.schema;
                     continue;
                 }
@@ -264,18 +264,18 @@ impl IterativeSchemaFixer {
             let revalidation = SchemaValidator::validate(&combined.schema);
 
             if revalidation.is_valid {
-                eprintln!("✅ Schema validation passed after LLM correction");
+                tracing::debug!("Schema validation passed after LLM correction");
                 if !revalidation.warnings.is_empty() {
-                    eprintln!(
-                        "⚠️  Schema warnings after correction: {:?}",
+                    tracing::debug!(
+                        "Schema warnings after correction: {:?}",
                         revalidation.warnings
                     );
                 }
                 return Ok(combined.schema);
             }
 
-            eprintln!(
-                "⚠️  Validation errors after LLM correction: {:?}",
+            tracing::warn!(
+                "Validation errors after LLM correction: {:?}",
                 revalidation.errors
             );
             current_schema = combined.schema;
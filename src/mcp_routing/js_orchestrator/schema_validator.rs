@@ -1,4 +1,7 @@
-use serde_json::{Map, Value};
+use serde_json::{json, Map, Value};
+
+/// Set on the permissive schema aiw substitutes for a tool that published none
+pub const INFERRED_SCHEMA_MARKER: &str = "x-aiw-inferred";
 
 /// Validation outcome for a generated JSON schema.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -18,6 +21,20 @@ impl SchemaValidationResult {
     }
 }
 
+/// One argument that does not match a tool's input schema.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ArgumentViolation {
+    /// Dotted path of the offending field (`$` is the argument object itself)
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ArgumentViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
 /// Lightweight JSON schema validator tailored for workflow input schemas.
 pub struct SchemaValidator;
 
@@ -43,6 +60,195 @@ impl SchemaValidator {
         SchemaValidationResult::from(errors, warnings)
     }
 
+    /// Whether a downstream tool's `input_schema` says nothing about its
+    /// arguments (empty, or no `type`/`properties`/composite keyword).
+    ///
+    /// `{"type": "object", "properties": {}}` is a real schema for a tool
+    /// without arguments and is not schemaless.
+    pub fn is_schemaless(schema: &Map<String, Value>) -> bool {
+        const STRUCTURAL: [&str; 6] = ["type", "properties", "$ref", "anyOf", "oneOf", "allOf"];
+        !STRUCTURAL.iter().any(|key| schema.contains_key(*key))
+    }
+
+    /// Free-form object schema used in place of a missing one; any arguments pass.
+    pub fn inferred_schema() -> Map<String, Value> {
+        let mut schema = Map::new();
+        schema.insert("type".into(), json!("object"));
+        schema.insert("additionalProperties".into(), json!(true));
+        schema.insert(
+            "description".into(),
+            json!(
+                "The server published no input schema; arguments are passed through \
+                 unchecked. Infer them from the tool description."
+            ),
+        );
+        schema.insert(INFERRED_SCHEMA_MARKER.into(), json!(true));
+        schema
+    }
+
+    /// Check tool call arguments against the tool's input schema.
+    ///
+    /// Covers the keywords MCP servers actually use: `type`, `required`,
+    /// `properties`, `additionalProperties: false`, `enum`, `items`,
+    /// `minimum`/`maximum` and `minLength`/`maxLength`. Unknown keywords and
+    /// composite schemas (`anyOf`, `$ref`, ...) are not checked.
+    pub fn validate_arguments(
+        schema: &Map<String, Value>,
+        arguments: &Value,
+    ) -> Vec<ArgumentViolation> {
+        let mut violations = Vec::new();
+        Self::check_value(schema, arguments, "$", &mut violations);
+        violations
+    }
+
+    fn check_value(
+        schema: &Map<String, Value>,
+        value: &Value,
+        path: &str,
+        out: &mut Vec<ArgumentViolation>,
+    ) {
+        let mut violation = |message: String| {
+            out.push(ArgumentViolation {
+                path: path.to_string(),
+                message,
+            })
+        };
+
+        if let Some(expected) = schema.get("type") {
+            let allowed: Vec<&str> = match expected {
+                Value::String(kind) => vec![kind.as_str()],
+                Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|kind| Self::has_type(value, kind)) {
+                violation(format!(
+                    "expected {}, got {}",
+                    allowed.join(" or "),
+                    Self::type_name(value)
+                ));
+                return;
+            }
+        }
+
+        if let Some(Value::Array(options)) = schema.get("enum") {
+            if !options.contains(value) {
+                let options: Vec<String> = options.iter().map(Value::to_string).collect();
+                violation(format!("must be one of {}", options.join(", ")));
+            }
+        }
+
+        match value {
+            Value::Number(number) => {
+                let number = number.as_f64().unwrap_or_default();
+                if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                    if number < min {
+                        violation(format!("must be >= {}", min));
+                    }
+                }
+                if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                    if number > max {
+                        violation(format!("must be <= {}", max));
+                    }
+                }
+            }
+            Value::String(text) => {
+                let len = text.chars().count() as u64;
+                if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                    if len < min {
+                        violation(format!("must be at least {} characters", min));
+                    }
+                }
+                if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                    if len > max {
+                        violation(format!("must be at most {} characters", max));
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(Value::Object(item_schema)) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        Self::check_value(item_schema, item, &format!("{}[{}]", path, index), out);
+                    }
+                }
+            }
+            Value::Object(fields) => Self::check_object(schema, fields, path, out),
+            _ => {}
+        }
+    }
+
+    fn check_object(
+        schema: &Map<String, Value>,
+        fields: &Map<String, Value>,
+        path: &str,
+        out: &mut Vec<ArgumentViolation>,
+    ) {
+        let child = |name: &str| {
+            if path == "$" {
+                name.to_string()
+            } else {
+                format!("{}.{}", path, name)
+            }
+        };
+        let properties = schema.get("properties").and_then(Value::as_object);
+
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !fields.contains_key(name) {
+                    out.push(ArgumentViolation {
+                        path: child(name),
+                        message: "required field is missing".to_string(),
+                    });
+                }
+            }
+        }
+
+        for (name, value) in fields {
+            match properties.and_then(|props| props.get(name)) {
+                Some(Value::Object(field_schema)) => {
+                    Self::check_value(field_schema, value, &child(name), out)
+                }
+                Some(_) => {}
+                None => {
+                    if schema.get("additionalProperties") == Some(&Value::Bool(false)) {
+                        out.push(ArgumentViolation {
+                            path: child(name),
+                            message: "unknown field".to_string(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fn has_type(value: &Value, kind: &str) -> bool {
+        match kind {
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "integer" => {
+                value.is_i64()
+                    || value.is_u64()
+                    || value.as_f64().is_some_and(|number| number.fract() == 0.0)
+            }
+            "boolean" => value.is_boolean(),
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "null" => value.is_null(),
+            // Unknown type keywords are not ours to reject
+            _ => true,
+        }
+    }
+
+    fn type_name(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
     fn validate_root_type(root: &Map<String, Value>, errors: &mut Vec<String>) {
         match root.get("type") {
             Some(Value::String(kind)) if kind == "object" => {}
@@ -149,7 +355,29 @@ impl SchemaValidator {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::json;
+
+    #[test]
+    fn detects_schemaless_tools_and_infers_a_permissive_schema() {
+        let object = |value: Value| value.as_object().cloned().unwrap();
+        assert!(SchemaValidator::is_schemaless(&Map::new()));
+        assert!(SchemaValidator::is_schemaless(&object(
+            json!({"description": "anything"})
+        )));
+        assert!(!SchemaValidator::is_schemaless(&object(
+            json!({"type": "object", "properties": {}})
+        )));
+        assert!(!SchemaValidator::is_schemaless(&object(
+            json!({"anyOf": []})
+        )));
+
+        let inferred = SchemaValidator::inferred_schema();
+        assert!(!SchemaValidator::is_schemaless(&inferred));
+        assert_eq!(inferred[INFERRED_SCHEMA_MARKER], json!(true));
+        assert!(
+            SchemaValidator::validate_arguments(&inferred, &json!({"any": ["thing", 1]}))
+                .is_empty()
+        );
+    }
 
     #[test]
     fn validates_minimal_object_schema() {
@@ -199,4 +427,40 @@ mod tests {
             .iter()
             .any(|w| w.contains("not present in properties")));
     }
+
+    #[test]
+    fn reports_each_offending_argument() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string", "minLength": 1 },
+                "depth": { "type": "integer", "minimum": 0 },
+                "mode": { "enum": ["fast", "full"] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["path"],
+            "additionalProperties": false
+        });
+        let schema = schema.as_object().unwrap();
+
+        let ok = json!({"path": "src", "depth": 2, "mode": "fast", "tags": ["a"]});
+        assert!(SchemaValidator::validate_arguments(schema, &ok).is_empty());
+
+        let bad = json!({"depth": -1.5, "mode": "slow", "tags": ["a", 3], "extra": true});
+        let mut violations: Vec<String> = SchemaValidator::validate_arguments(schema, &bad)
+            .into_iter()
+            .map(|violation| violation.to_string())
+            .collect();
+        violations.sort();
+        assert_eq!(
+            violations,
+            vec![
+                "depth: expected integer, got number",
+                "extra: unknown field",
+                "mode: must be one of \"fast\", \"full\"",
+                "path: required field is missing",
+                "tags[1]: expected string, got number",
+            ]
+        );
+    }
 }
@@ -122,9 +122,10 @@ impl WorkflowOrchestrator {
                 let server = parts[0].to_string();
                 let tool_name = parts[1].to_string();
 
-                eprintln!(
-                    "   ⚡ [OPTIMIZATION] Direct proxy mode: {}::{}",
-                    server, tool_name
+                tracing::debug!(
+                    "[OPTIMIZATION] Direct proxy mode: {}::{}",
+                    server,
+                    tool_name
                 );
 
                 // Build schema from plan's input_params
@@ -141,7 +142,7 @@ impl WorkflowOrchestrator {
         }
 
         // Full JS orchestration path
-        eprintln!("   🔧 [ORCHESTRATION] Generating JS workflow...");
+        tracing::debug!("[ORCHESTRATION] Generating JS workflow...");
 
         let js_code = self
             .planner
@@ -179,8 +180,8 @@ impl WorkflowOrchestrator {
             {
                 Ok(schema) => schema,
                 Err(e) => {
-                    eprintln!("⚠️  Iterative schema fixing failed: {}", e);
-                    eprintln!("ℹ️  Falling back to static SchemaCorrector");
+                    tracing::warn!("Iterative schema fixing failed: {}", e);
+                    tracing::info!("Falling back to static SchemaCorrector");
                     self.fallback_schema_correction(&js_code, built_schema)?
                 }
             },
@@ -202,29 +203,26 @@ impl WorkflowOrchestrator {
         let validation = SchemaValidator::validate(&schema);
         if validation.is_valid {
             if !validation.warnings.is_empty() {
-                eprintln!(
-                    "⚠️  Input schema warnings: {}",
-                    validation.warnings.join("; ")
-                );
+                tracing::debug!("Input schema warnings: {}", validation.warnings.join("; "));
             }
             return Ok(schema);
         }
 
-        eprintln!(
-            "⚠️  Input schema failed validation, attempting autocorrect: {}",
+        tracing::info!(
+            "Input schema failed validation, attempting autocorrect: {}",
             validation.errors.join("; ")
         );
         let corrected = SchemaCorrector::correct(js_code, schema.clone())
             .context("Failed to self-correct workflow input schema from generated code")?;
         if !corrected.applied_fixes.is_empty() {
-            eprintln!(
-                "ℹ️  Applied schema fixes: {}",
+            tracing::info!(
+                "Applied schema fixes: {}",
                 corrected.applied_fixes.join("; ")
             );
         }
         if !corrected.warnings.is_empty() {
-            eprintln!(
-                "⚠️  Schema warnings after correction: {}",
+            tracing::debug!(
+                "Schema warnings after correction: {}",
                 corrected.warnings.join("; ")
             );
         }
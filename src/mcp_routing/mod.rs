@@ -1,74 +1,131 @@
 mod capability_generator; // REQ-013: Capability description generation
+pub mod category;
 pub mod codegen;
 pub mod config;
 pub mod config_watcher;
 mod decision;
 mod embedding;
+pub mod health;
+pub mod history;
 mod index;
 pub mod js_orchestrator; // REQ-013: JS orchestration
 pub mod models;
 mod pool;
 pub mod registry; // REQ-013: Dynamic tool registry
 
-pub use embedding::{EmbeddingBackend, MockEmbeddingBackend};
+pub use embedding::{
+    export_mirror_endpoint, hashed_embedding, select_embedding_backend, EmbeddingBackend,
+    EmbeddingBackendKind, FastEmbedBackend, MockEmbeddingBackend, SelectedEmbedding,
+    EMBEDDING_DIMENSION,
+};
 pub use index::{MemRoutingIndex, MethodEmbedding, ToolEmbedding};
-pub use pool::McpConnectionPool;
+pub use pool::{probe_server, McpConnectionPool};
 
-pub use decision::{CandidateToolInfo, DecisionEngine, DecisionInput, DecisionOutcome, LlmClient};
+pub use decision::{
+    CandidateToolInfo, DecisionEngine, DecisionError, DecisionInput, DecisionOutcome, LlmClient,
+};
 
 use self::{
     config::McpConfigManager,
     index::{ScoredMethod, ScoredTool},
+    js_orchestrator::SchemaValidator,
     models::{
-        ExecuteToolRequest, ExecuteToolResponse, IntelligentRouteRequest, IntelligentRouteResponse,
-        MethodSchemaResponse, RouteExecutionResult, SelectedRoute, ToolVectorRecord,
+        DiscoveredServerTools, DiscoveredToolInfo, DiscoveredToolsReport, ExecuteToolRequest,
+        ExecuteToolResponse, IntelligentRouteRequest, IntelligentRouteResponse,
+        MethodSchemaResponse, RouteExecutionResult, RoutingMode, SelectedRoute, ToolVectorRecord,
     },
     pool::DiscoveredTool,
 };
 use anyhow::{anyhow, Result};
-use fastembed::{EmbeddingModel, InitOptions, TextEmbedding};
 use memvdb::normalize;
 use parking_lot::Mutex;
 use rmcp::model::Tool;
 use serde_json::{json, Value};
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 
 const METHOD_VECTOR_PREFIX: &str = "method";
 
+/// The caller gave up on an in-flight LLM orchestration; nothing was registered.
+#[derive(Debug, thiserror::Error)]
+#[error("orchestration cancelled")]
+pub struct OrchestrationCancelled;
+
 pub struct IntelligentRouter {
-    embedder: Arc<Mutex<TextEmbedding>>,
+    embedder: Arc<dyn EmbeddingBackend>,
     index: Mutex<MemRoutingIndex>,
     decision_engine: Arc<DecisionEngine>,
     connection_pool: Arc<McpConnectionPool>,
     tool_registry: RwLock<HashMap<String, Tool>>,
     dynamic_registry: Option<Arc<registry::DynamicToolRegistry>>, // REQ-013
     js_orchestrator: Option<Arc<js_orchestrator::WorkflowOrchestrator>>, // REQ-013
+    /// Top vector score at which dynamic mode skips LLM orchestration
+    fast_path_threshold: f32,
+    /// Upper bound for LLM orchestration before falling back to vector search
+    orchestration_timeout: Duration,
+    history: Option<Arc<history::HistoryStore>>,
+    argument_validation: config::ArgumentValidation,
+    result_limits: config::ResultLimits,
+    routing_mode: RoutingMode,
+    /// The decision LLM endpoint answered the startup probe; when false the
+    /// single-step LLM decision is skipped in favour of the top vector match
+    decision_llm_reachable: bool,
 }
 
 impl IntelligentRouter {
     pub async fn initialize() -> Result<Self> {
         let config_manager = McpConfigManager::load()?;
         let config_arc = Arc::new(config_manager.config().clone());
-
-        // Initialize embedder with all-MiniLM-L6-v2 via fastembed (ONNX Runtime)
-        let embedder = Arc::new(Mutex::new(
-            TextEmbedding::try_new(
-                InitOptions::new(EmbeddingModel::AllMiniLML6V2)
-                    .with_show_download_progress(true)
-            )
-            .map_err(|e| anyhow!("Failed to initialize fastembed: {}", e))?
-        ));
+        // mcp.json `routing` section wins over OPENAI_* / AIW_EMBEDDING_* env vars
+        let routing = config_arc.routing.resolve();
+
+        // all-MiniLM-L6-v2 via fastembed, a local model directory, or the keyword-hash fallback;
+        // the download and its retry backoff block, so keep them off the runtime threads
+        let embedder = {
+            let routing = routing.clone();
+            tokio::task::spawn_blocking(move || select_embedding_backend(&routing))
+                .await
+                .map_err(|e| anyhow!("Embedding backend selection failed: {}", e))??
+                .backend
+        };
 
         // Initialize code generator using factory pattern
-        let decision_endpoint = std::env::var("OPENAI_ENDPOINT")
-            .unwrap_or_else(|_| "http://localhost:11434".to_string());
-        let decision_model =
-            std::env::var("OPENAI_MODEL").unwrap_or_else(|_| "qwen3:1.7b".to_string());
+        let decision_endpoint = routing.llm_endpoint.value.clone();
+        let decision_model = routing.llm_model.value.clone();
+        let decision_timeout = routing.llm_timeout_secs.value;
 
-        // Discover downstream MCP tools first (needed for capability description)
+        // Discover downstream MCP tools first (needed for capability description);
+        // probe the decision LLM meanwhile so an absent one is known up front
         let connection_pool = Arc::new(McpConnectionPool::new(config_arc.clone()));
-        let discovered = connection_pool.warm_up().await?;
+        let (discovered, llm_probe) = tokio::join!(
+            connection_pool.warm_up(
+                routing.warm_up_concurrency.value,
+                Duration::from_secs(routing.discovery_timeout_secs.value)
+            ),
+            decision::probe_endpoint(&decision_endpoint, decision::LLM_PROBE_TIMEOUT)
+        );
+        let discovered = discovered?;
+        if discovered.is_empty() {
+            let enabled = enabled_server_count(&config_arc);
+            if enabled == 0 {
+                tracing::warn!(
+                    "No MCP servers configured in {}: intelligent_route has nothing to route to. \
+                     Add one with `aiw mcp add <name> <command> [args...]`",
+                    config_manager.path().display()
+                );
+            } else {
+                tracing::warn!(
+                    "None of the {} enabled MCP server(s) provided any tools: intelligent_route \
+                     has nothing to route to. Run `aiw mcp test <name>` to see why",
+                    enabled
+                );
+            }
+        }
 
         // REQ-013 Phase 1: Generate capability description
         let capability_generator = capability_generator::CapabilityGenerator::new();
@@ -76,8 +133,8 @@ impl IntelligentRouter {
         let capability_description = capability_generator
             .generate_capability_description(&discovered)?;
 
-        eprintln!(
-            "📝 Generated capability description: {}",
+        tracing::info!(
+            "Generated capability description: {}",
             capability_description
         );
 
@@ -97,6 +154,15 @@ impl IntelligentRouter {
                         "description": "Maximum number of candidate tools to consider (default: 3)",
                         "minimum": 1,
                         "maximum": 10
+                    },
+                    "server_allowlist": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Only route among these MCP servers"
+                    },
+                    "category": {
+                        "type": "string",
+                        "description": "Only route among MCP servers of this category (as set in mcp.json)"
                     }
                 },
                 "required": ["user_request"]
@@ -106,7 +172,7 @@ impl IntelligentRouter {
 
         // Create dynamic registry with max 5 dynamic tools (REQ-013: FIFO eviction)
         let registry_config = registry::RegistryConfig {
-            max_dynamic_tools: 5,
+            max_dynamic_tools: routing.max_dynamic_tools.value,
             default_ttl_seconds: 86400, // 1 day TTL (effectively permanent)
             cleanup_interval_seconds: 3600, // 1 hour cleanup
         };
@@ -117,15 +183,13 @@ impl IntelligentRouter {
         let _cleanup_task = dynamic_registry.start_cleanup_task();
 
         // Check if external LLM API is available for orchestration
-        let has_external_api = std::env::var("OPENAI_TOKEN").is_ok()
-            || std::env::var("OPENAI_ENDPOINT")
-                .ok()
-                .map(|v| v != "http://localhost:11434")
-                .unwrap_or(false);
+        let has_external_api = routing.has_external_llm();
+        let codegen_backend = codegen::CodegenBackend::for_token(routing.llm_token.value.is_some());
 
         let (decision_engine, js_orchestrator) = if has_external_api {
             // External API available: try to create js_orchestrator
-            match codegen::CodeGeneratorFactory::from_env(
+            match codegen::CodeGeneratorFactory::create(
+                codegen_backend,
                 decision_endpoint.clone(),
                 decision_model.clone(),
             ) {
@@ -133,7 +197,7 @@ impl IntelligentRouter {
                     let decision_engine = Arc::new(DecisionEngine::new(
                         &decision_endpoint,
                         &decision_model,
-                        120,
+                        decision_timeout,
                     )?);
                     let orchestrator = Some(Arc::new(
                         js_orchestrator::WorkflowOrchestrator::with_planner(generator),
@@ -141,34 +205,72 @@ impl IntelligentRouter {
                     (decision_engine, orchestrator)
                 }
                 Err(e) => {
-                    eprintln!("⚠️  Code generator initialization failed: {}", e);
-                    eprintln!("🔍 Falling back to vector-only mode");
+                    tracing::warn!("Code generator initialization failed: {}", e);
+                    tracing::info!("Falling back to vector-only mode");
                     let decision_engine = Arc::new(DecisionEngine::new(
                         &decision_endpoint,
                         &decision_model,
-                        120,
+                        decision_timeout,
                     )?);
                     (decision_engine, None)
                 }
             }
         } else {
             // No external API: skip js_orchestrator, use vector + single-step LLM decision
-            eprintln!("🔍 No external LLM API detected (set OPENAI_TOKEN or OPENAI_ENDPOINT to enable orchestration)");
+            tracing::info!("No external LLM API detected (set llm_token/llm_endpoint via `aiw routing config set` or OPENAI_TOKEN/OPENAI_ENDPOINT to enable orchestration)");
             let decision_engine = Arc::new(DecisionEngine::new(
                 &decision_endpoint,
                 &decision_model,
-                120,
+                decision_timeout,
             )?);
             (decision_engine, None)
         };
 
-        let mut index = MemRoutingIndex::new(384)?; // all-MiniLM-L6-v2 dimension
+        // The probe only speaks for the decision endpoint: the AI CLI planner runs a
+        // local CLI and keeps orchestrating even when the endpoint is down
+        let decision_llm_reachable = match &llm_probe {
+            Ok(()) => true,
+            Err(err) => {
+                tracing::warn!(
+                    "{:#}; LLM decisions fall back to the top vector match (restart the MCP server once the LLM is up)",
+                    err
+                );
+                false
+            }
+        };
+        let js_orchestrator = js_orchestrator.filter(|_| {
+            decision_llm_reachable || matches!(codegen_backend, codegen::CodegenBackend::AiCli)
+        });
+        let routing_mode = if js_orchestrator.is_some() {
+            RoutingMode::Orchestration
+        } else if decision_llm_reachable {
+            RoutingMode::VectorLlm
+        } else {
+            RoutingMode::VectorOnly
+        };
+        tracing::info!("Routing mode: {:?}", routing_mode);
+
+        let mut index = MemRoutingIndex::new(embedder.dimension())?;
         let tool_registry = RwLock::new(HashMap::new());
         let embeddings = build_embeddings(&embedder, &discovered, config_arc.as_ref())?;
         index.rebuild(&embeddings.tools, &embeddings.methods)?;
 
         populate_registry(&tool_registry, discovered).await;
 
+        let history = if routing.history_enabled.value {
+            match history::HistoryStore::default_path()
+                .and_then(|path| history::HistoryStore::open(&path))
+            {
+                Ok(store) => Some(Arc::new(store)),
+                Err(e) => {
+                    tracing::warn!("Routing history disabled: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         Ok(Self {
             embedder,
             index: Mutex::new(index),
@@ -177,12 +279,19 @@ impl IntelligentRouter {
             tool_registry,
             dynamic_registry: Some(dynamic_registry),
             js_orchestrator,
+            fast_path_threshold: routing.fast_path_threshold.value,
+            orchestration_timeout: Duration::from_secs(routing.orchestration_timeout_secs.value),
+            history,
+            argument_validation: routing.argument_validation.value,
+            result_limits: routing.result_limits(),
+            routing_mode,
+            decision_llm_reachable,
         })
     }
 
     /// Build a router from explicit dependencies (used for deterministic testing).
     pub fn new_with_components(
-        embedder: Arc<Mutex<TextEmbedding>>,
+        embedder: Arc<dyn EmbeddingBackend>,
         index: MemRoutingIndex,
         decision_engine: Arc<DecisionEngine>,
         connection_pool: Arc<McpConnectionPool>,
@@ -190,6 +299,11 @@ impl IntelligentRouter {
         dynamic_registry: Option<Arc<registry::DynamicToolRegistry>>,
         js_orchestrator: Option<Arc<js_orchestrator::WorkflowOrchestrator>>,
     ) -> Self {
+        let routing_mode = if js_orchestrator.is_some() {
+            RoutingMode::Orchestration
+        } else {
+            RoutingMode::VectorLlm
+        };
         Self {
             embedder,
             index: Mutex::new(index),
@@ -198,14 +312,57 @@ impl IntelligentRouter {
             tool_registry,
             dynamic_registry,
             js_orchestrator,
+            fast_path_threshold: config::DEFAULT_FAST_PATH_THRESHOLD,
+            orchestration_timeout: Duration::from_secs(config::DEFAULT_ORCHESTRATION_TIMEOUT_SECS),
+            history: None,
+            argument_validation: config::ArgumentValidation::default(),
+            result_limits: config::ResultLimits::default(),
+            routing_mode,
+            decision_llm_reachable: true,
         }
     }
 
+    /// Record routes in `history` and use it to bias ranking.
+    pub fn with_history(mut self, history: Arc<history::HistoryStore>) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    pub fn with_argument_validation(mut self, mode: config::ArgumentValidation) -> Self {
+        self.argument_validation = mode;
+        self
+    }
+
+    /// How proxied tool calls handle arguments that don't match the input schema
+    pub fn argument_validation(&self) -> config::ArgumentValidation {
+        self.argument_validation
+    }
+
+    pub fn with_result_limits(mut self, limits: config::ResultLimits) -> Self {
+        self.result_limits = limits;
+        self
+    }
+
+    /// Size caps applied to results of proxied tool calls
+    pub fn result_limits(&self) -> config::ResultLimits {
+        self.result_limits
+    }
+
     /// Get the dynamic tool registry (for sharing with MCP server)
     pub fn dynamic_registry(&self) -> Option<Arc<registry::DynamicToolRegistry>> {
         self.dynamic_registry.clone()
     }
 
+    /// Routing pipeline in effect (orchestration, vector + LLM, or vector only).
+    pub fn routing_mode(&self) -> RoutingMode {
+        self.routing_mode
+    }
+
+    /// Embedding backend shared with other prompt-matching features.
+    pub fn embedder(&self) -> Arc<dyn EmbeddingBackend> {
+        Arc::clone(&self.embedder)
+    }
+
     /// Get read access to the downstream tool registry.
     pub fn tool_registry(&self) -> &RwLock<HashMap<String, Tool>> {
         &self.tool_registry
@@ -214,6 +371,17 @@ impl IntelligentRouter {
     pub async fn intelligent_route(
         &self,
         request: IntelligentRouteRequest,
+    ) -> Result<IntelligentRouteResponse> {
+        self.intelligent_route_with_cancel(request, CancellationToken::new())
+            .await
+    }
+
+    /// Like [`Self::intelligent_route`], but LLM orchestration is aborted once `cancel`
+    /// fires (e.g. the MCP client cancelled the request or disconnected).
+    pub async fn intelligent_route_with_cancel(
+        &self,
+        request: IntelligentRouteRequest,
+        cancel: CancellationToken,
     ) -> Result<IntelligentRouteResponse> {
         if request.user_request.trim().is_empty() {
             return Ok(IntelligentRouteResponse {
@@ -225,66 +393,138 @@ impl IntelligentRouter {
                 alternatives: Vec::new(),
                 tool_schema: None,
                 dynamically_registered: false,
+                orchestration_timed_out: false,
+                schema_inferred: false,
+                route_id: None,
             });
         }
 
-        let embed = self.embedder
-            .lock()
-            .embed(vec![request.user_request.clone()], None)
-            .map_err(|e| anyhow!("Embedding generation failed: {}", e))?
+        if let Some(message) = self.no_tools_message().await {
+            return Ok(IntelligentRouteResponse {
+                success: false,
+                message,
+                confidence: 0.0,
+                selected_tool: None,
+                result: None,
+                alternatives: Vec::new(),
+                tool_schema: None,
+                dynamically_registered: false,
+                orchestration_timed_out: false,
+                schema_inferred: false,
+                route_id: None,
+            });
+        }
+
+        let embed = self
+            .embedder
+            .embed_batch(&[request.user_request.clone()])?
             .into_iter()
             .next()
             .ok_or_else(|| anyhow!("No embedding generated"))?;
         let embed = normalize(&embed);
 
+        let mut response = self.route(&request, &embed, &cancel).await?;
+        response.route_id = self.remember(&request, &embed, &response);
+        Ok(response)
+    }
+
+    /// Actionable message when no downstream tool exists at all, so an empty
+    /// router isn't reported as "no tools matched".
+    async fn no_tools_message(&self) -> Option<String> {
+        if !self.tool_registry.read().await.is_empty() {
+            return None;
+        }
+        let enabled = enabled_server_count(&self.connection_pool.get_config().await);
+        Some(if enabled == 0 {
+            "No MCP servers configured — run `aiw mcp add <name> <command> [args...]` \
+             (or `aiw mcp enable <name>`); mcp.json is reloaded automatically"
+                .to_string()
+        } else {
+            format!(
+                "None of the {} enabled MCP server(s) provided any tools — they failed to \
+                 start or are down. Run `aiw mcp test <name>` to see why",
+                enabled
+            )
+        })
+    }
+
+    /// Pick a tool for an embedded, non-empty request.
+    async fn route(
+        &self,
+        request: &IntelligentRouteRequest,
+        embed: &[f32],
+        cancel: &CancellationToken,
+    ) -> Result<IntelligentRouteResponse> {
         // Query mode: skip LLM orchestration, use vector search only (no tool registration)
         if matches!(request.execution_mode, models::ExecutionMode::Query) {
-            eprintln!("🔍 Query mode: using vector search (no tool registration)");
-            return self.vector_mode(&request, &embed).await;
+            tracing::info!("Query mode: using vector search (no tool registration)");
+            return self.vector_mode(request, embed).await;
         }
 
         // Dynamic mode: fast-path via vector search when top match is high-confidence,
         // otherwise try full LLM orchestration (which can take minutes).
         match self.js_orchestrator.as_ref() {
             None => {
-                eprintln!("🔍 LLM not configured, using vector search mode");
-                self.vector_mode(&request, &embed).await
+                tracing::info!("LLM not configured, using vector search mode");
+                self.vector_mode(request, embed).await
             }
             Some(orchestrator) => {
                 // Fast-path: if vector search yields a high-confidence single-tool match,
                 // skip the heavy LLM orchestration pipeline (plan + codegen + schema fix).
-                let fast_threshold = 0.75_f32;
-                let top_score = {
-                    let index = self.index.lock();
-                    index
-                        .search_tools(&embed, 1)
-                        .ok()
-                        .and_then(|scores| scores.into_iter().next())
-                        .map(|st| st.score)
-                };
+                let top_score = self
+                    .search_candidates(request, embed, 1)
+                    .ok()
+                    .and_then(|(tools, _)| tools.into_iter().next())
+                    .map(|st| st.score);
 
                 if let Some(score) = top_score {
-                    if score >= fast_threshold {
-                        eprintln!(
-                            "⚡ High-confidence vector match ({:.2}), using fast vector_mode (skipping LLM orchestration)",
+                    if score >= self.fast_path_threshold {
+                        tracing::info!(
+                            "High-confidence vector match ({:.2}), using fast vector_mode (skipping LLM orchestration)",
                             score
                         );
-                        return self.vector_mode(&request, &embed).await;
+                        return self.vector_mode(request, embed).await;
                     }
                 }
 
-                eprintln!("🤖 Trying LLM orchestration mode...");
-                match self
-                    .try_orchestrate(orchestrator.as_ref(), &request, &embed)
-                    .await
-                {
+                tracing::info!("Trying LLM orchestration mode...");
+                let orchestration = tokio::time::timeout(
+                    self.orchestration_timeout,
+                    self.try_orchestrate(orchestrator.as_ref(), request, embed, cancel),
+                );
+                let Ok(outcome) = orchestration.await else {
+                    tracing::warn!(
+                        "LLM orchestration exceeded {}s, falling back to vector mode",
+                        self.orchestration_timeout.as_secs()
+                    );
+                    let mut response = self.vector_mode(request, embed).await?;
+                    response.orchestration_timed_out = true;
+                    return Ok(response);
+                };
+                match outcome {
                     Ok(response) => {
-                        eprintln!("✅ LLM orchestration succeeded");
+                        tracing::info!("LLM orchestration succeeded");
                         Ok(response)
                     }
+                    Err(err) if err.is::<OrchestrationCancelled>() => {
+                        tracing::info!("LLM orchestration cancelled by caller");
+                        Ok(IntelligentRouteResponse {
+                            success: false,
+                            message: "Routing cancelled before orchestration completed; no tool was registered".into(),
+                            confidence: 0.0,
+                            selected_tool: None,
+                            result: None,
+                            alternatives: Vec::new(),
+                            tool_schema: None,
+                            dynamically_registered: false,
+                            orchestration_timed_out: false,
+                            schema_inferred: false,
+                            route_id: None,
+                        })
+                    }
                     Err(err) => {
-                        eprintln!("⚠️  LLM failed: {}, falling back to vector mode", err);
-                        self.vector_mode(&request, &embed).await
+                        tracing::warn!("LLM failed: {}, falling back to vector mode", err);
+                        self.vector_mode(request, embed).await
                     }
                 }
             }
@@ -301,39 +541,64 @@ impl IntelligentRouter {
             .max_candidates
             .unwrap_or(config::DEFAULT_MAX_TOOLS_PER_REQUEST);
 
-        let (tool_scores, method_scores) = {
-            let index = self.index.lock();
-            let tools = index.search_tools(embed, max_tools)?;
-            let methods = index.search_methods(embed, max_tools * 2)?;
-            (tools, methods)
-        };
+        let (tool_scores, method_scores) = self.search_candidates(request, embed, max_tools)?;
 
         if tool_scores.is_empty() {
+            let message = if request.filters_candidates() {
+                "No MCP tools matched the request on the allowed servers/category"
+            } else {
+                "No MCP tools matched the request"
+            };
             return Ok(IntelligentRouteResponse {
                 success: false,
-                message: "No MCP tools matched the request".into(),
+                message: message.into(),
                 confidence: 0.0,
                 selected_tool: None,
                 result: None,
                 alternatives: Vec::new(),
                 tool_schema: None,
                 dynamically_registered: false,
+                orchestration_timed_out: false,
+                schema_inferred: false,
+                route_id: None,
             });
         }
 
-        let candidate_infos = build_candidates(&tool_scores, &method_scores);
+        let mut candidate_infos = build_candidates(&tool_scores, &method_scores);
+        // Tools on servers that are down would only fail; route around them
+        candidate_infos.retain(|candidate| self.connection_pool.is_healthy(&candidate.server));
+        if candidate_infos.is_empty() {
+            return Ok(IntelligentRouteResponse {
+                success: false,
+                message: "All matching tools are on unhealthy MCP servers".into(),
+                confidence: 0.0,
+                selected_tool: None,
+                result: None,
+                alternatives: Vec::new(),
+                tool_schema: None,
+                dynamically_registered: false,
+                orchestration_timed_out: false,
+                schema_inferred: false,
+                route_id: None,
+            });
+        }
 
         // Try LLM decision first, fall back to pure vector top-1 if LLM unavailable
-        let (server, tool, arguments, rationale, confidence) = match self
-            .decision_engine
-            .decide(DecisionInput {
-                user_request: request.user_request.clone(),
-                candidates: candidate_infos.clone(),
-            })
-            .await
-        {
+        let decision = if !self.decision_llm_reachable {
+            Err(DecisionError::LlmUnavailable(
+                "unreachable at startup".to_string(),
+            ))
+        } else {
+            self.decision_engine
+                .decide(DecisionInput {
+                    user_request: request.user_request.clone(),
+                    candidates: candidate_infos.clone(),
+                })
+                .await
+        };
+        let (server, tool, arguments, rationale, confidence) = match decision {
             Ok(decision) => {
-                eprintln!("✅ Vector mode: LLM decision succeeded");
+                tracing::info!("Vector mode: LLM decision succeeded");
                 (
                     decision.server,
                     decision.tool,
@@ -343,13 +608,25 @@ impl IntelligentRouter {
                 )
             }
             Err(e) => {
-                eprintln!("⚠️  Vector mode: LLM unavailable ({}), using top vector match", e);
+                let rationale = match &e {
+                    DecisionError::InvalidTool { server, tool, .. } => {
+                        tracing::warn!("Vector mode: {}, using top vector match", e);
+                        format!("Best vector match (LLM chose unknown tool {}::{})", server, tool)
+                    }
+                    _ => {
+                        tracing::info!(
+                            "Vector mode: LLM unavailable ({}), using top vector match",
+                            e
+                        );
+                        "Best vector match (LLM unavailable)".to_string()
+                    }
+                };
                 let top = &candidate_infos[0];
                 (
                     top.server.clone(),
                     top.tool.clone(),
                     Value::Object(Default::default()),
-                    "Best vector match (LLM unavailable)".to_string(),
+                    rationale,
                     0.6, // reasonable default confidence for top vector match
                 )
             }
@@ -370,6 +647,27 @@ impl IntelligentRouter {
             }
         };
 
+        let category = candidate_infos
+            .iter()
+            .find(|cand| cand.server == server && cand.tool == tool)
+            .map(|cand| cand.category.clone());
+
+        let schema_inferred = self
+            .tool_registry
+            .read()
+            .await
+            .get(&registry_key(&server, &tool))
+            .is_some_and(|definition| SchemaValidator::is_schemaless(&definition.input_schema));
+        let execute_message = if schema_inferred {
+            format!(
+                "{}. The tool publishes no input schema; infer its arguments from the \
+                 description and expect the server to reject wrong ones",
+                execute_message
+            )
+        } else {
+            execute_message
+        };
+
         Ok(IntelligentRouteResponse {
             success: true,
             confidence,
@@ -379,6 +677,7 @@ impl IntelligentRouter {
                 tool_name: tool,
                 arguments,
                 rationale,
+                category,
             }),
             result: None,
             alternatives: candidate_infos
@@ -390,25 +689,124 @@ impl IntelligentRouter {
                     tool_name: cand.tool,
                     arguments: Value::Null,
                     rationale: cand.description,
+                    category: Some(cand.category),
                 })
                 .collect(),
             tool_schema: None,
             dynamically_registered: false,
+            orchestration_timed_out: false,
+            schema_inferred,
+            route_id: None,
         })
     }
 
+    /// Vector search limited to the servers and category the request allows.
+    /// With a filter the whole index is scored, so the top `limit` matches are
+    /// taken from the allowed servers only.
+    /// Routing history, when enabled, then re-ranks the matches.
+    fn search_candidates(
+        &self,
+        request: &IntelligentRouteRequest,
+        embed: &[f32],
+        limit: usize,
+    ) -> Result<(Vec<ScoredTool>, Vec<ScoredMethod>)> {
+        let (mut tools, methods) = self.search_index(request, embed, limit)?;
+        self.apply_history_bias(embed, &mut tools);
+        Ok((tools, methods))
+    }
+
+    fn search_index(
+        &self,
+        request: &IntelligentRouteRequest,
+        embed: &[f32],
+        limit: usize,
+    ) -> Result<(Vec<ScoredTool>, Vec<ScoredMethod>)> {
+        let index = self.index.lock();
+        if !request.filters_candidates() {
+            let tools = index.search_tools(embed, limit)?;
+            let methods = index.search_methods(embed, limit * 2)?;
+            return Ok((tools, methods));
+        }
+
+        let all = index.tool_count();
+        let mut tools = index.search_tools(embed, all)?;
+        tools.retain(|tool| request.admits(&tool.server, tool.category.as_deref()));
+        tools.truncate(limit);
+        let mut methods = index.search_methods(embed, all)?;
+        methods.retain(|method| {
+            tools
+                .iter()
+                .any(|tool| tool.server == method.server && tool.tool == method.tool)
+        });
+        Ok((tools, methods))
+    }
+
+    /// Nudge tools that recently succeeded (up) or failed (down) for similar requests.
+    fn apply_history_bias(&self, embed: &[f32], tools: &mut [ScoredTool]) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        let bias = match history.recent_for(embed) {
+            Ok(matches) => history::score_bias(&matches),
+            Err(e) => {
+                tracing::warn!("Failed to read routing history: {}", e);
+                return;
+            }
+        };
+        if bias.is_empty() {
+            return;
+        }
+
+        for tool in tools.iter_mut() {
+            if let Some(delta) = bias.get(&registry_key(&tool.server, &tool.tool)) {
+                tool.score += delta;
+            }
+        }
+        tools.sort_by(|a, b| {
+            b.score.total_cmp(&a.score).then_with(|| {
+                registry_key(&a.server, &a.tool).cmp(&registry_key(&b.server, &b.tool))
+            })
+        });
+    }
+
+    /// Store a successful route so later similar requests can be biased by it.
+    /// Returns the history id that the tool's outcome is later recorded against.
+    fn remember(
+        &self,
+        request: &IntelligentRouteRequest,
+        embed: &[f32],
+        response: &IntelligentRouteResponse,
+    ) -> Option<i64> {
+        let (Some(history), Some(selected)) = (&self.history, &response.selected_tool) else {
+            return None;
+        };
+        // JS workflows are not a downstream tool; there is nothing to prefer next time
+        if !response.success || selected.mcp_server == "orchestrated" {
+            return None;
+        }
+        match history.record(&request.user_request, embed, selected, true) {
+            Ok(id) => Some(id),
+            Err(e) => {
+                tracing::warn!("Failed to record routing history: {}", e);
+                None
+            }
+        }
+    }
+
     /// Attempt to orchestrate a workflow via the JS orchestrator (LLM-first path).
     async fn try_orchestrate(
         &self,
         orchestrator: &js_orchestrator::WorkflowOrchestrator,
         request: &IntelligentRouteRequest,
         embed: &[f32],
+        cancel: &CancellationToken,
     ) -> Result<IntelligentRouteResponse> {
         let _ = embed; // reserved for future vector-based orchestration hints
-        eprintln!("   🔍 [DEBUG] try_orchestrate started");
+        tracing::debug!("try_orchestrate started");
 
         // BUG FIX #1: For orchestration, pass ALL tools to LLM planner, not just top vector matches
         // The LLM needs complete tool visibility to plan optimal workflows
+        let config = self.connection_pool.get_config().await;
         let candidate_infos: Vec<CandidateToolInfo> = {
             let registry = self.tool_registry.read().await;
             registry
@@ -423,19 +821,28 @@ impl IntelligentRouter {
                         .map(|d| d.to_string())
                         .unwrap_or_default();
                     let schema = serde_json::to_string(&*tool_def.input_schema).ok();
+                    let category = category::resolve(
+                        server_category(&config, &server),
+                        &server,
+                        &tool_name,
+                        &description,
+                    );
 
                     CandidateToolInfo {
                         server,
                         tool: tool_name,
                         description,
                         schema_snippet: schema,
+                        category,
                     }
                 })
+                .filter(|candidate| request.admits(&candidate.server, Some(&candidate.category)))
+                .filter(|candidate| self.connection_pool.is_healthy(&candidate.server))
                 .collect()
         };
 
-        eprintln!(
-            "   🔍 [DEBUG] Passing {} tools to orchestrator (all available tools)",
+        tracing::debug!(
+            "Passing {} tools to orchestrator (all available tools)",
             candidate_infos.len()
         );
 
@@ -443,27 +850,41 @@ impl IntelligentRouter {
             return Err(anyhow!("No candidate tools for orchestration"));
         }
 
-        eprintln!("   🔍 [DEBUG] Calling orchestrator.orchestrate()...");
+        tracing::debug!("Calling orchestrator.orchestrate()...");
 
-        let orchestrated_tool = match orchestrator
-            .orchestrate(&request.user_request, &candidate_infos)
-            .await
-        {
+        // Dropping the orchestrate future aborts any in-flight LLM call
+        let orchestrated = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Err(OrchestrationCancelled.into()),
+            result = orchestrator.orchestrate(&request.user_request, &candidate_infos) => result,
+        };
+        let orchestrated_tool = match orchestrated {
             Ok(tool) => {
-                eprintln!("   ✅ [DEBUG] Orchestration succeeded: {}", tool.name);
+                tracing::debug!("Orchestration succeeded: {}", tool.name);
                 tool
             }
             Err(e) => {
-                eprintln!("   ❌ [DEBUG] Orchestration failed: {}", e);
+                tracing::debug!("Orchestration failed: {}", e);
                 return Err(e);
             }
         };
 
+        // Cancelled after planning finished: still don't register anything
+        if cancel.is_cancelled() {
+            return Err(OrchestrationCancelled.into());
+        }
+
         let Some(registry) = self.dynamic_registry.as_ref() else {
             return Err(anyhow!("Dynamic registry not initialized"));
         };
 
         // Decide registration type based on optimization result
+        let category = orchestrated_tool.proxy_info.as_ref().and_then(|proxy| {
+            candidate_infos
+                .iter()
+                .find(|cand| cand.server == proxy.server && cand.tool == proxy.tool_name)
+                .map(|cand| cand.category.clone())
+        });
         let (mcp_server, message) = if let Some(proxy_info) = &orchestrated_tool.proxy_info {
             // Direct proxy mode: register as proxied tool (no JS wrapper)
             let tool_key = format!("{}::{}", proxy_info.server, proxy_info.tool_name);
@@ -540,11 +961,15 @@ impl IntelligentRouter {
                 tool_name: orchestrated_tool.name.clone(),
                 arguments: Value::Object(Default::default()),
                 rationale: orchestrated_tool.description.clone(),
+                category,
             }),
             result: None,
             alternatives: Vec::new(),
             tool_schema: Some(orchestrated_tool.input_schema),
             dynamically_registered: true,
+            orchestration_timed_out: false,
+            schema_inferred: false,
+            route_id: None,
         })
     }
 
@@ -562,9 +987,16 @@ impl IntelligentRouter {
                 description: None,
                 annotations: None,
                 message: Some(format!("Unknown tool {server}::{tool}")),
+                schema_inferred: false,
             });
         };
-        let schema = Value::Object((*definition.input_schema).clone());
+        let schema_inferred = SchemaValidator::is_schemaless(&definition.input_schema);
+        let schema = if schema_inferred {
+            tracing::debug!("{server}::{tool} has no input schema; using a permissive one");
+            Value::Object(SchemaValidator::inferred_schema())
+        } else {
+            Value::Object((*definition.input_schema).clone())
+        };
         let annotations = definition
             .annotations
             .as_ref()
@@ -574,7 +1006,10 @@ impl IntelligentRouter {
             schema: Some(schema),
             description: definition.description.as_ref().map(|d| d.to_string()),
             annotations,
-            message: None,
+            message: schema_inferred.then(|| {
+                format!("{server}::{tool} publishes no input schema; the schema is inferred")
+            }),
+            schema_inferred,
         })
     }
 
@@ -592,6 +1027,18 @@ impl IntelligentRouter {
             .await;
         let duration = start.elapsed().as_millis();
 
+        // 只有经由路由选中的调用才回写结果，直接调用不影响历史偏好
+        if let (Some(history), Some(route_id)) = (&self.history, request.route_id) {
+            if let Err(e) = history.record_outcome(
+                route_id,
+                &request.mcp_server,
+                &request.tool_name,
+                execution.is_ok(),
+            ) {
+                tracing::warn!("Failed to record tool outcome: {}", e);
+            }
+        }
+
         match execution {
             Ok(output) => Ok(ExecuteToolResponse {
                 success: true,
@@ -615,6 +1062,156 @@ impl IntelligentRouter {
     pub fn connection_pool(&self) -> Arc<McpConnectionPool> {
         Arc::clone(&self.connection_pool)
     }
+
+    /// What each configured server reported in tools/list, marking which tools
+    /// made it into the routing index. `server` limits the report to one server.
+    pub async fn discovered_tools_report(
+        &self,
+        server: Option<&str>,
+    ) -> Result<DiscoveredToolsReport> {
+        let health = self.connection_pool.health().await;
+        if let Some(server) = server {
+            if !health.iter().any(|entry| entry.server == server) {
+                return Err(anyhow!("Unknown MCP server '{}'", server));
+            }
+        }
+        let mut listed = self.connection_pool.tools_by_server().await;
+
+        let servers = health
+            .into_iter()
+            .filter(|entry| server.map_or(true, |server| entry.server == server))
+            .map(|entry| {
+                let (tools, list_error) = match listed.remove(&entry.server) {
+                    Some(Ok(tools)) => (tools, None),
+                    Some(Err(err)) => (Vec::new(), Some(err)),
+                    None => (Vec::new(), None),
+                };
+                let index = self.index.lock();
+                let mut tools: Vec<DiscoveredToolInfo> = tools
+                    .into_iter()
+                    .map(|tool| DiscoveredToolInfo {
+                        indexed: index.contains_tool(&tool.server, &tool.definition.name),
+                        schemaless: SchemaValidator::is_schemaless(&tool.definition.input_schema),
+                        description: tool.definition.description.map(|d| d.to_string()),
+                        name: tool.definition.name.to_string(),
+                    })
+                    .collect();
+                tools.sort_by(|a, b| a.name.cmp(&b.name));
+                DiscoveredServerTools {
+                    server: entry.server,
+                    status: entry.status,
+                    connected: entry.connected,
+                    error: list_error.or(entry.last_error),
+                    tools,
+                }
+            })
+            .collect();
+        Ok(DiscoveredToolsReport { servers })
+    }
+
+    /// Re-run tool discovery on the live connections and rebuild the routing index.
+    pub async fn refresh_tools(&self) -> Result<()> {
+        let discovered = self.connection_pool.discovered_tools().await;
+        let config = self.connection_pool.get_config().await;
+        let embeddings = build_embeddings(&self.embedder, &discovered, config.as_ref())?;
+        self.index
+            .lock()
+            .rebuild(&embeddings.tools, &embeddings.methods)?;
+        populate_registry(&self.tool_registry, discovered).await;
+        Ok(())
+    }
+
+    /// Embed the tools of one (newly added) server and add them to the routing
+    /// index and tool registry, leaving every other server's entries as they are.
+    pub async fn add_server_tools(&self, server: &str) -> Result<usize> {
+        let discovered = self.connection_pool.server_tools(server).await?;
+        let config = self.connection_pool.get_config().await;
+        let embeddings = build_embeddings(&self.embedder, &discovered, config.as_ref())?;
+        self.index
+            .lock()
+            .insert(&embeddings.tools, &embeddings.methods)?;
+
+        let mut registry = self.tool_registry.write().await;
+        let prefix = registry_key(server, "");
+        registry.retain(|key, _| !key.starts_with(&prefix));
+        let count = discovered.len();
+        for tool in discovered {
+            registry.insert(
+                registry_key(&tool.server, &tool.definition.name),
+                tool.definition,
+            );
+        }
+        Ok(count)
+    }
+
+    /// Drop one server's tools from the routing index and tool registry.
+    pub async fn remove_server_tools(&self, server: &str) -> Result<usize> {
+        let removed = self.index.lock().remove_server(server)?;
+        let prefix = registry_key(server, "");
+        self.tool_registry
+            .write()
+            .await
+            .retain(|key, _| !key.starts_with(&prefix));
+        Ok(removed)
+    }
+
+    /// Apply a reloaded mcp.json: restart changed servers in the pool and
+    /// update the routing index only for servers that were added, removed or
+    /// relaunched.
+    pub async fn apply_config(&self, new_config: Arc<config::McpConfig>) {
+        let old_config = self.connection_pool.get_config().await;
+        let changes = old_config.server_changes(&new_config);
+        self.connection_pool.update_config(new_config).await;
+
+        for server in &changes.removed {
+            match self.remove_server_tools(server).await {
+                Ok(count) => tracing::info!(
+                    "Removed {} tools of MCP server '{}' from routing",
+                    count,
+                    server
+                ),
+                Err(e) => {
+                    tracing::warn!("Failed to remove tools of '{}' from routing: {}", server, e)
+                }
+            }
+        }
+        for server in &changes.added {
+            match self.add_server_tools(server).await {
+                Ok(count) => {
+                    tracing::info!("MCP server '{}' is routable ({} tools)", server, count)
+                }
+                Err(e) => tracing::warn!("Failed to add tools of '{}' to routing: {}", server, e),
+            }
+        }
+    }
+
+    /// Start the pool supervisor and re-index a crashed server's tools
+    /// whenever it is brought back.
+    pub fn start_supervisor(self: &Arc<Self>) {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let mut restarts = self.connection_pool.subscribe_restarts();
+        self.connection_pool.spawn_supervisor();
+
+        let router = Arc::downgrade(self);
+        tokio::spawn(async move {
+            loop {
+                let restarted = restarts.recv().await;
+                let Some(router) = router.upgrade() else {
+                    break;
+                };
+                let result = match restarted {
+                    Ok(server) => router.add_server_tools(&server).await.map(|_| ()),
+                    // Missed some restarts: re-index everything
+                    Err(RecvError::Lagged(_)) => router.refresh_tools().await,
+                    Err(RecvError::Closed) => break,
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Failed to refresh routing index after a restart: {}", e);
+                }
+            }
+        });
+    }
 }
 
 struct PreparedEmbeddings {
@@ -623,22 +1220,27 @@ struct PreparedEmbeddings {
 }
 
 fn build_embeddings(
-    embedder: &Arc<Mutex<TextEmbedding>>,
+    embedder: &Arc<dyn EmbeddingBackend>,
     tools: &[DiscoveredTool],
-    _config: &config::McpConfig,
+    config: &config::McpConfig,
 ) -> Result<PreparedEmbeddings> {
     // Collect all docs for batch embedding (much faster than one-by-one)
     let mut docs = Vec::with_capacity(tools.len());
     let mut metas: Vec<(String, String, String, HashMap<String, String>)> = Vec::with_capacity(tools.len());
 
     for tool in tools {
-        let category = "uncategorized".to_string();
         let description = tool
             .definition
             .description
             .as_deref()
             .unwrap_or("No description provided")
             .to_string();
+        let category = category::resolve(
+            server_category(config, &tool.server),
+            &tool.server,
+            &tool.definition.name,
+            &description,
+        );
         let schema_value = Value::Object((*tool.definition.input_schema).clone());
         let schema_string = schema_value.to_string();
 
@@ -660,8 +1262,7 @@ fn build_embeddings(
 
     // Batch embed all documents at once
     let vectors = embedder
-        .lock()
-        .embed(docs, None)
+        .embed_batch(&docs)
         .map_err(|e| anyhow!("Batch embedding failed: {}", e))?;
 
     let mut tool_embeddings = Vec::with_capacity(vectors.len());
@@ -728,11 +1329,31 @@ fn build_candidates(tools: &[ScoredTool], methods: &[ScoredMethod]) -> Vec<Candi
                 tool: tool.tool.clone(),
                 description: tool.description.clone().unwrap_or_default(),
                 schema_snippet: schema,
+                category: tool
+                    .category
+                    .clone()
+                    .unwrap_or_else(|| models::UNCATEGORIZED.to_string()),
             }
         })
         .collect()
 }
 
+/// `category` configured for a server in mcp.json
+fn server_category<'a>(config: &'a config::McpConfig, server: &str) -> Option<&'a str> {
+    config
+        .mcp_servers
+        .get(server)
+        .and_then(|server| server.category.as_deref())
+}
+
+fn enabled_server_count(config: &config::McpConfig) -> usize {
+    config
+        .mcp_servers
+        .values()
+        .filter(|server| server.enabled.unwrap_or(true))
+        .count()
+}
+
 fn registry_key(server: &str, tool: &str) -> String {
     format!("{server}::{tool}")
 }
@@ -1,8 +1,12 @@
+use super::health::ServerStatus;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+/// Category recorded for tools whose server has no `category` in mcp.json.
+pub const UNCATEGORIZED: &str = "uncategorized";
+
 /// Execution mode for intelligent routing (automatically chosen based on client capabilities).
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -27,6 +31,19 @@ pub enum DecisionMode {
     Vector,
 }
 
+/// Routing pipeline the server actually runs, settled at startup.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingMode {
+    /// LLM workflow orchestration, with vector search as fast path and fallback.
+    /// With the AI CLI planner this holds even when the decision endpoint is down.
+    Orchestration,
+    /// Vector search plus a single-step LLM decision among the candidates.
+    VectorLlm,
+    /// Vector search only: the decision LLM was unreachable at startup.
+    VectorOnly,
+}
+
 #[derive(Debug, Clone)]
 pub struct ToolVectorRecord {
     pub id: String,
@@ -52,6 +69,12 @@ pub struct IntelligentRouteRequest {
     pub session_id: Option<String>,
     #[serde(default)]
     pub max_candidates: Option<usize>,
+    /// Only route among these MCP servers (empty = all servers).
+    #[serde(default)]
+    pub server_allowlist: Vec<String>,
+    /// Only route among servers of this category (the `category` field in mcp.json).
+    #[serde(default)]
+    pub category: Option<String>,
     /// Decision engine to use (auto/llm/vector). Auto selects based on LLM endpoint availability.
     #[serde(default)]
     pub decision_mode: DecisionMode,
@@ -62,6 +85,30 @@ pub struct IntelligentRouteRequest {
     pub metadata: HashMap<String, String>,
 }
 
+impl IntelligentRouteRequest {
+    /// Whether the request narrows the set of candidate servers.
+    pub fn filters_candidates(&self) -> bool {
+        !self.server_allowlist.is_empty() || self.category.is_some()
+    }
+
+    /// Whether a tool on `server` (with the server's configured `category`) may be routed to.
+    /// Servers without a category count as [`UNCATEGORIZED`].
+    pub fn admits(&self, server: &str, category: Option<&str>) -> bool {
+        if !self.server_allowlist.is_empty()
+            && !self
+                .server_allowlist
+                .iter()
+                .any(|allowed| allowed == server)
+        {
+            return false;
+        }
+        match &self.category {
+            Some(wanted) => wanted.eq_ignore_ascii_case(category.unwrap_or(UNCATEGORIZED)),
+            None => true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct IntelligentRouteResponse {
     pub success: bool,
@@ -77,6 +124,18 @@ pub struct IntelligentRouteResponse {
     /// Indicates if a tool was dynamically registered
     #[serde(default)]
     pub dynamically_registered: bool,
+    /// LLM orchestration hit `orchestration_timeout_secs`; this is the vector-search
+    /// fallback, and retrying later may yield an orchestrated workflow
+    #[serde(default)]
+    pub orchestration_timed_out: bool,
+    /// The selected tool published no input schema; `tool_schema` (if any) is a
+    /// permissive stand-in, so arguments must be inferred from the description
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub schema_inferred: bool,
+    /// Routing history id of this decision; pass it back in `ExecuteToolRequest`
+    /// so the tool's outcome is credited to this route
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -85,6 +144,9 @@ pub struct SelectedRoute {
     pub tool_name: String,
     pub arguments: Value,
     pub rationale: String,
+    /// Category of the routed tool (see `mcp_routing::category`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -104,6 +166,9 @@ pub struct MethodSchemaResponse {
     pub description: Option<String>,
     pub annotations: Option<Value>,
     pub message: Option<String>,
+    /// `schema` was substituted because the tool published none
+    #[serde(default)]
+    pub schema_inferred: bool,
 }
 
 /// Request to execute a specific tool with confirmed parameters.
@@ -115,6 +180,9 @@ pub struct ExecuteToolRequest {
     pub arguments: Value,
     #[serde(default)]
     pub session_id: Option<String>,
+    /// `IntelligentRouteResponse::route_id` of the decision that chose this tool
+    #[serde(default)]
+    pub route_id: Option<i64>,
 }
 
 /// Response from executing a specific tool.
@@ -125,12 +193,44 @@ pub struct ExecuteToolResponse {
     pub result: Option<RouteExecutionResult>,
 }
 
+/// Raw tool discovery per downstream server, as returned by `list_discovered_tools`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiscoveredToolsReport {
+    pub servers: Vec<DiscoveredServerTools>,
+}
+
+/// Tools one server reported in tools/list and how the router sees them.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiscoveredServerTools {
+    pub server: String,
+    pub status: ServerStatus,
+    /// Whether a live connection exists; tools are only listed for connected servers.
+    pub connected: bool,
+    /// Last connection or tools/list error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub tools: Vec<DiscoveredToolInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiscoveredToolInfo {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the tool has an embedding in the routing index (i.e. intelligent_route can select it).
+    pub indexed: bool,
+    /// The server published no usable input schema, so arguments are not validated.
+    pub schemaless: bool,
+}
+
 impl Default for IntelligentRouteRequest {
     fn default() -> Self {
         Self {
             user_request: String::new(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: HashMap::new(),
@@ -1,19 +1,34 @@
 use crate::mcp_routing::config::{McpConfig, McpServerConfig};
+use crate::mcp_routing::health::{CallGate, HealthTracker, ServerHealth};
 use crate::utils::env;
 use anyhow::{anyhow, Context, Result};
+use futures::future::join_all;
 use parking_lot::Mutex;
 use rmcp::{
     model::{CallToolRequestParams, ClientInfo, Tool},
     service::{RoleClient, RunningService, ServiceExt},
     transport::{ConfigureCommandExt, TokioChildProcess},
+    ServiceError,
 };
 use serde_json::{to_value, Value};
 use std::{
     collections::HashMap,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::{Duration, Instant},
 };
-use tokio::{process::Command, sync::RwLock, time::timeout};
+use tokio::{
+    process::Command,
+    sync::{broadcast, RwLock, Semaphore},
+    time::timeout,
+};
+
+/// How often the supervisor checks for crashed servers and due restarts
+const SUPERVISOR_INTERVAL: Duration = Duration::from_secs(5);
+/// How long a child server gets to exit after its connection is cancelled
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub struct DiscoveredTool {
@@ -24,6 +39,9 @@ pub struct DiscoveredTool {
 pub struct McpConnectionPool {
     config: Arc<RwLock<Arc<McpConfig>>>,
     handles: RwLock<HashMap<String, Arc<McpServerHandle>>>,
+    health: Mutex<HealthTracker>,
+    restarts: broadcast::Sender<String>,
+    closed: AtomicBool,
 }
 
 struct ServerState {
@@ -42,6 +60,9 @@ impl McpConnectionPool {
         Self {
             config: Arc::new(RwLock::new(config)),
             handles: RwLock::new(HashMap::new()),
+            health: Mutex::new(HealthTracker::default()),
+            restarts: broadcast::channel(16).0,
+            closed: AtomicBool::new(false),
         }
     }
 
@@ -63,28 +84,24 @@ impl McpConnectionPool {
                 let should_remove = match new_config.mcp_servers.get(name) {
                     None => {
                         // Server removed from config
-                        eprintln!("🗑️  Shutting down removed MCP server: {}", name);
+                        tracing::info!("Shutting down removed MCP server: {}", name);
                         true
                     }
                     Some(server_config) => {
                         // Server disabled
                         if !server_config.enabled.unwrap_or(true) {
-                            eprintln!("⏸️  Shutting down disabled MCP server: {}", name);
+                            tracing::info!("Shutting down disabled MCP server: {}", name);
                             true
                         } else {
                             // Check if config changed (command, args, or env)
                             let old_server = old_config.mcp_servers.get(name);
                             let config_changed = match old_server {
                                 None => true, // New server
-                                Some(old) => {
-                                    old.command != server_config.command
-                                        || old.args != server_config.args
-                                        || old.env != server_config.env
-                                }
+                                Some(old) => !old.same_launch(server_config),
                             };
 
                             if config_changed {
-                                eprintln!("🔄 Restarting MCP server with changed config: {}", name);
+                                tracing::info!("Restarting MCP server with changed config: {}", name);
                             }
                             config_changed
                         }
@@ -100,14 +117,17 @@ impl McpConnectionPool {
         // Remove servers (dropping the handle kills the child process via kill_on_drop)
         {
             let mut handles = self.handles.write().await;
+            let mut health = self.health.lock();
             for name in to_remove {
+                // A restarted server starts with a clean health record
+                health.reset(&name);
                 if let Some(handle) = handles.remove(&name) {
                     drop(handle);
                 }
             }
         }
 
-        eprintln!("✅ MCP configuration reloaded");
+        tracing::info!("MCP configuration reloaded");
     }
 
     /// Get current configuration
@@ -115,32 +135,78 @@ impl McpConnectionPool {
         self.config.read().await.clone()
     }
 
-    pub async fn warm_up(&self) -> Result<Vec<DiscoveredTool>> {
-        let mut all = Vec::new();
+    /// Connect to every enabled server and list its tools.
+    ///
+    /// At most `concurrency` servers are discovered at once, each bounded by
+    /// `discovery_timeout`. A server that fails or times out is logged, marked
+    /// down in the health tracker and skipped; tools come back ordered by server
+    /// name regardless of which server answered first.
+    pub async fn warm_up(
+        &self,
+        concurrency: usize,
+        discovery_timeout: Duration,
+    ) -> Result<Vec<DiscoveredTool>> {
         let config = self.config.read().await.clone();
-
-        for (name, server) in config.mcp_servers.iter() {
+        let mut servers: Vec<(&String, &McpServerConfig)> = config
+            .mcp_servers
+            .iter()
             // Skip disabled servers (Claude Code compatibility)
-            if !server.enabled.unwrap_or(true) {
-                continue;
-            }
+            .filter(|(_, server)| server.enabled.unwrap_or(true))
+            .collect();
+        servers.sort_by(|a, b| a.0.cmp(b.0));
 
-            match self.ensure_handle(name.clone(), server.clone()).await {
-                Ok(handle) => match handle.list_tools().await {
-                    Ok(mut tools) => {
-                        eprintln!("✅ Connected to MCP server '{}': {} tools", name, tools.len());
-                        all.append(&mut tools);
+        let permits = Semaphore::new(concurrency.max(1));
+        let discoveries = servers.into_iter().map(|(name, server)| {
+            let permits = &permits;
+            async move {
+                let _permit = permits.acquire().await.ok()?;
+                let discovery = async {
+                    let handle = self.ensure_handle(name.clone(), server.clone()).await?;
+                    Ok::<_, anyhow::Error>(handle.list_tools().await)
+                };
+                match timeout(discovery_timeout, discovery).await {
+                    Ok(Ok(Ok(tools))) => {
+                        tracing::info!("Connected to MCP server '{}': {} tools", name, tools.len());
+                        Some(tools)
+                    }
+                    Ok(Ok(Err(e))) => {
+                        tracing::warn!("Failed to list tools from '{}': {}", name, e);
+                        None
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!("Failed to connect to MCP server '{}': {}", name, e);
+                        self.health
+                            .lock()
+                            .mark_down(name, format!("{e:#}"), Instant::now());
+                        None
                     }
-                    Err(e) => {
-                        eprintln!("⚠️  Failed to list tools from '{}': {}", name, e);
+                    Err(_) => {
+                        tracing::warn!(
+                            "MCP server '{}' did not finish discovery within {}s; continuing without it",
+                            name,
+                            discovery_timeout.as_secs()
+                        );
+                        self.health.lock().mark_down(
+                            name,
+                            format!(
+                                "discovery timed out after {}s",
+                                discovery_timeout.as_secs()
+                            ),
+                            Instant::now(),
+                        );
+                        None
                     }
-                },
-                Err(e) => {
-                    eprintln!("⚠️  Failed to connect to MCP server '{}': {}", name, e);
                 }
             }
-        }
-        Ok(all)
+        });
+
+        // join_all keeps input order, so the result is sorted by server name
+        Ok(join_all(discoveries)
+            .await
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect())
     }
 
     pub async fn ensure_handle(
@@ -152,12 +218,27 @@ impl McpConnectionPool {
             return Ok(existing);
         }
 
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(anyhow!(
+                "MCP connection pool is shut down; not starting '{}'",
+                name
+            ));
+        }
+
+        // Spawn without holding the map lock so servers can start in parallel
+        let handle = Arc::new(McpServerHandle::spawn(name.clone(), config).await?);
+
         let mut guard = self.handles.write().await;
         if let Some(existing) = guard.get(&name).cloned() {
+            // Another caller won the race; dropping ours kills the extra child
             return Ok(existing);
         }
-
-        let handle = Arc::new(McpServerHandle::spawn(name.clone(), config).await?);
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(anyhow!(
+                "MCP connection pool is shut down; not starting '{}'",
+                name
+            ));
+        }
         guard.insert(name, handle.clone());
         Ok(handle)
     }
@@ -170,15 +251,234 @@ impl McpConnectionPool {
             .ok_or_else(|| anyhow!("Unknown MCP server '{}'", server))?
             .clone();
 
-        let handle = self
+        let gate = self.health.lock().begin_call(server, Instant::now());
+        match gate {
+            CallGate::Healthy => {}
+            CallGate::Blocked(message) => return Err(anyhow!(message)),
+            CallGate::Reconnect => {
+                tracing::info!("Reconnecting to unhealthy MCP server '{}'", server);
+                self.handles.write().await.remove(server);
+            }
+        }
+
+        let result = match self
             .ensure_handle(server.to_string(), server_config)
             .await
-            .context("Failed to initialize MCP server connection")?;
+            .context("Failed to initialize MCP server connection")
+        {
+            Ok(handle) => handle.call_tool(tool_name, args).await,
+            Err(err) => Err(err),
+        };
+
+        match &result {
+            Ok(_) => self.health.lock().record_success(server, Instant::now()),
+            // The server answered with a protocol-level error, so it is alive
+            Err(err) if server_responded(err) => {
+                self.health.lock().record_success(server, Instant::now())
+            }
+            Err(err) => {
+                let error = format!("{err:#}");
+                let down = self
+                    .health
+                    .lock()
+                    .record_failure(server, error, Instant::now());
+                if down {
+                    tracing::warn!("MCP server '{}' marked unhealthy: {:#}", server, err);
+                    self.handles.write().await.remove(server);
+                }
+            }
+        }
+        result
+    }
+
+    /// Whether routing may send calls to `server` (up, or due for a reconnect)
+    pub fn is_healthy(&self, server: &str) -> bool {
+        self.health.lock().is_healthy(server, Instant::now())
+    }
+
+    /// Up/down state of every configured server, sorted by name
+    pub async fn health(&self) -> Vec<ServerHealth> {
+        let config = self.config.read().await.clone();
+        let handles = self.handles.read().await;
+        let health = self.health.lock();
+        let now = Instant::now();
+
+        let mut servers: Vec<ServerHealth> = config
+            .mcp_servers
+            .iter()
+            .map(|(name, server)| {
+                health.snapshot(
+                    name,
+                    server.enabled.unwrap_or(true),
+                    handles.contains_key(name),
+                    now,
+                )
+            })
+            .collect();
+        servers.sort_by(|a, b| a.server.cmp(&b.server));
+        servers
+    }
+
+    /// Tools of every live connection, used to rebuild the routing index
+    pub async fn discovered_tools(&self) -> Vec<DiscoveredTool> {
+        let handles: Vec<_> = self.handles.read().await.values().cloned().collect();
+        let mut all = Vec::new();
+        for handle in handles {
+            match handle.list_tools().await {
+                Ok(mut tools) => all.append(&mut tools),
+                Err(e) => tracing::warn!("Failed to list tools from '{}': {}", handle.name, e),
+            }
+        }
+        all
+    }
+
+    /// Tools of every live connection grouped by server, keeping the listing
+    /// error of servers that did not answer
+    pub async fn tools_by_server(&self) -> HashMap<String, Result<Vec<DiscoveredTool>, String>> {
+        let handles: Vec<_> = self.handles.read().await.values().cloned().collect();
+        let mut servers = HashMap::new();
+        for handle in handles {
+            let tools = handle.list_tools().await.map_err(|e| format!("{e:#}"));
+            servers.insert(handle.name.clone(), tools);
+        }
+        servers
+    }
 
-        handle.call_tool(tool_name, args).await
+    /// Connect to `server` if needed and list its tools. A server that fails
+    /// to start is marked down so the supervisor retries it.
+    pub async fn server_tools(&self, server: &str) -> Result<Vec<DiscoveredTool>> {
+        let config = self.config.read().await.clone();
+        let server_config = config
+            .mcp_servers
+            .get(server)
+            .ok_or_else(|| anyhow!("Unknown MCP server '{}'", server))?
+            .clone();
+
+        let handle = match self.ensure_handle(server.to_string(), server_config).await {
+            Ok(handle) => handle,
+            Err(err) => {
+                let error = format!("{err:#}");
+                self.health.lock().mark_down(server, error, Instant::now());
+                return Err(err);
+            }
+        };
+        handle.list_tools().await
+    }
+
+    /// Names of servers the supervisor brought back after a crash
+    pub fn subscribe_restarts(&self) -> broadcast::Receiver<String> {
+        self.restarts.subscribe()
+    }
+
+    /// Watch for crashed servers and respawn them with backoff. Stops once
+    /// the pool is dropped.
+    pub fn spawn_supervisor(self: &Arc<Self>) {
+        let pool = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(SUPERVISOR_INTERVAL);
+            loop {
+                ticker.tick().await;
+                let Some(pool) = pool.upgrade() else {
+                    break;
+                };
+                pool.supervise_once().await;
+            }
+        });
+    }
+
+    /// Stop every child server and refuse to start new ones, so no downstream
+    /// server outlives the warden process. Returns how many servers were stopped.
+    pub async fn shutdown(&self) -> usize {
+        self.closed.store(true, Ordering::SeqCst);
+        let handles: Vec<_> = self.handles.write().await.drain().collect();
+        let stopped = handles.len();
+
+        for (name, handle) in handles {
+            // A call still in flight holds the other reference; its child is
+            // killed via kill_on_drop once that call returns.
+            let Ok(handle) = Arc::try_unwrap(handle) else {
+                continue;
+            };
+            let running = handle.state.into_inner().running;
+            if timeout(SHUTDOWN_TIMEOUT, running.cancel()).await.is_err() {
+                tracing::warn!(
+                    "MCP server '{}' did not stop within {}s; killing it",
+                    name,
+                    SHUTDOWN_TIMEOUT.as_secs()
+                );
+            }
+        }
+        stopped
+    }
+
+    async fn supervise_once(&self) {
+        if self.closed.load(Ordering::SeqCst) {
+            return;
+        }
+
+        // Reap connections whose child process went away
+        let crashed: Vec<String> = {
+            let mut handles = self.handles.write().await;
+            let crashed: Vec<String> = handles
+                .iter()
+                .filter(|(_, handle)| !handle.is_alive())
+                .map(|(name, _)| name.clone())
+                .collect();
+            for name in &crashed {
+                handles.remove(name);
+            }
+            crashed
+        };
+        for name in &crashed {
+            tracing::warn!("MCP server '{}' exited unexpectedly", name);
+            self.health.lock().mark_down(
+                name,
+                "process exited unexpectedly".to_string(),
+                Instant::now(),
+            );
+        }
+
+        // Respawn down servers whose backoff has elapsed
+        let config = self.config.read().await.clone();
+        for (name, server) in config.mcp_servers.iter() {
+            if !server.enabled.unwrap_or(true) || self.handles.read().await.contains_key(name) {
+                continue;
+            }
+            let gate = self.health.lock().begin_call(name, Instant::now());
+            if gate != CallGate::Reconnect {
+                continue;
+            }
+
+            tracing::info!("Restarting MCP server '{}'", name);
+            match self.ensure_handle(name.clone(), server.clone()).await {
+                Ok(_) => {
+                    self.health.lock().record_success(name, Instant::now());
+                    tracing::info!("MCP server '{}' restarted", name);
+                    let _ = self.restarts.send(name.clone());
+                }
+                Err(err) => {
+                    let error = format!("{err:#}");
+                    let mut health = self.health.lock();
+                    health.record_failure(name, error, Instant::now());
+                    if health.has_given_up(name) {
+                        tracing::error!(
+                            "MCP server '{}' is permanently down after repeated restart failures: {:#}",
+                            name, err
+                        );
+                    }
+                }
+            }
+        }
     }
 }
 
+fn server_responded(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<ServiceError>(),
+        Some(ServiceError::McpError(_))
+    )
+}
+
 impl McpServerHandle {
     async fn spawn(name: String, config: McpServerConfig) -> Result<Self> {
         let running = spawn_client(&config).await?;
@@ -196,6 +496,11 @@ impl McpServerHandle {
         })
     }
 
+    /// False once the transport to the child process has closed (it exited or crashed)
+    pub fn is_alive(&self) -> bool {
+        !self.state.lock().running.peer().is_transport_closed()
+    }
+
     pub async fn list_tools(&self) -> Result<Vec<DiscoveredTool>> {
         let needs_refresh = {
             let state = self.state.lock();
@@ -266,6 +571,21 @@ impl McpServerHandle {
     }
 }
 
+/// Start a server on its own, list its tools and shut it down again.
+///
+/// Uses the same spawn path as the pool so `aiw mcp test` sees exactly what the
+/// router would.
+pub async fn probe_server(name: &str, config: &McpServerConfig) -> Result<Vec<Tool>> {
+    let running = spawn_client(config).await?;
+    let tools = timeout(Duration::from_secs(15), running.peer().list_all_tools()).await;
+    // Shut down regardless of how discovery went
+    let _ = running.cancel().await;
+
+    tools
+        .map_err(|_| anyhow!("MCP server '{}' tool discovery timed out (15s)", name))?
+        .map_err(|err| anyhow!(err))
+}
+
 /// Expand environment variable placeholder (${VAR_NAME})
 /// Windows: case-insensitive, Linux/macOS: case-sensitive
 fn expand_env_var(value: &str) -> String {
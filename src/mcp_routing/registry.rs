@@ -5,11 +5,15 @@
 
 use anyhow::{anyhow, Result};
 use rmcp::model::Tool;
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+use super::js_orchestrator::{McpCallSite, McpFunctionInjector};
+
 /// Registry configuration (defaults follow SPEC/02-ARCHITECTURE.md §1157-1201)
 #[derive(Debug, Clone)]
 pub struct RegistryConfig {
@@ -79,6 +83,8 @@ pub struct ProxiedMcpTool {
     pub server: String,
     pub original_name: String,
     pub metadata: ToolMetadata,
+    /// Routing history id of the decision that registered this tool, if any
+    pub route_id: Option<i64>,
 }
 
 /// Registered tool entry within the registry map
@@ -128,7 +134,55 @@ impl RegisteredTool {
     }
 }
 
+/// What a dynamic tool actually runs, for auditing before relying on it
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ToolSource {
+    pub name: String,
+    /// "js_orchestrated" or "proxied_mcp"
+    pub kind: String,
+    pub input_schema: serde_json::Value,
+    /// Generated workflow code (JS tools only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub js_code: Option<String>,
+    /// Downstream MCP functions the workflow calls (JS tools only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mcp_calls: Vec<McpCallSite>,
+    /// Downstream server the call is forwarded to (proxied tools only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_server: Option<String>,
+    /// Tool name on the downstream server (proxied tools only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_tool: Option<String>,
+}
+
 impl RegisteredTool {
+    /// Describe the code or proxy target behind this tool
+    pub fn source(&self) -> ToolSource {
+        let tool = self.tool();
+        let mut source = ToolSource {
+            name: tool.name.to_string(),
+            kind: String::new(),
+            input_schema: serde_json::Value::Object((*tool.input_schema).clone()),
+            js_code: None,
+            mcp_calls: Vec::new(),
+            proxy_server: None,
+            proxy_tool: None,
+        };
+        match self {
+            RegisteredTool::JsOrchestrated(js) => {
+                source.kind = "js_orchestrated".to_string();
+                source.mcp_calls = McpFunctionInjector::call_sites(&js.js_code);
+                source.js_code = Some(js.js_code.clone());
+            }
+            RegisteredTool::ProxiedMcp(proxy) => {
+                source.kind = "proxied_mcp".to_string();
+                source.proxy_server = Some(proxy.server.clone());
+                source.proxy_tool = Some(proxy.original_name.clone());
+            }
+        }
+        source
+    }
+
     fn new_js(tool: Tool, js_code: String, ttl: u64) -> Self {
         RegisteredTool::JsOrchestrated(JsOrchestratedTool {
             tool,
@@ -137,12 +191,19 @@ impl RegisteredTool {
         })
     }
 
-    fn new_proxied(tool: Tool, server: String, original_name: String, ttl: u64) -> Self {
+    fn new_proxied(
+        tool: Tool,
+        server: String,
+        original_name: String,
+        route_id: Option<i64>,
+        ttl: u64,
+    ) -> Self {
         RegisteredTool::ProxiedMcp(ProxiedMcpTool {
             tool,
             server,
             original_name,
             metadata: ToolMetadata::new(ttl),
+            route_id,
         })
     }
 
@@ -236,7 +297,7 @@ impl DynamicToolRegistry {
                 ticker.tick().await;
                 let removed = registry.cleanup_expired_tools().await;
                 if removed > 0 {
-                    eprintln!("🧹 Cleaned up {removed} expired dynamic tools");
+                    tracing::info!("Cleaned up {removed} expired dynamic tools");
                 }
             }
         })
@@ -280,6 +341,19 @@ impl DynamicToolRegistry {
         server: String,
         original_name: String,
         tool: Tool,
+    ) -> Result<bool> {
+        self.register_routed_tool(server, original_name, tool, None)
+            .await
+    }
+
+    /// Register a proxied MCP tool chosen by routing decision `route_id`, so
+    /// calls through it report their outcome to that route's history entry
+    pub async fn register_routed_tool(
+        &self,
+        server: String,
+        original_name: String,
+        tool: Tool,
+        route_id: Option<i64>,
     ) -> Result<bool> {
         if server.trim().is_empty() {
             return Err(anyhow!("Server name cannot be empty"));
@@ -295,6 +369,7 @@ impl DynamicToolRegistry {
                 tool,
                 server,
                 original_name,
+                route_id,
                 self.config.default_ttl_seconds,
             ),
         );
@@ -338,10 +413,15 @@ impl DynamicToolRegistry {
         drop(base_tools);
         drop(base_snapshot);
 
+        // Order dynamic tools by registration time so paginated list_tools stays stable
         let map = self.dynamic_tools.read().await;
-        for entry in map.values() {
-            snapshot.push(entry.tool().clone());
-        }
+        let mut dynamic: Vec<&RegisteredTool> = map.values().collect();
+        dynamic.sort_by(|a, b| {
+            a.registered_at()
+                .cmp(&b.registered_at())
+                .then_with(|| a.tool().name.cmp(&b.tool().name))
+        });
+        snapshot.extend(dynamic.into_iter().map(|entry| entry.tool().clone()));
         drop(map);
 
         let arc_snapshot = Arc::new(snapshot);
@@ -355,6 +435,11 @@ impl DynamicToolRegistry {
         map.get(name).cloned()
     }
 
+    /// Whether `name` belongs to a base (non-expiring) tool
+    pub async fn is_base_tool(&self, name: &str) -> bool {
+        self.base_tools.read().await.contains_key(name)
+    }
+
     /// Whether a tool exists (base or dynamic)
     pub async fn has_tool(&self, name: &str) -> bool {
         if self.base_tools.read().await.contains_key(name) {
@@ -411,7 +496,7 @@ impl DynamicToolRegistry {
 
         if let Some(oldest) = Self::find_oldest_tool(tools) {
             tools.remove(&oldest);
-            eprintln!("⚠️  Tool limit reached, evicted oldest tool: {oldest}");
+            tracing::warn!("Tool limit reached, evicted oldest tool: {oldest}");
         }
     }
 
@@ -463,6 +548,48 @@ mod tests {
         assert!(registry.has_tool("read_file").await);
     }
 
+    #[tokio::test]
+    async fn test_tool_source() {
+        let registry = DynamicToolRegistry::new(vec![]);
+        registry
+            .register_js_tool(
+                "workflow".to_string(),
+                "Test workflow".to_string(),
+                serde_json::json!({"type": "object"}),
+                "async function workflow() { return mcp.call('git', 'status', {}); }".to_string(),
+            )
+            .await
+            .unwrap();
+        registry
+            .register_proxied_tool(
+                "filesystem".to_string(),
+                "read_file".to_string(),
+                create_test_tool("read_file"),
+            )
+            .await
+            .unwrap();
+
+        let js = registry.get_tool("workflow").await.unwrap().source();
+        assert_eq!(js.kind, "js_orchestrated");
+        assert_eq!(js.input_schema["type"], "object");
+        assert!(js.js_code.unwrap().contains("mcp.call"));
+        assert_eq!(
+            js.mcp_calls,
+            vec![McpCallSite {
+                server: Some("git".to_string()),
+                tool: Some("status".to_string()),
+            }]
+        );
+        assert!(js.proxy_server.is_none());
+
+        let proxied = registry.get_tool("read_file").await.unwrap().source();
+        assert_eq!(proxied.kind, "proxied_mcp");
+        assert_eq!(proxied.proxy_server.as_deref(), Some("filesystem"));
+        assert_eq!(proxied.proxy_tool.as_deref(), Some("read_file"));
+        assert!(proxied.js_code.is_none());
+        assert!(proxied.mcp_calls.is_empty());
+    }
+
     #[tokio::test]
     async fn test_tool_expiration_cleanup() {
         let registry = DynamicToolRegistry::with_config(
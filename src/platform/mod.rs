@@ -4,6 +4,31 @@ pub fn current_pid() -> u32 {
     std::process::id()
 }
 
+/// `process_alive` that also rejects a recycled PID: when the start time
+/// recorded at launch is known and differs from the process now holding
+/// `pid`, the original process is gone.
+pub fn process_alive_since(pid: u32, start_time: Option<u64>) -> bool {
+    if !process_alive(pid) {
+        return false;
+    }
+    match (start_time, process_start_time(pid)) {
+        (Some(expected), Some(actual)) => expected == actual,
+        _ => true,
+    }
+}
+
+/// 任务优先级使用 Unix nice 值刻度，数值越大越谦让 CPU
+pub const MIN_PRIORITY: i32 = -20;
+pub const MAX_PRIORITY: i32 = 19;
+
+/// Clamp a requested nice value to what this process is allowed to apply.
+///
+/// On Unix an unprivileged user can only lower its priority, so the floor is
+/// the supervisor's own nice value; Windows accepts the whole range.
+pub fn clamp_priority(nice: i32) -> i32 {
+    nice.clamp(min_allowed_priority(), MAX_PRIORITY)
+}
+
 #[cfg(unix)]
 mod unix;
 #[cfg(unix)]
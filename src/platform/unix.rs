@@ -54,9 +54,42 @@ pub fn process_alive(pid: u32) -> bool {
     }
 }
 
+/// Start time of the process currently holding `pid`
+///
+/// Linux: `starttime` (clock ticks since boot) from `/proc/<pid>/stat`;
+/// macOS: creation time in milliseconds. Only compared against values taken
+/// on the same machine, so the unit does not matter.
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        parse_stat_start_time(&stat)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let process = psutil::process::Process::new(pid).ok()?;
+        Some(process.create_time().as_millis() as u64)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Field 22 of `/proc/<pid>/stat`. The command name (field 2) may contain
+/// spaces and parentheses, so fields are counted after the last `)`.
+#[cfg(any(target_os = "linux", target_os = "android", test))]
+fn parse_stat_start_time(stat: &str) -> Option<u64> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    rest.split_whitespace().nth(19)?.parse().ok()
+}
+
 /// Terminate process
 ///
-/// First try graceful termination (SIGTERM), force termination (SIGKILL) if it fails
+/// First try graceful termination (SIGTERM), force termination (SIGKILL) if it fails.
+/// When `pid` leads its own process group (supervised children do), the whole group
+/// is signalled so helpers spawned by the CLI do not outlive it.
 pub fn terminate_process(pid: u32) {
     #[cfg(unix)]
     {
@@ -67,18 +100,28 @@ pub fn terminate_process(pid: u32) {
             return;
         }
 
+        let is_group_leader =
+            unsafe { libc::getpgid(c_pid) } == c_pid && unsafe { libc::getpgrp() } != c_pid;
+        let send = |signal| {
+            if is_group_leader {
+                send_group_signal(c_pid, signal)
+            } else {
+                unsafe_send_signal(c_pid, signal)
+            }
+        };
+
         // Graceful termination
-        if unsafe_send_signal(c_pid, libc::SIGTERM).is_ok() {
+        if send(libc::SIGTERM).is_ok() {
             thread::sleep(Duration::from_millis(500));
 
             // Check if already terminated
-            if !process_alive(pid) {
+            if send(0).is_err() {
                 return;
             }
         }
 
         // Force termination
-        if unsafe_send_signal(c_pid, libc::SIGKILL).is_ok() {
+        if send(libc::SIGKILL).is_ok() {
             debug(format!("pid={} sent SIGKILL", pid));
         }
     }
@@ -90,6 +133,44 @@ pub fn terminate_process(pid: u32) {
     }
 }
 
+/// Terminate the process group led by `pgid`
+///
+/// Supervised children call `setpgid(0, 0)`, so their group id equals their pid and
+/// the group also holds any helpers they spawned. Sends SIGTERM to the whole group,
+/// waits up to 500 ms without blocking the async runtime, then SIGKILLs what is left.
+pub async fn terminate_process_group(pgid: u32) {
+    let c_pgid = pgid as libc::pid_t;
+    if c_pgid <= 1 || send_group_signal(c_pgid, 0).is_err() {
+        return;
+    }
+
+    if send_group_signal(c_pgid, libc::SIGTERM).is_ok() {
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(500);
+        while tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if send_group_signal(c_pgid, 0).is_err() {
+                return;
+            }
+        }
+    }
+
+    if send_group_signal(c_pgid, libc::SIGKILL).is_ok() {
+        debug(format!("pgid={} sent SIGKILL", pgid));
+    }
+}
+
+/// Lowest nice value this process may give a child
+///
+/// Root can use the full range; everyone else cannot go below the current nice value.
+pub fn min_allowed_priority() -> i32 {
+    if unsafe { libc::geteuid() } == 0 {
+        return super::MIN_PRIORITY;
+    }
+    // getpriority 可以合法返回 -1，这里不区分错误，结果只用作下限
+    let current = unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) };
+    current.clamp(super::MIN_PRIORITY, super::MAX_PRIORITY)
+}
+
 /// Safely set process group ID
 ///
 /// Encapsulates unsafe setpgid call
@@ -119,6 +200,17 @@ fn unsafe_send_signal(pid: libc::pid_t, signal: libc::c_int) -> Result<(), libc:
     }
 }
 
+/// Send a signal to every process in a group
+#[cfg(unix)]
+fn send_group_signal(pgid: libc::pid_t, signal: libc::c_int) -> Result<(), libc::c_int> {
+    let result = unsafe { libc::killpg(pgid, signal) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(get_last_errno())
+    }
+}
+
 /// Get last error code
 ///
 /// Encapsulates unsafe errno access
@@ -146,3 +238,49 @@ fn get_last_errno() -> libc::c_int {
         0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stat_start_time_skips_the_command_name() {
+        let stat = "4242 (my (odd) cmd) S 1 4242 4242 0 -1 4194560 100 0 0 0 \
+                    5 3 0 0 20 0 1 0 987654 1000 200";
+        assert_eq!(parse_stat_start_time(stat), Some(987654));
+        assert_eq!(parse_stat_start_time("garbage"), None);
+    }
+
+    #[test]
+    fn priority_is_clamped_to_the_allowed_range() {
+        use crate::platform::{clamp_priority, MAX_PRIORITY, MIN_PRIORITY};
+
+        let floor = min_allowed_priority();
+        assert!(floor >= MIN_PRIORITY);
+        assert_eq!(clamp_priority(100), MAX_PRIORITY);
+        assert_eq!(clamp_priority(-100), floor);
+        assert_eq!(clamp_priority(MAX_PRIORITY), MAX_PRIORITY);
+    }
+
+    #[tokio::test]
+    async fn terminating_a_group_also_stops_grandchildren() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "sleep 30 & wait"]);
+        prepare_command(&mut command).unwrap();
+        let mut child = command.spawn().unwrap();
+        let pgid = child.id() as libc::pid_t;
+        std::thread::sleep(Duration::from_millis(100));
+
+        terminate_process_group(child.id()).await;
+        let _ = child.wait();
+        assert!(send_group_signal(pgid, 0).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn own_start_time_is_stable() {
+        let pid = std::process::id();
+        let first = process_start_time(pid).expect("own start time");
+        assert_eq!(process_start_time(pid), Some(first));
+    }
+}
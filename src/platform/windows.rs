@@ -2,7 +2,9 @@ use crate::logging::debug;
 use std::io;
 use std::os::windows::io::AsRawHandle;
 use windows::core::PCWSTR;
-use windows::Win32::Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE, STILL_ACTIVE};
+use windows::Win32::Foundation::{
+    CloseHandle, FILETIME, HANDLE, INVALID_HANDLE_VALUE, STILL_ACTIVE,
+};
 use windows::Win32::System::Console::{
     GetConsoleMode, GetStdHandle, SetConsoleMode, CONSOLE_MODE, ENABLE_VIRTUAL_TERMINAL_PROCESSING,
     STD_ERROR_HANDLE, STD_OUTPUT_HANDLE,
@@ -13,8 +15,10 @@ use windows::Win32::System::JobObjects::{
     JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
 };
 use windows::Win32::System::Threading::{
-    GetExitCodeProcess, OpenProcess, TerminateProcess, WaitForSingleObject,
-    PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+    GetExitCodeProcess, GetProcessTimes, OpenProcess, TerminateProcess, WaitForSingleObject,
+    ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS,
+    IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS, PROCESS_QUERY_LIMITED_INFORMATION,
+    PROCESS_TERMINATE,
 };
 
 pub fn prepare_command(_cmd: &mut std::process::Command) -> io::Result<()> {
@@ -44,6 +48,25 @@ pub fn enable_virtual_terminal_processing() -> io::Result<()> {
     Ok(())
 }
 
+/// Windows has no per-user floor for priority classes below realtime
+pub fn min_allowed_priority() -> i32 {
+    super::MIN_PRIORITY
+}
+
+/// Map a nice value onto the closest process priority class (creation flag)
+///
+/// Realtime is never used; the most favourable value maps to HIGH.
+pub fn priority_class(nice: i32) -> u32 {
+    let class = match nice {
+        15.. => IDLE_PRIORITY_CLASS,
+        5..=14 => BELOW_NORMAL_PRIORITY_CLASS,
+        -4..=4 => NORMAL_PRIORITY_CLASS,
+        -14..=-5 => ABOVE_NORMAL_PRIORITY_CLASS,
+        _ => HIGH_PRIORITY_CLASS,
+    };
+    class.0
+}
+
 pub fn process_alive(pid: u32) -> bool {
     unsafe {
         let handle = match OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) {
@@ -57,6 +80,20 @@ pub fn process_alive(pid: u32) -> bool {
     }
 }
 
+/// Process creation time (FILETIME, 100ns ticks since 1601)
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut created = FILETIME::default();
+        let mut exited = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        let ok = GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user).is_ok();
+        let _ = CloseHandle(handle);
+        ok.then(|| (u64::from(created.dwHighDateTime) << 32) | u64::from(created.dwLowDateTime))
+    }
+}
+
 pub fn terminate_process(pid: u32) {
     unsafe {
         let handle = match OpenProcess(
@@ -75,6 +112,11 @@ pub fn terminate_process(pid: u32) {
     }
 }
 
+/// Windows 没有进程组信号；子进程树由 Job 对象在句柄关闭时统一结束，这里只终止主进程
+pub async fn terminate_process_group(pid: u32) {
+    let _ = tokio::task::spawn_blocking(move || terminate_process(pid)).await;
+}
+
 pub fn after_spawn(child: &std::process::Child) -> io::Result<Option<JobHandle>> {
     unsafe {
         let job = match CreateJobObjectW(None, PCWSTR::null()) {
@@ -1,12 +1,50 @@
 //! Provider configuration data structures
 
+use crate::utils::config_migration::{self, MigrationError, MigrationStep};
 use anyhow::{anyhow, Result};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
+use std::path::Path;
 
 const DEFAULT_SCHEMA_URL: &str = "https://agentic-warden.dev/schema/provider.json";
 
+/// providers.json 的迁移步骤，`PROVIDERS_MIGRATIONS[i]` 把版本 i+1 升级到 i+2
+pub const PROVIDERS_MIGRATIONS: &[MigrationStep] = &[compatible_with_to_list];
+
+/// Schema version written by this binary
+pub const PROVIDERS_SCHEMA_VERSION: u64 = PROVIDERS_MIGRATIONS.len() as u64 + 1;
+
+/// v1 → v2：`compatible_with` 允许写成 `"claude,codex"` 字符串，统一改为数组
+fn compatible_with_to_list(root: &mut Map<String, Value>) -> Result<(), String> {
+    let Some(Value::Object(providers)) = root.get_mut("providers") else {
+        return Ok(());
+    };
+    for (name, provider) in providers.iter_mut() {
+        let Some(field) = provider.get_mut("compatible_with") else {
+            continue;
+        };
+        if let Value::String(raw) = field {
+            let types: Vec<Value> = raw
+                .split(',')
+                .map(|item| item.trim().to_lowercase())
+                .filter(|item| !item.is_empty())
+                .map(Value::String)
+                .collect();
+            if types.is_empty() {
+                return Err(format!("provider '{}' has an empty compatible_with", name));
+            }
+            *field = Value::Array(types);
+        }
+    }
+    Ok(())
+}
+
+fn default_schema_version() -> u64 {
+    PROVIDERS_SCHEMA_VERSION
+}
+
 /// Provider configuration file root structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProvidersConfig {
@@ -14,11 +52,27 @@ pub struct ProvidersConfig {
     #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
     pub schema: Option<String>,
 
+    /// Schema version; older files are upgraded on load
+    /// 文件格式版本，旧版本在加载时自动迁移
+    #[serde(default = "default_schema_version")]
+    pub version: u64,
+
     /// All provider configurations
     pub providers: HashMap<String, Provider>,
 
     /// Default provider name
     pub default_provider: String,
+
+    /// Per-AI-type default providers, e.g. `{ "claude": "kimi", "gemini": "openrouter" }`
+    /// 按 AI 类型指定的默认供应商，未配置的类型回退到 `default_provider`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub default_providers: HashMap<AiType, String>,
+
+    /// Pick the provider whose `scenario` best matches the task prompt when an
+    /// MCP client starts a task without naming one
+    /// MCP 启动任务未指定供应商时，按任务内容与 scenario 的相似度选择（默认关闭）
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_provider_by_scenario: bool,
 }
 
 /// Single Provider configuration - env 是唯一数据源
@@ -49,6 +103,15 @@ pub struct Provider {
     /// - 有值且 > 0 → 临时禁用，超过此时间自动恢复
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disabled_until: Option<i64>,
+
+    /// 覆盖 `env` 中的同名变量或追加新变量（优先级：env_overrides > env）
+    /// 用于自建网关需要改写 `ANTHROPIC_BASE_URL` 或追加 `OPENAI_ORG` 等场景
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env_overrides: HashMap<String, String>,
+
+    /// `--aiw-provider auto` 的选择优先级，数值越小越优先；未设置时排在最后
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u32>,
 }
 
 fn default_true() -> bool {
@@ -115,24 +178,44 @@ impl ProvidersConfig {
                 compatible_with: None,
                 env: HashMap::new(),
                 disabled_until: None,
+                env_overrides: HashMap::new(),
+                priority: None,
             },
         );
 
         Self {
             schema: Some(Self::default_schema()),
+            version: PROVIDERS_SCHEMA_VERSION,
             providers,
             default_provider: "official".to_string(),
+            default_providers: HashMap::new(),
+            auto_provider_by_scenario: false,
         }
     }
 
     /// Load configuration from file (placeholder - use manager for actual loading)
     pub fn load(path: &std::path::Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        let content = Self::read_migrated(path)?;
         let mut config: Self = serde_json::from_str(&content)?;
         config.ensure_defaults_and_validate()?;
         Ok(config)
     }
 
+    /// 读取文件内容，必要时先升级到当前版本并备份原文件
+    pub fn read_migrated(path: &Path) -> Result<String, MigrationError> {
+        let migrated = config_migration::migrate_file(path, PROVIDERS_MIGRATIONS)?;
+        if let (Some(from), Some(backup)) = (migrated.migrated_from, &migrated.backup) {
+            eprintln!(
+                "ℹ️  Upgraded {} from version {} to {} (backup: {})",
+                path.display(),
+                from,
+                PROVIDERS_SCHEMA_VERSION,
+                backup.display()
+            );
+        }
+        Ok(migrated.content)
+    }
+
     /// Add a provider to the configuration
     pub fn add_provider(&mut self, id: String, provider: Provider) {
         self.providers.insert(id, provider);
@@ -143,6 +226,13 @@ impl ProvidersConfig {
         if id == self.default_provider {
             return Err(anyhow!("Cannot remove the default provider '{}'", id));
         }
+        if let Some(ai_type) = self.default_for_type(id) {
+            return Err(anyhow!(
+                "Cannot remove provider '{}': it is the default for {}",
+                id,
+                ai_type
+            ));
+        }
         if !self.can_delete_provider(id) {
             return Err(anyhow!(
                 "Provider '{}' is protected and cannot be deleted",
@@ -158,10 +248,18 @@ impl ProvidersConfig {
         self.providers.get(id)
     }
 
+    /// Find an AI type that uses this provider as its type-specific default
+    pub fn default_for_type(&self, id: &str) -> Option<&AiType> {
+        self.default_providers
+            .iter()
+            .find(|(_, provider_id)| provider_id.as_str() == id)
+            .map(|(ai_type, _)| ai_type)
+    }
+
     /// Check if a provider can be deleted
     pub fn can_delete_provider(&self, id: &str) -> bool {
-        // Protect the "official" provider
-        id != "official" && id != self.default_provider
+        // Protect the "official" provider and every configured default
+        id != "official" && id != self.default_provider && self.default_for_type(id).is_none()
     }
 
     /// Ensure optional fields have defaults applied
@@ -190,6 +288,23 @@ impl ProvidersConfig {
             ));
         }
 
+        for (ai_type, provider_id) in &self.default_providers {
+            let provider = self.providers.get(provider_id).ok_or_else(|| {
+                anyhow!(
+                    "Default provider '{}' for {} does not exist",
+                    provider_id,
+                    ai_type
+                )
+            })?;
+            if !provider.supports(ai_type) {
+                return Err(anyhow!(
+                    "Provider '{}' is not compatible with {} and cannot be its default",
+                    provider_id,
+                    ai_type
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -233,6 +348,14 @@ impl Provider {
         false
     }
 
+    /// Check whether `compatible_with` allows the given AI type, ignoring enabled state
+    pub fn supports(&self, ai_type: &AiType) -> bool {
+        match &self.compatible_with {
+            None => true,
+            Some(types) => types.contains(ai_type),
+        }
+    }
+
     /// Check if this provider is compatible with given AI type
     /// Returns false if provider is disabled.
     /// Returns true if compatible_with is None (compatible with all) or contains the ai_type
@@ -240,10 +363,8 @@ impl Provider {
         if !self.is_enabled() {
             return false;
         }
-        match &self.compatible_with {
-            None => true, // None means compatible with all types
-            Some(types) => types.contains(ai_type),
-        }
+        // None means compatible with all types
+        self.supports(ai_type)
     }
 
     /// Get a summary string for display
@@ -294,6 +415,8 @@ mod tests {
                 map
             },
             disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
         };
 
         assert!(provider.env.contains_key("ANTHROPIC_API_KEY"));
@@ -325,6 +448,8 @@ mod tests {
                 map
             },
             disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
         };
 
         let summary = provider.summary();
@@ -348,12 +473,28 @@ mod tests {
         assert_eq!(provider.env.get("ANTHROPIC_API_KEY").unwrap(), "sk-test");
     }
 
+    #[test]
+    fn test_provider_env_overrides_optional() {
+        let json = r#"{"env":{"OPENAI_API_KEY":"sk-test"}}"#;
+        let provider: Provider = serde_json::from_str(json).expect("should deserialize");
+        assert!(provider.env_overrides.is_empty());
+        let serialized = serde_json::to_string(&provider).unwrap();
+        assert!(!serialized.contains("env_overrides"));
+
+        let json = r#"{"env":{},"env_overrides":{"OPENAI_ORG":"org-1"}}"#;
+        let provider: Provider = serde_json::from_str(json).expect("should deserialize");
+        assert_eq!(provider.env_overrides.get("OPENAI_ORG").unwrap(), "org-1");
+    }
+
     #[test]
     fn test_config_validation() {
         let mut config = ProvidersConfig {
             schema: None,
+            version: PROVIDERS_SCHEMA_VERSION,
             providers: HashMap::new(),
             default_provider: "test".to_string(),
+            default_providers: HashMap::new(),
+            auto_provider_by_scenario: false,
         };
 
         // Empty providers should fail
@@ -372,6 +513,8 @@ mod tests {
                     map
                 },
                 disabled_until: None,
+                env_overrides: HashMap::new(),
+                priority: None,
             },
         );
 
@@ -382,4 +525,83 @@ mod tests {
         config.default_provider = "nonexistent".to_string();
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_type_specific_default_must_be_compatible() {
+        let mut config = ProvidersConfig::create_default();
+        config.providers.insert(
+            "kimi".to_string(),
+            Provider {
+                enabled: true,
+                scenario: None,
+                compatible_with: Some(vec![AiType::Claude]),
+                env: HashMap::new(),
+                disabled_until: None,
+                env_overrides: HashMap::new(),
+                priority: None,
+            },
+        );
+
+        config
+            .default_providers
+            .insert(AiType::Claude, "kimi".to_string());
+        assert!(config.validate().is_ok());
+        assert!(!config.can_delete_provider("kimi"));
+        assert!(config.remove_provider("kimi").is_err());
+
+        config
+            .default_providers
+            .insert(AiType::Gemini, "kimi".to_string());
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("not compatible with gemini"), "{}", err);
+
+        config
+            .default_providers
+            .insert(AiType::Gemini, "missing".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_default_providers_json_roundtrip() {
+        let json = r#"{
+            "providers": {"official": {"env": {}}},
+            "default_provider": "official",
+            "default_providers": {"claude": "official"}
+        }"#;
+        let config: ProvidersConfig = serde_json::from_str(json).expect("should deserialize");
+        assert_eq!(config.default_providers[&AiType::Claude], "official");
+
+        let serialized = serde_json::to_string(&ProvidersConfig::create_default()).unwrap();
+        assert!(!serialized.contains("default_providers"));
+    }
+
+    #[test]
+    fn test_unversioned_file_is_migrated_on_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("providers.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "providers": {"kimi": {"env": {}, "compatible_with": "Claude, codex"}},
+                "default_provider": "kimi"
+            }"#,
+        )
+        .unwrap();
+
+        let config = ProvidersConfig::load(&path).unwrap();
+        assert_eq!(config.version, PROVIDERS_SCHEMA_VERSION);
+        assert_eq!(
+            config.providers["kimi"].compatible_with,
+            Some(vec![AiType::Claude, AiType::Codex])
+        );
+        assert!(dir.path().join("providers.json.v1.bak").is_file());
+
+        std::fs::write(
+            &path,
+            r#"{"version": 99, "providers": {}, "default_provider": "kimi"}"#,
+        )
+        .unwrap();
+        let err = ProvidersConfig::load(&path).unwrap_err().to_string();
+        assert!(err.contains("upgrade aiw"), "{}", err);
+    }
 }
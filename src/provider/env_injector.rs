@@ -5,6 +5,10 @@
 use std::collections::HashMap;
 use std::process::Command;
 
+use super::config::Provider;
+use super::error::ProviderResult;
+use super::secrets;
+
 /// Handles environment variable injection for different AI types
 pub struct EnvInjector;
 
@@ -23,6 +27,48 @@ impl EnvInjector {
         }
     }
 
+    /// Provider variables to inject: `env` with `env_overrides` merged on top
+    ///
+    /// Precedence: `env_overrides` > `env`, so an override replaces a mapped
+    /// variable with the same name and adds any variable `env` lacks.
+    pub fn provider_env(provider: &Provider) -> HashMap<String, String> {
+        let mut env = provider.env.clone();
+        env.extend(
+            provider
+                .env_overrides
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        env
+    }
+
+    /// [`Self::provider_env`] with `${env:..}` / `${file:..}` / `${keychain:..}`
+    /// references replaced by the secrets they point to
+    pub fn resolved_provider_env(provider: &Provider) -> ProviderResult<HashMap<String, String>> {
+        secrets::resolve_env(&Self::provider_env(provider))
+    }
+
+    /// Copy of `provider` whose `env` and `env_overrides` hold resolved secrets
+    pub fn resolve_secrets(provider: &Provider) -> ProviderResult<Provider> {
+        let mut resolved = provider.clone();
+        for (key, value) in resolved.env.iter_mut() {
+            // 被 env_overrides 覆盖的变量不会被注入，不必解析
+            if !provider.env_overrides.contains_key(key) {
+                *value = secrets::resolve_value(key, value)?;
+            }
+        }
+        resolved.env_overrides = secrets::resolve_env(&provider.env_overrides)?;
+        Ok(resolved)
+    }
+
+    /// Build the environment a CLI process would see: current process env
+    /// overlaid with the provider's variables
+    pub fn build_env(env_vars: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut env: HashMap<String, String> = std::env::vars().collect();
+        env.extend(env_vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+        env
+    }
+
     /// Mask sensitive values for display
     pub fn mask_sensitive_value(_key: &str, value: &str) -> String {
         if value.len() <= 8 {
@@ -39,3 +85,60 @@ impl EnvInjector {
         format!("{}***{}", &key[..4], &key[key.len() - 4..])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(env: &[(&str, &str)], env_overrides: &[(&str, &str)]) -> Provider {
+        let map = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+        Provider {
+            enabled: true,
+            scenario: None,
+            compatible_with: None,
+            env: map(env),
+            disabled_until: None,
+            env_overrides: map(env_overrides),
+            priority: None,
+        }
+    }
+
+    #[test]
+    fn env_overrides_beat_env_for_the_same_key() {
+        let gateway = provider(
+            &[
+                ("ANTHROPIC_API_KEY", "sk-test-123"),
+                ("ANTHROPIC_BASE_URL", "https://api.example.com"),
+            ],
+            &[("ANTHROPIC_BASE_URL", "https://gateway.internal")],
+        );
+
+        let injected = EnvInjector::provider_env(&gateway);
+        assert_eq!(injected["ANTHROPIC_BASE_URL"], "https://gateway.internal");
+        assert_eq!(injected["ANTHROPIC_API_KEY"], "sk-test-123");
+        assert_eq!(injected.len(), 2);
+    }
+
+    #[test]
+    fn override_only_keys_are_injected() {
+        let gateway = provider(
+            &[("ANTHROPIC_API_KEY", "sk-test-123")],
+            &[("OPENAI_ORG", "org-123")],
+        );
+
+        let injected = EnvInjector::provider_env(&gateway);
+        assert_eq!(injected["OPENAI_ORG"], "org-123");
+        assert_eq!(injected["ANTHROPIC_API_KEY"], "sk-test-123");
+        assert_eq!(injected.len(), 2);
+
+        // 只有 env_overrides 的供应商同样注入
+        let injected = EnvInjector::provider_env(&provider(&[], &[("OPENAI_ORG", "org-123")]));
+        assert_eq!(injected["OPENAI_ORG"], "org-123");
+        assert_eq!(injected.len(), 1);
+    }
+}
@@ -34,6 +34,13 @@ pub enum ProviderError {
     #[error("Provider '{0}' already exists")]
     DuplicateProvider(String),
 
+    #[error("Cannot resolve {variable} from {reference}: {reason}")]
+    SecretUnresolved {
+        variable: String,
+        reference: String,
+        reason: String,
+    },
+
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 
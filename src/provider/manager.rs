@@ -2,11 +2,18 @@
 
 use super::config::{AiType, Provider, ProvidersConfig};
 use super::error::{ProviderError, ProviderResult};
+use super::probe::{cached_quick_check, ProbeHealth};
 use crate::common::constants::files::PROVIDERS_JSON;
-use crate::config::AUTH_DIRECTORY;
+use crate::mcp_routing::history::cosine;
+use crate::mcp_routing::EmbeddingBackend;
+use crate::utils::config_paths::aiw_config_dir;
 use anyhow::Result;
+use std::time::Duration;
 use std::{fs, path::PathBuf};
 
+/// Minimum cosine similarity for a scenario match; below it the default provider is used.
+pub const SCENARIO_MATCH_MIN_SCORE: f32 = 0.3;
+
 /// Provider configuration manager
 pub struct ProviderManager {
     config_path: PathBuf,
@@ -42,6 +49,13 @@ impl ProviderManager {
             )));
         }
 
+        if let Some(ai_type) = self.providers_config.default_for_type(provider_id) {
+            return Err(ProviderError::InvalidConfig(format!(
+                "Cannot remove provider '{}': it is the default for {}. Change that default first.",
+                provider_id, ai_type
+            )));
+        }
+
         if !self.providers_config.can_delete_provider(provider_id) {
             return Err(ProviderError::InvalidConfig(format!(
                 "Provider '{}' is protected and cannot be deleted",
@@ -74,8 +88,8 @@ impl ProviderManager {
             )));
         }
 
-        // Validate environment variable keys and values
-        for (key, value) in &provider.env {
+        // Validate environment variable keys and values (overrides included)
+        for (key, value) in provider.env.iter().chain(provider.env_overrides.iter()) {
             // Check for valid environment variable names
             if key.is_empty() || key.starts_with(char::is_numeric) {
                 return Err(ProviderError::InvalidConfig(format!(
@@ -141,6 +155,10 @@ impl ProviderManager {
             Self::save_to_file(&config_path, &providers_config)?;
             providers_config
         };
+        // 每个命令都会加载配置，这里只记 debug；完整报告由 `aiw provider validate` 输出
+        for issue in super::validation::check_config(&providers_config) {
+            tracing::debug!("{}: {}", config_path.display(), issue);
+        }
 
         Ok(Self {
             config_path,
@@ -161,12 +179,10 @@ impl ProviderManager {
 
     /// Get configuration file path
     fn get_config_path() -> ProviderResult<PathBuf> {
-        let home_dir = dirs::home_dir().ok_or_else(|| {
+        let config_dir = aiw_config_dir().ok_or_else(|| {
             ProviderError::ConfigLoadError("Cannot find home directory".to_string())
         })?;
 
-        let config_dir = home_dir.join(AUTH_DIRECTORY);
-
         // Ensure directory exists
         if !config_dir.exists() {
             fs::create_dir_all(&config_dir)?;
@@ -186,8 +202,8 @@ impl ProviderManager {
 
     /// Load configuration from file
     fn load_from_file(path: &PathBuf) -> ProviderResult<ProvidersConfig> {
-        let content =
-            fs::read_to_string(path).map_err(|e| ProviderError::ConfigLoadError(e.to_string()))?;
+        let content = ProvidersConfig::read_migrated(path)
+            .map_err(|e| ProviderError::ConfigLoadError(e.to_string()))?;
 
         let mut config: ProvidersConfig = serde_json::from_str(&content)
             .map_err(|e| ProviderError::ConfigLoadError(format!("Invalid JSON: {}", e)))?;
@@ -254,6 +270,21 @@ impl ProviderManager {
         Some((name, provider))
     }
 
+    /// Get the default provider for an AI type
+    ///
+    /// Uses the type-specific entry in `default_providers` when it is set and
+    /// enabled, otherwise falls back to the global default.
+    pub fn get_default_provider_for(&self, ai_type: &AiType) -> Option<(String, &Provider)> {
+        if let Some(name) = self.providers_config.default_providers.get(ai_type) {
+            if let Some(provider) = self.providers_config.providers.get(name) {
+                if provider.is_compatible_with(ai_type) {
+                    return Some((name.clone(), provider));
+                }
+            }
+        }
+        self.get_default_provider()
+    }
+
     /// Add new provider
     pub fn add_provider(&mut self, name: String, provider: Provider) -> ProviderResult<()> {
         self.ensure_mutable_id(&name)?;
@@ -273,8 +304,13 @@ impl ProviderManager {
         self.ensure_mutable_id(name)?;
         self.validate_provider(name, &provider)?;
 
-        self.providers_config
-            .add_provider(name.to_string(), provider);
+        // 先在副本上校验，避免把按类型默认值指向不兼容的 Provider
+        let mut updated = self.providers_config.clone();
+        updated.add_provider(name.to_string(), provider);
+        updated
+            .validate()
+            .map_err(|e| ProviderError::InvalidConfig(e.to_string()))?;
+        self.providers_config = updated;
         self.save()?;
         Ok(())
     }
@@ -290,6 +326,48 @@ impl ProviderManager {
         Ok(())
     }
 
+    /// Remove a provider that may currently be a default
+    ///
+    /// Every default (global or per AI type) that points at `name` is moved to
+    /// `replacement` first. Without a replacement this behaves like
+    /// [`Self::remove_provider`] and refuses to remove a default. Nothing is
+    /// saved unless the resulting configuration validates.
+    pub fn remove_provider_replacing_default(
+        &mut self,
+        name: &str,
+        replacement: Option<&str>,
+    ) -> ProviderResult<()> {
+        let Some(replacement) = replacement else {
+            return self.remove_provider(name);
+        };
+        self.ensure_provider_exists(name)?;
+        self.ensure_mutable_id(name)?;
+        self.ensure_provider_exists(replacement)?;
+        if replacement == name {
+            return Err(ProviderError::InvalidConfig(format!(
+                "Provider '{}' cannot replace itself as default",
+                name
+            )));
+        }
+
+        let mut updated = self.providers_config.clone();
+        if updated.default_provider == name {
+            updated.default_provider = replacement.to_string();
+        }
+        for provider_id in updated.default_providers.values_mut() {
+            if provider_id == name {
+                *provider_id = replacement.to_string();
+            }
+        }
+        updated
+            .remove_provider(name)
+            .and_then(|_| updated.validate())
+            .map_err(|e| ProviderError::InvalidConfig(e.to_string()))?;
+        self.providers_config = updated;
+        self.save()?;
+        Ok(())
+    }
+
     /// Set default provider
     pub fn set_default(&mut self, name: &str) -> ProviderResult<()> {
         // Verify provider exists
@@ -406,6 +484,102 @@ impl ProviderManager {
         Some((selected_name, provider))
     }
 
+    /// Resolve `--aiw-provider auto` to a concrete provider name
+    ///
+    /// Considers enabled providers compatible with `ai_type` (excluding the
+    /// empty "official" placeholder) that are not in CLI cooldown. Each one gets
+    /// a quick health probe; healthy providers win, then lower `priority`, then
+    /// lower measured latency. Returns None when nothing is eligible so callers
+    /// can fall back to the regular auto handling.
+    pub async fn resolve_auto(&mut self, ai_type: &AiType) -> Option<String> {
+        let _ = self.restore_expired_providers();
+
+        let cooldown = crate::auto_mode::CliCooldownManager::global();
+        let cli_type = crate::cli_type::parse_cli_type(&ai_type.to_string());
+        let candidates: Vec<(String, Provider)> = self
+            .providers_config
+            .providers
+            .iter()
+            .filter(|(name, provider)| *name != "official" && provider.is_compatible_with(ai_type))
+            .filter(|(name, _)| {
+                cli_type
+                    .as_ref()
+                    .map_or(true, |cli| !cooldown.is_in_cooldown(cli, name))
+            })
+            .map(|(name, provider)| (name.clone(), provider.clone()))
+            .collect();
+
+        let checks = candidates.iter().map(|(name, provider)| async move {
+            let (health, latency) = cached_quick_check(name, provider, ai_type).await;
+            AutoCandidate {
+                name: name.clone(),
+                priority: provider.priority,
+                health,
+                latency,
+            }
+        });
+        let ranked = futures::future::join_all(checks).await;
+        pick_auto_candidate(ranked)
+    }
+
+    /// Pick the provider whose `scenario` best matches `prompt`
+    ///
+    /// Only runs when `auto_provider_by_scenario` is enabled. Candidates are
+    /// enabled providers compatible with `ai_type` that describe a scenario
+    /// (excluding "official"). Returns None when nothing scores at least
+    /// [`SCENARIO_MATCH_MIN_SCORE`], leaving the configured default in charge.
+    pub fn select_by_scenario(
+        &self,
+        ai_type: &AiType,
+        prompt: &str,
+        embedder: &dyn EmbeddingBackend,
+    ) -> Option<String> {
+        if !self.providers_config.auto_provider_by_scenario {
+            return None;
+        }
+
+        let mut candidates: Vec<(&String, &str)> = self
+            .providers_config
+            .providers
+            .iter()
+            .filter(|(name, provider)| {
+                *name != "official" && provider.is_enabled() && provider.is_compatible_with(ai_type)
+            })
+            .filter_map(|(name, provider)| {
+                let scenario = provider.scenario.as_deref()?.trim();
+                (!scenario.is_empty()).then_some((name, scenario))
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates.sort_by(|a, b| a.0.cmp(b.0));
+
+        let inputs: Vec<String> = std::iter::once(prompt.to_string())
+            .chain(candidates.iter().map(|(_, scenario)| scenario.to_string()))
+            .collect();
+        let vectors = match embedder.embed_batch(&inputs) {
+            Ok(vectors) => vectors,
+            Err(err) => {
+                tracing::warn!("Scenario provider selection skipped: {}", err);
+                return None;
+            }
+        };
+        let (prompt_vector, scenario_vectors) = vectors.split_first()?;
+
+        // 同分时按名称取第一个，保证结果稳定
+        let mut best: Option<(&String, f32)> = None;
+        for ((name, _), vector) in candidates.iter().zip(scenario_vectors) {
+            let score = cosine(prompt_vector, vector);
+            if best.map_or(true, |(_, best_score)| score > best_score) {
+                best = Some((*name, score));
+            }
+        }
+        let (name, score) = best?;
+        tracing::debug!("Best scenario match: {} ({:.3})", name, score);
+        (score >= SCENARIO_MATCH_MIN_SCORE).then(|| name.clone())
+    }
+
     // ===== Token Management =====
     // Note: Regional token support was removed in favor of simplified design
 
@@ -566,6 +740,11 @@ impl ProviderManager {
                 // Optionally update default provider
                 self.providers_config.default_provider = imported_config.default_provider;
             }
+
+            // Imported per-type defaults point at providers merged above
+            self.providers_config
+                .default_providers
+                .extend(imported_config.default_providers);
         } else {
             // Replace entire configuration
             self.providers_config = imported_config;
@@ -576,6 +755,35 @@ impl ProviderManager {
     }
 }
 
+/// Auto 选择的候选项及其健康检查结果
+#[derive(Debug, Clone)]
+struct AutoCandidate {
+    name: String,
+    priority: Option<u32>,
+    health: ProbeHealth,
+    latency: Option<Duration>,
+}
+
+/// 按 健康状态 > 配置优先级 > 延迟 > 名称 选出最佳候选
+fn pick_auto_candidate(mut candidates: Vec<AutoCandidate>) -> Option<String> {
+    candidates.sort_by(|a, b| {
+        a.health
+            .cmp(&b.health)
+            .then_with(|| {
+                a.priority
+                    .unwrap_or(u32::MAX)
+                    .cmp(&b.priority.unwrap_or(u32::MAX))
+            })
+            .then_with(|| {
+                a.latency
+                    .unwrap_or(Duration::MAX)
+                    .cmp(&b.latency.unwrap_or(Duration::MAX))
+            })
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    candidates.into_iter().next().map(|c| c.name)
+}
+
 impl Default for ProviderManager {
     fn default() -> Self {
         Self::new().unwrap_or_else(|_| {
@@ -615,6 +823,8 @@ mod tests {
             compatible_with: None,
             env: HashMap::new(),
             disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
         };
 
         assert!(manager
@@ -637,6 +847,8 @@ mod tests {
             compatible_with: None,
             env: HashMap::new(),
             disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
         };
 
         // "auto" should be rejected as reserved name (case-insensitive)
@@ -651,6 +863,34 @@ mod tests {
             .is_err());
     }
 
+    #[test]
+    fn test_env_overrides_are_validated() {
+        let manager = ProviderManager {
+            config_path: PathBuf::new(),
+            providers_config: ProvidersConfig::default(),
+        };
+
+        let mut provider = Provider {
+            enabled: true,
+            scenario: None,
+            compatible_with: None,
+            env: HashMap::new(),
+            disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
+        };
+        provider
+            .env_overrides
+            .insert("ANTHROPIC_BASE_URL".to_string(), "ftp://proxy".to_string());
+        assert!(manager.validate_provider("gateway", &provider).is_err());
+
+        provider.env_overrides.insert(
+            "ANTHROPIC_BASE_URL".to_string(),
+            "https://proxy.internal".to_string(),
+        );
+        assert!(manager.validate_provider("gateway", &provider).is_ok());
+    }
+
     #[test]
     fn test_provider_compatibility() {
         use crate::provider::config::AiType;
@@ -662,6 +902,8 @@ mod tests {
             compatible_with: None,
             env: HashMap::new(),
             disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
         };
         assert!(provider_all.is_compatible_with(&AiType::Claude));
         assert!(provider_all.is_compatible_with(&AiType::Codex));
@@ -674,6 +916,8 @@ mod tests {
             compatible_with: Some(vec![AiType::Claude]),
             env: HashMap::new(),
             disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
         };
         assert!(provider_claude.is_compatible_with(&AiType::Claude));
         assert!(!provider_claude.is_compatible_with(&AiType::Codex));
@@ -686,6 +930,8 @@ mod tests {
             compatible_with: Some(vec![AiType::Claude, AiType::Codex]),
             env: HashMap::new(),
             disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
         };
         assert!(provider_multi.is_compatible_with(&AiType::Claude));
         assert!(provider_multi.is_compatible_with(&AiType::Codex));
@@ -711,6 +957,8 @@ mod tests {
                     map
                 },
                 disabled_until: None,
+                env_overrides: HashMap::new(),
+                priority: None,
             },
         );
 
@@ -727,6 +975,8 @@ mod tests {
                     map
                 },
                 disabled_until: None,
+                env_overrides: HashMap::new(),
+                priority: None,
             },
         );
 
@@ -773,6 +1023,8 @@ mod tests {
                     map
                 },
                 disabled_until: None,
+                env_overrides: HashMap::new(),
+                priority: None,
             },
         );
 
@@ -785,4 +1037,224 @@ mod tests {
         let result = manager.get_random_compatible_provider(&AiType::Codex);
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_select_by_scenario() {
+        use crate::mcp_routing::{hashed_embedding, MockEmbeddingBackend, EMBEDDING_DIMENSION};
+
+        let provider = |scenario: &str, compatible_with: Option<Vec<AiType>>| Provider {
+            enabled: true,
+            scenario: Some(scenario.to_string()),
+            compatible_with,
+            env: HashMap::new(),
+            disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
+        };
+        let mut providers_config = ProvidersConfig::default();
+        providers_config.providers.insert(
+            "reviewer".to_string(),
+            provider("security audit and code review", None),
+        );
+        providers_config.providers.insert(
+            "writer".to_string(),
+            provider("documentation writing and translation", None),
+        );
+        providers_config.providers.insert(
+            "gemini-reviewer".to_string(),
+            provider("code review", Some(vec![AiType::Gemini])),
+        );
+        let mut manager = ProviderManager {
+            config_path: PathBuf::new(),
+            providers_config,
+        };
+        let embedder = MockEmbeddingBackend::new(EMBEDDING_DIMENSION, hashed_embedding);
+        let prompt = "Run a security audit and code review of the auth module";
+
+        // 默认关闭
+        assert_eq!(
+            manager.select_by_scenario(&AiType::Claude, prompt, &embedder),
+            None
+        );
+
+        manager.providers_config.auto_provider_by_scenario = true;
+        assert_eq!(
+            manager.select_by_scenario(&AiType::Claude, prompt, &embedder),
+            Some("reviewer".to_string())
+        );
+        // 分数过低时交给默认 Provider
+        assert_eq!(
+            manager.select_by_scenario(&AiType::Claude, "zzz qqq", &embedder),
+            None
+        );
+        // gemini-reviewer 完全匹配，但只兼容 gemini
+        assert_eq!(
+            manager.select_by_scenario(&AiType::Claude, "code review", &embedder),
+            Some("reviewer".to_string())
+        );
+        assert_eq!(
+            manager.select_by_scenario(&AiType::Gemini, "code review", &embedder),
+            Some("gemini-reviewer".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_default_provider_for_prefers_type_specific_default() {
+        use crate::provider::config::AiType;
+
+        let mut providers_config = ProvidersConfig::default();
+        providers_config.providers.insert(
+            "kimi".to_string(),
+            Provider {
+                enabled: true,
+                scenario: None,
+                compatible_with: Some(vec![AiType::Claude]),
+                env: HashMap::new(),
+                disabled_until: None,
+                env_overrides: HashMap::new(),
+                priority: None,
+            },
+        );
+        providers_config
+            .default_providers
+            .insert(AiType::Claude, "kimi".to_string());
+
+        let mut manager = ProviderManager {
+            config_path: PathBuf::new(),
+            providers_config,
+        };
+
+        let (name, _) = manager.get_default_provider_for(&AiType::Claude).unwrap();
+        assert_eq!(name, "kimi");
+
+        // No gemini-specific default: fall back to the global default
+        let (name, _) = manager.get_default_provider_for(&AiType::Gemini).unwrap();
+        assert_eq!(name, "official");
+
+        // Disabled type-specific default also falls back
+        manager
+            .providers_config
+            .providers
+            .get_mut("kimi")
+            .unwrap()
+            .enabled = false;
+        let (name, _) = manager.get_default_provider_for(&AiType::Claude).unwrap();
+        assert_eq!(name, "official");
+    }
+
+    #[test]
+    fn test_pick_auto_candidate_ranking() {
+        let candidate = |name: &str, priority, health, latency_ms: Option<u64>| AutoCandidate {
+            name: name.to_string(),
+            priority,
+            health,
+            latency: latency_ms.map(Duration::from_millis),
+        };
+
+        // Healthy beats a higher-priority provider that failed its probe
+        let picked = pick_auto_candidate(vec![
+            candidate("broken", Some(1), ProbeHealth::Failing, Some(10)),
+            candidate("ok", None, ProbeHealth::Healthy, Some(900)),
+        ]);
+        assert_eq!(picked.as_deref(), Some("ok"));
+
+        // Among healthy providers, configured priority wins over latency
+        let picked = pick_auto_candidate(vec![
+            candidate("fast", None, ProbeHealth::Healthy, Some(50)),
+            candidate("preferred", Some(1), ProbeHealth::Healthy, Some(400)),
+        ]);
+        assert_eq!(picked.as_deref(), Some("preferred"));
+
+        // Same priority: lower latency wins; unprobed providers rank below healthy ones
+        let picked = pick_auto_candidate(vec![
+            candidate("unknown", None, ProbeHealth::Unknown, None),
+            candidate("slow", None, ProbeHealth::Healthy, Some(800)),
+            candidate("fast", None, ProbeHealth::Healthy, Some(80)),
+        ]);
+        assert_eq!(picked.as_deref(), Some("fast"));
+
+        assert_eq!(pick_auto_candidate(Vec::new()), None);
+    }
+
+    fn provider_for(types: Option<Vec<AiType>>) -> Provider {
+        Provider {
+            enabled: true,
+            scenario: None,
+            compatible_with: types,
+            env: HashMap::new(),
+            disabled_until: None,
+            env_overrides: HashMap::new(),
+            priority: None,
+        }
+    }
+
+    #[test]
+    fn test_remove_default_moves_defaults_to_replacement() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut providers_config = ProvidersConfig::default();
+        providers_config
+            .providers
+            .insert("kimi".to_string(), provider_for(None));
+        providers_config
+            .providers
+            .insert("glm".to_string(), provider_for(None));
+        providers_config.default_provider = "kimi".to_string();
+        providers_config
+            .default_providers
+            .insert(AiType::Claude, "kimi".to_string());
+        let mut manager = ProviderManager {
+            config_path: dir.path().join("providers.json"),
+            providers_config,
+        };
+
+        assert!(manager.remove_provider("kimi").is_err());
+        assert!(manager
+            .remove_provider_replacing_default("kimi", Some("missing"))
+            .is_err());
+        assert!(manager.providers_config.providers.contains_key("kimi"));
+
+        manager
+            .remove_provider_replacing_default("kimi", Some("glm"))
+            .unwrap();
+        assert!(!manager.providers_config.providers.contains_key("kimi"));
+        assert_eq!(manager.default_provider_name(), "glm");
+        assert_eq!(
+            manager.providers_config.default_providers[&AiType::Claude],
+            "glm"
+        );
+        let saved = ProvidersConfig::load(&dir.path().join("providers.json")).unwrap();
+        assert_eq!(saved.default_provider, "glm");
+    }
+
+    #[test]
+    fn test_update_rejects_breaking_a_type_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut providers_config = ProvidersConfig::default();
+        providers_config
+            .providers
+            .insert("kimi".to_string(), provider_for(Some(vec![AiType::Claude])));
+        providers_config
+            .default_providers
+            .insert(AiType::Claude, "kimi".to_string());
+        let mut manager = ProviderManager {
+            config_path: dir.path().join("providers.json"),
+            providers_config,
+        };
+
+        let err = manager
+            .update_provider("kimi", provider_for(Some(vec![AiType::Gemini])))
+            .unwrap_err();
+        assert!(err.to_string().contains("not compatible"));
+        assert_eq!(
+            manager.providers_config.providers["kimi"].compatible_with,
+            Some(vec![AiType::Claude])
+        );
+
+        manager
+            .update_provider(
+                "kimi",
+                provider_for(Some(vec![AiType::Claude, AiType::Gemini])),
+            )
+            .unwrap();
+    }
 }
@@ -8,6 +8,9 @@ pub mod env_injector;
 pub mod env_mapping;
 pub mod error;
 pub mod manager;
+pub mod probe;
+pub mod secrets;
+pub mod validation;
 
 // Re-export commonly used types
 pub use config::AiType;
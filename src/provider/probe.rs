@@ -0,0 +1,422 @@
+//! Provider 连通性探测
+//!
+//! 按 CLI 实际启动时的方式构建环境变量（进程环境 + Provider env），
+//! 向 API 端点发一个最小请求验证凭据和连通性。`aiw provider test` 与
+//! `--aiw-provider auto` 的健康检查共用这里的请求构建和结果归类；
+//! 健康检查只访问不计费的模型列表端点，结果按 [`QUICK_CHECK_CACHE_TTL`] 缓存。
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use super::config::Provider;
+use super::{AiType, EnvInjector};
+use crate::error::{errors, AgenticWardenError};
+
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+const DEFAULT_CLAUDE_PROBE_MODEL: &str = "claude-3-5-haiku-latest";
+
+/// 单个 AI 类型的探测请求
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeRequest {
+    pub method: reqwest::Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<serde_json::Value>,
+}
+
+/// 探测结果
+#[derive(Debug)]
+pub struct ProbeOutcome {
+    pub ai_type: AiType,
+    pub status: Option<u16>,
+    pub latency: Duration,
+    pub error: Option<AgenticWardenError>,
+}
+
+fn env_value<'a>(env: &'a HashMap<String, String>, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .filter_map(|key| env.get(*key))
+        .map(|value| value.trim())
+        .find(|value| !value.is_empty())
+}
+
+fn base_url(env: &HashMap<String, String>, keys: &[&str], default: &str) -> String {
+    env_value(env, keys)
+        .unwrap_or(default)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn claude_headers(env: &HashMap<String, String>) -> Option<Vec<(String, String)>> {
+    let mut headers = vec![("anthropic-version".to_string(), "2023-06-01".to_string())];
+    if let Some(token) = env_value(env, &["ANTHROPIC_AUTH_TOKEN"]) {
+        headers.push(("authorization".to_string(), format!("Bearer {}", token)));
+    } else {
+        let key = env_value(env, &["ANTHROPIC_API_KEY"])?;
+        headers.push(("x-api-key".to_string(), key.to_string()));
+    }
+    Some(headers)
+}
+
+fn claude_base_url(env: &HashMap<String, String>) -> String {
+    base_url(env, &["ANTHROPIC_BASE_URL"], "https://api.anthropic.com")
+}
+
+/// 根据环境变量构建探测请求；缺少 API Key 时返回 `None`
+///
+/// Claude 发送 `max_tokens: 1` 的最小补全请求（第三方兼容端点通常不提供 `/models`），
+/// 其余类型只列出模型，足以验证鉴权且不消耗 token。
+pub fn probe_request(ai_type: &AiType, env: &HashMap<String, String>) -> Option<ProbeRequest> {
+    match ai_type {
+        AiType::Claude => {
+            let mut headers = claude_headers(env)?;
+            headers.push(("content-type".to_string(), "application/json".to_string()));
+            let model = env_value(env, &["ANTHROPIC_MODEL", "ANTHROPIC_SMALL_FAST_MODEL"])
+                .unwrap_or(DEFAULT_CLAUDE_PROBE_MODEL);
+            Some(ProbeRequest {
+                method: reqwest::Method::POST,
+                url: format!("{}/v1/messages", claude_base_url(env)),
+                headers,
+                body: Some(serde_json::json!({
+                    "model": model,
+                    "max_tokens": 1,
+                    "messages": [{ "role": "user", "content": "ping" }],
+                })),
+            })
+        }
+        AiType::Codex => {
+            let key = env_value(env, &["OPENAI_API_KEY"])?;
+            let mut headers = vec![("authorization".to_string(), format!("Bearer {}", key))];
+            if let Some(org) = env_value(env, &["OPENAI_ORG_ID"]) {
+                headers.push(("openai-organization".to_string(), org.to_string()));
+            }
+            Some(ProbeRequest {
+                method: reqwest::Method::GET,
+                url: format!(
+                    "{}/models",
+                    base_url(env, &["OPENAI_BASE_URL"], "https://api.openai.com/v1")
+                ),
+                headers,
+                body: None,
+            })
+        }
+        AiType::Gemini => {
+            let key = env_value(env, &["GEMINI_API_KEY", "GOOGLE_API_KEY"])?;
+            Some(ProbeRequest {
+                method: reqwest::Method::GET,
+                url: format!(
+                    "{}/v1beta/models",
+                    base_url(
+                        env,
+                        &["GOOGLE_GEMINI_BASE_URL"],
+                        "https://generativelanguage.googleapis.com"
+                    )
+                ),
+                headers: vec![("x-goog-api-key".to_string(), key.to_string())],
+                body: None,
+            })
+        }
+        AiType::Grok => {
+            let key = env_value(env, &["XAI_API_KEY"])?;
+            Some(ProbeRequest {
+                method: reqwest::Method::GET,
+                url: format!(
+                    "{}/models",
+                    base_url(env, &["XAI_BASE_URL"], "https://api.x.ai/v1")
+                ),
+                headers: vec![("authorization".to_string(), format!("Bearer {}", key))],
+                body: None,
+            })
+        }
+        AiType::Auto => None,
+    }
+}
+
+/// 把失败的 HTTP 状态归类到统一错误类型，以便复用 `to_user_facing` 的提示
+pub fn classify_http_failure(provider: &str, status: u16, detail: &str) -> AgenticWardenError {
+    let message = if detail.is_empty() {
+        format!("HTTP {}", status)
+    } else {
+        format!("HTTP {}: {}", status, detail)
+    };
+    match status {
+        401 | 403 => errors::auth_error(message, provider),
+        408 | 504 => AgenticWardenError::Timeout {
+            message,
+            timeout_ms: PROBE_TIMEOUT.as_millis() as u64,
+            source: None,
+        },
+        _ => errors::provider_error(provider, message),
+    }
+}
+
+fn classify_transport_failure(err: reqwest::Error) -> AgenticWardenError {
+    if err.is_timeout() {
+        AgenticWardenError::Timeout {
+            message: "Provider endpoint did not respond".to_string(),
+            timeout_ms: PROBE_TIMEOUT.as_millis() as u64,
+            source: Some(Box::new(err)),
+        }
+    } else {
+        AgenticWardenError::Network {
+            message: err.to_string(),
+            url: err.url().map(|url| url.to_string()),
+            source: Some(Box::new(err)),
+        }
+    }
+}
+
+pub fn build_client(env: &HashMap<String, String>) -> Result<reqwest::Client, reqwest::Error> {
+    let mut builder = reqwest::Client::builder().timeout(PROBE_TIMEOUT);
+    // Provider 自带代理时优先使用（例如 Gemini 的 https_proxy）
+    if let Some(proxy) = env_value(env, &["https_proxy", "HTTPS_PROXY"]) {
+        builder = builder.proxy(reqwest::Proxy::https(proxy)?);
+    }
+    builder.build()
+}
+
+pub async fn run_probe(
+    client: &reqwest::Client,
+    provider: &str,
+    ai_type: AiType,
+    request: ProbeRequest,
+) -> ProbeOutcome {
+    let mut builder = client.request(request.method, &request.url);
+    for (key, value) in &request.headers {
+        builder = builder.header(key.as_str(), value.as_str());
+    }
+    if let Some(body) = &request.body {
+        builder = builder.json(body);
+    }
+
+    let started = Instant::now();
+    match builder.send().await {
+        Ok(response) => {
+            let latency = started.elapsed();
+            let status = response.status();
+            let error = if status.is_success() {
+                None
+            } else {
+                let detail = response.text().await.unwrap_or_default();
+                let detail: String = detail.trim().chars().take(200).collect();
+                Some(classify_http_failure(provider, status.as_u16(), &detail))
+            };
+            ProbeOutcome {
+                ai_type,
+                status: Some(status.as_u16()),
+                latency,
+                error,
+            }
+        }
+        Err(err) => ProbeOutcome {
+            ai_type,
+            status: None,
+            latency: started.elapsed(),
+            error: Some(classify_transport_failure(err)),
+        },
+    }
+}
+
+/// Auto 选择时单次健康检查的时限，避免慢端点拖住任务启动
+pub const QUICK_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 快速健康检查结果，排序时 Healthy 优先
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProbeHealth {
+    Healthy,
+    /// 没有可用于探测的 API Key（CLI 可能使用自身登录）
+    Unknown,
+    Failing,
+}
+
+/// Auto 选择复用健康检查结果的时长，避免每次启动任务都重新探测所有候选
+pub const QUICK_CHECK_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// 健康检查请求：与 [`probe_request`] 相同，但 Claude 改为 `GET /v1/models`，
+/// 只验证鉴权和连通性，不产生补全计费
+pub fn health_request(ai_type: &AiType, env: &HashMap<String, String>) -> Option<ProbeRequest> {
+    match ai_type {
+        AiType::Claude => Some(ProbeRequest {
+            method: reqwest::Method::GET,
+            url: format!("{}/v1/models", claude_base_url(env)),
+            headers: claude_headers(env)?,
+            body: None,
+        }),
+        _ => probe_request(ai_type, env),
+    }
+}
+
+/// 健康检查结果归类：第三方兼容端点常常没有 `/models`（404/405），
+/// 此时端点可达但鉴权无法免费验证，记为 Unknown 而不是 Failing
+fn quick_health(outcome: &ProbeOutcome) -> ProbeHealth {
+    if outcome.error.is_none() {
+        return ProbeHealth::Healthy;
+    }
+    match outcome.status {
+        Some(404 | 405) => ProbeHealth::Unknown,
+        _ => ProbeHealth::Failing,
+    }
+}
+
+/// 在 [`QUICK_CHECK_TIMEOUT`] 内探测 Provider 对某个 AI 类型是否可用，
+/// 返回健康状态和测得的延迟
+pub async fn quick_check(
+    name: &str,
+    provider: &Provider,
+    ai_type: &AiType,
+) -> (ProbeHealth, Option<Duration>) {
+    // 钥匙串引用会阻塞，不能在 runtime 线程上解析
+    let resolved = {
+        let provider = provider.clone();
+        tokio::task::spawn_blocking(move || EnvInjector::resolved_provider_env(&provider)).await
+    };
+    let Ok(Ok(provider_env)) = resolved else {
+        return (ProbeHealth::Failing, None);
+    };
+    let env = EnvInjector::build_env(&provider_env);
+    let Some(request) = health_request(ai_type, &env) else {
+        return (ProbeHealth::Unknown, None);
+    };
+    let Ok(client) = build_client(&env) else {
+        return (ProbeHealth::Failing, None);
+    };
+
+    match tokio::time::timeout(
+        QUICK_CHECK_TIMEOUT,
+        run_probe(&client, name, ai_type.clone(), request),
+    )
+    .await
+    {
+        Ok(outcome) => (quick_health(&outcome), Some(outcome.latency)),
+        Err(_) => (ProbeHealth::Failing, None),
+    }
+}
+
+type HealthCache = HashMap<(String, AiType), (Instant, ProbeHealth, Option<Duration>)>;
+
+fn health_cache() -> &'static Mutex<HealthCache> {
+    static CACHE: OnceLock<Mutex<HealthCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// [`quick_check`] 加上进程内缓存：同一 Provider / AI 类型在
+/// [`QUICK_CHECK_CACHE_TTL`] 内复用上次结果（MCP 服务会连续启动很多任务）
+pub async fn cached_quick_check(
+    name: &str,
+    provider: &Provider,
+    ai_type: &AiType,
+) -> (ProbeHealth, Option<Duration>) {
+    let key = (name.to_string(), ai_type.clone());
+    if let Some((checked_at, health, latency)) = health_cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&key).copied())
+    {
+        if checked_at.elapsed() < QUICK_CHECK_CACHE_TTL {
+            return (health, latency);
+        }
+    }
+
+    let (health, latency) = quick_check(name, provider, ai_type).await;
+    if let Ok(mut cache) = health_cache().lock() {
+        cache.retain(|_, (checked_at, _, _)| checked_at.elapsed() < QUICK_CHECK_CACHE_TTL);
+        cache.insert(key, (Instant::now(), health, latency));
+    }
+    (health, latency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn claude_probe_prefers_auth_token_and_custom_base_url() {
+        let env = env_map(&[
+            ("ANTHROPIC_AUTH_TOKEN", "tok"),
+            ("ANTHROPIC_API_KEY", "key"),
+            (
+                "ANTHROPIC_BASE_URL",
+                "https://open.bigmodel.cn/api/anthropic/",
+            ),
+            ("ANTHROPIC_MODEL", "glm-4.6"),
+        ]);
+        let request = probe_request(&AiType::Claude, &env).unwrap();
+
+        assert_eq!(request.method, reqwest::Method::POST);
+        assert_eq!(
+            request.url,
+            "https://open.bigmodel.cn/api/anthropic/v1/messages"
+        );
+        assert!(request
+            .headers
+            .contains(&("authorization".to_string(), "Bearer tok".to_string())));
+        assert!(!request.headers.iter().any(|(k, _)| k == "x-api-key"));
+        let body = request.body.unwrap();
+        assert_eq!(body["model"], "glm-4.6");
+        assert_eq!(body["max_tokens"], 1);
+    }
+
+    #[test]
+    fn probes_without_api_key_are_skipped() {
+        let env = env_map(&[("OPENAI_BASE_URL", "https://example.com/v1")]);
+        assert!(probe_request(&AiType::Codex, &env).is_none());
+        assert!(probe_request(&AiType::Gemini, &env).is_none());
+        assert!(probe_request(&AiType::Auto, &env).is_none());
+
+        let env = env_map(&[("OPENAI_API_KEY", "sk-test")]);
+        let request = probe_request(&AiType::Codex, &env).unwrap();
+        assert_eq!(request.url, "https://api.openai.com/v1/models");
+        assert!(request.body.is_none());
+    }
+
+    #[test]
+    fn claude_health_check_lists_models_instead_of_sending_a_message() {
+        let env = env_map(&[
+            ("ANTHROPIC_API_KEY", "key"),
+            ("ANTHROPIC_BASE_URL", "https://example.com/anthropic/"),
+        ]);
+        let request = health_request(&AiType::Claude, &env).unwrap();
+
+        assert_eq!(request.method, reqwest::Method::GET);
+        assert_eq!(request.url, "https://example.com/anthropic/v1/models");
+        assert!(request.body.is_none());
+        assert!(request
+            .headers
+            .contains(&("x-api-key".to_string(), "key".to_string())));
+        assert!(health_request(&AiType::Claude, &HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn missing_models_endpoint_is_unknown_rather_than_failing() {
+        let outcome = |status: u16| ProbeOutcome {
+            ai_type: AiType::Claude,
+            status: Some(status),
+            latency: Duration::from_millis(10),
+            error: (status >= 400).then(|| classify_http_failure("glm", status, "")),
+        };
+
+        assert_eq!(quick_health(&outcome(200)), ProbeHealth::Healthy);
+        assert_eq!(quick_health(&outcome(404)), ProbeHealth::Unknown);
+        assert_eq!(quick_health(&outcome(401)), ProbeHealth::Failing);
+        assert_eq!(quick_health(&outcome(500)), ProbeHealth::Failing);
+    }
+
+    #[test]
+    fn http_failures_map_to_actionable_hints() {
+        let auth = classify_http_failure("glm", 401, "invalid api key");
+        assert!(matches!(auth, AgenticWardenError::Auth { .. }));
+        assert!(auth.to_user_facing().hint.is_some());
+
+        let server = classify_http_failure("glm", 500, "");
+        assert!(matches!(server, AgenticWardenError::Provider { .. }));
+        assert!(server.to_user_facing().message.contains("HTTP 500"));
+    }
+}
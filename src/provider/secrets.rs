@@ -0,0 +1,266 @@
+//! Secret references in provider env values
+//!
+//! An `env` / `env_overrides` value written as `${env:NAME}`, `${file:/path}` or
+//! `${keychain:service}` is looked up when the CLI process is launched, so
+//! providers.json does not have to hold the secret itself.
+//! 只有整个值是一个引用时才解析；其他值（包括 `${HOME}` 这类没有来源前缀、或 `${x:y}`
+//! 这类来源未知的写法）按字面量原样注入。
+
+use super::error::{ProviderError, ProviderResult};
+use crate::utils::config_paths::expand_home;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 钥匙串查询的时限：钥匙串锁定或等待授权弹窗时不让任务启动无限挂起
+pub const KEYCHAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where a referenced secret lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretRef<'a> {
+    /// Variable in the aiw process environment
+    Env(&'a str),
+    /// File contents, trailing newline removed (`~/` is expanded)
+    File(&'a str),
+    /// OS keychain entry by service name
+    Keychain(&'a str),
+}
+
+/// Parse `value` as a secret reference
+///
+/// `Ok(None)` for literal values, including `${source:...}` with an unknown
+/// source (see [`unknown_source`]); an error for a known source with an empty
+/// target, which is almost certainly a typo.
+pub fn parse_reference(value: &str) -> Result<Option<SecretRef<'_>>, String> {
+    let Some((source, target)) = split_reference(value) else {
+        return Ok(None);
+    };
+    let reference = match source {
+        "env" => SecretRef::Env(target),
+        "file" => SecretRef::File(target),
+        "keychain" => SecretRef::Keychain(target),
+        _ => return Ok(None),
+    };
+    if target.is_empty() {
+        return Err(format!("'{}' does not name a secret", value.trim()));
+    }
+    Ok(Some(reference))
+}
+
+/// The source of a `${source:...}` value that is not env, file or keychain
+///
+/// Such values are injected as literals; `aiw provider validate` uses this to
+/// point out a possible typo.
+pub fn unknown_source(value: &str) -> Option<&str> {
+    split_reference(value)
+        .map(|(source, _)| source)
+        .filter(|source| !matches!(*source, "env" | "file" | "keychain"))
+}
+
+/// `${source:target}` 拆成（来源，目标），都已去掉首尾空白
+fn split_reference(value: &str) -> Option<(&str, &str)> {
+    let inner = value
+        .trim()
+        .strip_prefix("${")
+        .and_then(|rest| rest.strip_suffix('}'))?;
+    let (source, target) = inner.split_once(':')?;
+    Some((source.trim(), target.trim()))
+}
+
+/// Resolve one env value; literals are returned unchanged
+pub fn resolve_value(key: &str, value: &str) -> ProviderResult<String> {
+    let unresolved = |reason: String| ProviderError::SecretUnresolved {
+        variable: key.to_string(),
+        reference: value.trim().to_string(),
+        reason,
+    };
+    let Some(reference) = parse_reference(value).map_err(unresolved)? else {
+        return Ok(value.to_string());
+    };
+    let secret = match reference {
+        SecretRef::Env(name) => std::env::var(name)
+            .map_err(|_| unresolved(format!("environment variable {} is not set", name)))?,
+        SecretRef::File(path) => {
+            let path = expand_home(path);
+            let content = std::fs::read_to_string(&path)
+                .map_err(|err| unresolved(format!("cannot read {}: {}", path.display(), err)))?;
+            content.trim_end_matches(['\r', '\n']).to_string()
+        }
+        SecretRef::Keychain(service) => read_keychain(service).map_err(unresolved)?,
+    };
+    if secret.is_empty() {
+        return Err(unresolved("the secret is empty".to_string()));
+    }
+    Ok(secret)
+}
+
+/// Resolve every value of an env map
+pub fn resolve_env(env: &HashMap<String, String>) -> ProviderResult<HashMap<String, String>> {
+    env.iter()
+        .map(|(key, value)| Ok((key.clone(), resolve_value(key, value)?)))
+        .collect()
+}
+
+/// 读取系统钥匙串：macOS 用 `security`，Linux 用 libsecret 的 `secret-tool`
+fn read_keychain(service: &str) -> Result<String, String> {
+    #[cfg(target_os = "macos")]
+    let (program, args) = ("security", ["find-generic-password", "-s", service, "-w"]);
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let (program, args) = ("secret-tool", ["lookup", "service", service]);
+    #[cfg(not(unix))]
+    {
+        let _ = service;
+        Err("keychain references are only supported on macOS and Linux".to_string())
+    }
+
+    #[cfg(unix)]
+    {
+        let mut command = std::process::Command::new(program);
+        command.args(args);
+        let (status, stdout) = run_with_timeout(&mut command, KEYCHAIN_TIMEOUT)
+            .map_err(|err| format!("{}: {}", program, err))?;
+        if !status.success() {
+            return Err(format!(
+                "no keychain entry for service '{}' ({} exited with {})",
+                service, program, status
+            ));
+        }
+        Ok(stdout.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+/// 运行命令并读取 stdout，超过 `timeout` 时结束进程并返回错误
+#[cfg(unix)]
+fn run_with_timeout(
+    command: &mut std::process::Command,
+    timeout: Duration,
+) -> Result<(std::process::ExitStatus, String), String> {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|err| format!("cannot run: {}", err))?;
+    // 输出在子进程运行时读取，避免 stdout 写满管道后卡住
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let reader = std::thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = stdout.read_to_end(&mut output);
+        output
+    });
+
+    let deadline = std::time::Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if std::time::Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "no answer within {}s (is the keychain locked?)",
+                    timeout.as_secs()
+                ));
+            }
+            Err(err) => return Err(format!("cannot wait for the process: {}", err)),
+        }
+    };
+    let output = reader.join().unwrap_or_default();
+    Ok((status, String::from_utf8_lossy(&output).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    fn only_whole_references_with_known_sources_are_parsed() {
+        assert_eq!(
+            parse_reference("${env:KIMI_KEY}"),
+            Ok(Some(SecretRef::Env("KIMI_KEY")))
+        );
+        assert_eq!(
+            parse_reference("${file:~/.secrets/kimi}"),
+            Ok(Some(SecretRef::File("~/.secrets/kimi")))
+        );
+        assert_eq!(
+            parse_reference("${keychain:aiw-kimi}"),
+            Ok(Some(SecretRef::Keychain("aiw-kimi")))
+        );
+        assert_eq!(parse_reference("sk-literal"), Ok(None));
+        assert_eq!(parse_reference("${HOME}"), Ok(None));
+        assert_eq!(parse_reference("prefix-${env:KEY}"), Ok(None));
+        // 来源未知的值按字面量处理
+        assert_eq!(parse_reference("${vault:kimi}"), Ok(None));
+        assert_eq!(unknown_source("${vault:kimi}"), Some("vault"));
+        assert_eq!(unknown_source("${env:KIMI_KEY}"), None);
+        assert_eq!(unknown_source("sk-literal"), None);
+        assert_eq!(
+            resolve_value("KEY", "${x:y}").unwrap(),
+            "${x:y}",
+            "literal values shaped like a reference keep working"
+        );
+        assert!(parse_reference("${env:}").is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn references_resolve_from_env_and_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let key_file = dir.path().join("key");
+        std::fs::write(&key_file, "sk-from-file\n").unwrap();
+        std::env::set_var("AIW_TEST_SECRET_REF", "sk-from-env");
+
+        let env = HashMap::from([
+            ("LITERAL".to_string(), "https://api.example.com".to_string()),
+            (
+                "FROM_ENV".to_string(),
+                "${env:AIW_TEST_SECRET_REF}".to_string(),
+            ),
+            (
+                "FROM_FILE".to_string(),
+                format!("${{file:{}}}", key_file.display()),
+            ),
+        ]);
+        let resolved = resolve_env(&env).unwrap();
+        assert_eq!(resolved["LITERAL"], "https://api.example.com");
+        assert_eq!(resolved["FROM_ENV"], "sk-from-env");
+        assert_eq!(resolved["FROM_FILE"], "sk-from-file");
+
+        std::env::remove_var("AIW_TEST_SECRET_REF");
+        let err = resolve_value("ANTHROPIC_API_KEY", "${env:AIW_TEST_SECRET_REF}").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Cannot resolve ANTHROPIC_API_KEY from ${env:AIW_TEST_SECRET_REF}: \
+             environment variable AIW_TEST_SECRET_REF is not set"
+        );
+        let err = resolve_value("KEY", "${file:/nonexistent/aiw-key}").unwrap_err();
+        assert!(err.to_string().contains("cannot read /nonexistent/aiw-key"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn slow_keychain_helpers_are_stopped_at_the_timeout() {
+        let started = std::time::Instant::now();
+        let err = run_with_timeout(
+            std::process::Command::new("sleep").arg("5"),
+            Duration::from_millis(100),
+        )
+        .unwrap_err();
+        assert!(err.contains("no answer within"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(4));
+
+        let (status, stdout) = run_with_timeout(
+            std::process::Command::new("echo").arg("sk-1"),
+            KEYCHAIN_TIMEOUT,
+        )
+        .unwrap();
+        assert!(status.success());
+        assert_eq!(stdout, "sk-1\n");
+    }
+}
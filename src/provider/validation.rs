@@ -0,0 +1,501 @@
+//! providers.json 语义检查
+//!
+//! [`ProvidersConfig::validate`](super::config::ProvidersConfig::validate) 只拦截会让加载失败的错误，
+//! 且遇到第一个就返回。这里直接在 JSON 上检查，一次列出全部问题：未知的
+//! `compatible_with` 取值、重复的 Provider 名称、缺失的必填字段、默认 Provider 的兼容性，
+//! 写错的密钥引用，以及可疑但合法的配置（如不兼容任何 CLI 的 Provider）。
+
+use super::config::{AiType, ProvidersConfig};
+use super::secrets;
+use serde::de::{Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+
+/// `compatible_with` / `default_providers` 中允许的 CLI 类型
+pub const CLI_AI_TYPES: [AiType; 4] = [AiType::Claude, AiType::Codex, AiType::Gemini, AiType::Grok];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// 配置无法加载或默认值无效
+    Error,
+    /// 可以加载，但行为可能与预期不符
+    Warning,
+}
+
+/// 单条检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    /// 所属 Provider，文件级问题为 None
+    pub provider: Option<String>,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(provider: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            provider: provider.map(str::to_string),
+            message: message.into(),
+        }
+    }
+
+    fn warning(provider: Option<&str>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            provider: provider.map(str::to_string),
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.provider {
+            Some(name) => write!(f, "provider '{}': {}", name, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// 检查 providers.json 原文（含重复键检测，解析成 `Value` 后重复键会被静默覆盖）
+pub fn check_providers_json(content: &str) -> Vec<ConfigIssue> {
+    let root: Value = match serde_json::from_str(content) {
+        Ok(root) => root,
+        Err(err) => return vec![ConfigIssue::error(None, format!("invalid JSON: {}", err))],
+    };
+
+    let mut issues = Vec::new();
+    if let Ok(ProviderKeys {
+        providers: Some(names),
+    }) = serde_json::from_str::<ProviderKeys>(content)
+    {
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for name in &names.0 {
+            match seen.get(&name.to_lowercase()) {
+                Some(first) if *first == name.as_str() => issues.push(ConfigIssue::error(
+                    Some(name),
+                    "defined more than once; only the last definition is used",
+                )),
+                Some(first) => issues.push(ConfigIssue::warning(
+                    Some(name),
+                    format!("name differs from '{}' only by case", first),
+                )),
+                None => {
+                    seen.insert(name.to_lowercase(), name);
+                }
+            }
+        }
+    }
+
+    issues.extend(check_value(&root));
+    issues
+}
+
+/// 检查已加载的配置；只会得到警告或默认值相关的问题
+pub fn check_config(config: &ProvidersConfig) -> Vec<ConfigIssue> {
+    match serde_json::to_value(config) {
+        Ok(root) => check_value(&root),
+        Err(err) => vec![ConfigIssue::error(None, err.to_string())],
+    }
+}
+
+fn check_value(root: &Value) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let Some(root) = root.as_object() else {
+        issues.push(ConfigIssue::error(None, "top level must be a JSON object"));
+        return issues;
+    };
+
+    let providers = match root.get("providers") {
+        None => {
+            issues.push(ConfigIssue::error(
+                None,
+                "missing required field 'providers'",
+            ));
+            None
+        }
+        Some(Value::Object(providers)) if providers.is_empty() => {
+            issues.push(ConfigIssue::error(None, "no providers are configured"));
+            None
+        }
+        Some(Value::Object(providers)) => Some(providers),
+        Some(_) => {
+            issues.push(ConfigIssue::error(None, "'providers' must be an object"));
+            None
+        }
+    };
+
+    if let Some(providers) = providers {
+        let mut names: Vec<&String> = providers.keys().collect();
+        names.sort();
+        for name in names {
+            check_provider(name, &providers[name], &mut issues);
+        }
+    }
+
+    let empty = Map::new();
+    let providers = providers.unwrap_or(&empty);
+    match root.get("default_provider") {
+        None => issues.push(ConfigIssue::error(
+            None,
+            "missing required field 'default_provider'",
+        )),
+        Some(Value::String(name)) => check_global_default(root, providers, name, &mut issues),
+        Some(_) => issues.push(ConfigIssue::error(
+            None,
+            "'default_provider' must be a string",
+        )),
+    }
+    check_type_defaults(root, providers, &mut issues);
+
+    issues
+}
+
+fn check_provider(name: &str, provider: &Value, issues: &mut Vec<ConfigIssue>) {
+    let Some(provider) = provider.as_object() else {
+        issues.push(ConfigIssue::error(Some(name), "must be an object"));
+        return;
+    };
+
+    if provider
+        .get("enabled")
+        .is_some_and(|enabled| !enabled.is_boolean())
+    {
+        issues.push(ConfigIssue::error(
+            Some(name),
+            "'enabled' must be true or false",
+        ));
+    }
+
+    match provider.get("compatible_with") {
+        None | Some(Value::Null) => {}
+        Some(Value::Array(items)) if items.is_empty() => issues.push(ConfigIssue::warning(
+            Some(name),
+            "compatible_with is empty, so it is never used; remove it to allow all CLIs",
+        )),
+        Some(Value::Array(items)) => {
+            for item in items {
+                if parse_cli_type(item).is_none() {
+                    issues.push(ConfigIssue::error(
+                        Some(name),
+                        format!(
+                            "unknown compatible_with entry {} (expected claude, codex, gemini or grok)",
+                            item
+                        ),
+                    ));
+                }
+            }
+        }
+        Some(_) => issues.push(ConfigIssue::error(
+            Some(name),
+            "compatible_with must be a list such as [\"claude\", \"codex\"]",
+        )),
+    }
+
+    let mut env_vars = 0;
+    for field in ["env", "env_overrides"] {
+        match provider.get(field) {
+            None => {}
+            Some(Value::Object(vars)) => {
+                env_vars += vars.len();
+                for (key, value) in vars {
+                    match value.as_str().map(secrets::parse_reference) {
+                        None => issues.push(ConfigIssue::error(
+                            Some(name),
+                            format!("{}.{} must be a string", field, key),
+                        )),
+                        Some(Err(reason)) => issues.push(ConfigIssue::error(
+                            Some(name),
+                            format!("{}.{}: {}", field, key, reason),
+                        )),
+                        Some(Ok(_)) => {
+                            if let Some(source) = value.as_str().and_then(secrets::unknown_source) {
+                                issues.push(ConfigIssue::warning(
+                                    Some(name),
+                                    format!(
+                                        "{}.{}: unknown secret source '{}' (expected env, file or keychain); the value is used as written",
+                                        field, key, source
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+                }
+            }
+            Some(_) => issues.push(ConfigIssue::error(
+                Some(name),
+                format!("'{}' must be an object", field),
+            )),
+        }
+    }
+    if env_vars == 0 && name != "official" {
+        issues.push(ConfigIssue::warning(
+            Some(name),
+            "has no env vars, so CLIs launch exactly as with 'official'",
+        ));
+    }
+
+    if provider
+        .get("scenario")
+        .and_then(Value::as_str)
+        .is_some_and(|scenario| scenario.trim().is_empty())
+    {
+        issues.push(ConfigIssue::warning(
+            Some(name),
+            "scenario is blank and will never match a task",
+        ));
+    }
+}
+
+fn check_global_default(
+    root: &Map<String, Value>,
+    providers: &Map<String, Value>,
+    name: &str,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    let Some(provider) = providers.get(name) else {
+        if !providers.is_empty() {
+            issues.push(ConfigIssue::error(
+                None,
+                format!("default provider '{}' does not exist", name),
+            ));
+        }
+        return;
+    };
+
+    if provider.get("enabled").and_then(Value::as_bool) == Some(false) {
+        issues.push(ConfigIssue::warning(
+            Some(name),
+            "is the default provider but disabled; CLIs fall back to other providers",
+        ));
+    }
+
+    // 没有单独默认值的 CLI 类型都会用到全局默认 Provider
+    let type_defaults = root.get("default_providers").and_then(Value::as_object);
+    let Some(compatible) = compatible_types(provider) else {
+        return;
+    };
+    for ai_type in CLI_AI_TYPES {
+        let has_type_default =
+            type_defaults.is_some_and(|defaults| defaults.contains_key(&ai_type.to_string()));
+        if !has_type_default && !compatible.contains(&ai_type) {
+            issues.push(ConfigIssue::warning(
+                Some(name),
+                format!(
+                    "is the default provider but not compatible with {0}; {0} tasks use another \
+                     compatible provider (set default_providers.{0} to choose one)",
+                    ai_type
+                ),
+            ));
+        }
+    }
+}
+
+fn check_type_defaults(
+    root: &Map<String, Value>,
+    providers: &Map<String, Value>,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    let Some(defaults) = root.get("default_providers") else {
+        return;
+    };
+    let Some(defaults) = defaults.as_object() else {
+        issues.push(ConfigIssue::error(
+            None,
+            "'default_providers' must be an object",
+        ));
+        return;
+    };
+
+    let mut keys: Vec<&String> = defaults.keys().collect();
+    keys.sort();
+    for key in keys {
+        let Some(ai_type) = parse_cli_type(&Value::String(key.clone())) else {
+            issues.push(ConfigIssue::error(
+                None,
+                format!(
+                    "default_providers has unknown CLI type '{}' (expected claude, codex, gemini or grok)",
+                    key
+                ),
+            ));
+            continue;
+        };
+        let Some(name) = defaults[key].as_str() else {
+            issues.push(ConfigIssue::error(
+                None,
+                format!("default_providers.{} must be a provider name", key),
+            ));
+            continue;
+        };
+        match providers.get(name) {
+            None => issues.push(ConfigIssue::error(
+                None,
+                format!("default provider '{}' for {} does not exist", name, ai_type),
+            )),
+            Some(provider)
+                if compatible_types(provider).is_some_and(|types| !types.contains(&ai_type)) =>
+            {
+                issues.push(ConfigIssue::error(
+                    Some(name),
+                    format!("is the default for {} but not compatible with it", ai_type),
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+fn parse_cli_type(value: &Value) -> Option<AiType> {
+    value
+        .as_str()?
+        .parse::<AiType>()
+        .ok()
+        .filter(|ai_type| CLI_AI_TYPES.contains(ai_type))
+}
+
+/// `compatible_with` 中可识别的 CLI 类型；未设置时为 None（兼容全部）
+fn compatible_types(provider: &Value) -> Option<Vec<AiType>> {
+    let items = provider.get("compatible_with")?.as_array()?;
+    Some(items.iter().filter_map(parse_cli_type).collect())
+}
+
+/// 按出现顺序收集 `providers` 的键（包括重复键）
+struct ProviderNames(Vec<String>);
+
+impl<'de> Deserialize<'de> for ProviderNames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NamesVisitor;
+
+        impl<'de> Visitor<'de> for NamesVisitor {
+            type Value = ProviderNames;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an object of providers")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut names = Vec::new();
+                while let Some((name, IgnoredAny)) = map.next_entry::<String, IgnoredAny>()? {
+                    names.push(name);
+                }
+                Ok(ProviderNames(names))
+            }
+        }
+
+        deserializer.deserialize_map(NamesVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+struct ProviderKeys {
+    #[serde(default)]
+    providers: Option<ProviderNames>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(issues: &[ConfigIssue]) -> Vec<String> {
+        issues.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn default_config_has_no_issues() {
+        assert!(check_config(&ProvidersConfig::create_default()).is_empty());
+    }
+
+    #[test]
+    fn reports_unknown_types_duplicates_and_missing_fields() {
+        let content = r#"{
+            "providers": {
+                "kimi": {"compatible_with": ["claude", "cursor"], "env": {"ANTHROPIC_API_KEY": "k"}},
+                "kimi": {"env": {"ANTHROPIC_API_KEY": "k"}},
+                "Kimi": {"compatible_with": [], "env": {"ANTHROPIC_API_KEY": "k"}},
+                "bare": {}
+            }
+        }"#;
+        let issues = check_providers_json(content);
+        let text = messages(&issues).join("\n");
+
+        assert!(
+            text.contains("provider 'kimi': defined more than once"),
+            "{text}"
+        );
+        assert!(text.contains("provider 'Kimi': name differs from 'kimi' only by case"));
+        assert!(text.contains("provider 'Kimi': compatible_with is empty"));
+        assert!(text.contains("provider 'bare': has no env vars"));
+        assert!(text.contains("missing required field 'default_provider'"));
+        // 重复键只保留最后一个定义，所以 "cursor" 不会被检查到
+        assert!(!text.contains("cursor"));
+        assert!(issues.iter().any(ConfigIssue::is_error));
+    }
+
+    #[test]
+    fn checks_default_provider_compatibility() {
+        let content = r#"{
+            "providers": {
+                "official": {},
+                "kimi": {"compatible_with": ["claude", "auto"], "env": {"ANTHROPIC_API_KEY": "k"}}
+            },
+            "default_provider": "kimi",
+            "default_providers": {"codex": "kimi", "gemini": "official", "cursor": "official"}
+        }"#;
+        let issues = check_providers_json(content);
+        let text = messages(&issues).join("\n");
+
+        assert!(
+            text.contains("unknown compatible_with entry \"auto\""),
+            "{text}"
+        );
+        assert!(text.contains("provider 'kimi': is the default for codex but not compatible"));
+        assert!(text.contains("unknown CLI type 'cursor'"));
+        assert!(text.contains("not compatible with grok"));
+        // codex / gemini 有单独的默认值，不报全局默认的兼容性
+        assert!(!text.contains("not compatible with gemini"));
+        assert!(!text.contains("does not exist"));
+    }
+
+    #[test]
+    fn reports_malformed_secret_references() {
+        let content = r#"{
+            "providers": {
+                "kimi": {
+                    "env": {"ANTHROPIC_API_KEY": "${vault:kimi}", "ANTHROPIC_BASE_URL": "${HOME}"},
+                    "env_overrides": {"OPENAI_API_KEY": "${keychain:}", "OPENAI_ORG": "${env:ORG}"}
+                }
+            },
+            "default_provider": "kimi"
+        }"#;
+        let issues = check_providers_json(content);
+        let text = messages(&issues).join("\n");
+
+        // 来源未知的值仍按字面量注入，只提示可能的拼写错误
+        let vault = issues
+            .iter()
+            .find(|issue| issue.message.contains("unknown secret source 'vault'"))
+            .unwrap_or_else(|| panic!("{text}"));
+        assert_eq!(vault.severity, Severity::Warning);
+        assert!(vault.message.starts_with("env.ANTHROPIC_API_KEY:"));
+        assert!(
+            text.contains("env_overrides.OPENAI_API_KEY: '${keychain:}' does not name a secret")
+        );
+        assert!(!text.contains("ANTHROPIC_BASE_URL"));
+        assert!(!text.contains("OPENAI_ORG"));
+    }
+
+    #[test]
+    fn invalid_json_is_a_single_error() {
+        let issues = check_providers_json("{ not json");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].is_error());
+        assert!(issues[0].message.starts_with("invalid JSON"));
+    }
+}
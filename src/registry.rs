@@ -4,9 +4,93 @@
 //! The main TaskRegistry implementation has been moved to unified_registry to avoid duplication.
 
 use crate::error::RegistryError;
-use crate::task_record::TaskRecord;
+use crate::task_record::{TaskRecord, TaskStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json;
 use shared_hashmap::SharedMemoryHashMap;
+use std::collections::BTreeMap;
+
+/// 注册表聚合统计（只读，不修改任何条目）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RegistryStats {
+    /// Total number of entries.
+    pub total: usize,
+    /// Entries still running.
+    pub running: usize,
+    /// Entries that finished successfully.
+    pub completed: usize,
+    /// Entries that finished with a non-zero exit code or were reaped.
+    pub failed: usize,
+    /// Launches waiting for a free concurrency slot (not yet in the registry).
+    #[serde(default)]
+    pub queued: usize,
+    /// Entry count per AI CLI type ("unknown" when not recorded).
+    pub by_ai_type: BTreeMap<String, usize>,
+    /// Total size of log files still on disk.
+    pub log_bytes: u64,
+    /// Age in seconds of the oldest running task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_running_secs: Option<i64>,
+    /// Entries launched inside a git worktree.
+    pub with_worktree: usize,
+}
+
+impl RegistryStats {
+    /// 从任务记录聚合统计
+    pub fn collect<'a, I>(records: I, now: DateTime<Utc>) -> Self
+    where
+        I: IntoIterator<Item = &'a TaskRecord>,
+    {
+        let mut stats = Self::default();
+
+        for record in records {
+            stats.total += 1;
+            match record.status {
+                TaskStatus::Running => {
+                    stats.running += 1;
+                    let age = now.signed_duration_since(record.started_at).num_seconds();
+                    stats.oldest_running_secs = Some(
+                        stats
+                            .oldest_running_secs
+                            .map_or(age, |oldest| oldest.max(age)),
+                    );
+                }
+                TaskStatus::CompletedButUnread => {
+                    if is_failed(record) {
+                        stats.failed += 1;
+                    } else {
+                        stats.completed += 1;
+                    }
+                }
+            }
+
+            let ai_type = record.ai_type.as_deref().unwrap_or("unknown");
+            *stats.by_ai_type.entry(ai_type.to_string()).or_default() += 1;
+
+            if record.worktree_info.is_some() {
+                stats.with_worktree += 1;
+            }
+
+            if !record.log_path.is_empty() {
+                if let Ok(metadata) = std::fs::metadata(&record.log_path) {
+                    stats.log_bytes += metadata.len();
+                }
+            }
+        }
+
+        stats
+    }
+}
+
+fn is_failed(record: &TaskRecord) -> bool {
+    let bad_exit = record.exit_code.is_some_and(|code| code != 0);
+    let reaped = matches!(
+        record.cleanup_reason.as_deref(),
+        Some("timeout") | Some("manager_missing")
+    );
+    bad_exit || reaped
+}
 
 /// Get current process PID
 #[allow(dead_code)]
@@ -38,6 +122,48 @@ mod tests {
     use crate::config::SHARED_MEMORY_SIZE;
     use crate::core::shared_map::open_or_create;
 
+    #[test]
+    fn test_registry_stats_collect() {
+        let now = chrono::Utc::now();
+        let mut running = TaskRecord::new(
+            now - chrono::Duration::seconds(90),
+            "1".to_string(),
+            String::new(),
+            None,
+        );
+        running.ai_type = Some("claude".to_string());
+
+        let mut ok = TaskRecord::new(now, "2".to_string(), String::new(), None).mark_completed(
+            None,
+            Some(0),
+            now,
+        );
+        ok.ai_type = Some("codex".to_string());
+        ok.worktree_info = Some(crate::task_record::WorktreeInfo {
+            path: "/tmp/wt".to_string(),
+            branch: "main".to_string(),
+            commit: "abc".to_string(),
+        });
+
+        let failed = TaskRecord::new(now, "3".to_string(), String::new(), None).mark_completed(
+            None,
+            Some(1),
+            now,
+        );
+
+        let records = [running, ok, failed];
+        let stats = RegistryStats::collect(records.iter(), now);
+
+        assert_eq!(stats.total, 3);
+        assert_eq!(stats.running, 1);
+        assert_eq!(stats.completed, 1);
+        assert_eq!(stats.failed, 1);
+        assert_eq!(stats.with_worktree, 1);
+        assert_eq!(stats.oldest_running_secs, Some(90));
+        assert_eq!(stats.by_ai_type.get("claude"), Some(&1));
+        assert_eq!(stats.by_ai_type.get("unknown"), Some(&1));
+    }
+
     #[test]
     fn test_get_current_process_pid() {
         let pid = get_current_process_pid();
@@ -4,9 +4,12 @@
 
 use crate::{
     error::RegistryError,
+    logging::warn,
+    platform,
     storage::{InProcessStorage, SharedMemoryStorage},
     unified_registry::Registry,
 };
+use chrono::Utc;
 use once_cell::sync::OnceCell;
 use parking_lot::Mutex;
 use std::sync::Arc;
@@ -54,11 +57,19 @@ impl RegistryFactory {
     }
 }
 
+/// 连接后先做一次对账，避免把崩溃进程留下的条目当成仍在运行
+fn reconciled(registry: CliRegistry) -> CliRegistry {
+    if let Err(err) = registry.reconcile_tasks(Utc::now(), platform::process_alive) {
+        warn(format!("task registry reconciliation failed: {err}"));
+    }
+    registry
+}
+
 /// 创建CLI任务注册表（跨进程共享）
 ///
 /// 连接到当前进程的共享内存命名空间
 pub fn create_cli_registry() -> Result<CliRegistry, RegistryError> {
-    Ok(Registry::new(SharedMemoryStorage::connect()?))
+    Ok(reconciled(Registry::new(SharedMemoryStorage::connect()?)))
 }
 
 /// 创建MCP任务注册表（进程内独享）
@@ -70,14 +81,16 @@ pub fn create_mcp_registry() -> McpRegistry {
 
 /// 为指定PID创建CLI任务注册表
 pub fn create_cli_registry_for_pid(pid: u32) -> Result<CliRegistry, RegistryError> {
-    Ok(Registry::new(SharedMemoryStorage::connect_for_pid(pid)?))
+    Ok(reconciled(Registry::new(
+        SharedMemoryStorage::connect_for_pid(pid)?,
+    )))
 }
 
 /// 使用自定义命名空间创建CLI任务注册表
 pub fn create_cli_registry_with_namespace(namespace: String) -> Result<CliRegistry, RegistryError> {
-    Ok(Registry::new(SharedMemoryStorage::connect_with_namespace(
-        namespace,
-    )?))
+    Ok(reconciled(Registry::new(
+        SharedMemoryStorage::connect_with_namespace(namespace)?,
+    )))
 }
 
 #[cfg(test)]
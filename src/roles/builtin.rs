@@ -77,42 +77,75 @@ pub fn get_builtin_roles(names: &[&str], lang: &str) -> (Vec<Role>, Vec<String>)
     (valid_roles, invalid_names)
 }
 
+/// Builtin role tables by language; `en` is the final fallback of every chain
+const BUILTIN_LANGUAGES: &[(&str, &[(&str, &str)])] = &[
+    ("zh-CN", BUILTIN_ROLES_ZH_CN),
+    ("en", BUILTIN_ROLES_EN),
+];
+
+const FALLBACK_LANGUAGE: &str = "en";
+
 /// Get a builtin role by name and language
 ///
 /// # Arguments
 /// * `name` - Role name (e.g., "common", "debugger")
-/// * `lang` - Language code: "en" for English, "zh-CN" for Chinese (default)
+/// * `lang` - Language code, e.g. "en", "zh-CN", "zh" or "zh_TW"
 ///
 /// # Returns
-/// * `Ok(Role)` - Role with embedded content
+/// * `Ok(Role)` - Role with embedded content; `Role::language` is the language actually used
 /// * `Err(RoleError)` - If role name not found
 ///
 /// # Language Fallback
-/// If the requested role is not available in the requested language,
-/// this function will fallback to Chinese (zh-CN) version.
+/// Tried in order (see [`language_chain`]): the requested language, any bundled
+/// language with the same base (e.g. "zh-TW" → "zh-CN"), then English. If the
+/// role exists in none of those, any other bundled translation is used.
 pub fn get_builtin_role(name: &str, lang: &str) -> Result<Role, RoleError> {
-    // Select role array based on language
-    let roles = if lang == "en" {
-        BUILTIN_ROLES_EN
-    } else {
-        BUILTIN_ROLES_ZH_CN
-    };
-
-    // Try to find role in requested language
-    if let Some((_, content)) = roles.iter().find(|(role_name, _)| *role_name == name) {
-        return Ok(parse_role_content(name, content, lang));
-    }
-
-    // Fallback to Chinese if not found in English
-    if lang == "en" {
-        if let Some((_, content)) = BUILTIN_ROLES_ZH_CN.iter().find(|(role_name, _)| *role_name == name) {
-            return Ok(parse_role_content(name, content, "zh-CN"));
+    for candidate in language_chain(lang) {
+        let Some((_, roles)) = BUILTIN_LANGUAGES
+            .iter()
+            .find(|(table_lang, _)| *table_lang == candidate)
+        else {
+            continue;
+        };
+        if let Some((_, content)) = roles.iter().find(|(role_name, _)| *role_name == name) {
+            return Ok(parse_role_content(name, content, candidate));
         }
     }
 
     Err(RoleError::NotFound(name.to_string()))
 }
 
+/// Bundled languages to try for `lang`, most preferred first.
+///
+/// Matching ignores case and treats `_` like `-`, so "zh_cn" selects "zh-CN".
+pub fn language_chain(lang: &str) -> Vec<&'static str> {
+    let requested = lang.trim().replace('_', "-").to_ascii_lowercase();
+    let base = requested.split('-').next().unwrap_or_default().to_string();
+
+    let mut chain: Vec<&'static str> = Vec::new();
+    let mut push = |candidate: &'static str| {
+        if !chain.contains(&candidate) {
+            chain.push(candidate);
+        }
+    };
+    let bundled = || BUILTIN_LANGUAGES.iter().map(|(table_lang, _)| *table_lang);
+
+    bundled()
+        .filter(|table_lang| table_lang.to_ascii_lowercase() == requested)
+        .for_each(&mut push);
+    bundled()
+        .filter(|table_lang| {
+            table_lang
+                .split('-')
+                .next()
+                .is_some_and(|table_base| table_base.eq_ignore_ascii_case(&base))
+        })
+        .for_each(&mut push);
+    push(FALLBACK_LANGUAGE);
+    bundled().for_each(&mut push);
+    chain
+}
+
 /// Parse role content and create Role struct
 fn parse_role_content(name: &str, content: &str, lang: &str) -> Role {
     // Extract description (first line, remove # prefix)
@@ -129,6 +162,7 @@ fn parse_role_content(name: &str, content: &str, lang: &str) -> Role {
         description,
         content: content.to_string(),
         file_path: format!("builtin:{}:{}", lang, name).into(),
+        language: Some(lang.to_string()),
     }
 }
 
@@ -219,6 +253,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_language_chain() {
+        assert_eq!(language_chain("zh-CN"), vec!["zh-CN", "en"]);
+        assert_eq!(language_chain("zh_tw"), vec!["zh-CN", "en"]);
+        assert_eq!(language_chain("zh"), vec!["zh-CN", "en"]);
+        assert_eq!(language_chain("en-US"), vec!["en", "zh-CN"]);
+        assert_eq!(language_chain("fr"), vec!["en", "zh-CN"]);
+    }
+
+    #[test]
+    fn test_role_reports_resolved_language() {
+        let role = get_builtin_role("common", "zh-TW").unwrap();
+        assert_eq!(role.language.as_deref(), Some("zh-CN"));
+        let role = get_builtin_role("common", "de").unwrap();
+        assert_eq!(role.language.as_deref(), Some("en"));
+        assert_eq!(role.as_info().language.as_deref(), Some("en"));
+    }
+
     #[test]
     fn test_get_builtin_roles_all_valid() {
         let (valid_roles, invalid_names) = get_builtin_roles(&["common", "security", "debugger"], "en");
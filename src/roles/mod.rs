@@ -12,7 +12,10 @@
 //! - Content: full role prompt used by downstream tools.
 
 pub mod builtin;
+pub mod overrides;
 
+use crate::config::LANG_ENV;
+use crate::utils::config_paths::aiw_config_dir;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::fs;
@@ -32,6 +35,8 @@ pub struct Role {
     pub description: String,
     pub content: String,
     pub file_path: PathBuf,
+    /// 内置角色实际使用的语言（经过回退后）；用户角色为 `None`
+    pub language: Option<String>,
 }
 
 /// Lightweight role info returned by listing APIs.
@@ -40,6 +45,9 @@ pub struct RoleInfo {
     pub name: String,
     pub description: String,
     pub file_path: String,
+    /// Language of the builtin role content, after fallback; absent for user roles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
 impl Role {
@@ -48,6 +56,7 @@ impl Role {
             name: self.name.clone(),
             description: self.description.clone(),
             file_path: self.file_path.display().to_string(),
+            language: self.language.clone(),
         }
     }
 }
@@ -79,6 +88,36 @@ pub enum RoleError {
 
 pub type RoleResult<T> = Result<T, RoleError>;
 
+/// Detect the preferred builtin role language.
+///
+/// `AIW_LANG` wins when set (e.g. `en`, `zh-CN`, `zh`); otherwise Chinese system
+/// locales map to `zh-CN` and everything else uses `en`.
+pub fn detect_language() -> String {
+    if let Ok(lang) = std::env::var(LANG_ENV) {
+        let lang = lang.trim();
+        if !lang.is_empty() {
+            return lang.to_string();
+        }
+    }
+    if let Some(locale) = sys_locale::get_locale() {
+        if locale.starts_with("zh") {
+            return "zh-CN".to_string();
+        }
+    }
+    "en".to_string()
+}
+
+/// Resolve a role by name: user roles under `~/.aiw/role/` take precedence,
+/// then the builtin role in `lang` (following [`builtin::language_chain`]).
+pub fn load_role(name: &str, lang: &str) -> RoleResult<Role> {
+    if let Ok(manager) = RoleManager::new() {
+        if let Ok(role) = manager.get_role(name) {
+            return Ok(role);
+        }
+    }
+    builtin::get_builtin_role(name, lang)
+}
+
 /// Role manager responsible for loading role definitions from disk.
 #[derive(Debug, Clone)]
 pub struct RoleManager {
@@ -99,9 +138,9 @@ impl RoleManager {
         }
 
         // Fall back to default directory
-        let home_dir = dirs::home_dir().ok_or(RoleError::HomeDirectoryUnavailable)?;
+        let config_dir = aiw_config_dir().ok_or(RoleError::HomeDirectoryUnavailable)?;
         Ok(Self {
-            base_dir: home_dir.join(".aiw").join("role"),
+            base_dir: config_dir.join("role"),
         })
     }
 
@@ -150,9 +189,7 @@ impl RoleManager {
     /// Retrieve a single role by name (without extension).
     pub fn get_role(&self, name: &str) -> RoleResult<Role> {
         let normalized_name = self.normalize_and_validate_name(name)?;
-        let candidate_path = self
-            .base_dir
-            .join(format!("{normalized_name}.{ROLE_FILE_EXTENSION}"));
+        let candidate_path = self.role_path(&normalized_name);
 
         if !candidate_path.exists() {
             return Err(RoleError::NotFound(normalized_name.to_string()));
@@ -162,6 +199,11 @@ impl RoleManager {
         self.parse_role_file(&candidate_path, &base_dir)
     }
 
+    /// Path of the file a role named `name` is read from.
+    pub fn role_path(&self, name: &str) -> PathBuf {
+        self.base_dir.join(format!("{name}.{ROLE_FILE_EXTENSION}"))
+    }
+
     /// Retrieve multiple roles by names.
     ///
     /// Returns a tuple of (valid_roles, invalid_role_names).
@@ -237,6 +279,7 @@ impl RoleManager {
             description,
             content,
             file_path: canonical_path,
+            language: None,
         })
     }
 
@@ -311,6 +354,7 @@ impl RoleManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serial_test::serial;
     use tempfile::TempDir;
 
     fn create_test_role_file(dir: &Path, name: &str, description: &str, content: &str) {
@@ -400,4 +444,18 @@ mod tests {
         assert!(invalid_names.is_empty());
         assert_eq!(valid_roles[0].name, "single");
     }
+
+    #[test]
+    #[serial]
+    fn test_aiw_lang_overrides_locale() {
+        std::env::set_var(LANG_ENV, " zh ");
+        assert_eq!(detect_language(), "zh");
+        let role = builtin::get_builtin_role("common", &detect_language()).unwrap();
+        assert_eq!(role.language.as_deref(), Some("zh-CN"));
+
+        std::env::set_var(LANG_ENV, "");
+        let detected = detect_language();
+        assert!(detected == "en" || detected == "zh-CN");
+        std::env::remove_var(LANG_ENV);
+    }
 }
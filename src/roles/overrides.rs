@@ -0,0 +1,223 @@
+//! Builtin roles shadowed by user roles
+//!
+//! A user role in `~/.aiw/role/` with the same name as a builtin role wins in
+//! [`load_role`](super::load_role). This module reports which builtins are
+//! shadowed that way and renders a line diff between the two versions.
+
+use super::{builtin, Role, RoleError, RoleManager};
+use std::path::PathBuf;
+
+/// How a builtin role name resolves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideState {
+    /// No user file; the builtin role is used.
+    Builtin,
+    /// A user role replaces the builtin one.
+    Overridden(Role),
+    /// A user file exists but cannot be parsed, so the builtin role is still used.
+    Invalid { path: PathBuf, error: String },
+}
+
+/// One builtin role name and what shadows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuiltinOverride {
+    pub name: String,
+    pub state: OverrideState,
+}
+
+/// Every builtin role name, in listing order, with its override state.
+pub fn builtin_overrides(manager: &RoleManager) -> Vec<BuiltinOverride> {
+    builtin::list_builtin_roles()
+        .into_iter()
+        .map(|name| {
+            let state = match manager.get_role(&name) {
+                Ok(role) => OverrideState::Overridden(role),
+                Err(RoleError::NotFound(_)) => OverrideState::Builtin,
+                Err(err) => OverrideState::Invalid {
+                    path: manager.role_path(&name),
+                    error: err.to_string(),
+                },
+            };
+            BuiltinOverride { name, state }
+        })
+        .collect()
+}
+
+/// Unified diff from `old` to `new` with `context` unchanged lines around each
+/// change. Returns an empty string when the texts have the same lines.
+pub fn unified_diff(
+    old_label: &str,
+    new_label: &str,
+    old: &str,
+    new: &str,
+    context: usize,
+) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_ops(&old_lines, &new_lines);
+    if ops.iter().all(|op| matches!(op, Op::Same(..))) {
+        return String::new();
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for hunk in hunks(&ops, context) {
+        let slice = &ops[hunk.clone()];
+        let (old_start, new_start) = match slice[0] {
+            Op::Same(o, n) => (o, n),
+            Op::Removed(o) => (o, next_new_index(&ops[..hunk.start])),
+            Op::Added(n) => (next_old_index(&ops[..hunk.start]), n),
+        };
+        let old_count = slice
+            .iter()
+            .filter(|op| !matches!(op, Op::Added(_)))
+            .count();
+        let new_count = slice
+            .iter()
+            .filter(|op| !matches!(op, Op::Removed(_)))
+            .count();
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start + 1,
+            old_count,
+            new_start + 1,
+            new_count
+        ));
+        for op in slice {
+            let line = match *op {
+                Op::Same(o, _) => format!(" {}", old_lines[o]),
+                Op::Removed(o) => format!("-{}", old_lines[o]),
+                Op::Added(n) => format!("+{}", new_lines[n]),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Edit script entry, holding line indices into the old and/or new text
+#[derive(Debug, Clone, Copy)]
+enum Op {
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Longest-common-subsequence line diff
+fn diff_ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let width = new.len() + 1;
+    // lcs[i * width + j] = LCS length of old[i..] and new[j..]
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push(Op::Same(i, j));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            ops.push(Op::Removed(i));
+            i += 1;
+        } else {
+            ops.push(Op::Added(j));
+            j += 1;
+        }
+    }
+    ops.extend((i..old.len()).map(Op::Removed));
+    ops.extend((j..new.len()).map(Op::Added));
+    ops
+}
+
+/// Ranges of `ops` to print: each change plus `context` lines around it,
+/// merging ranges that touch
+fn hunks(ops: &[Op], context: usize) -> Vec<std::ops::Range<usize>> {
+    let mut ranges: Vec<std::ops::Range<usize>> = Vec::new();
+    for (idx, op) in ops.iter().enumerate() {
+        if matches!(op, Op::Same(..)) {
+            continue;
+        }
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(ops.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+    ranges
+}
+
+fn next_old_index(before: &[Op]) -> usize {
+    before
+        .iter()
+        .filter(|op| !matches!(op, Op::Added(_)))
+        .count()
+}
+
+fn next_new_index(before: &[Op]) -> usize {
+    before
+        .iter()
+        .filter(|op| !matches!(op, Op::Removed(_)))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn overrides_report_shadowed_and_invalid_user_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("security.md"),
+            "My security rules\n------------\nNever push to main",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("debugger.md"), "no delimiter").unwrap();
+        std::fs::write(dir.path().join("my-own.md"), "Mine\n------------\nbody").unwrap();
+
+        let manager = RoleManager::with_base_dir(dir.path()).unwrap();
+        let overrides = builtin_overrides(&manager);
+
+        assert_eq!(overrides.len(), builtin::list_builtin_roles().len());
+        let state = |name: &str| {
+            overrides
+                .iter()
+                .find(|entry| entry.name == name)
+                .map(|entry| entry.state.clone())
+                .unwrap()
+        };
+        match state("security") {
+            OverrideState::Overridden(role) => assert_eq!(role.description, "My security rules"),
+            other => panic!("expected security to be overridden, got {other:?}"),
+        }
+        assert!(matches!(state("debugger"), OverrideState::Invalid { .. }));
+        assert_eq!(state("common"), OverrideState::Builtin);
+        assert!(!overrides.iter().any(|entry| entry.name == "my-own"));
+    }
+
+    #[test]
+    fn unified_diff_shows_changes_with_context() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let new = "a\nb\nc\nD\ne\nf\ng\nh\ni\n";
+
+        let diff = unified_diff("builtin", "user", old, new, 1);
+        assert_eq!(
+            diff,
+            "--- builtin\n+++ user\n\
+             @@ -3,3 +3,3 @@\n c\n-d\n+D\n e\n\
+             @@ -8,1 +8,2 @@\n h\n+i\n"
+        );
+        assert_eq!(unified_diff("builtin", "user", old, old, 3), "");
+    }
+}
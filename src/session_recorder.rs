@@ -0,0 +1,395 @@
+//! 交互模式会话录制（`--record`）
+//!
+//! 在 PTY 中启动 AI CLI，子进程仍然看到真实终端；父进程把终端原始字节
+//! 双向转发，并将输出原样（含 ANSI 控制序列）写入 `~/.aiw/sessions/` 下的日志。
+//! 录制中的会话以 [`SessionRecord`] 形式登记到注册表，和后台任务一起出现在 status 中。
+
+use crate::cli_type::CliType;
+use crate::platform;
+use crate::storage::TaskStorage;
+use crate::supervisor::ProcessError;
+use crate::task_record::{SessionRecord, TaskRecord};
+use crate::unified_registry::Registry;
+use crate::utils::config_paths::aiw_config_dir;
+use chrono::{DateTime, Utc};
+use portable_pty::{native_pty_system, Child, CommandBuilder, PtySize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const SESSIONS_DIR: &str = "sessions";
+const DEFAULT_TERMINAL_SIZE: (u16, u16) = (80, 24);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_LOG_NAME_ATTEMPTS: u32 = 1000;
+
+/// 会话日志目录：`~/.aiw/sessions/`
+pub fn sessions_dir() -> io::Result<PathBuf> {
+    let dir = aiw_config_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Cannot find home directory"))?
+        .join(SESSIONS_DIR);
+    std::fs::create_dir_all(&dir)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+
+    Ok(dir)
+}
+
+/// 生成会话日志文件名，例如 `claude-20260101-120000-4242.log`
+///
+/// 时间戳只精确到秒，追加 aiw 的 PID；同一进程同一秒内的重名再追加序号 `attempt`。
+pub fn session_log_name(cli_type: &CliType, started_at: DateTime<Utc>, attempt: u32) -> String {
+    let stem = format!(
+        "{}-{}-{}",
+        cli_type.display_name(),
+        started_at.format("%Y%m%d-%H%M%S"),
+        platform::current_pid()
+    );
+    if attempt == 0 {
+        format!("{stem}.log")
+    } else {
+        format!("{stem}-{attempt}.log")
+    }
+}
+
+/// 在 `dir` 下新建会话日志，绝不覆盖已有日志
+fn create_session_log(
+    dir: &Path,
+    cli_type: &CliType,
+    started_at: DateTime<Utc>,
+) -> io::Result<(PathBuf, File)> {
+    for attempt in 0..MAX_LOG_NAME_ATTEMPTS {
+        let path = dir.join(session_log_name(cli_type, started_at, attempt));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("No free session log name in {}", dir.display()),
+    ))
+}
+
+/// 把已经配置好的 `std::process::Command` 转成 PTY 命令（参数、环境变量、工作目录保持一致）
+pub fn command_builder_from(command: &std::process::Command) -> CommandBuilder {
+    let mut builder = CommandBuilder::new(command.get_program());
+    builder.args(command.get_args());
+    for (key, value) in command.get_envs() {
+        match value {
+            Some(value) => builder.env(key, value),
+            None => builder.env_remove(key),
+        }
+    }
+    match command.get_current_dir() {
+        Some(dir) => builder.cwd(dir),
+        None => {
+            if let Ok(dir) = std::env::current_dir() {
+                builder.cwd(dir);
+            }
+        }
+    }
+    builder
+}
+
+/// 终端 raw 模式守卫：控制字符（Ctrl-C / Ctrl-Z / Ctrl-D 等）作为字节交给子进程，
+/// 而不是由 aiw 自己的终端处理；离开作用域时恢复终端。
+struct RawModeGuard {
+    enabled: bool,
+}
+
+impl RawModeGuard {
+    fn enable() -> Self {
+        Self {
+            enabled: crossterm::terminal::enable_raw_mode().is_ok(),
+        }
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        if self.enabled {
+            let _ = crossterm::terminal::disable_raw_mode();
+        }
+    }
+}
+
+/// 会话注册守卫：正常结束时由 [`SessionRegistration::finish`] 写入退出码；
+/// 中途出错提前返回时在 drop 中标记为失败，避免注册表里残留 Running 条目。
+struct SessionRegistration<'a, S: TaskStorage> {
+    registry: &'a Registry<S>,
+    pid: u32,
+    log_path: PathBuf,
+    active: bool,
+}
+
+impl<'a, S: TaskStorage> SessionRegistration<'a, S> {
+    fn new(registry: &'a Registry<S>, pid: u32, log_path: PathBuf) -> Self {
+        Self {
+            registry,
+            pid,
+            log_path,
+            active: true,
+        }
+    }
+
+    fn finish(mut self, exit_code: i32) -> Result<(), ProcessError> {
+        self.active = false;
+        self.registry.mark_completed(
+            self.pid,
+            Some(format!(
+                "Recorded session saved to {}",
+                self.log_path.display()
+            )),
+            Some(exit_code),
+            Utc::now(),
+        )?;
+        Ok(())
+    }
+}
+
+impl<S: TaskStorage> Drop for SessionRegistration<'_, S> {
+    fn drop(&mut self) {
+        if self.active {
+            let _ = self.registry.mark_completed(
+                self.pid,
+                Some(format!(
+                    "Recorded session failed; partial log at {}",
+                    self.log_path.display()
+                )),
+                None,
+                Utc::now(),
+            );
+        }
+    }
+}
+
+fn terminal_size() -> (u16, u16) {
+    crossterm::terminal::size().unwrap_or(DEFAULT_TERMINAL_SIZE)
+}
+
+fn pty_size((cols, rows): (u16, u16)) -> PtySize {
+    PtySize {
+        rows,
+        cols,
+        pixel_width: 0,
+        pixel_height: 0,
+    }
+}
+
+fn pty_error(context: &str, err: impl std::fmt::Display) -> ProcessError {
+    ProcessError::Other(format!("{context}: {err}"))
+}
+
+/// 启动后出错时结束并回收子进程，避免留下失去终端转发的孤儿或僵尸进程
+fn kill_and_reap(child: &mut dyn Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// 在 PTY 中运行交互式 AI CLI 并录制会话，阻塞直到子进程退出
+///
+/// `on_spawn` 在子进程启动后以其 PID 调用（例如用于 Claude 运行时补丁）。
+pub fn run_recorded<S, F>(
+    registry: &Registry<S>,
+    cli_type: &CliType,
+    command: &std::process::Command,
+    on_spawn: F,
+) -> Result<(i32, PathBuf), ProcessError>
+where
+    S: TaskStorage,
+    F: FnOnce(u32),
+{
+    let started_at = Utc::now();
+    let (log_path, mut log_file) = create_session_log(&sessions_dir()?, cli_type, started_at)?;
+
+    let mut size = terminal_size();
+    let pair = native_pty_system()
+        .openpty(pty_size(size))
+        .map_err(|e| pty_error("Failed to open PTY", e))?;
+    // 读写端在启动子进程前取好，之后的失败路径只剩需要回收子进程的几处
+    let mut reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| pty_error("Failed to read from PTY", e))?;
+    let mut writer = pair
+        .master
+        .take_writer()
+        .map_err(|e| pty_error("Failed to write to PTY", e))?;
+
+    let mut child = pair
+        .slave
+        .spawn_command(command_builder_from(command))
+        .map_err(|e| pty_error("Failed to spawn recorded session", e))?;
+    // 只保留子进程持有的 slave 端，这样子进程退出后读端才能收到 EOF
+    drop(pair.slave);
+
+    let mut registration = None;
+    if let Some(pid) = child.process_id() {
+        let mut record = TaskRecord::new(
+            started_at,
+            pid.to_string(),
+            log_path.to_string_lossy().into_owned(),
+            Some(platform::current_pid()),
+        );
+        record.ai_type = Some(cli_type.display_name().to_string());
+        record.process_start_time = platform::process_start_time(pid);
+        record.session = Some(SessionRecord {
+            command: session_command_line(command),
+            cols: size.0,
+            rows: size.1,
+        });
+        if let Err(err) = registry.register(pid, &record) {
+            kill_and_reap(child.as_mut());
+            return Err(err.into());
+        }
+        registration = Some(SessionRegistration::new(registry, pid, log_path.clone()));
+        on_spawn(pid);
+    }
+
+    let raw_mode = RawModeGuard::enable();
+
+    // 子进程输出 → 终端 + 会话日志
+    let output_thread = std::thread::spawn(move || -> io::Result<()> {
+        let mut stdout = io::stdout();
+        let mut buf = [0u8; 8192];
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                // Linux 上 slave 全部关闭后读取 master 返回 EIO，视为会话结束
+                Err(_) => break,
+            };
+            stdout.write_all(&buf[..n])?;
+            stdout.flush()?;
+            log_file.write_all(&buf[..n])?;
+        }
+        log_file.flush()
+    });
+
+    // 终端输入 → 子进程。stdin 读取会一直阻塞，线程随 aiw 进程退出，不做 join
+    std::thread::spawn(move || {
+        let mut stdin = io::stdin();
+        let mut buf = [0u8; 1024];
+        loop {
+            match stdin.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if writer.write_all(&buf[..n]).is_err() || writer.flush().is_err() {
+                        break;
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            }
+        }
+    });
+
+    // 等待退出，期间把终端尺寸变化同步给 PTY（子进程会收到 SIGWINCH）
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(err) => {
+                kill_and_reap(child.as_mut());
+                return Err(err.into());
+            }
+        }
+        let current = terminal_size();
+        if current != size {
+            size = current;
+            let _ = pair.master.resize(pty_size(size));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    drop(raw_mode);
+
+    match output_thread.join() {
+        Ok(result) => result?,
+        Err(_) => return Err(io::Error::other("Session recorder output thread panicked").into()),
+    }
+
+    let exit_code = status.exit_code() as i32;
+    if let Some(registration) = registration {
+        registration.finish(exit_code)?;
+    }
+
+    Ok((exit_code, log_path))
+}
+
+fn session_command_line(command: &std::process::Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|part| part.to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn session_log_name_is_timestamped() {
+        let started_at = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+        let pid = platform::current_pid();
+        assert_eq!(
+            session_log_name(&CliType::Claude, started_at, 0),
+            format!("claude-20260102-030405-{pid}.log")
+        );
+        assert_eq!(
+            session_log_name(&CliType::Claude, started_at, 2),
+            format!("claude-20260102-030405-{pid}-2.log")
+        );
+    }
+
+    #[test]
+    fn sessions_in_the_same_second_get_separate_logs() {
+        let dir = tempfile::tempdir().unwrap();
+        let started_at = Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap();
+
+        let (first, mut first_file) =
+            create_session_log(dir.path(), &CliType::Codex, started_at).unwrap();
+        first_file.write_all(b"first").unwrap();
+        let (second, _) = create_session_log(dir.path(), &CliType::Codex, started_at).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(std::fs::read_to_string(&first).unwrap(), "first");
+    }
+
+    #[test]
+    fn command_builder_keeps_args_env_and_cwd() {
+        let mut command = std::process::Command::new("codex");
+        command
+            .args(["exec", "--full-auto"])
+            .env("OPENAI_API_KEY", "sk-test")
+            .env_remove("CLAUDECODE")
+            .current_dir("/tmp");
+
+        let builder = command_builder_from(&command);
+        let argv: Vec<String> = builder
+            .get_argv()
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(argv, vec!["codex", "exec", "--full-auto"]);
+        assert_eq!(
+            builder.get_env("OPENAI_API_KEY").and_then(|v| v.to_str()),
+            Some("sk-test")
+        );
+        assert!(builder.get_env("CLAUDECODE").is_none());
+        assert_eq!(
+            builder.get_cwd().map(PathBuf::from),
+            Some(PathBuf::from("/tmp"))
+        );
+        assert_eq!(session_command_line(&command), "codex exec --full-auto");
+    }
+}
@@ -29,6 +29,25 @@ pub fn install(child_pid: u32) -> io::Result<SignalGuard> {
     Ok(SignalGuard)
 }
 
+/// 等待 SIGINT 或 SIGTERM（Windows 上为 Ctrl-C），供长期运行的 MCP server 优雅退出
+pub async fn shutdown_signal() -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result,
+            _ = sigterm.recv() => Ok(()),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await
+    }
+}
+
 #[cfg(unix)]
 fn setup_unix_signal_handlers() -> io::Result<()> {
     use std::sync::Once;
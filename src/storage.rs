@@ -1,7 +1,11 @@
 use crate::{
-    config::{MAX_RECORD_AGE, SHARED_MEMORY_SIZE},
+    config::{
+        MAX_RECORD_AGE, REGISTRY_BYTES_PER_ENTRY, REGISTRY_MAX_ENTRIES_DEFAULT,
+        REGISTRY_MAX_ENTRIES_ENV, REGISTRY_MAX_VALUE_BYTES_DEFAULT, REGISTRY_MAX_VALUE_BYTES_ENV,
+        SHARED_MEMORY_SIZE,
+    },
     core::models::ProcessTreeInfo,
-    core::shared_map::open_or_create,
+    core::shared_map::{backup_and_remove, lock_namespace, open_if_exists, open_or_create},
     error::RegistryError,
     logging::warn,
     task_record::{TaskRecord, TaskStatus, WorktreeInfo},
@@ -10,6 +14,7 @@ use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 use parking_lot::Mutex;
 use shared_hashmap::SharedMemoryHashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// 任务注册表条目
@@ -34,6 +39,51 @@ pub enum CleanupReason {
     ProcessExited,
     Timeout,
     ManagerMissing,
+    /// 进程在未回写结果的情况下消失（崩溃、被强杀）
+    ProcessVanished,
+}
+
+impl CleanupReason {
+    /// 写入 `TaskRecord::cleanup_reason` 的字符串形式
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CleanupReason::ProcessExited => "process_exited",
+            CleanupReason::Timeout => "timeout",
+            CleanupReason::ManagerMissing => "manager_missing",
+            CleanupReason::ProcessVanished => "process_vanished",
+        }
+    }
+}
+
+/// 任务归档清理结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// 被删除的条目 PID
+    pub removed_pids: Vec<u32>,
+    /// 删除日志文件释放的字节数
+    pub reclaimed_bytes: u64,
+}
+
+impl PruneReport {
+    pub fn removed_entries(&self) -> usize {
+        self.removed_pids.len()
+    }
+}
+
+/// 共享内存注册表修复结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepairReport {
+    pub namespace: String,
+    /// 检测到的损坏原因；`None` 表示注册表健康
+    pub corruption: Option<String>,
+    /// 原始共享内存段的备份文件
+    pub backup_path: Option<PathBuf>,
+    /// 是否删除并重新初始化了共享内存段
+    pub reinitialized: bool,
+    /// 重新写回的有效条目数
+    pub restored_entries: usize,
+    /// 无法解析而丢弃的条目数
+    pub dropped_entries: usize,
 }
 
 /// 任务存储的统一接口
@@ -65,11 +115,25 @@ pub trait TaskStorage: Send + Sync {
         F: Fn(u32) -> bool,
         G: Fn(u32) -> Result<(), String>;
 
+    /// 把进程已消失、仍标记为运行中的条目补标记为完成（`process_vanished`）
+    ///
+    /// 与 `sweep_stale_entries` 不同，这里只修正状态、保留条目，调用方仍能读到任务结果。
+    fn reconcile_vanished<F>(
+        &self,
+        now: DateTime<Utc>,
+        is_process_alive: F,
+    ) -> Result<Vec<CleanupEvent>, RegistryError>
+    where
+        F: Fn(u32) -> bool;
+
     /// 获取已完成但未读的任务
     fn get_completed_unread_tasks(&self) -> Result<Vec<(u32, TaskRecord)>, RegistryError>;
 
     /// 检查是否有运行中的任务
     fn has_running_tasks(&self, filter: Option<&ProcessTreeInfo>) -> Result<bool, RegistryError>;
+
+    /// 删除指定 PID 的条目，返回实际删除的数量
+    fn remove_entries(&self, pids: &[u32]) -> Result<usize, RegistryError>;
 }
 
 /// 进程内任务存储（线程安全）
@@ -107,17 +171,72 @@ impl InProcessStorage {
     }
 
     /// Bind a task_id (and optional worktree info) to an existing PID entry.
+    ///
+    /// 只写入提供的字段，与并发的 `mark_completed` 互不覆盖；`worktree` 为 `None`
+    /// 时保留已有的 worktree 信息。条目不存在时返回 false。
     pub fn update_task_metadata(
         &self,
         pid: u32,
         task_id: String,
         worktree: Option<WorktreeInfo>,
-    ) {
-        if let Some(mut record) = self.tasks.get_mut(&pid) {
-            record.task_id = Some(task_id.clone());
-            record.worktree_info = worktree;
+    ) -> bool {
+        {
+            let Some(mut record) = self.tasks.get_mut(&pid) else {
+                return false;
+            };
+            if let Some(previous) = record.task_id.replace(task_id.clone()) {
+                if previous != task_id {
+                    self.task_id_index
+                        .remove_if(&previous, |_, mapped| *mapped == pid);
+                }
+            }
+            if worktree.is_some() {
+                record.worktree_info = worktree;
+            }
+        }
+        self.bind_task_id(task_id, pid);
+        true
+    }
+
+    /// 把 task_id 指向 `pid`
+    ///
+    /// task_id 可以在旧任务结束后复用：此前持有它的条目会被清空 task_id，
+    /// 保证同一时刻只有一条记录带着这个 id。调用时不能持有 `tasks` 的引用。
+    fn bind_task_id(&self, task_id: String, pid: u32) {
+        let Some(previous_pid) = self.task_id_index.insert(task_id.clone(), pid) else {
+            return;
+        };
+        if previous_pid == pid {
+            return;
+        }
+        if let Some(mut previous) = self.tasks.get_mut(&previous_pid) {
+            if previous.task_id.as_deref() == Some(task_id.as_str()) {
+                previous.task_id = None;
+            }
+        }
+    }
+
+    /// Set or clear the human-friendly alias of an entry; returns false if it is missing.
+    pub fn set_task_name(&self, pid: u32, name: Option<String>) -> bool {
+        match self.tasks.get_mut(&pid) {
+            Some(mut record) => {
+                record.name = name;
+                true
+            }
+            None => false,
         }
-        self.task_id_index.insert(task_id, pid);
+    }
+
+    /// All entries whose alias equals `name`, ordered by PID.
+    pub fn find_by_name(&self, name: &str) -> Vec<(u32, TaskRecord)> {
+        let mut matches: Vec<(u32, TaskRecord)> = self
+            .tasks
+            .iter()
+            .filter(|r| r.value().name.as_deref() == Some(name))
+            .map(|r| (*r.key(), r.value().clone()))
+            .collect();
+        matches.sort_by_key(|(pid, _)| *pid);
+        matches
     }
 }
 
@@ -129,10 +248,10 @@ impl Default for InProcessStorage {
 
 impl TaskStorage for InProcessStorage {
     fn register(&self, pid: u32, record: &TaskRecord) -> Result<(), RegistryError> {
+        self.tasks.insert(pid, record.clone());
         if let Some(ref task_id) = record.task_id {
-            self.task_id_index.insert(task_id.clone(), pid);
+            self.bind_task_id(task_id.clone(), pid);
         }
-        self.tasks.insert(pid, record.clone());
         Ok(())
     }
 
@@ -187,18 +306,17 @@ impl TaskStorage for InProcessStorage {
             .filter_map(|entry| {
                 let pid = *entry.key();
                 let record = entry.value();
+                // PID 被其他进程复用时视为原进程已退出，也绝不能终止它
+                let alive = is_process_alive(pid) && !record.pid_reused(pid);
 
                 // 如果进程已不存在且任务未标记完成，补标记
-                if !is_process_alive(pid) && record.status == TaskStatus::Running {
+                if !alive && record.status == TaskStatus::Running {
                     return Some((pid, CleanupReason::ProcessExited));
                 }
 
                 // 如果记录太旧（超过12小时）
                 let age = now.signed_duration_since(record.started_at);
-                if age > max_age
-                    && record.status == TaskStatus::Running
-                    && is_process_alive(pid)
-                {
+                if age > max_age && record.status == TaskStatus::Running && alive {
                     // 尝试终止
                     let _ = terminate_process(pid);
                     return Some((pid, CleanupReason::Timeout));
@@ -212,14 +330,7 @@ impl TaskStorage for InProcessStorage {
             if let Some(mut record) = self.tasks.get_mut(&pid) {
                 record.status = TaskStatus::CompletedButUnread;
                 record.completed_at = Some(now);
-                record.cleanup_reason = Some(
-                    match cleanup_reason {
-                        CleanupReason::ProcessExited => "process_exited",
-                        CleanupReason::Timeout => "timeout",
-                        CleanupReason::ManagerMissing => "manager_missing",
-                    }
-                    .to_string(),
-                );
+                record.cleanup_reason = Some(cleanup_reason.as_str().to_string());
 
                 cleanup_events.push(CleanupEvent {
                     _pid: pid,
@@ -232,6 +343,35 @@ impl TaskStorage for InProcessStorage {
         Ok(cleanup_events)
     }
 
+    fn reconcile_vanished<F>(
+        &self,
+        now: DateTime<Utc>,
+        is_process_alive: F,
+    ) -> Result<Vec<CleanupEvent>, RegistryError>
+    where
+        F: Fn(u32) -> bool,
+    {
+        let mut events = Vec::new();
+        for mut entry in self.tasks.iter_mut() {
+            let pid = *entry.key();
+            let record = entry.value_mut();
+            if record.status != TaskStatus::Running
+                || (is_process_alive(pid) && !record.pid_reused(pid))
+            {
+                continue;
+            }
+            record.status = TaskStatus::CompletedButUnread;
+            record.completed_at = Some(now);
+            record.cleanup_reason = Some(CleanupReason::ProcessVanished.as_str().to_string());
+            events.push(CleanupEvent {
+                _pid: pid,
+                record: record.clone(),
+                reason: CleanupReason::ProcessVanished,
+            });
+        }
+        Ok(events)
+    }
+
     fn get_completed_unread_tasks(&self) -> Result<Vec<(u32, TaskRecord)>, RegistryError> {
         let completed: Vec<(u32, TaskRecord)> = self
             .tasks
@@ -275,6 +415,73 @@ impl TaskStorage for InProcessStorage {
                 .any(|entry| entry.value().status == TaskStatus::Running))
         }
     }
+
+    fn remove_entries(&self, pids: &[u32]) -> Result<usize, RegistryError> {
+        let mut removed = 0;
+        for pid in pids {
+            if let Some((_, record)) = self.tasks.remove(pid) {
+                // 复用的 task_id 可能已指向新任务，只删除仍指向本条目的映射
+                if let Some(task_id) = record.task_id {
+                    self.task_id_index
+                        .remove_if(&task_id, |_, mapped| mapped == pid);
+                }
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+/// 共享内存注册表的容量限制
+///
+/// 共享内存段大小按 `max_entries × 4 KiB` 估算（不低于 16 MiB），即默认
+/// 4096 个条目占 16 MiB；每个 warden 进程各有一段，调大条目数会线性增加内存占用。
+/// `max_value_bytes` 只限制单条记录，不额外占用内存。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegistryLimits {
+    pub max_entries: usize,
+    pub max_value_bytes: usize,
+}
+
+impl Default for RegistryLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: REGISTRY_MAX_ENTRIES_DEFAULT,
+            max_value_bytes: REGISTRY_MAX_VALUE_BYTES_DEFAULT,
+        }
+    }
+}
+
+impl RegistryLimits {
+    /// 环境变量优先，其次 `~/.aiw/config.json`，最后使用默认值；0 视为未设置
+    pub fn resolve() -> Self {
+        let user_config = crate::utils::config_paths::ConfigPaths::new()
+            .ok()
+            .map(|paths| paths.user_config);
+        let read_env = |var: &str| {
+            std::env::var(var)
+                .ok()
+                .and_then(|raw| raw.trim().parse::<usize>().ok())
+        };
+        let defaults = Self::default();
+        Self {
+            max_entries: read_env(REGISTRY_MAX_ENTRIES_ENV)
+                .or_else(|| user_config.as_ref()?.registry_max_entries)
+                .filter(|value| *value > 0)
+                .unwrap_or(defaults.max_entries),
+            max_value_bytes: read_env(REGISTRY_MAX_VALUE_BYTES_ENV)
+                .or_else(|| user_config.as_ref()?.registry_max_value_bytes)
+                .filter(|value| *value > 0)
+                .unwrap_or(defaults.max_value_bytes),
+        }
+    }
+
+    /// 创建共享内存段时使用的大小
+    pub fn shared_memory_size(&self) -> usize {
+        self.max_entries
+            .saturating_mul(REGISTRY_BYTES_PER_ENTRY)
+            .max(SHARED_MEMORY_SIZE)
+    }
 }
 
 /// 跨进程任务存储（SharedMemory）
@@ -282,6 +489,7 @@ impl TaskStorage for InProcessStorage {
 #[derive(Debug, Clone)]
 pub struct SharedMemoryStorage {
     namespace: String,
+    limits: RegistryLimits,
     map: Arc<Mutex<SharedMemoryHashMap<String, String>>>,
 }
 
@@ -301,14 +509,122 @@ impl SharedMemoryStorage {
     }
 
     /// 使用指定的命名空间连接
+    ///
+    /// 共享内存段无法打开（锁残留、区域损坏等）时返回 [`RegistryError::Corrupted`]，
+    /// 提示用户执行 `aiw registry reset`。
     pub fn connect_with_namespace(namespace: String) -> Result<Self, RegistryError> {
-        let map = open_or_create(&namespace, SHARED_MEMORY_SIZE)?;
+        Self::connect_with_limits(namespace, RegistryLimits::resolve())
+    }
+
+    /// 使用指定的命名空间和容量限制连接
+    pub fn connect_with_limits(
+        namespace: String,
+        limits: RegistryLimits,
+    ) -> Result<Self, RegistryError> {
+        let map = open_or_create(&namespace, limits.shared_memory_size())
+            .map_err(|err| RegistryError::corrupted(&namespace, err.to_string()))?;
         Ok(Self {
             namespace,
+            limits,
             map: Arc::new(Mutex::new(map)),
         })
     }
 
+    /// 当前连接的命名空间
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// 列出本机已有的任务注册表命名空间（`{pid}_task`）
+    ///
+    /// 仅 Linux 可以枚举 `/dev/shm`；其他平台返回空列表，需要显式指定 PID。
+    pub fn list_namespaces() -> Vec<String> {
+        #[cfg(target_os = "linux")]
+        {
+            let mut namespaces: Vec<String> = std::fs::read_dir("/dev/shm")
+                .map(|dir| {
+                    dir.filter_map(|entry| entry.ok())
+                        .filter_map(|entry| entry.file_name().into_string().ok())
+                        .filter(|name| name.ends_with("_task"))
+                        .collect()
+                })
+                .unwrap_or_default();
+            namespaces.sort();
+            namespaces
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            Vec::new()
+        }
+    }
+
+    /// 检测并修复命名空间对应的共享内存注册表
+    ///
+    /// 无法打开或包含无法解析的条目时视为损坏：先把原始共享内存段备份到
+    /// `backup_dir`，删除后重新初始化，再写回仍然有效的条目。`force` 为 true 时
+    /// 即使未检测到损坏也执行重建。共享内存段不存在时返回 [`RegistryError::NotFound`]。
+    pub fn repair(
+        namespace: &str,
+        force: bool,
+        backup_dir: &Path,
+    ) -> Result<RepairReport, RegistryError> {
+        let mut report = RepairReport {
+            namespace: namespace.to_string(),
+            ..RepairReport::default()
+        };
+
+        let size = RegistryLimits::resolve().shared_memory_size();
+        let mut salvaged: Vec<(u32, TaskRecord)> = Vec::new();
+        // 只修复已存在的注册表，不能因为 PID 写错而凭空创建一个
+        match open_if_exists(namespace, size) {
+            Ok(None) => return Err(RegistryError::not_found(namespace)),
+            Ok(Some(map)) => {
+                for (key, value) in map.iter() {
+                    let parsed = key
+                        .parse::<u32>()
+                        .ok()
+                        .zip(serde_json::from_str::<TaskRecord>(&value).ok());
+                    match parsed {
+                        Some(entry) => salvaged.push(entry),
+                        None => report.dropped_entries += 1,
+                    }
+                }
+                if report.dropped_entries > 0 {
+                    report.corruption =
+                        Some(format!("{} unreadable entries", report.dropped_entries));
+                }
+            }
+            Err(err) => report.corruption = Some(err.to_string()),
+        }
+
+        if report.corruption.is_none() && !force {
+            return Ok(report);
+        }
+
+        std::fs::create_dir_all(backup_dir).map_err(|err| {
+            RegistryError::Shared(format!(
+                "cannot create backup directory {}: {err}",
+                backup_dir.display()
+            ))
+        })?;
+        let backup_path = backup_dir.join(format!(
+            "{}-{}.shm",
+            namespace,
+            Utc::now().format("%Y%m%d-%H%M%S")
+        ));
+        if backup_and_remove(namespace, size, &backup_path)? {
+            report.backup_path = Some(backup_path);
+        }
+
+        let storage = Self::connect_with_namespace(namespace.to_string())?;
+        for (pid, record) in &salvaged {
+            storage.register(*pid, record)?;
+        }
+        report.reinitialized = true;
+        report.restored_entries = salvaged.len();
+        Ok(report)
+    }
+
     /// 删除共享内存（用于进程结束时清理）
     pub fn cleanup(&self) -> Result<(), RegistryError> {
         use shared_memory::ShmemConf;
@@ -316,7 +632,7 @@ impl SharedMemoryStorage {
         // 尝试删除共享内存
         if let Ok(mut shmem) = ShmemConf::new()
             .os_id(&self.namespace)
-            .size(SHARED_MEMORY_SIZE)
+            .size(self.limits.shared_memory_size())
             .open()
         {
             let _ = shmem.set_owner(true);
@@ -330,9 +646,32 @@ impl SharedMemoryStorage {
         f: impl FnOnce(&mut SharedMemoryHashMap<String, String>) -> Result<T, RegistryError>,
     ) -> Result<T, RegistryError> {
         let mut guard = self.map.lock();
+        // 进程内互斥之外再持有跨进程锁，保证读改写期间没有其他 warden 写入
+        let _lock = lock_namespace(&self.namespace).map_err(|err| {
+            RegistryError::Shared(format!("cannot lock registry {}: {err}", self.namespace))
+        })?;
         f(&mut guard)
     }
 
+    fn full(&self, reason: impl Into<String>) -> RegistryError {
+        RegistryError::Full {
+            namespace: self.namespace.clone(),
+            reason: reason.into(),
+        }
+    }
+
+    /// 单条记录超过 `max_value_bytes` 时返回 [`RegistryError::Full`]
+    fn check_value_size(&self, pid: u32, value: &str) -> Result<(), RegistryError> {
+        if value.len() > self.limits.max_value_bytes {
+            return Err(self.full(format!(
+                "record for pid {pid} is {} bytes, limit is {} bytes",
+                value.len(),
+                self.limits.max_value_bytes
+            )));
+        }
+        Ok(())
+    }
+
     fn remove_keys(&self, keys: &[String]) -> Result<(), RegistryError> {
         if keys.is_empty() {
             return Ok(());
@@ -350,8 +689,20 @@ impl TaskStorage for SharedMemoryStorage {
     fn register(&self, pid: u32, record: &TaskRecord) -> Result<(), RegistryError> {
         let key = pid.to_string();
         let value = serde_json::to_string(record)?;
+        self.check_value_size(pid, &value)?;
         self.with_map(|map| {
-            map.try_insert(key.clone(), value)?;
+            if map.get(&key).is_none() {
+                let entries = map.iter().count();
+                if entries >= self.limits.max_entries {
+                    return Err(self.full(format!(
+                        "{entries} entries, limit is {}",
+                        self.limits.max_entries
+                    )));
+                }
+            }
+            // 条目数未到上限但共享内存段本身已写满（记录普遍较大）
+            map.try_insert(key.clone(), value)
+                .map_err(|err| self.full(format!("shared memory exhausted: {err}")))?;
             Ok(())
         })
     }
@@ -368,10 +719,17 @@ impl TaskStorage for SharedMemoryStorage {
             let existing = map
                 .get(&key)
                 .ok_or_else(|| RegistryError::Map(format!("no task found for pid {pid}")))?;
-            let record: TaskRecord = serde_json::from_str(&existing)?;
+            let record: TaskRecord = serde_json::from_str(&existing).map_err(|err| {
+                RegistryError::corrupted(
+                    &self.namespace,
+                    format!("unreadable record for pid {pid}: {err}"),
+                )
+            })?;
             let updated_record = record.mark_completed(result, exit_code, completed_at);
             let updated_value = serde_json::to_string(&updated_record)?;
-            let _ = map.insert(key.clone(), updated_value);
+            self.check_value_size(pid, &updated_value)?;
+            map.try_insert(key.clone(), updated_value)
+                .map_err(|err| self.full(format!("shared memory exhausted: {err}")))?;
             Ok(())
         })
     }
@@ -430,8 +788,8 @@ impl TaskStorage for SharedMemoryStorage {
             let mut should_cleanup = false;
             let mut cleanup_reason = CleanupReason::ProcessExited;
 
-            // 检查进程是否存活
-            if !is_process_alive(entry.pid) {
+            // 检查进程是否存活（PID 被复用同样视为已退出）
+            if !is_process_alive(entry.pid) || entry.record.pid_reused(entry.pid) {
                 should_cleanup = true;
                 cleanup_reason = CleanupReason::ProcessExited;
             } else {
@@ -460,14 +818,7 @@ impl TaskStorage for SharedMemoryStorage {
                 removals.push(entry.pid.to_string());
 
                 // Update record with cleanup reason
-                entry.record.cleanup_reason = Some(
-                    match cleanup_reason {
-                        CleanupReason::ProcessExited => "process_exited",
-                        CleanupReason::Timeout => "timeout",
-                        CleanupReason::ManagerMissing => "manager_missing",
-                    }
-                    .to_string(),
-                );
+                entry.record.cleanup_reason = Some(cleanup_reason.as_str().to_string());
 
                 events.push(CleanupEvent {
                     _pid: entry.pid,
@@ -484,6 +835,55 @@ impl TaskStorage for SharedMemoryStorage {
         Ok(events)
     }
 
+    fn reconcile_vanished<F>(
+        &self,
+        now: DateTime<Utc>,
+        is_process_alive: F,
+    ) -> Result<Vec<CleanupEvent>, RegistryError>
+    where
+        F: Fn(u32) -> bool,
+    {
+        let vanished: Vec<u32> = self
+            .entries()?
+            .into_iter()
+            .filter(|entry| {
+                entry.record.status == TaskStatus::Running
+                    && (!is_process_alive(entry.pid) || entry.record.pid_reused(entry.pid))
+            })
+            .map(|entry| entry.pid)
+            .collect();
+        if vanished.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.with_map(|map| {
+            let mut events = Vec::new();
+            for pid in vanished {
+                let key = pid.to_string();
+                // 在锁内重新读取，避免覆盖 supervisor 刚写入的完成结果
+                let Some(value) = map.get(&key) else {
+                    continue;
+                };
+                let Ok(mut record) = serde_json::from_str::<TaskRecord>(&value) else {
+                    continue;
+                };
+                if record.status != TaskStatus::Running {
+                    continue;
+                }
+                record.status = TaskStatus::CompletedButUnread;
+                record.completed_at = Some(now);
+                record.cleanup_reason = Some(CleanupReason::ProcessVanished.as_str().to_string());
+                let _ = map.insert(key, serde_json::to_string(&record)?);
+                events.push(CleanupEvent {
+                    _pid: pid,
+                    record,
+                    reason: CleanupReason::ProcessVanished,
+                });
+            }
+            Ok(events)
+        })
+    }
+
     fn get_completed_unread_tasks(&self) -> Result<Vec<(u32, TaskRecord)>, RegistryError> {
         let entries = self.entries()?;
         let mut completed_pids = Vec::new();
@@ -532,6 +932,23 @@ impl TaskStorage for SharedMemoryStorage {
                 .any(|entry| entry.record.status == TaskStatus::Running))
         }
     }
+
+    fn remove_entries(&self, pids: &[u32]) -> Result<usize, RegistryError> {
+        if pids.is_empty() {
+            return Ok(0);
+        }
+        self.with_map(|map| {
+            let mut removed = 0;
+            for pid in pids {
+                let key = pid.to_string();
+                if map.get(&key).is_some() {
+                    map.remove(&key);
+                    removed += 1;
+                }
+            }
+            Ok(removed)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -575,6 +992,54 @@ mod tests {
         assert_eq!(completed[0].1.result, Some("success".to_string()));
     }
 
+    #[test]
+    fn test_in_process_storage_task_names() {
+        let storage = InProcessStorage::new();
+        for pid in [301, 302] {
+            let record = TaskRecord::new(
+                Utc::now(),
+                pid.to_string(),
+                "/tmp/test.log".to_string(),
+                Some(100),
+            );
+            storage.register(pid, &record).unwrap();
+        }
+
+        assert!(storage.set_task_name(302, Some("review".to_string())));
+        assert!(!storage.set_task_name(999, Some("review".to_string())));
+        let found = storage.find_by_name("review");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 302);
+
+        storage.set_task_name(301, Some("review".to_string()));
+        let pids: Vec<u32> = storage
+            .find_by_name("review")
+            .iter()
+            .map(|(pid, _)| *pid)
+            .collect();
+        assert_eq!(pids, vec![301, 302]);
+
+        storage.set_task_name(302, None);
+        assert_eq!(storage.find_by_name("review").len(), 1);
+        assert!(storage.get_task(302).unwrap().name.is_none());
+    }
+
+    #[test]
+    fn repair_reports_missing_registry_instead_of_creating_it() {
+        let namespace = format!("aiw_repair_missing_{}", std::process::id());
+        let backup = tempfile::tempdir().unwrap();
+
+        let err = SharedMemoryStorage::repair(&namespace, true, backup.path()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("no registry for namespace '{}'", namespace)
+        );
+        assert_eq!(
+            RegistryError::not_found("4242_task").to_string(),
+            "no registry for pid 4242"
+        );
+    }
+
     #[test]
     fn test_in_process_storage_sweep_stale() {
         let storage = InProcessStorage::new();
@@ -660,6 +1125,14 @@ mod tests {
                 Ok(Vec::new())
             }
 
+            fn reconcile_vanished<F>(
+                &self,
+                _now: DateTime<Utc>,
+                _is_process_alive: F,
+            ) -> Result<Vec<CleanupEvent>, RegistryError> {
+                Ok(Vec::new())
+            }
+
             fn get_completed_unread_tasks(&self) -> Result<Vec<(u32, TaskRecord)>, RegistryError> {
                 Ok(Vec::new())
             }
@@ -670,6 +1143,14 @@ mod tests {
             ) -> Result<bool, RegistryError> {
                 Ok(false)
             }
+
+            fn remove_entries(&self, pids: &[u32]) -> Result<usize, RegistryError> {
+                let mut tasks = self.tasks.lock().unwrap();
+                Ok(pids
+                    .iter()
+                    .filter(|pid| tasks.remove(pid).is_some())
+                    .count())
+            }
         }
 
         #[test]
@@ -11,11 +11,13 @@ use std::os::unix::process::CommandExt;
 #[cfg(windows)]
 use crate::platform::ChildResources;
 use crate::platform::{self};
-use crate::provider::{AiType, ProviderManager};
+use crate::provider::{AiType, EnvInjector, ProviderManager};
 use crate::signal;
-use crate::storage::TaskStorage;
+use crate::storage::{InProcessStorage, TaskStorage};
+use crate::task_hooks::{TaskHooks, TaskOutcome};
 use crate::task_record::TaskRecord;
 use crate::unified_registry::Registry;
+use crate::utils::protocol_stdout;
 use chrono::{DateTime, Utc};
 use std::collections::VecDeque;
 use std::ffi::OsString;
@@ -40,7 +42,7 @@ use thiserror::Error;
 use tokio::fs::OpenOptions;
 use tokio::io::{AsyncRead, AsyncWriteExt, BufWriter};
 use tokio::process::Command;
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 
 #[derive(Debug, Error)]
 pub enum ProcessError {
@@ -52,6 +54,8 @@ pub enum ProcessError {
     ProcessTree(#[from] ProcessTreeError),
     #[error("CLI executable not found: {0}")]
     CliNotFound(String),
+    #[error("Cancelled: {message}")]
+    Cancelled { pid: u32, message: String },
     #[error("{0}")]
     Other(String),
 }
@@ -130,9 +134,10 @@ fn apply_max_context_tokens_patches(pid: u32, cli_type: &CliType) {
     } else {
         match patcher.apply_max_context_tokens_patch(max_tokens, auto_compact) {
             Ok(_addr) => {
-                eprintln!(
-                    "✅ MaxContextTokens unlocked (max_tokens={}, auto_compact={})",
-                    max_tokens, auto_compact
+                tracing::info!(
+                    "MaxContextTokens unlocked (max_tokens={}, auto_compact={})",
+                    max_tokens,
+                    auto_compact
                 );
             }
             Err(e) => {
@@ -149,7 +154,7 @@ fn apply_max_context_tokens_patches(pid: u32, cli_type: &CliType) {
     {
         match patcher.apply_literal_memory_patch(patch) {
             Ok(_addr) => {
-                eprintln!("✅ AntiTelemetry applied (event_logging endpoint -> 404)");
+                tracing::info!("AntiTelemetry applied (event_logging endpoint -> 404)");
             }
             Err(e) => {
                 tracing::debug!("anti-telemetry memory patch failed: {}", e);
@@ -172,7 +177,7 @@ fn apply_max_context_tokens_patches(pid: u32, cli_type: &CliType) {
         };
         match result {
             Ok(_addr) => {
-                eprintln!("✅ AntiSpy applied (timezone + relay detection -> null)");
+                tracing::info!("AntiSpy applied (timezone + relay detection -> null)");
             }
             Err(e) => {
                 tracing::debug!("anti-spy memory patch failed: {}", e);
@@ -194,7 +199,7 @@ fn apply_max_context_tokens_patches(pid: u32, cli_type: &CliType) {
         };
         match result {
             Ok(_addr) => {
-                eprintln!("✅ AntiPromptBias applied (Provider context prompt -> skipped)");
+                tracing::info!("AntiPromptBias applied (Provider context prompt -> skipped)");
             }
             Err(e) => {
                 tracing::debug!("anti-prompt-bias memory patch failed: {}", e);
@@ -216,7 +221,7 @@ fn apply_max_context_tokens_patches(pid: u32, cli_type: &CliType) {
         };
         match result {
             Ok(_addr) => {
-                eprintln!("✅ AntiAtis applied (x-cc-atis tracking header -> disabled)");
+                tracing::info!("AntiAtis applied (x-cc-atis tracking header -> disabled)");
             }
             Err(e) => {
                 tracing::debug!("anti-atis memory patch failed: {}", e);
@@ -248,7 +253,7 @@ fn apply_antitelemetry_memory_patch_background(patcher: &RuntimePatcher) {
     {
         match patcher.apply_literal_memory_patch(patch) {
             Ok(_addr) => {
-                eprintln!("✅ AntiTelemetry applied (event_logging endpoint -> 404)");
+                tracing::info!("AntiTelemetry applied (event_logging endpoint -> 404)");
             }
             Err(_) => {
                 // Silent failure - best-effort
@@ -270,7 +275,7 @@ fn apply_antitelemetry_memory_patch_background(patcher: &RuntimePatcher) {
         };
         match result {
             Ok(_addr) => {
-                eprintln!("✅ AntiSpy applied (timezone + relay detection -> null)");
+                tracing::info!("AntiSpy applied (timezone + relay detection -> null)");
             }
             Err(_) => {
                 // Silent failure - best-effort
@@ -292,7 +297,7 @@ fn apply_antitelemetry_memory_patch_background(patcher: &RuntimePatcher) {
         };
         match result {
             Ok(_addr) => {
-                eprintln!("✅ AntiPromptBias applied (Provider context prompt -> skipped)");
+                tracing::info!("AntiPromptBias applied (Provider context prompt -> skipped)");
             }
             Err(_) => {
                 // Silent failure - best-effort
@@ -314,7 +319,7 @@ fn apply_antitelemetry_memory_patch_background(patcher: &RuntimePatcher) {
         };
         match result {
             Ok(_addr) => {
-                eprintln!("✅ AntiAtis applied (x-cc-atis tracking header -> disabled)");
+                tracing::info!("AntiAtis applied (x-cc-atis tracking header -> disabled)");
             }
             Err(_) => {
                 // Silent failure - best-effort
@@ -354,6 +359,89 @@ pub struct CapturedOutput {
     pub stderr: String,
 }
 
+/// `execute_cli_internal` 的完整结果
+struct CliRun {
+    pid: u32,
+    exit_code: i32,
+    output: Option<CapturedOutput>,
+    /// 实际注入的 provider，回退到 CLI 原生配置时为 None
+    provider: Option<String>,
+    log_path: PathBuf,
+}
+
+/// 单次 CLI 执行的子进程 PID 与退出码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CliExit {
+    pub pid: u32,
+    pub exit_code: i32,
+}
+
+/// Auto 模式故障切换成功时的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailoverOutcome {
+    /// 成功组合的子进程 PID，任务结果从它的日志提取
+    pub winner_pid: u32,
+    /// 同一次执行中其余组合的子进程 PID（失败、落败被取消或未被采用的）
+    pub other_pids: Vec<u32>,
+}
+
+/// 多 CLI 执行中单个 CLI 的结果
+#[derive(Debug, Clone)]
+pub struct CliExecOutcome {
+    pub cli_type: CliType,
+    /// 实际使用的 provider，使用 CLI 原生配置时为 None
+    pub provider: Option<String>,
+    pub exit_code: i32,
+    pub duration: std::time::Duration,
+    pub log_path: PathBuf,
+}
+
+/// `execute_multiple_clis` 的结果，按执行顺序排列
+#[derive(Debug, Clone, Default)]
+pub struct MultiExecResult {
+    pub outcomes: Vec<CliExecOutcome>,
+}
+
+impl MultiExecResult {
+    /// 各 CLI 的退出码（旧接口返回的 `Vec<i32>`）
+    pub fn exit_codes(&self) -> Vec<i32> {
+        self.outcomes
+            .iter()
+            .map(|outcome| outcome.exit_code)
+            .collect()
+    }
+
+    /// 第一个非零退出码，全部成功时为 0
+    pub fn final_exit_code(&self) -> i32 {
+        self.outcomes
+            .iter()
+            .map(|outcome| outcome.exit_code)
+            .find(|&code| code != 0)
+            .unwrap_or(0)
+    }
+}
+
+/// 终端上实时镜像输出，被程序捕获时只输出尾部
+///
+/// 如果不是 TTY（被程序捕获，如 CC 的 Bash 工具），使用 TailOnly 模式，
+/// 防止大量输出冲爆调用方的上下文。
+fn interactive_output_strategy() -> OutputStrategy {
+    if std::io::stdout().is_terminal() {
+        OutputStrategy::Mirror
+    } else {
+        OutputStrategy::TailOnly
+    }
+}
+
+/// 命令行直接启动 CLI 时的任务 span
+///
+/// 与 MCP `start_task` 的 span 同名同字段；命令行启动没有外部 task_id，这里生成一个，
+/// JSON 日志中的 launch/exit 事件据此关联。
+pub fn cli_task_span(ai_type: &str) -> tracing::Span {
+    let task_id = uuid::Uuid::new_v4().to_string();
+    tracing::info_span!("task", task_id = %task_id, ai_type = %ai_type)
+}
+
 pub async fn execute_cli<S: TaskStorage>(
     registry: &Registry<S>,
     cli_type: &CliType,
@@ -361,26 +449,73 @@ pub async fn execute_cli<S: TaskStorage>(
     provider: Option<String>,
     cwd: Option<std::path::PathBuf>,
 ) -> Result<i32, ProcessError> {
-    // 检测 stdout 是否是 TTY
-    // 如果不是 TTY（被程序捕获，如 CC 的 Bash 工具），使用 TailOnly 模式
-    // 这样可以防止大量输出冲爆 CC 的上下文
-    let output_strategy = if std::io::stdout().is_terminal() {
-        OutputStrategy::Mirror
-    } else {
-        OutputStrategy::TailOnly
-    };
+    // 与 start_task 共用并发名额，内部调用（如代码生成）不会绕过上限
+    let _slot = crate::mcp::launch_limiter::global()
+        .acquire(cli_type.display_name())
+        .await;
+    execute_cli_with_env(registry, cli_type, args, provider, cwd, &[], None).await
+}
+
+/// 与 `execute_cli` 相同，但额外注入任务级环境变量
+///
+/// `extra_env` 在 provider 环境变量之后应用，同名变量以任务级为准。
+/// 只有变量名会写入 TaskRecord，值不落盘。
+pub async fn execute_cli_with_env<S: TaskStorage>(
+    registry: &Registry<S>,
+    cli_type: &CliType,
+    args: &[OsString],
+    provider: Option<String>,
+    cwd: Option<std::path::PathBuf>,
+    extra_env: &[(String, String)],
+    priority: Option<i32>,
+) -> Result<i32, ProcessError> {
+    execute_cli_internal(
+        registry,
+        cli_type,
+        args,
+        provider,
+        None,
+        interactive_output_strategy(),
+        cwd,
+        extra_env,
+        priority,
+        None,
+    )
+    .await
+    .map(|run| run.exit_code)
+}
 
+/// 可取消的 CLI 执行（auto 模式 Race/All 策略使用）
+///
+/// `cancel` 变为 `true` 时终止子进程并返回 [`ProcessError::Cancelled`]。
+/// 多个任务并发运行，因此固定使用 TailOnly 输出，避免输出交错。
+pub async fn execute_cli_cancellable<S: TaskStorage>(
+    registry: &Registry<S>,
+    cli_type: &CliType,
+    args: &[OsString],
+    provider: Option<String>,
+    cwd: Option<std::path::PathBuf>,
+    extra_env: &[(String, String)],
+    priority: Option<i32>,
+    cancel: watch::Receiver<bool>,
+) -> Result<CliExit, ProcessError> {
     execute_cli_internal(
         registry,
         cli_type,
         args,
         provider,
         None,
-        output_strategy,
+        OutputStrategy::TailOnly,
         cwd,
+        extra_env,
+        priority,
+        Some(cancel),
     )
     .await
-    .map(|(exit_code, _)| exit_code)
+    .map(|run| CliExit {
+        pid: run.pid,
+        exit_code: run.exit_code,
+    })
 }
 
 /// Execute CLI and capture stdout output (for code generation)
@@ -396,7 +531,7 @@ pub async fn execute_cli_with_output<S: TaskStorage>(
     let buffer = Arc::new(Mutex::new(Vec::new()));
     let scrolling_display = Arc::new(Mutex::new(ScrollingDisplay::new(DEFAULT_MAX_DISPLAY_LINES)));
 
-    let (_, output_opt) = execute_cli_internal(
+    let run = execute_cli_internal(
         registry,
         cli_type,
         args,
@@ -404,10 +539,13 @@ pub async fn execute_cli_with_output<S: TaskStorage>(
         Some(timeout),
         OutputStrategy::CaptureWithDisplay(buffer.clone(), scrolling_display.clone()),
         cwd,
+        &[],
+        None,
+        None,
     )
     .await?;
 
-    match output_opt {
+    match run.output {
         Some(output) => {
             let mut stdout = output.stdout;
             // 应用50行限制：只保留最后50行
@@ -439,7 +577,7 @@ pub async fn execute_cli_with_full_output<S: TaskStorage>(
     let stdout = Arc::new(Mutex::new(Vec::new()));
     let stderr = Arc::new(Mutex::new(Vec::new()));
 
-    let (exit_code, output_opt) = execute_cli_internal(
+    let run = execute_cli_internal(
         registry,
         cli_type,
         args,
@@ -447,20 +585,42 @@ pub async fn execute_cli_with_full_output<S: TaskStorage>(
         Some(timeout),
         OutputStrategy::CaptureAll(stdout.clone(), stderr.clone()),
         cwd,
+        &[],
+        None,
+        None,
     )
     .await?;
 
-    let output = output_opt.ok_or_else(|| {
-        ProcessError::Other("Output capture failed unexpectedly".to_string())
-    })?;
+    let output = run
+        .output
+        .ok_or_else(|| ProcessError::Other("Output capture failed unexpectedly".to_string()))?;
+
+    Ok((run.exit_code, output))
+}
+
+/// 同步调用方使用的 [`resolve_provider`]：钥匙串查询最多等待 `KEYCHAIN_TIMEOUT`，
+/// 在多线程 runtime 中调用时先把当前线程移出 runtime，不占住工作线程
+fn resolve_provider_off_runtime(
+    cli_type: &CliType,
+    provider: &Option<String>,
+) -> Result<(String, crate::provider::config::Provider, bool, ProviderManager), ProcessError> {
+    use tokio::runtime::{Handle, RuntimeFlavor};
 
-    Ok((exit_code, output))
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| resolve_provider(cli_type, provider))
+        }
+        _ => resolve_provider(cli_type, provider),
+    }
 }
 
 /// Internal CLI execution with configurable output handling
 /// 解析 Provider 配置：处理 auto/指定/默认三种模式
 ///
 /// 返回 (provider_name, provider_config, is_fallback, provider_manager)
+///
+/// 会解析 providers.json 中的密钥引用（可能查询钥匙串），不能直接在 async 上下文中调用：
+/// 用 `spawn_blocking` 或 [`resolve_provider_off_runtime`]。
 fn resolve_provider(
     cli_type: &CliType,
     provider: &Option<String>,
@@ -513,7 +673,7 @@ fn resolve_provider(
                 Err(_) => {
                     // Silently fallback to default provider
                     if let Some((default_name, default_config)) =
-                        provider_manager.get_default_provider()
+                        provider_manager.get_default_provider_for(&ai_type)
                     {
                         (default_name, default_config.clone(), true)
                     } else if let Ok(official) = provider_manager.get_provider("official") {
@@ -528,9 +688,9 @@ fn resolve_provider(
             }
         }
     } else {
-        // No -mp flag: prefer default provider, fallback to round-robin compatible providers
+        // No -mp flag: prefer the AI-type default (or global default), fallback to round-robin
         let default = provider_manager
-            .get_default_provider()
+            .get_default_provider_for(&ai_type)
             .map(|(name, config)| (name, config.clone()));
         if let Some((default_name, default_config)) = default {
             if default_config.is_compatible_with(&ai_type) {
@@ -557,12 +717,23 @@ fn resolve_provider(
                     compatible_with: None,
                     env: std::collections::HashMap::new(),
                     disabled_until: None,
+                    env_overrides: std::collections::HashMap::new(),
+                    priority: None,
                 },
                 true,
             )
         }
     };
 
+    // providers.json 中的密钥引用在启动 CLI 前解析，解析失败时不启动
+    let provider_config = if is_fallback {
+        provider_config
+    } else {
+        EnvInjector::resolve_secrets(&provider_config).map_err(|e| {
+            ProcessError::Other(format!("Provider '{}': {}", provider_name, e))
+        })?
+    };
+
     Ok((provider_name, provider_config, is_fallback, provider_manager))
 }
 
@@ -574,7 +745,10 @@ async fn execute_cli_internal<S: TaskStorage>(
     timeout: Option<std::time::Duration>,
     output_strategy: OutputStrategy,
     cwd: Option<std::path::PathBuf>,
-) -> Result<(i32, Option<CapturedOutput>), ProcessError> {
+    extra_env: &[(String, String)],
+    priority: Option<i32>,
+    cancel: Option<watch::Receiver<bool>>,
+) -> Result<CliRun, ProcessError> {
     // Validate CWD if provided
     if let Some(ref dir) = cwd {
         if !dir.exists() {
@@ -605,8 +779,14 @@ async fn execute_cli_internal<S: TaskStorage>(
     };
     registry.sweep_stale_entries(Utc::now(), platform::process_alive, &terminate_wrapper)?;
 
-    let (provider_name, provider_config, is_fallback, mut provider_manager) =
-        resolve_provider(cli_type, &provider)?;
+    // 密钥引用可能要等钥匙串应答，放到阻塞线程里解析
+    let (provider_name, provider_config, is_fallback, mut provider_manager) = {
+        let cli_type = cli_type.clone();
+        let provider = provider.clone();
+        tokio::task::spawn_blocking(move || resolve_provider(&cli_type, &provider))
+            .await
+            .map_err(|e| ProcessError::Other(format!("Provider resolution failed: {}", e)))??
+    };
 
     // Display provider info only in debug/verbose scenarios (silent by default)
 
@@ -617,6 +797,8 @@ async fn execute_cli_internal<S: TaskStorage>(
     command.stdin(Stdio::null());
     command.stdout(Stdio::piped());
     command.stderr(Stdio::piped());
+    // 调用方丢弃 future（如取消编排）时不留下孤儿进程，进程组由 ChildGroupGuard 清理
+    command.kill_on_drop(true);
 
     // Remove nesting-detection env vars so child CLI processes don't think
     // they are running inside another session (e.g. Claude Code's CLAUDECODE check).
@@ -628,11 +810,14 @@ async fn execute_cli_internal<S: TaskStorage>(
         command.current_dir(dir);
     }
 
+    // None 表示继承 supervisor 自身的优先级
+    let priority = priority.map(platform::clamp_priority);
+
     // Platform-specific command preparation
     #[cfg(unix)]
     {
         unsafe {
-            command.pre_exec(|| {
+            command.pre_exec(move || {
                 let result = libc::setpgid(0, 0);
                 if result != 0 {
                     return Err(io::Error::last_os_error());
@@ -644,14 +829,25 @@ async fn execute_cli_internal<S: TaskStorage>(
                         return Err(io::Error::last_os_error());
                     }
                 }
+                if let Some(nice) = priority {
+                    if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
                 Ok(())
             });
         }
     }
+    #[cfg(windows)]
+    {
+        if let Some(nice) = priority {
+            command.creation_flags(platform::priority_class(nice));
+        }
+    }
 
     // Inject environment variables (skip in fallback mode)
     if !is_fallback {
-        for (key, value) in &provider_config.env {
+        for (key, value) in EnvInjector::provider_env(&provider_config) {
             command.env(key, value);
         }
         // For Codex with third-party providers, isolate CODEX_HOME to avoid
@@ -662,6 +858,11 @@ async fn execute_cli_internal<S: TaskStorage>(
 
     }
 
+    // Task-level env vars are applied last so they win over provider injection
+    for (key, value) in extra_env {
+        command.env(key, value);
+    }
+
     let mut child = command.spawn()?;
     let child_pid = child
         .id()
@@ -675,7 +876,7 @@ async fn execute_cli_internal<S: TaskStorage>(
     let log_path = match generate_log_path(child_pid) {
         Ok(path) => path,
         Err(err) => {
-            platform::terminate_process(child_pid);
+            platform::terminate_process_group(child_pid).await;
             let _ = child.wait().await;
             return Err(err.into());
         }
@@ -690,7 +891,7 @@ async fn execute_cli_internal<S: TaskStorage>(
     {
         Ok(file) => file,
         Err(err) => {
-            platform::terminate_process(child_pid);
+            platform::terminate_process_group(child_pid).await;
             let _ = child.wait().await;
             return Err(err.into());
         }
@@ -708,6 +909,15 @@ async fn execute_cli_internal<S: TaskStorage>(
         }
     ));
 
+    tracing::info!(
+        event = "launch",
+        pid = child_pid,
+        ai_type = cli_type.display_name(),
+        provider = provider_name.as_str(),
+        log_path = %log_path.display(),
+        "task launched"
+    );
+
     #[cfg(windows)]
     {
         let _resources = ChildResources::with_job(None);
@@ -801,6 +1011,23 @@ async fn execute_cli_internal<S: TaskStorage>(
             log_path.to_string_lossy().into_owned(),
             Some(platform::current_pid()),
         );
+        record.ai_type = Some(cli_type.display_name().to_string());
+        record.env_var_names = extra_env.iter().map(|(key, _)| key.clone()).collect();
+        record.process_start_time = platform::process_start_time(child_pid);
+        let argv: Vec<String> = std::iter::once(cli_command.clone())
+            .chain(args.iter().map(|arg| arg.to_string_lossy().into_owned()))
+            .collect();
+        record.model = cli_type.model_from_args(&argv[1..]);
+        record.set_command_line(&argv);
+        record.priority = priority;
+        if !is_fallback {
+            record.provider = Some(provider_name.clone());
+            let mut names: Vec<String> = EnvInjector::provider_env(&provider_config)
+                .into_keys()
+                .collect();
+            names.sort();
+            record.provider_env_names = names;
+        }
 
         // Get process tree information
         match ProcessTreeInfo::current() {
@@ -818,29 +1045,121 @@ async fn execute_cli_internal<S: TaskStorage>(
         }
 
         if let Err(err) = registry.register(child_pid, &record) {
-            platform::terminate_process(child_pid);
+            platform::terminate_process_group(child_pid).await;
             let _ = child.wait().await;
             return Err(err.into());
         }
         Some(RegistrationGuard::new(registry, child_pid))
     };
 
-    // Wait with optional timeout
-    let status = if let Some(timeout_duration) = timeout {
-        tokio::select! {
-            result = child.wait() => result?,
-            _ = tokio::time::sleep(timeout_duration) => {
-                platform::terminate_process(child_pid);
-                let _ = child.wait().await;
-                return Err(ProcessError::Other(format!(
-                    "CLI execution timed out after {:?}",
-                    timeout_duration
-                )));
+    let mut child_group = ChildGroupGuard::new(child_pid);
+
+    // Wait with optional timeout and cancellation
+    let timed_out = async {
+        match timeout {
+            Some(timeout_duration) => tokio::time::sleep(timeout_duration).await,
+            None => std::future::pending().await,
+        }
+    };
+    let cancelled = async {
+        match cancel {
+            Some(mut rx) => {
+                // 发送端提前关闭视为不会再取消
+                if rx.wait_for(|cancelled| *cancelled).await.is_err() {
+                    std::future::pending::<()>().await;
+                }
             }
+            None => std::future::pending().await,
+        }
+    };
+    let status = tokio::select! {
+        result = child.wait() => result?,
+        _ = timed_out => {
+            platform::terminate_process_group(child_pid).await;
+            let _ = child.wait().await;
+            child_group.disarm();
+            tracing::warn!(
+                event = "timeout",
+                pid = child_pid,
+                ai_type = cli_type.display_name(),
+                timeout_secs = timeout.unwrap_or_default().as_secs(),
+                "task timed out"
+            );
+            tracing::info!(
+                event = "exit",
+                pid = child_pid,
+                ai_type = cli_type.display_name(),
+                reason = "timeout",
+                "task exited"
+            );
+            run_task_hooks(
+                registry,
+                child_pid,
+                cli_type,
+                &log_path,
+                Some("timed_out".to_owned()),
+                None,
+                false,
+            );
+            return Err(ProcessError::Other(format!(
+                "CLI execution timed out after {:?}",
+                timeout.unwrap_or_default()
+            )));
         }
+        _ = cancelled => {
+            platform::terminate_process_group(child_pid).await;
+            let _ = child.wait().await;
+            child_group.disarm();
+            tracing::info!(
+                event = "cancelled",
+                pid = child_pid,
+                ai_type = cli_type.display_name(),
+                "task cancelled"
+            );
+            tracing::info!(
+                event = "exit",
+                pid = child_pid,
+                ai_type = cli_type.display_name(),
+                reason = "cancelled",
+                "task exited"
+            );
+            if let Some(guard) = registration_guard {
+                let _ = guard.mark_completed(Some("cancelled".to_owned()), None, Utc::now());
+            }
+            run_task_hooks(
+                registry,
+                child_pid,
+                cli_type,
+                &log_path,
+                Some("cancelled".to_owned()),
+                None,
+                false,
+            );
+            return Err(ProcessError::Cancelled {
+                pid: child_pid,
+                message: format!("{} (pid {}) was cancelled", cli_type.display_name(), child_pid),
+            });
+        }
+    };
+
+    child_group.disarm();
+
+    let signal_name = exit_signal_name(&status);
+    let reason = if signal_name.is_some() {
+        "signal"
     } else {
-        child.wait().await?
+        "exited"
     };
+    tracing::info!(
+        event = "exit",
+        pid = child_pid,
+        ai_type = cli_type.display_name(),
+        reason,
+        exit_code = status.code(),
+        signal = signal_name.as_deref(),
+        success = status.success(),
+        "task exited"
+    );
 
     drop(signal_guard);
 
@@ -870,10 +1189,10 @@ async fn execute_cli_internal<S: TaskStorage>(
         // 输出最后的50行
         match &output_strategy {
             OutputStrategy::TailOnly => {
-                // 非 TTY 模式：输出纯文本（无 ANSI 转义码）
+                // 非 TTY 模式：输出纯文本（无 ANSI 转义码）；MCP stdio 模式下改写到 stderr
                 let tail_output = display.get_plain_tail();
                 if !tail_output.is_empty() {
-                    let _ = tokio::io::stdout().write_all(tail_output.as_bytes()).await;
+                    let _ = protocol_stdout::write_user_output(tail_output.as_bytes()).await;
                 }
             }
             OutputStrategy::Mirror => {
@@ -902,12 +1221,28 @@ async fn execute_cli_internal<S: TaskStorage>(
                 "{}_failed_with_exit_code_{code}",
                 if is_capture_mode { "codegen" } else { "cli" }
             )),
-            (false, None) => Some(format!(
-                "{}_failed_without_exit_code",
-                if is_capture_mode { "codegen" } else { "cli" }
-            )),
+            (false, None) => Some(match exit_signal_name(&status) {
+                Some(signal) => format!(
+                    "{}{}{signal}",
+                    if is_capture_mode { "codegen" } else { "cli" },
+                    crate::task_record::SIGNAL_RESULT_MARKER
+                ),
+                None => format!(
+                    "{}_failed_without_exit_code",
+                    if is_capture_mode { "codegen" } else { "cli" }
+                ),
+            }),
         };
-        let _ = guard.mark_completed(result, exit_code, completed_at);
+        let _ = guard.mark_completed(result.clone(), exit_code, completed_at);
+        run_task_hooks(
+            registry,
+            child_pid,
+            cli_type,
+            &log_path,
+            result,
+            exit_code,
+            status.success(),
+        );
     }
 
     // Auto-disable provider on failure (non-zero exit code, non-fallback, non-empty provider)
@@ -959,7 +1294,13 @@ async fn execute_cli_internal<S: TaskStorage>(
         _ => None,
     };
 
-    Ok((extract_exit_code(status), captured_output))
+    Ok(CliRun {
+        pid: child_pid,
+        exit_code: extract_exit_code(status),
+        output: captured_output,
+        provider: (!is_fallback).then_some(provider_name),
+        log_path,
+    })
 }
 
 /// Generate a secure log file path in runtime directory
@@ -1020,10 +1361,8 @@ fn setup_codex_home_for_provider<C>(
     }
 
     // Write a clean auth.json with only the API key from provider env
-    let api_key = provider_config
-        .env
-        .get("OPENAI_API_KEY")
-        .cloned()
+    let api_key = EnvInjector::provider_env(provider_config)
+        .remove("OPENAI_API_KEY")
         .unwrap_or_default();
     let auth_json = if api_key.is_empty() {
         r#"{"OPENAI_API_KEY": null}"#.to_string()
@@ -1198,11 +1537,7 @@ impl StreamMirror {
     async fn write(self, data: &[u8]) -> io::Result<()> {
         use tokio::io::AsyncWriteExt;
         match self {
-            StreamMirror::Stdout => {
-                let mut handle = tokio::io::stdout();
-                handle.write_all(data).await?;
-                handle.flush().await
-            }
+            StreamMirror::Stdout => protocol_stdout::write_user_output(data).await,
             StreamMirror::Stderr => {
                 let mut handle = tokio::io::stderr();
                 handle.write_all(data).await?;
@@ -1388,10 +1723,60 @@ where
     Ok(())
 }
 
+/// 任务进入终态后在后台运行 config.json 的 `task_hooks`，钩子出错不影响任务状态
+fn run_task_hooks<S: TaskStorage>(
+    registry: &Registry<S>,
+    pid: u32,
+    cli_type: &CliType,
+    log_path: &std::path::Path,
+    result: Option<String>,
+    exit_code: Option<i32>,
+    success: bool,
+) {
+    let hooks = TaskHooks::from_user_config();
+    if hooks.is_empty() {
+        return;
+    }
+    // MCP start_task 在注册后才把 UUID 绑定到记录上，命令行任务没有 UUID
+    let task_id = registry
+        .entries()
+        .ok()
+        .and_then(|entries| entries.into_iter().find(|entry| entry.pid == pid))
+        .and_then(|entry| entry.record.task_id)
+        .unwrap_or_else(|| pid.to_string());
+    let outcome = TaskOutcome {
+        task_id,
+        pid,
+        ai_type: cli_type.display_name().to_string(),
+        exit_code,
+        success,
+        result,
+        log_path: log_path.to_string_lossy().into_owned(),
+        completed_at: Utc::now(),
+    };
+    hooks.spawn(outcome);
+}
+
 fn extract_exit_code(status: ExitStatus) -> i32 {
     status.code().unwrap_or(1)
 }
 
+/// 被信号终止时的信号名（如 `SIGKILL`），正常退出返回 None
+#[cfg(unix)]
+fn exit_signal_name(status: &ExitStatus) -> Option<String> {
+    use std::os::unix::process::ExitStatusExt;
+    let signal = status.signal()?;
+    Some(match nix::sys::signal::Signal::try_from(signal) {
+        Ok(signal) => signal.as_str().to_string(),
+        Err(_) => format!("signal_{signal}"),
+    })
+}
+
+#[cfg(not(unix))]
+fn exit_signal_name(_status: &ExitStatus) -> Option<String> {
+    None
+}
+
 struct RegistrationGuard<'a, S: TaskStorage> {
     registry: &'a Registry<S>,
     pid: u32,
@@ -1422,6 +1807,47 @@ impl<'a, S: TaskStorage> RegistrationGuard<'a, S> {
     }
 }
 
+/// 子进程结束前 future 被丢弃时结束整个进程组
+///
+/// `kill_on_drop` 只杀直接子进程；CLI 派生的辅助进程与它同组，需要按组清理。
+/// Drop 中不能 await，有 runtime 时交给后台任务，否则在线程里同步结束。
+struct ChildGroupGuard {
+    pid: u32,
+    armed: bool,
+}
+
+impl ChildGroupGuard {
+    fn new(pid: u32) -> Self {
+        Self { pid, armed: true }
+    }
+
+    /// 子进程已被回收，不再需要清理
+    fn disarm(&mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for ChildGroupGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let pid = self.pid;
+        tracing::info!(
+            pid,
+            "supervised task dropped before exit; terminating its process group"
+        );
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(platform::terminate_process_group(pid));
+            }
+            Err(_) => {
+                std::thread::spawn(move || platform::terminate_process(pid));
+            }
+        }
+    }
+}
+
 impl<S: TaskStorage> Drop for RegistrationGuard<'_, S> {
     fn drop(&mut self) {
         // 注意：TaskStorage trait不提供remove方法
@@ -1435,12 +1861,16 @@ impl<S: TaskStorage> Drop for RegistrationGuard<'_, S> {
 /// This function spawns the target AI CLI (claude/codex/gemini) as a new session
 /// leader using setsid(), giving it terminal control. The parent AIW process exits
 /// immediately after spawning, while a background task applies runtime patches.
+///
+/// With `record`, the CLI runs inside a PTY instead and AIW stays attached until it
+/// exits, teeing the session to `~/.aiw/sessions/` (see [`crate::session_recorder`]).
 pub fn start_interactive_cli<S: TaskStorage>(
-    _registry: &Registry<S>,
+    registry: &Registry<S>,
     cli_type: &CliType,
     provider: Option<String>,
     cli_args: &[String],
     cwd: Option<std::path::PathBuf>,
+    record: bool,
 ) -> Result<i32, ProcessError> {
     // Validate CWD if provided
     if let Some(ref dir) = cwd {
@@ -1461,7 +1891,7 @@ pub fn start_interactive_cli<S: TaskStorage>(
     platform::init_platform();
 
     let (provider_name, provider_config, is_fallback, _provider_manager) =
-        resolve_provider(cli_type, &provider)?;
+        resolve_provider_off_runtime(cli_type, &provider)?;
 
     let cli_command = get_cli_command(cli_type)?;
 
@@ -1483,14 +1913,17 @@ pub fn start_interactive_cli<S: TaskStorage>(
     command.env_remove("CLAUDE_CODE_ENTRYPOINT");
 
     // Platform-specific: create new session with setsid() to get terminal control
+    // (the PTY used for recording already makes the child a session leader)
     #[cfg(unix)]
-    unsafe {
-        command.pre_exec(|| {
-            // Create new session so child becomes session leader
-            // This gives it control of the terminal
-            libc::setsid();
-            Ok(())
-        });
+    if !record {
+        unsafe {
+            command.pre_exec(|| {
+                // Create new session so child becomes session leader
+                // This gives it control of the terminal
+                libc::setsid();
+                Ok(())
+            });
+        }
     }
 
     // Set working directory if provided
@@ -1500,7 +1933,7 @@ pub fn start_interactive_cli<S: TaskStorage>(
 
     // Inject environment variables (skip in fallback mode)
     if !is_fallback {
-        for (key, value) in &provider_config.env {
+        for (key, value) in EnvInjector::provider_env(&provider_config) {
             command.env(key, value);
         }
         // For Codex with third-party providers, isolate CODEX_HOME to avoid
@@ -1511,6 +1944,18 @@ pub fn start_interactive_cli<S: TaskStorage>(
 
     }
 
+    if record {
+        let patch_claude = matches!(cli_type, CliType::Claude);
+        let (exit_code, log_path) =
+            crate::session_recorder::run_recorded(registry, cli_type, &command, |pid| {
+                if patch_claude {
+                    spawn_claude_runtime_patch(pid);
+                }
+            })?;
+        eprintln!("Session recorded to {}", log_path.display());
+        return Ok(exit_code);
+    }
+
     // Spawn the child process
     let child = command.spawn()
         .map_err(|e| ProcessError::Other(format!("Failed to spawn {}: {}", cli_command, e)))?;
@@ -1519,48 +1964,7 @@ pub fn start_interactive_cli<S: TaskStorage>(
 
     // For Claude CLI, apply runtime memory patch in background
     if matches!(cli_type, CliType::Claude) {
-        // Use tokio runtime to spawn background task
-        let _ = std::thread::spawn(move || {
-            // Create a new tokio runtime for this thread
-            let rt = match tokio::runtime::Runtime::new() {
-                Ok(rt) => rt,
-                Err(_) => return,
-            };
-
-            rt.block_on(async {
-                // Give Claude process some time to start up
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-                // Apply runtime patch
-                match RuntimePatcher::new(child_pid) {
-                    Ok(patcher) => {
-                        let cfg = crate::config::PatchConfig::load().unwrap_or_default();
-                        match patcher.apply_max_context_tokens_patch(
-                            cfg.max_context_tokens,
-                            cfg.auto_compact_window,
-                        ) {
-                            Ok(addr) => {
-                                eprintln!(
-                                    "✅ MaxContextTokens unlocked (patch at 0x{:x})",
-                                    addr
-                                );
-                            }
-                            Err(_) => {
-                                // Patch failed - could be wrong version or pattern not found
-                                // Silent failure since user might not need this feature
-                            }
-                        }
-
-                        // AntiTelemetry 内存补丁（独立于 max-token）
-                        apply_antitelemetry_memory_patch_background(&patcher);
-                    }
-                    Err(_) => {
-                        // Process might have already exited or other error
-                        // Silent failure
-                    }
-                }
-            });
-        });
+        spawn_claude_runtime_patch(child_pid);
     }
 
     // Exit immediately with success code
@@ -1568,53 +1972,143 @@ pub fn start_interactive_cli<S: TaskStorage>(
     Ok(0)
 }
 
+/// Apply the Claude runtime memory patches from a background thread.
+fn spawn_claude_runtime_patch(child_pid: u32) {
+    // Use tokio runtime to spawn background task
+    let _ = std::thread::spawn(move || {
+        // Create a new tokio runtime for this thread
+        let rt = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(_) => return,
+        };
+
+        rt.block_on(async {
+            // Give Claude process some time to start up
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+            // Apply runtime patch
+            match RuntimePatcher::new(child_pid) {
+                Ok(patcher) => {
+                    let cfg = crate::config::PatchConfig::load().unwrap_or_default();
+                    match patcher.apply_max_context_tokens_patch(
+                        cfg.max_context_tokens,
+                        cfg.auto_compact_window,
+                    ) {
+                        Ok(addr) => {
+                            tracing::info!("MaxContextTokens unlocked (patch at 0x{:x})", addr);
+                        }
+                        Err(_) => {
+                            // Patch failed - could be wrong version or pattern not found
+                            // Silent failure since user might not need this feature
+                        }
+                    }
+
+                    // AntiTelemetry 内存补丁（独立于 max-token）
+                    apply_antitelemetry_memory_patch_background(&patcher);
+                }
+                Err(_) => {
+                    // Process might have already exited or other error
+                    // Silent failure
+                }
+            }
+        });
+    });
+}
+
 /// Execute multiple CLI processes (for codex|claude|gemini syntax)
 pub async fn execute_multiple_clis<S: TaskStorage>(
     registry: &Registry<S>,
     cli_selector: &crate::cli_type::CliSelector,
     task_prompt: &str,
     provider: Option<String>,
+    model: Option<&str>,
     cli_args: &[String],
     cwd: Option<std::path::PathBuf>,
-) -> Result<Vec<i32>, ProcessError> {
-    let mut exit_codes = Vec::new();
+) -> Result<MultiExecResult, ProcessError> {
+    let mut result = MultiExecResult::default();
 
     for cli_type in &cli_selector.types {
-        let cli_args = cli_type.build_full_access_args_with_cli(task_prompt, cli_args);
+        let mut passthrough = match model {
+            Some(model) => cli_type.model_args(model).map_err(ProcessError::Other)?,
+            None => Vec::new(),
+        };
+        passthrough.extend(cli_args.iter().cloned());
+        let cli_args =
+            cli_type.build_full_access_args_in(task_prompt, &passthrough, cwd.as_deref());
         let os_args: Vec<OsString> = cli_args.into_iter().map(|s| s.into()).collect();
 
-        let exit_code = execute_cli(registry, cli_type, &os_args, provider.clone(), cwd.clone()).await?;
-        exit_codes.push(exit_code);
+        let started = std::time::Instant::now();
+        let run = execute_cli_internal(
+            registry,
+            cli_type,
+            &os_args,
+            provider.clone(),
+            None,
+            interactive_output_strategy(),
+            cwd.clone(),
+            &[],
+            None,
+            None,
+        )
+        .await?;
+        result.outcomes.push(CliExecOutcome {
+            cli_type: cli_type.clone(),
+            provider: run.provider,
+            exit_code: run.exit_code,
+            duration: started.elapsed(),
+            log_path: run.log_path,
+        });
     }
 
-    Ok(exit_codes)
+    Ok(result)
 }
 
 /// Auto 模式故障切换：遍历所有 CLI+Provider 组合，失败自动切换到下一个
 ///
 /// 与 `AutoModeExecutor::execute()` 不同，本函数是异步的且使用 Mirror/TailOnly 输出策略，
-/// 适用于 CLI 直接执行和 MCP 后台任务场景。
+/// 适用于 CLI 直接执行和 MCP 后台任务场景。成功时返回获胜组合及其余组合的 PID，
+/// 调用方据此把任务结果绑定到获胜者。
 pub async fn execute_cli_with_failover<S: TaskStorage>(
     registry: &Registry<S>,
     base: &crate::task_prepare::PreparedTaskBase,
-) -> Result<i32, ProcessError> {
+) -> Result<FailoverOutcome, ProcessError> {
     use crate::auto_mode::config::ExecutionOrderConfig;
-    use crate::auto_mode::CliCooldownManager;
+    use crate::auto_mode::{AutoStrategy, CliCooldownManager};
     use crate::task_prepare::finalize_for_entry;
 
     let entries = ExecutionOrderConfig::get_execution_entries()
         .map_err(|e| ProcessError::Other(format!("Failed to load auto execution config: {}", e)))?;
 
+    let strategy = ExecutionOrderConfig::get_strategy_config().unwrap_or_else(|e| {
+        warn(format!(
+            "Invalid auto_strategy config, using sequential: {}",
+            e
+        ));
+        Default::default()
+    });
+    match strategy.mode {
+        AutoStrategy::Sequential => {}
+        AutoStrategy::Race => {
+            let available = available_auto_entries(&entries)?;
+            return execute_failover_race(registry, base, available, strategy.top_k).await;
+        }
+        AutoStrategy::All => {
+            let available = available_auto_entries(&entries)?;
+            return execute_failover_all(registry, base, available).await;
+        }
+    }
+
     let cooldown = CliCooldownManager::global();
     let mut last_error: Option<String> = None;
     let mut skipped_count = 0;
     let total_count = entries.len();
+    let mut failed_pids = Vec::new();
 
     for entry in &entries {
         let cli_type = match entry.to_cli_type() {
             Some(t) => t,
             None => {
-                eprintln!("[aiw-auto] Invalid CLI type '{}', skipping...", entry.cli);
+                tracing::warn!("[aiw-auto] Invalid CLI type '{}', skipping...", entry.cli);
                 continue;
             }
         };
@@ -1622,7 +2116,7 @@ pub async fn execute_cli_with_failover<S: TaskStorage>(
         // 检查冷却期
         if cooldown.is_in_cooldown(&cli_type, &entry.provider) {
             if let Some(remaining) = cooldown.remaining_cooldown_secs(&cli_type, &entry.provider) {
-                eprintln!(
+                tracing::warn!(
                     "[aiw-auto] {} is in cooldown ({}s remaining), skipping...",
                     entry.display_name(),
                     remaining
@@ -1632,39 +2126,56 @@ pub async fn execute_cli_with_failover<S: TaskStorage>(
             continue;
         }
 
-        eprintln!("[aiw-auto] Trying {}...", entry.display_name());
+        tracing::info!("[aiw-auto] Trying {}...", entry.display_name());
 
         let prepared = finalize_for_entry(base, cli_type.clone(), Some(entry.provider.clone()));
 
-        let result = execute_cli(
+        let result = execute_cli_internal(
             registry,
             &prepared.cli_type,
             &prepared.args,
             prepared.provider,
+            None,
+            interactive_output_strategy(),
             prepared.cwd,
+            &prepared.env,
+            prepared.priority,
+            None,
         )
         .await;
 
         match result {
-            Ok(0) => {
-                eprintln!("[aiw-auto] {} succeeded", entry.display_name());
-                return Ok(0);
+            Ok(run) if run.exit_code == 0 => {
+                tracing::info!("[aiw-auto] {} succeeded", entry.display_name());
+                return Ok(FailoverOutcome {
+                    winner_pid: run.pid,
+                    other_pids: failed_pids,
+                });
             }
-            Ok(exit_code) => {
+            Ok(run) => {
+                failed_pids.push(run.pid);
                 cooldown.mark_failure(&cli_type, &entry.provider);
-                let msg = format!("{} exited with code {}", entry.display_name(), exit_code);
-                eprintln!("[aiw-auto] {} failed (exit code {})", entry.display_name(), exit_code);
+                let msg = format!(
+                    "{} exited with code {}",
+                    entry.display_name(),
+                    run.exit_code
+                );
+                tracing::warn!(
+                    "[aiw-auto] {} failed (exit code {})",
+                    entry.display_name(),
+                    run.exit_code
+                );
                 last_error = Some(msg);
             }
             Err(e) => {
                 cooldown.mark_failure(&cli_type, &entry.provider);
                 let msg = format!("{}: {}", entry.display_name(), e);
-                eprintln!("[aiw-auto] {} error: {}", entry.display_name(), e);
+                tracing::warn!("[aiw-auto] {} error: {}", entry.display_name(), e);
                 last_error = Some(msg);
             }
         }
 
-        eprintln!("[aiw-auto] Trying next entry...");
+        tracing::info!("[aiw-auto] Trying next entry...");
     }
 
     if skipped_count == total_count {
@@ -1679,22 +2190,441 @@ pub async fn execute_cli_with_failover<S: TaskStorage>(
     ))
 }
 
+/// 过滤出未处于冷却期的有效组合
+fn available_auto_entries(
+    entries: &[crate::auto_mode::ExecutionEntry],
+) -> Result<Vec<(crate::auto_mode::ExecutionEntry, CliType)>, ProcessError> {
+    let cooldown = crate::auto_mode::CliCooldownManager::global();
+    let mut available = Vec::new();
+
+    for entry in entries {
+        let Some(cli_type) = entry.to_cli_type() else {
+            tracing::warn!("[aiw-auto] Invalid CLI type '{}', skipping...", entry.cli);
+            continue;
+        };
+        if let Some(remaining) = cooldown.remaining_cooldown_secs(&cli_type, &entry.provider) {
+            tracing::warn!(
+                "[aiw-auto] {} is in cooldown ({}s remaining), skipping...",
+                entry.display_name(),
+                remaining
+            );
+            continue;
+        }
+        available.push((entry.clone(), cli_type));
+    }
+
+    if available.is_empty() {
+        return Err(ProcessError::Other(
+            "All CLI+Provider combinations are in cooldown period. Please wait and try again."
+                .to_string(),
+        ));
+    }
+    Ok(available)
+}
+
+/// 执行结果对应的子进程 PID；启动前就失败的执行没有 PID
+fn attempt_pid(result: &Result<CliExit, ProcessError>) -> Option<u32> {
+    match result {
+        Ok(run) => Some(run.pid),
+        Err(ProcessError::Cancelled { pid, .. }) => Some(*pid),
+        Err(_) => None,
+    }
+}
+
+/// Race 策略：每批并发启动 `top_k` 个组合，第一个成功的获胜，其余被取消
+///
+/// 被取消的组合只记录失败，不进入冷却；整批都失败时继续下一批。
+async fn execute_failover_race<S: TaskStorage>(
+    registry: &Registry<S>,
+    base: &crate::task_prepare::PreparedTaskBase,
+    available: Vec<(crate::auto_mode::ExecutionEntry, CliType)>,
+    top_k: usize,
+) -> Result<FailoverOutcome, ProcessError> {
+    use crate::task_prepare::finalize_for_entry;
+    use futures::stream::{FuturesUnordered, StreamExt};
+
+    let cooldown = crate::auto_mode::CliCooldownManager::global();
+    let mut last_error: Option<String> = None;
+    let mut other_pids = Vec::new();
+
+    for batch in available.chunks(top_k.max(1)) {
+        let names: Vec<String> = batch
+            .iter()
+            .map(|(entry, _)| entry.display_name())
+            .collect();
+        tracing::info!("[aiw-auto] Racing {}...", names.join(", "));
+
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        let mut racers: FuturesUnordered<_> = batch
+            .iter()
+            .map(|(entry, cli_type)| {
+                let prepared =
+                    finalize_for_entry(base, cli_type.clone(), Some(entry.provider.clone()));
+                let cancel = cancel_rx.clone();
+                async move {
+                    let result = execute_cli_cancellable(
+                        registry,
+                        &prepared.cli_type,
+                        &prepared.args,
+                        prepared.provider.clone(),
+                        prepared.cwd.clone(),
+                        &prepared.env,
+                        prepared.priority,
+                        cancel,
+                    )
+                    .await;
+                    (entry, cli_type, result)
+                }
+            })
+            .collect();
+
+        let mut winner: Option<u32> = None;
+        while let Some((entry, cli_type, result)) = racers.next().await {
+            match (result, winner) {
+                (Ok(run), None) if run.exit_code == 0 => {
+                    tracing::info!("[aiw-auto] {} won the race", entry.display_name());
+                    winner = Some(run.pid);
+                    let _ = cancel_tx.send(true);
+                }
+                (Ok(run), Some(_)) if run.exit_code == 0 => {
+                    other_pids.push(run.pid);
+                }
+                // 已有获胜者后结束的组合都视为被取消，不计入冷却
+                (result, Some(_)) => {
+                    other_pids.extend(attempt_pid(&result));
+                    tracing::info!(
+                        "[aiw-auto] {} cancelled (lost the race)",
+                        entry.display_name()
+                    );
+                }
+                (Ok(run), None) => {
+                    other_pids.push(run.pid);
+                    cooldown.mark_failure(cli_type, &entry.provider);
+                    tracing::warn!(
+                        "[aiw-auto] {} failed (exit code {})",
+                        entry.display_name(),
+                        run.exit_code
+                    );
+                    last_error = Some(format!(
+                        "{} exited with code {}",
+                        entry.display_name(),
+                        run.exit_code
+                    ));
+                }
+                (Err(e), None) => {
+                    cooldown.mark_failure(cli_type, &entry.provider);
+                    tracing::warn!("[aiw-auto] {} error: {}", entry.display_name(), e);
+                    last_error = Some(format!("{}: {}", entry.display_name(), e));
+                }
+            }
+        }
+
+        if let Some(winner_pid) = winner {
+            return Ok(FailoverOutcome {
+                winner_pid,
+                other_pids,
+            });
+        }
+        tracing::warn!("[aiw-auto] Whole batch failed, trying next batch...");
+    }
+
+    Err(ProcessError::Other(last_error.unwrap_or_else(|| {
+        "All CLI+Provider combinations failed".to_string()
+    })))
+}
+
+/// All 策略：并发运行所有可用组合，任意一个成功即返回成功
+///
+/// 多个组合成功时，按执行顺序排在最前的一个作为获胜者。
+async fn execute_failover_all<S: TaskStorage>(
+    registry: &Registry<S>,
+    base: &crate::task_prepare::PreparedTaskBase,
+    available: Vec<(crate::auto_mode::ExecutionEntry, CliType)>,
+) -> Result<FailoverOutcome, ProcessError> {
+    use crate::task_prepare::finalize_for_entry;
+
+    let cooldown = crate::auto_mode::CliCooldownManager::global();
+    let names: Vec<String> = available
+        .iter()
+        .map(|(entry, _)| entry.display_name())
+        .collect();
+    tracing::info!("[aiw-auto] Running {} in parallel...", names.join(", "));
+
+    // 发送端保持存活到所有任务结束，All 模式从不取消
+    let (_cancel_tx, cancel_rx) = watch::channel(false);
+    let runs = available.iter().map(|(entry, cli_type)| {
+        let prepared = finalize_for_entry(base, cli_type.clone(), Some(entry.provider.clone()));
+        let cancel = cancel_rx.clone();
+        async move {
+            let result = execute_cli_cancellable(
+                registry,
+                &prepared.cli_type,
+                &prepared.args,
+                prepared.provider.clone(),
+                prepared.cwd.clone(),
+                &prepared.env,
+                prepared.priority,
+                cancel,
+            )
+            .await;
+            (entry, cli_type, result)
+        }
+    });
+
+    let mut winner: Option<u32> = None;
+    let mut other_pids = Vec::new();
+    let mut last_error: Option<String> = None;
+    for (entry, cli_type, result) in futures::future::join_all(runs).await {
+        match result {
+            Ok(run) if run.exit_code == 0 => {
+                tracing::info!("[aiw-auto] {} succeeded", entry.display_name());
+                match winner {
+                    None => winner = Some(run.pid),
+                    Some(_) => other_pids.push(run.pid),
+                }
+            }
+            Ok(run) => {
+                other_pids.push(run.pid);
+                cooldown.mark_failure(cli_type, &entry.provider);
+                tracing::warn!(
+                    "[aiw-auto] {} failed (exit code {})",
+                    entry.display_name(),
+                    run.exit_code
+                );
+                last_error = Some(format!(
+                    "{} exited with code {}",
+                    entry.display_name(),
+                    run.exit_code
+                ));
+            }
+            Err(e) => {
+                cooldown.mark_failure(cli_type, &entry.provider);
+                tracing::warn!("[aiw-auto] {} error: {}", entry.display_name(), e);
+                last_error = Some(format!("{}: {}", entry.display_name(), e));
+            }
+        }
+    }
+
+    if let Some(winner_pid) = winner {
+        return Ok(FailoverOutcome {
+            winner_pid,
+            other_pids,
+        });
+    }
+    Err(ProcessError::Other(last_error.unwrap_or_else(|| {
+        "All CLI+Provider combinations failed".to_string()
+    })))
+}
+
+/// tail 读取时每次向前读取的块大小
+const TAIL_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// tail 读取最多回溯的字节数，避免超长行把整个日志读进内存
+pub const TAIL_MAX_BYTES: u64 = 8 * 1024 * 1024;
+
 /// 读取任务日志文件，支持 tail 截取
 ///
-/// - `tail_lines = Some(n)`: 只返回最后 n 行
+/// - `tail_lines = Some(n)`: 只返回最后 n 行（从文件末尾分块读取，最多 [`TAIL_MAX_BYTES`]）
 /// - `tail_lines = None`: 返回全部内容
+///
+/// 日志按 lossy UTF-8 解码，非法字节替换为 U+FFFD，不会让整个日志不可读。
 pub fn read_task_logs(log_path: &std::path::Path, tail_lines: Option<usize>) -> Result<String, String> {
-    let content = std::fs::read_to_string(log_path)
+    let result = match tail_lines {
+        Some(n) => read_tail_lines(log_path, n, TAIL_MAX_BYTES),
+        None => std::fs::read(log_path).map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+    };
+    result.map_err(|e| format!("Failed to read log file {}: {}", log_path.display(), e))
+}
+
+/// 从文件末尾分块向前读取最后 `n` 行，行切分规则与 `str::lines` 相同
+///
+/// 最多回溯 `max_bytes` 字节。没有读到文件开头时，最前面的不完整行会被丢弃；
+/// 回溯范围内一个换行都没有时保留这段内容，只跳过被截断的 UTF-8 字符。
+pub fn read_tail_lines(log_path: &std::path::Path, n: usize, max_bytes: u64) -> io::Result<String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(log_path)?;
+    let len = file.metadata()?.len();
+    let floor = len.saturating_sub(max_bytes);
+
+    let mut pos = len;
+    let mut chunks: Vec<Vec<u8>> = Vec::new();
+    let mut newlines = 0;
+    let mut trailing_newline = false;
+    while pos > floor {
+        let size = TAIL_CHUNK_SIZE.min(pos - floor);
+        pos -= size;
+        let mut chunk = vec![0u8; size as usize];
+        file.seek(SeekFrom::Start(pos))?;
+        file.read_exact(&mut chunk)?;
+
+        if chunks.is_empty() {
+            trailing_newline = chunk.last() == Some(&b'\n');
+        }
+        newlines += chunk.iter().filter(|&&byte| byte == b'\n').count();
+        chunks.push(chunk);
+        // 末尾换行不构成新行；其余换行达到 n 个即可保证有 n 个完整行
+        if newlines - usize::from(trailing_newline) >= n {
+            break;
+        }
+    }
+
+    let bytes: Vec<u8> = chunks.into_iter().rev().flatten().collect();
+    let start = if pos == 0 {
+        0
+    } else {
+        match bytes.iter().position(|&byte| byte == b'\n') {
+            Some(index) => index + 1,
+            None => bytes
+                .iter()
+                .take_while(|&&byte| byte & 0xC0 == 0x80)
+                .count(),
+        }
+    };
+    let text = String::from_utf8_lossy(&bytes[start..]);
+    let lines: Vec<&str> = text.lines().collect();
+    Ok(lines[lines.len().saturating_sub(n)..].join("\n"))
+}
+
+/// 从 `offset` 开始增量读取日志，返回 (新内容, 下次读取的偏移)
+///
+/// 末尾不完整的 UTF-8 字符留到下次读取；文件被截断（比偏移更短）时从头开始读。
+pub fn read_log_from(log_path: &std::path::Path, offset: u64) -> Result<(String, u64), String> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = std::fs::File::open(log_path)
+        .map_err(|e| format!("Failed to read log file {}: {}", log_path.display(), e))?;
+    let len = file
+        .metadata()
+        .map_err(|e| format!("Failed to read log file {}: {}", log_path.display(), e))?
+        .len();
+    let start = if len < offset { 0 } else { offset };
+
+    let mut bytes = Vec::new();
+    file.seek(SeekFrom::Start(start))
+        .and_then(|_| file.read_to_end(&mut bytes))
         .map_err(|e| format!("Failed to read log file {}: {}", log_path.display(), e))?;
 
-    match tail_lines {
-        Some(n) => {
-            let all_lines: Vec<&str> = content.lines().collect();
-            let start = all_lines.len().saturating_sub(n);
-            Ok(all_lines[start..].join("\n"))
+    let complete = match std::str::from_utf8(&bytes) {
+        Ok(_) => bytes.len(),
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        Err(_) => bytes.len(),
+    };
+    let text = String::from_utf8_lossy(&bytes[..complete]).into_owned();
+    Ok((text, start + complete as u64))
+}
+
+/// marker 策略下结构化结果块的起止标记
+pub const RESULT_START_MARKER: &str = "<<<AIW_RESULT>>>";
+pub const RESULT_END_MARKER: &str = "<<<END_AIW_RESULT>>>";
+
+/// 只在日志末尾这么多字节内查找结果，避免对大日志逐字符尝试解析
+const RESULT_SCAN_BYTES: usize = 256 * 1024;
+
+/// 从任务输出中提取结构化结果的方式
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultExtraction {
+    /// Last top-level JSON object printed by the task.
+    #[default]
+    LastJson,
+    /// Content of the last block between `<<<AIW_RESULT>>>` and `<<<END_AIW_RESULT>>>`.
+    Marker,
+    /// Do not extract; keep the success/failure status string.
+    None,
+}
+
+/// 按策略从输出中提取结果；JSON 统一压缩为单行
+pub fn extract_result(output: &str, strategy: ResultExtraction) -> Option<String> {
+    match strategy {
+        ResultExtraction::LastJson => last_json_object(output).map(|value| value.to_string()),
+        ResultExtraction::Marker => marker_block(output).map(|block| {
+            serde_json::from_str::<serde_json::Value>(block)
+                .map(|value| value.to_string())
+                .unwrap_or_else(|_| block.to_string())
+        }),
+        ResultExtraction::None => None,
+    }
+}
+
+/// 输出中最后一个顶层 JSON 对象（嵌套对象不会被单独选中）
+fn last_json_object(output: &str) -> Option<serde_json::Value> {
+    let mut last = None;
+    let mut pos = 0;
+    while let Some(offset) = output[pos..].find('{') {
+        let start = pos + offset;
+        let mut stream =
+            serde_json::Deserializer::from_str(&output[start..]).into_iter::<serde_json::Value>();
+        match stream.next() {
+            Some(Ok(value @ serde_json::Value::Object(_))) => {
+                pos = start + stream.byte_offset();
+                last = Some(value);
+            }
+            _ => pos = start + 1,
         }
-        None => Ok(content),
     }
+    last
+}
+
+fn marker_block(output: &str) -> Option<&str> {
+    let start = output.rfind(RESULT_START_MARKER)? + RESULT_START_MARKER.len();
+    let end = output[start..].find(RESULT_END_MARKER)? + start;
+    let block = output[start..end].trim();
+    (!block.is_empty()).then_some(block)
+}
+
+/// 读取日志末尾，截断位置落在多字节字符中间时按有损方式解码
+fn read_log_tail(log_path: &std::path::Path, max_bytes: usize) -> Result<String, String> {
+    let bytes = std::fs::read(log_path)
+        .map_err(|e| format!("Failed to read log file {}: {}", log_path.display(), e))?;
+    let start = bytes.len().saturating_sub(max_bytes);
+    Ok(String::from_utf8_lossy(&bytes[start..]).into_owned())
+}
+
+/// 任务成功结束后，从日志中提取结构化结果写入 `TaskRecord::result`
+///
+/// 日志同时包含 stdout 与 stderr。只处理退出码为 0 的任务；未找到结果时
+/// 保留原有的 `success` 状态字符串并返回 `None`。
+pub fn capture_task_result(
+    registry: &Registry<InProcessStorage>,
+    task_id: &str,
+    strategy: ResultExtraction,
+) -> Result<Option<String>, String> {
+    if strategy == ResultExtraction::None {
+        return Ok(None);
+    }
+
+    let (pid, record) = registry
+        .get_by_task_id(task_id)
+        .ok_or_else(|| format!("task_id '{}' not found in registry", task_id))?;
+    if record.exit_code != Some(0) {
+        return Ok(None);
+    }
+
+    let output = read_log_tail(std::path::Path::new(&record.log_path), RESULT_SCAN_BYTES)?;
+    let Some(result) = extract_result(&output, strategy) else {
+        return Ok(None);
+    };
+
+    registry
+        .mark_completed(
+            pid,
+            Some(result.clone()),
+            record.exit_code,
+            record.completed_at.unwrap_or_else(Utc::now),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(Some(result))
 }
 
 /// 停止指定 PID 的任务进程
@@ -1705,8 +2635,16 @@ pub async fn stop_task_process<S: TaskStorage>(
     registry: &Registry<S>,
     pid: u32,
 ) -> Result<(bool, String), String> {
-    // 进程已退出
-    if !platform::process_alive(pid) {
+    // 启动时记录的进程启动时间，用来识别被复用的 PID
+    let start_time = registry
+        .entries()
+        .ok()
+        .and_then(|entries| entries.into_iter().find(|entry| entry.pid == pid))
+        .and_then(|entry| entry.record.process_start_time);
+    let alive = || platform::process_alive_since(pid, start_time);
+
+    // 进程已退出（或 PID 已被其他进程复用）
+    if !alive() {
         registry
             .mark_completed(
                 pid,
@@ -1734,14 +2672,14 @@ pub async fn stop_task_process<S: TaskStorage>(
     // 等待最多 5 秒
     let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(5);
     while tokio::time::Instant::now() < deadline {
-        if !platform::process_alive(pid) {
+        if !alive() {
             break;
         }
         tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
     }
 
     // 仍然存活则 SIGKILL
-    if platform::process_alive(pid) {
+    if alive() {
         #[cfg(unix)]
         {
             use nix::sys::signal::{kill, Signal};
@@ -3,7 +3,7 @@ use flate2::write::GzEncoder;
 use flate2::Compression;
 use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tar::Builder;
 use tracing::{debug, info, warn};
 
@@ -673,6 +673,68 @@ impl ConfigPacker {
         }
     }
 
+    /// Pack an explicit list of entries into a tar.gz: files copied from disk
+    /// plus generated documents, each stored under its archive path.
+    /// Files that no longer exist are skipped; returns the compressed size.
+    pub fn pack_entries<O: AsRef<Path>>(
+        &self,
+        files: &[(String, PathBuf)],
+        documents: &[(String, Vec<u8>)],
+        output_file: O,
+    ) -> SyncResult<u64> {
+        let output_path = output_file.as_ref();
+
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                SyncError::config_packing(format!("Failed to create output directory: {}", e))
+            })?;
+        }
+
+        let file = fs::File::create(output_path).map_err(|e| {
+            SyncError::config_packing(format!("Failed to create output file: {}", e))
+        })?;
+        let mut tar = Builder::new(GzEncoder::new(file, Compression::default()));
+
+        let mtime = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        for (tar_path, content) in documents {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            header.set_cksum();
+            tar.append_data(&mut header, tar_path, content.as_slice())
+                .map_err(|e| {
+                    SyncError::config_packing(format!("Failed to add {} to tar: {}", tar_path, e))
+                })?;
+        }
+
+        for (tar_path, path) in files {
+            if !path.is_file() {
+                debug!("Skipping missing file: {}", path.display());
+                continue;
+            }
+            self.add_file_to_tar(&mut tar, path, tar_path)?;
+        }
+
+        let encoder = tar.into_inner().map_err(|e| {
+            SyncError::config_packing(format!("Failed to finish tar creation: {}", e))
+        })?;
+        let mut file = encoder.finish().map_err(|e| {
+            SyncError::config_packing(format!("Failed to finish compression: {}", e))
+        })?;
+        file.flush().map_err(|e| {
+            SyncError::config_packing(format!("Failed to flush output file: {}", e))
+        })?;
+
+        let metadata = fs::metadata(output_path).map_err(|e| {
+            SyncError::config_packing(format!("Failed to get output file metadata: {}", e))
+        })?;
+        Ok(metadata.len())
+    }
+
     /// Get information about an archive
     pub fn get_archive_info<P: AsRef<Path>>(&self, archive_file: P) -> SyncResult<ArchiveInfo> {
         let archive_path = archive_file.as_ref();
@@ -768,4 +830,43 @@ mod tests {
         assert!(unpacked_root.join("file1.txt").exists());
         assert!(unpacked_root.join("file2.txt").exists());
     }
+
+    #[test]
+    fn test_pack_entries_mixes_files_and_documents() {
+        let source_dir = TempDir::new().unwrap();
+        let log = source_dir.path().join("task.log");
+        fs::write(&log, "line 1\nline 2\n").unwrap();
+        let output_dir = TempDir::new().unwrap();
+        let archive_file = output_dir.path().join("bundle.tar.gz");
+
+        let packer = ConfigPacker::new();
+        packer
+            .pack_entries(
+                &[
+                    ("bundle/logs/task.log".to_string(), log),
+                    (
+                        "bundle/logs/missing.log".to_string(),
+                        source_dir.path().join("missing.log"),
+                    ),
+                ],
+                &[("bundle/task.json".to_string(), b"{}".to_vec())],
+                &archive_file,
+            )
+            .unwrap();
+
+        assert_eq!(
+            packer.get_archive_info(&archive_file).unwrap().file_count,
+            2
+        );
+        let unpacked = output_dir.path().join("out");
+        packer.unpack_archive(&archive_file, &unpacked).unwrap();
+        assert_eq!(
+            fs::read_to_string(unpacked.join("bundle/logs/task.log")).unwrap(),
+            "line 1\nline 2\n"
+        );
+        assert_eq!(
+            fs::read_to_string(unpacked.join("bundle/task.json")).unwrap(),
+            "{}"
+        );
+    }
 }
@@ -2,12 +2,13 @@ use super::config_packer::ConfigPacker;
 use super::directory_hasher::{DirectoryHash, DirectoryHasher};
 use super::error::{SyncError, SyncResult as ErrorResult};
 use super::google_drive_service::GoogleDriveService;
-use super::oauth_client::OAuthClient;
+use super::oauth_client::{token_needs_refresh, OAuthClient};
 use super::smart_oauth::SmartOAuthAuthenticator;
 use super::sync_config_manager::SyncConfigManager;
-use crate::config::{AUTH_DIRECTORY, AUTH_FILE_NAME};
-use crate::error::AgenticWardenError;
-use chrono::{Duration, Utc};
+use crate::config::AUTH_FILE_NAME;
+use crate::error::{errors, AgenticWardenError, SyncOperation};
+use crate::utils::config_paths::aiw_config_dir;
+use chrono::{DateTime, Duration, Utc};
 use dialoguer::Confirm;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -33,6 +34,47 @@ pub struct ConfigSyncManager {
     config_packer: ConfigPacker,
     drive_service: Option<GoogleDriveService>,
     temp_archive_path: Option<std::path::PathBuf>,
+    conflict_policy: PullConflictPolicy,
+}
+
+/// How a pull reacts when local changes are newer than the remote backup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PullConflictPolicy {
+    /// Ask before overwriting (requires an interactive terminal).
+    Prompt,
+    /// Overwrite local changes (`--force`).
+    Force,
+    /// Leave the local directory untouched.
+    #[default]
+    Skip,
+}
+
+/// Local changes made since the last sync that a pull would overwrite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullConflict {
+    pub last_synced: DateTime<Utc>,
+    pub remote_modified: Option<DateTime<Utc>>,
+}
+
+/// Detect whether pulling would clobber local changes that are newer than the remote backup.
+///
+/// The local copy counts as changed when its hash differs from the one stored at the last
+/// sync; the remote counts as older when it has not been modified since that sync.
+pub fn detect_pull_conflict(
+    local: Option<&DirectoryHash>,
+    stored: Option<&DirectoryHash>,
+    remote_modified: Option<DateTime<Utc>>,
+) -> Option<PullConflict> {
+    let (local, stored) = (local?, stored?);
+    if local.hash == stored.hash {
+        return None;
+    }
+
+    let remote_is_older = remote_modified.map_or(true, |modified| modified <= stored.timestamp);
+    remote_is_older.then(|| PullConflict {
+        last_synced: stored.timestamp,
+        remote_modified,
+    })
 }
 
 #[derive(Debug, Clone)]
@@ -61,6 +103,14 @@ pub enum PushProgressEvent {
         file_name: String,
         size: Option<u64>,
     },
+    /// Upload failed transiently and is about to be retried.
+    Retrying {
+        directory: String,
+        attempt: u32,
+        max_attempts: u32,
+        delay_ms: u64,
+        reason: String,
+    },
     /// Uploaded archive is being verified and hashes updated.
     Verifying { directory: String },
     /// Directory was skipped (no changes or missing path).
@@ -84,6 +134,20 @@ pub enum PullProgressEvent {
         file_name: Option<String>,
         size: Option<u64>,
     },
+    /// Download failed transiently and is about to be retried.
+    Retrying {
+        directory: String,
+        attempt: u32,
+        max_attempts: u32,
+        delay_ms: u64,
+        reason: String,
+    },
+    /// Local changes are newer than the remote backup.
+    Conflict {
+        directory: String,
+        last_synced: DateTime<Utc>,
+        remote_modified: Option<DateTime<Utc>>,
+    },
     /// Downloaded archive is being decompressed.
     Decompressing { directory: String },
     /// Restoring files from the archive to the target directory.
@@ -121,12 +185,38 @@ impl ConfigSyncManager {
             config_packer: ConfigPacker::new(),
             drive_service,
             temp_archive_path: None,
+            conflict_policy: PullConflictPolicy::default(),
         })
     }
 
+    /// Choose how pulls handle local changes newer than the remote backup.
+    pub fn set_conflict_policy(&mut self, policy: PullConflictPolicy) {
+        self.conflict_policy = policy;
+    }
+
     #[allow(dead_code)]
     pub async fn push_all(&mut self) -> ErrorResult<SyncSummary> {
-        let directories = self.config_manager.get_sync_directories()?;
+        self.push_all_with_observer(|_| {}).await
+    }
+
+    /// Push every configured directory, reporting per-directory progress.
+    pub async fn push_all_with_observer<F>(&mut self, observer: F) -> ErrorResult<SyncSummary>
+    where
+        F: FnMut(PushProgressEvent),
+    {
+        self.push_selected_with_observer(&[], observer).await
+    }
+
+    /// Push only the requested directories (all configured ones when empty).
+    pub async fn push_selected_with_observer<F>(
+        &mut self,
+        requested: &[String],
+        mut observer: F,
+    ) -> ErrorResult<SyncSummary>
+    where
+        F: FnMut(PushProgressEvent),
+    {
+        let directories = self.config_manager.select_sync_directories(requested)?;
 
         if directories.is_empty() {
             return Ok(SyncSummary {
@@ -152,8 +242,22 @@ impl ConfigSyncManager {
         };
 
         // Process each directory
-        for directory_path in directories {
-            let result = self.push_directory(&directory_path).await?;
+        let total = directories.len();
+        for (index, directory_path) in directories.iter().enumerate() {
+            let result = self
+                .push_directory_with_observer(directory_path, |event| {
+                    observer(match event {
+                        PushProgressEvent::StartingDirectory { directory, .. } => {
+                            PushProgressEvent::StartingDirectory {
+                                directory,
+                                index,
+                                total,
+                            }
+                        }
+                        other => other,
+                    })
+                })
+                .await?;
 
             if result.changed {
                 summary.changed_directories += 1;
@@ -240,7 +344,10 @@ impl ConfigSyncManager {
 
         sync_result.changed = true;
 
-        // Ensure Google Drive service is available
+        // Ensure Google Drive service is available with a token that will not expire mid-transfer
+        if self.drive_service.is_some() {
+            self.ensure_fresh_token().await?;
+        }
         let service = self
             .drive_service
             .as_mut()
@@ -299,7 +406,18 @@ impl ConfigSyncManager {
         }
 
         // Upload new file
-        let uploaded_file = service.upload_file(&archive_path, Some(&folder_id)).await?;
+        let uploaded_file = service
+            .upload_file_with_retry(&archive_path, Some(&folder_id), |notice| {
+                observer(PushProgressEvent::Retrying {
+                    directory: directory_name.to_string(),
+                    attempt: notice.attempt,
+                    max_attempts: notice.max_attempts,
+                    delay_ms: notice.delay.as_millis() as u64,
+                    reason: notice.reason.clone(),
+                });
+            })
+            .await
+            .map_err(|err| SyncError::upload_failed(format!("{:#}", err)))?;
         observer(PushProgressEvent::Verifying {
             directory: directory_name.to_string(),
         });
@@ -321,7 +439,27 @@ impl ConfigSyncManager {
 
     #[allow(dead_code)]
     pub async fn pull_all(&mut self) -> ErrorResult<SyncSummary> {
-        let directories = self.config_manager.get_sync_directories()?;
+        self.pull_all_with_observer(|_| {}).await
+    }
+
+    /// Pull every configured directory, reporting per-directory progress.
+    pub async fn pull_all_with_observer<F>(&mut self, observer: F) -> ErrorResult<SyncSummary>
+    where
+        F: FnMut(PullProgressEvent),
+    {
+        self.pull_selected_with_observer(&[], observer).await
+    }
+
+    /// Pull only the requested directories (all configured ones when empty).
+    pub async fn pull_selected_with_observer<F>(
+        &mut self,
+        requested: &[String],
+        mut observer: F,
+    ) -> ErrorResult<SyncSummary>
+    where
+        F: FnMut(PullProgressEvent),
+    {
+        let directories = self.config_manager.select_sync_directories(requested)?;
 
         if directories.is_empty() {
             return Ok(SyncSummary {
@@ -347,8 +485,22 @@ impl ConfigSyncManager {
         };
 
         // Process each directory
-        for directory_path in directories {
-            let result = self.pull_directory(&directory_path).await?;
+        let total = directories.len();
+        for (index, directory_path) in directories.iter().enumerate() {
+            let result = self
+                .pull_directory_with_observer(directory_path, |event| {
+                    observer(match event {
+                        PullProgressEvent::StartingDirectory { directory, .. } => {
+                            PullProgressEvent::StartingDirectory {
+                                directory,
+                                index,
+                                total,
+                            }
+                        }
+                        other => other,
+                    })
+                })
+                .await?;
 
             if result.changed {
                 summary.changed_directories += 1;
@@ -400,7 +552,10 @@ impl ConfigSyncManager {
             message: String::new(),
         };
 
-        // Ensure Google Drive service is available
+        // Ensure Google Drive service is available with a token that will not expire mid-transfer
+        if self.drive_service.is_some() {
+            self.ensure_fresh_token().await?;
+        }
         let service = self
             .drive_service
             .as_mut()
@@ -471,6 +626,41 @@ impl ConfigSyncManager {
             }
         };
 
+        // Refuse to clobber local edits made since the last sync unless allowed
+        let local_hash = if path.exists() {
+            Some(self.directory_hasher.calculate_hash(path)?)
+        } else {
+            None
+        };
+        let stored_hash = self.config_manager.get_directory_hash(directory_name)?;
+        if let Some(conflict) = detect_pull_conflict(
+            local_hash.as_ref(),
+            stored_hash.as_ref(),
+            backup_file.modified_time.or(backup_file.created_time),
+        ) {
+            observer(PullProgressEvent::Conflict {
+                directory: directory_name.to_string(),
+                last_synced: conflict.last_synced,
+                remote_modified: conflict.remote_modified,
+            });
+            if !self.confirm_overwrite(directory_name, &conflict)? {
+                let reason =
+                    "Local changes are newer than the remote backup (use --force to overwrite)"
+                        .to_string();
+                observer(PullProgressEvent::Skipped {
+                    directory: directory_name.to_string(),
+                    reason: reason.clone(),
+                });
+                sync_result.message = reason;
+                return Ok(sync_result);
+            }
+        }
+
+        let service = self
+            .drive_service
+            .as_mut()
+            .ok_or(SyncError::authentication_required())?;
+
         let reported_size_i64 = backup_file.size.unwrap_or_default();
         if reported_size_i64 > 0 {
             sync_result.file_size = Some(reported_size_i64 as u64);
@@ -501,8 +691,17 @@ impl ConfigSyncManager {
 
         // Download the file
         service
-            .download_file(&backup_file.id, &local_archive_path)
-            .await?;
+            .download_file_with_retry(&backup_file.id, &local_archive_path, |notice| {
+                observer(PullProgressEvent::Retrying {
+                    directory: directory_name.to_string(),
+                    attempt: notice.attempt,
+                    max_attempts: notice.max_attempts,
+                    delay_ms: notice.delay.as_millis() as u64,
+                    reason: notice.reason.clone(),
+                });
+            })
+            .await
+            .map_err(|err| SyncError::download_failed(format!("{:#}", err)))?;
         sync_result.message.push_str(" Downloaded backup file");
 
         observer(PullProgressEvent::Decompressing {
@@ -546,6 +745,36 @@ impl ConfigSyncManager {
         Ok(sync_result)
     }
 
+    fn confirm_overwrite(
+        &self,
+        directory_name: &str,
+        conflict: &PullConflict,
+    ) -> ErrorResult<bool> {
+        match self.conflict_policy {
+            PullConflictPolicy::Force => Ok(true),
+            PullConflictPolicy::Skip => Ok(false),
+            PullConflictPolicy::Prompt => {
+                let remote = conflict
+                    .remote_modified
+                    .map(|time| time.to_rfc3339())
+                    .unwrap_or_else(|| "unknown".to_string());
+                let prompt = format!(
+                    "'{}' changed locally since the last sync ({}) but the remote backup is older ({}). Overwrite local changes?",
+                    directory_name,
+                    conflict.last_synced.to_rfc3339(),
+                    remote
+                );
+                Confirm::new()
+                    .with_prompt(prompt)
+                    .default(false)
+                    .interact()
+                    .map_err(|err| {
+                        SyncError::sync_config(format!("Failed to read confirmation: {}", err))
+                    })
+            }
+        }
+    }
+
     pub fn get_sync_status(&self) -> ErrorResult<HashMap<String, DirectoryHash>> {
         self.config_manager.get_all_directory_hashes()
     }
@@ -556,7 +785,7 @@ impl ConfigSyncManager {
 
     pub async fn authenticate_google_drive(&mut self) -> ErrorResult<()> {
         if self.drive_service.is_some() {
-            return Ok(());
+            return self.ensure_fresh_token().await;
         }
 
         let mut stored_auth = Self::load_stored_auth_state()?.unwrap_or_default();
@@ -662,6 +891,14 @@ impl ConfigSyncManager {
             }
         }
 
+        // Start with a token that will outlive the next operation
+        let oauth_client = match stored_auth.access_token.clone() {
+            Some(access_token) if !token_needs_refresh(stored_expiry(&stored_auth), Utc::now()) => {
+                oauth_client.with_access_token(access_token, stored_expiry(&stored_auth))
+            }
+            _ => Self::refresh_stored_token(&mut stored_auth).await?,
+        };
+
         let drive_service = GoogleDriveService::new(oauth_client)
             .await
             .map_err(|err| {
@@ -911,6 +1148,58 @@ impl ConfigSyncManager {
 }
 
 impl ConfigSyncManager {
+    /// Refresh the access token when it is within the safety margin of expiring,
+    /// so long uploads and downloads do not fail halfway with an expired token.
+    async fn ensure_fresh_token(&mut self) -> ErrorResult<()> {
+        let Some(mut stored_auth) = Self::load_stored_auth_state()? else {
+            return Ok(());
+        };
+        if stored_auth.refresh_token.is_none()
+            || !token_needs_refresh(stored_expiry(&stored_auth), Utc::now())
+        {
+            return Ok(());
+        }
+
+        let oauth_client = Self::refresh_stored_token(&mut stored_auth).await?;
+        let drive_service = GoogleDriveService::new(oauth_client).await.map_err(|err| {
+            error!(target: "aiw::sync", "Failed to initialize Google Drive service: {}", err);
+            Self::auth_failed_error()
+        })?;
+        self.drive_service = Some(drive_service);
+        Ok(())
+    }
+
+    /// Exchange the stored refresh token for a new access token and persist it to auth.json
+    async fn refresh_stored_token(auth: &mut StoredAuthState) -> ErrorResult<OAuthClient> {
+        let mut oauth_client = OAuthClient::new(
+            auth.client_id.clone(),
+            auth.client_secret.clone(),
+            auth.refresh_token.clone(),
+        )
+        .with_scopes(Self::default_scopes());
+
+        let token_response = oauth_client.refresh_access_token().await.map_err(|err| {
+            error!(target: "aiw::sync", "Failed to refresh Google Drive access token: {}", err);
+            errors::sync_error(
+                SyncOperation::GoogleDriveAuth,
+                format!(
+                    "Failed to refresh the Google Drive access token ({}). Re-authorize Google Drive access and try again.",
+                    err
+                ),
+            )
+        })?;
+
+        auth.access_token = Some(token_response.access_token.clone());
+        if let Some(refresh_token) = token_response.refresh_token.clone() {
+            auth.refresh_token = Some(refresh_token);
+        }
+        auth.expires_at = oauth_client.expires_at().map(|expiry| expiry.timestamp());
+        Self::save_auth_state(auth)?;
+
+        info!(target: "aiw::sync", "Refreshed Google Drive access token");
+        Ok(oauth_client)
+    }
+
     fn auth_failed_error() -> AgenticWardenError {
         SyncError::google_drive("Authentication failed, please retry".to_string())
     }
@@ -920,8 +1209,7 @@ impl ConfigSyncManager {
     }
 
     fn auth_file_path() -> ErrorResult<PathBuf> {
-        let home_dir = dirs::home_dir().ok_or_else(Self::auth_failed_error)?;
-        let auth_dir = home_dir.join(AUTH_DIRECTORY);
+        let auth_dir = aiw_config_dir().ok_or_else(Self::auth_failed_error)?;
 
         if let Err(err) = fs::create_dir_all(&auth_dir) {
             error!(
@@ -1038,6 +1326,11 @@ impl ConfigSyncManager {
     }
 }
 
+fn stored_expiry(auth: &StoredAuthState) -> Option<DateTime<Utc>> {
+    auth.expires_at
+        .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1058,6 +1351,40 @@ mod tests {
         // Should not panic but return an error result
         assert!(result.is_ok());
     }
+
+    fn hash(value: &str, timestamp: DateTime<Utc>) -> DirectoryHash {
+        DirectoryHash {
+            hash: value.to_string(),
+            file_count: 1,
+            total_size: 1,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn pull_conflict_requires_local_changes_and_older_remote() {
+        let synced_at = Utc::now() - Duration::hours(2);
+        let stored = hash("synced", synced_at);
+        let edited = hash("edited", Utc::now());
+        let older_remote = Some(synced_at - Duration::minutes(5));
+
+        let conflict = detect_pull_conflict(Some(&edited), Some(&stored), older_remote).unwrap();
+        assert_eq!(conflict.last_synced, synced_at);
+        assert_eq!(conflict.remote_modified, older_remote);
+
+        // Unchanged local copy or a remote updated after the last sync pulls normally
+        let unchanged = hash("synced", Utc::now());
+        assert!(detect_pull_conflict(Some(&unchanged), Some(&stored), older_remote).is_none());
+        let newer_remote = Some(synced_at + Duration::minutes(5));
+        assert!(detect_pull_conflict(Some(&edited), Some(&stored), newer_remote).is_none());
+    }
+
+    #[test]
+    fn pull_conflict_needs_a_local_copy_and_sync_baseline() {
+        let edited = hash("edited", Utc::now());
+        assert!(detect_pull_conflict(None, Some(&edited), None).is_none());
+        assert!(detect_pull_conflict(Some(&edited), None, None).is_none());
+    }
 }
 
 /// Copy directory contents recursively
@@ -6,11 +6,98 @@ use chrono::{DateTime, Utc};
 use mime_guess::from_path;
 use reqwest::multipart::{Form, Part};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
 use std::path::Path;
-use tracing::{debug, info};
+use std::time::Duration;
+use tracing::{debug, info, warn};
 
 use super::oauth_client::OAuthClient;
+use crate::error::{errors, RecoveryStrategy, SyncOperation};
+
+/// 单次上传/下载请求的超时，避免网络卡住时同步无限挂起
+pub const TRANSFER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Drive API 返回的非成功响应，保留状态码用于区分是否可重试
+#[derive(Debug, Clone)]
+pub struct DriveApiError {
+    pub status: reqwest::StatusCode,
+    pub message: String,
+}
+
+impl DriveApiError {
+    /// 只有服务端错误值得重试；认证失败（401/403）和其他 4xx 重试也不会成功
+    pub fn is_retryable(&self) -> bool {
+        self.status.is_server_error()
+    }
+}
+
+impl fmt::Display for DriveApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.status)
+    }
+}
+
+impl std::error::Error for DriveApiError {}
+
+/// 判断传输失败是否可重试：5xx、超时和网络连接错误可重试，其余直接失败
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        if let Some(api) = cause.downcast_ref::<DriveApiError>() {
+            api.is_retryable()
+        } else if let Some(http) = cause.downcast_ref::<reqwest::Error>() {
+            http.is_timeout() || http.is_connect() || http.is_request() || http.is_body()
+        } else {
+            false
+        }
+    })
+}
+
+/// 上传/下载的重试策略，取自对应同步操作的 `RecoveryStrategy`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn for_operation(operation: SyncOperation) -> Self {
+        match errors::sync_error(operation, String::new()).recovery_strategy() {
+            RecoveryStrategy::Retry {
+                max_attempts,
+                base_delay_ms,
+            } => Self {
+                max_attempts: max_attempts.max(1),
+                base_delay: Duration::from_millis(base_delay_ms),
+            },
+            _ => Self {
+                max_attempts: 1,
+                base_delay: Duration::ZERO,
+            },
+        }
+    }
+
+    /// 第 `attempt` 次尝试失败后的等待时间，按 2 的幂指数退避
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        self.base_delay.saturating_mul(1 << exponent)
+    }
+
+    /// 第 `attempt` 次尝试失败后，若还应重试则返回等待时间
+    pub fn next_delay(&self, attempt: u32, err: &anyhow::Error) -> Option<Duration> {
+        (attempt < self.max_attempts && is_retryable(err)).then(|| self.delay_after(attempt))
+    }
+}
+
+/// 重试前发给调用方的通知
+#[derive(Debug, Clone)]
+pub struct RetryNotice {
+    /// 即将进行的尝试序号（从 2 开始）
+    pub attempt: u32,
+    pub max_attempts: u32,
+    pub delay: Duration,
+    pub reason: String,
+}
 
 /// Google Drive File Information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -264,17 +351,23 @@ impl GoogleDriveService {
             .http_client
             .post(upload_url)
             .header("Authorization", format!("Bearer {}", access_token))
+            .timeout(TRANSFER_TIMEOUT)
             .multipart(form)
             .send()
             .await
             .context("Failed to upload file")?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response
                 .text()
                 .await
                 .context("Failed to read error response")?;
-            return Err(anyhow!("Failed to upload file: {}", error_text));
+            return Err(DriveApiError {
+                status,
+                message: format!("Failed to upload file: {}", error_text),
+            }
+            .into());
         }
 
         let file_response: DriveFileResponse = response
@@ -291,11 +384,47 @@ impl GoogleDriveService {
         Ok(file_id)
     }
 
-    /// Upload file from local path
+    /// Upload file from local path, retrying transient failures
     pub async fn upload_file(
         &mut self,
         file_path: &Path,
         folder_id: Option<&str>,
+    ) -> Result<DriveFile> {
+        self.upload_file_with_retry(file_path, folder_id, |_| {})
+            .await
+    }
+
+    /// 带指数退避重试的上传，每次重试前调用 `on_retry`
+    pub async fn upload_file_with_retry<F>(
+        &mut self,
+        file_path: &Path,
+        folder_id: Option<&str>,
+        mut on_retry: F,
+    ) -> Result<DriveFile>
+    where
+        F: FnMut(&RetryNotice),
+    {
+        let policy = RetryPolicy::for_operation(SyncOperation::Upload);
+        let mut attempt = 1;
+        loop {
+            match self.upload_file_once(file_path, folder_id).await {
+                Ok(file) => return Ok(file),
+                Err(err) => match policy.next_delay(attempt, &err) {
+                    Some(delay) => {
+                        attempt += 1;
+                        notify_retry(&mut on_retry, &policy, attempt, delay, &err);
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => return Err(give_up(err, attempt)),
+                },
+            }
+        }
+    }
+
+    async fn upload_file_once(
+        &mut self,
+        file_path: &Path,
+        folder_id: Option<&str>,
     ) -> Result<DriveFile> {
         let file_name = file_path
             .file_name()
@@ -330,16 +459,22 @@ impl GoogleDriveService {
             .http_client
             .get(&download_url)
             .header("Authorization", format!("Bearer {}", access_token))
+            .timeout(TRANSFER_TIMEOUT)
             .send()
             .await
             .context("Failed to download file")?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if !status.is_success() {
             let error_text = response
                 .text()
                 .await
                 .context("Failed to read error response")?;
-            return Err(anyhow!("Failed to download file: {}", error_text));
+            return Err(DriveApiError {
+                status,
+                message: format!("Failed to download file: {}", error_text),
+            }
+            .into());
         }
 
         let bytes = response
@@ -354,8 +489,40 @@ impl GoogleDriveService {
         Ok(bytes.to_vec())
     }
 
-    /// Download file to local path
+    /// Download file to local path, retrying transient failures
     pub async fn download_file(&mut self, file_id: &str, output_path: &Path) -> Result<()> {
+        self.download_file_with_retry(file_id, output_path, |_| {})
+            .await
+    }
+
+    /// 带指数退避重试的下载，每次重试前调用 `on_retry`
+    pub async fn download_file_with_retry<F>(
+        &mut self,
+        file_id: &str,
+        output_path: &Path,
+        mut on_retry: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&RetryNotice),
+    {
+        let policy = RetryPolicy::for_operation(SyncOperation::Download);
+        let mut attempt = 1;
+        loop {
+            match self.download_file_once(file_id, output_path).await {
+                Ok(()) => return Ok(()),
+                Err(err) => match policy.next_delay(attempt, &err) {
+                    Some(delay) => {
+                        attempt += 1;
+                        notify_retry(&mut on_retry, &policy, attempt, delay, &err);
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => return Err(give_up(err, attempt)),
+                },
+            }
+        }
+    }
+
+    async fn download_file_once(&mut self, file_id: &str, output_path: &Path) -> Result<()> {
         info!("Downloading file to: {:?}", output_path);
 
         let content = self.download_file_content(file_id).await?;
@@ -745,6 +912,35 @@ impl GoogleDriveService {
     }
 }
 
+fn notify_retry<F>(
+    on_retry: &mut F,
+    policy: &RetryPolicy,
+    attempt: u32,
+    delay: Duration,
+    err: &anyhow::Error,
+) where
+    F: FnMut(&RetryNotice),
+{
+    warn!(
+        "Drive transfer failed, retrying in {:?} (attempt {}/{}): {:#}",
+        delay, attempt, policy.max_attempts, err
+    );
+    on_retry(&RetryNotice {
+        attempt,
+        max_attempts: policy.max_attempts,
+        delay,
+        reason: format!("{:#}", err),
+    });
+}
+
+fn give_up(err: anyhow::Error, attempts: u32) -> anyhow::Error {
+    if attempts > 1 {
+        err.context(format!("Giving up after {} attempts", attempts))
+    } else {
+        err
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -770,6 +966,47 @@ mod tests {
         assert_eq!(drive_file.mime_type, "text/plain");
     }
 
+    fn api_error(code: u16) -> anyhow::Error {
+        DriveApiError {
+            status: reqwest::StatusCode::from_u16(code).unwrap(),
+            message: "Failed to upload file".to_string(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn only_server_errors_are_retryable() {
+        assert!(is_retryable(&api_error(500)));
+        assert!(is_retryable(&api_error(503)));
+        assert!(!is_retryable(&api_error(401)));
+        assert!(!is_retryable(&api_error(403)));
+        assert!(!is_retryable(&api_error(404)));
+        assert!(!is_retryable(&anyhow!("Invalid file name")));
+        assert!(is_retryable(&api_error(502).context("while syncing")));
+    }
+
+    #[test]
+    fn transfer_policy_follows_sync_recovery_strategy() {
+        let policy = RetryPolicy::for_operation(SyncOperation::Upload);
+        assert_eq!(policy.max_attempts, 3);
+        assert_eq!(policy.base_delay, Duration::from_millis(2000));
+        assert_eq!(RetryPolicy::for_operation(SyncOperation::Download), policy);
+        assert_eq!(
+            RetryPolicy::for_operation(SyncOperation::ConfigPacking).max_attempts,
+            1
+        );
+    }
+
+    #[test]
+    fn retry_delays_back_off_exponentially_until_attempts_run_out() {
+        let policy = RetryPolicy::for_operation(SyncOperation::Upload);
+        let err = api_error(500);
+        assert_eq!(policy.next_delay(1, &err), Some(Duration::from_secs(2)));
+        assert_eq!(policy.next_delay(2, &err), Some(Duration::from_secs(4)));
+        assert_eq!(policy.next_delay(3, &err), None);
+        assert_eq!(policy.next_delay(1, &api_error(403)), None);
+    }
+
     // Note: Integration tests with real Google Drive API require OAuth setup
     // These would be in the separate integration test files
 }
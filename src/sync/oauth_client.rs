@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tracing::{debug, info};
@@ -93,6 +94,21 @@ pub struct OAuthClient {
     config: OAuthConfig,
     /// Path to the file where OAuth tokens are persisted
     auth_file_path: PathBuf,
+    /// When the cached access token expires
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// Refresh access tokens this long before they expire
+pub const TOKEN_REFRESH_MARGIN_SECS: i64 = 60;
+
+/// Whether a token expiring at `expires_at` should be refreshed at `now`.
+///
+/// Tokens with unknown expiry are always refreshed.
+pub fn token_needs_refresh(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    match expires_at {
+        Some(expires_at) => expires_at - now <= Duration::seconds(TOKEN_REFRESH_MARGIN_SECS),
+        None => true,
+    }
 }
 
 impl OAuthClient {
@@ -124,9 +140,26 @@ impl OAuthClient {
         Self {
             config,
             auth_file_path,
+            expires_at: None,
         }
     }
 
+    /// Seed the client with a previously issued access token
+    pub fn with_access_token(
+        mut self,
+        access_token: String,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.config.access_token = Some(access_token);
+        self.expires_at = expires_at;
+        self
+    }
+
+    /// When the cached access token expires, if known
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.expires_at
+    }
+
     /// Create OAuth client with scopes
     pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
         self.config.scopes = scopes;
@@ -233,6 +266,8 @@ impl OAuthClient {
                 self.config.refresh_token = token_response.refresh_token.clone();
             }
             self.config.expires_in = token_response.expires_in;
+            self.expires_at =
+                Some(Utc::now() + Duration::seconds(token_response.expires_in as i64));
 
             // Persist tokens to disk
             if let Err(e) = self.save() {
@@ -282,6 +317,13 @@ impl OAuthClient {
 
     /// Get authenticated access token
     pub async fn access_token(&mut self) -> Result<String> {
+        // Reuse the cached token until it is about to expire
+        if let Some(access_token) = &self.config.access_token {
+            if !token_needs_refresh(self.expires_at, Utc::now()) {
+                return Ok(access_token.clone());
+            }
+        }
+
         // If we have a refresh token, try to use it
         if let Some(_refresh_token) = &self.config.refresh_token {
             match self.refresh_access_token().await {
@@ -471,4 +513,27 @@ mod tests {
         .with_scopes(vec![]);
         assert!(invalid_client3.validate_config().is_err());
     }
+
+    #[test]
+    fn tokens_are_refreshed_within_the_safety_margin() {
+        let now = Utc::now();
+        assert!(token_needs_refresh(None, now));
+        assert!(token_needs_refresh(Some(now - Duration::seconds(5)), now));
+        assert!(token_needs_refresh(Some(now + Duration::seconds(30)), now));
+        assert!(!token_needs_refresh(Some(now + Duration::minutes(10)), now));
+    }
+
+    #[tokio::test]
+    async fn cached_access_token_is_reused_until_near_expiry() {
+        let mut client = OAuthClient::new("id".to_string(), "secret".to_string(), None)
+            .with_access_token(
+                "cached".to_string(),
+                Some(Utc::now() + Duration::minutes(30)),
+            );
+        assert_eq!(client.access_token().await.unwrap(), "cached");
+
+        let mut expiring = OAuthClient::new("id".to_string(), "secret".to_string(), None)
+            .with_access_token("stale".to_string(), Some(Utc::now()));
+        assert!(expiring.access_token().await.is_err());
+    }
 }
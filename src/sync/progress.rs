@@ -0,0 +1,300 @@
+//! 同步命令的终端进度显示
+//!
+//! 每个目录一行进度条，随 `PushProgressEvent` / `PullProgressEvent` 推进各阶段。
+
+use super::config_sync_manager::{PullProgressEvent, PushProgressEvent};
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 每个目录经历的阶段数：push 为打包/上传/校验，pull 为下载/解压/恢复
+const STAGE_COUNT: u64 = 3;
+
+/// 单个事件对应的进度条状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageUpdate {
+    /// 推进到第 `position` 个阶段并显示消息
+    Advance { position: u64, message: String },
+    /// 仅更新消息（如重试），不改变阶段
+    Message(String),
+    /// 等待用户确认（如拉取冲突），暂停 spinner 以免覆盖提示
+    Paused(String),
+    /// 目录处理结束
+    Finish(String),
+}
+
+/// 把 push 事件映射为所属目录和进度更新
+pub fn push_update(event: PushProgressEvent) -> (String, StageUpdate) {
+    match event {
+        PushProgressEvent::StartingDirectory { directory, .. } => (
+            directory,
+            StageUpdate::Advance {
+                position: 0,
+                message: "checking for changes".to_string(),
+            },
+        ),
+        PushProgressEvent::Compressing { directory } => (
+            directory,
+            StageUpdate::Advance {
+                position: 0,
+                message: "compressing".to_string(),
+            },
+        ),
+        PushProgressEvent::Uploading {
+            directory, size, ..
+        } => (
+            directory,
+            StageUpdate::Advance {
+                position: 1,
+                message: transfer_message("uploading", size),
+            },
+        ),
+        PushProgressEvent::Retrying {
+            directory,
+            attempt,
+            max_attempts,
+            delay_ms,
+            reason,
+        } => (
+            directory,
+            StageUpdate::Message(retry_message(attempt, max_attempts, delay_ms, &reason)),
+        ),
+        PushProgressEvent::Verifying { directory } => (
+            directory,
+            StageUpdate::Advance {
+                position: 2,
+                message: "verifying".to_string(),
+            },
+        ),
+        PushProgressEvent::Skipped { directory, reason } => (
+            directory,
+            StageUpdate::Finish(format!("skipped: {}", reason)),
+        ),
+        PushProgressEvent::Completed { directory } => {
+            (directory, StageUpdate::Finish("uploaded".to_string()))
+        }
+    }
+}
+
+/// 把 pull 事件映射为所属目录和进度更新
+pub fn pull_update(event: PullProgressEvent) -> (String, StageUpdate) {
+    match event {
+        PullProgressEvent::StartingDirectory { directory, .. } => (
+            directory,
+            StageUpdate::Advance {
+                position: 0,
+                message: "looking up backup".to_string(),
+            },
+        ),
+        PullProgressEvent::Downloading {
+            directory, size, ..
+        } => (
+            directory,
+            StageUpdate::Advance {
+                position: 0,
+                message: transfer_message("downloading", size),
+            },
+        ),
+        PullProgressEvent::Retrying {
+            directory,
+            attempt,
+            max_attempts,
+            delay_ms,
+            reason,
+        } => (
+            directory,
+            StageUpdate::Message(retry_message(attempt, max_attempts, delay_ms, &reason)),
+        ),
+        PullProgressEvent::Conflict { directory, .. } => (
+            directory,
+            StageUpdate::Paused("local changes are newer than the backup".to_string()),
+        ),
+        PullProgressEvent::Decompressing { directory } => (
+            directory,
+            StageUpdate::Advance {
+                position: 1,
+                message: "decompressing".to_string(),
+            },
+        ),
+        PullProgressEvent::Restoring {
+            directory,
+            files_restored,
+            total_files,
+        } => {
+            let message = match (files_restored, total_files) {
+                (Some(done), Some(total)) => format!("restoring {}/{} files", done, total),
+                _ => "restoring".to_string(),
+            };
+            (
+                directory,
+                StageUpdate::Advance {
+                    position: 2,
+                    message,
+                },
+            )
+        }
+        PullProgressEvent::Skipped { directory, reason } => (
+            directory,
+            StageUpdate::Finish(format!("skipped: {}", reason)),
+        ),
+        PullProgressEvent::Completed { directory } => {
+            (directory, StageUpdate::Finish("restored".to_string()))
+        }
+    }
+}
+
+fn transfer_message(verb: &str, size: Option<u64>) -> String {
+    match size {
+        Some(bytes) => format!("{} {}", verb, HumanBytes(bytes)),
+        None => verb.to_string(),
+    }
+}
+
+fn retry_message(attempt: u32, max_attempts: u32, delay_ms: u64, reason: &str) -> String {
+    format!(
+        "retrying {}/{} in {:.1}s: {}",
+        attempt,
+        max_attempts,
+        delay_ms as f64 / 1000.0,
+        reason
+    )
+}
+
+/// 多目录进度条，按目录名分配各自的进度条
+pub struct SyncProgress {
+    multi: MultiProgress,
+    bars: HashMap<String, ProgressBar>,
+}
+
+impl SyncProgress {
+    pub fn new() -> Self {
+        Self::with_target(ProgressDrawTarget::stderr())
+    }
+
+    pub fn with_target(target: ProgressDrawTarget) -> Self {
+        Self {
+            multi: MultiProgress::with_draw_target(target),
+            bars: HashMap::new(),
+        }
+    }
+
+    pub fn on_push(&mut self, event: PushProgressEvent) {
+        let (directory, update) = push_update(event);
+        self.apply(&directory, update);
+    }
+
+    pub fn on_pull(&mut self, event: PullProgressEvent) {
+        let (directory, update) = pull_update(event);
+        self.apply(&directory, update);
+    }
+
+    /// 结束所有仍在进行的进度条（例如中途出错）
+    pub fn finish(&mut self) {
+        for bar in self.bars.values() {
+            if !bar.is_finished() {
+                bar.abandon();
+            }
+        }
+    }
+
+    fn apply(&mut self, directory: &str, update: StageUpdate) {
+        let bar = self.bar(directory);
+        match update {
+            StageUpdate::Advance { position, message } => {
+                bar.enable_steady_tick(Duration::from_millis(100));
+                bar.set_position(position);
+                bar.set_message(message);
+            }
+            StageUpdate::Message(message) => bar.set_message(message),
+            StageUpdate::Paused(message) => {
+                bar.disable_steady_tick();
+                bar.set_message(message);
+            }
+            StageUpdate::Finish(message) => {
+                bar.set_position(STAGE_COUNT);
+                bar.finish_with_message(message);
+            }
+        }
+    }
+
+    fn bar(&mut self, directory: &str) -> ProgressBar {
+        if let Some(bar) = self.bars.get(directory) {
+            return bar.clone();
+        }
+
+        let bar = self.multi.add(ProgressBar::new(STAGE_COUNT));
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} {prefix:<10} [{bar:20.cyan/blue}] {pos}/{len} {msg}")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+        bar.set_prefix(directory.to_string());
+        self.bars.insert(directory.to_string(), bar.clone());
+        bar
+    }
+
+    #[cfg(test)]
+    fn position(&self, directory: &str) -> Option<u64> {
+        self.bars.get(directory).map(|bar| bar.position())
+    }
+}
+
+impl Default for SyncProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_events_advance_through_stages() {
+        let (directory, update) = push_update(PushProgressEvent::Uploading {
+            directory: ".claude".to_string(),
+            file_name: ".claude.tar.gz".to_string(),
+            size: Some(2048),
+        });
+        assert_eq!(directory, ".claude");
+        assert_eq!(
+            update,
+            StageUpdate::Advance {
+                position: 1,
+                message: "uploading 2.00 KiB".to_string(),
+            }
+        );
+
+        let (_, update) = push_update(PushProgressEvent::Retrying {
+            directory: ".claude".to_string(),
+            attempt: 2,
+            max_attempts: 3,
+            delay_ms: 2000,
+            reason: "Failed to upload file (503 Service Unavailable)".to_string(),
+        });
+        assert_eq!(
+            update,
+            StageUpdate::Message(
+                "retrying 2/3 in 2.0s: Failed to upload file (503 Service Unavailable)".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn each_directory_gets_its_own_bar() {
+        let mut progress = SyncProgress::with_target(ProgressDrawTarget::hidden());
+        progress.on_pull(PullProgressEvent::Decompressing {
+            directory: ".codex".to_string(),
+        });
+        progress.on_pull(PullProgressEvent::Skipped {
+            directory: ".gemini".to_string(),
+            reason: "No backup found".to_string(),
+        });
+        progress.finish();
+
+        assert_eq!(progress.position(".codex"), Some(1));
+        assert_eq!(progress.position(".gemini"), Some(STAGE_COUNT));
+        assert_eq!(progress.position(".claude"), None);
+    }
+}
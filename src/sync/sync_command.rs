@@ -1,23 +1,35 @@
-use super::config_sync_manager::ConfigSyncManager;
+use super::config_sync_manager::{ConfigSyncManager, PullConflictPolicy};
 use super::error::{SyncError, SyncResult};
+use super::progress::SyncProgress;
 use crate::error::AgenticWardenError;
 use console::Term;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{HumanBytes, ProgressBar, ProgressStyle};
 
 /// Handle sync commands
-pub async fn handle_sync_command(command: &str, config_name: Option<String>) -> SyncResult<i32> {
+///
+/// Without a configuration name, push/pull sync each configured directory
+/// individually and render per-directory progress. `force` lets a pull overwrite
+/// local changes that are newer than the remote backup without asking.
+pub async fn handle_sync_command(
+    command: &str,
+    config_name: Option<String>,
+    force: bool,
+) -> SyncResult<i32> {
     let mut sync_cmd = SyncCommand::new()?;
 
-    match command {
-        "push" => sync_cmd.execute_push(config_name).await,
-        "pull" => sync_cmd.execute_pull(config_name).await,
-        "status" => sync_cmd.execute_status().await,
-        "reset" => {
+    match (command, config_name) {
+        ("push", None) => sync_cmd.execute_push_directories(&[]).await,
+        ("pull", None) => sync_cmd.execute_pull_directories(&[], force).await,
+        ("push", config_name) => sync_cmd.execute_push(config_name).await,
+        ("pull", config_name) => sync_cmd.execute_pull(config_name).await,
+        ("status", _) => sync_cmd.execute_status().await,
+        ("list-dirs", _) => sync_cmd.execute_list_dirs(),
+        ("reset", _) => {
             // Reset sync state
             eprintln!("Reset command not yet implemented");
             Ok(0)
         }
-        _ => Err(SyncError::sync_config(format!(
+        (command, _) => Err(SyncError::sync_config(format!(
             "Unknown sync command: {}",
             command
         ))),
@@ -35,6 +47,130 @@ impl SyncCommand {
         })
     }
 
+    /// Authenticate with Google Drive, returning `false` after reporting an auth failure
+    async fn authenticate(&mut self, term: &Term) -> SyncResult<bool> {
+        term.write_line("🔐 Authenticating with Google Drive...")?;
+        if let Err(e) = self.manager.authenticate_google_drive().await {
+            if let AgenticWardenError::Auth {
+                message, provider, ..
+            } = &e
+            {
+                if provider == "google_drive" {
+                    term.write_line("🚫 Google Drive authentication failed:")?;
+                    term.write_line(&format!("   {}", message))?;
+                    term.write_line("")?;
+                    term.write_line("This app uses built-in OAuth credentials.")?;
+                    term.write_line("Please ensure you have a Google account and try again.")?;
+                    term.write_line("")?;
+                    term.write_line("The error might be temporary. Please try again later.")?;
+                    return Ok(false);
+                }
+            }
+            return Err(e);
+        }
+        term.write_line("✅ Authentication successful!")?;
+        term.write_line("")?;
+        Ok(true)
+    }
+
+    /// Push configured directories (all when `directories` is empty), showing a
+    /// progress bar per directory
+    pub async fn execute_push_directories(&mut self, directories: &[String]) -> SyncResult<i32> {
+        let term = Term::stdout();
+        // Validate targets before prompting for authentication
+        self.manager
+            .config_manager
+            .select_sync_directories(directories)?;
+
+        term.write_line("🚀 Starting directory sync push...")?;
+        term.write_line("")?;
+        if !self.authenticate(&term).await? {
+            return Ok(1);
+        }
+
+        let mut progress = SyncProgress::new();
+        let result = self
+            .manager
+            .push_selected_with_observer(directories, |event| progress.on_push(event))
+            .await;
+        progress.finish();
+        let summary = result?;
+        term.write_line("")?;
+
+        term.write_line("📊 Push Summary:")?;
+        term.write_line(&format!("   Directories: {}", summary.total_directories))?;
+        term.write_line(&format!("   Changed: {}", summary.changed_directories))?;
+        term.write_line(&format!(
+            "   Uploaded: {} ({})",
+            summary.uploaded_files,
+            HumanBytes(summary.total_bytes_uploaded)
+        ))?;
+        Ok(0)
+    }
+
+    /// Pull configured directories (all when `directories` is empty), showing a
+    /// progress bar per directory
+    pub async fn execute_pull_directories(
+        &mut self,
+        directories: &[String],
+        force: bool,
+    ) -> SyncResult<i32> {
+        let term = Term::stdout();
+        self.manager
+            .config_manager
+            .select_sync_directories(directories)?;
+        let policy = if force {
+            PullConflictPolicy::Force
+        } else if term.is_term() {
+            PullConflictPolicy::Prompt
+        } else {
+            PullConflictPolicy::Skip
+        };
+        self.manager.set_conflict_policy(policy);
+
+        term.write_line("🚀 Starting directory sync pull...")?;
+        term.write_line("")?;
+        if !self.authenticate(&term).await? {
+            return Ok(1);
+        }
+
+        let mut progress = SyncProgress::new();
+        let result = self
+            .manager
+            .pull_selected_with_observer(directories, |event| progress.on_pull(event))
+            .await;
+        progress.finish();
+        let summary = result?;
+        term.write_line("")?;
+
+        term.write_line("📊 Pull Summary:")?;
+        term.write_line(&format!("   Directories: {}", summary.total_directories))?;
+        term.write_line(&format!("   Restored: {}", summary.changed_directories))?;
+        Ok(0)
+    }
+
+    /// List the directories configured for sync
+    pub fn execute_list_dirs(&self) -> SyncResult<i32> {
+        let term = Term::stdout();
+        let directories = self.manager.config_manager.list_sync_directories()?;
+
+        if directories.is_empty() {
+            term.write_line("No directories configured for sync.")?;
+            return Ok(0);
+        }
+
+        term.write_line("Configured sync directories:")?;
+        for (configured, expanded) in directories {
+            let marker = if std::path::Path::new(&expanded).exists() {
+                "✓"
+            } else {
+                "✗"
+            };
+            term.write_line(&format!("  {} {} ({})", marker, configured, expanded))?;
+        }
+        Ok(0)
+    }
+
     /// Execute push command with a configuration name
     pub async fn execute_push(&mut self, config_name: Option<String>) -> SyncResult<i32> {
         let term = Term::stdout();
@@ -92,27 +228,9 @@ impl SyncCommand {
         }
         term.write_line("")?;
 
-        term.write_line("🔐 Authenticating with Google Drive...")?;
-        if let Err(e) = self.manager.authenticate_google_drive().await {
-            if let AgenticWardenError::Auth {
-                message, provider, ..
-            } = &e
-            {
-                if provider == "google_drive" {
-                    term.write_line("🚫 Google Drive authentication failed:")?;
-                    term.write_line(&format!("   {}", message))?;
-                    term.write_line("")?;
-                    term.write_line("This app uses built-in OAuth credentials.")?;
-                    term.write_line("Please ensure you have a Google account and try again.")?;
-                    term.write_line("")?;
-                    term.write_line("The error might be temporary. Please try again later.")?;
-                    return Ok(1);
-                }
-            }
-            return Err(e);
+        if !self.authenticate(&term).await? {
+            return Ok(1);
         }
-        term.write_line("✅ Authentication successful!")?;
-        term.write_line("")?;
 
         term.write_line("🔍 Checking for existing configuration...")?;
         let existing_config = self.manager.verify_named_config(&config_name).await?;
@@ -215,27 +333,9 @@ impl SyncCommand {
         term.write_line(&format!("📦 Configuration name: '{}'", config_name))?;
         term.write_line("")?;
 
-        term.write_line("🔐 Authenticating with Google Drive...")?;
-        if let Err(e) = self.manager.authenticate_google_drive().await {
-            if let AgenticWardenError::Auth {
-                message, provider, ..
-            } = &e
-            {
-                if provider == "google_drive" {
-                    term.write_line("🚫 Google Drive authentication failed:")?;
-                    term.write_line(&format!("   {}", message))?;
-                    term.write_line("")?;
-                    term.write_line("This app uses built-in OAuth credentials.")?;
-                    term.write_line("Please ensure you have a Google account and try again.")?;
-                    term.write_line("")?;
-                    term.write_line("The error might be temporary. Please try again later.")?;
-                    return Ok(1);
-                }
-            }
-            return Err(e);
+        if !self.authenticate(&term).await? {
+            return Ok(1);
         }
-        term.write_line("✅ Authentication successful!")?;
-        term.write_line("")?;
 
         let progress = ProgressBar::new(3);
         progress.set_style(
@@ -8,6 +8,7 @@ pub enum NetworkStatus {
     Unknown,
 }
 use crate::sync::error::{SyncError, SyncResult};
+use crate::utils::config_paths::aiw_config_dir;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -69,11 +70,9 @@ impl Default for SyncState {
 
 /// Resolve the default sync file path within the aiw directory.
 pub fn default_sync_file_path() -> SyncResult<PathBuf> {
-    let home_dir = dirs::home_dir().ok_or_else(|| {
+    let warden_dir = aiw_config_dir().ok_or_else(|| {
         SyncError::sync_config("Could not determine the home directory".to_string())
     })?;
-
-    let warden_dir = home_dir.join(".aiw");
     fs::create_dir_all(&warden_dir).map_err(|err| {
         SyncError::sync_config(format!("Failed to create config directory: {err}"))
     })?;
@@ -2,6 +2,7 @@
 use super::directory_hasher::DirectoryHash;
 use super::error::{SyncError, SyncResult};
 use super::sync_config::{SyncConfig, SyncData, SyncState};
+use crate::utils::config_paths::aiw_config_dir;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
@@ -12,11 +13,9 @@ pub struct SyncConfigManager {
 
 impl SyncConfigManager {
     pub fn new() -> SyncResult<Self> {
-        let home_dir = dirs::home_dir()
+        let warden_dir = aiw_config_dir()
             .ok_or_else(|| SyncError::sync_config("Could not find home directory".to_string()))?;
 
-        let warden_dir = home_dir.join(".aiw");
-
         // Create directory if it doesn't exist
         fs::create_dir_all(&warden_dir).map_err(|e| {
             SyncError::sync_config(format!("Failed to create aiw directory: {}", e))
@@ -193,12 +192,79 @@ impl SyncConfigManager {
         Ok(expanded_dirs)
     }
 
+    /// Configured directories as written in sync.json, paired with their expanded paths
+    pub fn list_sync_directories(&self) -> SyncResult<Vec<(String, String)>> {
+        let sync_data = self.load_sync_data()?;
+        sync_data
+            .config
+            .directories
+            .into_iter()
+            .map(|dir| {
+                let expanded = self.expand_path(&dir)?;
+                Ok((dir, expanded))
+            })
+            .collect()
+    }
+
+    /// Resolve CLI-provided directories against the configured set.
+    ///
+    /// An empty request selects every configured directory.
+    pub fn select_sync_directories(&self, requested: &[String]) -> SyncResult<Vec<String>> {
+        let configured = self.list_sync_directories()?;
+        if requested.is_empty() {
+            return Ok(configured
+                .into_iter()
+                .map(|(_, expanded)| expanded)
+                .collect());
+        }
+        select_directories(&configured, requested)
+    }
+
     pub fn reset_state(&self) -> SyncResult<()> {
         let default_state = SyncState::default();
         self.save_state(&default_state)
     }
 }
 
+/// Match each requested directory by configured spelling, expanded path or
+/// directory name (with or without the leading dot).
+fn select_directories(
+    configured: &[(String, String)],
+    requested: &[String],
+) -> SyncResult<Vec<String>> {
+    let mut selected: Vec<String> = Vec::new();
+    for request in requested {
+        let request = request.trim_end_matches('/');
+        let matched = configured.iter().find(|(raw, expanded)| {
+            let name = Path::new(expanded)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            raw == request
+                || expanded == request
+                || name == request
+                || name.trim_start_matches('.') == request
+        });
+
+        match matched {
+            Some((_, expanded)) => {
+                if !selected.contains(expanded) {
+                    selected.push(expanded.clone());
+                }
+            }
+            None => {
+                let valid: Vec<&str> = configured.iter().map(|(raw, _)| raw.as_str()).collect();
+                return Err(SyncError::sync_config(format!(
+                    "Unknown sync directory '{}'. Configured directories: {}",
+                    request,
+                    valid.join(", ")
+                )));
+            }
+        }
+    }
+    Ok(selected)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,4 +317,30 @@ mod tests {
         let unchanged = manager.expand_path("/absolute/path").unwrap();
         assert_eq!(unchanged, "/absolute/path");
     }
+
+    fn configured() -> Vec<(String, String)> {
+        vec![
+            ("~/.claude".to_string(), "/home/u/.claude".to_string()),
+            ("~/.codex".to_string(), "/home/u/.codex".to_string()),
+        ]
+    }
+
+    #[test]
+    fn selects_directories_by_name_path_or_configured_spelling() {
+        let requested = vec![
+            "codex".to_string(),
+            "~/.claude".to_string(),
+            "/home/u/.codex/".to_string(),
+        ];
+        let selected = select_directories(&configured(), &requested).unwrap();
+        assert_eq!(selected, vec!["/home/u/.codex", "/home/u/.claude"]);
+    }
+
+    #[test]
+    fn unknown_directory_lists_the_configured_set() {
+        let err = select_directories(&configured(), &[".gemini".to_string()]).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("Unknown sync directory '.gemini'"));
+        assert!(message.contains("~/.claude, ~/.codex"));
+    }
 }
@@ -0,0 +1,305 @@
+//! 任务诊断包导出
+//!
+//! CLI (`aiw task export`) 和 MCP (`export_task`) 共用：把任务日志、序列化的
+//! `TaskRecord`、解析后的命令行、注入的环境变量名（值一律脱敏）和 worktree
+//! 信息打成一个 tar.gz，便于附在 bug 报告里。
+
+use crate::sync::config_packer::ConfigPacker;
+use crate::task_record::TaskRecord;
+use crate::utils::config_paths::ConfigPaths;
+use anyhow::{bail, Context, Result};
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+const REDACTED: &str = "<redacted>";
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportSummary {
+    /// Path of the written tarball.
+    pub path: String,
+    /// Archive paths of the bundled files.
+    pub entries: Vec<String>,
+    /// Compressed size in bytes.
+    pub compressed_bytes: u64,
+}
+
+/// 未指定输出路径时使用 `~/.aiw/exports/task-<id>-<时间戳>.tar.gz`
+pub fn default_output_path(pid: u32, record: &TaskRecord) -> Result<PathBuf> {
+    Ok(exports_dir()?.join(format!(
+        "{}-{}.tar.gz",
+        bundle_name(pid, record),
+        Utc::now().format("%Y%m%dT%H%M%S")
+    )))
+}
+
+/// 校验 MCP 客户端给出的输出路径
+///
+/// 客户端不受信任，只允许写到 `~/.aiw/exports` 或任务的 worktree 内；
+/// 相对路径按 `~/.aiw/exports` 解析。
+pub fn checked_output_path(record: &TaskRecord, requested: &str) -> Result<PathBuf> {
+    let mut roots = vec![exports_dir()?];
+    if let Some(worktree) = &record.worktree_info {
+        roots.push(PathBuf::from(&worktree.path));
+    }
+    resolve_within(&roots, Path::new(requested))
+}
+
+fn exports_dir() -> Result<PathBuf> {
+    Ok(ConfigPaths::new()?.config_dir.join("exports"))
+}
+
+/// `requested` 必须落在某个 `roots` 之内（相对路径以第一个为基准），
+/// 不允许 `..`，已存在的上级目录按真实路径比较以排除符号链接逃逸
+fn resolve_within(roots: &[PathBuf], requested: &Path) -> Result<PathBuf> {
+    if requested
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
+        bail!("Export path {} must not contain '..'", requested.display());
+    }
+    let output = if requested.is_absolute() {
+        requested.to_path_buf()
+    } else {
+        roots[0].join(requested)
+    };
+    if output.file_name().is_none() {
+        bail!("Export path {} has no file name", output.display());
+    }
+
+    let real_parent = output.parent().and_then(existing_ancestor_canonical);
+    let allowed = roots.iter().any(|root| {
+        if !output.starts_with(root) {
+            return false;
+        }
+        match (&real_parent, root.canonicalize()) {
+            (Some(parent), Ok(root)) => parent.starts_with(root),
+            // 根目录尚未创建：其下不可能有已存在的符号链接
+            _ => true,
+        }
+    });
+    if !allowed {
+        let roots: Vec<String> = roots.iter().map(|r| r.display().to_string()).collect();
+        bail!(
+            "Export path {} is outside the allowed directories ({})",
+            output.display(),
+            roots.join(", ")
+        );
+    }
+    Ok(output)
+}
+
+/// 最近一个已存在祖先目录的真实路径
+fn existing_ancestor_canonical(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|ancestor| ancestor.exists())
+        .and_then(|ancestor| ancestor.canonicalize().ok())
+}
+
+/// 把任务打包到 `output`
+///
+/// 不覆盖已有文件：`output` 已存在时直接报错。
+pub fn export_task(pid: u32, record: &TaskRecord, output: &Path) -> Result<ExportSummary> {
+    if output.exists() {
+        bail!(
+            "{} already exists; refusing to overwrite it",
+            output.display()
+        );
+    }
+    let root = bundle_name(pid, record);
+    let documents = bundle_documents(pid, record)?
+        .into_iter()
+        .map(|(name, content)| (format!("{}/{}", root, name), content))
+        .collect::<Vec<_>>();
+
+    let log_path = PathBuf::from(&record.log_path);
+    let log_name = log_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "task.log".to_string());
+    let files = vec![(format!("{}/logs/{}", root, log_name), log_path)];
+
+    let compressed_bytes = ConfigPacker::new()
+        .pack_entries(&files, &documents, output)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    let mut entries: Vec<String> = documents.into_iter().map(|(name, _)| name).collect();
+    entries.extend(
+        files
+            .into_iter()
+            .filter(|(_, path)| path.is_file())
+            .map(|(name, _)| name),
+    );
+    Ok(ExportSummary {
+        path: output.display().to_string(),
+        entries,
+        compressed_bytes,
+    })
+}
+
+fn bundle_name(pid: u32, record: &TaskRecord) -> String {
+    match &record.task_id {
+        Some(task_id) => format!("task-{}", task_id),
+        None => format!("task-{}", pid),
+    }
+}
+
+/// 包内生成的文本文件（文件名 → 内容）
+fn bundle_documents(pid: u32, record: &TaskRecord) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut task = serde_json::to_value(record)?;
+    if let Some(object) = task.as_object_mut() {
+        object.insert("pid".to_string(), pid.into());
+    }
+
+    let mut documents = vec![
+        ("task.json".to_string(), serde_json::to_vec_pretty(&task)?),
+        ("command.txt".to_string(), command_text(record).into_bytes()),
+        ("env.txt".to_string(), env_text(record).into_bytes()),
+    ];
+    if let Some(worktree) = &record.worktree_info {
+        documents.push((
+            "worktree.json".to_string(),
+            serde_json::to_vec_pretty(worktree)?,
+        ));
+    }
+    Ok(documents)
+}
+
+fn command_text(record: &TaskRecord) -> String {
+    let command_line = record.full_command_line();
+    if !command_line.is_empty() {
+        let quoted: Vec<String> = command_line.iter().map(|arg| shell_quote(arg)).collect();
+        return format!("{}\n", quoted.join(" "));
+    }
+    match &record.session {
+        Some(session) => format!("{}\n", session.command),
+        None => "# command line was not recorded for this task\n".to_string(),
+    }
+}
+
+/// 只列变量名，值统一替换为 `<redacted>`
+fn env_text(record: &TaskRecord) -> String {
+    let mut text = String::new();
+    match &record.provider {
+        Some(provider) => text.push_str(&format!("# provider: {}\n", provider)),
+        None => text.push_str("# provider: none\n"),
+    }
+    for name in &record.provider_env_names {
+        text.push_str(&format!("{}={}\n", name, REDACTED));
+    }
+    if !record.env_var_names.is_empty() {
+        text.push_str("# task env (overrides provider values)\n");
+        for name in &record.env_var_names {
+            text.push_str(&format!("{}={}\n", name, REDACTED));
+        }
+    }
+    text
+}
+
+fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@,+%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task_record::WorktreeInfo;
+    use std::fs;
+
+    #[test]
+    fn bundle_contains_log_metadata_and_redacted_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("4242.log");
+        fs::write(&log_path, "boom\n").unwrap();
+
+        let mut record = TaskRecord::new(
+            Utc::now(),
+            "4242".to_string(),
+            log_path.to_string_lossy().into_owned(),
+            Some(1),
+        );
+        record.task_id = Some("abc".to_string());
+        record.command_line = vec![
+            "claude".to_string(),
+            "-p".to_string(),
+            "fix the bug's cause".to_string(),
+        ];
+        record.provider = Some("glm".to_string());
+        record.provider_env_names = vec!["ANTHROPIC_API_KEY".to_string()];
+        record.env_var_names = vec!["RUST_LOG".to_string()];
+        record.worktree_info = Some(WorktreeInfo {
+            path: "/tmp/wt".to_string(),
+            branch: "aiw/abc".to_string(),
+            commit: "deadbeef".to_string(),
+        });
+
+        let output = dir.path().join("bundle.tar.gz");
+        let summary = export_task(4242, &record, &output).unwrap();
+        assert_eq!(summary.entries.len(), 5);
+        assert!(summary.compressed_bytes > 0);
+
+        let unpacked = dir.path().join("out");
+        ConfigPacker::new()
+            .unpack_archive(&output, &unpacked)
+            .unwrap();
+        let root = unpacked.join("task-abc");
+        assert_eq!(
+            fs::read_to_string(root.join("logs/4242.log")).unwrap(),
+            "boom\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("command.txt")).unwrap(),
+            "claude -p 'fix the bug'\\''s cause'\n"
+        );
+        let env = fs::read_to_string(root.join("env.txt")).unwrap();
+        assert!(env.contains("# provider: glm"));
+        assert!(env.contains("ANTHROPIC_API_KEY=<redacted>"));
+        assert!(env.contains("RUST_LOG=<redacted>"));
+        let task: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(root.join("task.json")).unwrap()).unwrap();
+        assert_eq!(task["pid"], 4242);
+        assert_eq!(task["task_id"], "abc");
+        assert!(root.join("worktree.json").is_file());
+
+        // 不覆盖已有文件
+        let err = export_task(4242, &record, &output).unwrap_err();
+        assert!(err.to_string().contains("refusing to overwrite"), "{err}");
+    }
+
+    #[test]
+    fn output_paths_are_confined_to_allowed_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let exports = dir.path().join("exports");
+        let worktree = dir.path().join("wt");
+        fs::create_dir_all(&worktree).unwrap();
+        let roots = vec![exports.clone(), worktree.clone()];
+
+        assert_eq!(
+            resolve_within(&roots, Path::new("bundle.tar.gz")).unwrap(),
+            exports.join("bundle.tar.gz")
+        );
+        let inside = worktree.join("debug/bundle.tar.gz");
+        assert_eq!(resolve_within(&roots, &inside).unwrap(), inside);
+
+        assert!(resolve_within(&roots, Path::new("/etc/passwd")).is_err());
+        assert!(resolve_within(&roots, Path::new("../escape.tar.gz")).is_err());
+        assert!(resolve_within(&roots, &worktree.join("../escape.tar.gz")).is_err());
+
+        #[cfg(unix)]
+        {
+            let outside = tempfile::tempdir().unwrap();
+            std::os::unix::fs::symlink(outside.path(), worktree.join("link")).unwrap();
+            assert!(resolve_within(&roots, &worktree.join("link/bundle.tar.gz")).is_err());
+        }
+    }
+}
@@ -0,0 +1,456 @@
+//! 任务结束钩子
+//!
+//! `~/.aiw/config.json` 的 `task_hooks` 在任务进入终态时通知外部：
+//!
+//! ```json
+//! {
+//!   "task_hooks": {
+//!     "on_complete": "notify-send aiw \"$AIW_TASK_ID: $AIW_TASK_RESULT\"",
+//!     "on_failure": "https://ci.example.com/hooks/aiw",
+//!     "timeout_secs": 10
+//!   }
+//! }
+//! ```
+//!
+//! `on_complete` 在每个任务结束时运行，`on_failure` 只在失败（非零退出、被取消、超时）时运行。
+//! 以 `http://`/`https://` 开头的值按 webhook 处理，POST JSON payload；其余值交给
+//! `sh -c`（Windows 为 `cmd /C`），payload 写入 stdin，同时以 `AIW_*` 环境变量提供。
+//! 钩子的输出被丢弃，不会混入 MCP stdio 协议流；出错或超时只记 warn 日志，不改变任务状态。
+//!
+//! 钩子在独立的 tokio 任务中运行，不拖慢任务结束；匹配的钩子并发执行，共用一个
+//! `timeout_secs` 截止时间。命令钩子有自己的进程组，超时时整组结束。命令行模式在
+//! 退出前调用 [`wait_for_pending`]，最多再等一个截止时间。
+
+use crate::config::TASK_HOOK_TIMEOUT_DEFAULT;
+use crate::utils::config_paths::ConfigPaths;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+
+/// config.json 中的 `task_hooks`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskHooks {
+    /// 每个任务结束后运行（shell 命令或 webhook URL）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_complete: Option<String>,
+    /// 任务失败时额外运行（shell 命令或 webhook URL）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_failure: Option<String>,
+    /// 钩子的最长执行时间（秒，默认 10），所有匹配的钩子共用；超时的命令会被杀掉
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// 触发钩子的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    Complete,
+    Failure,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::Complete => "complete",
+            HookEvent::Failure => "failure",
+        }
+    }
+}
+
+/// 传给钩子的任务元数据
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskOutcome {
+    /// MCP 任务的 UUID；命令行任务没有 UUID，使用 PID
+    pub task_id: String,
+    pub pid: u32,
+    pub ai_type: String,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    /// 注册表中记录的结果，如 `success`、`cli_failed_with_exit_code_1`、`cli_terminated_by_SIGKILL`、`cancelled`
+    pub result: Option<String>,
+    pub log_path: String,
+    pub completed_at: DateTime<Utc>,
+}
+
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    event: HookEvent,
+    #[serde(flatten)]
+    task: &'a TaskOutcome,
+}
+
+impl TaskHooks {
+    /// 从 `~/.aiw/config.json` 读取；配置不可读时视为未配置
+    pub fn from_user_config() -> Self {
+        ConfigPaths::new()
+            .map(|paths| paths.user_config.task_hooks)
+            .unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.on_complete.is_none() && self.on_failure.is_none()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        match self.timeout_secs {
+            Some(secs) if secs > 0 => Duration::from_secs(secs),
+            _ => TASK_HOOK_TIMEOUT_DEFAULT,
+        }
+    }
+
+    /// 该结果要运行的钩子，按 on_complete、on_failure 的顺序
+    pub fn targets(&self, outcome: &TaskOutcome) -> Vec<(HookEvent, &str)> {
+        let mut targets = Vec::new();
+        if let Some(target) = non_empty(&self.on_complete) {
+            targets.push((HookEvent::Complete, target));
+        }
+        if !outcome.success {
+            if let Some(target) = non_empty(&self.on_failure) {
+                targets.push((HookEvent::Failure, target));
+            }
+        }
+        targets
+    }
+
+    /// 并发运行匹配的钩子，整体不超过 `timeout()`；失败和超时只记日志
+    pub async fn run(&self, outcome: &TaskOutcome) {
+        let timeout = self.timeout();
+        let runs = self
+            .targets(outcome)
+            .into_iter()
+            .map(|(event, target)| async move {
+                match run_hook(event, target, outcome, timeout).await {
+                    Ok(()) => tracing::debug!(
+                        event = event.as_str(),
+                        task_id = outcome.task_id.as_str(),
+                        "task hook finished"
+                    ),
+                    Err(err) => tracing::warn!(
+                        event = event.as_str(),
+                        task_id = outcome.task_id.as_str(),
+                        "task_hooks.on_{} failed: {}",
+                        event.as_str(),
+                        err
+                    ),
+                }
+            });
+        futures::future::join_all(runs).await;
+    }
+
+    /// 在后台任务中运行钩子，调用方不等待
+    pub fn spawn(self, outcome: TaskOutcome) {
+        let handle = tokio::spawn(async move { self.run(&outcome).await });
+        let mut pending = pending_hooks().lock();
+        pending.retain(|handle| !handle.is_finished());
+        pending.push(handle);
+    }
+}
+
+/// 尚未结束的后台钩子
+fn pending_hooks() -> &'static Mutex<Vec<JoinHandle<()>>> {
+    static PENDING: std::sync::OnceLock<Mutex<Vec<JoinHandle<()>>>> = std::sync::OnceLock::new();
+    PENDING.get_or_init(Default::default)
+}
+
+/// 等待后台钩子结束，最多等一个 `timeout_secs`；命令行进程退出前调用，
+/// 否则运行时关闭会直接丢弃还在执行的钩子
+pub async fn wait_for_pending() {
+    let handles: Vec<JoinHandle<()>> = std::mem::take(&mut *pending_hooks().lock());
+    if handles.is_empty() {
+        return;
+    }
+    let deadline = TaskHooks::from_user_config().timeout();
+    let aborts: Vec<_> = handles.iter().map(JoinHandle::abort_handle).collect();
+    if tokio::time::timeout(deadline, futures::future::join_all(handles))
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "task hooks still running after {}s; abandoning them",
+            deadline.as_secs()
+        );
+        for abort in aborts {
+            abort.abort();
+        }
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn is_webhook(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
+
+/// 运行单个钩子，超过 `timeout` 时放弃；命令钩子连同其进程组一起结束
+pub async fn run_hook(
+    event: HookEvent,
+    target: &str,
+    outcome: &TaskOutcome,
+    timeout: Duration,
+) -> Result<(), String> {
+    let payload = HookPayload {
+        event,
+        task: outcome,
+    };
+    if is_webhook(target) {
+        tokio::time::timeout(timeout, post_webhook(target, &payload, timeout))
+            .await
+            .map_err(|_| timed_out(timeout))?
+    } else {
+        run_command(target, &payload, timeout).await
+    }
+}
+
+fn timed_out(timeout: Duration) -> String {
+    format!("timed out after {}s", timeout.as_secs())
+}
+
+async fn post_webhook(
+    url: &str,
+    payload: &HookPayload<'_>,
+    timeout: Duration,
+) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|err| err.to_string())?;
+    client
+        .post(url)
+        .json(payload)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+async fn run_command(
+    command_line: &str,
+    payload: &HookPayload<'_>,
+    timeout: Duration,
+) -> Result<(), String> {
+    let body = serde_json::to_vec(payload).map_err(|err| err.to_string())?;
+    let task = payload.task;
+
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(command_line);
+        command
+    };
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(command_line);
+        command
+    };
+    command
+        .env("AIW_HOOK_EVENT", payload.event.as_str())
+        .env("AIW_TASK_ID", &task.task_id)
+        .env("AIW_TASK_PID", task.pid.to_string())
+        .env("AIW_AI_TYPE", &task.ai_type)
+        .env(
+            "AIW_EXIT_CODE",
+            task.exit_code
+                .map(|code| code.to_string())
+                .unwrap_or_default(),
+        )
+        .env(
+            "AIW_TASK_RESULT",
+            task.result.as_deref().unwrap_or_default(),
+        )
+        .env("AIW_LOG_PATH", &task.log_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // 独立进程组：超时时连同钩子启动的子进程一起结束
+    #[cfg(unix)]
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setpgid(0, 0) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let mut child = command
+        .spawn()
+        .map_err(|err| format!("cannot start '{}': {}", command_line, err))?;
+    let pid = child.id();
+    let stdin = child.stdin.take();
+    let run = async move {
+        if let Some(mut stdin) = stdin {
+            // 钩子不读 stdin 时会提前关闭管道，忽略写入错误
+            let _ = stdin.write_all(&body).await;
+        }
+        child.wait_with_output().await
+    };
+    let output = match tokio::time::timeout(timeout, run).await {
+        Ok(output) => output.map_err(|err| err.to_string())?,
+        Err(_) => {
+            if let Some(pid) = pid {
+                crate::platform::terminate_process_group(pid).await;
+            }
+            return Err(timed_out(timeout));
+        }
+    };
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stderr = stderr.trim();
+    Err(match output.status.code() {
+        Some(code) if stderr.is_empty() => format!("exited with code {}", code),
+        Some(code) => format!("exited with code {}: {}", code, stderr),
+        None => "terminated by a signal".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(success: bool) -> TaskOutcome {
+        TaskOutcome {
+            task_id: "3f2a".to_string(),
+            pid: 4242,
+            ai_type: "codex".to_string(),
+            exit_code: Some(if success { 0 } else { 2 }),
+            success,
+            result: Some(
+                if success {
+                    "success"
+                } else {
+                    "cli_failed_with_exit_code_2"
+                }
+                .to_string(),
+            ),
+            log_path: "/tmp/.aiw/logs/4242.log".to_string(),
+            completed_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn failure_hook_runs_only_for_failed_tasks() {
+        let hooks: TaskHooks = serde_json::from_str(
+            r#"{"on_complete": "notify-send done", "on_failure": "https://ci.example.com/hook"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            hooks.targets(&outcome(true)),
+            vec![(HookEvent::Complete, "notify-send done")]
+        );
+        assert_eq!(
+            hooks.targets(&outcome(false)),
+            vec![
+                (HookEvent::Complete, "notify-send done"),
+                (HookEvent::Failure, "https://ci.example.com/hook")
+            ]
+        );
+        assert_eq!(hooks.timeout(), TASK_HOOK_TIMEOUT_DEFAULT);
+        assert!(TaskHooks::default().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_hook_gets_env_and_json_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = format!(
+            "cat > '{0}/payload.json'; echo \"$AIW_HOOK_EVENT $AIW_TASK_ID $AIW_AI_TYPE $AIW_EXIT_CODE $AIW_LOG_PATH\" > '{0}/env.txt'",
+            dir.path().display()
+        );
+
+        run_hook(
+            HookEvent::Failure,
+            &script,
+            &outcome(false),
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+
+        let env = std::fs::read_to_string(dir.path().join("env.txt")).unwrap();
+        assert_eq!(env.trim(), "failure 3f2a codex 2 /tmp/.aiw/logs/4242.log");
+        let payload: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("payload.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(payload["event"], "failure");
+        assert_eq!(payload["task_id"], "3f2a");
+        assert_eq!(payload["exit_code"], 2);
+        assert_eq!(payload["result"], "cli_failed_with_exit_code_2");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn slow_or_failing_hooks_return_errors() {
+        let started = std::time::Instant::now();
+        let err = run_hook(
+            HookEvent::Complete,
+            "sleep 30",
+            &outcome(true),
+            Duration::from_secs(1),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("timed out"), "{err}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        let err = run_hook(
+            HookEvent::Complete,
+            "echo broken >&2; exit 3",
+            &outcome(true),
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err, "exited with code 3: broken");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn timed_out_hooks_take_their_children_with_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("sleep.pid");
+        let script = format!("sleep 30 & echo $! > '{}'; wait", pid_file.display());
+
+        let err = run_hook(
+            HookEvent::Complete,
+            &script,
+            &outcome(true),
+            Duration::from_secs(1),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("timed out"), "{err}");
+
+        let pid: i32 = std::fs::read_to_string(&pid_file)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+        // 被杀的孤儿进程可能还没被回收，僵尸状态也算已结束
+        let running = std::fs::read_to_string(format!("/proc/{pid}/stat"))
+            .ok()
+            .and_then(|stat| {
+                let (_, rest) = stat.rsplit_once(')')?;
+                Some(!rest.trim_start().starts_with('Z'))
+            })
+            .unwrap_or(false);
+        assert!(!running, "sleep {pid} outlived its hook");
+    }
+}
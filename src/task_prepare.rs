@@ -4,7 +4,8 @@
 //! 角色处理、Auto CLI 解析、worktree 创建、参数构建。
 
 use crate::cli_type::CliType;
-use crate::roles::{builtin::get_builtin_role, Role, RoleManager};
+use crate::provider::{AiType, ProviderManager};
+use crate::roles::{detect_language, load_role, Role};
 use crate::task_record::WorktreeInfo;
 use std::ffi::OsString;
 use std::path::PathBuf;
@@ -13,11 +14,21 @@ use std::path::PathBuf;
 pub struct TaskParams {
     pub cli_type: CliType,
     pub prompt: String,
+    /// 逗号分隔的角色名，按顺序组合
     pub role: Option<String>,
+    pub role_position: RolePosition,
     pub provider: Option<String>,
+    /// 模型覆盖，翻译为各 CLI 自己的模型参数
+    pub model: Option<String>,
     pub cli_args: Vec<String>,
     pub cwd: Option<PathBuf>,
     pub create_worktree: bool,
+    /// 任务 key：同一 key 的重试/重启复用上次创建且无改动的 worktree
+    pub worktree_key: Option<String>,
+    /// 任务级环境变量，覆盖 provider 注入的同名变量
+    pub env: Vec<(String, String)>,
+    /// nice 值，None 表示继承当前进程优先级
+    pub priority: Option<i32>,
 }
 
 /// 公共准备结果（角色+worktree），不含 CLI 特定信息
@@ -27,6 +38,9 @@ pub struct PreparedTaskBase {
     pub worktree_info: Option<WorktreeInfo>,
     pub cli_args: Vec<String>,
     pub user_provider: Option<String>,
+    pub model: Option<String>,
+    pub env: Vec<(String, String)>,
+    pub priority: Option<i32>,
 }
 
 /// 准备完成的任务，可直接交给 supervisor 执行
@@ -37,6 +51,8 @@ pub struct PreparedTask {
     pub provider: Option<String>,
     pub cwd: Option<PathBuf>,
     pub worktree_info: Option<WorktreeInfo>,
+    pub env: Vec<(String, String)>,
+    pub priority: Option<i32>,
 }
 
 /// 统一的任务准备函数
@@ -66,8 +82,21 @@ pub fn prepare_task(params: TaskParams) -> anyhow::Result<PreparedTask> {
 ///
 /// 用于故障切换场景：先做公共准备，再对每个 CLI+Provider 组合调用 `finalize_for_entry`
 pub fn prepare_task_base(params: TaskParams) -> anyhow::Result<PreparedTaskBase> {
-    // 角色处理 → 富化 prompt
-    let prompt = apply_role(params.role.as_deref(), &params.prompt)?;
+    validate_env_names(&params.env)?;
+    // Auto 的具体 CLI 在故障切换时才确定，届时再翻译
+    if let (Some(model), false) = (&params.model, matches!(params.cli_type, CliType::Auto)) {
+        params
+            .cli_type
+            .model_args(model)
+            .map_err(anyhow::Error::msg)?;
+    }
+
+    // 角色处理 → 富化 prompt，再检查长度上限
+    let prompt = apply_role(params.role.as_deref(), params.role_position, &params.prompt)?;
+    let prompt = match PromptLimit::from_user_config() {
+        Some(limit) => enforce_prompt_limit(prompt, &params.prompt, params.role_position, limit)?,
+        None => prompt,
+    };
 
     // Worktree 创建（条件性）
     let (cwd, worktree_info) = if params.create_worktree {
@@ -75,7 +104,10 @@ pub fn prepare_task_base(params: TaskParams) -> anyhow::Result<PreparedTaskBase>
             std::env::current_dir().unwrap_or_else(|_| ".".into())
         });
         crate::worktree::check_git_repository(&work_dir)?;
-        let (wt_path, branch, commit) = crate::worktree::create_worktree(&work_dir)?;
+        let (wt_path, branch, commit) = match params.worktree_key.as_deref() {
+            Some(key) => crate::worktree::create_or_reuse_worktree(&work_dir, key)?,
+            None => crate::worktree::create_worktree(&work_dir)?,
+        };
         let info = WorktreeInfo {
             path: wt_path.display().to_string(),
             branch,
@@ -92,12 +124,124 @@ pub fn prepare_task_base(params: TaskParams) -> anyhow::Result<PreparedTaskBase>
         worktree_info,
         cli_args: params.cli_args,
         user_provider: params.provider,
+        model: params.model,
+        env: params.env,
+        priority: params.priority,
+    })
+}
+
+/// 角色注入后的 prompt 长度上限（config.json 的 `max_prompt_chars`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptLimit {
+    pub max_chars: usize,
+    /// 超限时截断任务文本（`truncate_long_prompts`），否则报错
+    pub truncate: bool,
+}
+
+impl PromptLimit {
+    /// 未配置 `max_prompt_chars` 或配置为 0 时返回 `None`
+    pub fn from_user_config() -> Option<Self> {
+        let config = crate::utils::config_paths::ConfigPaths::new()
+            .ok()?
+            .user_config;
+        config
+            .max_prompt_chars
+            .filter(|max| *max > 0)
+            .map(|max_chars| Self {
+                max_chars,
+                truncate: config.truncate_long_prompts,
+            })
+    }
+}
+
+/// 检查组合后的 prompt 是否超限，按字符计数；角色部分单独统计以便报错时说明占比
+///
+/// 截断只作用于任务文本，角色内容保持完整；角色本身已超限时仍然报错。
+fn enforce_prompt_limit(
+    composed: String,
+    task: &str,
+    position: RolePosition,
+    limit: PromptLimit,
+) -> anyhow::Result<String> {
+    let total_chars = composed.chars().count();
+    if total_chars <= limit.max_chars {
+        return Ok(composed);
+    }
+
+    let task_chars = task.chars().count();
+    let role_chars = total_chars - task_chars;
+    if !limit.truncate || role_chars >= limit.max_chars {
+        let hint = if role_chars >= limit.max_chars {
+            "The roles alone exceed the limit; use fewer or shorter roles"
+        } else {
+            "Shorten the task, use fewer roles, or set truncate_long_prompts in ~/.aiw/config.json"
+        };
+        anyhow::bail!(
+            "Prompt is {} characters, over the max_prompt_chars limit of {} (roles: {}, task: {}). {}.",
+            total_chars,
+            limit.max_chars,
+            role_chars,
+            task_chars,
+            hint
+        );
+    }
+
+    let keep = limit.max_chars - role_chars;
+    let cut = task.char_indices().nth(keep).map_or(task.len(), |(i, _)| i);
+    eprintln!(
+        "Warning: Task truncated from {} to {} characters to fit max_prompt_chars = {} (roles use {}).",
+        task_chars, keep, limit.max_chars, role_chars
+    );
+    // 组合时任务文本原样位于开头或末尾，只替换这一段
+    Ok(match position {
+        RolePosition::Before => {
+            let roles = &composed[..composed.len() - task.len()];
+            format!("{}{}", roles, &task[..cut])
+        }
+        RolePosition::After => format!("{}{}", &task[..cut], &composed[task.len()..]),
     })
 }
 
+/// 将 `auto` Provider 解析为具体名称（兼容 + 非冷却 + 健康检查择优）
+///
+/// 非 auto、CLI 本身为 Auto 或没有可选 Provider 时原样返回，交给 supervisor 按原有逻辑兜底
+pub async fn resolve_auto_provider(cli_type: &CliType, provider: Option<String>) -> Option<String> {
+    let is_auto = provider
+        .as_deref()
+        .is_some_and(|p| p.eq_ignore_ascii_case("auto"));
+    if !is_auto || matches!(cli_type, CliType::Auto) {
+        return provider;
+    }
+    let Ok(ai_type) = cli_type.display_name().parse::<AiType>() else {
+        return provider;
+    };
+    let Ok(mut manager) = ProviderManager::new() else {
+        return provider;
+    };
+    manager.resolve_auto(&ai_type).await.or(provider)
+}
+
+/// 校验任务级环境变量名（不能为空，不能包含 '=' 或 NUL）
+fn validate_env_names(env: &[(String, String)]) -> anyhow::Result<()> {
+    for (key, _) in env {
+        if key.is_empty() || key.contains('=') || key.contains('\0') {
+            anyhow::bail!("Invalid environment variable name: '{}'", key);
+        }
+    }
+    Ok(())
+}
+
 /// 基于公共准备结果 + 具体 CLI+Provider 构建最终 PreparedTask
 pub fn finalize_for_entry(base: &PreparedTaskBase, cli_type: CliType, provider: Option<String>) -> PreparedTask {
-    let args = cli_type.build_full_access_args_with_cli(&base.prompt, &base.cli_args);
+    let mut cli_args = Vec::new();
+    if let Some(model) = &base.model {
+        match cli_type.model_args(model) {
+            Ok(model_args) => cli_args.extend(model_args),
+            Err(err) => tracing::warn!("Ignoring model override: {}", err),
+        }
+    }
+    cli_args.extend(base.cli_args.iter().cloned());
+    let args = cli_type.build_full_access_args_in(&base.prompt, &cli_args, base.cwd.as_deref());
     let os_args: Vec<OsString> = args.into_iter().map(OsString::from).collect();
 
     PreparedTask {
@@ -107,24 +251,60 @@ pub fn finalize_for_entry(base: &PreparedTaskBase, cli_type: CliType, provider:
         provider,
         cwd: base.cwd.clone(),
         worktree_info: base.worktree_info.clone(),
+        env: base.env.clone(),
+        priority: base.priority,
     }
 }
 
 // --- 统一的角色处理函数 ---
 
 const DEFAULT_ROLE: &str = "common";
+const ROLE_SEPARATOR: &str = "\n\n---\n\n";
+
+/// 角色内容相对任务 prompt 的位置
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum RolePosition {
+    /// Role content comes first, then the task.
+    #[default]
+    Before,
+    /// The task comes first, then the role content.
+    After,
+}
+
+/// MCP `role` 参数：逗号分隔字符串或有序角色名列表
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
+#[serde(untagged)]
+pub enum RoleSpec {
+    /// Comma-separated role names, e.g. "security,common".
+    Joined(String),
+    /// Ordered list of role names.
+    List(Vec<String>),
+}
 
-/// 检测用户首选语言（基于系统 locale）
-fn detect_language() -> String {
-    if let Some(locale) = sys_locale::get_locale() {
-        if locale.starts_with("zh") {
-            return "zh-CN".to_string();
+impl RoleSpec {
+    /// 转成 `TaskParams::role` 使用的逗号分隔形式
+    pub fn to_role_string(&self) -> String {
+        match self {
+            RoleSpec::Joined(joined) => joined.clone(),
+            RoleSpec::List(names) => names.join(","),
         }
     }
-    "en".to_string()
 }
 
-/// 解析逗号分隔的角色字符串（去重，保持顺序）
+/// 解析逗号分隔的角色字符串（按名称去重，保持顺序）
 fn parse_role_names(role_str: &str) -> Vec<&str> {
     let mut seen = std::collections::HashSet::new();
     role_str
@@ -137,23 +317,15 @@ fn parse_role_names(role_str: &str) -> Vec<&str> {
 
 /// 加载单个角色（优先用户自定义，其次内置）
 fn load_single_role(name: &str, lang: &str) -> Option<Role> {
-    if let Ok(manager) = RoleManager::new() {
-        if let Ok(role) = manager.get_role(name) {
-            return Some(role);
-        }
-    }
-    if let Ok(role) = get_builtin_role(name, lang) {
-        return Some(role);
-    }
-    None
+    load_role(name, lang).ok()
 }
 
 /// 加载多个角色，返回 (有效角色列表, 无效角色名列表)
-fn load_roles(names: &[&str], lang: &str) -> (Vec<Role>, Vec<String>) {
+fn load_roles(names: &[&str], load: &impl Fn(&str) -> Option<Role>) -> (Vec<Role>, Vec<String>) {
     let mut valid_roles = Vec::new();
     let mut invalid_names = Vec::new();
     for name in names {
-        if let Some(role) = load_single_role(name, lang) {
+        if let Some(role) = load(name) {
             valid_roles.push(role);
         } else {
             invalid_names.push(name.to_string());
@@ -162,30 +334,69 @@ fn load_roles(names: &[&str], lang: &str) -> (Vec<Role>, Vec<String>) {
     (valid_roles, invalid_names)
 }
 
+/// 按内容去重：不同名称但内容相同（如内置角色的别名副本）只保留第一个
+fn dedupe_role_contents(roles: Vec<Role>) -> Vec<Role> {
+    let mut seen = std::collections::HashSet::new();
+    roles
+        .into_iter()
+        .filter(|role| {
+            let fresh = seen.insert(role.content.trim().to_string());
+            if !fresh {
+                eprintln!(
+                    "Warning: Role '{}' repeats an earlier role's content, skipping.",
+                    role.name
+                );
+            }
+            fresh
+        })
+        .collect()
+}
+
 /// 组合多个角色内容与用户 prompt
-fn combine_role_contents(roles: &[Role], prompt: &str) -> String {
+fn combine_role_contents(roles: &[Role], prompt: &str, position: RolePosition) -> String {
     if roles.is_empty() {
         return prompt.to_string();
     }
     let role_contents: Vec<&str> = roles.iter().map(|r| r.content.as_str()).collect();
-    let combined = role_contents.join("\n\n---\n\n");
-    format!("{}\n\n---\n\n{}", combined, prompt)
+    let combined = role_contents.join(ROLE_SEPARATOR);
+    match position {
+        RolePosition::Before => format!("{}{}{}", combined, ROLE_SEPARATOR, prompt),
+        RolePosition::After => format!("{}{}{}", prompt, ROLE_SEPARATOR, combined),
+    }
 }
 
 /// 应用角色到 prompt（支持多角色，逗号分隔）
-fn apply_role(role_str: Option<&str>, prompt: &str) -> anyhow::Result<String> {
+fn apply_role(
+    role_str: Option<&str>,
+    position: RolePosition,
+    prompt: &str,
+) -> anyhow::Result<String> {
     let role_str = match role_str {
         Some(s) => s,
         None => return Ok(prompt.to_string()),
     };
 
+    let lang = detect_language();
+    Ok(apply_role_with(role_str, position, prompt, |name| {
+        load_single_role(name, &lang)
+    }))
+}
+
+/// 角色组合核心逻辑，角色加载方式由调用方提供
+///
+/// 只有全部角色名都无效时才回退到 `common`；部分无效时跳过无效项，其余保持原顺序
+fn apply_role_with(
+    role_str: &str,
+    position: RolePosition,
+    prompt: &str,
+    load: impl Fn(&str) -> Option<Role>,
+) -> String {
     let role_names = parse_role_names(role_str);
     if role_names.is_empty() {
-        return Ok(prompt.to_string());
+        return prompt.to_string();
     }
 
-    let lang = detect_language();
-    let (valid_roles, invalid_names) = load_roles(&role_names, &lang);
+    let (valid_roles, invalid_names) = load_roles(&role_names, &load);
 
     for name in &invalid_names {
         eprintln!("Warning: Role '{}' not found, skipping.", name);
@@ -196,12 +407,136 @@ fn apply_role(role_str: Option<&str>, prompt: &str) -> anyhow::Result<String> {
             "Warning: All specified roles not found, falling back to '{}' role.",
             DEFAULT_ROLE
         );
-        if let Some(fallback) = load_single_role(DEFAULT_ROLE, &lang) {
-            return Ok(combine_role_contents(&[fallback], prompt));
+        if let Some(fallback) = load(DEFAULT_ROLE) {
+            return combine_role_contents(&[fallback], prompt, position);
         }
         eprintln!("Warning: Default role '{}' also not available.", DEFAULT_ROLE);
-        return Ok(prompt.to_string());
+        return prompt.to_string();
     }
 
-    Ok(combine_role_contents(&valid_roles, prompt))
+    combine_role_contents(&dedupe_role_contents(valid_roles), prompt, position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn loader(roles: &[(&str, &str)]) -> impl Fn(&str) -> Option<Role> {
+        let roles: HashMap<String, String> = roles
+            .iter()
+            .map(|(name, content)| (name.to_string(), content.to_string()))
+            .collect();
+        move |name| {
+            roles.get(name).map(|content| Role {
+                name: name.to_string(),
+                description: String::new(),
+                content: content.clone(),
+                file_path: PathBuf::from(format!("{}.md", name)),
+                language: None,
+            })
+        }
+    }
+
+    #[test]
+    fn mixed_valid_and_invalid_roles_keep_requested_order() {
+        let load = loader(&[("a", "A"), ("b", "B"), ("common", "C")]);
+        let prompt = apply_role_with("b, missing, a", RolePosition::Before, "task", &load);
+        assert_eq!(prompt, "B\n\n---\n\nA\n\n---\n\ntask");
+    }
+
+    #[test]
+    fn after_position_appends_roles() {
+        let load = loader(&[("a", "A"), ("b", "B")]);
+        let prompt = apply_role_with("a,nope,b", RolePosition::After, "task", &load);
+        assert_eq!(prompt, "task\n\n---\n\nA\n\n---\n\nB");
+    }
+
+    #[test]
+    fn roles_are_deduped_by_content() {
+        let load = loader(&[("a", "same\n"), ("alias", "same"), ("b", "B")]);
+        let prompt = apply_role_with("a,alias,b,a", RolePosition::Before, "task", &load);
+        assert_eq!(prompt, "same\n\n\n---\n\nB\n\n---\n\ntask");
+    }
+
+    #[test]
+    fn falls_back_to_common_only_when_all_roles_are_invalid() {
+        let load = loader(&[("common", "C")]);
+        assert_eq!(
+            apply_role_with("x,y", RolePosition::After, "task", &load),
+            "task\n\n---\n\nC"
+        );
+
+        let without_common = loader(&[]);
+        assert_eq!(
+            apply_role_with("x,y", RolePosition::Before, "task", &without_common),
+            "task"
+        );
+        assert_eq!(
+            apply_role_with(" , ", RolePosition::Before, "task", &load),
+            "task"
+        );
+    }
+
+    fn limit(max_chars: usize, truncate: bool) -> PromptLimit {
+        PromptLimit {
+            max_chars,
+            truncate,
+        }
+    }
+
+    #[test]
+    fn prompt_limit_error_reports_role_and_task_sizes() {
+        let load = loader(&[("a", "ROLE")]);
+        let composed = apply_role_with("a", RolePosition::Before, "0123456789", &load);
+        let err = enforce_prompt_limit(
+            composed,
+            "0123456789",
+            RolePosition::Before,
+            limit(15, false),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Prompt is 21 characters"), "{}", err);
+        assert!(err.contains("limit of 15 (roles: 11, task: 10)"), "{}", err);
+
+        let within = enforce_prompt_limit(
+            "short".to_string(),
+            "short",
+            RolePosition::Before,
+            limit(5, false),
+        );
+        assert_eq!(within.unwrap(), "short");
+    }
+
+    #[test]
+    fn prompt_limit_truncates_only_the_task() {
+        let load = loader(&[("a", "ROLE")]);
+        let task = "任务一二三四五六";
+
+        let before = apply_role_with("a", RolePosition::Before, task, &load);
+        let truncated =
+            enforce_prompt_limit(before, task, RolePosition::Before, limit(14, true)).unwrap();
+        assert_eq!(truncated, "ROLE\n\n---\n\n任务一");
+
+        let after = apply_role_with("a", RolePosition::After, task, &load);
+        let truncated =
+            enforce_prompt_limit(after, task, RolePosition::After, limit(13, true)).unwrap();
+        assert_eq!(truncated, "任务\n\n---\n\nROLE");
+
+        let roles_too_long = apply_role_with("a", RolePosition::Before, task, &load);
+        let err = enforce_prompt_limit(roles_too_long, task, RolePosition::Before, limit(11, true))
+            .unwrap_err();
+        assert!(err.to_string().contains("roles alone exceed"));
+    }
+
+    #[test]
+    fn role_spec_accepts_string_or_list() {
+        let joined: RoleSpec = serde_json::from_str("\"a,b\"").unwrap();
+        let list: RoleSpec = serde_json::from_str("[\"b\", \"a\"]").unwrap();
+        assert_eq!(joined.to_role_string(), "a,b");
+        assert_eq!(list.to_role_string(), "b,a");
+        let position: RolePosition = serde_json::from_str("\"after\"").unwrap();
+        assert_eq!(position, RolePosition::After);
+    }
 }
@@ -1,7 +1,22 @@
 use crate::core::models::{AiCliProcessInfo, ProcessTreeInfo};
 use crate::error::AgenticResult;
-use chrono::{DateTime, Utc};
+use crate::platform;
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Longest argument kept in [`TaskRecord::command_line`]. Registry values live in
+/// fixed-size shared-memory slots, so a long prompt is only stored in full in the
+/// command file next to the task log.
+pub const MAX_RECORDED_ARG_CHARS: usize = 256;
+
+/// 进程被信号终止时 `result` 的写法：`cli_terminated_by_SIGKILL`
+pub const SIGNAL_RESULT_MARKER: &str = "_terminated_by_";
+
+/// Sidecar file holding the task's full argv as a JSON array (`<log>.cmd.json`)
+pub fn command_file_path(log_path: &Path) -> PathBuf {
+    log_path.with_extension("cmd.json")
+}
 
 /// Worktree information for isolated task execution.
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
@@ -14,6 +29,17 @@ pub struct WorktreeInfo {
     pub commit: String,
 }
 
+/// Interactive session recorded through a PTY (`--record`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, schemars::JsonSchema)]
+pub struct SessionRecord {
+    /// Command line of the recorded AI CLI.
+    pub command: String,
+    /// Terminal columns at session start.
+    pub cols: u16,
+    /// Terminal rows at session start.
+    pub rows: u16,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default, schemars::JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TaskStatus {
@@ -22,6 +48,13 @@ pub enum TaskStatus {
     CompletedButUnread,
 }
 
+impl TaskStatus {
+    /// 任务已结束，不会再产生输出或状态变化
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, TaskStatus::CompletedButUnread)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskRecord {
     pub started_at: DateTime<Utc>,
@@ -53,9 +86,45 @@ pub struct TaskRecord {
     /// MCP-layer UUID identifier for external consumers.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub task_id: Option<String>,
+    /// Human-friendly alias that tools accept in place of `task_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     /// Worktree isolation info (if task was launched with worktree=true).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub worktree_info: Option<WorktreeInfo>,
+    /// AI CLI that runs this task (claude/codex/gemini/...).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_type: Option<String>,
+    /// Set for recorded interactive sessions; `log_path` then points at the transcript.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<SessionRecord>,
+    /// Names of per-task environment variables (values are never persisted).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_var_names: Vec<String>,
+    /// Start time of the task process at launch (see `platform::process_start_time`),
+    /// used to tell the task apart from a later process that reuses its PID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_start_time: Option<u64>,
+    /// Resolved command line of the AI CLI (executable followed by its arguments),
+    /// with arguments longer than [`MAX_RECORDED_ARG_CHARS`] shortened.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command_line: Vec<String>,
+    /// File with the unshortened command line, see [`command_file_path`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_file: Option<String>,
+    /// Model selected on the command line (`--model`), if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Provider whose environment was injected; None when running without one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Names of the environment variables injected by the provider (values are never persisted).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub provider_env_names: Vec<String>,
+    /// Scheduling priority applied at launch (Unix nice value, already clamped);
+    /// None when the task inherited the supervisor's priority.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
 }
 
 impl TaskRecord {
@@ -81,7 +150,18 @@ impl TaskRecord {
             process_tree: None,
             ai_cli_process: None,
             task_id: None,
+            name: None,
             worktree_info: None,
+            ai_type: None,
+            session: None,
+            env_var_names: Vec::new(),
+            process_start_time: None,
+            command_line: Vec::new(),
+            command_file: None,
+            model: None,
+            provider: None,
+            provider_env_names: Vec::new(),
+            priority: None,
         }
     }
 
@@ -102,6 +182,18 @@ impl TaskRecord {
             .or(self.root_parent_pid)
     }
 
+    /// 任务进程 `pid` 是否仍存活（PID 被复用视为已退出）
+    pub fn process_alive(&self, pid: u32) -> bool {
+        platform::process_alive_since(pid, self.process_start_time)
+    }
+
+    /// `pid` 仍有进程，但启动时间与记录不符：原任务进程已退出、PID 被复用
+    pub fn pid_reused(&self, pid: u32) -> bool {
+        self.process_start_time.is_some_and(|expected| {
+            platform::process_start_time(pid).is_some_and(|actual| actual != expected)
+        })
+    }
+
     pub fn mark_completed(
         mut self,
         result: Option<String>,
@@ -115,6 +207,50 @@ impl TaskRecord {
         self
     }
 
+    /// 记录命令行：完整 argv 写入日志旁的命令文件，注册表里只保留截短后的参数
+    ///
+    /// 命令文件写入失败时只保留截短的版本，不影响任务启动。
+    pub fn set_command_line(&mut self, argv: &[String]) {
+        if !self.log_path.is_empty() {
+            let path = command_file_path(Path::new(&self.log_path));
+            let written = serde_json::to_vec(argv)
+                .map_err(std::io::Error::from)
+                .and_then(|bytes| std::fs::write(&path, bytes));
+            match written {
+                Ok(()) => self.command_file = Some(path.to_string_lossy().into_owned()),
+                Err(err) => {
+                    tracing::debug!("Failed to write command file {}: {}", path.display(), err)
+                }
+            }
+        }
+        self.command_line = argv.iter().map(|arg| shorten_arg(arg)).collect();
+    }
+
+    /// 完整命令行：优先读取命令文件，读不到时退回注册表中截短的版本
+    pub fn full_command_line(&self) -> Vec<String> {
+        self.command_file
+            .as_deref()
+            .and_then(|path| std::fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_else(|| self.command_line.clone())
+    }
+
+    /// 任务耗时：已结束的任务为总时长，运行中的任务为截至 `now` 的时长（不小于 0）
+    pub fn duration(&self, now: DateTime<Utc>) -> Duration {
+        let end = self.completed_at.unwrap_or(now);
+        (end - self.started_at).max(Duration::zero())
+    }
+
+    /// 任务被信号终止时返回信号名（没有退出码，结果中带有 [`SIGNAL_RESULT_MARKER`]）
+    pub fn terminating_signal(&self) -> Option<&str> {
+        if self.exit_code.is_some() {
+            return None;
+        }
+        let result = self.result.as_deref()?;
+        let (_, signal) = result.split_once(SIGNAL_RESULT_MARKER)?;
+        (!signal.is_empty()).then_some(signal)
+    }
+
     pub fn with_cleanup_reason(mut self, reason: &str) -> Self {
         let result = self.result.clone();
         let exit_code = self.exit_code;
@@ -125,11 +261,75 @@ impl TaskRecord {
     }
 }
 
+fn shorten_arg(arg: &str) -> String {
+    let chars = arg.chars().count();
+    if chars <= MAX_RECORDED_ARG_CHARS {
+        return arg.to_string();
+    }
+    let kept: String = arg.chars().take(MAX_RECORDED_ARG_CHARS).collect();
+    format!("{}… ({} chars)", kept, chars)
+}
+
+/// 紧凑的时长显示（`42s`、`3m5s`、`1h1m`、`2d3h`），用于纯文本表格
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let minutes = seconds / 60;
+    let hours = minutes / 60;
+    if hours >= 24 {
+        format!("{}d{}h", hours / 24, hours % 24)
+    } else if hours > 0 {
+        format!("{hours}h{}m", minutes % 60)
+    } else if minutes > 0 {
+        format!("{minutes}m{}s", seconds % 60)
+    } else {
+        format!("{seconds}s")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Utc;
 
+    #[test]
+    fn duration_is_elapsed_while_running_and_total_once_completed() {
+        let started = Utc::now() - Duration::seconds(600);
+        let record = TaskRecord::new(started, "1".to_string(), "/tmp/1.log".to_string(), None);
+        let now = started + Duration::seconds(90);
+        assert_eq!(record.duration(now), Duration::seconds(90));
+        // 时钟回拨不产生负数
+        assert_eq!(
+            record.duration(started - Duration::seconds(5)),
+            Duration::zero()
+        );
+
+        let completed = record.mark_completed(None, Some(0), started + Duration::seconds(3_700));
+        assert_eq!(completed.duration(Utc::now()), Duration::seconds(3_700));
+        assert_eq!(format_duration(completed.duration(Utc::now())), "1h1m");
+        assert_eq!(format_duration(Duration::seconds(42)), "42s");
+        assert_eq!(format_duration(Duration::hours(50)), "2d2h");
+    }
+
+    #[test]
+    fn terminating_signal_is_read_from_the_result() {
+        let record = TaskRecord::new(Utc::now(), "1".to_string(), "/tmp/1.log".to_string(), None);
+        let killed = record.clone().mark_completed(
+            Some("cli_terminated_by_SIGKILL".to_string()),
+            None,
+            Utc::now(),
+        );
+        assert_eq!(killed.terminating_signal(), Some("SIGKILL"));
+
+        let failed = record.clone().mark_completed(
+            Some("cli_failed_without_exit_code".to_string()),
+            None,
+            Utc::now(),
+        );
+        assert_eq!(failed.terminating_signal(), None);
+        let exited = record.mark_completed(Some("success".to_string()), Some(0), Utc::now());
+        assert_eq!(exited.terminating_signal(), None);
+    }
+
     #[test]
     fn test_task_record_with_process_tree() {
         let base_time = Utc::now();
@@ -184,6 +384,79 @@ mod tests {
         assert!(deserialized.process_tree.is_some());
     }
 
+    #[test]
+    fn test_task_record_env_var_names_roundtrip() {
+        let mut record = TaskRecord::new(
+            Utc::now(),
+            "1234".to_string(),
+            "/tmp/1234.log".to_string(),
+            None,
+        );
+
+        // 未设置时不写入 JSON
+        let json_str = serde_json::to_string(&record).expect("Failed to serialize");
+        assert!(!json_str.contains("env_var_names"));
+
+        record.env_var_names = vec!["RUST_LOG".to_string()];
+        let json_str = serde_json::to_string(&record).expect("Failed to serialize");
+        let deserialized: TaskRecord =
+            serde_json::from_str(&json_str).expect("Failed to deserialize");
+        assert_eq!(deserialized.env_var_names, vec!["RUST_LOG".to_string()]);
+    }
+
+    #[test]
+    fn long_prompts_go_to_the_command_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join("4242.log");
+        let mut record = TaskRecord::new(
+            Utc::now(),
+            "4242".to_string(),
+            log_path.to_string_lossy().into_owned(),
+            None,
+        );
+        let prompt = "x".repeat(200_000);
+        let argv = vec!["claude".to_string(), "-p".to_string(), prompt.clone()];
+
+        record.set_command_line(&argv);
+
+        assert_eq!(record.command_line[..2], argv[..2]);
+        assert!(record.command_line[2].ends_with("… (200000 chars)"));
+        // 注册表里的记录远小于共享内存的单值上限
+        assert!(serde_json::to_vec(&record).unwrap().len() < 4096);
+        assert_eq!(
+            record.command_file.as_deref(),
+            Some(command_file_path(&log_path).to_string_lossy().as_ref())
+        );
+        assert_eq!(record.full_command_line(), argv);
+
+        std::fs::remove_file(command_file_path(&log_path)).unwrap();
+        assert_eq!(record.full_command_line(), record.command_line);
+    }
+
+    #[test]
+    fn test_task_record_detects_reused_pid() {
+        let pid = std::process::id();
+        let mut record = TaskRecord::new(
+            Utc::now(),
+            pid.to_string(),
+            "/tmp/reused.log".to_string(),
+            None,
+        );
+
+        // 未记录启动时间时无法判断，按 PID 存活处理
+        assert!(!record.pid_reused(pid));
+        assert!(record.process_alive(pid));
+
+        record.process_start_time = platform::process_start_time(pid);
+        assert!(!record.pid_reused(pid));
+
+        if let Some(start) = record.process_start_time {
+            record.process_start_time = Some(start.wrapping_add(1));
+            assert!(record.pid_reused(pid));
+            assert!(!record.process_alive(pid));
+        }
+    }
+
     #[test]
     fn test_task_record_backward_compatibility() {
         // Test that a record without process tree fields can still be deserialized
@@ -33,7 +33,7 @@ impl TuiApp {
         let mut app = App::new();
         while let Some(ExternalScreen::McpBrowse) = app.run()? {
             // Launch MCP Browse TUI (async function)
-            Self::run_async(crate::commands::mcp::registry::browse::execute(None))?;
+            Self::run_async(crate::commands::mcp::registry::browse::execute(None, None))?;
             // After MCP Browse exits, continue with our TUI
             app = App::new();
         }
@@ -50,7 +50,7 @@ impl TuiApp {
         }
         while let Some(ExternalScreen::McpBrowse) = app.run()? {
             // Launch MCP Browse TUI (async function)
-            Self::run_async(crate::commands::mcp::registry::browse::execute(None))?;
+            Self::run_async(crate::commands::mcp::registry::browse::execute(None, None))?;
             // After MCP Browse exits, recreate app with initial screen
             app = App::new();
             if let Some(screen) = initial_screen.clone() {
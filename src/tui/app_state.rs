@@ -7,11 +7,12 @@
 //! plumbing large amounts of state through constructors.
 
 use crate::{
-    config::{AUTH_DIRECTORY, AUTH_FILE_NAME},
+    config::AUTH_FILE_NAME,
     provider::config::Provider as ProviderConfig,
     storage::RegistryEntry,
     sync::smart_oauth::{AuthState, SmartOAuthAuthenticator},
     task_record::{TaskRecord, TaskStatus},
+    utils::config_paths::aiw_config_dir,
 };
 use anyhow::{anyhow, Context, Result};
 use chrono::{DateTime, TimeZone, Utc};
@@ -21,7 +22,10 @@ use std::{
     collections::HashMap,
     fs,
     path::PathBuf,
-    sync::OnceLock,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
     time::{Duration as StdDuration, Instant},
 };
 
@@ -35,6 +39,8 @@ pub struct AppState {
     sync_progress: RwLock<HashMap<TransferKind, TransferProgress>>,
     authenticators: RwLock<HashMap<String, SmartOAuthAuthenticator>>,
     oauth_flows: RwLock<HashMap<String, OAuthFlow>>,
+    /// 任务集合或状态每变化一次加一，供 TUI 判断是否有新活动
+    tasks_revision: AtomicU64,
 }
 
 impl Default for AppState {
@@ -46,6 +52,7 @@ impl Default for AppState {
             sync_progress: RwLock::new(HashMap::new()),
             authenticators: RwLock::new(HashMap::new()),
             oauth_flows: RwLock::new(HashMap::new()),
+            tasks_revision: AtomicU64::new(0),
         }
     }
 }
@@ -92,10 +99,24 @@ impl AppState {
     /// Replace all cached tasks with the provided snapshots.
     pub fn replace_tasks(&self, snapshots: Vec<TaskSnapshot>) {
         let mut tasks = self.tasks.write();
+        let changed = tasks.len() != snapshots.len()
+            || snapshots.iter().any(|snapshot| {
+                tasks
+                    .get(&snapshot.pid)
+                    .map_or(true, |existing| existing.status != snapshot.status)
+            });
         tasks.clear();
         for snapshot in snapshots {
             tasks.insert(snapshot.pid, snapshot);
         }
+        if changed {
+            self.tasks_revision.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// 任务状态版本号；只有任务增删或状态变化时才会改变
+    pub fn tasks_revision(&self) -> u64 {
+        self.tasks_revision.load(Ordering::Relaxed)
     }
 
     /// Replace cached tasks using registry entries.
@@ -310,9 +331,8 @@ impl AppState {
     }
 
     fn auth_file_path() -> Result<PathBuf> {
-        let home =
-            dirs::home_dir().context("failed to determine home directory for OAuth storage")?;
-        let dir = home.join(AUTH_DIRECTORY);
+        let dir =
+            aiw_config_dir().context("failed to determine home directory for OAuth storage")?;
         fs::create_dir_all(&dir).with_context(|| {
             format!(
                 "failed to create OAuth storage directory {}",
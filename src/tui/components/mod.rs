@@ -9,7 +9,7 @@ pub mod style_manager;
 
 pub use component_factory::ComponentFactory;
 pub use layout_builder::LayoutBuilder;
-pub use style_manager::StyleManager;
+pub use style_manager::{StyleManager, Theme};
 
 /// Common component types for standardized UI elements
 #[derive(Debug, Clone)]
@@ -2,63 +2,250 @@
 //!
 //! Eliminates repeated style definitions across screens, providing consistent
 //! styling and reducing the 66+ repeated style creation calls.
+//!
+//! Colors come from the active [`Theme`], loaded once from `~/.aiw/theme.json`:
+//!
+//! ```json
+//! { "preset": "light", "colors": { "primary": "#d946ef", "text_dim": "gray" } }
+//! ```
 
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context, Result};
 use ratatui::{
     style::{Color, Modifier, Style},
     text::{Line, Span},
 };
+use serde::Deserialize;
+
+use crate::logging::debug;
+use crate::utils::config_paths::aiw_config_dir;
+
+const THEME_FILE: &str = "theme.json";
+
+static ACTIVE_THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Named color roles shared by all screens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub primary: Color,
+    pub secondary: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    pub info: Color,
+    pub text: Color,
+    pub text_dim: Color,
+    /// Full-screen background
+    pub background: Color,
+    /// Raised panels, status bars and the selected row
+    pub surface: Color,
+    pub border: Color,
+}
+
+/// `~/.aiw/theme.json` contents
+#[derive(Debug, Default, Deserialize)]
+struct ThemeFile {
+    #[serde(default)]
+    preset: Option<String>,
+    /// Per-role overrides: role name → color (`"#rrggbb"`, `"red"`, `"42"`)
+    #[serde(default)]
+    colors: HashMap<String, String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    /// Slate/indigo palette, the original MCP browse colors
+    pub fn dark() -> Self {
+        Self {
+            primary: Color::Rgb(99, 102, 241),   // Indigo
+            secondary: Color::Rgb(168, 85, 247), // Purple
+            success: Color::Rgb(16, 185, 129),   // Emerald
+            warning: Color::Rgb(245, 158, 11),   // Amber
+            error: Color::Rgb(248, 113, 113),    // Red 400
+            info: Color::Rgb(56, 189, 248),      // Sky
+            text: Color::Rgb(226, 232, 240),     // Slate 200
+            text_dim: Color::Rgb(148, 163, 184), // Slate 400
+            background: Color::Rgb(15, 23, 42),  // Slate 900
+            surface: Color::Rgb(30, 41, 59),     // Slate 800
+            border: Color::Rgb(71, 85, 105),     // Slate 600
+        }
+    }
+
+    /// Dark text on light slate for light terminal backgrounds
+    pub fn light() -> Self {
+        Self {
+            primary: Color::Rgb(79, 70, 229),      // Indigo 600
+            secondary: Color::Rgb(147, 51, 234),   // Purple 600
+            success: Color::Rgb(5, 150, 105),      // Emerald 600
+            warning: Color::Rgb(217, 119, 6),      // Amber 600
+            error: Color::Rgb(220, 38, 38),        // Red 600
+            info: Color::Rgb(2, 132, 199),         // Sky 600
+            text: Color::Rgb(15, 23, 42),          // Slate 900
+            text_dim: Color::Rgb(71, 85, 105),     // Slate 600
+            background: Color::Rgb(248, 250, 252), // Slate 50
+            surface: Color::Rgb(226, 232, 240),    // Slate 200
+            border: Color::Rgb(148, 163, 184),     // Slate 400
+        }
+    }
+
+    /// Basic ANSI colors only, for low-vision users and limited terminals
+    pub fn high_contrast() -> Self {
+        Self {
+            primary: Color::LightCyan,
+            secondary: Color::LightMagenta,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            info: Color::LightBlue,
+            text: Color::White,
+            text_dim: Color::Gray,
+            background: Color::Black,
+            surface: Color::Blue,
+            border: Color::White,
+        }
+    }
+
+    /// Look up a preset by name (`dark`, `light`, `high-contrast`)
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().replace('_', "-").as_str() {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" | "contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    fn role_mut(&mut self, role: &str) -> Option<&mut Color> {
+        Some(match role {
+            "primary" => &mut self.primary,
+            "secondary" => &mut self.secondary,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            "info" => &mut self.info,
+            "text" => &mut self.text,
+            "text_dim" => &mut self.text_dim,
+            "background" => &mut self.background,
+            "surface" => &mut self.surface,
+            "border" => &mut self.border,
+            _ => return None,
+        })
+    }
+
+    /// Parse a theme file: start from `preset` (default dark) and apply `colors` overrides
+    pub fn from_json(content: &str) -> Result<Self> {
+        let file: ThemeFile = serde_json::from_str(content).context("invalid theme.json")?;
+        let mut theme = match file.preset.as_deref() {
+            Some(name) => Self::preset(name).ok_or_else(|| {
+                anyhow!("unknown theme preset '{name}' (expected dark, light or high-contrast)")
+            })?,
+            None => Self::dark(),
+        };
+        for (role, value) in &file.colors {
+            let slot = theme
+                .role_mut(role)
+                .ok_or_else(|| anyhow!("unknown theme color role '{role}'"))?;
+            *slot = Color::from_str(value)
+                .map_err(|_| anyhow!("invalid color '{value}' for role '{role}'"))?;
+        }
+        Ok(theme)
+    }
+
+    /// Load a theme file, falling back to the dark preset when it is missing
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::dark());
+        }
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::from_json(&content)
+    }
+
+    /// The theme for this process, loaded from `~/.aiw/theme.json` on first use
+    pub fn current() -> &'static Theme {
+        ACTIVE_THEME.get_or_init(|| {
+            let Some(path) = aiw_config_dir().map(|dir| dir.join(THEME_FILE)) else {
+                return Self::dark();
+            };
+            Self::load_from(&path).unwrap_or_else(|err| {
+                // 终端处于 raw 模式，不能直接打印；配置错误时退回默认主题
+                debug(format!("ignoring {}: {err:#}", path.display()));
+                Self::dark()
+            })
+        })
+    }
+}
 
 /// Centralized style manager for consistent TUI styling
 pub struct StyleManager;
 
 impl StyleManager {
-    /// Create title style (cyan, bold)
+    /// Active color theme
+    pub fn theme() -> &'static Theme {
+        Theme::current()
+    }
+
+    /// Create title style (primary, bold)
     pub fn title() -> Style {
         Style::default()
-            .fg(Color::Cyan)
+            .fg(Self::theme().primary)
             .add_modifier(Modifier::BOLD)
     }
 
-    /// Create header style (cyan, bold)
+    /// Create header style (primary, bold)
     pub fn header() -> Style {
         Self::title()
     }
 
-    /// Create error style (light red)
+    /// Create error style
     pub fn error() -> Style {
-        Style::default().fg(Color::LightRed)
+        Style::default().fg(Self::theme().error)
     }
 
-    /// Create warning style (yellow)
+    /// Create warning style
     pub fn warning() -> Style {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(Self::theme().warning)
     }
 
-    /// Create success style (green)
+    /// Create success style
     pub fn success() -> Style {
-        Style::default().fg(Color::Green)
+        Style::default().fg(Self::theme().success)
     }
 
-    /// Create info style (blue)
+    /// Create info style
     pub fn info() -> Style {
-        Style::default().fg(Color::Blue)
+        Style::default().fg(Self::theme().info)
     }
 
-    /// Create muted style (gray)
+    /// Create muted style (dimmed text)
     pub fn muted() -> Style {
-        Style::default().fg(Color::Gray)
+        Style::default().fg(Self::theme().text_dim)
     }
 
-    /// Create highlight style (yellow, bold)
+    /// Create highlight style (warning color, bold)
     pub fn highlight() -> Style {
         Style::default()
-            .fg(Color::Yellow)
+            .fg(Self::theme().warning)
             .add_modifier(Modifier::BOLD)
     }
 
-    /// Create selected style (dark gray background)
+    /// Create selected style (surface background)
     pub fn selected() -> Style {
-        Style::default().bg(Color::DarkGray)
+        Style::default().bg(Self::theme().surface)
+    }
+
+    /// Create plain text style
+    pub fn text() -> Style {
+        Style::default().fg(Self::theme().text)
     }
 
     /// Create status style based on status string
@@ -124,3 +311,44 @@ impl StyleManager {
         ratatui::widgets::Borders::NONE
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_theme_is_the_dark_browse_palette() {
+        let theme = Theme::default();
+        assert_eq!(theme, Theme::dark());
+        assert_eq!(theme.primary, Color::Rgb(99, 102, 241));
+        assert_eq!(theme.surface, Color::Rgb(30, 41, 59));
+    }
+
+    #[test]
+    fn theme_file_applies_preset_and_overrides() {
+        let theme = Theme::from_json(
+            r##"{ "preset": "light", "colors": { "primary": "#ff0000", "text_dim": "gray" } }"##,
+        )
+        .unwrap();
+        assert_eq!(theme.primary, Color::Rgb(255, 0, 0));
+        assert_eq!(theme.text_dim, Color::Gray);
+        assert_eq!(theme.text, Theme::light().text);
+
+        let contrast = Theme::from_json(r#"{ "preset": "high_contrast" }"#).unwrap();
+        assert_eq!(contrast, Theme::high_contrast());
+    }
+
+    #[test]
+    fn invalid_theme_files_are_rejected() {
+        assert!(Theme::from_json(r#"{ "preset": "solarized" }"#).is_err());
+        assert!(Theme::from_json(r#"{ "colors": { "accent": "red" } }"#).is_err());
+        assert!(Theme::from_json(r#"{ "colors": { "primary": "not-a-color" } }"#).is_err());
+    }
+
+    #[test]
+    fn missing_theme_file_falls_back_to_dark() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme = Theme::load_from(&dir.path().join("theme.json")).unwrap();
+        assert_eq!(theme, Theme::dark());
+    }
+}
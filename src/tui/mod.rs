@@ -4,7 +4,10 @@
 
 use std::time::Instant;
 use crossterm::{
-    event::{self, Event, KeyEvent, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, MouseEvent,
+        MouseEventKind,
+    },
     execute,
     terminal::{
         disable_raw_mode, enable_raw_mode, ClearType, EnterAlternateScreen, LeaveAlternateScreen,
@@ -16,16 +19,19 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
     Terminal,
 };
-use std::{collections::HashMap, io::stdout, time::Duration};
+use std::{collections::HashMap, io::stdout};
 
 mod data_binding;
+mod tick;
 
 pub mod app;
 pub mod app_state;
 pub mod components;
 pub mod screens;
 
+use self::app_state::AppState;
 use self::data_binding::DataBindingController;
+use self::tick::AdaptiveTick;
 
 // 重新导出常用类型
 pub use screens::{ExternalScreen, Screen, ScreenAction, ScreenType};
@@ -76,39 +82,63 @@ impl App {
         execute!(
             stdout,
             EnterAlternateScreen,
+            EnableMouseCapture,
             crossterm::terminal::Clear(ClearType::All)
         )?;
 
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
-        let tick_rate = Duration::from_millis(16); // ~60 FPS for better responsiveness
 
-        const UPDATE_INTERVAL: Duration = Duration::from_millis(100);
+        // 空闲时退避刷新频率；按键和任务状态变化会恢复快速刷新
+        let mut tick = AdaptiveTick::from_config(Instant::now());
+        let mut tasks_revision = AppState::global().tasks_revision();
+        let mut needs_redraw = true;
 
         loop {
             self.ensure_screen_ready()?;
 
-            // 节流更新，避免过于频繁的 screen.update() 调用
             let now = Instant::now();
-            if now.duration_since(self.last_update) >= UPDATE_INTERVAL {
+            let revision = AppState::global().tasks_revision();
+            if revision != tasks_revision {
+                tasks_revision = revision;
+                tick.record_activity(now);
+            }
+
+            // 节流更新，避免过于频繁的 screen.update() 调用
+            if now.duration_since(self.last_update) >= tick.interval(now) {
                 if let Some(screen) = self.screens.get_mut(&self.current_screen) {
                     screen.update()?;
                 }
                 self.last_update = now;
+                needs_redraw = true;
             }
 
-            terminal.draw(|frame| {
-                self.render(frame);
-            })?;
+            if needs_redraw {
+                terminal.draw(|frame| {
+                    self.render(frame);
+                })?;
+                needs_redraw = false;
+            }
 
-            if event::poll(tick_rate)? {
+            let timeout = tick.poll_timeout(Instant::now(), self.last_update);
+            if event::poll(timeout)? {
                 match event::read()? {
-                    Event::Key(key) => self.handle_input(key)?,
+                    Event::Key(key) => {
+                        self.handle_input(key)?;
+                        tick.record_activity(Instant::now());
+                    }
+                    // 鼠标移动不算活动，避免无意义的重绘
+                    Event::Mouse(mouse) if mouse.kind == MouseEventKind::Moved => continue,
+                    Event::Mouse(mouse) => {
+                        self.handle_mouse(mouse)?;
+                        tick.record_activity(Instant::now());
+                    }
                     Event::Resize(_, _) => {
                         // 下一次循环会重新渲染
                     }
                     _ => {}
                 }
+                needs_redraw = true;
             }
 
             if self.should_quit || self.launch_external.is_some() {
@@ -119,6 +149,7 @@ impl App {
         execute!(
             terminal.backend_mut(),
             LeaveAlternateScreen,
+            DisableMouseCapture,
             crossterm::terminal::Clear(ClearType::All)
         )?;
         disable_raw_mode()?;
@@ -191,7 +222,26 @@ impl App {
             .get_mut(&self.current_screen)
             .expect("current screen must be initialised");
 
-        match screen.handle_key(key)? {
+        let action = screen.handle_key(key)?;
+        self.apply_action(action);
+        Ok(())
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<(), Box<dyn std::error::Error>> {
+        self.ensure_screen_ready()?;
+
+        let screen = self
+            .screens
+            .get_mut(&self.current_screen)
+            .expect("current screen must be initialised");
+
+        let action = screen.handle_mouse(mouse)?;
+        self.apply_action(action);
+        Ok(())
+    }
+
+    fn apply_action(&mut self, action: ScreenAction) {
+        match action {
             ScreenAction::None => {}
             ScreenAction::SwitchTo(new_screen) => {
                 self.current_screen = new_screen.clone();
@@ -215,8 +265,6 @@ impl App {
                 self.launch_external = Some(external);
             }
         }
-
-        Ok(())
     }
 
     fn render(&mut self, frame: &mut ratatui::Frame) {
@@ -1,6 +1,7 @@
 //! Dashboard screen implementation
 //!
-//! Displays AI CLI status, default provider, and a summary of running tasks.
+//! Displays AI CLI status, default provider, a summary of running tasks and
+//! aggregate registry statistics.
 
 use anyhow::Result;
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
@@ -14,8 +15,10 @@ use ratatui::{
 
 use crate::cli_manager::{CliToolDetector, InstallType};
 use crate::mcp_routing::config::McpConfigManager;
+use crate::registry::RegistryStats;
 use crate::roles::{builtin, RoleManager};
 use crate::tui::app_state::{AppState, TaskUiState};
+use crate::tui::components::ComponentFactory;
 
 use super::{Screen, ScreenAction, ScreenType};
 
@@ -47,6 +50,7 @@ struct DashboardState {
     running_tasks: Vec<TaskSummary>,
     total_running_tasks: usize,
     system_overview: SystemOverview,
+    registry_stats: RegistryStats,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -107,6 +111,7 @@ impl DashboardScreen {
         self.state.running_tasks = running_tasks;
         self.state.total_running_tasks = total_running_tasks;
         self.state.system_overview = self.collect_system_overview();
+        self.state.registry_stats = self.collect_registry_stats();
     }
 
     fn resolve_default_provider(&self) -> Option<String> {
@@ -143,6 +148,14 @@ impl DashboardScreen {
         (running, total_running)
     }
 
+    fn collect_registry_stats(&self) -> RegistryStats {
+        let snapshots = self.app_state.tasks_snapshot();
+        RegistryStats::collect(
+            snapshots.iter().map(|snapshot| &snapshot.record),
+            Utc::now(),
+        )
+    }
+
     fn collect_system_overview(&self) -> SystemOverview {
         // Collect MCP server stats
         let (mcp_enabled, mcp_total) = match McpConfigManager::load() {
@@ -216,6 +229,7 @@ impl DashboardScreen {
                 .map(|ty| match ty {
                     InstallType::Native => "native",
                     InstallType::Npm => "npm",
+                    InstallType::Brew => "brew",
                     InstallType::Unknown => "unknown",
                 })
                 .unwrap_or("-");
@@ -355,6 +369,48 @@ impl DashboardScreen {
             .wrap(Wrap { trim: true });
         frame.render_widget(paragraph, area);
     }
+
+    fn render_registry_stats(&self, frame: &mut Frame, area: Rect) {
+        let stats = &self.state.registry_stats;
+        let oldest = stats
+            .oldest_running_secs
+            .map(|secs| format_duration(ChronoDuration::seconds(secs)))
+            .unwrap_or_else(|| "-".to_string());
+        let by_type = stats
+            .by_ai_type
+            .iter()
+            .map(|(ai_type, count)| format!("{ai_type}:{count}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let rows = vec![
+            ("Running", stats.running.to_string()),
+            ("Completed", stats.completed.to_string()),
+            ("Failed", stats.failed.to_string()),
+            (
+                "By CLI",
+                if by_type.is_empty() {
+                    "-".to_string()
+                } else {
+                    by_type
+                },
+            ),
+            ("Worktrees", stats.with_worktree.to_string()),
+            ("Log size", format_bytes(stats.log_bytes)),
+            ("Oldest", oldest),
+        ]
+        .into_iter()
+        .map(|(metric, value)| Row::new(vec![Cell::from(metric), Cell::from(value)]))
+        .collect();
+
+        let table = ComponentFactory::table_with_header(
+            rows,
+            Row::new(vec!["Metric", "Value"]),
+            vec![Constraint::Length(10), Constraint::Min(0)],
+            Some("Registry Stats".to_string()),
+        );
+        frame.render_widget(table, area);
+    }
 }
 
 impl Screen for DashboardScreen {
@@ -363,7 +419,7 @@ impl Screen for DashboardScreen {
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(7),
-                Constraint::Length(9),
+                Constraint::Length(10),
                 Constraint::Length(3),
                 Constraint::Min(0),
             ])
@@ -371,12 +427,17 @@ impl Screen for DashboardScreen {
 
         let mid = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .constraints([
+                Constraint::Percentage(45),
+                Constraint::Percentage(25),
+                Constraint::Percentage(30),
+            ])
             .split(layout[1]);
 
         self.render_cli_status(frame, layout[0]);
         self.render_task_summary(frame, mid[0]);
-        self.render_system_overview(frame, mid[1]);
+        self.render_registry_stats(frame, mid[1]);
+        self.render_system_overview(frame, mid[2]);
         self.render_shortcuts(frame, layout[2]);
         self.render_error(frame, layout[3]);
     }
@@ -422,6 +483,18 @@ fn truncate(text: &str, max_len: usize) -> String {
     }
 }
 
+fn format_bytes(bytes: u64) -> String {
+    const KIB: u64 = 1024;
+    const MIB: u64 = KIB * 1024;
+    if bytes >= MIB {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    } else if bytes >= KIB {
+        format!("{:.1} KiB", bytes as f64 / KIB as f64)
+    } else {
+        format!("{bytes} B")
+    }
+}
+
 fn format_duration(duration: ChronoDuration) -> String {
     let total_seconds = duration.num_seconds().max(0);
     let hours = total_seconds / 3600;
@@ -521,10 +594,15 @@ mod tests {
             }],
             total_running_tasks: 1,
             system_overview: SystemOverview::default(),
+            registry_stats: RegistryStats {
+                total: 1,
+                running: 1,
+                ..RegistryStats::default()
+            },
         };
         screen.last_refresh = Some(Utc::now());
 
-        let backend = TestBackend::new(80, 20);
+        let backend = TestBackend::new(120, 24);
         let mut terminal = Terminal::new(backend).unwrap();
         terminal
             .draw(|frame| screen.render(frame, frame.size()))
@@ -535,6 +613,7 @@ mod tests {
         assert!(
             rendered.contains("AI CLI Status")
                 && rendered.contains("Task Overview")
+                && rendered.contains("Registry Stats")
                 && rendered.contains("Claude CLI"),
             "unexpected render output:\n{rendered}"
         );
@@ -1,7 +1,7 @@
 //! TUI screens module - Simplified implementation
 
 use anyhow::Result;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyEvent, MouseEvent};
 use ratatui::{layout::Rect, Frame};
 use std::fmt;
 
@@ -80,6 +80,11 @@ pub trait Screen {
     /// Handle key input
     fn handle_key(&mut self, key: KeyEvent) -> Result<ScreenAction>;
 
+    /// Handle mouse input (clicks, wheel); screens without mouse support ignore it
+    fn handle_mouse(&mut self, _mouse: MouseEvent) -> Result<ScreenAction> {
+        Ok(ScreenAction::None)
+    }
+
     /// Update screen state (called on Tick event)
     fn update(&mut self) -> Result<()> {
         Ok(()) // Default implementation does nothing
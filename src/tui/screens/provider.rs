@@ -1,36 +1,70 @@
-//! Provider management screen - Simplified view-only version
+//! Provider management screen
 //!
-//! Displays provider list. For editing, users should modify provider.json directly.
+//! Displays the provider list, tests reachability inline and switches the
+//! default provider. For editing, users should modify provider.json directly.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
 
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
     Frame,
 };
 
 use super::{Screen, ScreenAction};
+use crate::provider::config::Provider;
 use crate::provider::manager::ProviderManager;
+use crate::provider::probe::{probe_request, quick_check, ProbeHealth};
+use crate::provider::{AiType, EnvInjector};
+use crate::tui::components::StyleManager;
+
+const SPINNER_FRAMES: [&str; 4] = ["⠋", "⠙", "⠹", "⠸"];
+
+/// AI types tried in order when picking what to probe for a provider
+const PROBE_ORDER: [AiType; 4] = [AiType::Claude, AiType::Codex, AiType::Gemini, AiType::Grok];
+
+/// Last test state of one provider, shown as a badge next to it
+#[derive(Debug, Clone, PartialEq)]
+enum TestState {
+    Running,
+    Done {
+        health: ProbeHealth,
+        latency: Option<Duration>,
+    },
+}
+
+type TestResult = (String, ProbeHealth, Option<Duration>);
 
 pub struct ProviderScreen {
     list_state: ListState,
     providers: Vec<(String, String, bool)>, // (id, summary, enabled)
     default_provider: Option<String>,
     message: Option<String>,
+    tests: HashMap<String, TestState>,
+    results_tx: Sender<TestResult>,
+    results_rx: Receiver<TestResult>,
+    spinner_frame: usize,
 }
 
 impl ProviderScreen {
     pub fn new() -> Result<Self> {
+        let (results_tx, results_rx) = mpsc::channel();
         let mut screen = Self {
             list_state: ListState::default(),
             providers: Vec::new(),
             default_provider: None,
             message: None,
+            tests: HashMap::new(),
+            results_tx,
+            results_rx,
+            spinner_frame: 0,
         };
         screen.refresh_providers()?;
         if !screen.providers.is_empty() {
@@ -54,6 +88,93 @@ impl ProviderScreen {
         Ok(())
     }
 
+    fn selected_id(&self) -> Option<String> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.providers.get(index))
+            .map(|(id, _, _)| id.clone())
+    }
+
+    /// Probe the selected provider on a worker thread; the result arrives in `update`
+    fn start_test(&mut self) -> Result<()> {
+        let Some(id) = self.selected_id() else {
+            return Ok(());
+        };
+        if self.tests.get(&id) == Some(&TestState::Running) {
+            return Ok(());
+        }
+        let manager = ProviderManager::new()?;
+        let provider = manager.get_provider(&id)?.clone();
+        let ai_type = Self::probe_target(&provider);
+
+        self.tests.insert(id.clone(), TestState::Running);
+        self.message = Some(format!("Testing '{}' ({})...", id, ai_type));
+
+        let results = self.results_tx.clone();
+        std::thread::spawn(move || {
+            let (health, latency) = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime.block_on(quick_check(&id, &provider, &ai_type)),
+                Err(_) => (ProbeHealth::Failing, None),
+            };
+            let _ = results.send((id, health, latency));
+        });
+        Ok(())
+    }
+
+    /// First compatible AI type that has credentials to probe with
+    fn probe_target(provider: &Provider) -> AiType {
+        let env = EnvInjector::build_env(&EnvInjector::provider_env(provider));
+        let compatible: Vec<AiType> = PROBE_ORDER
+            .into_iter()
+            .filter(|ai_type| provider.supports(ai_type))
+            .collect();
+        compatible
+            .iter()
+            .find(|ai_type| probe_request(ai_type, &env).is_some())
+            .or_else(|| compatible.first())
+            .cloned()
+            .unwrap_or(AiType::Claude)
+    }
+
+    fn set_default(&mut self) -> Result<()> {
+        let Some(id) = self.selected_id() else {
+            return Ok(());
+        };
+        let mut manager = ProviderManager::new()?;
+        manager.set_default(&id)?;
+        self.default_provider = Some(id.clone());
+        self.message = Some(format!("Default provider set to '{}'", id));
+        Ok(())
+    }
+
+    fn badge(&self, id: &str) -> Span<'static> {
+        match self.tests.get(id) {
+            None => Span::raw(""),
+            Some(TestState::Running) => Span::styled(
+                format!(
+                    " {} testing",
+                    SPINNER_FRAMES[self.spinner_frame % SPINNER_FRAMES.len()]
+                ),
+                StyleManager::info(),
+            ),
+            Some(TestState::Done { health, latency }) => {
+                let latency = latency
+                    .map(|l| format!(" {}ms", l.as_millis()))
+                    .unwrap_or_default();
+                match health {
+                    ProbeHealth::Healthy => StyleManager::success_span(format!(" [ok{}]", latency)),
+                    ProbeHealth::Unknown => StyleManager::warning_span(" [no key]"),
+                    ProbeHealth::Failing => {
+                        StyleManager::error_span(format!(" [unreachable{}]", latency))
+                    }
+                }
+            }
+        }
+    }
+
     fn get_config_path() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -74,13 +195,9 @@ impl Screen for ProviderScreen {
             .split(area);
 
         // Header
-        let header = Paragraph::new("Provider Configuration (View-Only)")
+        let header = Paragraph::new("Provider Configuration")
             .block(Block::default().borders(Borders::ALL))
-            .style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            );
+            .style(StyleManager::title());
         frame.render_widget(header, chunks[0]);
 
         // Provider list
@@ -95,17 +212,19 @@ impl Screen for ProviderScreen {
                     .unwrap_or(false);
 
                 let status = if !enabled {
-                    Span::styled(" [disabled]", Style::default().fg(Color::Red))
+                    Span::styled(" [disabled]", StyleManager::error())
                 } else {
                     Span::raw("")
                 };
 
+                let theme = StyleManager::theme();
                 let line = vec![
                     Span::raw(if is_default { "✓ " } else { "  " }),
-                    Span::styled(id, Style::default().fg(if *enabled { Color::Yellow } else { Color::DarkGray })),
+                    Span::styled(id, Style::default().fg(if *enabled { theme.warning } else { theme.border })),
                     Span::raw(": "),
-                    Span::styled(summary, Style::default().fg(if *enabled { Color::Gray } else { Color::DarkGray })),
+                    Span::styled(summary, Style::default().fg(if *enabled { theme.text_dim } else { theme.border })),
                     status,
+                    self.badge(id),
                 ];
 
                 ListItem::new(Line::from(line))
@@ -114,7 +233,7 @@ impl Screen for ProviderScreen {
 
         let list = List::new(items)
             .block(Block::default().borders(Borders::ALL).title("Providers"))
-            .highlight_style(Style::default().bg(Color::DarkGray))
+            .highlight_style(StyleManager::selected())
             .highlight_symbol(">> ");
 
         frame.render_stateful_widget(list, chunks[1], &mut self.list_state);
@@ -126,7 +245,7 @@ impl Screen for ProviderScreen {
         } else {
             format!(
                 "Configuration file: {}\n\n\
-                 [↑↓] Navigate  [r] Refresh  [q] Back\n\
+                 [↑↓] Navigate  [t] Test  [d] Set default  [r] Refresh  [q] Back\n\
                  To edit providers, modify the configuration file directly",
                 config_path.display()
             )
@@ -134,7 +253,7 @@ impl Screen for ProviderScreen {
 
         let footer = Paragraph::new(footer_text)
             .block(Block::default().borders(Borders::ALL))
-            .style(Style::default().fg(Color::White))
+            .style(StyleManager::text())
             .wrap(Wrap { trim: true });
         frame.render_widget(footer, chunks[2]);
     }
@@ -155,6 +274,16 @@ impl Screen for ProviderScreen {
                     }
                 }
             }
+            KeyCode::Char('t') | KeyCode::Char('T') => {
+                if let Err(err) = self.start_test() {
+                    self.message = Some(format!("Failed to test provider: {}", err));
+                }
+            }
+            KeyCode::Char('d') | KeyCode::Char('D') => {
+                if let Err(err) = self.set_default() {
+                    self.message = Some(format!("Failed to set default: {}", err));
+                }
+            }
             KeyCode::Char('r') | KeyCode::Char('R') => {
                 if let Err(err) = self.refresh_providers() {
                     self.message = Some(format!("Failed to refresh: {}", err));
@@ -169,6 +298,35 @@ impl Screen for ProviderScreen {
         }
         Ok(ScreenAction::None)
     }
+
+    fn update(&mut self) -> Result<()> {
+        while let Ok((id, health, latency)) = self.results_rx.try_recv() {
+            self.message = Some(match health {
+                ProbeHealth::Healthy => format!("'{}' is reachable", id),
+                ProbeHealth::Unknown => {
+                    format!(
+                        "'{}' has no API key to test with (the CLI may use its own login)",
+                        id
+                    )
+                }
+                ProbeHealth::Failing => {
+                    format!(
+                        "'{}' is unreachable; run 'aiw provider test {}' for details",
+                        id, id
+                    )
+                }
+            });
+            self.tests.insert(id, TestState::Done { health, latency });
+        }
+        if self
+            .tests
+            .values()
+            .any(|state| *state == TestState::Running)
+        {
+            self.spinner_frame = self.spinner_frame.wrapping_add(1);
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -185,18 +343,30 @@ mod tests {
         let _ = result;
     }
 
-    #[test]
-    fn test_navigation_keys() {
+    fn screen_with(ids: &[&str]) -> ProviderScreen {
+        let (results_tx, results_rx) = mpsc::channel();
         let mut screen = ProviderScreen {
             list_state: ListState::default(),
-            providers: vec![
-                ("test1".to_string(), "summary1".to_string(), true),
-                ("test2".to_string(), "summary2".to_string(), true),
-            ],
-            default_provider: Some("test1".to_string()),
+            providers: ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (id.to_string(), format!("summary{}", i + 1), true))
+                .collect(),
+            default_provider: ids.first().map(|id| id.to_string()),
             message: None,
+            tests: HashMap::new(),
+            results_tx,
+            results_rx,
+            spinner_frame: 0,
         };
         screen.list_state.select(Some(0));
+        screen
+    }
+
+    #[test]
+    fn test_navigation_keys() {
+        let mut screen = screen_with(&["test1", "test2"]);
+        screen.list_state.select(Some(0));
 
         // Test down navigation
         let action = screen
@@ -218,4 +388,31 @@ mod tests {
             .expect("handle key");
         assert!(matches!(back, ScreenAction::Back));
     }
+
+    #[test]
+    fn test_results_update_badges() {
+        let mut screen = screen_with(&["fast", "down"]);
+        screen.tests.insert("fast".to_string(), TestState::Running);
+        screen.tests.insert("down".to_string(), TestState::Running);
+        assert!(screen.badge("fast").content.contains("testing"));
+        assert_eq!(screen.badge("other").content, "");
+
+        let tx = screen.results_tx.clone();
+        tx.send((
+            "fast".to_string(),
+            ProbeHealth::Healthy,
+            Some(Duration::from_millis(120)),
+        ))
+        .unwrap();
+        tx.send(("down".to_string(), ProbeHealth::Failing, None))
+            .unwrap();
+        screen.update().expect("update");
+
+        let ok = screen.badge("fast");
+        assert_eq!(ok.content, " [ok 120ms]");
+        assert_eq!(ok.style, StyleManager::success());
+        let failing = screen.badge("down");
+        assert_eq!(failing.content, " [unreachable]");
+        assert_eq!(failing.style, StyleManager::error());
+    }
 }
@@ -8,10 +8,10 @@ use std::time::{Duration, Instant};
 
 use anyhow::Result;
 use chrono::{DateTime, Local, Utc};
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Frame,
@@ -22,6 +22,7 @@ use crate::platform;
 use crate::registry_factory::{create_cli_registry, CliRegistry};
 use crate::task_record::{TaskRecord, TaskStatus};
 use crate::tui::app_state::{AppState, TaskSnapshot};
+use crate::tui::components::StyleManager;
 
 const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
 
@@ -34,6 +35,8 @@ pub struct StatusScreen {
     last_refresh: Instant,
     last_loaded_at: Option<DateTime<Utc>>,
     message: Option<String>,
+    /// Task list area from the last render, for mapping mouse clicks
+    list_area: Option<Rect>,
 }
 
 #[derive(Clone)]
@@ -75,6 +78,7 @@ impl StatusScreen {
                 .unwrap_or_else(Instant::now),
             last_loaded_at: None,
             message: None,
+            list_area: None,
         };
 
         screen.sync_from_registry()?;
@@ -185,12 +189,33 @@ impl StatusScreen {
         }
     }
 
-    fn format_elapsed(record: &TaskRecord) -> String {
-        let end = record.completed_at.unwrap_or_else(Utc::now);
-        let mut seconds = (end - record.started_at).num_seconds();
-        if seconds < 0 {
-            seconds = 0;
+    /// Map a terminal row to a flat entry index; group header rows map to nothing
+    fn entry_at_row(&self, row: u16) -> Option<usize> {
+        let area = self.list_area?;
+        let top = area.y + 1;
+        let bottom = area.y + area.height.saturating_sub(1);
+        if row < top || row >= bottom {
+            return None;
+        }
+
+        let mut remaining = usize::from(row - top);
+        let mut flat_index = 0;
+        for group in &self.groups {
+            if remaining == 0 {
+                return None;
+            }
+            remaining -= 1;
+            if remaining < group.tasks.len() {
+                return Some(flat_index + remaining);
+            }
+            remaining -= group.tasks.len();
+            flat_index += group.tasks.len();
         }
+        None
+    }
+
+    fn format_elapsed(record: &TaskRecord) -> String {
+        let seconds = record.duration(Utc::now()).num_seconds();
         let minutes = seconds / 60;
         let hours = minutes / 60;
         if hours > 0 {
@@ -222,9 +247,7 @@ impl StatusScreen {
         for (group_idx, group) in self.groups.iter().enumerate() {
             items.push(ListItem::new(Line::from(vec![Span::styled(
                 group.label.clone(),
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD),
+                StyleManager::highlight(),
             )])));
 
             for (task_idx, task) in group.tasks.iter().enumerate() {
@@ -235,22 +258,25 @@ impl StatusScreen {
                     .unwrap_or(false);
 
                 let (status_label, status_color) = match task.record.status {
-                    TaskStatus::Running => ("RUN", Color::Green),
-                    TaskStatus::CompletedButUnread => ("DONE", Color::Blue),
+                    TaskStatus::Running => ("RUN", StyleManager::theme().success),
+                    TaskStatus::CompletedButUnread => ("DONE", StyleManager::theme().info),
                 };
 
                 let prefix = if is_selected { "> " } else { "  " };
                 let elapsed = Self::format_elapsed(&task.record);
-                let log = truncate(&task.record.log_id, 40);
+                let label = match &task.record.name {
+                    Some(name) => format!("{} ({})", name, truncate(&task.record.log_id, 24)),
+                    None => truncate(&task.record.log_id, 40),
+                };
 
                 let content = Line::from(vec![
                     Span::raw(prefix),
                     Span::styled(status_label, Style::default().fg(status_color)),
                     Span::raw(" "),
                     Span::raw(format!("PID {} ", task.pid)),
-                    Span::styled(format!("[{}]", elapsed), Style::default().fg(Color::Gray)),
+                    Span::styled(format!("[{}]", elapsed), StyleManager::muted()),
                     Span::raw(" "),
-                    Span::raw(log),
+                    Span::raw(label),
                 ]);
 
                 let style = if is_selected {
@@ -273,8 +299,11 @@ impl StatusScreen {
             let started_local: DateTime<Local> = DateTime::from(record.started_at);
             let completed_local = record.completed_at.map(DateTime::<Local>::from);
 
-            let mut lines = vec![
-                detail_line("PID", task.pid.to_string()),
+            let mut lines = vec![detail_line("PID", task.pid.to_string())];
+            if let Some(name) = &record.name {
+                lines.push(detail_line("Name", name.clone()));
+            }
+            lines.extend([
                 detail_line(
                     "Status",
                     match record.status {
@@ -288,13 +317,19 @@ impl StatusScreen {
                     started_local.format("%Y-%m-%d %H:%M:%S").to_string(),
                 ),
                 detail_line("Elapsed", Self::format_elapsed(record)),
-            ];
+            ]);
             if let Some(completed) = completed_local {
                 lines.push(detail_line(
                     "Completed",
                     completed.format("%Y-%m-%d %H:%M:%S").to_string(),
                 ));
             }
+            if let Some(model) = &record.model {
+                lines.push(detail_line("Model", model.clone()));
+            }
+            if let Some(priority) = record.priority {
+                lines.push(detail_line("Priority", format!("nice {}", priority)));
+            }
             if let Some(manager) = record.manager_pid {
                 lines.push(detail_line("Manager PID", manager.to_string()));
             }
@@ -314,6 +349,15 @@ impl StatusScreen {
                     .join(" -> ");
                 lines.push(detail_line("Process Chain", chain));
             }
+            if let Some(session) = record.session.as_ref() {
+                lines.push(detail_line(
+                    "Session",
+                    format!(
+                        "recorded interactive ({}x{}): {}",
+                        session.cols, session.rows, session.command
+                    ),
+                ));
+            }
             lines.push(detail_line("Log ID", record.log_id.clone()));
             lines.push(detail_line("Log Path", record.log_path.clone()));
             if let Some(reason) = &record.cleanup_reason {
@@ -346,11 +390,7 @@ impl Screen for StatusScreen {
 
         let header = Paragraph::new(self.summary_line())
             .alignment(ratatui::layout::Alignment::Center)
-            .style(
-                Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
-            )
+            .style(StyleManager::title())
             .block(Block::default().borders(Borders::ALL));
         frame.render_widget(header, layout[0]);
 
@@ -360,19 +400,23 @@ impl Screen for StatusScreen {
             .split(layout[1]);
 
         if self.flat_entries.is_empty() {
+            self.list_area = None;
             let empty = Paragraph::new("No active tasks detected.")
                 .alignment(ratatui::layout::Alignment::Center)
                 .block(Block::default().borders(Borders::ALL).title("Tasks"));
             frame.render_widget(empty, body[0]);
         } else {
+            self.list_area = Some(body[0]);
             self.render_list(frame, body[0]);
         }
 
         self.render_details(frame, body[1]);
 
-        let help = Paragraph::new("[↑/↓] Navigate  [R] Refresh  [K] Kill  [ESC/Q] Back")
-            .alignment(ratatui::layout::Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
+        let help = Paragraph::new(
+            "[↑/↓/Wheel] Navigate  [Click] Select  [R] Refresh  [K] Kill  [ESC/Q] Back",
+        )
+        .alignment(ratatui::layout::Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
         frame.render_widget(help, layout[2]);
 
         let status_text = self.message.as_deref().unwrap_or("Ready");
@@ -421,6 +465,30 @@ impl Screen for StatusScreen {
         }
     }
 
+    fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<ScreenAction> {
+        let over_list = self.list_area.is_some_and(|area| {
+            mouse.column >= area.x
+                && mouse.column < area.x + area.width
+                && mouse.row >= area.y
+                && mouse.row < area.y + area.height
+        });
+        if !over_list {
+            return Ok(ScreenAction::None);
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.move_selection_up(),
+            MouseEventKind::ScrollDown => self.move_selection_down(),
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(index) = self.entry_at_row(mouse.row) {
+                    self.selected_index = index;
+                }
+            }
+            _ => {}
+        }
+        Ok(ScreenAction::None)
+    }
+
     fn update(&mut self) -> Result<()> {
         if self.last_refresh.elapsed() >= REFRESH_INTERVAL {
             self.sync_from_registry()?;
@@ -439,15 +507,7 @@ impl StatusScreen {
 }
 
 fn detail_line(label: &str, value: String) -> Line<'static> {
-    Line::from(vec![
-        Span::styled(
-            format!("{label}: "),
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Span::raw(value),
-    ])
+    StyleManager::detail_line(label, value)
 }
 
 fn truncate(text: &str, max_len: usize) -> String {
@@ -564,4 +624,52 @@ mod tests {
             "render output missing details:\n{rendered}"
         );
     }
+
+    #[test]
+    fn status_screen_mouse_selects_tasks_and_skips_group_headers() {
+        let mut screen = StatusScreen::new().expect("screen should initialise");
+        screen.groups = vec![
+            TaskGroup {
+                label: "Manager PID 1".into(),
+                tasks: vec![
+                    sample_task(10, Some(1), None),
+                    sample_task(11, Some(1), None),
+                ],
+            },
+            TaskGroup {
+                label: "Standalone Tasks".into(),
+                tasks: vec![sample_task(30, None, None)],
+            },
+        ];
+        screen.flat_entries = StatusScreen::build_flat_index(&screen.groups);
+        screen.selected_index = 0;
+        screen.list_area = Some(Rect::new(0, 5, 50, 10));
+
+        let click = |row| MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 3,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+
+        // Rows: 6 header, 7-8 manager tasks, 9 header, 10 standalone task
+        screen.handle_mouse(click(10)).expect("handle mouse");
+        assert_eq!(screen.selected_index, 2);
+        screen.handle_mouse(click(8)).expect("handle mouse");
+        assert_eq!(screen.selected_index, 1);
+        screen.handle_mouse(click(9)).expect("handle mouse");
+        screen.handle_mouse(click(5)).expect("handle mouse");
+        assert_eq!(screen.selected_index, 1);
+
+        let wheel = MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            column: 3,
+            row: 7,
+            modifiers: KeyModifiers::NONE,
+        };
+        screen.handle_mouse(wheel).expect("handle mouse");
+        assert_eq!(screen.selected_index, 2);
+        screen.handle_mouse(wheel).expect("handle mouse");
+        assert_eq!(screen.selected_index, 2);
+    }
 }
@@ -0,0 +1,118 @@
+//! 自适应刷新节奏
+//!
+//! 有按键或任务状态变化时按基础间隔刷新；持续空闲后退避到较慢的间隔，
+//! 下一次活动时立即恢复，避免空闲终端持续占用 CPU。
+
+use std::time::{Duration, Instant};
+
+/// 默认屏幕刷新间隔
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+/// 允许配置的最小刷新间隔（约 60 FPS）
+pub const MIN_REFRESH_INTERVAL: Duration = Duration::from_millis(16);
+/// 空闲后的刷新间隔
+pub const IDLE_REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+/// 无活动多久后进入空闲
+pub const IDLE_THRESHOLD: Duration = Duration::from_secs(3);
+
+/// 根据最近活动时间决定刷新间隔
+#[derive(Debug, Clone)]
+pub struct AdaptiveTick {
+    base: Duration,
+    idle: Duration,
+    idle_after: Duration,
+    last_activity: Instant,
+}
+
+impl AdaptiveTick {
+    pub fn new(base: Duration, now: Instant) -> Self {
+        let base = base.max(MIN_REFRESH_INTERVAL);
+        Self {
+            base,
+            idle: IDLE_REFRESH_INTERVAL.max(base),
+            idle_after: IDLE_THRESHOLD,
+            last_activity: now,
+        }
+    }
+
+    /// 从 `~/.aiw/config.json` 的 `tui_refresh_ms` 读取基础间隔
+    pub fn from_config(now: Instant) -> Self {
+        let base = crate::utils::config_paths::ConfigPaths::new()
+            .ok()
+            .and_then(|paths| paths.user_config.tui_refresh_ms)
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_REFRESH_INTERVAL);
+        Self::new(base, now)
+    }
+
+    /// 记录一次活动（按键、任务状态变化），恢复快速刷新
+    pub fn record_activity(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+
+    pub fn is_idle(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_activity) >= self.idle_after
+    }
+
+    /// 当前应使用的刷新间隔
+    pub fn interval(&self, now: Instant) -> Duration {
+        if self.is_idle(now) {
+            self.idle
+        } else {
+            self.base
+        }
+    }
+
+    /// 距离下一次刷新还需等待的时长，用作事件轮询超时
+    pub fn poll_timeout(&self, now: Instant, last_update: Instant) -> Duration {
+        self.interval(now)
+            .saturating_sub(now.saturating_duration_since(last_update))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_engages_after_idle_period_and_resets_on_activity() {
+        let start = Instant::now();
+        let mut tick = AdaptiveTick::new(Duration::from_millis(100), start);
+
+        assert_eq!(tick.interval(start), Duration::from_millis(100));
+        let almost_idle = start + IDLE_THRESHOLD - Duration::from_millis(1);
+        assert_eq!(tick.interval(almost_idle), Duration::from_millis(100));
+
+        let idle = start + IDLE_THRESHOLD;
+        assert!(tick.is_idle(idle));
+        assert_eq!(tick.interval(idle), IDLE_REFRESH_INTERVAL);
+
+        tick.record_activity(idle);
+        assert!(!tick.is_idle(idle));
+        assert_eq!(tick.interval(idle), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn base_interval_is_clamped_and_never_slower_than_idle() {
+        let now = Instant::now();
+        let fast = AdaptiveTick::new(Duration::from_millis(1), now);
+        assert_eq!(fast.interval(now), MIN_REFRESH_INTERVAL);
+
+        let slow = AdaptiveTick::new(Duration::from_secs(2), now);
+        assert_eq!(slow.interval(now + IDLE_THRESHOLD), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn poll_timeout_counts_down_to_next_update() {
+        let now = Instant::now();
+        let tick = AdaptiveTick::new(Duration::from_millis(100), now);
+        let last_update = now;
+        assert_eq!(
+            tick.poll_timeout(now + Duration::from_millis(40), last_update),
+            Duration::from_millis(60)
+        );
+        assert_eq!(
+            tick.poll_timeout(now + Duration::from_millis(150), last_update),
+            Duration::ZERO
+        );
+    }
+}
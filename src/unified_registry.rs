@@ -5,10 +5,11 @@
 use crate::{
     core::models::ProcessTreeInfo,
     error::RegistryError,
-    storage::{CleanupEvent, RegistryEntry, TaskStorage},
-    task_record::TaskRecord,
+    storage::{CleanupEvent, PruneReport, RegistryEntry, RepairReport, TaskStorage},
+    task_record::{TaskRecord, TaskStatus},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use std::path::Path;
 use std::sync::Arc;
 
 /// 通用任务注册表
@@ -95,6 +96,18 @@ impl<S: TaskStorage> Registry<S> {
             .sweep_stale_entries(now, is_process_alive, terminate_process)
     }
 
+    /// 对账：进程已消失的运行中任务补标记为完成（`cleanup_reason = "process_vanished"`）
+    pub fn reconcile_tasks<F>(
+        &self,
+        now: DateTime<Utc>,
+        is_process_alive: F,
+    ) -> Result<Vec<CleanupEvent>, RegistryError>
+    where
+        F: Fn(u32) -> bool,
+    {
+        self.storage.reconcile_vanished(now, is_process_alive)
+    }
+
     /// 获取已完成但未读的任务
     pub fn get_completed_unread_tasks(&self) -> Result<Vec<(u32, TaskRecord)>, RegistryError> {
         self.storage.get_completed_unread_tasks()
@@ -107,6 +120,93 @@ impl<S: TaskStorage> Registry<S> {
     ) -> Result<bool, RegistryError> {
         self.storage.has_running_tasks(filter)
     }
+
+    /// 找出仍标记为运行中、但进程早已不存在的条目
+    pub fn dead_running_entries<F>(
+        &self,
+        is_process_alive: F,
+    ) -> Result<Vec<RegistryEntry>, RegistryError>
+    where
+        F: Fn(u32) -> bool,
+    {
+        Ok(self
+            .storage
+            .entries()?
+            .into_iter()
+            .filter(|entry| {
+                entry.record.status == TaskStatus::Running
+                    && (!is_process_alive(entry.pid) || entry.record.pid_reused(entry.pid))
+            })
+            .collect())
+    }
+
+    /// 按 PID 删除条目，返回实际删除的数量
+    pub fn remove_entries(&self, pids: &[u32]) -> Result<usize, RegistryError> {
+        self.storage.remove_entries(pids)
+    }
+
+    /// 清理已结束的任务条目及其日志文件
+    ///
+    /// - 完成时间早于 `max_age` 的终态条目会被删除
+    /// - 剩余终态条目超过 `max_completed` 时，按完成时间淘汰最旧的
+    /// - 只看记录状态：运行中的条目永远不会被删除；终态条目的 PID 可能已被其他进程复用，
+    ///   因此不再检查进程是否存活
+    pub fn prune_tasks(
+        &self,
+        now: DateTime<Utc>,
+        max_age: Duration,
+        max_completed: usize,
+    ) -> Result<PruneReport, RegistryError> {
+        let mut terminal: Vec<RegistryEntry> = self
+            .storage
+            .entries()?
+            .into_iter()
+            .filter(|entry| entry.record.status.is_terminal())
+            .collect();
+
+        // 最新的在前，便于按上限截断
+        terminal.sort_by_key(|entry| {
+            std::cmp::Reverse(entry.record.completed_at.unwrap_or(entry.record.started_at))
+        });
+
+        let doomed: Vec<RegistryEntry> = terminal
+            .into_iter()
+            .enumerate()
+            .filter(|(index, entry)| {
+                let finished_at = entry.record.completed_at.unwrap_or(entry.record.started_at);
+                *index >= max_completed || now.signed_duration_since(finished_at) > max_age
+            })
+            .map(|(_, entry)| entry)
+            .collect();
+
+        if doomed.is_empty() {
+            return Ok(PruneReport::default());
+        }
+
+        let pids: Vec<u32> = doomed.iter().map(|entry| entry.pid).collect();
+        self.storage.remove_entries(&pids)?;
+
+        let mut reclaimed_bytes = 0;
+        for entry in &doomed {
+            if entry.record.log_path.is_empty() {
+                continue;
+            }
+            let path = std::path::Path::new(&entry.record.log_path);
+            let command_file = crate::task_record::command_file_path(path);
+            for path in [path, command_file.as_path()] {
+                if let Ok(metadata) = std::fs::metadata(path) {
+                    if std::fs::remove_file(path).is_ok() {
+                        reclaimed_bytes += metadata.len();
+                    }
+                }
+            }
+        }
+
+        Ok(PruneReport {
+            removed_pids: pids,
+            reclaimed_bytes,
+        })
+    }
 }
 
 // 为了方便使用，提供类型别名
@@ -126,13 +226,26 @@ impl Registry<InProcessStorage> {
     }
 
     /// Bind a task_id and optional worktree info to an existing PID entry.
+    ///
+    /// Fields that are not provided are left untouched; returns false if the
+    /// entry no longer exists.
     pub fn update_task_metadata(
         &self,
         pid: u32,
         task_id: String,
         worktree: Option<crate::task_record::WorktreeInfo>,
-    ) {
-        self.storage.update_task_metadata(pid, task_id, worktree);
+    ) -> bool {
+        self.storage.update_task_metadata(pid, task_id, worktree)
+    }
+
+    /// Set or clear a task's alias; returns false if the entry no longer exists.
+    pub fn set_task_name(&self, pid: u32, name: Option<String>) -> bool {
+        self.storage.set_task_name(pid, name)
+    }
+
+    /// Entries carrying the given alias (several if the alias is ambiguous).
+    pub fn find_by_name(&self, name: &str) -> Vec<(u32, crate::task_record::TaskRecord)> {
+        self.storage.find_by_name(name)
     }
 }
 
@@ -166,11 +279,21 @@ impl Registry<SharedMemoryStorage> {
     pub fn cleanup(&self) -> Result<(), RegistryError> {
         self.storage.cleanup()
     }
+
+    /// 检测并修复指定命名空间的注册表（备份损坏的共享内存段后重建）
+    pub fn repair(
+        namespace: &str,
+        force: bool,
+        backup_dir: &Path,
+    ) -> Result<RepairReport, RegistryError> {
+        SharedMemoryStorage::repair(namespace, force, backup_dir)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::CleanupReason;
     use chrono::Utc;
 
     fn create_test_task(log_id: &str) -> TaskRecord {
@@ -232,6 +355,135 @@ mod tests {
         assert_eq!(entries2[0].pid, 200);
     }
 
+    #[test]
+    fn test_prune_tasks_respects_age_cap_and_status() {
+        let registry = Registry::in_process();
+        let dir = tempfile::TempDir::new().unwrap();
+        let now = Utc::now();
+
+        let register = |pid: u32, hours_ago: i64| {
+            let log_path = dir.path().join(format!("{pid}.log"));
+            std::fs::write(&log_path, "0123456789").unwrap();
+            let finished = now - Duration::hours(hours_ago);
+            let task = TaskRecord::new(
+                finished,
+                pid.to_string(),
+                log_path.to_string_lossy().to_string(),
+                None,
+            );
+            registry.register(pid, &task).unwrap();
+            registry
+                .mark_completed(pid, None, Some(0), finished)
+                .unwrap();
+        };
+
+        register(1, 48); // 超龄
+        register(2, 3);
+        register(3, 2);
+        register(4, 1);
+        register(5, 72); // 超龄，PID 已被其他存活进程复用也照样清理
+        registry.register(6, &create_test_task("running")).unwrap();
+
+        let report = registry.prune_tasks(now, Duration::hours(24), 2).unwrap();
+
+        let mut removed = report.removed_pids.clone();
+        removed.sort();
+        assert_eq!(removed, vec![1, 2, 5]);
+        assert_eq!(report.reclaimed_bytes, 30);
+        assert!(!dir.path().join("1.log").exists());
+
+        // 运行中的条目无论多旧都保留
+        let mut remaining: Vec<u32> = registry.entries().unwrap().iter().map(|e| e.pid).collect();
+        remaining.sort();
+        assert_eq!(remaining, vec![3, 4, 6]);
+    }
+
+    #[test]
+    fn test_reused_task_id_survives_pruning_the_old_task() {
+        let registry = Registry::in_process();
+        let now = Utc::now();
+
+        let mut old = create_test_task("old");
+        old.task_id = Some("nightly".to_string());
+        registry.register(30, &old).unwrap();
+        registry
+            .mark_completed(30, None, Some(0), now - Duration::hours(48))
+            .unwrap();
+
+        let mut new = create_test_task("new");
+        new.task_id = Some("nightly".to_string());
+        registry.register(31, &new).unwrap();
+        // 旧记录让出 task_id，避免出现两条同 id 的记录
+        assert_eq!(registry.storage().get_task(30).unwrap().task_id, None);
+
+        let report = registry.prune_tasks(now, Duration::hours(24), 10).unwrap();
+        assert_eq!(report.removed_pids, vec![30]);
+
+        let (pid, record) = registry.storage().get_by_task_id("nightly").unwrap();
+        assert_eq!(pid, 31);
+        assert_eq!(record.log_id, "new");
+    }
+
+    #[test]
+    fn test_reconcile_tasks_marks_vanished_processes() {
+        let registry = Registry::in_process();
+        registry.register(20, &create_test_task("alive")).unwrap();
+        registry
+            .register(21, &create_test_task("vanished"))
+            .unwrap();
+        registry
+            .register(22, &create_test_task("finished"))
+            .unwrap();
+        registry
+            .mark_completed(22, Some("done".into()), Some(0), Utc::now())
+            .unwrap();
+
+        let events = registry
+            .reconcile_tasks(Utc::now(), |pid| pid == 20)
+            .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].reason, CleanupReason::ProcessVanished);
+
+        let entries = registry.entries().unwrap();
+        let find = |pid: u32| entries.iter().find(|e| e.pid == pid).unwrap();
+        assert_eq!(find(20).record.status, TaskStatus::Running);
+        assert_eq!(find(21).record.status, TaskStatus::CompletedButUnread);
+        assert_eq!(
+            find(21).record.cleanup_reason.as_deref(),
+            Some("process_vanished")
+        );
+        assert_eq!(find(22).record.cleanup_reason, None);
+
+        // 再次对账不会重复产生事件
+        assert!(registry
+            .reconcile_tasks(Utc::now(), |pid| pid == 20)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_dead_running_entries() {
+        let registry = Registry::in_process();
+        registry.register(10, &create_test_task("alive")).unwrap();
+        registry.register(11, &create_test_task("dead")).unwrap();
+        registry
+            .register(12, &create_test_task("finished"))
+            .unwrap();
+        registry
+            .mark_completed(12, None, Some(0), Utc::now())
+            .unwrap();
+
+        let dead = registry.dead_running_entries(|pid| pid == 10).unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].pid, 11);
+
+        assert_eq!(registry.remove_entries(&[11]).unwrap(), 1);
+        assert!(registry
+            .dead_running_entries(|pid| pid == 10)
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_shared_storage_access() {
         let storage = Arc::new(InProcessStorage::new());
@@ -0,0 +1,65 @@
+//! ANSI 转义序列清理
+//!
+//! AI CLI 的进度条和颜色输出会原样写进任务日志，展示前用这里的函数去掉控制序列。
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// 去掉 CSI（`ESC [ … final`）、OSC（`ESC ] … BEL/ST`）以及其它两字节 ESC 序列
+///
+/// 换行、回车和制表符保留；不完整的序列（日志被截断在序列中间）直接丢弃。
+pub fn strip_ansi(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != ESC {
+            output.push(ch);
+            continue;
+        }
+        match chars.next() {
+            // CSI：参数/中间字节直到 0x40..=0x7E 的结束字节
+            Some('[') => {
+                for next in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&next) {
+                        break;
+                    }
+                }
+            }
+            // OSC：以 BEL 或 ST（ESC \）结束
+            Some(']') => {
+                while let Some(next) = chars.next() {
+                    if next == BEL {
+                        break;
+                    }
+                    if next == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // 其它 ESC 序列（如 ESC 7 / ESC =）只占一个字符
+            Some(_) | None => {}
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_colors_cursor_moves_and_titles() {
+        let raw = "\x1b[1;32mok\x1b[0m done\n\x1b[2K\r50%\x1b[1A\x1b]0;title\x07\x1b]8;;http://x\x1b\\link\x1b7";
+        assert_eq!(strip_ansi(raw), "ok done\n\r50%link");
+    }
+
+    #[test]
+    fn keeps_plain_text_and_drops_truncated_sequences() {
+        assert_eq!(strip_ansi("plain ☃ text\t\n"), "plain ☃ text\t\n");
+        assert_eq!(strip_ansi("tail\x1b[38;5"), "tail");
+        assert_eq!(strip_ansi("tail\x1b"), "tail");
+    }
+}
@@ -0,0 +1,218 @@
+//! JSON 配置文件的 schema 版本迁移
+//!
+//! 根对象的 `version` 字段记录 schema 版本，缺省视为 1。加载时按顺序执行迁移步骤，
+//! 发生迁移时先把原文件备份为 `<文件名>.v<旧版本>.bak`，再写回升级后的内容。
+//! 版本比当前程序新的文件直接报错，而不是按旧结构猜测着解析。
+
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// 记录 schema 版本的字段名
+pub const VERSION_FIELD: &str = "version";
+
+/// 把根对象从版本 N 升级到 N+1
+pub type MigrationStep = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error(
+        "{path} uses schema version {found}, but this aiw only understands up to version {supported}; upgrade aiw to read it"
+    )]
+    TooNew {
+        path: String,
+        found: u64,
+        supported: u64,
+    },
+    #[error("{path}: \"version\" must be a positive integer")]
+    InvalidVersion { path: String },
+    #[error("{path}: upgrading from version {from} failed: {detail}")]
+    StepFailed {
+        path: String,
+        from: u64,
+        detail: String,
+    },
+    #[error("Failed to migrate {path}: {source}")]
+    Io {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// 迁移结果
+#[derive(Debug)]
+pub struct Migrated {
+    /// 迁移后的 JSON 文本（无需迁移时为原文）
+    pub content: String,
+    /// 发生迁移时的原版本号
+    pub migrated_from: Option<u64>,
+    /// 原文件备份路径
+    pub backup: Option<PathBuf>,
+}
+
+/// `steps[i]` 把版本 `i + 1` 升级到 `i + 2`，当前版本为 `steps.len() + 1`
+pub fn current_version(steps: &[MigrationStep]) -> u64 {
+    steps.len() as u64 + 1
+}
+
+/// 对内存中的 JSON 执行迁移，返回原版本号（已是当前版本时为 `None`）
+///
+/// 根节点不是对象时原样返回，交给调用方按原有逻辑报错。
+pub fn migrate_value(
+    value: &mut Value,
+    steps: &[MigrationStep],
+    path: &Path,
+) -> Result<Option<u64>, MigrationError> {
+    let Value::Object(root) = value else {
+        return Ok(None);
+    };
+    let display = path.display().to_string();
+    let supported = current_version(steps);
+
+    let found = match root.get(VERSION_FIELD) {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| MigrationError::InvalidVersion {
+                path: display.clone(),
+            })?,
+    };
+    if found > supported {
+        return Err(MigrationError::TooNew {
+            path: display,
+            found,
+            supported,
+        });
+    }
+    if found == supported {
+        return Ok(None);
+    }
+
+    for (index, step) in steps.iter().enumerate().skip(found as usize - 1) {
+        step(root).map_err(|detail| MigrationError::StepFailed {
+            path: display.clone(),
+            from: index as u64 + 1,
+            detail,
+        })?;
+    }
+    root.insert(VERSION_FIELD.to_string(), Value::from(supported));
+    Ok(Some(found))
+}
+
+/// 读取 `path` 并按需迁移；迁移后备份原文件并写回
+///
+/// 内容不是合法 JSON 时不做处理，原文返回给调用方解析并报告错误。
+pub fn migrate_file(path: &Path, steps: &[MigrationStep]) -> Result<Migrated, MigrationError> {
+    let io_error = |source| MigrationError::Io {
+        path: path.display().to_string(),
+        source,
+    };
+    let content = fs::read_to_string(path).map_err(io_error)?;
+    let Ok(mut value) = serde_json::from_str::<Value>(&content) else {
+        return Ok(Migrated {
+            content,
+            migrated_from: None,
+            backup: None,
+        });
+    };
+
+    let Some(from) = migrate_value(&mut value, steps, path)? else {
+        return Ok(Migrated {
+            content,
+            migrated_from: None,
+            backup: None,
+        });
+    };
+
+    let backup = backup_path(path, from);
+    // fs::copy 保留权限位，providers.json 的 0600 不会因备份放宽
+    fs::copy(path, &backup).map_err(io_error)?;
+    let migrated = serde_json::to_string_pretty(&value).map_err(|err| io_error(err.into()))?;
+    fs::write(path, &migrated).map_err(io_error)?;
+
+    Ok(Migrated {
+        content: migrated,
+        migrated_from: Some(from),
+        backup: Some(backup),
+    })
+}
+
+fn backup_path(path: &Path, version: u64) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "config.json".to_string());
+    path.with_file_name(format!("{}.v{}.bak", name, version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_old_field(root: &mut Map<String, Value>) -> Result<(), String> {
+        if let Some(value) = root.remove("old") {
+            root.insert("new".to_string(), value);
+        }
+        Ok(())
+    }
+
+    fn require_name(root: &mut Map<String, Value>) -> Result<(), String> {
+        root.entry("name").or_insert_with(|| json!("unnamed"));
+        Ok(())
+    }
+
+    const STEPS: &[MigrationStep] = &[rename_old_field, require_name];
+
+    #[test]
+    fn unversioned_file_is_upgraded_and_backed_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        fs::write(&path, r#"{"old": 1}"#).unwrap();
+
+        let migrated = migrate_file(&path, STEPS).unwrap();
+        assert_eq!(migrated.migrated_from, Some(1));
+        let value: Value = serde_json::from_str(&migrated.content).unwrap();
+        assert_eq!(value, json!({"new": 1, "name": "unnamed", "version": 3}));
+        assert_eq!(fs::read_to_string(&path).unwrap(), migrated.content);
+
+        let backup = migrated.backup.unwrap();
+        assert_eq!(backup, dir.path().join("settings.json.v1.bak"));
+        assert_eq!(fs::read_to_string(backup).unwrap(), r#"{"old": 1}"#);
+
+        let again = migrate_file(&path, STEPS).unwrap();
+        assert_eq!(again.migrated_from, None);
+    }
+
+    #[test]
+    fn only_later_steps_run_for_newer_files() {
+        let mut value = json!({"old": 1, "version": 2});
+        let from = migrate_value(&mut value, STEPS, Path::new("x.json")).unwrap();
+        assert_eq!(from, Some(2));
+        assert_eq!(value, json!({"old": 1, "name": "unnamed", "version": 3}));
+    }
+
+    #[test]
+    fn newer_or_invalid_versions_are_rejected() {
+        let mut value = json!({"version": 9});
+        let err = migrate_value(&mut value, STEPS, Path::new("x.json")).unwrap_err();
+        assert!(matches!(
+            err,
+            MigrationError::TooNew {
+                found: 9,
+                supported: 3,
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("upgrade aiw"));
+
+        let mut value = json!({"version": "2"});
+        assert!(matches!(
+            migrate_value(&mut value, STEPS, Path::new("x.json")),
+            Err(MigrationError::InvalidVersion { .. })
+        ));
+    }
+}
@@ -6,16 +6,63 @@
 //! - 持久化配置（provider、auth、config）保存在 ~/.aiw/
 //! - 运行时数据（日志、临时文件）保存在 /tmp/.aiw/
 
+use crate::cli_type::{parse_cli_type, ArgTemplate, CliType};
+use crate::config::{AUTH_DIRECTORY, CONFIG_DIR_ENV};
+use crate::task_hooks::TaskHooks;
+use crate::utils::config_migration::{self, MigrationError, MigrationStep};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+/// config.json 的迁移步骤，`CONFIG_MIGRATIONS[i]` 把版本 i+1 升级到 i+2
+pub const CONFIG_MIGRATIONS: &[MigrationStep] = &[rename_cli_execution_order];
+
+/// v1 → v2：早期版本把 auto 模式的执行顺序写在 `cli_execution_order`
+fn rename_cli_execution_order(root: &mut Map<String, Value>) -> Result<(), String> {
+    if let Some(order) = root.remove("cli_execution_order") {
+        root.entry("auto_execution_order").or_insert(order);
+    }
+    Ok(())
+}
+
 /// 用户配置（从 config.json 读取）
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct UserConfig {
     /// 用户角色目录（默认 ~/.aiw/role/）
     #[serde(default)]
     pub user_roles_dir: Option<String>,
+    /// TUI 基础刷新间隔（毫秒，默认 100；空闲时自动退避）
+    #[serde(default)]
+    pub tui_refresh_ms: Option<u64>,
+    /// MCP 路由向量化后端（auto/fastembed/local/mock，环境变量优先）
+    #[serde(default)]
+    pub embedding_backend: Option<String>,
+    /// 本地 embedding 模型目录（离线环境使用，环境变量优先）
+    #[serde(default)]
+    pub embedding_model_dir: Option<String>,
+    /// 首个参数不是 CLI 类型时（如 `aiw "修复测试"`）使用的 AI CLI（claude/codex/gemini/grok）
+    #[serde(default)]
+    pub default_ai_type: Option<String>,
+    /// 角色注入后 prompt 的最大字符数，未设置或为 0 时不限制
+    #[serde(default)]
+    pub max_prompt_chars: Option<usize>,
+    /// 超过 `max_prompt_chars` 时截断任务文本并警告，而不是直接报错
+    #[serde(default)]
+    pub truncate_long_prompts: bool,
+    /// 按 CLI 名（claude/codex/gemini/grok）覆盖非交互启动参数，占位符见 [`crate::cli_type::TEMPLATE_PLACEHOLDERS`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub cli_arg_templates: BTreeMap<String, Vec<String>>,
+    /// 共享内存任务注册表最大条目数（默认 4096，每个条目约占 4 KiB 共享内存）
+    #[serde(default)]
+    pub registry_max_entries: Option<usize>,
+    /// 单条任务记录序列化后的最大字节数（默认 64 KiB）
+    #[serde(default)]
+    pub registry_max_value_bytes: Option<usize>,
+    /// 任务结束时运行的钩子（`on_complete` / `on_failure`），见 [`crate::task_hooks`]
+    #[serde(default, skip_serializing_if = "TaskHooks::is_empty")]
+    pub task_hooks: TaskHooks,
 }
 
 impl UserConfig {
@@ -23,7 +70,9 @@ impl UserConfig {
     pub fn load(config_file: &PathBuf) -> Self {
         if config_file.exists() {
             if let Ok(content) = std::fs::read_to_string(config_file) {
-                if let Ok(config) = serde_json::from_str(&content) {
+                if let Ok(mut config) = serde_json::from_str::<Self>(&content) {
+                    config.drop_invalid_arg_templates(config_file);
+                    config.drop_invalid_default_ai_type(config_file);
                     return config;
                 }
             }
@@ -31,22 +80,139 @@ impl UserConfig {
         Self::default()
     }
 
+    /// 加载前先把 config.json 升级到当前版本；版本比程序新时返回错误
+    pub fn load_migrated(config_file: &PathBuf) -> Result<Self, MigrationError> {
+        if config_file.exists() {
+            let migrated = config_migration::migrate_file(config_file, CONFIG_MIGRATIONS)?;
+            if let (Some(from), Some(backup)) = (migrated.migrated_from, &migrated.backup) {
+                eprintln!(
+                    "ℹ️  Upgraded {} from version {} to {} (backup: {})",
+                    config_file.display(),
+                    from,
+                    config_migration::current_version(CONFIG_MIGRATIONS),
+                    backup.display()
+                );
+            }
+        }
+        Ok(Self::load(config_file))
+    }
+
+    /// 去掉无法使用的参数模板并警告，对应 CLI 回退到内置参数
+    fn drop_invalid_arg_templates(&mut self, config_file: &PathBuf) {
+        self.cli_arg_templates.retain(|name, args| {
+            let valid = match parse_cli_type(name) {
+                Some(CliType::Auto) | None => Err(format!("unknown AI CLI '{}'", name)),
+                Some(_) => ArgTemplate::parse(args.clone()).map(|_| ()),
+            };
+            if let Err(err) = &valid {
+                tracing::warn!(
+                    "Ignoring cli_arg_templates.{} in {}: {}",
+                    name,
+                    config_file.display(),
+                    err
+                );
+            }
+            valid.is_ok()
+        });
+    }
+
+    /// 去掉无法作为默认 CLI 的 `default_ai_type` 并警告
+    fn drop_invalid_default_ai_type(&mut self, config_file: &PathBuf) {
+        let Some(name) = self.default_ai_type.as_deref() else {
+            return;
+        };
+        if let Err(err) = parse_default_ai_type(name) {
+            tracing::warn!("Ignoring {} in {}", err, config_file.display());
+            self.default_ai_type = None;
+        }
+    }
+
+    /// 该 CLI 的参数模板（已在加载时校验）
+    pub fn arg_template(&self, cli_type: &CliType) -> Option<ArgTemplate> {
+        let (_, args) = self
+            .cli_arg_templates
+            .iter()
+            .find(|(name, _)| parse_cli_type(name).as_ref() == Some(cli_type))?;
+        ArgTemplate::parse(args.clone()).ok()
+    }
+
     /// 获取用户角色目录（支持 ~ 展开）
     pub fn get_user_roles_dir(&self) -> Option<PathBuf> {
-        self.user_roles_dir.as_ref().map(|dir| {
-            if let Some(rest) = dir.strip_prefix("~/") {
-                if let Some(home) = dirs::home_dir() {
-                    return home.join(rest);
-                }
-            }
-            PathBuf::from(dir)
-        })
+        self.user_roles_dir.as_deref().map(expand_home)
+    }
+
+    /// 获取本地 embedding 模型目录（支持 ~ 展开）
+    pub fn get_embedding_model_dir(&self) -> Option<PathBuf> {
+        self.embedding_model_dir.as_deref().map(expand_home)
+    }
+}
+
+/// 解析 `default_ai_type`：只接受可直接启动的 CLI，`auto` 需要通过 `aiw auto` 使用
+pub fn parse_default_ai_type(name: &str) -> Result<CliType, String> {
+    match parse_cli_type(name.trim()) {
+        Some(CliType::Auto) | None => Err(format!(
+            "invalid default_ai_type '{}' (expected claude, codex, gemini or grok)",
+            name
+        )),
+        Some(cli_type) => Ok(cli_type),
+    }
+}
+
+/// 展开开头的 `~/`
+pub fn expand_home(dir: &str) -> PathBuf {
+    if let Some(rest) = dir.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    PathBuf::from(dir)
+}
+
+/// aiw 持久化配置根目录
+///
+/// `AIW_CONFIG_DIR`（支持 `~/` 开头）优先，否则为 `~/.aiw`；找不到 home 目录时返回 `None`。
+/// 所有 `~/.aiw/...` 路径都应从这里派生，以便测试和多套配置并存。
+pub fn aiw_config_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var(CONFIG_DIR_ENV) {
+        let dir = dir.trim();
+        if !dir.is_empty() {
+            return Some(expand_home(dir));
+        }
+    }
+    dirs::home_dir().map(|home| home.join(AUTH_DIRECTORY))
+}
+
+/// 从 argv 中取出 `--config-dir <DIR>` / `--config-dir=<DIR>`
+///
+/// 只识别紧跟程序名的位置（子命令之前），之后的参数可能属于被转发的 AI CLI。
+pub fn take_config_dir_arg(args: &mut Vec<String>) -> std::result::Result<Option<PathBuf>, String> {
+    let Some(first) = args.get(1) else {
+        return Ok(None);
+    };
+    if let Some(value) = first.strip_prefix("--config-dir=") {
+        if value.is_empty() {
+            return Err("--config-dir requires a directory".to_string());
+        }
+        let dir = expand_home(value);
+        args.remove(1);
+        return Ok(Some(dir));
+    }
+    if first != "--config-dir" {
+        return Ok(None);
+    }
+    match args.get(2) {
+        Some(value) if !value.is_empty() && !value.starts_with('-') => {
+            let dir = expand_home(value);
+            args.drain(1..3);
+            Ok(Some(dir))
+        }
+        _ => Err("--config-dir requires a directory".to_string()),
     }
 }
 
 /// 配置文件路径集合
 pub struct ConfigPaths {
-    /// 持久化配置目录（~/.aiw/）
+    /// 持久化配置目录（~/.aiw/，可由 `AIW_CONFIG_DIR` 覆盖）
     pub config_dir: PathBuf,
     /// 运行时数据目录（/tmp/.aiw/）
     pub runtime_dir: PathBuf,
@@ -67,11 +233,9 @@ pub struct ConfigPaths {
 impl ConfigPaths {
     /// 创建配置路径
     pub fn new() -> Result<Self> {
-        let home_dir =
-            dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
-
-        // 持久化配置目录：~/.aiw/
-        let config_dir = home_dir.join(".aiw");
+        // 持久化配置目录：~/.aiw/ 或 AIW_CONFIG_DIR
+        let config_dir =
+            aiw_config_dir().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
 
         // 运行时数据目录：使用系统临时目录（跨平台）
         // Linux/macOS: /tmp/.aiw/
@@ -79,7 +243,7 @@ impl ConfigPaths {
         let runtime_dir = std::env::temp_dir().join(".aiw");
 
         let config_file = config_dir.join("config.json");
-        let user_config = UserConfig::load(&config_file);
+        let user_config = UserConfig::load_migrated(&config_file)?;
 
         Ok(Self {
             provider_config: config_dir.join("provider.json"),
@@ -103,3 +267,100 @@ impl ConfigPaths {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn config_dir_flag_is_taken_only_before_the_command() {
+        let mut args = argv(&["aiw", "--config-dir", "/tmp/profile", "status"]);
+        assert_eq!(
+            take_config_dir_arg(&mut args).unwrap(),
+            Some(PathBuf::from("/tmp/profile"))
+        );
+        assert_eq!(args, argv(&["aiw", "status"]));
+
+        let mut args = argv(&["aiw", "--config-dir=/tmp/p2", "claude", "hi"]);
+        assert_eq!(
+            take_config_dir_arg(&mut args).unwrap(),
+            Some(PathBuf::from("/tmp/p2"))
+        );
+        assert_eq!(args, argv(&["aiw", "claude", "hi"]));
+
+        let mut args = argv(&["aiw", "claude", "--config-dir", "x"]);
+        assert_eq!(take_config_dir_arg(&mut args).unwrap(), None);
+        assert_eq!(args.len(), 4);
+
+        assert!(take_config_dir_arg(&mut argv(&["aiw", "--config-dir"])).is_err());
+        assert!(take_config_dir_arg(&mut argv(&["aiw", "--config-dir", "--help"])).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn env_override_moves_every_config_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::env::set_var(CONFIG_DIR_ENV, dir.path());
+
+        assert_eq!(aiw_config_dir(), Some(dir.path().to_path_buf()));
+        let paths = ConfigPaths::new().unwrap();
+        assert_eq!(paths.config_dir, dir.path());
+        assert_eq!(paths.config_file, dir.path().join("config.json"));
+
+        std::env::set_var(CONFIG_DIR_ENV, "  ");
+        assert!(aiw_config_dir().is_some_and(|dir| dir.ends_with(AUTH_DIRECTORY)));
+        std::env::remove_var(CONFIG_DIR_ENV);
+    }
+
+    #[test]
+    fn old_config_json_is_migrated_and_newer_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("config.json");
+        std::fs::write(
+            &config_file,
+            r#"{"cli_execution_order": ["codex", "claude", "gemini"], "tui_refresh_ms": 50}"#,
+        )
+        .unwrap();
+
+        let config = UserConfig::load_migrated(&config_file).unwrap();
+        assert_eq!(config.tui_refresh_ms, Some(50));
+        let value: Value =
+            serde_json::from_str(&std::fs::read_to_string(&config_file).unwrap()).unwrap();
+        assert_eq!(value["auto_execution_order"][0], "codex");
+        assert!(value.get("cli_execution_order").is_none());
+        assert_eq!(value["version"], 2);
+        assert!(dir.path().join("config.json.v1.bak").is_file());
+
+        std::fs::write(&config_file, r#"{"version": 99}"#).unwrap();
+        let err = UserConfig::load_migrated(&config_file).unwrap_err();
+        assert!(matches!(err, MigrationError::TooNew { found: 99, .. }));
+    }
+
+    #[test]
+    fn invalid_cli_arg_templates_are_dropped_at_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_file = dir.path().join("config.json");
+        std::fs::write(
+            &config_file,
+            r#"{"cli_arg_templates": {
+                "codex": ["exec", "--model", "o3", "{cli_args}", "{prompt}"],
+                "claude": ["-p"],
+                "dragon": ["{prompt}"]
+            }}"#,
+        )
+        .unwrap();
+
+        let config = UserConfig::load(&config_file);
+        assert_eq!(
+            config.cli_arg_templates.keys().collect::<Vec<_>>(),
+            vec!["codex"]
+        );
+        assert!(config.arg_template(&CliType::Codex).is_some());
+        assert!(config.arg_template(&CliType::Claude).is_none());
+    }
+}
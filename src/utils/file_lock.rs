@@ -0,0 +1,86 @@
+//! 跨进程文件锁
+//!
+//! 基于 `flock(LOCK_EX)`：锁随文件描述符释放，持锁进程崩溃不会留下死锁。
+//! 非 Unix 平台暂无跨进程锁，只依赖调用方的进程内互斥。
+
+use std::path::Path;
+
+/// 排他文件锁，drop 时释放
+pub struct FileLock {
+    #[cfg(unix)]
+    _file: std::fs::File,
+}
+
+/// 获取 `path` 上的排他锁（阻塞等待），必要时创建锁文件及其父目录
+pub fn lock_exclusive(path: &Path) -> std::io::Result<FileLock> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::io::AsRawFd;
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        loop {
+            // SAFETY: fd 在 file 存活期间有效
+            if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } == 0 {
+                return Ok(FileLock { _file: file });
+            }
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(err);
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(FileLock {})
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn creates_missing_parent_directories() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("nested").join("state.json.lock");
+
+        let _lock = lock_exclusive(&path).unwrap();
+
+        assert!(path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn second_lock_waits_for_the_first_to_drop() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json.lock");
+        let first = lock_exclusive(&path).unwrap();
+        let acquired = Arc::new(AtomicBool::new(false));
+
+        let waiter = {
+            let path = path.clone();
+            let acquired = acquired.clone();
+            std::thread::spawn(move || {
+                let _second = lock_exclusive(&path).unwrap();
+                acquired.store(true, Ordering::SeqCst);
+            })
+        };
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(!acquired.load(Ordering::SeqCst));
+        drop(first);
+        waiter.join().unwrap();
+        assert!(acquired.load(Ordering::SeqCst));
+    }
+}
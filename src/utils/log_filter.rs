@@ -0,0 +1,190 @@
+//! 任务日志按行过滤（类似 `grep -C`）
+//!
+//! MCP `manage_task` 的 logs 动作和 `aiw task attach --grep` 共用：只保留匹配的行，
+//! 可附带前后若干行上下文，不相邻的片段之间用 `--` 分隔。
+
+use regex::Regex;
+use std::collections::VecDeque;
+
+/// 片段分隔行，与 grep 的输出一致
+pub const GROUP_SEPARATOR: &str = "--";
+
+#[derive(Debug, Clone)]
+enum Matcher {
+    Substring(String),
+    Regex(Regex),
+}
+
+/// 校验过的行过滤条件
+#[derive(Debug, Clone)]
+pub struct LineFilter {
+    matcher: Matcher,
+    context: usize,
+}
+
+impl LineFilter {
+    /// `pattern` 默认按普通子串匹配；`is_regex` 时先编译，非法的正则直接报错
+    pub fn new(pattern: &str, is_regex: bool, context: usize) -> Result<Self, String> {
+        if pattern.is_empty() {
+            return Err("Log filter pattern cannot be empty".to_string());
+        }
+        let matcher = if is_regex {
+            Regex::new(pattern)
+                .map(Matcher::Regex)
+                .map_err(|err| format!("Invalid regex pattern {:?}: {}", pattern, err))?
+        } else {
+            Matcher::Substring(pattern.to_string())
+        };
+        Ok(Self { matcher, context })
+    }
+
+    pub fn is_match(&self, line: &str) -> bool {
+        match &self.matcher {
+            Matcher::Substring(needle) => line.contains(needle.as_str()),
+            Matcher::Regex(regex) => regex.is_match(line),
+        }
+    }
+
+    /// 过滤完整文本
+    pub fn apply(&self, text: &str) -> String {
+        let mut stream = self.stream();
+        let mut output = stream.push(text);
+        output.push_str(&stream.finish());
+        output
+    }
+
+    /// 增量过滤器，用于跟随仍在写入的日志
+    pub fn stream(&self) -> LineFilterStream {
+        LineFilterStream {
+            filter: self.clone(),
+            partial: String::new(),
+            before: VecDeque::new(),
+            after_remaining: 0,
+            line_no: 0,
+            last_emitted: None,
+        }
+    }
+}
+
+/// [`LineFilter`] 的流式状态：跨多次 `push` 保留未完成的行和上下文窗口
+#[derive(Debug)]
+pub struct LineFilterStream {
+    filter: LineFilter,
+    /// 尚未遇到换行的行尾
+    partial: String,
+    /// 最近的未输出行，作为下一个匹配的前置上下文
+    before: VecDeque<String>,
+    after_remaining: usize,
+    line_no: usize,
+    last_emitted: Option<usize>,
+}
+
+impl LineFilterStream {
+    /// 输入新读到的日志，返回可以确定输出的行（只处理以换行结束的行）
+    pub fn push(&mut self, chunk: &str) -> String {
+        self.partial.push_str(chunk);
+        let Some(end) = self.partial.rfind('\n') else {
+            return String::new();
+        };
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+
+        let mut output = String::new();
+        for line in complete.split_inclusive('\n') {
+            self.process_line(line, &mut output);
+        }
+        output
+    }
+
+    /// 日志结束：处理最后一个没有换行的行
+    pub fn finish(&mut self) -> String {
+        let mut output = String::new();
+        if !self.partial.is_empty() {
+            let mut line = std::mem::take(&mut self.partial);
+            line.push('\n');
+            self.process_line(&line, &mut output);
+        }
+        output
+    }
+
+    fn process_line(&mut self, line: &str, output: &mut String) {
+        let line_no = self.line_no;
+        self.line_no += 1;
+        let context = self.filter.context;
+
+        if self.filter.is_match(line.trim_end_matches(['\n', '\r'])) {
+            let first = line_no - self.before.len();
+            if context > 0 && self.last_emitted.is_some_and(|last| first > last + 1) {
+                output.push_str(GROUP_SEPARATOR);
+                output.push('\n');
+            }
+            for buffered in self.before.drain(..) {
+                output.push_str(&buffered);
+            }
+            output.push_str(line);
+            self.after_remaining = context;
+            self.last_emitted = Some(line_no);
+        } else if self.after_remaining > 0 {
+            output.push_str(line);
+            self.after_remaining -= 1;
+            self.last_emitted = Some(line_no);
+        } else if context > 0 {
+            if self.before.len() == context {
+                self.before.pop_front();
+            }
+            self.before.push_back(line.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "start\nerror: disk full\nretrying\nok\nok\nok\nerror: timeout\ndone";
+
+    #[test]
+    fn substring_and_regex_patterns_keep_matching_lines() {
+        let filter = LineFilter::new("error", false, 0).unwrap();
+        assert_eq!(filter.apply(LOG), "error: disk full\nerror: timeout\n");
+
+        let filter = LineFilter::new(r"^(start|done)$", true, 0).unwrap();
+        assert_eq!(filter.apply(LOG), "start\ndone\n");
+
+        // 非正则模式下元字符按字面匹配
+        let filter = LineFilter::new("error: (", false, 0).unwrap();
+        assert_eq!(filter.apply(LOG), "");
+
+        let err = LineFilter::new("error: (", true, 0).unwrap_err();
+        assert!(
+            err.starts_with("Invalid regex pattern \"error: (\""),
+            "{err}"
+        );
+        assert!(LineFilter::new("", false, 0).is_err());
+    }
+
+    #[test]
+    fn context_lines_are_grouped_with_separators() {
+        let filter = LineFilter::new("error", false, 1).unwrap();
+        assert_eq!(
+            filter.apply(LOG),
+            "start\nerror: disk full\nretrying\n--\nok\nerror: timeout\ndone\n"
+        );
+
+        // 上下文重叠的匹配合并为一个片段
+        let filter = LineFilter::new("error", false, 3).unwrap();
+        assert_eq!(filter.apply(LOG), format!("{}\n", LOG));
+    }
+
+    #[test]
+    fn streaming_handles_lines_split_across_chunks() {
+        let filter = LineFilter::new("error", false, 1).unwrap();
+        let mut stream = filter.stream();
+        let mut output = String::new();
+        for chunk in ["sta", "rt\nerr", "or: disk full\nretr", "ying\nok\n"] {
+            output.push_str(&stream.push(chunk));
+        }
+        output.push_str(&stream.finish());
+        assert_eq!(output, "start\nerror: disk full\nretrying\n");
+    }
+}
@@ -6,6 +6,92 @@ use anyhow::Result;
 use std::path::PathBuf;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+/// 终端日志输出格式，由 `AIW_LOG_FORMAT` 控制
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// 人类可读格式（默认）
+    #[default]
+    Pretty,
+    /// 每行一个 JSON 对象，事件字段平铺在顶层，便于日志聚合系统采集
+    Json,
+}
+
+impl LogFormat {
+    pub fn parse(value: &str) -> Self {
+        if value.trim().eq_ignore_ascii_case("json") {
+            Self::Json
+        } else {
+            Self::Pretty
+        }
+    }
+
+    pub fn from_env() -> Self {
+        std::env::var(crate::config::LOG_FORMAT_ENV)
+            .map(|value| Self::parse(&value))
+            .unwrap_or_default()
+    }
+}
+
+/// `-q/--quiet` 对应的过滤级别
+pub const QUIET_LOG_LEVEL: &str = "error";
+
+/// 日志过滤规则：`AIW_LOG` 优先，其次 `RUST_LOG`，都未设置（或无法解析）时使用 `default`
+fn cli_env_filter(default: &str) -> EnvFilter {
+    EnvFilter::try_from_env(crate::config::LOG_ENV)
+        .or_else(|_| EnvFilter::try_from_default_env())
+        .unwrap_or_else(|_| EnvFilter::new(default))
+}
+
+/// 初始化 CLI 进程的全局 tracing subscriber
+///
+/// 默认格式只输出 WARN 及以上；JSON 格式默认输出 aiw 的 INFO 事件（任务启动/退出/超时），
+/// 可用 `AIW_LOG` / `RUST_LOG` 覆盖，并始终写到 stderr 以免混入命令输出。
+/// `to_stderr` 用于 MCP stdio 模式，此时 stdout 是 JSON-RPC 通道。
+pub fn init_cli_logger(format: LogFormat, to_stderr: bool) {
+    match format {
+        LogFormat::Pretty if to_stderr => tracing_subscriber::fmt()
+            .with_target(false)
+            .with_env_filter(cli_env_filter("warn"))
+            .with_writer(std::io::stderr)
+            .init(),
+        LogFormat::Pretty => tracing_subscriber::fmt()
+            .with_target(false)
+            .with_env_filter(cli_env_filter("warn"))
+            .init(),
+        LogFormat::Json => {
+            tracing_subscriber::fmt()
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(false)
+                .with_env_filter(cli_env_filter("warn,aiw=info"))
+                .with_writer(std::io::stderr)
+                .init();
+        }
+    }
+}
+
+/// 从 argv 中取出 `-q` / `--quiet`
+///
+/// 与 `--config-dir` 相同，只在子命令之前查找（可与 `--config-dir` 任意排列），
+/// 子命令之后的 `-q` 可能属于被转发的 AI CLI。
+pub fn take_quiet_flag(args: &mut Vec<String>) -> bool {
+    let mut quiet = false;
+    let mut index = 1;
+    while let Some(arg) = args.get(index) {
+        match arg.as_str() {
+            "-q" | "--quiet" => {
+                args.remove(index);
+                quiet = true;
+            }
+            "--config-dir" => index += 2,
+            other if other.starts_with("--config-dir=") => index += 1,
+            _ => break,
+        }
+    }
+    quiet
+}
+
 /// 初始化日志系统
 ///
 /// # Arguments
@@ -78,3 +164,36 @@ pub fn init_logger(log_level: Option<&str>, log_file: Option<PathBuf>) -> Result
     tracing::info!("Logger initialized");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_format_defaults_to_pretty() {
+        assert_eq!(LogFormat::parse("json"), LogFormat::Json);
+        assert_eq!(LogFormat::parse(" JSON "), LogFormat::Json);
+        assert_eq!(LogFormat::parse("pretty"), LogFormat::Pretty);
+        assert_eq!(LogFormat::parse(""), LogFormat::Pretty);
+        assert_eq!(LogFormat::default(), LogFormat::Pretty);
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn quiet_flag_is_taken_only_before_the_command() {
+        let mut args = argv(&["aiw", "-q", "--config-dir", "/tmp/x", "--quiet", "status"]);
+        assert!(take_quiet_flag(&mut args));
+        assert_eq!(args, argv(&["aiw", "--config-dir", "/tmp/x", "status"]));
+
+        let mut args = argv(&["aiw", "--config-dir=/tmp/x", "-q", "claude", "-q"]);
+        assert!(take_quiet_flag(&mut args));
+        assert_eq!(args, argv(&["aiw", "--config-dir=/tmp/x", "claude", "-q"]));
+
+        let mut args = argv(&["aiw", "codex", "-q", "task"]);
+        assert!(!take_quiet_flag(&mut args));
+        assert_eq!(args.len(), 4);
+    }
+}
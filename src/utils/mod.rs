@@ -2,9 +2,14 @@
 //!
 //! 提供各种工具函数和辅助功能
 
+pub mod ansi;
+pub mod config_migration;
 pub mod config_paths;
 pub mod env;
+pub mod file_lock;
+pub mod log_filter;
 pub mod logger;
+pub mod protocol_stdout;
 pub mod version;
 
 // Re-exports removed - not used in current implementation
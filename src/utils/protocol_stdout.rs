@@ -0,0 +1,33 @@
+//! stdout 独占给 MCP stdio 传输
+//!
+//! `aiw mcp serve` 使用 stdio 传输时，stdout 上只能出现 JSON-RPC 帧；任何额外字节都会让
+//! 部分客户端解析失败并断开。启动传输前调用 [`reserve_for_protocol`]，之后面向用户的输出
+//! （例如任务结束时的尾部日志）统一经 [`write_user_output`] 写出，自动改道到 stderr。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::io::AsyncWriteExt;
+
+static RESERVED: AtomicBool = AtomicBool::new(false);
+
+/// 标记 stdout 已被 MCP 协议占用（进程内不可撤销）
+pub fn reserve_for_protocol() {
+    RESERVED.store(true, Ordering::SeqCst);
+}
+
+/// stdout 是否只允许写协议帧
+pub fn is_reserved() -> bool {
+    RESERVED.load(Ordering::SeqCst)
+}
+
+/// 写出本应进入 stdout 的用户输出；stdout 被协议占用时写到 stderr
+pub async fn write_user_output(data: &[u8]) -> std::io::Result<()> {
+    if is_reserved() {
+        let mut handle = tokio::io::stderr();
+        handle.write_all(data).await?;
+        handle.flush().await
+    } else {
+        let mut handle = tokio::io::stdout();
+        handle.write_all(data).await?;
+        handle.flush().await
+    }
+}
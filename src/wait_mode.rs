@@ -6,12 +6,12 @@ use crate::core::process_tree::ProcessTreeError;
 use crate::error::RegistryError;
 use crate::logging::warn;
 use crate::platform;
-use crate::registry_factory::{create_cli_registry, create_mcp_registry};
-use crate::storage::{CleanupReason, RegistryEntry};
+use crate::registry_factory::{create_cli_registry, create_mcp_registry, CliRegistry};
+use crate::storage::{CleanupReason, RegistryEntry, SharedMemoryStorage};
 use crate::task_record::TaskRecord;
 use crate::task_record::TaskStatus;
 use chrono::{DateTime, Local, Utc};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::Write;
 use std::thread;
 use std::time::{Duration, Instant};
@@ -23,6 +23,31 @@ pub enum WaitError {
     Registry(#[from] RegistryError),
     #[error("process tree error: {0}")]
     ProcessTree(#[from] ProcessTreeError),
+    #[error("no task matches '{0}'")]
+    UnknownTask(String),
+}
+
+/// `aiw wait <TASK_ID>...` / `aiw wait --all` 的轮询间隔
+const TARGET_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 等待期间条目被其他等待者读取并移除时的 cleanup_reason：任务已结束，但退出码未知
+const RECORD_REMOVED: &str = "record_removed";
+
+/// 指定任务等待的结果，用于决定退出码
+#[derive(Debug, Default)]
+pub struct TargetWaitOutcome {
+    pub succeeded: usize,
+    pub failed: usize,
+    /// 已结束但记录被其他等待者移除、无法得知退出码的任务，不计为失败
+    pub unknown: usize,
+    /// 达到最长等待时间时仍未结束的任务 PID
+    pub still_running: Vec<u32>,
+}
+
+impl TargetWaitOutcome {
+    pub fn is_success(&self) -> bool {
+        self.failed == 0 && self.still_running.is_empty()
+    }
 }
 
 pub fn run() -> Result<(), WaitError> {
@@ -146,6 +171,173 @@ pub fn run() -> Result<(), WaitError> {
     }
 }
 
+/// 等待指定任务（或 `all` 时所有运行中的任务）进入终态
+///
+/// `targets` 按任务 ID、PID 或日志 ID 匹配，支持 `*` 通配；任一目标没有匹配到任务时
+/// 直接报错。与 [`run`] 不同，这里会扫描本机所有任务注册表，且不按进程树过滤。
+/// 轮询间隔用异步 sleep，不占用运行时的工作线程。
+pub async fn run_targets(targets: &[String], all: bool) -> Result<TargetWaitOutcome, WaitError> {
+    let registries = open_all_cli_registries()?;
+    let snapshot = collect_entries(&registries)?;
+    let mut pending = select_targets(&snapshot, targets, all)?;
+    let mut outcome = TargetWaitOutcome::default();
+    let mut report = TaskReport::new();
+    let start = Instant::now();
+
+    if pending.is_empty() {
+        println!("No running tasks to wait for.");
+        return Ok(outcome);
+    }
+    println!("⏳ Waiting for {} task(s)...", pending.len());
+
+    loop {
+        let now = Utc::now();
+        for registry in &registries {
+            registry.reconcile_tasks(now, platform::process_alive)?;
+        }
+        let current: BTreeMap<u32, TaskRecord> = collect_entries(&registries)?
+            .into_iter()
+            .map(|entry| (entry.pid, entry.record))
+            .collect();
+
+        let mut finished = Vec::new();
+        for (pid, last_seen) in &pending {
+            let record = match current.get(pid) {
+                Some(record) if record.status == TaskStatus::Running => continue,
+                Some(record) => record.clone(),
+                // 条目已被其他等待者读取并移除，无法再得知退出码
+                None => {
+                    let mut record = last_seen.clone();
+                    record.cleanup_reason = Some(RECORD_REMOVED.to_string());
+                    record
+                }
+            };
+            finished.push((*pid, record));
+        }
+
+        for (pid, record) in finished {
+            pending.remove(&pid);
+            let completion = TaskCompletion::from_record(pid, record);
+            emit_realtime_update(&completion);
+            if completion.is_success() {
+                outcome.succeeded += 1;
+            } else if completion.is_unknown() {
+                outcome.unknown += 1;
+            } else {
+                outcome.failed += 1;
+            }
+            report.add_completion(completion);
+        }
+
+        if pending.is_empty() {
+            print_report(&report, None, false, start.elapsed());
+            return Ok(outcome);
+        }
+
+        if start.elapsed() >= MAX_WAIT_DURATION {
+            let running: Vec<RegistryEntry> = pending
+                .iter()
+                .map(|(pid, record)| RegistryEntry {
+                    pid: *pid,
+                    key: pid.to_string(),
+                    record: record.clone(),
+                })
+                .collect();
+            outcome.still_running = pending.keys().copied().collect();
+            print_report(&report, Some(&running), true, start.elapsed());
+            return Ok(outcome);
+        }
+
+        tokio::time::sleep(TARGET_POLL_INTERVAL).await;
+    }
+}
+
+/// 当前进程的注册表加上本机其他进程的注册表（仅 Linux 可枚举）
+pub(crate) fn open_all_cli_registries() -> Result<Vec<CliRegistry>, WaitError> {
+    let own = create_cli_registry()?;
+    let own_namespace = own.storage().namespace().to_string();
+    let mut registries = vec![own];
+    for namespace in SharedMemoryStorage::list_namespaces() {
+        if namespace == own_namespace {
+            continue;
+        }
+        match CliRegistry::shared_memory_with_namespace(namespace.clone()) {
+            Ok(registry) => registries.push(registry),
+            Err(err) => warn(format!("skipping task registry {namespace}: {err}")),
+        }
+    }
+    Ok(registries)
+}
+
+pub(crate) fn collect_entries(registries: &[CliRegistry]) -> Result<Vec<RegistryEntry>, WaitError> {
+    let mut entries = Vec::new();
+    for registry in registries {
+        entries.extend(registry.entries()?);
+    }
+    Ok(entries)
+}
+
+/// 从注册表快照中选出要等待的任务，按 PID 去重
+fn select_targets(
+    entries: &[RegistryEntry],
+    targets: &[String],
+    all: bool,
+) -> Result<BTreeMap<u32, TaskRecord>, WaitError> {
+    let mut selected = BTreeMap::new();
+    if all {
+        for entry in entries {
+            if entry.record.status == TaskStatus::Running {
+                selected.insert(entry.pid, entry.record.clone());
+            }
+        }
+        return Ok(selected);
+    }
+
+    for target in targets {
+        let mut matched = false;
+        for entry in entries.iter().filter(|entry| entry_matches(entry, target)) {
+            selected.insert(entry.pid, entry.record.clone());
+            matched = true;
+        }
+        if !matched {
+            return Err(WaitError::UnknownTask(target.clone()));
+        }
+    }
+    Ok(selected)
+}
+
+pub(crate) fn entry_matches(entry: &RegistryEntry, pattern: &str) -> bool {
+    let pid = entry.pid.to_string();
+    [
+        entry.record.task_id.as_deref(),
+        entry.record.name.as_deref(),
+        Some(pid.as_str()),
+        Some(entry.record.log_id.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|candidate| glob_match(pattern, candidate))
+}
+
+/// 只支持 `*` 的简单通配匹配
+fn glob_match(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = value.strip_prefix(parts.next().unwrap_or("")) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
 /// Check if a task should be processed based on root parent PID
 pub fn should_process_task(record: &TaskRecord, current_root_parent: Option<u32>) -> bool {
     // If we don't have root parent info, process all tasks
@@ -186,23 +378,23 @@ fn emit_realtime_update(task: &TaskCompletion) {
         .exit_code
         .map(|code| code.to_string())
         .unwrap_or_else(|| "未提供".to_string());
-    let status_word = if task.is_success() {
-        "完成"
-    } else {
-        "失败"
+    let status_word = task.status_word();
+    let task_label = match &task.task_id {
+        Some(task_id) => format!("{task_id} PID={}", task.pid),
+        None => format!("PID={}", task.pid),
     };
     let header = format!(
-        "{} 任务{} PID={} (exit_code: {}) @ {}",
+        "{} 任务{} {} (exit_code: {}) @ {}",
         task.status_icon(),
         status_word,
-        task.pid,
+        task_label,
         exit_code,
         task.completed_time_local()
     );
     let log_line = format!("日志文件: {}", task.log_path);
     let summary_line = format!("{}: {}", task.summary_label(), task.summary_text());
 
-    if task.is_success() {
+    if !task.is_failure() {
         println!("{header}");
         println!("{log_line}");
         println!("{summary_line}");
@@ -229,6 +421,7 @@ fn print_report(
 #[derive(Clone)]
 struct TaskCompletion {
     pid: u32,
+    task_id: Option<String>,
     log_path: String,
     started_at: DateTime<Utc>,
     completed_at: DateTime<Utc>,
@@ -243,6 +436,7 @@ impl TaskCompletion {
         record.completed_at = Some(completed_at);
         Self {
             pid,
+            task_id: record.task_id,
             log_path: record.log_path,
             started_at: record.started_at,
             completed_at,
@@ -256,14 +450,35 @@ impl TaskCompletion {
         self.cleanup_reason.is_none() && self.exit_code.unwrap_or(0) == 0
     }
 
+    /// 任务已结束，但记录在读取前被移除，成功与否无从得知
+    fn is_unknown(&self) -> bool {
+        self.cleanup_reason.as_deref() == Some(RECORD_REMOVED)
+    }
+
+    fn is_failure(&self) -> bool {
+        !self.is_success() && !self.is_unknown()
+    }
+
     fn status_icon(&self) -> &'static str {
         if self.is_success() {
             "✅"
+        } else if self.is_unknown() {
+            "❔"
         } else {
             "❌"
         }
     }
 
+    fn status_word(&self) -> &'static str {
+        if self.is_success() {
+            "完成"
+        } else if self.is_unknown() {
+            "已结束（结果未知）"
+        } else {
+            "失败"
+        }
+    }
+
     fn completed_time_local(&self) -> String {
         self.completed_at
             .with_timezone(&Local)
@@ -272,16 +487,18 @@ impl TaskCompletion {
     }
 
     fn summary_label(&self) -> &'static str {
-        if self.is_success() {
-            "结果摘要"
-        } else {
+        if self.is_failure() {
             "错误摘要"
+        } else {
+            "结果摘要"
         }
     }
 
     fn summary_text(&self) -> String {
         if let Some(result) = &self.result {
             result.clone()
+        } else if self.is_unknown() {
+            "任务已结束，但记录已被其他等待者读取并移除，无法得知退出码。".to_string()
         } else if let Some(reason) = &self.cleanup_reason {
             format!("任务被清理: {reason}")
         } else if self.is_success() {
@@ -332,7 +549,11 @@ impl TaskReport {
     }
 
     fn failed_count(&self) -> usize {
-        self.total_count() - self.successful_count()
+        self.completions.iter().filter(|c| c.is_failure()).count()
+    }
+
+    fn unknown_count(&self) -> usize {
+        self.completions.iter().filter(|c| c.is_unknown()).count()
     }
 
     fn total_duration(&self) -> Option<chrono::Duration> {
@@ -390,6 +611,9 @@ impl TaskReport {
         writeln!(buffer, "- 总任务数: {}", self.total_count())?;
         writeln!(buffer, "- 成功: {}个", self.successful_count())?;
         writeln!(buffer, "- 失败: {}个", self.failed_count())?;
+        if self.unknown_count() > 0 {
+            writeln!(buffer, "- 结果未知: {}个", self.unknown_count())?;
+        }
         writeln!(
             buffer,
             "- 总耗时: {}",
@@ -474,21 +698,13 @@ impl TaskCompletion {
             format!(
                 "{} {} (exit_code: {exit_code}, cleanup: {reason})",
                 self.status_icon(),
-                if self.is_success() {
-                    "完成"
-                } else {
-                    "失败"
-                }
+                self.status_word()
             )
         } else {
             format!(
                 "{} {} (exit_code: {exit_code})",
                 self.status_icon(),
-                if self.is_success() {
-                    "完成"
-                } else {
-                    "失败"
-                }
+                self.status_word()
             )
         }
     }
@@ -578,6 +794,86 @@ mod tests {
         assert_eq!(read_interval(), WAIT_INTERVAL_DEFAULT);
     }
 
+    fn entry(pid: u32, task_id: Option<&str>, status: TaskStatus) -> RegistryEntry {
+        let mut record = TaskRecord::new(
+            Utc::now(),
+            format!("log-{pid}"),
+            format!("/tmp/{pid}.log"),
+            None,
+        );
+        record.task_id = task_id.map(str::to_string);
+        record.status = status;
+        RegistryEntry {
+            pid,
+            key: pid.to_string(),
+            record,
+        }
+    }
+
+    #[test]
+    fn glob_match_supports_star_wildcards() {
+        assert!(glob_match("abc", "abc"));
+        assert!(!glob_match("abc", "abcd"));
+        assert!(glob_match("review-*", "review-42"));
+        assert!(glob_match("*-42", "review-42"));
+        assert!(glob_match("r*v*2", "review-42"));
+        assert!(glob_match("*", "anything"));
+        assert!(!glob_match("review-*", "build-42"));
+        assert!(!glob_match("a*bc", "abc-bc-x"));
+    }
+
+    #[test]
+    fn select_targets_matches_task_id_pid_or_log_id() {
+        let entries = vec![
+            entry(101, Some("review-1"), TaskStatus::Running),
+            entry(102, Some("review-2"), TaskStatus::CompletedButUnread),
+            entry(103, None, TaskStatus::Running),
+        ];
+
+        let targets = vec!["review-*".to_string(), "103".to_string()];
+        let selected = select_targets(&entries, &targets, false).expect("targets should match");
+        let pids: Vec<u32> = selected.keys().copied().collect();
+        assert_eq!(pids, vec![101, 102, 103]);
+
+        let by_log = select_targets(&entries, &["log-102".to_string()], false).unwrap();
+        assert_eq!(by_log.keys().copied().collect::<Vec<_>>(), vec![102]);
+
+        let err = select_targets(&entries, &["missing".to_string()], false).unwrap_err();
+        assert!(matches!(err, WaitError::UnknownTask(id) if id == "missing"));
+    }
+
+    #[test]
+    fn removed_records_are_reported_as_unknown_not_failed() {
+        let mut record = entry(301, Some("review-3"), TaskStatus::Running).record;
+        record.cleanup_reason = Some(RECORD_REMOVED.to_string());
+        let removed = TaskCompletion::from_record(301, record);
+        assert!(removed.is_unknown());
+        assert!(!removed.is_failure());
+        assert_eq!(removed.status_word(), "已结束（结果未知）");
+
+        let mut record = entry(302, None, TaskStatus::CompletedButUnread).record;
+        record.exit_code = Some(2);
+        let failed = TaskCompletion::from_record(302, record);
+        assert!(failed.is_failure());
+
+        let mut report = TaskReport::new();
+        report.add_completion(removed);
+        report.add_completion(failed);
+        assert_eq!(report.successful_count(), 0);
+        assert_eq!(report.failed_count(), 1);
+        assert_eq!(report.unknown_count(), 1);
+    }
+
+    #[test]
+    fn select_all_only_takes_running_tasks() {
+        let entries = vec![
+            entry(201, None, TaskStatus::Running),
+            entry(202, None, TaskStatus::CompletedButUnread),
+        ];
+        let selected = select_targets(&entries, &[], true).unwrap();
+        assert_eq!(selected.keys().copied().collect::<Vec<_>>(), vec![201]);
+    }
+
     #[test]
     fn test_should_process_task_filtering() {
         let base_time = Utc::now();
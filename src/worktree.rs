@@ -2,8 +2,29 @@
 //!
 //! Extracted from `commands/ai_cli.rs` for reuse across CLI and MCP contexts.
 
+use crate::utils::config_paths::aiw_config_dir;
+use crate::utils::file_lock::lock_exclusive;
 use anyhow::{anyhow, Result};
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 任务 key → worktree 映射文件（位于 `~/.aiw/`）
+pub const WORKTREE_MAP_FILE: &str = "worktrees.json";
+
+/// 按任务 key 创建的 worktree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyedWorktree {
+    pub path: PathBuf,
+    /// Repository the worktree was created from (canonicalized).
+    pub repo: PathBuf,
+    /// Branch checked out in the repository at creation time.
+    pub branch: String,
+    /// Commit the worktree was created at.
+    pub commit: String,
+    pub created_at: DateTime<Utc>,
+}
 
 /// Check if the given path is inside a git repository.
 pub(crate) fn check_git_repository(work_dir: &PathBuf) -> Result<()> {
@@ -73,3 +94,200 @@ pub(crate) fn create_worktree(repo_path: &PathBuf) -> Result<(PathBuf, String, S
 
     Ok((worktree_path, branch_name, commit_hash))
 }
+
+/// Create a worktree for `key`, or reuse the one created earlier for the same
+/// key when it still exists, belongs to the same repository and has no
+/// uncommitted or untracked changes.
+///
+/// Returns `(worktree_path, branch_name, commit_hash)` like [`create_worktree`].
+/// The key→worktree mapping is kept in `~/.aiw/worktrees.json`.
+pub(crate) fn create_or_reuse_worktree(
+    work_dir: &PathBuf,
+    key: &str,
+) -> Result<(PathBuf, String, String)> {
+    let map_path = aiw_config_dir()
+        .ok_or_else(|| anyhow!("Cannot find home directory"))?
+        .join(WORKTREE_MAP_FILE);
+    create_or_reuse_worktree_at(&map_path, work_dir, key)
+}
+
+fn create_or_reuse_worktree_at(
+    map_path: &Path,
+    work_dir: &PathBuf,
+    key: &str,
+) -> Result<(PathBuf, String, String)> {
+    let repo = std::fs::canonicalize(work_dir)
+        .map_err(|e| anyhow!("Failed to resolve {}: {}", work_dir.display(), e))?;
+    // 整个读改写期间持锁，并发启动的任务不会丢失彼此的映射
+    let _lock = lock_exclusive(&map_path.with_extension("json.lock"))
+        .map_err(|e| anyhow!("Failed to lock {}: {}", map_path.display(), e))?;
+    let mut map = load_worktree_map(map_path);
+    let recorded = map.len();
+    // 目录已被删除的 worktree 不可能再复用
+    map.retain(|_, entry| entry.path.exists());
+
+    if let Some(existing) = map.get(key) {
+        if let Some(commit) = reusable_commit(existing, &repo) {
+            tracing::info!(
+                "Reusing worktree {} for task {}",
+                existing.path.display(),
+                key
+            );
+            let reused = (existing.path.clone(), existing.branch.clone(), commit);
+            if map.len() != recorded {
+                record_worktree_map(map_path, &map, key);
+            }
+            return Ok(reused);
+        }
+        if existing.path.exists() {
+            tracing::warn!(
+                "Worktree {} of task {} has local changes or no longer matches {}; creating a new one",
+                existing.path.display(),
+                key,
+                repo.display()
+            );
+        }
+    }
+
+    let (path, branch, commit) = create_worktree(work_dir)?;
+    map.insert(
+        key.to_string(),
+        KeyedWorktree {
+            path: path.clone(),
+            repo,
+            branch: branch.clone(),
+            commit: commit.clone(),
+            created_at: Utc::now(),
+        },
+    );
+    record_worktree_map(map_path, &map, key);
+    Ok((path, branch, commit))
+}
+
+/// 保存映射；失败只影响之后的复用，不中断任务启动
+fn record_worktree_map(map_path: &Path, map: &BTreeMap<String, KeyedWorktree>, key: &str) {
+    if let Err(err) = save_worktree_map(map_path, map) {
+        tracing::warn!(
+            "Failed to record worktree of task {} in {}: {}",
+            key,
+            map_path.display(),
+            err
+        );
+    }
+}
+
+/// Current HEAD of a recorded worktree if it can be handed out again
+fn reusable_commit(entry: &KeyedWorktree, repo: &Path) -> Option<String> {
+    if entry.repo != repo || !entry.path.is_dir() {
+        return None;
+    }
+    let worktree = git2::Repository::open(&entry.path).ok()?;
+    if !worktree.is_worktree() {
+        return None;
+    }
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    if !worktree.statuses(Some(&mut options)).ok()?.is_empty() {
+        return None;
+    }
+    let head = worktree.head().ok()?.peel_to_commit().ok()?;
+    Some(head.id().to_string())
+}
+
+/// Read the key→worktree mapping; a missing or unreadable file is empty
+pub fn load_worktree_map(path: &Path) -> BTreeMap<String, KeyedWorktree> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|err| {
+        tracing::warn!("Ignoring unreadable {}: {}", path.display(), err);
+        BTreeMap::new()
+    })
+}
+
+fn save_worktree_map(path: &Path, map: &BTreeMap<String, KeyedWorktree>) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // 先写临时文件再替换，并发任务不会读到半截 JSON
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(map)?)?;
+    std::fs::rename(tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn init_repo(dir: &Path) {
+        let repo = git2::Repository::init(dir).unwrap();
+        std::fs::write(dir.join("README.md"), "hello\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("aiw", "aiw@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[])
+            .unwrap();
+    }
+
+    fn remove_worktree(repo: &Path, worktree: &Path) {
+        let _ = Command::new("git")
+            .args(["worktree", "remove", "--force"])
+            .arg(worktree)
+            .current_dir(repo)
+            .output();
+    }
+
+    #[test]
+    fn same_key_reuses_a_clean_worktree() {
+        let repo_dir = tempfile::tempdir().unwrap();
+        init_repo(repo_dir.path());
+        let repo = repo_dir.path().to_path_buf();
+        let map_path = repo_dir.path().join(".aiw").join(WORKTREE_MAP_FILE);
+
+        let (first, _, commit) = create_or_reuse_worktree_at(&map_path, &repo, "task-a").unwrap();
+        let (again, _, again_commit) =
+            create_or_reuse_worktree_at(&map_path, &repo, "task-a").unwrap();
+        assert_eq!(first, again);
+        assert_eq!(commit, again_commit);
+
+        let (other, _, _) = create_or_reuse_worktree_at(&map_path, &repo, "task-b").unwrap();
+        assert_ne!(first, other);
+
+        // 有未提交改动的 worktree 不复用
+        std::fs::write(first.join("notes.txt"), "wip").unwrap();
+        let (fresh, _, _) = create_or_reuse_worktree_at(&map_path, &repo, "task-a").unwrap();
+        assert_ne!(first, fresh);
+
+        let map = load_worktree_map(&map_path);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["task-a"].path, fresh);
+        assert_eq!(map["task-b"].path, other);
+
+        for worktree in [&first, &other, &fresh] {
+            remove_worktree(repo_dir.path(), worktree);
+        }
+    }
+
+    #[test]
+    fn entries_of_deleted_worktrees_are_dropped() {
+        let repo_dir = tempfile::tempdir().unwrap();
+        init_repo(repo_dir.path());
+        let repo = repo_dir.path().to_path_buf();
+        let map_path = repo_dir.path().join(".aiw").join(WORKTREE_MAP_FILE);
+
+        let (first, _, _) = create_or_reuse_worktree_at(&map_path, &repo, "task-a").unwrap();
+        let (second, _, _) = create_or_reuse_worktree_at(&map_path, &repo, "task-b").unwrap();
+        remove_worktree(repo_dir.path(), &first);
+        assert!(!first.exists());
+
+        let (again, _, _) = create_or_reuse_worktree_at(&map_path, &repo, "task-b").unwrap();
+        assert_eq!(again, second);
+        let map = load_worktree_map(&map_path);
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec!["task-b"]);
+
+        remove_worktree(repo_dir.path(), &second);
+    }
+}
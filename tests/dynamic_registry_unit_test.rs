@@ -197,6 +197,78 @@ async fn test_tool_registry_has_tool() {
     assert!(!registry.has_tool("non_existent").await);
 }
 
+#[tokio::test]
+async fn test_manual_js_tool_registration() {
+    use aiw::mcp::{register_js_tool, RegisterJsToolParams};
+    use aiw::mcp_routing::registry::DynamicToolRegistry;
+    use serde_json::json;
+
+    let base_tools = vec![Tool {
+        name: Cow::Borrowed("base_tool"),
+        description: Some(Cow::Borrowed("Base")),
+        input_schema: Arc::new(Map::new()),
+        title: None,
+        output_schema: None,
+        icons: None,
+        annotations: None,
+        execution: None,
+        meta: None,
+    }];
+    let registry = DynamicToolRegistry::new(base_tools);
+
+    let params = |name: &str, js_code: &str| RegisterJsToolParams {
+        name: name.to_string(),
+        description: "Hand-written workflow".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {"path": {"type": "string"}},
+            "required": ["path"]
+        }),
+        js_code: js_code.to_string(),
+    };
+    let workflow = r#"
+        async function workflow(input) {
+            return await mcp.call("filesystem", "read_file", { path: input.path });
+        }
+    "#;
+
+    let first = register_js_tool(&registry, params("read_it", workflow))
+        .await
+        .unwrap();
+    assert!(first.is_new);
+    assert!(registry.has_tool("read_it").await);
+
+    let replaced = register_js_tool(&registry, params("read_it", workflow))
+        .await
+        .unwrap();
+    assert!(!replaced.is_new);
+
+    // 与基础工具重名
+    let err = register_js_tool(&registry, params("base_tool", workflow))
+        .await
+        .unwrap_err();
+    assert!(err.contains("built-in"), "{err}");
+
+    // 安全检查不通过的代码不会注册
+    let err = register_js_tool(
+        &registry,
+        params(
+            "evil",
+            "async function workflow(input) { return eval('1'); }",
+        ),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.contains("js_code"), "{err}");
+    assert!(!registry.has_tool("evil").await);
+
+    // 非 object 根的 schema
+    let mut bad_schema = params("bad_schema", workflow);
+    bad_schema.input_schema = json!({"type": "string"});
+    let err = register_js_tool(&registry, bad_schema).await.unwrap_err();
+    assert!(err.contains("input_schema"), "{err}");
+}
+
 // Note: Full integration test with IntelligentRouter::initialize() would require:
 // 1. Running MCP servers (filesystem, memory, etc.)
 // 2. Valid Ollama endpoint for LLM mode
@@ -114,6 +114,7 @@ pub fn write_mcp_config(home: &TempHome, servers: Vec<ServerFixture>) -> Result<
     let config = McpConfig {
         version: "1.0".to_string(),
         mcp_servers,
+        routing: Default::default(),
     };
     let config_path = aiw_dir.join("mcp.json");
     fs::write(&config_path, serde_json::to_string_pretty(&config)?)?;
@@ -1,15 +1,16 @@
+use aiw::commands::mcp::registry::types::McpServerDetail;
 use aiw::commands::mcp::registry::{
-    aggregator::RegistryAggregator,
+    aggregator::{sort_results, RegistryAggregator, SearchSort},
     official::OfficialRegistrySource,
     smithery::SmitherySource,
     source::RegistrySource,
     types::{McpServerInfo, ServerInstallType},
 };
-use aiw::commands::mcp::registry::types::McpServerDetail;
 use aiw::commands::mcp::McpServerConfig;
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use mockito::Matcher;
+use std::time::Duration;
 
 #[tokio::test]
 async fn official_source_maps_identifier_and_env() {
@@ -148,6 +149,80 @@ async fn aggregator_prefers_higher_priority_sources() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn aggregator_returns_partial_results_when_a_source_times_out() -> Result<()> {
+    let responsive = StubSource {
+        id: "registry",
+        priority: 1,
+        result: vec![info("fast", "Answers quickly", None)],
+    };
+    let aggregator =
+        RegistryAggregator::with_sources(vec![Box::new(responsive), Box::new(HangingSource)])
+            .with_timeouts(Duration::from_millis(50), Duration::from_millis(100));
+
+    let outcome = aggregator.search_with_failures("query", None, 10).await?;
+    assert_eq!(outcome.results.len(), 1);
+    assert_eq!(outcome.results[0].display_name, "fast");
+    assert_eq!(outcome.failures.len(), 1);
+    assert_eq!(outcome.failures[0].0, "smithery");
+    assert!(outcome.failures[0].1.contains("did not respond"));
+
+    let err = aggregator
+        .get_server_detail("smithery:slow", None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("did not respond"), "{err}");
+
+    let only_hanging = RegistryAggregator::with_sources(vec![Box::new(HangingSource)])
+        .with_timeouts(Duration::from_millis(50), Duration::from_millis(100));
+    let err = only_hanging.search("query", None, 10).await.unwrap_err();
+    assert!(err.to_string().contains("--timeout"), "{err}");
+    Ok(())
+}
+
+fn info(name: &str, description: &str, downloads: Option<u64>) -> McpServerInfo {
+    McpServerInfo {
+        qualified_name: format!("@test/{}", name),
+        display_name: name.to_string(),
+        description: Some(description.to_string()),
+        source: "registry".to_string(),
+        install: ServerInstallType::Npm {
+            package: format!("@test/{}", name),
+        },
+        author: None,
+        downloads,
+    }
+}
+
+#[test]
+fn search_results_follow_requested_sort() {
+    let results = vec![
+        info("notes", "Take notes on git history", Some(900)),
+        info("git-tools", "Extra helpers", Some(10)),
+        info("Git", "Git operations", None),
+        info("alpha", "Unrelated", Some(500)),
+    ];
+    let names = |list: &[McpServerInfo]| {
+        list.iter()
+            .map(|info| info.display_name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let mut by_relevance = results.clone();
+    sort_results(&mut by_relevance, "git", SearchSort::Relevance);
+    assert_eq!(names(&by_relevance), ["Git", "git-tools", "notes", "alpha"]);
+
+    let mut by_downloads = results.clone();
+    sort_results(&mut by_downloads, "git", SearchSort::Downloads);
+    assert_eq!(names(&by_downloads), ["notes", "alpha", "git-tools", "Git"]);
+
+    let mut by_name = results;
+    sort_results(&mut by_name, "git", SearchSort::Name);
+    assert_eq!(names(&by_name), ["alpha", "Git", "git-tools", "notes"]);
+
+    assert!("popular".parse::<SearchSort>().is_err());
+}
+
 struct StubSource {
     id: &'static str,
     priority: u8,
@@ -180,3 +255,33 @@ impl RegistrySource for StubSource {
         Err(anyhow!("not implemented"))
     }
 }
+
+/// A registry that never answers
+struct HangingSource;
+
+#[async_trait]
+impl RegistrySource for HangingSource {
+    fn source_name(&self) -> &'static str {
+        "smithery"
+    }
+
+    fn source_id(&self) -> &'static str {
+        "smithery"
+    }
+
+    fn priority(&self) -> u8 {
+        2
+    }
+
+    async fn search(&self, _query: &str, _limit: usize) -> Result<Vec<McpServerInfo>> {
+        std::future::pending().await
+    }
+
+    async fn get_server(&self, _name: &str) -> Result<Option<McpServerDetail>> {
+        std::future::pending().await
+    }
+
+    async fn get_install_config(&self, _name: &str) -> Result<McpServerConfig> {
+        std::future::pending().await
+    }
+}
@@ -98,6 +98,8 @@ async fn test_routing_request_construction() {
         user_request: "list files in /tmp".to_string(),
         session_id: Some("test-session".to_string()),
         max_candidates: Some(5),
+        server_allowlist: Vec::new(),
+        category: None,
         decision_mode: DecisionMode::LlmReact,
         execution_mode: ExecutionMode::Query,
         metadata: [("key".to_string(), "value".to_string())]
@@ -112,6 +114,30 @@ async fn test_routing_request_construction() {
     assert_eq!(custom_request.max_candidates, Some(5));
 }
 
+#[tokio::test]
+async fn test_routing_request_candidate_filters() {
+    let unfiltered = IntelligentRouteRequest::default();
+    assert!(!unfiltered.filters_candidates());
+    assert!(unfiltered.admits("git", None));
+
+    let request: IntelligentRouteRequest = serde_json::from_value(serde_json::json!({
+        "user_request": "read README.md",
+        "server_allowlist": ["filesystem", "fs-backup"],
+        "category": "Filesystem"
+    }))
+    .unwrap();
+    assert!(request.filters_candidates());
+    assert!(request.admits("filesystem", Some("filesystem")));
+    assert!(!request.admits("git", Some("filesystem")), "not allowlisted");
+    assert!(!request.admits("fs-backup", None), "uncategorized server");
+
+    let by_category = IntelligentRouteRequest {
+        category: Some("uncategorized".to_string()),
+        ..IntelligentRouteRequest::default()
+    };
+    assert!(by_category.admits("anything", None));
+}
+
 #[tokio::test]
 async fn test_concurrent_tool_operations() {
     let registry: Arc<DynamicToolRegistry> = Arc::new(DynamicToolRegistry::new(vec![]));
@@ -0,0 +1,136 @@
+//! MCP stdio 模式下 stdout 只能出现 JSON-RPC 帧
+//!
+//! 把进程的 fd 1 重定向到临时文件，模拟一次 `start_task` 工具调用（任务输出会在结束时
+//! 以尾部日志的形式回显），然后检查 stdout 上的每一行都是协议帧。
+#![cfg(unix)]
+
+use aiw::config::CONFIG_DIR_ENV;
+use aiw::mcp::{list_tasks, start_task, StartTaskParams};
+use aiw::provider::config::AiType;
+use aiw::task_record::TaskStatus;
+use aiw::utils::protocol_stdout;
+use rmcp::service::RoleServer;
+use serial_test::serial;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+
+const FRAME: &str = r#"{"jsonrpc":"2.0","id":1,"result":{}}"#;
+
+/// 在存活期间把 fd 1 指向 `path`
+struct StdoutCapture {
+    saved: i32,
+}
+
+impl StdoutCapture {
+    fn start(path: &Path) -> Self {
+        let file = fs::File::create(path).unwrap();
+        std::io::stdout().flush().unwrap();
+        // SAFETY: 只复制/替换标准输出描述符，drop 时恢复
+        unsafe {
+            let saved = libc::dup(1);
+            assert!(saved >= 0);
+            assert!(libc::dup2(file.as_raw_fd(), 1) >= 0);
+            Self { saved }
+        }
+    }
+}
+
+impl Drop for StdoutCapture {
+    fn drop(&mut self) {
+        let _ = std::io::stdout().flush();
+        // SAFETY: saved 是 start 中 dup 得到的有效描述符
+        unsafe {
+            libc::dup2(self.saved, 1);
+            libc::close(self.saved);
+        }
+    }
+}
+
+fn write_fake_cli(dir: &Path) -> String {
+    let path = dir.join("fake-codex");
+    fs::write(
+        &path,
+        "#!/bin/sh\necho 'codex: working on it'\necho 'codex: done'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[tokio::test]
+#[serial]
+async fn simulated_tool_call_leaves_only_protocol_frames_on_stdout() {
+    let dir = tempfile::tempdir().unwrap();
+    env::set_var(CONFIG_DIR_ENV, dir.path().join(".aiw"));
+    env::set_var("CODEX_BIN", write_fake_cli(dir.path()));
+
+    protocol_stdout::reserve_for_protocol();
+    let captured = dir.path().join("stdout.txt");
+    let capture = StdoutCapture::start(&captured);
+
+    // 服务端响应照常写 stdout
+    let mut stdout = tokio::io::stdout();
+    stdout
+        .write_all(format!("{FRAME}\n").as_bytes())
+        .await
+        .unwrap();
+    stdout.flush().await.unwrap();
+
+    let params = StartTaskParams {
+        ai_type: Some(AiType::Codex),
+        task: "say hello".to_string(),
+        name: None,
+        task_id: None,
+        provider: None,
+        model: None,
+        priority: None,
+        role: None,
+        role_position: None,
+        cwd: None,
+        cli_args: None,
+        worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
+    };
+    let peer: Arc<RwLock<Option<rmcp::service::Peer<RoleServer>>>> = Arc::new(RwLock::new(None));
+    let launch = start_task(params, peer).await.expect("task should launch");
+    let pid = launch.pid.expect("task should not be queued");
+
+    for _ in 0..100 {
+        let tasks = list_tasks().await.unwrap();
+        let running = tasks
+            .iter()
+            .any(|task| task.pid == pid && task.status == TaskStatus::Running);
+        if !running {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    // 尾部日志在标记完成之后写出
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    drop(capture);
+
+    let output = fs::read_to_string(&captured).unwrap();
+    let lines: Vec<&str> = output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    for line in &lines {
+        let frame: serde_json::Value = serde_json::from_str(line)
+            .unwrap_or_else(|_| panic!("non-protocol output on stdout: {line:?}"));
+        assert_eq!(frame["jsonrpc"], "2.0", "{line}");
+    }
+    assert_eq!(lines, vec![FRAME]);
+
+    env::remove_var(CONFIG_DIR_ENV);
+    env::remove_var("CODEX_BIN");
+}
@@ -36,6 +36,8 @@ mod tests {
             user_request: fs_task.to_string(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: Default::default(),
@@ -86,6 +88,8 @@ mod tests {
             user_request: memory_task.to_string(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: Default::default(),
@@ -139,6 +143,8 @@ mod tests {
             user_request: mixed_task.to_string(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: Default::default(),
@@ -188,6 +194,8 @@ mod tests {
             user_request: vector_task.to_string(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Query,
             metadata: Default::default(),
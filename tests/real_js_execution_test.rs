@@ -40,6 +40,8 @@ mod tests {
             user_request: complex_task.to_string(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Query,
             metadata: Default::default(),
@@ -97,6 +99,8 @@ mod tests {
             user_request: "请编写一个JavaScript函数，输入是数字数组，返回排序后的数组".to_string(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Query,
             metadata: Default::default(),
@@ -139,6 +143,8 @@ mod tests {
             user_request: "请执行以下工作流：\n1. 生成一个随机数列表\n2. 计算平均值\n3. 找出最大值和最小值\n4. 返回统计摘要".to_string(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Query,
             metadata: Default::default(),
@@ -183,6 +189,8 @@ mod tests {
                     .to_string(),
             session_id: None,
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Query,
             metadata: Default::default(),
@@ -122,6 +122,8 @@ mod tests {
             user_request: test_task.to_string(),
             session_id: Some(format!("ollama-e2e-{}", chrono::Utc::now().timestamp())),
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: Default::default(),
@@ -224,6 +226,8 @@ mod tests {
             user_request: test_task.to_string(),
             session_id: Some(format!("ai-cli-e2e-{}", chrono::Utc::now().timestamp())),
             max_candidates: None,
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: Default::default(),
@@ -421,6 +425,8 @@ mod tests {
                             chrono::Utc::now().timestamp()
                         )),
                         max_candidates: None,
+                        server_allowlist: Vec::new(),
+                        category: None,
                         decision_mode: DecisionMode::Auto,
                         execution_mode: ExecutionMode::Dynamic,
                         metadata: Default::default(),
@@ -461,6 +467,8 @@ mod tests {
                             chrono::Utc::now().timestamp()
                         )),
                         max_candidates: None,
+                        server_allowlist: Vec::new(),
+                        category: None,
                         decision_mode: DecisionMode::Auto,
                         execution_mode: ExecutionMode::Dynamic,
                         metadata: Default::default(),
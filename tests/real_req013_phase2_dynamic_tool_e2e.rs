@@ -58,6 +58,8 @@ mod tests {
             user_request: "list all files in /tmp directory".to_string(),
             session_id: Some("test-session-001".to_string()),
             max_candidates: Some(3),
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic, // ← 关键：Dynamic模式
             metadata: Default::default(),
@@ -202,6 +204,8 @@ mod tests {
             user_request: complex_task.to_string(),
             session_id: Some("test-js-workflow".to_string()),
             max_candidates: Some(5),
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: Default::default(),
@@ -292,6 +296,8 @@ mod tests {
                 user_request: task.to_string(),
                 session_id: Some(format!("test-fifo-{}", i)),
                 max_candidates: Some(3),
+                server_allowlist: Vec::new(),
+                category: None,
                 decision_mode: DecisionMode::Auto,
                 execution_mode: ExecutionMode::Dynamic,
                 metadata: Default::default(),
@@ -397,6 +403,8 @@ mod tests {
             user_request: same_task.to_string(),
             session_id: Some("test-reuse-1".to_string()),
             max_candidates: Some(3),
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: Default::default(),
@@ -428,6 +436,8 @@ mod tests {
             user_request: same_task.to_string(),
             session_id: Some("test-reuse-2".to_string()),
             max_candidates: Some(3),
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic,
             metadata: Default::default(),
@@ -503,6 +513,8 @@ mod tests {
             user_request: test_task.to_string(),
             session_id: Some("test-query-mode".to_string()),
             max_candidates: Some(3),
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Query, // ← Query模式
             metadata: Default::default(),
@@ -530,6 +542,8 @@ mod tests {
             user_request: test_task.to_string(),
             session_id: Some("test-dynamic-mode".to_string()),
             max_candidates: Some(3),
+            server_allowlist: Vec::new(),
+            category: None,
             decision_mode: DecisionMode::Auto,
             execution_mode: ExecutionMode::Dynamic, // ← Dynamic模式
             metadata: Default::default(),
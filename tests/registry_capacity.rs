@@ -0,0 +1,83 @@
+//! 共享内存注册表容量上限
+//!
+//! 写满 `max_entries` 后再注册新任务应返回 `RegistryError::Full`，而不是
+//! 底层 map 的通用错误；已存在条目的覆盖写入和完成标记不受条目数限制。
+#![cfg(unix)]
+
+use aiw::error::RegistryError;
+use aiw::storage::{RegistryLimits, SharedMemoryStorage, TaskStorage};
+use aiw::task_record::TaskRecord;
+use chrono::Utc;
+
+const BASE_PID: u32 = 80_000;
+
+fn record(pid: u32) -> TaskRecord {
+    TaskRecord::new(
+        Utc::now(),
+        format!("capacity-{}", pid),
+        format!("/tmp/capacity-{}.log", pid),
+        Some(std::process::id()),
+    )
+}
+
+#[test]
+fn registering_past_capacity_reports_registry_full() {
+    let namespace = format!("aiw_capacity_{}", std::process::id());
+    let limits = RegistryLimits {
+        max_entries: 3,
+        max_value_bytes: 2048,
+    };
+    let storage = SharedMemoryStorage::connect_with_limits(namespace.clone(), limits).unwrap();
+
+    for pid in BASE_PID..BASE_PID + 3 {
+        storage.register(pid, &record(pid)).unwrap();
+    }
+
+    let err = storage
+        .register(BASE_PID + 3, &record(BASE_PID + 3))
+        .unwrap_err();
+    match &err {
+        RegistryError::Full {
+            namespace: ns,
+            reason,
+        } => {
+            assert_eq!(ns, &namespace);
+            assert!(reason.contains("limit is 3"), "{reason}");
+        }
+        other => panic!("expected RegistryError::Full, got {other:?}"),
+    }
+    assert!(err.to_string().contains("registry_max_entries"));
+
+    // 已有条目仍可更新
+    storage.register(BASE_PID, &record(BASE_PID)).unwrap();
+    storage
+        .mark_completed(BASE_PID + 1, Some("done".to_string()), Some(0), Utc::now())
+        .unwrap();
+
+    // 单条记录超过 max_value_bytes
+    let err = storage
+        .mark_completed(BASE_PID + 2, Some("x".repeat(4096)), Some(0), Utc::now())
+        .unwrap_err();
+    assert!(
+        matches!(&err, RegistryError::Full { reason, .. } if reason.contains("2048 bytes")),
+        "{err}"
+    );
+
+    assert_eq!(storage.entries().unwrap().len(), 3);
+    let _ = storage.cleanup();
+}
+
+#[test]
+fn registry_limits_size_the_shared_memory_region() {
+    let small = RegistryLimits {
+        max_entries: 10,
+        max_value_bytes: 1024,
+    };
+    assert_eq!(small.shared_memory_size(), aiw::config::SHARED_MEMORY_SIZE);
+
+    let large = RegistryLimits {
+        max_entries: 16_384,
+        ..RegistryLimits::default()
+    };
+    assert_eq!(large.shared_memory_size(), 16_384 * 4096);
+}
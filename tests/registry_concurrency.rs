@@ -0,0 +1,141 @@
+//! 注册表并发写入压力测试
+//!
+//! 多个线程同时对同一批条目执行标记完成、绑定元数据和对账，验证没有更新丢失。
+
+use aiw::storage::{SharedMemoryStorage, TaskStorage};
+use aiw::task_record::{TaskRecord, TaskStatus, WorktreeInfo};
+use aiw::unified_registry::Registry;
+use chrono::Utc;
+use std::sync::{Arc, Barrier};
+use std::thread;
+
+const TASKS: u32 = 48;
+const BASE_PID: u32 = 70_000;
+
+fn record(pid: u32) -> TaskRecord {
+    TaskRecord::new(
+        Utc::now(),
+        format!("stress-{}", pid),
+        format!("/tmp/stress-{}.log", pid),
+        Some(std::process::id()),
+    )
+}
+
+fn worktree(pid: u32) -> WorktreeInfo {
+    WorktreeInfo {
+        path: format!("/tmp/wt-{}", pid),
+        branch: format!("aiw/{}", pid),
+        commit: "deadbeef".to_string(),
+    }
+}
+
+#[test]
+fn in_process_metadata_binds_and_completions_do_not_clobber_each_other() {
+    let registry = Registry::in_process();
+    for pid in BASE_PID..BASE_PID + TASKS {
+        registry.register(pid, &record(pid)).unwrap();
+    }
+
+    // 每个条目同时被三类写入者修改：完成、带 worktree 的绑定、不带 worktree 的重复绑定
+    let barrier = Arc::new(Barrier::new(3));
+    let handles: Vec<_> = (0..3)
+        .map(|role| {
+            let registry = registry.clone();
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                barrier.wait();
+                for pid in BASE_PID..BASE_PID + TASKS {
+                    match role {
+                        0 => {
+                            let result = Some(format!("done-{}", pid));
+                            registry
+                                .mark_completed(pid, result, Some(0), Utc::now())
+                                .unwrap()
+                        }
+                        1 => assert!(registry.update_task_metadata(
+                            pid,
+                            format!("task-{}", pid),
+                            Some(worktree(pid)),
+                        )),
+                        _ => assert!(registry.update_task_metadata(
+                            pid,
+                            format!("task-{}", pid),
+                            None
+                        )),
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    for pid in BASE_PID..BASE_PID + TASKS {
+        let (found, record) = registry.get_by_task_id(&format!("task-{}", pid)).unwrap();
+        assert_eq!(found, pid);
+        assert_eq!(record.status, TaskStatus::CompletedButUnread);
+        assert_eq!(record.result, Some(format!("done-{}", pid)));
+        assert_eq!(
+            record.worktree_info.unwrap().path,
+            format!("/tmp/wt-{}", pid)
+        );
+    }
+    assert!(!registry.update_task_metadata(1, "missing".to_string(), None));
+}
+
+#[cfg(unix)]
+#[test]
+fn shared_memory_completions_survive_concurrent_reconcile_from_other_connections() {
+    let namespace = format!("aiw_stress_{}", std::process::id());
+    let setup = SharedMemoryStorage::connect_with_namespace(namespace.clone()).unwrap();
+    for pid in BASE_PID..BASE_PID + TASKS {
+        setup.register(pid, &record(pid)).unwrap();
+    }
+
+    // 每个线程使用独立连接（各自的进程内互斥），模拟多个 warden 进程
+    const WRITERS: usize = 6;
+    let barrier = Arc::new(Barrier::new(WRITERS));
+    let handles: Vec<_> = (0..WRITERS)
+        .map(|writer| {
+            let namespace = namespace.clone();
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                let storage = SharedMemoryStorage::connect_with_namespace(namespace).unwrap();
+                barrier.wait();
+                for pid in BASE_PID..BASE_PID + TASKS {
+                    if writer % 2 == 0 {
+                        if pid % (WRITERS as u32 / 2) == (writer / 2) as u32 {
+                            storage
+                                .mark_completed(
+                                    pid,
+                                    Some(format!("done-{}", pid)),
+                                    Some(0),
+                                    Utc::now(),
+                                )
+                                .unwrap();
+                        }
+                    } else {
+                        storage.reconcile_vanished(Utc::now(), |_| false).unwrap();
+                    }
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let entries = setup.entries().unwrap();
+    assert_eq!(entries.len(), TASKS as usize);
+    for entry in entries {
+        assert_eq!(
+            entry.record.result,
+            Some(format!("done-{}", entry.pid)),
+            "completion of {} was overwritten",
+            entry.pid
+        );
+        assert_eq!(entry.record.status, TaskStatus::CompletedButUnread);
+    }
+    let _ = setup.cleanup();
+}
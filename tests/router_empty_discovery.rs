@@ -0,0 +1,82 @@
+//! 没有发现任何下游工具时的 intelligent_route
+//!
+//! 空路由器应返回可操作的提示（配置服务器 / 检查服务器），而不是通用的 "no match"。
+
+use aiw::mcp_routing::config::McpConfig;
+use aiw::mcp_routing::models::IntelligentRouteRequest;
+use aiw::mcp_routing::{
+    hashed_embedding, DecisionEngine, IntelligentRouter, McpConnectionPool, MemRoutingIndex,
+    MockEmbeddingBackend, EMBEDDING_DIMENSION,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+fn empty_router(config: serde_json::Value) -> IntelligentRouter {
+    let config: McpConfig = serde_json::from_value(config).unwrap();
+    IntelligentRouter::new_with_components(
+        Arc::new(MockEmbeddingBackend::new(
+            EMBEDDING_DIMENSION,
+            hashed_embedding,
+        )),
+        MemRoutingIndex::new(EMBEDDING_DIMENSION).unwrap(),
+        Arc::new(DecisionEngine::new("http://localhost:11434", "qwen3:1.7b", 5).unwrap()),
+        Arc::new(McpConnectionPool::new(Arc::new(config))),
+        RwLock::new(HashMap::new()),
+        None,
+        None,
+    )
+}
+
+fn request(text: &str) -> IntelligentRouteRequest {
+    IntelligentRouteRequest {
+        user_request: text.to_string(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn no_configured_servers_points_at_mcp_add() {
+    let router = empty_router(json!({ "mcpServers": {} }));
+
+    let response = router
+        .intelligent_route(request("list files in /tmp"))
+        .await
+        .unwrap();
+
+    assert!(!response.success);
+    assert!(response.selected_tool.is_none());
+    assert!(response.alternatives.is_empty());
+    assert!(
+        response.message.contains("No MCP servers configured"),
+        "{}",
+        response.message
+    );
+    assert!(response.message.contains("aiw mcp add"));
+}
+
+#[tokio::test]
+async fn servers_without_tools_point_at_mcp_test() {
+    let router = empty_router(json!({
+        "mcpServers": {
+            "filesystem": { "command": "npx", "args": ["-y", "@modelcontextprotocol/server-filesystem"] },
+            "disabled": { "command": "uvx", "enabled": false }
+        }
+    }));
+
+    let response = router
+        .intelligent_route(request("list files in /tmp"))
+        .await
+        .unwrap();
+
+    assert!(!response.success);
+    assert!(
+        response
+            .message
+            .contains("None of the 1 enabled MCP server"),
+        "{}",
+        response.message
+    );
+    assert!(response.message.contains("aiw mcp test"));
+}
@@ -1,10 +1,12 @@
 #![cfg(unix)]
 
 use aiw::mcp::{
-    list_tasks, manage_task, start_task, ManageAction, ManageTaskParams, StartTaskParams,
+    is_owned_by, list_tasks, list_tasks_filtered, manage_task, rename_task, start_task,
+    ListTasksParams, ManageAction, ManageTaskParams, RenameTaskParams, StartTaskParams,
 };
 use aiw::platform;
 use aiw::provider::config::AiType;
+use aiw::task_prepare::RoleSpec;
 use rmcp::service::RoleServer;
 use serial_test::serial;
 use std::env;
@@ -101,15 +103,24 @@ async fn start_task_launches_and_returns_pid() {
     let params = StartTaskParams {
         ai_type: Some(AiType::Codex),
         task: "echo hello".to_string(),
+        name: None,
+        task_id: None,
         provider: None,
+        model: None,
+        priority: None,
         role: None,
+        role_position: None,
         cwd: None,
         cli_args: None,
         worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
     };
 
     let launch = start_task(params, mock_peer()).await.expect("task should launch");
-    assert!(launch.pid > 0, "pid should be positive");
+    let pid = launch.pid.expect("launch should not be queued");
+    assert!(pid > 0, "pid should be positive");
     assert!(!launch.task_id.is_empty(), "task_id should be populated");
 
     // Allow the CLI to exit
@@ -125,22 +136,109 @@ async fn list_tasks_returns_running_tasks() {
     let params = StartTaskParams {
         ai_type: Some(AiType::Codex),
         task: "echo hello".to_string(),
+        name: None,
+        task_id: None,
         provider: None,
+        model: None,
+        priority: None,
         role: None,
+        role_position: None,
         cwd: None,
         cli_args: None,
         worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
     };
     let launch = start_task(params, mock_peer()).await.expect("task should launch");
 
     let tasks = list_tasks().await.expect("list_tasks should succeed");
-    let found = tasks.iter().any(|task| task.pid == launch.pid);
+    let found = tasks.iter().any(|task| Some(task.pid) == launch.pid);
     assert!(found, "list_tasks should include newly started task");
 
     sleep(Duration::from_millis(3000)).await;
     drop(home);
 }
 
+#[tokio::test]
+#[serial]
+async fn start_task_records_clamped_priority() {
+    let home = TempHome::new();
+
+    let params = StartTaskParams {
+        ai_type: Some(AiType::Codex),
+        task: "echo background".to_string(),
+        name: None,
+        task_id: None,
+        provider: None,
+        model: None,
+        priority: Some(42),
+        role: None,
+        role_position: None,
+        cwd: None,
+        cli_args: None,
+        worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
+    };
+    let launch = start_task(params, mock_peer()).await.expect("task should launch");
+
+    let tasks = list_tasks().await.expect("list_tasks should succeed");
+    let task = tasks
+        .iter()
+        .find(|task| Some(task.pid) == launch.pid)
+        .expect("launched task should be listed");
+    assert_eq!(task.priority, Some(platform::MAX_PRIORITY));
+
+    sleep(Duration::from_millis(3000)).await;
+    drop(home);
+}
+
+#[tokio::test]
+#[serial]
+async fn list_tasks_owned_only_keeps_tasks_from_this_session() {
+    let home = TempHome::new();
+
+    let params = StartTaskParams {
+        ai_type: Some(AiType::Codex),
+        task: "echo owned".to_string(),
+        name: None,
+        task_id: None,
+        provider: None,
+        model: None,
+        priority: None,
+        role: None,
+        role_position: None,
+        cwd: None,
+        cli_args: None,
+        worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
+    };
+    let launch = start_task(params, mock_peer()).await.expect("task should launch");
+
+    let owned = list_tasks_filtered(ListTasksParams {
+        owned_only: Some(true),
+    })
+    .await
+    .expect("owned list_tasks should succeed");
+    let task = owned
+        .iter()
+        .find(|task| Some(task.pid) == launch.pid)
+        .expect("owned_only should include tasks launched by this process");
+    assert_eq!(task.manager_pid, Some(platform::current_pid()));
+
+    let session = [platform::current_pid()].into_iter().collect();
+    assert!(is_owned_by(Some(platform::current_pid()), &session));
+    assert!(!is_owned_by(Some(u32::MAX), &session));
+    assert!(!is_owned_by(None, &session));
+
+    sleep(Duration::from_millis(3000)).await;
+    drop(home);
+}
+
 #[tokio::test]
 #[serial]
 async fn stop_task_terminates_process() {
@@ -149,11 +247,19 @@ async fn stop_task_terminates_process() {
     let params = StartTaskParams {
         ai_type: Some(AiType::Codex),
         task: "echo hello".to_string(),
+        name: None,
+        task_id: None,
         provider: None,
+        model: None,
+        priority: None,
         role: None,
+        role_position: None,
         cwd: None,
         cli_args: None,
         worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
     };
     let launch = start_task(params, mock_peer()).await.expect("task should launch");
 
@@ -161,6 +267,10 @@ async fn stop_task_terminates_process() {
         task_id: launch.task_id,
         action: ManageAction::Stop,
         tail_lines: None,
+        strip_ansi: None,
+        pattern: None,
+        pattern_is_regex: false,
+        context_lines: None,
     })
     .await
     .expect("manage_task stop should succeed");
@@ -169,7 +279,7 @@ async fn stop_task_terminates_process() {
     // Allow signal propagation
     sleep(Duration::from_millis(500)).await;
     assert!(
-        !platform::process_alive(launch.pid),
+        !platform::process_alive(launch.pid.unwrap()),
         "process should be terminated"
     );
     drop(home);
@@ -183,11 +293,19 @@ async fn get_task_logs_produces_log_file() {
     let params = StartTaskParams {
         ai_type: Some(AiType::Codex),
         task: "echo hello".to_string(),
+        name: None,
+        task_id: None,
         provider: None,
+        model: None,
+        priority: None,
         role: None,
+        role_position: None,
         cwd: None,
         cli_args: None,
         worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
     };
     let launch = start_task(params, mock_peer()).await.expect("task should launch");
 
@@ -198,6 +316,10 @@ async fn get_task_logs_produces_log_file() {
         task_id: launch.task_id.clone(),
         action: ManageAction::Logs,
         tail_lines: None,
+        strip_ansi: None,
+        pattern: None,
+        pattern_is_regex: false,
+        context_lines: None,
     })
     .await
     .expect("log retrieval should succeed");
@@ -212,6 +334,10 @@ async fn get_task_logs_produces_log_file() {
         task_id: launch.task_id.clone(),
         action: ManageAction::Logs,
         tail_lines: Some(1),
+        strip_ansi: None,
+        pattern: None,
+        pattern_is_regex: false,
+        context_lines: None,
     })
     .await
     .expect("tail log retrieval should succeed");
@@ -226,6 +352,40 @@ async fn get_task_logs_produces_log_file() {
         full_content.len() >= tail_content.len(),
         "full log should be at least as long as tail"
     );
+
+    let first_line = full_content
+        .lines()
+        .find(|line| !line.is_empty())
+        .unwrap_or("codex")
+        .to_string();
+    let filtered = manage_task(ManageTaskParams {
+        task_id: launch.task_id.clone(),
+        action: ManageAction::Logs,
+        tail_lines: None,
+        strip_ansi: None,
+        pattern: Some(first_line.clone()),
+        pattern_is_regex: false,
+        context_lines: None,
+    })
+    .await
+    .expect("filtered log retrieval should succeed");
+    let filtered_content = filtered.log_content.unwrap_or_default();
+    assert!(filtered_content
+        .lines()
+        .all(|line| line.contains(first_line.as_str())));
+
+    let err = manage_task(ManageTaskParams {
+        task_id: launch.task_id.clone(),
+        action: ManageAction::Logs,
+        tail_lines: None,
+        strip_ansi: None,
+        pattern: Some("[unclosed".to_string()),
+        pattern_is_regex: true,
+        context_lines: None,
+    })
+    .await
+    .expect_err("an invalid regex should be rejected");
+    assert!(err.contains("Invalid regex pattern"), "{err}");
     drop(home);
 }
 
@@ -238,11 +398,19 @@ async fn start_task_injects_role_prompt() {
     let params = StartTaskParams {
         ai_type: Some(AiType::Codex),
         task: "echo hello".to_string(),
+        name: None,
+        task_id: None,
         provider: None,
-        role: Some("test-role".to_string()),
+        model: None,
+        priority: None,
+        role: Some(RoleSpec::Joined("test-role".to_string())),
+        role_position: None,
         cwd: None,
         cli_args: None,
         worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
     };
 
     let launch = start_task(params, mock_peer()).await.expect("task should launch");
@@ -252,6 +420,10 @@ async fn start_task_injects_role_prompt() {
         task_id: launch.task_id.clone(),
         action: ManageAction::Logs,
         tail_lines: None,
+        strip_ansi: None,
+        pattern: None,
+        pattern_is_regex: false,
+        context_lines: None,
     })
     .await
     .expect("should read logs");
@@ -264,3 +436,462 @@ async fn start_task_injects_role_prompt() {
     );
     drop(home);
 }
+
+#[tokio::test]
+#[serial]
+async fn start_task_reports_launch_failure_instead_of_timeout() {
+    let home = TempHome::new();
+    let empty_path = TempDir::new().expect("temp dir");
+    let _path_guard = EnvGuard::set("PATH", empty_path.path().to_str().unwrap());
+
+    let params = StartTaskParams {
+        ai_type: Some(AiType::Codex),
+        task: "echo hello".to_string(),
+        name: None,
+        task_id: None,
+        provider: None,
+        model: None,
+        priority: None,
+        role: None,
+        role_position: None,
+        cwd: None,
+        cli_args: None,
+        worktree: None,
+        env: None,
+        registration_timeout_secs: Some(30),
+        result_extraction: None,
+    };
+
+    let started = std::time::Instant::now();
+    let err = start_task(params, mock_peer())
+        .await
+        .expect_err("launch should fail when codex is missing");
+    assert!(
+        err.contains("Failed to launch task"),
+        "unexpected error: {err}"
+    );
+    assert!(
+        started.elapsed() < Duration::from_secs(30),
+        "launch failure should be reported before the registration timeout"
+    );
+    drop(home);
+}
+
+#[tokio::test]
+#[serial]
+async fn task_names_resolve_like_task_ids() {
+    let home = TempHome::new();
+
+    let named = start_task(
+        StartTaskParams {
+            ai_type: Some(AiType::Codex),
+            task: "echo hello".to_string(),
+            name: Some("lint-fix".to_string()),
+            task_id: None,
+            provider: None,
+            model: None,
+            priority: None,
+            role: None,
+            role_position: None,
+            cwd: None,
+            cli_args: None,
+            worktree: None,
+            env: None,
+            registration_timeout_secs: None,
+            result_extraction: None,
+        },
+        mock_peer(),
+    )
+    .await
+    .expect("named task should launch");
+
+    let status = manage_task(ManageTaskParams {
+        task_id: "lint-fix".to_string(),
+        action: ManageAction::Status,
+        tail_lines: None,
+        strip_ansi: None,
+        pattern: None,
+        pattern_is_regex: false,
+        context_lines: None,
+    })
+    .await
+    .expect("name should resolve");
+    assert_eq!(
+        status.task_id, named.task_id,
+        "result should carry the UUID"
+    );
+
+    let tasks = list_tasks().await.expect("list_tasks should succeed");
+    let listed = tasks
+        .iter()
+        .find(|task| task.task_id.as_deref() == Some(named.task_id.as_str()))
+        .expect("named task should be listed");
+    assert_eq!(listed.name.as_deref(), Some("lint-fix"));
+
+    let other = start_task(
+        StartTaskParams {
+            ai_type: Some(AiType::Codex),
+            task: "echo again".to_string(),
+            name: None,
+            task_id: None,
+            provider: None,
+            model: None,
+            priority: None,
+            role: None,
+            role_position: None,
+            cwd: None,
+            cli_args: None,
+            worktree: None,
+            env: None,
+            registration_timeout_secs: None,
+            result_extraction: None,
+        },
+        mock_peer(),
+    )
+    .await
+    .expect("second task should launch");
+
+    let renamed = rename_task(RenameTaskParams {
+        task_id: other.task_id.clone(),
+        name: Some("lint-fix".to_string()),
+    })
+    .await
+    .expect("rename should succeed");
+    assert_eq!(renamed.name.as_deref(), Some("lint-fix"));
+
+    let err = manage_task(ManageTaskParams {
+        task_id: "lint-fix".to_string(),
+        action: ManageAction::Status,
+        tail_lines: None,
+        strip_ansi: None,
+        pattern: None,
+        pattern_is_regex: false,
+        context_lines: None,
+    })
+    .await
+    .expect_err("shared name should be ambiguous");
+    assert!(err.contains("ambiguous"), "unexpected error: {err}");
+    assert!(err.contains(&named.task_id) && err.contains(&other.task_id));
+
+    let err = rename_task(RenameTaskParams {
+        task_id: other.task_id.clone(),
+        name: Some("has space".to_string()),
+    })
+    .await
+    .expect_err("names with whitespace should be rejected");
+    assert!(err.contains("whitespace"), "unexpected error: {err}");
+
+    rename_task(RenameTaskParams {
+        task_id: other.task_id,
+        name: None,
+    })
+    .await
+    .expect("clearing the name should succeed");
+    manage_task(ManageTaskParams {
+        task_id: "lint-fix".to_string(),
+        action: ManageAction::Status,
+        tail_lines: None,
+        strip_ansi: None,
+        pattern: None,
+        pattern_is_regex: false,
+        context_lines: None,
+    })
+    .await
+    .expect("name should be unique again");
+
+    sleep(Duration::from_millis(3000)).await;
+    drop(home);
+}
+
+#[tokio::test]
+#[serial]
+async fn start_task_rejects_model_for_cli_without_model_flag() {
+    let home = TempHome::new();
+
+    let params = StartTaskParams {
+        ai_type: Some(AiType::Grok),
+        task: "echo hello".to_string(),
+        name: None,
+        task_id: None,
+        provider: None,
+        model: Some("grok-4".to_string()),
+        priority: None,
+        role: None,
+        role_position: None,
+        cwd: None,
+        cli_args: None,
+        worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
+    };
+
+    let err = start_task(params, mock_peer())
+        .await
+        .expect_err("grok has no model flag");
+    assert!(
+        err.contains("grok does not support selecting a model"),
+        "unexpected error: {err}"
+    );
+    drop(home);
+}
+
+fn params_with_task_id(task_id: &str) -> StartTaskParams {
+    StartTaskParams {
+        ai_type: Some(AiType::Codex),
+        task: "echo hello".to_string(),
+        name: None,
+        task_id: Some(task_id.to_string()),
+        provider: None,
+        model: None,
+        priority: None,
+        role: None,
+        role_position: None,
+        cwd: None,
+        cli_args: None,
+        worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn supplied_task_id_is_used_and_not_shared_with_a_running_task() {
+    let home = TempHome::new();
+
+    let launch = start_task(params_with_task_id("ci-job-4711"), mock_peer())
+        .await
+        .expect("task with a supplied id should launch");
+    assert_eq!(launch.task_id, "ci-job-4711");
+
+    let status = manage_task(ManageTaskParams {
+        task_id: "ci-job-4711".to_string(),
+        action: ManageAction::Status,
+        tail_lines: None,
+        strip_ansi: None,
+        pattern: None,
+        pattern_is_regex: false,
+        context_lines: None,
+    })
+    .await
+    .expect("supplied id should resolve");
+    assert_eq!(Some(status.pid), launch.pid);
+
+    let err = start_task(params_with_task_id("ci-job-4711"), mock_peer())
+        .await
+        .expect_err("a running task already owns the id");
+    assert!(err.contains("already used"), "unexpected error: {err}");
+
+    sleep(Duration::from_millis(3000)).await;
+    drop(home);
+}
+
+#[tokio::test]
+#[serial]
+async fn malformed_task_ids_are_rejected() {
+    let home = TempHome::new();
+
+    let too_long = "x".repeat(129);
+    for task_id in ["", "job 1", "job\u{7}1", too_long.as_str()] {
+        let err = start_task(params_with_task_id(task_id), mock_peer())
+            .await
+            .expect_err("malformed task_id should be rejected");
+        assert!(err.contains("Invalid task_id"), "unexpected error: {err}");
+    }
+    drop(home);
+}
+
+#[tokio::test]
+#[serial]
+async fn task_env_reaches_the_cli_and_overrides_provider_env() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = TempHome::new();
+    let aiw_dir = home.path().join(".aiw");
+    fs::create_dir_all(&aiw_dir).expect("create .aiw");
+    fs::write(
+        aiw_dir.join("providers.json"),
+        r#"{
+            "default_provider": "gateway",
+            "providers": {
+                "gateway": {
+                    "env": {"AIW_TEST_TOKEN": "from-provider", "AIW_PROVIDER_ONLY": "kept"}
+                }
+            }
+        }"#,
+    )
+    .expect("write providers.json");
+
+    // 假的 codex：把看到的变量打印到任务日志
+    let bin_dir = TempDir::new().expect("temp dir");
+    let fake_codex = bin_dir.path().join("codex");
+    fs::write(
+        &fake_codex,
+        "#!/bin/sh\necho \"token=$AIW_TEST_TOKEN provider_only=$AIW_PROVIDER_ONLY task_only=$AIW_TASK_ONLY\"\n",
+    )
+    .expect("write fake codex");
+    fs::set_permissions(&fake_codex, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin_dir.path().display(),
+        env::var("PATH").unwrap_or_default()
+    );
+    let _path_guard = EnvGuard::set("PATH", &path);
+
+    let task_env = [("AIW_TEST_TOKEN", "from-task"), ("AIW_TASK_ONLY", "set")]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let params = StartTaskParams {
+        ai_type: Some(AiType::Codex),
+        task: "print env".to_string(),
+        name: None,
+        task_id: None,
+        provider: Some("gateway".to_string()),
+        model: None,
+        priority: None,
+        role: None,
+        role_position: None,
+        cwd: None,
+        cli_args: None,
+        worktree: None,
+        env: Some(task_env),
+        registration_timeout_secs: None,
+        result_extraction: None,
+    };
+    let launch = start_task(params, mock_peer()).await.expect("task should launch");
+
+    let mut content = String::new();
+    for _ in 0..50 {
+        sleep(Duration::from_millis(100)).await;
+        let logs = manage_task(ManageTaskParams {
+            task_id: launch.task_id.clone(),
+            action: ManageAction::Logs,
+            tail_lines: None,
+            strip_ansi: Some(true),
+            pattern: Some("token=".to_string()),
+            pattern_is_regex: false,
+            context_lines: None,
+        })
+        .await
+        .expect("log retrieval should succeed");
+        content = logs.log_content.unwrap_or_default();
+        if !content.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(
+        content.trim(),
+        "token=from-task provider_only=kept task_only=set",
+        "task env should win over the provider's value for the same key"
+    );
+    drop(home);
+}
+
+#[tokio::test]
+#[serial]
+async fn race_task_id_resolves_to_the_winner() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = TempHome::new();
+    let aiw_dir = home.path().join(".aiw");
+    fs::create_dir_all(&aiw_dir).expect("create .aiw");
+    fs::write(
+        aiw_dir.join("providers.json"),
+        r#"{"default_provider": "gateway", "providers": {"gateway": {"env": {}}}}"#,
+    )
+    .expect("write providers.json");
+    fs::write(
+        aiw_dir.join("config.json"),
+        r#"{
+            "auto_execution_order": [
+                {"cli": "codex", "provider": "gateway"},
+                {"cli": "gemini", "provider": "gateway"}
+            ],
+            "auto_strategy": {"mode": "race", "top_k": 2}
+        }"#,
+    )
+    .expect("write config.json");
+
+    // codex 先启动、先登记，但一直不结束，会输给稍后成功退出的 gemini
+    let bin_dir = TempDir::new().expect("temp dir");
+    for (name, script) in [
+        ("codex", "#!/bin/sh\necho loser-output\nexec sleep 30\n"),
+        ("gemini", "#!/bin/sh\nsleep 1\necho winner-output\n"),
+    ] {
+        let path = bin_dir.path().join(name);
+        fs::write(&path, script).expect("write fake cli");
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let path = format!(
+        "{}:{}",
+        bin_dir.path().display(),
+        env::var("PATH").unwrap_or_default()
+    );
+    let _path_guard = EnvGuard::set("PATH", &path);
+
+    let params = StartTaskParams {
+        ai_type: Some(AiType::Auto),
+        task: "race".to_string(),
+        name: None,
+        task_id: None,
+        provider: None,
+        model: None,
+        priority: None,
+        role: None,
+        role_position: None,
+        cwd: None,
+        cli_args: None,
+        worktree: None,
+        env: None,
+        registration_timeout_secs: None,
+        result_extraction: None,
+    };
+    // 并发的组合不能共用一个 worktree
+    let shared_worktree = StartTaskParams {
+        worktree: Some(true),
+        ..params.clone()
+    };
+    let err = start_task(shared_worktree, mock_peer())
+        .await
+        .expect_err("race with a worktree should be rejected");
+    assert!(err.contains("worktree is not supported"), "{err}");
+
+    let launch = start_task(params, mock_peer()).await.expect("task should launch");
+
+    let mut content = String::new();
+    let mut loser_listed = true;
+    for _ in 0..100 {
+        sleep(Duration::from_millis(100)).await;
+        let logs = manage_task(ManageTaskParams {
+            task_id: launch.task_id.clone(),
+            action: ManageAction::Logs,
+            tail_lines: None,
+            strip_ansi: Some(true),
+            pattern: Some("-output".to_string()),
+            pattern_is_regex: false,
+            context_lines: None,
+        })
+        .await
+        .expect("log retrieval should succeed");
+        content = logs.log_content.unwrap_or_default();
+        let tasks = list_tasks().await.expect("list_tasks should succeed");
+        loser_listed = tasks.iter().any(|task| {
+            fs::read_to_string(&task.log_file)
+                .map(|log| log.contains("loser-output"))
+                .unwrap_or(false)
+        });
+        if content.trim() == "winner-output" && !loser_listed {
+            break;
+        }
+    }
+    assert_eq!(
+        content.trim(),
+        "winner-output",
+        "task_id should point at the run that won the race"
+    );
+    assert!(!loser_listed, "the cancelled racer should not stay in the task list");
+    drop(home);
+}
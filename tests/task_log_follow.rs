@@ -0,0 +1,62 @@
+use aiw::supervisor::read_log_from;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+#[test]
+fn reads_only_content_appended_since_the_offset() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("task.log");
+    fs::write(&log, "first\n").unwrap();
+
+    let (text, offset) = read_log_from(&log, 0).unwrap();
+    assert_eq!(text, "first\n");
+    assert_eq!(offset, 6);
+
+    let (text, same) = read_log_from(&log, offset).unwrap();
+    assert!(text.is_empty());
+    assert_eq!(same, offset);
+
+    OpenOptions::new()
+        .append(true)
+        .open(&log)
+        .unwrap()
+        .write_all(b"second\n")
+        .unwrap();
+    let (text, offset) = read_log_from(&log, offset).unwrap();
+    assert_eq!(text, "second\n");
+    assert_eq!(offset, 13);
+}
+
+#[test]
+fn holds_back_a_partially_written_character() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("task.log");
+    let snowman = "☃".as_bytes();
+    fs::write(&log, [b"a".as_slice(), &snowman[..2]].concat()).unwrap();
+
+    let (text, offset) = read_log_from(&log, 0).unwrap();
+    assert_eq!(text, "a");
+    assert_eq!(offset, 1);
+
+    OpenOptions::new()
+        .append(true)
+        .open(&log)
+        .unwrap()
+        .write_all(&snowman[2..])
+        .unwrap();
+    let (text, _) = read_log_from(&log, offset).unwrap();
+    assert_eq!(text, "☃");
+}
+
+#[test]
+fn restarts_from_the_beginning_after_truncation() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("task.log");
+    fs::write(&log, "a long first run\n").unwrap();
+    let (_, offset) = read_log_from(&log, 0).unwrap();
+
+    fs::write(&log, "new\n").unwrap();
+    let (text, offset) = read_log_from(&log, offset).unwrap();
+    assert_eq!(text, "new\n");
+    assert_eq!(offset, 4);
+}
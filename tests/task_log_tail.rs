@@ -0,0 +1,76 @@
+use aiw::supervisor::{read_tail_lines, read_task_logs, TAIL_MAX_BYTES};
+use std::fs;
+
+/// 旧实现：整个文件读进内存再切片
+fn naive_tail(content: &str, n: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    lines[lines.len().saturating_sub(n)..].join("\n")
+}
+
+fn big_log(trailing_newline: bool) -> String {
+    // 超过一个读取块（64 KiB），覆盖跨块拼接
+    let mut content: String = (0..5_000)
+        .map(|i| format!("line {i:05} {}\r\n", "x".repeat(i % 40)))
+        .collect();
+    content.push_str("último ☃");
+    if trailing_newline {
+        content.push('\n');
+    }
+    content
+}
+
+#[test]
+fn chunked_tail_matches_naive_tail() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("task.log");
+
+    for trailing_newline in [true, false] {
+        let content = big_log(trailing_newline);
+        fs::write(&log, &content).unwrap();
+        for n in [0, 1, 2, 10, 1_234, 4_999, 5_000, 5_001, 10_000] {
+            assert_eq!(
+                read_tail_lines(&log, n, TAIL_MAX_BYTES).unwrap(),
+                naive_tail(&content, n),
+                "n={n} trailing_newline={trailing_newline}"
+            );
+        }
+    }
+
+    for content in ["", "\n", "\n\n\n", "only", "a\nb", "a\nb\n", "a\n\nb\n\n"] {
+        fs::write(&log, content).unwrap();
+        for n in 0..5 {
+            assert_eq!(
+                read_task_logs(&log, Some(n)).unwrap(),
+                naive_tail(content, n),
+                "content={content:?} n={n}"
+            );
+        }
+    }
+}
+
+#[test]
+fn tail_stops_at_max_bytes_without_partial_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("task.log");
+    fs::write(&log, "first line\nsecond line\nthird\n").unwrap();
+
+    // 回溯 15 字节落在 "second line" 中间，不完整的行被丢弃
+    assert_eq!(read_tail_lines(&log, 10, 15).unwrap(), "third");
+
+    // 范围内没有换行时保留内容，但跳过被截断的多字节字符
+    fs::write(&log, "☃☃☃").unwrap();
+    assert_eq!(read_tail_lines(&log, 1, 7).unwrap(), "☃☃");
+}
+
+#[test]
+fn invalid_utf8_does_not_make_the_log_unreadable() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = dir.path().join("task.log");
+    fs::write(&log, b"progress \xff\xfe 50%\r\ndone\n").unwrap();
+
+    assert_eq!(
+        read_task_logs(&log, None).unwrap(),
+        "progress \u{FFFD}\u{FFFD} 50%\r\ndone\n"
+    );
+    assert_eq!(read_task_logs(&log, Some(1)).unwrap(), "done");
+}
@@ -0,0 +1,67 @@
+use aiw::supervisor::{extract_result, ResultExtraction};
+use serde_json::{json, Value};
+
+fn parsed(result: Option<String>) -> Value {
+    serde_json::from_str(&result.expect("result should be extracted")).unwrap()
+}
+
+#[test]
+fn last_json_picks_the_final_top_level_object() {
+    let output = r#"
+thinking about {the task}
+{"step": 1}
+progress: done
+{
+  "status": "ok",
+  "files": ["a.rs", "b.rs"],
+  "meta": {"tokens": 42}
+}
+bye
+"#;
+
+    assert_eq!(
+        parsed(extract_result(output, ResultExtraction::LastJson)),
+        json!({"status": "ok", "files": ["a.rs", "b.rs"], "meta": {"tokens": 42}})
+    );
+}
+
+#[test]
+fn last_json_ignores_output_without_objects() {
+    assert_eq!(
+        extract_result("no json here {oops", ResultExtraction::LastJson),
+        None
+    );
+    assert_eq!(
+        extract_result("[1, 2, 3]", ResultExtraction::LastJson),
+        None
+    );
+}
+
+#[test]
+fn marker_returns_the_last_delimited_block() {
+    let output = "\
+<<<AIW_RESULT>>>draft<<<END_AIW_RESULT>>>
+log line
+<<<AIW_RESULT>>>
+{ \"answer\": 42 }
+<<<END_AIW_RESULT>>>
+";
+    assert_eq!(
+        parsed(extract_result(output, ResultExtraction::Marker)),
+        json!({"answer": 42})
+    );
+
+    let text = "<<<AIW_RESULT>>>  plain summary  <<<END_AIW_RESULT>>>";
+    assert_eq!(
+        extract_result(text, ResultExtraction::Marker).as_deref(),
+        Some("plain summary")
+    );
+
+    let unterminated = "<<<AIW_RESULT>>> never closed";
+    assert_eq!(extract_result(unterminated, ResultExtraction::Marker), None);
+}
+
+#[test]
+fn none_strategy_never_extracts() {
+    assert_eq!(extract_result(r#"{"a": 1}"#, ResultExtraction::None), None);
+}
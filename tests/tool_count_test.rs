@@ -24,6 +24,17 @@ mod tests {
             "intelligent_route",
             "list_roles",
             "list_providers",
+            "get_task_output",
+            "export_task",
+            "rename_task",
+            "get_tool_source",
+            "register_js_tool",
+            "prune_tasks",
+            "reconcile_tasks",
+            "registry_stats",
+            "mcp_server_health",
+            "list_discovered_tools",
+            "capabilities",
         ];
 
         // Find base tools (static ones we know about)
@@ -1,6 +1,7 @@
 //! Tests for MCP client capability detection.
 
-use aiw::mcp::capability_detector::ClientCapabilities;
+use aiw::mcp::capability_detector::{CapabilitiesReport, ClientCapabilities};
+use aiw::mcp_routing::models::{ExecutionMode, RoutingMode};
 use rmcp::model::{Implementation, InitializeRequestParam, ProtocolVersion};
 
 #[test]
@@ -98,3 +99,39 @@ fn test_capabilities_mutation() {
     caps.supports_dynamic_tools = true;
     assert!(caps.supports_dynamic_tools);
 }
+
+#[test]
+fn test_capabilities_report_reflects_effective_mode() {
+    let caps = ClientCapabilities {
+        supports_dynamic_tools: false,
+        client_name: "legacy-client".to_string(),
+        client_version: "0.9".to_string(),
+    };
+
+    let report = CapabilitiesReport::new(Some(&caps), 2, RoutingMode::VectorOnly);
+    assert!(report.client_connected);
+    assert_eq!(report.client_name.as_deref(), Some("legacy-client"));
+    assert_eq!(report.execution_mode, ExecutionMode::Query);
+    assert_eq!(report.dynamic_tool_count, 2);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["execution_mode"], "query");
+    assert_eq!(json["routing_mode"], "vector_only");
+
+    let dynamic = ClientCapabilities {
+        supports_dynamic_tools: true,
+        ..caps
+    };
+    assert_eq!(
+        CapabilitiesReport::new(Some(&dynamic), 0, RoutingMode::Orchestration).execution_mode,
+        ExecutionMode::Dynamic
+    );
+}
+
+#[test]
+fn test_capabilities_report_before_initialize() {
+    let report = CapabilitiesReport::new(None, 0, RoutingMode::VectorLlm);
+    assert!(!report.client_connected);
+    assert!(report.client_name.is_none());
+    assert_eq!(report.execution_mode, ExecutionMode::Dynamic);
+}
@@ -1,4 +1,7 @@
-use aiw::commands::parser::{Cli, ConfigAction, MarketplaceAction, McpAction, PluginAction};
+use aiw::commands::parser::{
+    Cli, CliAction, ConfigAction, MarketplaceAction, McpAction, PluginAction, ProviderAction,
+    RegistryAction, RoutingAction, RoutingConfigAction, SyncAction, SyncConfigAction, TaskAction,
+};
 use aiw::commands::{parse_external_as_ai_cli, Commands, RolesAction};
 
 fn parse(args: &[&str]) -> Commands {
@@ -12,19 +15,137 @@ fn parse(args: &[&str]) -> Commands {
 #[test]
 fn parses_status_and_provider_commands() {
     match parse(&["status"]) {
-        Commands::Status { tui: false } => {}
+        Commands::Status {
+            tui: false,
+            plain: false,
+            watch: false,
+            once: false,
+            interval: 2,
+        } => {}
         other => panic!("expected status command, got {other:?}"),
     }
 
     match parse(&["status", "--tui"]) {
-        Commands::Status { tui: true } => {}
+        Commands::Status { tui: true, .. } => {}
         other => panic!("expected status --tui command, got {other:?}"),
     }
 
+    match parse(&["status", "--plain", "--once", "--interval", "5"]) {
+        Commands::Status {
+            plain: true,
+            once: true,
+            interval: 5,
+            ..
+        } => {}
+        other => panic!("expected status --plain --once command, got {other:?}"),
+    }
+
     match parse(&["provider"]) {
-        Commands::Provider => {}
+        Commands::Provider { action: None } => {}
         other => panic!("expected provider command, got {other:?}"),
     }
+
+    match parse(&["doctor"]) {
+        Commands::Doctor => {}
+        other => panic!("expected doctor command, got {other:?}"),
+    }
+
+    match parse(&["provider", "test", "glm"]) {
+        Commands::Provider {
+            action: Some(ProviderAction::Test { name }),
+        } => assert_eq!(name, "glm"),
+        other => panic!("expected provider test command, got {other:?}"),
+    }
+
+    match parse(&["provider", "remove", "kimi", "--default", "glm", "-y"]) {
+        Commands::Provider {
+            action:
+                Some(ProviderAction::Remove {
+                    name,
+                    new_default,
+                    yes: true,
+                }),
+        } => {
+            assert_eq!(name, "kimi");
+            assert_eq!(new_default.as_deref(), Some("glm"));
+        }
+        other => panic!("expected provider remove command, got {other:?}"),
+    }
+
+    match parse(&[
+        "provider",
+        "edit",
+        "kimi",
+        "--compatible-with",
+        "claude,codex",
+        "--env",
+        "A=1",
+        "--unset-env",
+        "B",
+        "--disable",
+    ]) {
+        Commands::Provider {
+            action:
+                Some(ProviderAction::Edit {
+                    name,
+                    compatible_with,
+                    env_vars,
+                    unset_env,
+                    enable: false,
+                    disable: true,
+                    ..
+                }),
+        } => {
+            assert_eq!(name, "kimi");
+            assert_eq!(compatible_with.as_deref(), Some("claude,codex"));
+            assert_eq!(env_vars, vec!["A=1"]);
+            assert_eq!(unset_env, vec!["B"]);
+        }
+        other => panic!("expected provider edit command, got {other:?}"),
+    }
+
+    let conflicting = ["aiw", "provider", "edit", "kimi", "--enable", "--disable"];
+    assert!(Cli::try_parse_command_from(conflicting).is_err());
+
+    match parse(&["registry", "reset", "--pid", "123", "--purge-dead"]) {
+        Commands::Registry(RegistryAction::Reset {
+            pid: Some(123),
+            purge_dead: true,
+        }) => {}
+        other => panic!("expected registry reset command, got {other:?}"),
+    }
+
+    match parse(&["task", "export", "abc-123", "/tmp/bundle.tar.gz"]) {
+        Commands::Task(TaskAction::Export { task_id, output }) => {
+            assert_eq!(task_id, "abc-123");
+            assert_eq!(output, Some(std::path::PathBuf::from("/tmp/bundle.tar.gz")));
+        }
+        other => panic!("expected task export command, got {other:?}"),
+    }
+
+    match parse(&["task", "attach", "abc-123"]) {
+        Commands::Task(TaskAction::Attach { task_id, grep, .. }) => {
+            assert_eq!(task_id, "abc-123");
+            assert_eq!(grep, None);
+        }
+        other => panic!("expected task attach command, got {other:?}"),
+    }
+
+    match parse(&["task", "attach", "abc-123", "--grep", "^error", "--regex", "-C", "2"]) {
+        Commands::Task(TaskAction::Attach {
+            grep,
+            regex,
+            context,
+            ..
+        }) => {
+            assert_eq!(grep.as_deref(), Some("^error"));
+            assert!(regex);
+            assert_eq!(context, Some(2));
+        }
+        other => panic!("expected task attach command, got {other:?}"),
+    }
+    // --regex and --context only make sense together with --grep
+    assert!(Cli::try_parse_command_from(["aiw", "task", "attach", "abc-123", "--regex"]).is_err());
 }
 
 #[test]
@@ -66,6 +187,39 @@ fn parses_roles_list_command() {
     }
 }
 
+#[test]
+fn parses_roles_show_command_with_flags() {
+    match parse(&["roles", "show", "common", "--raw", "--lang", "en"]) {
+        Commands::Roles(RolesAction::Show { name, raw, lang }) => {
+            assert_eq!(name, "common");
+            assert!(raw);
+            assert_eq!(lang.as_deref(), Some("en"));
+        }
+        other => panic!("expected roles show command, got {other:?}"),
+    }
+}
+
+#[test]
+fn parses_roles_overrides_command() {
+    match parse(&["roles", "overrides"]) {
+        Commands::Roles(RolesAction::Overrides { name, diff, lang }) => {
+            assert_eq!(name, None);
+            assert!(!diff);
+            assert_eq!(lang, None);
+        }
+        other => panic!("expected roles overrides command, got {other:?}"),
+    }
+
+    match parse(&["roles", "overrides", "security", "--diff", "--lang", "zh-CN"]) {
+        Commands::Roles(RolesAction::Overrides { name, diff, lang }) => {
+            assert_eq!(name.as_deref(), Some("security"));
+            assert!(diff);
+            assert_eq!(lang.as_deref(), Some("zh-CN"));
+        }
+        other => panic!("expected roles overrides command, got {other:?}"),
+    }
+}
+
 #[test]
 fn parses_update_command() {
     match parse(&["update"]) {
@@ -92,11 +246,165 @@ fn captures_unknown_sync_commands_as_external() {
 #[test]
 fn parses_wait_command() {
     match parse(&["wait"]) {
-        Commands::Wait => {}
+        Commands::Wait { task_ids, all } => {
+            assert!(task_ids.is_empty());
+            assert!(!all);
+        }
+        other => panic!("expected wait command, got {other:?}"),
+    }
+}
+
+#[test]
+fn parses_wait_targets_and_all_flag() {
+    match parse(&["wait", "abc-123", "review-*"]) {
+        Commands::Wait { task_ids, all } => {
+            assert_eq!(task_ids, vec!["abc-123", "review-*"]);
+            assert!(!all);
+        }
+        other => panic!("expected wait command, got {other:?}"),
+    }
+
+    match parse(&["wait", "--all"]) {
+        Commands::Wait { task_ids, all } => {
+            assert!(task_ids.is_empty());
+            assert!(all);
+        }
         other => panic!("expected wait command, got {other:?}"),
     }
 }
 
+#[test]
+fn parses_routing_config_commands() {
+    match parse(&["routing", "config"]) {
+        Commands::Routing(RoutingAction::Config { action: None }) => {}
+        other => panic!("expected routing config, got {other:?}"),
+    }
+
+    match parse(&["routing", "config", "set", "fast_path_threshold", "0.8"]) {
+        Commands::Routing(RoutingAction::Config {
+            action: Some(RoutingConfigAction::Set { key, value }),
+        }) => {
+            assert_eq!(key, "fast_path_threshold");
+            assert_eq!(value, "0.8");
+        }
+        other => panic!("expected routing config set, got {other:?}"),
+    }
+}
+
+#[test]
+fn parses_cli_install_and_uninstall() {
+    match parse(&["cli", "install", "codex"]) {
+        Commands::Cli(CliAction::Install { tool }) => assert_eq!(tool, "codex"),
+        other => panic!("expected cli install, got {other:?}"),
+    }
+    match parse(&["cli", "uninstall", "gemini"]) {
+        Commands::Cli(CliAction::Uninstall { tool }) => assert_eq!(tool, "gemini"),
+        other => panic!("expected cli uninstall, got {other:?}"),
+    }
+}
+
+#[test]
+fn parses_sync_commands_with_directory_targets() {
+    match parse(&["sync", "push", "claude", "codex"]) {
+        Commands::Sync(SyncAction::Push { directories, name }) => {
+            assert_eq!(directories, vec!["claude", "codex"]);
+            assert!(name.is_none());
+        }
+        other => panic!("expected sync push, got {other:?}"),
+    }
+
+    match parse(&["sync", "pull", ".gemini", "--force"]) {
+        Commands::Sync(SyncAction::Pull {
+            directories, force, ..
+        }) => {
+            assert_eq!(directories, vec![".gemini"]);
+            assert!(force);
+        }
+        other => panic!("expected sync pull, got {other:?}"),
+    }
+
+    match parse(&["sync", "config", "list-dirs"]) {
+        Commands::Sync(SyncAction::Config {
+            action: SyncConfigAction::ListDirs,
+        }) => {}
+        other => panic!("expected sync config list-dirs, got {other:?}"),
+    }
+
+    let conflicting = ["agentic-warden", "sync", "push", "claude", "--name", "work"];
+    assert!(Cli::try_parse_command_from(conflicting).is_err());
+}
+
+#[test]
+fn parses_mcp_export_and_import_commands() {
+    match parse(&["mcp", "export", "team.json", "--redact"]) {
+        Commands::Mcp(McpAction::Export { file, redact }) => {
+            assert_eq!(file, "team.json");
+            assert!(redact);
+        }
+        other => panic!("expected mcp export, got {other:?}"),
+    }
+
+    match parse(&["mcp", "import", "team.json", "--overwrite"]) {
+        Commands::Mcp(McpAction::Import {
+            file,
+            replace,
+            overwrite,
+        }) => {
+            assert_eq!(file, "team.json");
+            assert!(!replace);
+            assert!(overwrite);
+        }
+        other => panic!("expected mcp import, got {other:?}"),
+    }
+
+    let conflict = Cli::try_parse_command_from([
+        "aiw",
+        "mcp",
+        "import",
+        "f.json",
+        "--replace",
+        "--overwrite",
+    ]);
+    assert!(conflict.is_err());
+    // 合并是默认行为，不再提供无效的 --merge 开关
+    assert!(Cli::try_parse_command_from(["aiw", "mcp", "import", "f.json", "--merge"]).is_err());
+}
+
+#[test]
+fn parses_mcp_restore_command() {
+    match parse(&["mcp", "restore", "2", "-y"]) {
+        Commands::Mcp(McpAction::Restore { backup, list, yes }) => {
+            assert_eq!(backup.as_deref(), Some("2"));
+            assert!(!list);
+            assert!(yes);
+        }
+        other => panic!("expected mcp restore, got {other:?}"),
+    }
+
+    match parse(&["mcp", "restore", "--list"]) {
+        Commands::Mcp(McpAction::Restore { backup, list, .. }) => {
+            assert_eq!(backup, None);
+            assert!(list);
+        }
+        other => panic!("expected mcp restore, got {other:?}"),
+    }
+}
+
+#[test]
+fn parses_mcp_tools_command() {
+    match parse(&["mcp", "tools"]) {
+        Commands::Mcp(McpAction::Tools { server }) => assert_eq!(server, None),
+        other => panic!("expected mcp tools, got {other:?}"),
+    }
+
+    match parse(&["mcp", "tools", "filesystem"]) {
+        Commands::Mcp(McpAction::Tools { server }) => {
+            assert_eq!(server.as_deref(), Some("filesystem"))
+        }
+        other => panic!("expected mcp tools, got {other:?}"),
+    }
+}
+
 #[test]
 fn parses_mcp_search_command() {
     match parse(&["mcp", "search", "filesystem", "--source", "registry", "--limit", "5"]) {
@@ -104,13 +412,33 @@ fn parses_mcp_search_command() {
             query,
             source,
             limit,
+            sort,
+            no_cache,
+            timeout,
         }) => {
             assert_eq!(query, "filesystem");
             assert_eq!(source.as_deref(), Some("registry"));
             assert_eq!(limit, Some(5));
+            assert_eq!(sort, None);
+            assert!(!no_cache);
+            assert_eq!(timeout, None);
+        }
+        other => panic!("expected mcp search command, got {other:?}"),
+    }
+
+    match parse(&["mcp", "search", "git", "--sort", "downloads", "--no-cache"]) {
+        Commands::Mcp(McpAction::Search { sort, no_cache, .. }) => {
+            assert_eq!(sort.as_deref(), Some("downloads"));
+            assert!(no_cache);
         }
         other => panic!("expected mcp search command, got {other:?}"),
     }
+
+    match parse(&["mcp", "search", "git", "--timeout", "30"]) {
+        Commands::Mcp(McpAction::Search { timeout, .. }) => assert_eq!(timeout, Some(30)),
+        other => panic!("expected mcp search command, got {other:?}"),
+    }
+    assert!(Cli::try_parse_command_from(["aiw", "mcp", "search", "git", "--timeout", "0"]).is_err());
 }
 
 #[test]
@@ -122,17 +450,25 @@ fn parses_mcp_install_command() {
         "--env",
         "TOKEN=abc",
         "--skip-env",
+        "--into",
+        "codex",
+        "--timeout",
+        "5",
     ]) {
         Commands::Mcp(McpAction::Install {
             name,
             source,
             env_vars,
             skip_env,
+            into,
+            timeout,
         }) => {
             assert_eq!(name, "@anthropic/filesystem");
             assert!(source.is_none());
             assert_eq!(env_vars, vec!["TOKEN=abc".to_string()]);
             assert!(skip_env);
+            assert_eq!(into.as_deref(), Some("codex"));
+            assert_eq!(timeout, Some(5));
         }
         other => panic!("expected mcp install command, got {other:?}"),
     }
@@ -157,6 +493,14 @@ fn parses_mcp_update_command() {
     }
 }
 
+#[test]
+fn parses_mcp_test_command() {
+    match parse(&["mcp", "test", "filesystem"]) {
+        Commands::Mcp(McpAction::Test { name }) => assert_eq!(name, "filesystem"),
+        other => panic!("expected mcp test command, got {other:?}"),
+    }
+}
+
 #[test]
 fn parses_plugin_marketplace_add_command() {
     match parse(&["plugin", "marketplace", "add", "https://github.com/acme/market", "--name", "acme"]) {
@@ -14,6 +14,8 @@ fn test_provider_env_var_generation() {
         compatible_with: None,
         env,
         disabled_until: None,
+        env_overrides: HashMap::new(),
+        priority: None,
     };
 
     assert_eq!(provider.env.get("CUSTOM_KEY"), Some(&"value".to_string()));